@@ -3,11 +3,30 @@ use std::io::{Result, Write};
 pub struct CodeWriter<W: Write> {
     w: W,
     indent_level: u32,
+
+    max_line_width: Option<u32>,
+    column: u32,
+    /// Set by [`append_break`](Self::append_break) and [`append_soft_break`](Self::append_soft_break) to the text written if the break doesn't become a newline; the text appended since then is buffered in `segment` until the next break or newline, so that we know whether it fits on the current line.
+    pending_break: Option<&'static str>,
+    segment: String,
 }
 
 impl<W: Write> CodeWriter<W> {
     pub fn new(w: W) -> CodeWriter<W> {
-        CodeWriter { w, indent_level: 0 }
+        CodeWriter {
+            w,
+            indent_level: 0,
+
+            max_line_width: None,
+            column: 0,
+            pending_break: None,
+            segment: String::new(),
+        }
+    }
+
+    /// If `Some`, [breaks](Self::append_break) are turned into newlines when the text that follows them would otherwise make the current line longer than `max_line_width` columns.
+    pub fn set_max_line_width(&mut self, max_line_width: Option<u32>) {
+        self.max_line_width = max_line_width;
     }
 
     pub fn indent(&mut self) {
@@ -23,18 +42,24 @@ impl<W: Write> CodeWriter<W> {
 
     pub fn append_indent(&mut self) -> Result<()> {
         for _ in 0..self.indent_level {
-            write!(self.w, "    ")?;
+            self.append("    ")?;
         }
         Ok(())
     }
 
     pub fn append_newline(&mut self) -> Result<()> {
-        writeln!(self.w, "")?;
+        self.flush_segment()?;
+        writeln!(self.w)?;
+        self.column = 0;
         Ok(())
     }
 
     pub fn append(&mut self, s: &str) -> Result<()> {
-        write!(self.w, "{}", s)?;
+        if self.pending_break.is_some() {
+            self.segment.push_str(s);
+        } else {
+            self.write(s)?;
+        }
         Ok(())
     }
 
@@ -44,4 +69,57 @@ impl<W: Write> CodeWriter<W> {
         self.append_newline()?;
         Ok(())
     }
+
+    /// Appends a space, or a newline followed by a continuation indent (one level deeper than the current indent level) if the text appended between this break and the next break or newline doesn't fit on the current line.
+    ///
+    /// Breaks must only be appended where whitespace is insignificant in the generated language, and never at the end of a line.
+    pub fn append_break(&mut self) -> Result<()> {
+        self.flush_segment()?;
+        self.pending_break = Some(" ");
+        Ok(())
+    }
+
+    /// Like [`append_break`](Self::append_break), but appends nothing instead of a space if the following text fits on the current line.
+    pub fn append_soft_break(&mut self) -> Result<()> {
+        self.flush_segment()?;
+        self.pending_break = Some("");
+        Ok(())
+    }
+
+    fn flush_segment(&mut self) -> Result<()> {
+        let separator = match self.pending_break.take() {
+            Some(separator) => separator,
+            None => return Ok(()),
+        };
+
+        let segment = std::mem::take(&mut self.segment);
+        if segment.is_empty() {
+            return Ok(());
+        }
+        let continuation_indent = (self.indent_level + 1) * 4;
+        match self.max_line_width {
+            // Breaking a line that's already at the continuation indent wouldn't make it any shorter
+            Some(max_line_width)
+                if self.column + (separator.len() + segment.len()) as u32 > max_line_width
+                    && self.column > continuation_indent =>
+            {
+                writeln!(self.w)?;
+                self.column = 0;
+                for _ in 0..self.indent_level + 1 {
+                    self.write("    ")?;
+                }
+            }
+            _ => self.write(separator)?,
+        }
+        self.write(&segment)
+    }
+
+    fn write(&mut self, s: &str) -> Result<()> {
+        write!(self.w, "{}", s)?;
+        match s.rfind('\n') {
+            Some(index) => self.column = (s.len() - index - 1) as u32,
+            None => self.column += s.len() as u32,
+        }
+        Ok(())
+    }
 }
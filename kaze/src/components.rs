@@ -0,0 +1,642 @@
+//! Reusable hardware components built on top of kaze's core [`Module`] API.
+//!
+//! Each function in this module creates a new [`Module`] in the given parent and returns it, so the result can be used directly as a top-level module for code generation, or driven as an instance inside another [`Module`]. Components that are mainly useful as instances return their [`Module`] along with its ports instead.
+
+use crate::graph::*;
+
+/// Creates a register file [`Module`] called `name` with `1 << address_bit_width` registers, each `data_bit_width` bits wide.
+///
+/// The register file is built on a [`Mem`], and has a single write port and `num_read_ports` read ports.
+/// The resulting [`Module`] has the following ports:
+///
+/// - `wr_addr` (`address_bit_width` bits), `wr_data` (`data_bit_width` bits), and `wr_enable` (1 bit), which make up the write port.
+/// - `rd{i}_addr` (`address_bit_width` bits), `rd{i}_enable` (1 bit), and `rd{i}_data` (`data_bit_width` bits) for each read port `i` in `0..num_read_ports`.
+///
+/// Reads are synchronous, just like [`Mem::read_port`]: when `rd{i}_enable` is asserted, `rd{i}_data` reflects the register at `rd{i}_addr` on the following cycle.
+/// When `rd{i}_enable` is not asserted, `rd{i}_data` holds its previous value, which makes it possible to stall a pipeline stage without losing the data it has read.
+///
+/// If `write_through` is `false`, a read from the same location that is being written in the same cycle returns the **previous** value at that location, which matches the read-under-write semantics of [`Mem`].
+/// If `write_through` is `true`, the register file instead forwards the newly-written value to any read port that reads the location being written in the same cycle.
+///
+/// # Panics
+///
+/// Panics if `num_read_ports` is `0`, or if `address_bit_width` or `data_bit_width` is less than [`MIN_SIGNAL_BIT_WIDTH`] or greater than [`MAX_SIGNAL_BIT_WIDTH`], respectively.
+///
+/// # Examples
+///
+/// ```
+/// use kaze::*;
+///
+/// let c = Context::new();
+///
+/// // 2R1W register file with 32 32-bit registers, suitable for a small RISC core
+/// let rf = components::register_file(&c, "rf", "RegisterFile", 5, 32, 2, true);
+/// ```
+pub fn register_file<'a>(
+    p: &'a impl ModuleParent<'a>,
+    instance_name: impl Into<String>,
+    name: impl Into<String>,
+    address_bit_width: u32,
+    data_bit_width: u32,
+    num_read_ports: u32,
+    write_through: bool,
+) -> &'a Module<'a> {
+    let name = name.into();
+    if num_read_ports == 0 {
+        panic!(
+            "Cannot create register file \"{}\" with 0 read ports. Register files must have at least 1 read port.",
+            name
+        );
+    }
+
+    let m = p.module(instance_name, name);
+
+    let mem = m.mem("mem", address_bit_width, data_bit_width);
+
+    let wr_addr = m.input("wr_addr", address_bit_width);
+    let wr_data = m.input("wr_data", data_bit_width);
+    let wr_enable = m.input("wr_enable", 1);
+    mem.write_port(wr_addr, wr_data, wr_enable);
+
+    for i in 0..num_read_ports {
+        let rd_addr = m.input(format!("rd{}_addr", i), address_bit_width);
+        let rd_enable = m.input(format!("rd{}_enable", i), 1);
+        let mut rd_data = mem.read_port(rd_addr, rd_enable);
+
+        if write_through {
+            // Remember whether this read collided with a write, along with the written value,
+            //  so we can select it instead of the (stale) value read from the mem on the next cycle
+            let forward = m.reg(format!("rd{}_forward", i), 1);
+            forward.default_value(false);
+            forward.drive_next(rd_enable.mux(wr_enable & wr_addr.eq(rd_addr), forward));
+            let forward_data = m.reg(format!("rd{}_forward_data", i), data_bit_width);
+            forward_data.drive_next(rd_enable.mux(wr_data, forward_data));
+            rd_data = forward.mux(forward_data, rd_data);
+        }
+
+        m.output(format!("rd{}_data", i), rd_data);
+    }
+
+    m
+}
+
+/// The ports of a FIFO [`Module`] created by [`fifo`].
+pub struct Fifo<'a> {
+    /// The FIFO [`Module`].
+    pub module: &'a Module<'a>,
+
+    /// Adds `enqueue_data` to the FIFO while high and the FIFO isn't full (1 bit).
+    pub enqueue: &'a Input<'a>,
+    /// The entry to add to the FIFO.
+    pub enqueue_data: &'a Input<'a>,
+    /// Removes the oldest entry from the FIFO while high and the FIFO isn't empty (1 bit).
+    pub dequeue: &'a Input<'a>,
+    /// The oldest entry in the FIFO, valid while the FIFO isn't empty.
+    pub dequeue_data: &'a Output<'a>,
+    /// High while the FIFO holds `depth` entries (1 bit).
+    pub full: &'a Output<'a>,
+    /// High while the FIFO holds no entries (1 bit).
+    pub empty: &'a Output<'a>,
+}
+
+/// Creates a synchronous FIFO [`Module`] called `name`, which holds up to `depth` entries, each `data_bit_width` bits wide, and returns its ports.
+///
+/// The FIFO is built on a [`Mem`] with a [combinational read port](Mem::read_port_comb), so the oldest entry is visible on `dequeue_data` as soon as it's enqueued (often called "first-word fall-through").
+/// Its ports are described in [`Fifo`].
+///
+/// Enqueues while the FIFO is full and dequeues while it's empty are ignored. An entry can be enqueued and another dequeued in the same cycle, unless the FIFO is full or empty, respectively.
+/// `depth` doesn't need to be a power of two; the underlying [`Mem`] is rounded up to the next power of two, and the read and write pointers wrap around after `depth` entries.
+///
+/// # Panics
+///
+/// Panics if `depth` is `0`, or if `data_bit_width` is less than [`MIN_SIGNAL_BIT_WIDTH`] or greater than [`MAX_SIGNAL_BIT_WIDTH`], respectively.
+///
+/// # Examples
+///
+/// ```
+/// use kaze::*;
+///
+/// let c = Context::new();
+///
+/// let m = c.module("m", "MyModule");
+///
+/// // 6 entries of 32 bits each
+/// let fifo = components::fifo(m, "fifo", "Fifo", 32, 6);
+/// fifo.enqueue.drive(m.input("push", 1));
+/// fifo.enqueue_data.drive(m.input("push_data", 32));
+/// fifo.dequeue.drive(m.input("pop", 1));
+/// m.output("pop_data", fifo.dequeue_data);
+/// m.output("ready", !fifo.full);
+/// m.output("valid", !fifo.empty);
+/// ```
+pub fn fifo<'a>(
+    p: &'a impl ModuleParent<'a>,
+    instance_name: impl Into<String>,
+    name: impl Into<String>,
+    data_bit_width: u32,
+    depth: u32,
+) -> Fifo<'a> {
+    let name = name.into();
+    if depth == 0 {
+        panic!(
+            "Cannot create FIFO \"{}\" with a depth of 0. FIFOs must have a depth of at least 1.",
+            name
+        );
+    }
+    let address_bit_width = (32 - (depth - 1).leading_zeros()).max(1);
+    let count_bit_width = 32 - depth.leading_zeros();
+
+    let m = p.module(instance_name, name);
+
+    let enqueue = m.input("enqueue", 1);
+    let enqueue_data = m.input("enqueue_data", data_bit_width);
+    let dequeue = m.input("dequeue", 1);
+
+    let count = m.reg("count", count_bit_width);
+    count.default_value(0u32);
+    let full = count.eq(m.lit(depth, count_bit_width));
+    let empty = count.eq(m.lit(0u32, count_bit_width));
+
+    let do_enqueue = enqueue & !full;
+    let do_dequeue = dequeue & !empty;
+
+    let next_address = |address: &'a dyn Signal<'a>| {
+        address.eq(m.lit(depth - 1, address_bit_width)).mux(
+            m.lit(0u32, address_bit_width),
+            address + m.lit(1u32, address_bit_width),
+        )
+    };
+
+    let write_address = m.reg("write_address", address_bit_width);
+    write_address.default_value(0u32);
+    write_address.drive_next(do_enqueue.mux(next_address(write_address), write_address));
+    let read_address = m.reg("read_address", address_bit_width);
+    read_address.default_value(0u32);
+    read_address.drive_next(do_dequeue.mux(next_address(read_address), read_address));
+
+    count.drive_next(
+        if_(
+            do_enqueue & !do_dequeue,
+            count + m.lit(1u32, count_bit_width),
+        )
+        .else_if(
+            do_dequeue & !do_enqueue,
+            count - m.lit(1u32, count_bit_width),
+        )
+        .else_(count),
+    );
+
+    let mem = m.mem("mem", address_bit_width, data_bit_width);
+    mem.write_port(write_address, enqueue_data, do_enqueue);
+
+    Fifo {
+        module: m,
+
+        enqueue,
+        enqueue_data,
+        dequeue,
+        dequeue_data: m.output("dequeue_data", mem.read_port_comb(read_address)),
+        full: m.output("full", full),
+        empty: m.output("empty", empty),
+    }
+}
+
+/// Creates a strobe selector [`Module`] called `name`, which passes through one of `num_strobes` single-cycle strobes (periodic enable pulses, eg. from a counter-based divider) and switches between them without creating extra or shortened pulses.
+///
+/// The resulting [`Module`] has the following ports:
+///
+/// - `strobe{i}` (1 bit) for each strobe `i` in `0..num_strobes`.
+/// - `select` (just enough bits to represent `num_strobes - 1`, with a minimum of 1 bit), which requests which strobe should be passed through.
+/// - `strobe_out` (1 bit), which is the currently-selected strobe.
+///
+/// The selection is registered, and initially selects `strobe0`. A different strobe requested with `select` only takes effect according to the following switching rule:
+///
+/// 1. The new selection is latched on a *common boundary*, which is a cycle in which both the currently-selected strobe and the requested strobe are high. Strobe `i` is still passed through on that cycle.
+/// 2. The latched selection is applied on the next cycle in which both of these strobes are low. Until then, the previously-selected strobe is still passed through.
+///
+/// This means every pulse on `strobe_out` is a complete pulse of one of the strobes, and the time between two consecutive pulses on `strobe_out` is never shorter than the period of the faster of the two strobes involved in a switch.
+/// Simply switching on any cycle in which all strobes are low is not enough to guarantee this: a pulse of the old strobe could be followed by a pulse of the new strobe only a couple of cycles later.
+///
+/// Strobes derived from the same counter (eg. an 8 kHz and a 1 kHz strobe, where every 8th pulse of the former lines up with a pulse of the latter) share a common boundary at least once per period of the slower strobe.
+/// Strobes that are never high in the same cycle can't be switched between, and requests for values of `select` that don't correspond to a strobe are ignored.
+///
+/// # Panics
+///
+/// Panics if `num_strobes` is `0`.
+///
+/// # Examples
+///
+/// ```
+/// use kaze::*;
+///
+/// let c = Context::new();
+///
+/// let strobe_select = components::strobe_select(&c, "strobe_select", "StrobeSelect", 2);
+/// ```
+pub fn strobe_select<'a>(
+    p: &'a impl ModuleParent<'a>,
+    instance_name: impl Into<String>,
+    name: impl Into<String>,
+    num_strobes: u32,
+) -> &'a Module<'a> {
+    let name = name.into();
+    if num_strobes == 0 {
+        panic!(
+            "Cannot create strobe selector \"{}\" with 0 strobes. Strobe selectors must have at least 1 strobe.",
+            name
+        );
+    }
+    let select_bit_width = (32 - (num_strobes - 1).leading_zeros()).max(1);
+
+    let m = p.module(instance_name, name);
+
+    let strobes = (0..num_strobes)
+        .map(|i| m.input(format!("strobe{}", i), 1))
+        .collect::<Vec<_>>();
+    let select = m.input("select", select_bit_width);
+
+    let strobe_at = |index: &'a dyn Signal<'a>| {
+        strobes
+            .iter()
+            .enumerate()
+            .fold(m.low(), |acc, (i, &strobe)| {
+                index.eq(m.lit(i as u32, select_bit_width)).mux(strobe, acc)
+            })
+    };
+
+    let current = m.reg("current", select_bit_width);
+    current.default_value(0u32);
+    let pending = m.reg("pending", select_bit_width);
+    pending.default_value(0u32);
+    let switching = m.reg("switching", 1);
+    switching.default_value(false);
+
+    let current_strobe = strobe_at(current);
+    let requested_strobe = strobe_at(select);
+    let pending_strobe = strobe_at(pending);
+
+    let boundary = !switching & select.ne(current) & current_strobe & requested_strobe;
+    let apply = switching & !current_strobe & !pending_strobe;
+
+    switching.drive_next(boundary.mux(m.high(), apply.mux(m.low(), switching)));
+    pending.drive_next(boundary.mux(select, pending));
+    current.drive_next(apply.mux(pending, current));
+
+    m.output("strobe_out", current_strobe);
+
+    m
+}
+
+/// Creates a strobe aligner [`Module`] called `name`, which delays a single-cycle strobe by `phase_offset_cycles` cycles.
+///
+/// The resulting [`Module`] has an input called `strobe_in` (1 bit) and an output called `strobe_out` (1 bit), which pulses `phase_offset_cycles` cycles after each pulse on `strobe_in`.
+///
+/// The delay is implemented with a small down-counter instead of a shift register, so its cost grows with the logarithm of `phase_offset_cycles` rather than linearly.
+/// As a consequence, only one pulse can be in flight at a time: the period of `strobe_in` must be at least `phase_offset_cycles` cycles, and a pulse that arrives while another one is still being delayed replaces it.
+/// If `phase_offset_cycles` is `0`, `strobe_out` is simply `strobe_in`.
+///
+/// # Examples
+///
+/// ```
+/// use kaze::*;
+///
+/// let c = Context::new();
+///
+/// // Delay a strobe by 3 cycles, eg. to line it up with a 3-stage pipeline
+/// let align_strobe = components::align_strobe(&c, "align_strobe", "AlignStrobe", 3);
+/// ```
+pub fn align_strobe<'a>(
+    p: &'a impl ModuleParent<'a>,
+    instance_name: impl Into<String>,
+    name: impl Into<String>,
+    phase_offset_cycles: u32,
+) -> &'a Module<'a> {
+    let m = p.module(instance_name, name);
+
+    let strobe_in = m.input("strobe_in", 1);
+
+    if phase_offset_cycles == 0 {
+        m.output("strobe_out", strobe_in);
+        return m;
+    }
+
+    // The counter holds the number of cycles until the pending pulse is emitted, plus 1, or 0
+    //  if there's no pending pulse
+    let count_bit_width = 32 - phase_offset_cycles.leading_zeros();
+    let count = m.reg("count", count_bit_width);
+    count.default_value(0u32);
+    count.drive_next(
+        strobe_in.mux(
+            m.lit(phase_offset_cycles, count_bit_width),
+            count
+                .eq(m.lit(0u32, count_bit_width))
+                .mux(count, count - m.lit(1u32, count_bit_width)),
+        ),
+    );
+
+    m.output("strobe_out", count.eq(m.lit(1u32, count_bit_width)));
+
+    m
+}
+
+/// The ports of a debug port [`Module`] created by [`debug_port`].
+pub struct DebugPort<'a> {
+    /// The debug port [`Module`].
+    pub module: &'a Module<'a>,
+
+    /// Shifts the frame by one bit each cycle while high (1 bit).
+    pub shift: &'a Input<'a>,
+    /// Executes the frame while high (1 bit).
+    pub update: &'a Input<'a>,
+    /// The serial data shifted into the frame's MSB (1 bit).
+    pub tdi: &'a Input<'a>,
+    /// The serial data shifted out of the frame's LSB (1 bit).
+    pub tdo: &'a Output<'a>,
+
+    /// The address of the register being accessed.
+    pub bus_addr: &'a Output<'a>,
+    /// The value to write to the register at `bus_addr`.
+    pub bus_write_data: &'a Output<'a>,
+    /// Asserted for one cycle for each write (1 bit).
+    pub bus_write_enable: &'a Output<'a>,
+    /// The current value of the register at `bus_addr`, which must be driven combinationally.
+    pub bus_read_data: &'a Input<'a>,
+}
+
+/// Creates a debug port [`Module`] called `name`, which provides peek/poke access to a bank of registers through a minimal serial protocol, and returns its ports.
+///
+/// Debug ports are meant for bring-up: the same protocol can be driven in generated simulators with [`runtime::debug`](crate::runtime::debug) and on hardware through any transport that can toggle a few pins, so register accesses behave identically in both.
+///
+/// The resulting [`Module`] has the following ports:
+///
+/// - `shift` (1 bit), `update` (1 bit), and `tdi` (1 bit), which are driven by the host, and `tdo` (1 bit), which is read by the host.
+/// - `bus_addr` (`address_bit_width` bits), `bus_write_data` (`data_bit_width` bits), and `bus_write_enable` (1 bit), which write to the register bank, and `bus_read_data` (`data_bit_width` bits), which must reflect the register at `bus_addr` combinationally.
+///
+/// Commands are exchanged through a *frame* register of `1 + address_bit_width + data_bit_width` bits. From LSB to MSB, a frame contains an opcode bit (`1` for writes, `0` for reads), an address, and a data value. The protocol works as follows:
+///
+/// 1. While `shift` is high, the frame is shifted towards its LSB by one bit each cycle, with `tdi` entering at the MSB. `tdo` always reflects the frame's LSB, so a host shifts a new frame in LSB-first while shifting the previous one out.
+/// 2. When `update` is high (and `shift` is low), the frame is executed. For writes, `bus_write_enable` is asserted for that cycle, with `bus_addr` and `bus_write_data` taken from the frame. For reads, the frame's data is replaced with `bus_read_data` on the following cycle, so it can be shifted out while shifting in the next frame.
+///
+/// # Panics
+///
+/// Panics if `address_bit_width` or `data_bit_width` is `0`, or if the resulting frame is wider than [`MAX_SIGNAL_BIT_WIDTH`].
+///
+/// # Examples
+///
+/// ```
+/// use kaze::*;
+///
+/// let c = Context::new();
+///
+/// let m = c.module("m", "MyModule");
+///
+/// // Debug port for up to 16 32-bit registers
+/// let debug_port = components::debug_port(m, "debug_port", "DebugPort", 4, 32);
+/// debug_port.shift.drive(m.input("dbg_shift", 1));
+/// debug_port.update.drive(m.input("dbg_update", 1));
+/// debug_port.tdi.drive(m.input("dbg_tdi", 1));
+/// m.output("dbg_tdo", debug_port.tdo);
+///
+/// let control = m.reg("control", 32);
+/// control.default_value(0u32);
+/// let is_control = debug_port.bus_addr.eq(m.lit(0u32, 4));
+/// control.drive_next((debug_port.bus_write_enable & is_control).mux(debug_port.bus_write_data, control));
+/// debug_port.bus_read_data.drive(is_control.mux(control, m.lit(0u32, 32)));
+/// ```
+pub fn debug_port<'a>(
+    p: &'a impl ModuleParent<'a>,
+    instance_name: impl Into<String>,
+    name: impl Into<String>,
+    address_bit_width: u32,
+    data_bit_width: u32,
+) -> DebugPort<'a> {
+    let name = name.into();
+    let frame_bit_width = 1 + address_bit_width + data_bit_width;
+    if address_bit_width == 0 || data_bit_width == 0 || frame_bit_width > MAX_SIGNAL_BIT_WIDTH {
+        panic!(
+            "Cannot create debug port \"{}\" with {} address bit(s) and {} data bit(s). Debug ports must have at least 1 address bit and 1 data bit, and their frames (1 opcode bit, plus the address and data bits) must not be wider than {} bit(s).",
+            name, address_bit_width, data_bit_width, MAX_SIGNAL_BIT_WIDTH
+        );
+    }
+
+    let m = p.module(instance_name, name);
+
+    let shift = m.input("shift", 1);
+    let update = m.input("update", 1);
+    let tdi = m.input("tdi", 1);
+    let bus_read_data = m.input("bus_read_data", data_bit_width);
+
+    let frame = m.reg("frame", frame_bit_width);
+    frame.default_value(0u32);
+    let is_write = frame.bit(0);
+
+    let execute = update & !shift;
+    frame.drive_next(
+        if_(shift, tdi.concat(frame.bits(frame_bit_width - 1, 1)))
+            .else_if(
+                execute & !is_write,
+                bus_read_data.concat(frame.bits(address_bit_width, 0)),
+            )
+            .else_(frame),
+    );
+
+    DebugPort {
+        module: m,
+
+        shift,
+        update,
+        tdi,
+        tdo: m.output("tdo", is_write),
+
+        bus_addr: m.output("bus_addr", frame.bits(address_bit_width, 1)),
+        bus_write_data: m.output(
+            "bus_write_data",
+            frame.bits(frame_bit_width - 1, address_bit_width + 1),
+        ),
+        bus_write_enable: m.output("bus_write_enable", execute & is_write),
+        bus_read_data,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create register file \"RegisterFile\" with 0 read ports. Register files must have at least 1 read port."
+    )]
+    fn register_file_no_read_ports_error() {
+        let c = Context::new();
+
+        // Panic
+        let _ = components::register_file(&c, "rf", "RegisterFile", 2, 8, 0, false);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create FIFO \"Fifo\" with a depth of 0. FIFOs must have a depth of at least 1."
+    )]
+    fn fifo_depth_error() {
+        let c = Context::new();
+
+        // Panic
+        let _ = components::fifo(&c, "fifo", "Fifo", 8, 0);
+    }
+
+    #[test]
+    fn fifo_ports() {
+        let c = Context::new();
+
+        for &(depth, address_bit_width, count_bit_width) in
+            &[(1, 1, 1), (2, 1, 2), (3, 2, 2), (4, 2, 3), (5, 3, 3)]
+        {
+            let m = components::fifo(&c, "fifo", format!("Fifo{}", depth), 8, depth).module;
+
+            assert_eq!(
+                m.inputs().collect::<Vec<_>>(),
+                vec![("dequeue", 1), ("enqueue", 1), ("enqueue_data", 8)]
+            );
+            assert_eq!(
+                m.outputs().collect::<Vec<_>>(),
+                vec![("dequeue_data", 8), ("empty", 1), ("full", 1)]
+            );
+            let mem = m.memories().next().unwrap();
+            assert_eq!(mem.address_bit_width(), address_bit_width);
+            let count = m.registers().find(|r| r.name() == "count").unwrap();
+            assert_eq!(count.data.bit_width, count_bit_width);
+        }
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create strobe selector \"StrobeSelect\" with 0 strobes. Strobe selectors must have at least 1 strobe."
+    )]
+    fn strobe_select_no_strobes_error() {
+        let c = Context::new();
+
+        // Panic
+        let _ = components::strobe_select(&c, "strobe_select", "StrobeSelect", 0);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create debug port \"DebugPort\" with 100 address bit(s) and 28 data bit(s). Debug ports must have at least 1 address bit and 1 data bit, and their frames (1 opcode bit, plus the address and data bits) must not be wider than 128 bit(s)."
+    )]
+    fn debug_port_frame_bit_width_error() {
+        let c = Context::new();
+
+        // Panic
+        let _ = components::debug_port(&c, "debug_port", "DebugPort", 100, 28);
+    }
+
+    #[test]
+    fn debug_port_ports() {
+        let c = Context::new();
+
+        let debug_port = components::debug_port(&c, "debug_port", "DebugPort", 3, 16);
+        let m = debug_port.module;
+
+        assert_eq!(
+            m.inputs.borrow().keys().collect::<Vec<_>>(),
+            vec!["bus_read_data", "shift", "tdi", "update"]
+        );
+        assert_eq!(m.inputs.borrow()["bus_read_data"].data.bit_width, 16);
+        assert_eq!(
+            m.outputs.borrow().keys().collect::<Vec<_>>(),
+            vec!["bus_addr", "bus_write_data", "bus_write_enable", "tdo"]
+        );
+        assert_eq!(debug_port.bus_addr.data.bit_width, 3);
+        assert_eq!(debug_port.bus_write_data.data.bit_width, 16);
+    }
+
+    #[test]
+    fn strobe_select_ports() {
+        let c = Context::new();
+
+        let m = components::strobe_select(&c, "strobe_select", "StrobeSelect", 5);
+
+        assert_eq!(
+            m.inputs.borrow().keys().collect::<Vec<_>>(),
+            vec!["select", "strobe0", "strobe1", "strobe2", "strobe3", "strobe4"]
+        );
+        assert_eq!(m.inputs.borrow()["select"].data.bit_width, 3);
+        assert_eq!(
+            m.outputs.borrow().keys().collect::<Vec<_>>(),
+            vec!["strobe_out"]
+        );
+    }
+
+    #[test]
+    fn register_file_verilog() {
+        let c = Context::new();
+
+        let rf = components::register_file(&c, "rf", "RegisterFile", 2, 8, 1, false);
+
+        let mut v = Vec::new();
+        verilog::generate(rf, verilog::GenerationOptions::default(), &mut v).unwrap();
+
+        assert_eq!(
+            String::from_utf8(v).unwrap(),
+            r#"module RegisterFile(
+    input wire reset_n,
+    input wire clk,
+
+    input wire [1:0] rd0_addr,
+    input wire rd0_enable,
+    input wire [1:0] wr_addr,
+    input wire [7:0] wr_data,
+    input wire wr_enable,
+    output wire [7:0] rd0_data
+    );
+
+    wire [1:0] __mem_rf_mem_0_read_port_0_address;
+    wire __mem_rf_mem_0_read_port_0_enable;
+    reg [7:0] __mem_rf_mem_0_read_port_0_value;
+    wire [1:0] __mem_rf_mem_0_write_port_address;
+    wire [7:0] __mem_rf_mem_0_write_port_value;
+    wire __mem_rf_mem_0_write_port_enable;
+
+    reg [7:0] __mem_rf_mem_0[0:3];
+
+    always @(posedge clk) begin
+        if (__mem_rf_mem_0_read_port_0_enable) begin
+            __mem_rf_mem_0_read_port_0_value <= __mem_rf_mem_0[__mem_rf_mem_0_read_port_0_address];
+        end
+        if (__mem_rf_mem_0_write_port_enable) begin
+            __mem_rf_mem_0[__mem_rf_mem_0_write_port_address] <= __mem_rf_mem_0_write_port_value;
+        end
+    end
+
+    assign rd0_data = __mem_rf_mem_0_read_port_0_value;
+    assign __mem_rf_mem_0_read_port_0_address = rd0_addr;
+    assign __mem_rf_mem_0_read_port_0_enable = rd0_enable;
+    assign __mem_rf_mem_0_write_port_address = wr_addr;
+    assign __mem_rf_mem_0_write_port_value = wr_data;
+    assign __mem_rf_mem_0_write_port_enable = wr_enable;
+
+endmodule
+
+"#
+        );
+    }
+
+    #[test]
+    fn register_file_write_through_verilog() {
+        let c = Context::new();
+
+        let rf = components::register_file(&c, "rf", "RegisterFile", 2, 8, 2, true);
+
+        let mut v = Vec::new();
+        verilog::generate(rf, verilog::GenerationOptions::default(), &mut v).unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        for i in 0..2 {
+            assert!(v.contains(&format!("input wire [1:0] rd{}_addr,", i)));
+            assert!(v.contains(&format!("input wire rd{}_enable,", i)));
+            assert!(v.contains(&format!("output wire [7:0] rd{}_data", i)));
+            assert!(v.contains(&format!("reg __reg_rf_rd{}_forward_", i)));
+            assert!(v.contains(&format!("reg [7:0] __reg_rf_rd{}_forward_data_", i)));
+        }
+    }
+}
@@ -1,16 +1,38 @@
+mod attribute;
+mod bundle;
+mod clock_divider;
 mod constant;
 mod context;
+mod deferred;
+mod inout;
+mod instance_array;
 pub(crate) mod internal_signal;
 mod mem;
 mod module;
+mod module_declaration;
+mod module_params;
 mod register;
+mod reset_domain;
 mod signal;
+mod signal_vec;
 mod sugar;
+mod warning;
 
+pub use attribute::*;
+pub use bundle::*;
+pub use clock_divider::*;
 pub use constant::*;
 pub use context::*;
+pub use deferred::*;
+pub use inout::*;
+pub use instance_array::*;
 pub use mem::*;
 pub use module::*;
+pub use module_declaration::*;
+pub use module_params::*;
 pub use register::*;
+pub use reset_domain::*;
 pub use signal::*;
+pub use signal_vec::*;
 pub use sugar::*;
+pub use warning::*;
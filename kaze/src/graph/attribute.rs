@@ -0,0 +1,97 @@
+use crate::identifiers::is_identifier;
+
+use std::cell::RefCell;
+
+/// The attribute namespace reserved for kaze's internal use.
+///
+/// Attributes in this namespace have meaning to kaze itself (for example, to optimization passes or code generators), so they can't be added with the general-purpose `attribute` methods; they're only set by dedicated, typed methods.
+/// Attributes in any other namespace are never interpreted by kaze, and are passed through verbatim to generated code.
+pub const RESERVED_ATTRIBUTE_NAMESPACE: &str = "kaze";
+
+/// A `(namespace, key, value)` triple attached to a [`Register`], [`Input`], or [`Output`].
+///
+/// Attributes are added with [`Register::attribute`], [`Input::attribute`], and [`Output::attribute`] (or in bulk with [`Module::apply_attribute_to_registers`]), and can be read back with [`Signal::attributes`].
+///
+/// In generated Verilog code, each attribute is emitted on the corresponding declaration as `(* namespace_key = "value" *)`.
+/// In generated Rust simulator code, attributes on top-level ports and registers are emitted as doc comments on the corresponding fields.
+///
+/// # Examples
+///
+/// ```
+/// use kaze::*;
+///
+/// let c = Context::new();
+///
+/// let m = c.module("m", "MyModule");
+///
+/// let sync = m.reg("sync", 1);
+/// sync.attribute("xilinx", "async_reg", "true");
+/// sync.drive_next(m.input("i", 1));
+/// m.output("o", sync);
+///
+/// assert_eq!(
+///     sync.attributes(),
+///     vec![Attribute {
+///         namespace: "xilinx".into(),
+///         key: "async_reg".into(),
+///         value: "true".into(),
+///     }]
+/// );
+/// ```
+///
+/// [`Input`]: super::Input
+/// [`Input::attribute`]: super::Input::attribute
+/// [`Module::apply_attribute_to_registers`]: super::Module::apply_attribute_to_registers
+/// [`Output`]: super::Output
+/// [`Output::attribute`]: super::Output::attribute
+/// [`Register`]: super::Register
+/// [`Register::attribute`]: super::Register::attribute
+/// [`Signal::attributes`]: super::Signal::attributes
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Attribute {
+    pub namespace: String,
+    pub key: String,
+    pub value: String,
+}
+
+/// Adds an attribute from the public API to `attributes`, rejecting the reserved namespace.
+///
+/// `target` describes the owner of `attributes` for error messages (eg. `register "r" in module "A"`).
+pub(super) fn add_user_attribute(
+    attributes: &RefCell<Vec<Attribute>>,
+    namespace: String,
+    key: String,
+    value: String,
+    target: impl FnOnce() -> String,
+) {
+    if namespace == RESERVED_ATTRIBUTE_NAMESPACE {
+        panic!("Cannot add attribute \"{}.{}\" to {}, because the \"{}\" attribute namespace is reserved for kaze's internal use.", namespace, key, target(), RESERVED_ATTRIBUTE_NAMESPACE);
+    }
+    add_attribute(attributes, namespace, key, value, target);
+}
+
+/// Adds an attribute to `attributes`, replacing the value of any existing attribute with the same namespace and key.
+pub(super) fn add_attribute(
+    attributes: &RefCell<Vec<Attribute>>,
+    namespace: String,
+    key: String,
+    value: String,
+    target: impl FnOnce() -> String,
+) {
+    if !is_identifier(&namespace) || !is_identifier(&key) {
+        panic!("Cannot add attribute \"{}.{}\" to {}. Attribute namespaces and keys must be non-empty, consist only of ASCII alphanumeric characters and underscores, and must not start with a digit.", namespace, key, target());
+    }
+    let mut attributes = attributes.borrow_mut();
+    if let Some(attribute) = attributes
+        .iter_mut()
+        .find(|a| a.namespace == namespace && a.key == key)
+    {
+        attribute.value = value;
+    } else {
+        attributes.push(Attribute {
+            namespace,
+            key,
+            value,
+        });
+    }
+}
@@ -0,0 +1,453 @@
+use super::module::*;
+use super::signal::*;
+
+use crate::identifiers::is_identifier;
+
+use std::ptr;
+
+/// The names and bit widths of a group of related signals, such as a valid/ready/data handshake interface, used to create [`InputBundle`]s and [`OutputBundle`]s with [`Module::input_bundle`] and [`Module::output_bundle`].
+///
+/// Fields are kept in the order they're added.
+///
+/// # Examples
+///
+/// ```
+/// use kaze::*;
+///
+/// let req = BundleSchema::new()
+///     .field("valid", 1)
+///     .field("data", 32);
+///
+/// assert_eq!(req.bit_width("data"), 32);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BundleSchema {
+    fields: Vec<(String, u32)>,
+}
+
+impl BundleSchema {
+    /// Creates a new, empty `BundleSchema`.
+    pub fn new() -> BundleSchema {
+        BundleSchema::default()
+    }
+
+    /// Adds a field called `name` with `bit_width` bits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is empty, contains characters other than ASCII alphanumeric characters and underscores, or starts with a digit, if a field called `name` was already added, or if `bit_width` is less than [`MIN_SIGNAL_BIT_WIDTH`] or greater than [`MAX_SIGNAL_BIT_WIDTH`], respectively.
+    pub fn field(mut self, name: impl Into<String>, bit_width: u32) -> BundleSchema {
+        let name = name.into();
+        validate_field_name(&name, self.fields.iter().map(|(n, _)| n));
+        if !(MIN_SIGNAL_BIT_WIDTH..=MAX_SIGNAL_BIT_WIDTH).contains(&bit_width) {
+            panic!(
+                "Cannot add a bundle field called \"{}\" with {} bit(s). Signals must not be narrower than {} bit(s) or wider than {} bit(s).",
+                name, bit_width, MIN_SIGNAL_BIT_WIDTH, MAX_SIGNAL_BIT_WIDTH
+            );
+        }
+        self.fields.push((name, bit_width));
+        self
+    }
+
+    /// Returns the bit width of the field called `name`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there's no field called `name`.
+    pub fn bit_width(&self, name: &str) -> u32 {
+        self.fields
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|&(_, bit_width)| bit_width)
+            .unwrap_or_else(|| panic!("{}", missing_field_message(name, self.field_names())))
+    }
+
+    pub(crate) fn fields(&self) -> &[(String, u32)] {
+        &self.fields
+    }
+
+    fn field_names(&self) -> Vec<&str> {
+        self.fields.iter().map(|(name, _)| name.as_str()).collect()
+    }
+}
+
+/// A group of named [`Signal`]s, used to drive an [`InputBundle`] or to create an [`OutputBundle`].
+///
+/// [`InputBundle::signals`] and [`OutputBundle::signals`] return the signals of existing bundles, so bundles can be passed through a hierarchy without naming each field.
+///
+/// # Examples
+///
+/// ```
+/// use kaze::*;
+///
+/// let c = Context::new();
+///
+/// let m = c.module("m", "MyModule");
+///
+/// let data = m.input("data", 32);
+/// let req = Bundle::new()
+///     .with("valid", m.high())
+///     .with("data", data);
+/// ```
+#[derive(Clone, Default)]
+pub struct Bundle<'a> {
+    fields: Vec<(String, &'a dyn Signal<'a>)>,
+}
+
+impl<'a> Bundle<'a> {
+    /// Creates a new, empty `Bundle`.
+    pub fn new() -> Bundle<'a> {
+        Bundle { fields: Vec::new() }
+    }
+
+    /// Adds a field called `name` which represents `signal`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is empty, contains characters other than ASCII alphanumeric characters and underscores, or starts with a digit, or if a field called `name` was already added.
+    pub fn with(mut self, name: impl Into<String>, signal: &'a dyn Signal<'a>) -> Bundle<'a> {
+        let name = name.into();
+        validate_field_name(&name, self.fields.iter().map(|(n, _)| n));
+        self.fields.push((name, signal));
+        self
+    }
+
+    /// Returns the [`Signal`] of the field called `name`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there's no field called `name`.
+    pub fn field(&self, name: &str) -> &'a dyn Signal<'a> {
+        self.fields
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|&(_, signal)| signal)
+            .unwrap_or_else(|| panic!("{}", missing_field_message(name, self.field_names())))
+    }
+
+    fn field_names(&self) -> Vec<&str> {
+        self.fields.iter().map(|(name, _)| name.as_str()).collect()
+    }
+
+    /// Checks that this bundle has exactly the fields in `schema`, with matching bit widths, and that all of its signals belong to `module`.
+    pub(crate) fn validate(
+        &self,
+        description: &str,
+        schema: &BundleSchema,
+        module: &'a Module<'a>,
+    ) {
+        let mut expected_names = schema.field_names();
+        let mut provided_names = self.field_names();
+        expected_names.sort_unstable();
+        provided_names.sort_unstable();
+        if expected_names != provided_names {
+            panic!(
+                "Cannot {} with a bundle that has different fields. Expected fields: {:?}, provided fields: {:?}.",
+                description,
+                schema.field_names(),
+                self.field_names()
+            );
+        }
+        for (name, bit_width) in schema.fields() {
+            let signal = self.field(name).internal_signal();
+            if signal.bit_width() != *bit_width {
+                panic!(
+                    "Cannot {}, because field \"{}\" has {} bit(s), but the provided signal has {} bit(s).",
+                    description,
+                    name,
+                    bit_width,
+                    signal.bit_width()
+                );
+            }
+            if !ptr::eq(signal.module, module) {
+                panic!(
+                    "Cannot {}, because the provided signal for field \"{}\" belongs to module \"{}\" instead of module \"{}\".",
+                    description, name, signal.module.name, module.name
+                );
+            }
+        }
+    }
+}
+
+/// A group of [`Input`]s created from a [`BundleSchema`] with [`Module::input_bundle`].
+///
+/// Each field is an [`Input`] called `<bundle name>_<field name>`.
+#[must_use]
+pub struct InputBundle<'a> {
+    pub(crate) module: &'a Module<'a>,
+    pub(crate) name: String,
+    pub(crate) schema: BundleSchema,
+    pub(crate) inputs: Vec<&'a Input<'a>>,
+}
+
+impl<'a> InputBundle<'a> {
+    /// Returns the [`Input`] of the field called `name`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there's no field called `name`.
+    pub fn field(&self, name: &str) -> &'a Input<'a> {
+        self.schema
+            .fields()
+            .iter()
+            .position(|(n, _)| n == name)
+            .map(|index| self.inputs[index])
+            .unwrap_or_else(|| panic!("{}", missing_field_message(name, self.schema.field_names())))
+    }
+
+    /// Returns a [`Bundle`] with the values of all of this bundle's inputs, eg. to forward them to an instance's [`InputBundle`].
+    pub fn signals(&self) -> Bundle<'a> {
+        self.schema
+            .fields()
+            .iter()
+            .zip(self.inputs.iter())
+            .fold(Bundle::new(), |bundle, ((name, _), &input)| {
+                bundle.with(name.clone(), input)
+            })
+    }
+
+    /// Drives each input in this bundle with the field of the same name in `source`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `source` doesn't have exactly the same fields as this bundle, if any of `source`'s fields have a different bit width than the corresponding input, if any of `source`'s signals don't belong to this bundle's module's parent module (eg. if they're this bundle's module's own outputs), or if any of the inputs are already driven.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let req = BundleSchema::new()
+    ///     .field("valid", 1)
+    ///     .field("data", 32);
+    ///
+    /// let outer = c.module("outer", "Outer");
+    /// let outer_req = outer.input_bundle("req", &req);
+    ///
+    /// let inner = outer.module("inner", "Inner");
+    /// let inner_req = inner.input_bundle("req", &req);
+    /// inner.output("data", inner_req.field("data"));
+    ///
+    /// inner_req.drive(&outer_req.signals());
+    /// ```
+    pub fn drive(&self, source: &Bundle<'a>) {
+        let description = format!(
+            "drive input bundle \"{}\" on an instance of \"{}\"",
+            self.name, self.module.name
+        );
+        let parent = self.module.parent.unwrap_or_else(|| {
+            panic!(
+                "Cannot {}, because \"{}\" is a top-level module.",
+                description, self.module.name
+            )
+        });
+        source.validate(&description, &self.schema, parent);
+        for ((name, _), input) in self.schema.fields().iter().zip(self.inputs.iter()) {
+            input.drive(source.field(name));
+        }
+    }
+}
+
+/// A group of [`Output`]s created with [`Module::output_bundle`].
+///
+/// Each field is an [`Output`] called `<bundle name>_<field name>`.
+#[must_use]
+pub struct OutputBundle<'a> {
+    pub(crate) schema: BundleSchema,
+    pub(crate) outputs: Vec<&'a Output<'a>>,
+}
+
+impl<'a> OutputBundle<'a> {
+    /// Returns the [`Output`] of the field called `name`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there's no field called `name`.
+    pub fn field(&self, name: &str) -> &'a Output<'a> {
+        self.schema
+            .fields()
+            .iter()
+            .position(|(n, _)| n == name)
+            .map(|index| self.outputs[index])
+            .unwrap_or_else(|| panic!("{}", missing_field_message(name, self.schema.field_names())))
+    }
+
+    /// Returns a [`Bundle`] with the values of all of this bundle's outputs, eg. to drive another instance's [`InputBundle`].
+    pub fn signals(&self) -> Bundle<'a> {
+        self.schema
+            .fields()
+            .iter()
+            .zip(self.outputs.iter())
+            .fold(Bundle::new(), |bundle, ((name, _), &output)| {
+                bundle.with(name.clone(), output)
+            })
+    }
+}
+
+fn validate_field_name<'b>(name: &str, mut existing_names: impl Iterator<Item = &'b String>) {
+    if !is_identifier(name) {
+        panic!("Cannot add a bundle field called \"{}\". Bundle field names must be non-empty, consist only of ASCII alphanumeric characters and underscores, and must not start with a digit.", name);
+    }
+    if existing_names.any(|n| n == name) {
+        panic!(
+            "Cannot add a bundle field called \"{}\", because a field with the same name was already added.",
+            name
+        );
+    }
+}
+
+fn missing_field_message(name: &str, field_names: Vec<&str>) -> String {
+    format!(
+        "Cannot find bundle field \"{}\". Bundle fields: {:?}.",
+        name, field_names
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::internal_signal::SignalData;
+
+    use crate::*;
+
+    fn handshake() -> BundleSchema {
+        BundleSchema::new().field("valid", 1).field("data", 8)
+    }
+
+    #[test]
+    fn ports() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        let req = m.input_bundle("req", &handshake());
+        let resp = m.output_bundle("resp", &handshake(), &req.signals());
+
+        assert_eq!(
+            m.inputs.borrow().keys().collect::<Vec<_>>(),
+            vec!["req_data", "req_valid"]
+        );
+        assert_eq!(
+            m.outputs.borrow().keys().collect::<Vec<_>>(),
+            vec!["resp_data", "resp_valid"]
+        );
+        assert_eq!(req.field("data").data.bit_width, 8);
+        assert_eq!(resp.field("valid").data.bit_width, 1);
+    }
+
+    #[test]
+    fn drive() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        let a = m.module("a", "A");
+        let a_req = a.input_bundle("req", &handshake());
+        let a_resp = a.output_bundle("resp", &handshake(), &a_req.signals());
+        let b = m.module("b", "B");
+        let b_req = b.input_bundle("req", &handshake());
+
+        b_req.drive(&a_resp.signals());
+
+        for (name, &input) in b.inputs.borrow().iter() {
+            let driven_value = input.data.driven_value.borrow().unwrap();
+            match driven_value.data {
+                SignalData::Output { data } => assert!(std::ptr::eq(
+                    data,
+                    a_resp.field(name.trim_start_matches("req_")).data
+                )),
+                _ => panic!("Input \"{}\" isn't driven by an output.", name),
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot drive input bundle \"req\" on an instance of \"B\" with a bundle that has different fields. Expected fields: [\"valid\", \"data\"], provided fields: [\"valid\"]."
+    )]
+    fn drive_missing_field_error() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        let b = m.module("b", "B");
+        let b_req = b.input_bundle("req", &handshake());
+
+        // Panic
+        b_req.drive(&Bundle::new().with("valid", m.high()));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot drive input bundle \"req\" on an instance of \"B\", because field \"data\" has 8 bit(s), but the provided signal has 16 bit(s)."
+    )]
+    fn drive_width_mismatch_error() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        let b = m.module("b", "B");
+        let b_req = b.input_bundle("req", &handshake());
+
+        // Panic
+        b_req.drive(
+            &Bundle::new()
+                .with("valid", m.high())
+                .with("data", m.input("data", 16)),
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot drive input bundle \"req\" on an instance of \"B\", because the provided signal for field \"valid\" belongs to module \"B\" instead of module \"M\"."
+    )]
+    fn drive_direction_error() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        let b = m.module("b", "B");
+        let b_req = b.input_bundle("req", &handshake());
+
+        // Panic
+        b_req.drive(&b_req.signals());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create output bundle \"resp\" in module \"M\", because field \"valid\" has 1 bit(s), but the provided signal has 8 bit(s)."
+    )]
+    fn output_width_mismatch_error() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+
+        // Panic
+        let _ = m.output_bundle(
+            "resp",
+            &handshake(),
+            &Bundle::new()
+                .with("valid", m.input("valid", 8))
+                .with("data", m.input("data", 8)),
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot add a bundle field called \"valid\", because a field with the same name was already added."
+    )]
+    fn duplicate_field_error() {
+        // Panic
+        let _ = handshake().field("valid", 1);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot find bundle field \"ready\". Bundle fields: [\"valid\", \"data\"]."
+    )]
+    fn missing_field_error() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        let req = m.input_bundle("req", &handshake());
+
+        // Panic
+        let _ = req.field("ready");
+    }
+}
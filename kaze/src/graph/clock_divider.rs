@@ -0,0 +1,122 @@
+use super::internal_signal::*;
+use super::module::*;
+use super::register::*;
+
+/// A divided clock, created by the [`Module::clock_divider`] method.
+///
+/// A `ClockDivider` divides its [`Module`]'s implicit clock by a constant factor, and clocks the [`Register`]s created with its [`reg`](Self::reg) method, which describes slow logic (for example, a peripheral that runs at `clk / 4`) without requiring a separate clock input.
+///
+/// In generated Rust simulator code, the divider is modeled as a counter that's clocked by the implicit clock and produces an enable pulse in every `divide_by`th cycle; registers clocked by the divider are updated by `posedge_clk` like any other register, but only keep their next values when the pulse is high.
+/// In generated Verilog code, the divided clock is a register marked with a `(* generated_clock = "divide_by_<divide_by>" *)` attribute, and registers clocked by the divider are updated in an `always` block on its positive edge, which expresses the intent to downstream tools (for example, for clock gating insertion).
+/// In both cases, the divided clock's first positive edge comes with the first positive edge of the implicit clock after reset, and every `divide_by`th edge after that.
+///
+/// A `ClockDivider` is also a [`Signal`](super::Signal) representing the divided clock, but it can't be used as data: code generation panics if it's used to compute the value of an output, register, memory port, or any other signal.
+///
+/// # Examples
+///
+/// ```
+/// use kaze::*;
+///
+/// let c = Context::new();
+///
+/// let m = c.module("m", "MyModule");
+///
+/// let div4 = m.clock_divider("div4", 4);
+///
+/// let slow_counter = div4.reg("slow_counter", 8);
+/// slow_counter.default_value(0u32);
+/// slow_counter.drive_next(slow_counter + m.lit(1u32, 8)); // Incremented once every 4 cycles
+/// m.output("slow_count", slow_counter);
+/// ```
+#[must_use]
+pub struct ClockDivider<'a> {
+    pub(crate) module: &'a Module<'a>,
+
+    pub(crate) name: String,
+    pub(crate) divide_by: u32,
+    /// The divided clock, which is high for the first `divide_by / 2` cycles of each period.
+    pub(crate) clock: &'a Register<'a>,
+    /// High in the last cycle of each period, ie. in the cycle before each positive edge of the divided clock.
+    pub(crate) enable: &'a InternalSignal<'a>,
+}
+
+impl<'a> ClockDivider<'a> {
+    /// Creates a [`Register`] in this `ClockDivider`'s [`Module`] called `name` with `bit_width` bits, which is clocked by this `ClockDivider`'s divided clock rather than by the [`Module`]'s implicit clock.
+    ///
+    /// Just like other registers, the returned [`Register`] is reset by its [`Module`]'s implicit reset (or a [`ResetDomain`](super::ResetDomain), if it's assigned to one).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bit_width` is less than [`MIN_SIGNAL_BIT_WIDTH`](super::MIN_SIGNAL_BIT_WIDTH) or greater than [`MAX_SIGNAL_BIT_WIDTH`](super::MAX_SIGNAL_BIT_WIDTH), respectively.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let div2 = m.clock_divider("div2", 2);
+    ///
+    /// let sampled = div2.reg("sampled", 16);
+    /// sampled.drive_next(m.input("i", 16)); // Sampled every other cycle
+    /// m.output("o", sampled);
+    /// ```
+    pub fn reg(&'a self, name: impl Into<String>, bit_width: u32) -> &'a Register<'a> {
+        self.module
+            .alloc_reg(name.into(), bit_width, None, Some(self))
+    }
+}
+
+impl<'a> GetInternalSignal<'a> for ClockDivider<'a> {
+    fn internal_signal(&'a self) -> &'a InternalSignal<'a> {
+        self.clock.value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create a clock divider called \"div\" in module \"A\" which divides by 1. Clock dividers must divide by at least 2."
+    )]
+    fn divide_by_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+
+        // Panic
+        let _ = m.clock_divider("div", 1);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create a clock divider called \"0div\" in module \"A\". Clock divider names must be non-empty, consist only of ASCII alphanumeric characters and underscores, and must not start with a digit."
+    )]
+    fn invalid_name_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+
+        // Panic
+        let _ = m.clock_divider("0div", 2);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create a clock divider called \"div\" in module \"A\", because this module already contains a clock divider with the same name."
+    )]
+    fn duplicate_name_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let _ = m.clock_divider("div", 2);
+
+        // Panic
+        let _ = m.clock_divider("div", 4);
+    }
+}
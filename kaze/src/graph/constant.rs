@@ -1,6 +1,10 @@
+use super::signal::*;
+
 /// A container for different types of integer constant values.
 ///
-/// This type isn't typically used explicitly, as the graph API always takes `Constant` parameters as `Into<Constant>`, and `Constant` implements `From` for most of Rust's unsigned integer types. If an API entry point requires a `Constant`, prefer passing integer values/literals directly.
+/// This type isn't typically used explicitly, as the graph API always takes `Constant` parameters as `Into<Constant>`, and `Constant` implements `From` for most of Rust's integer types. If an API entry point requires a `Constant`, prefer passing integer values/literals directly.
+///
+/// Signed values are converted to their two's complement bit patterns, truncated to the bit width of the signal, register, or memory element they're used with. A negative value fits into a bit width if it's representable as a two's complement value with that many bits (so `-1` fits into any bit width, and `-128` fits into 8 or more bits), while a non-negative signed value fits under the same rules as an unsigned value (so `255i32` fits into 8 bits).
 ///
 /// # Examples
 ///
@@ -16,6 +20,8 @@
 /// let c = m.reg("data", 20);
 /// c.default_value(5u32);
 /// let d = m.lit(42u32, 8);
+/// let e = m.lit(-1i32, 8); // Equivalent to m.lit(0xffu32, 8)
+/// let f = m.lit(-8i8, 4); // Equivalent to m.lit(0x8u32, 4)
 /// ```
 #[derive(Clone)]
 pub enum Constant {
@@ -27,9 +33,154 @@ pub enum Constant {
     U64(u64),
     /// Contains an unsigned, 128-bit value
     U128(u128),
+    /// Contains a signed, 128-bit value, which represents its two's complement bit pattern
+    I128(i128),
 }
 
 impl Constant {
+    /// Creates a `Constant` by parsing `s` as an unsigned integer in base `radix`, which must fit into `bit_width` bits.
+    ///
+    /// `s` may contain `_` characters between digits to improve readability, which are ignored. Letters in `s` can be either upper- or lowercase.
+    ///
+    /// This is useful for values that are wider than Rust's integer literals are convenient for, or that come from external sources such as test vectors.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `radix` is less than `2` or greater than `36`, if `bit_width` is less than [`MIN_SIGNAL_BIT_WIDTH`] or greater than [`MAX_SIGNAL_BIT_WIDTH`], respectively, if `s` contains no digits or any characters that aren't valid digits in base `radix` (other than `_`), or if the value represented by `s` doesn't fit into `bit_width` bits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let key = m.reg("key", 128);
+    /// key.default_value(Constant::from_str_radix("2b7e1516_28aed2a6_abf71588_09cf4f3c", 16, 128));
+    /// ```
+    pub fn from_str_radix(s: &str, radix: u32, bit_width: u32) -> Constant {
+        if !(2..=36).contains(&radix) {
+            panic!(
+                "Cannot parse \"{}\" with radix {}. The radix must be between 2 and 36.",
+                s, radix
+            );
+        }
+        validate_bit_width(bit_width, || format!("\"{}\"", s));
+        let mut value = 0u128;
+        let mut has_digits = false;
+        let mut has_overflowed = false;
+        for c in s.chars() {
+            if c == '_' {
+                continue;
+            }
+            let digit = match c.to_digit(radix) {
+                Some(digit) => digit,
+                _ => panic!(
+                    "Cannot parse \"{}\" as a base {} value, because it contains the invalid digit '{}'.",
+                    s, radix, c
+                ),
+            };
+            has_digits = true;
+            match value
+                .checked_mul(radix as _)
+                .and_then(|value| value.checked_add(digit as _))
+            {
+                Some(next_value) => value = next_value,
+                _ => has_overflowed = true,
+            }
+        }
+        if !has_digits {
+            panic!(
+                "Cannot parse \"{}\" as a base {} value, because it contains no digits.",
+                s, radix
+            );
+        }
+        let required_bits = if has_overflowed {
+            None
+        } else {
+            Some(128 - value.leading_zeros())
+        };
+        validate_fit(required_bits, bit_width, || format!("\"{}\"", s));
+        Constant::U128(value)
+    }
+
+    /// Creates a `Constant` from `bytes` in little-endian order (least significant byte first), which must fit into `bit_width` bits.
+    ///
+    /// `bytes` may be longer than `bit_width` requires, as long as the additional bytes are all `0`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bit_width` is less than [`MIN_SIGNAL_BIT_WIDTH`] or greater than [`MAX_SIGNAL_BIT_WIDTH`], respectively, or if the value represented by `bytes` doesn't fit into `bit_width` bits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let my_mem = m.mem("my_mem", 1, 24);
+    /// my_mem.initial_contents(&[
+    ///     Constant::from_bytes_le(&[0x56, 0x34, 0x12], 24), // 0x123456
+    ///     Constant::from_bytes_le(&[0xff], 24), // 0x0000ff
+    /// ]);
+    /// ```
+    pub fn from_bytes_le(bytes: &[u8], bit_width: u32) -> Constant {
+        Constant::from_bytes(bytes, true, bit_width)
+    }
+
+    /// Creates a `Constant` from `bytes` in big-endian order (most significant byte first), which must fit into `bit_width` bits.
+    ///
+    /// `bytes` may be longer than `bit_width` requires, as long as the additional bytes are all `0`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bit_width` is less than [`MIN_SIGNAL_BIT_WIDTH`] or greater than [`MAX_SIGNAL_BIT_WIDTH`], respectively, or if the value represented by `bytes` doesn't fit into `bit_width` bits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let r = m.reg("r", 24);
+    /// r.default_value(Constant::from_bytes_be(&[0x12, 0x34, 0x56], 24)); // 0x123456
+    /// ```
+    pub fn from_bytes_be(bytes: &[u8], bit_width: u32) -> Constant {
+        Constant::from_bytes(bytes, false, bit_width)
+    }
+
+    fn from_bytes(bytes: &[u8], is_little_endian: bool, bit_width: u32) -> Constant {
+        validate_bit_width(bit_width, || format!("{:02x?}", bytes));
+        let mut bytes_msb_first = bytes.to_vec();
+        if is_little_endian {
+            bytes_msb_first.reverse();
+        }
+        let mut value = 0u128;
+        let mut num_bytes = 0;
+        for byte in bytes_msb_first.into_iter().skip_while(|&byte| byte == 0) {
+            value = (value << 8) | byte as u128;
+            num_bytes += 1;
+            if num_bytes > 16 {
+                break;
+            }
+        }
+        let required_bits = if num_bytes > 16 {
+            None
+        } else {
+            Some(128 - value.leading_zeros())
+        };
+        validate_fit(required_bits, bit_width, || format!("{:02x?}", bytes));
+        Constant::U128(value)
+    }
+
     // TODO: Specific tests? I don't necessarily want to make this part of the public API at least.
     pub(super) fn required_bits(&self) -> u32 {
         match *self {
@@ -37,6 +188,29 @@ impl Constant {
             Constant::U32(value) => 32 - value.leading_zeros(),
             Constant::U64(value) => 64 - value.leading_zeros(),
             Constant::U128(value) => 128 - value.leading_zeros(),
+            Constant::I128(value) if value >= 0 => 128 - (value as u128).leading_zeros(),
+            // Negative values need one bit more than their complements' magnitudes for the sign bit
+            Constant::I128(value) => 129 - (!value as u128).leading_zeros(),
+        }
+    }
+
+    /// Returns this `Constant` as an unsigned bit pattern with `bit_width` bits, which must be at least [`required_bits`](Self::required_bits).
+    ///
+    /// Generated code only deals with unsigned values, so signed values are converted by each graph API entry point that takes a `Constant` once its bit width is known.
+    pub(super) fn to_bit_pattern(&self, bit_width: u32) -> Constant {
+        match *self {
+            Constant::I128(value) => {
+                Constant::U128(value as u128 & (u128::MAX >> (128 - bit_width)))
+            }
+            ref value => value.clone(),
+        }
+    }
+
+    /// Returns this `Constant`'s numeric value, as it's displayed in error messages.
+    pub(super) fn describe(&self) -> String {
+        match *self {
+            Constant::I128(value) => format!("{}", value),
+            ref value => format!("{}", value.numeric_value()),
         }
     }
 
@@ -46,6 +220,8 @@ impl Constant {
             Constant::U32(value) => value.into(),
             Constant::U64(value) => value.into(),
             Constant::U128(value) => value,
+            // Signed values are converted to bit patterns before they reach generated code
+            Constant::I128(value) => value as u128,
         }
     }
 }
@@ -85,3 +261,242 @@ impl From<u128> for Constant {
         Constant::U128(value)
     }
 }
+
+impl From<i8> for Constant {
+    fn from(value: i8) -> Self {
+        Constant::I128(value as _)
+    }
+}
+
+impl From<i16> for Constant {
+    fn from(value: i16) -> Self {
+        Constant::I128(value as _)
+    }
+}
+
+impl From<i32> for Constant {
+    fn from(value: i32) -> Self {
+        Constant::I128(value as _)
+    }
+}
+
+impl From<i64> for Constant {
+    fn from(value: i64) -> Self {
+        Constant::I128(value as _)
+    }
+}
+
+impl From<i128> for Constant {
+    fn from(value: i128) -> Self {
+        Constant::I128(value as _)
+    }
+}
+
+fn validate_bit_width(bit_width: u32, describe_value: impl Fn() -> String) {
+    if bit_width < MIN_SIGNAL_BIT_WIDTH {
+        panic!(
+            "Cannot create a constant from {} with {} bit(s). Signals must not be narrower than {} bit(s).",
+            describe_value(),
+            bit_width,
+            MIN_SIGNAL_BIT_WIDTH
+        );
+    }
+    if bit_width > MAX_SIGNAL_BIT_WIDTH {
+        panic!(
+            "Cannot create a constant from {} with {} bit(s). Signals must not be wider than {} bit(s).",
+            describe_value(),
+            bit_width,
+            MAX_SIGNAL_BIT_WIDTH
+        );
+    }
+}
+
+// `required_bits` is `None` if the value doesn't fit into 128 bits
+fn validate_fit(required_bits: Option<u32>, bit_width: u32, describe_value: impl Fn() -> String) {
+    match required_bits {
+        Some(required_bits) if required_bits <= bit_width => {}
+        Some(required_bits) => panic!("Cannot fit the specified value {} into the specified bit width '{}'. The value requires a bit width of at least {} bit(s).", describe_value(), bit_width, required_bits),
+        _ => panic!("Cannot fit the specified value {} into the specified bit width '{}'. The value requires a bit width of more than {} bit(s).", describe_value(), bit_width, MAX_SIGNAL_BIT_WIDTH),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str_radix() {
+        assert_eq!(Constant::from_str_radix("0", 10, 1).numeric_value(), 0);
+        assert_eq!(Constant::from_str_radix("255", 10, 8).numeric_value(), 255);
+        assert_eq!(
+            Constant::from_str_radix("DeadBeef", 16, 32).numeric_value(),
+            0xdeadbeef
+        );
+        assert_eq!(
+            Constant::from_str_radix("1111_0000", 2, 8).numeric_value(),
+            0xf0
+        );
+        assert_eq!(Constant::from_str_radix("z", 36, 6).numeric_value(), 35);
+        assert_eq!(
+            Constant::from_str_radix("0000_00ff", 16, 8).numeric_value(),
+            0xff
+        );
+        assert_eq!(
+            Constant::from_str_radix("ffffffff_ffffffff_ffffffff_ffffffff", 16, 128)
+                .numeric_value(),
+            u128::MAX
+        );
+    }
+
+    #[test]
+    fn from_bytes() {
+        assert_eq!(
+            Constant::from_bytes_le(&[0x56, 0x34, 0x12], 21).numeric_value(),
+            0x123456
+        );
+        assert_eq!(
+            Constant::from_bytes_be(&[0x12, 0x34, 0x56], 21).numeric_value(),
+            0x123456
+        );
+        assert_eq!(Constant::from_bytes_le(&[], 1).numeric_value(), 0);
+        assert_eq!(Constant::from_bytes_be(&[], 1).numeric_value(), 0);
+
+        // Additional bytes are fine as long as they're 0
+        let mut bytes = [0u8; 20];
+        bytes[0] = 0xff;
+        assert_eq!(Constant::from_bytes_le(&bytes, 8).numeric_value(), 0xff);
+        bytes.reverse();
+        assert_eq!(Constant::from_bytes_be(&bytes, 8).numeric_value(), 0xff);
+
+        let bytes = [0xa5u8; 16];
+        assert_eq!(
+            Constant::from_bytes_le(&bytes, 128).numeric_value(),
+            0xa5a5a5a5_a5a5a5a5_a5a5a5a5_a5a5a5a5
+        );
+    }
+
+    #[test]
+    fn signed() {
+        assert_eq!(Constant::from(-1i8).required_bits(), 1);
+        assert_eq!(Constant::from(-2i16).required_bits(), 2);
+        assert_eq!(Constant::from(-128i32).required_bits(), 8);
+        assert_eq!(Constant::from(-129i64).required_bits(), 9);
+        assert_eq!(Constant::from(i128::MIN).required_bits(), 128);
+        // Non-negative values don't need a sign bit
+        assert_eq!(Constant::from(0i32).required_bits(), 0);
+        assert_eq!(Constant::from(255i32).required_bits(), 8);
+        assert_eq!(Constant::from(i128::MAX).required_bits(), 127);
+
+        assert_eq!(
+            Constant::from(-1i32).to_bit_pattern(8).numeric_value(),
+            0xff
+        );
+        assert_eq!(Constant::from(-1i8).to_bit_pattern(4).numeric_value(), 0xf);
+        assert_eq!(Constant::from(-8i8).to_bit_pattern(4).numeric_value(), 0x8);
+        assert_eq!(
+            Constant::from(-2i64).to_bit_pattern(128).numeric_value(),
+            u128::MAX - 1
+        );
+        assert_eq!(
+            Constant::from(100i32).to_bit_pattern(8).numeric_value(),
+            100
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot parse \"10\" with radix 37. The radix must be between 2 and 36."
+    )]
+    fn from_str_radix_invalid_radix_error() {
+        // Panic
+        let _ = Constant::from_str_radix("10", 37, 8);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot parse \"12_g4\" as a base 16 value, because it contains the invalid digit 'g'."
+    )]
+    fn from_str_radix_invalid_digit_error() {
+        // Panic
+        let _ = Constant::from_str_radix("12_g4", 16, 16);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot parse \"-1\" as a base 10 value, because it contains the invalid digit '-'."
+    )]
+    fn from_str_radix_sign_error() {
+        // Panic
+        let _ = Constant::from_str_radix("-1", 10, 8);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot parse \"__\" as a base 2 value, because it contains no digits."
+    )]
+    fn from_str_radix_no_digits_error() {
+        // Panic
+        let _ = Constant::from_str_radix("__", 2, 8);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create a constant from \"ff\" with 129 bit(s). Signals must not be wider than 128 bit(s)."
+    )]
+    fn from_str_radix_bit_width_error() {
+        // Panic
+        let _ = Constant::from_str_radix("ff", 16, 129);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot fit the specified value \"1ff\" into the specified bit width '8'. The value requires a bit width of at least 9 bit(s)."
+    )]
+    fn from_str_radix_value_cannot_fit_into_bit_width_error() {
+        // Panic
+        let _ = Constant::from_str_radix("1ff", 16, 8);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot fit the specified value \"1_00000000_00000000_00000000_00000000\" into the specified bit width '128'. The value requires a bit width of more than 128 bit(s)."
+    )]
+    fn from_str_radix_overflow_error() {
+        // Panic
+        let _ = Constant::from_str_radix("1_00000000_00000000_00000000_00000000", 16, 128);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create a constant from [01, 02] with 0 bit(s). Signals must not be narrower than 1 bit(s)."
+    )]
+    fn from_bytes_bit_width_error() {
+        // Panic
+        let _ = Constant::from_bytes_le(&[0x01, 0x02], 0);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot fit the specified value [01, 02] into the specified bit width '9'. The value requires a bit width of at least 10 bit(s)."
+    )]
+    fn from_bytes_le_value_cannot_fit_into_bit_width_error() {
+        // Panic
+        let _ = Constant::from_bytes_le(&[0x01, 0x02], 9);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot fit the specified value [01, 02] into the specified bit width '8'. The value requires a bit width of at least 9 bit(s)."
+    )]
+    fn from_bytes_be_value_cannot_fit_into_bit_width_error() {
+        // Panic
+        let _ = Constant::from_bytes_be(&[0x01, 0x02], 8);
+    }
+
+    #[test]
+    #[should_panic(expected = "The value requires a bit width of more than 128 bit(s).")]
+    fn from_bytes_overflow_error() {
+        // Panic
+        let _ = Constant::from_bytes_be(&[0x01; 17], 128);
+    }
+}
@@ -1,11 +1,20 @@
+use super::clock_divider::*;
+use super::deferred::*;
+use super::inout::*;
+use super::instance_array::*;
 use super::internal_signal::*;
 use super::mem::*;
 use super::module::*;
+use super::module_declaration::*;
+use super::module_params::*;
 use super::register::*;
+use super::reset_domain::*;
+use super::warning::*;
 
 use typed_arena::Arena;
 
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
 
 // TODO: Move, doc
 pub trait ModuleParent<'a> {
@@ -14,9 +23,11 @@ pub trait ModuleParent<'a> {
     ///
     /// Conventionally, `name` should be `CamelCase`, though this is not enforced.
     ///
+    /// `instance_name` identifies this instance in its parent: it's used as the instance's scope in traces, as its instance identifier in generated Verilog code, and as part of the names of its registers and memories in generated code.
+    ///
     /// # Panics
     ///
-    /// Panics if a [`Module`] with the same `name` already exists in this `Context`.
+    /// Panics if a [`Module`] with the same `name` already exists in this `Context`, or if this is a [`Module`] that already contains an instance called `instance_name`.
     ///
     /// # Examples
     ///
@@ -42,6 +53,169 @@ pub trait ModuleParent<'a> {
     /// let _ = c.module("a", "A"); // Non-unique name, panic!
     /// ```
     fn module(&'a self, instance_name: impl Into<String>, name: impl Into<String>) -> &Module;
+
+    /// Creates a new [`Module`] like [`module`](Self::module), calls `build` with it to build its contents, and then [finalizes](Module::finalize) it.
+    ///
+    /// Once finalized, any attempt to add inputs, outputs, registers, memories, or instances to the module panics, so references to the module (or its signals) that escape `build` can't be used to keep changing it afterwards. Its ports can still be connected, eg. when it's an instance.
+    ///
+    /// # Panics
+    ///
+    /// Panics for the same reasons as [`module`](Self::module).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let adder = c.module_with("adder", "Adder", |m| {
+    ///     let a = m.input("a", 8);
+    ///     let b = m.input("b", 8);
+    ///     m.output("o", a + b);
+    /// });
+    /// ```
+    ///
+    /// The following example panics by adding an input to a `Module` after it was built:
+    ///
+    /// ```should_panic
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let adder = c.module_with("adder", "Adder", |m| {
+    ///     m.output("o", m.input("a", 8) + m.input("b", 8));
+    /// });
+    ///
+    /// let _ = adder.input("c", 8); // Module is finalized, panic!
+    /// ```
+    fn module_with(
+        &'a self,
+        instance_name: impl Into<String>,
+        name: impl Into<String>,
+        build: impl FnOnce(&'a Module<'a>),
+    ) -> &'a Module<'a> {
+        let m = self.module(instance_name, name);
+        build(m);
+        m.finalize();
+        m
+    }
+
+    /// Creates a new [`Module`] whose name is derived from `name` and `params` (see [`ModuleParams::module_name`]), and calls `build` with it and `params` to build its contents.
+    ///
+    /// This allows a single builder function to produce modules with different parameters (such as bit widths), where each distinct set of parameters gets a distinct module name, and therefore its own definition in generated Verilog code.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// fn adder<'a>(instance_name: &str, p: &'a impl ModuleParent<'a>, bit_width: u32) -> &'a Module<'a> {
+    ///     p.module_parameterized(instance_name, "Adder", ModuleParams::new().with("w", bit_width as _), |m, params| {
+    ///         let bit_width = params.get("w") as u32;
+    ///         let a = m.input("a", bit_width);
+    ///         let b = m.input("b", bit_width);
+    ///         m.output("o", a + b);
+    ///     })
+    /// }
+    ///
+    /// let c = Context::new();
+    ///
+    /// let adder_8 = adder("adder_8", &c, 8); // Module name is "Adder_w8"
+    /// let adder_32 = adder("adder_32", &c, 32); // Module name is "Adder_w32"
+    /// ```
+    fn module_parameterized(
+        &'a self,
+        instance_name: impl Into<String>,
+        name: impl Into<String>,
+        params: ModuleParams,
+        build: impl FnOnce(&'a Module<'a>, &ModuleParams),
+    ) -> &'a Module<'a> {
+        let m = self.module(instance_name, params.module_name(&name.into()));
+        build(m, &params);
+        m
+    }
+
+    /// Creates a new instance called `instance_name` of the [`Module`] declared by `declaration` (see [`Context::declare_module`]).
+    ///
+    /// If the module is already defined, the instance is built immediately. Otherwise, it's built when the module is defined with [`ModuleDeclaration::define`]; until then, it's an empty [`Module`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is a [`Module`] that already contains an instance called `instance_name`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let inner = c.declare_module("Inner");
+    ///
+    /// let m = c.module("m", "MyModule");
+    /// let inner_1 = m.instance("inner_1", &inner); // Empty until "Inner" is defined
+    ///
+    /// inner.define(|m| {
+    ///     m.output("o", m.high());
+    /// });
+    /// let inner_2 = m.instance("inner_2", &inner); // Built immediately
+    /// ```
+    fn instance(
+        &'a self,
+        instance_name: impl Into<String>,
+        declaration: &ModuleDeclaration<'a>,
+    ) -> &'a Module<'a> {
+        let m = self.module(instance_name, declaration.data.name.clone());
+        declaration.add_instance(m);
+        m
+    }
+
+    /// Creates `n` instances of the [`Module`] declared by `declaration` (see [`instance`](Self::instance)), called `<instance_name>_0` through `<instance_name>_<n - 1>`, and returns them as an [`InstanceArray`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is `0`, or if this is a [`Module`] that already contains an instance with any of the instances' names.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let lane = c.declare_module("Lane");
+    /// lane.define(|m| {
+    ///     m.output("sum", m.input("a", 8) + m.input("b", 8));
+    /// });
+    ///
+    /// let m = c.module("m", "MyModule");
+    /// let a = m.input("a", 32);
+    /// let b = m.input("b", 32);
+    ///
+    /// let lanes = m.instance_array("lane", &lane, 4);
+    /// lanes.drive_input_each("a", |i| a.bits(i as u32 * 8 + 7, i as u32 * 8));
+    /// lanes.drive_input_each("b", |i| b.bits(i as u32 * 8 + 7, i as u32 * 8));
+    /// m.output("sum", lanes.collect_outputs_concat("sum")); // Four independent 8-bit sums
+    /// ```
+    fn instance_array(
+        &'a self,
+        instance_name: impl Into<String>,
+        declaration: &ModuleDeclaration<'a>,
+        n: usize,
+    ) -> InstanceArray<'a> {
+        let instance_name = instance_name.into();
+        if n == 0 {
+            panic!(
+                "Attempted to create an instance array called \"{}\" with 0 instances.",
+                instance_name
+            );
+        }
+        let instances = (0..n)
+            .map(|i| self.instance(format!("{}_{}", instance_name, i), declaration))
+            .collect();
+        InstanceArray::new(instance_name, instances)
+    }
 }
 
 /// A top-level container/owner object for a [`Module`] graph.
@@ -65,12 +239,24 @@ pub struct Context<'a> {
     pub(super) input_arena: Arena<Input<'a>>,
     pub(super) output_data_arena: Arena<OutputData<'a>>,
     pub(super) output_arena: Arena<Output<'a>>,
+    pub(super) inout_arena: Arena<InOut<'a>>,
     pub(super) signal_arena: Arena<InternalSignal<'a>>,
     pub(super) register_data_arena: Arena<RegisterData<'a>>,
     pub(super) register_arena: Arena<Register<'a>>,
+    pub(super) deferred_register_data_arena: Arena<DeferredRegisterData<'a>>,
+    pub(super) deferred_output_data_arena: Arena<DeferredOutputData<'a>>,
     pub(super) mem_arena: Arena<Mem<'a>>,
+    pub(super) reset_domain_arena: Arena<ResetDomain<'a>>,
+    pub(super) clock_divider_arena: Arena<ClockDivider<'a>>,
+    pub(super) module_declaration_data_arena: Arena<ModuleDeclarationData>,
 
     pub(super) modules: RefCell<Vec<&'a Module<'a>>>,
+    module_once_cache: RefCell<HashMap<String, &'a Module<'a>>>,
+    declared_module_names: RefCell<HashSet<String>>,
+    warnings: RefCell<Vec<Warning>>,
+    suppressed_warnings: RefCell<Vec<SuppressedWarning>>,
+    suppressions: RefCell<Vec<Suppression>>,
+    deny_warnings: Cell<bool>,
 }
 
 impl<'a> Context<'a> {
@@ -90,13 +276,312 @@ impl<'a> Context<'a> {
             input_arena: Arena::new(),
             output_data_arena: Arena::new(),
             output_arena: Arena::new(),
+            inout_arena: Arena::new(),
             signal_arena: Arena::new(),
             register_data_arena: Arena::new(),
             register_arena: Arena::new(),
+            deferred_register_data_arena: Arena::new(),
+            deferred_output_data_arena: Arena::new(),
             mem_arena: Arena::new(),
+            reset_domain_arena: Arena::new(),
+            clock_divider_arena: Arena::new(),
+            module_declaration_data_arena: Arena::new(),
 
             modules: RefCell::new(Vec::new()),
+            module_once_cache: RefCell::new(HashMap::new()),
+            declared_module_names: RefCell::new(HashSet::new()),
+            warnings: RefCell::new(Vec::new()),
+            suppressed_warnings: RefCell::new(Vec::new()),
+            suppressions: RefCell::new(Vec::new()),
+            deny_warnings: Cell::new(false),
+        }
+    }
+
+    /// Returns the [`Warning`]s recorded while building [`Module`]s in this `Context` since this method was last called, in the order they were recorded, and clears them.
+    ///
+    /// Warnings that match a [suppression](Self::suppress) are returned by [`take_suppressed_warnings`](Self::take_suppressed_warnings) instead. Each suppression that hasn't matched any warning by the time this method is called is reported once, as a [`Warning::UnusedSuppression`] at the end of the returned warnings.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    /// let i = m.input("i", 32);
+    /// let amount = m.input("amount", 128);
+    /// m.output("o", i << amount);
+    ///
+    /// let warnings = c.take_warnings();
+    /// assert_eq!(warnings.len(), 1);
+    /// assert!(matches!(warnings[0], Warning::WideShiftAmount { amount_bit_width: 128, .. }));
+    /// assert!(c.take_warnings().is_empty());
+    /// ```
+    pub fn take_warnings(&self) -> Vec<Warning> {
+        let unused_suppressions = self
+            .suppressions
+            .borrow_mut()
+            .iter_mut()
+            .filter(|suppression| !suppression.used)
+            .map(|suppression| {
+                // Only report each stale suppression once
+                suppression.used = true;
+                Warning::UnusedSuppression {
+                    code: suppression.code.clone(),
+                    path_glob: suppression.path_glob.clone(),
+                    reason: suppression.reason.clone(),
+                }
+            })
+            .collect::<Vec<_>>();
+        for warning in unused_suppressions {
+            self.warn(warning);
         }
+        self.warnings.take()
+    }
+
+    /// Returns the [`Warning`]s that matched a [suppression](Self::suppress) since this method was last called, along with the reason they were suppressed, in the order they were recorded, and clears them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    /// c.suppress("wide_shift_amount", "My*", "Amount comes from a shared 128-bit bus");
+    ///
+    /// let m = c.module("m", "MyModule");
+    /// let i = m.input("i", 32);
+    /// let amount = m.input("amount", 128);
+    /// m.output("o", i << amount);
+    ///
+    /// assert!(c.take_warnings().is_empty());
+    /// let suppressed = c.take_suppressed_warnings();
+    /// assert_eq!(suppressed.len(), 1);
+    /// assert_eq!(suppressed[0].reason, "Amount comes from a shared 128-bit bus");
+    /// assert!(suppressed[0].to_string().ends_with("(suppressed: Amount comes from a shared 128-bit bus)"));
+    /// ```
+    pub fn take_suppressed_warnings(&self) -> Vec<SuppressedWarning> {
+        self.suppressed_warnings.take()
+    }
+
+    /// Suppresses [`Warning`]s with [code](Warning::code) `code` whose [path](Warning::path) matches `path_glob`, where `*` matches any sequence of characters and `?` matches any single character.
+    ///
+    /// Suppressed warnings are still recorded, but they're returned by [`take_suppressed_warnings`](Self::take_suppressed_warnings) along with `reason` instead of by [`take_warnings`](Self::take_warnings), and they don't panic when warnings are [denied](Self::deny_warnings). Suppressions only apply to warnings detected after they're added, and a suppression that doesn't match any warning is reported as a [`Warning::UnusedSuppression`] by [`take_warnings`](Self::take_warnings), so that stale suppressions are noticed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `code` isn't the code of a kind of [`Warning`], or if it's `"unused_suppression"`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    /// c.suppress("wide_shift_amount", "Decoder", "Decoder was removed");
+    ///
+    /// assert_eq!(
+    ///     c.take_warnings(),
+    ///     vec![Warning::UnusedSuppression {
+    ///         code: "wide_shift_amount".into(),
+    ///         path_glob: "Decoder".into(),
+    ///         reason: "Decoder was removed".into(),
+    ///     }]
+    /// );
+    /// ```
+    pub fn suppress(
+        &self,
+        code: impl Into<String>,
+        path_glob: impl Into<String>,
+        reason: impl Into<String>,
+    ) {
+        let code = code.into();
+        if !WARNING_CODES.contains(&code.as_str()) || code == "unused_suppression" {
+            panic!("Cannot suppress warnings with code \"{}\", because it's not the code of a kind of warning that can be suppressed.", code);
+        }
+        self.suppressions.borrow_mut().push(Suppression {
+            code,
+            path_glob: path_glob.into(),
+            reason: reason.into(),
+            used: false,
+        });
+    }
+
+    /// Makes any [`Warning`] that's detected from now on panic with its description, instead of being recorded.
+    ///
+    /// # Examples
+    ///
+    /// ```should_panic
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    /// c.deny_warnings();
+    ///
+    /// let m = c.module("m", "MyModule");
+    /// let i = m.input("i", 32);
+    /// let amount = m.input("amount", 128);
+    /// m.output("o", i << amount); // Panics
+    /// ```
+    pub fn deny_warnings(&self) {
+        self.deny_warnings.set(true);
+    }
+
+    pub(super) fn warn(&self, warning: Warning) {
+        let reason = self
+            .suppressions
+            .borrow_mut()
+            .iter_mut()
+            .find(|suppression| suppression.matches(&warning))
+            .map(|suppression| {
+                suppression.used = true;
+                suppression.reason.clone()
+            });
+        if let Some(reason) = reason {
+            self.suppressed_warnings
+                .borrow_mut()
+                .push(SuppressedWarning { warning, reason });
+            return;
+        }
+        if self.deny_warnings.get() {
+            panic!("{}", warning);
+        }
+        self.warnings.borrow_mut().push(warning);
+    }
+
+    /// Returns the [`Module`] previously created by this method for the module name derived from `name` and `params` (see [`ModuleParams::module_name`]), or creates it in this `Context` like [`ModuleParent::module_parameterized`] if there isn't one yet.
+    ///
+    /// This allows a builder function to be called any number of times with the same parameters without creating duplicate module definitions, and without having to track which definitions already exist. `instance_name` and `build` are only used when the [`Module`] is created.
+    ///
+    /// The [`Module`] is registered before `build` is called, so `build` may itself call this method (for example, to create other parameterized modules).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// fn fifo<'a>(c: &'a Context<'a>, bit_width: u32) -> &'a Module<'a> {
+    ///     c.module_once("fifo", "Fifo", ModuleParams::new().with("w", bit_width as _), |m, params| {
+    ///         let bit_width = params.get("w") as u32;
+    ///         m.output("o", m.input("i", bit_width));
+    ///     })
+    /// }
+    ///
+    /// let c = Context::new();
+    ///
+    /// let fifo_8 = fifo(&c, 8); // Module name is "Fifo_w8"
+    /// let fifo_16 = fifo(&c, 16); // Module name is "Fifo_w16"
+    /// assert!(std::ptr::eq(fifo(&c, 8), fifo_8)); // Same module as before
+    /// ```
+    pub fn module_once(
+        &'a self,
+        instance_name: impl Into<String>,
+        name: impl Into<String>,
+        params: ModuleParams,
+        build: impl FnOnce(&'a Module<'a>, &ModuleParams),
+    ) -> &'a Module<'a> {
+        let name = params.module_name(&name.into());
+        if let Some(&m) = self.module_once_cache.borrow().get(&name) {
+            return m;
+        }
+        let m = self.module(instance_name, name.clone());
+        self.module_once_cache.borrow_mut().insert(name, m);
+        build(m, &params);
+        m
+    }
+
+    /// Declares a [`Module`] called `name` in this `Context`, whose contents are specified later with [`ModuleDeclaration::define`].
+    ///
+    /// The returned [`ModuleDeclaration`] can be instantiated immediately with [`ModuleParent::instance`], so modules can be built in any order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a module called `name` was already declared in this `Context`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let inner = c.declare_module("Inner");
+    ///
+    /// let m = c.module("m", "MyModule");
+    /// let inner_inst = m.instance("inner", &inner);
+    /// inner.on_defined(move || {
+    ///     m.output("o", inner_inst.get_output("o"));
+    /// });
+    ///
+    /// inner.define(|m| {
+    ///     m.output("o", m.high());
+    /// });
+    /// ```
+    pub fn declare_module(&'a self, name: impl Into<String>) -> ModuleDeclaration<'a> {
+        let name = name.into();
+        if !self.declared_module_names.borrow_mut().insert(name.clone()) {
+            panic!(
+                "Cannot declare module \"{}\", because it's already declared.",
+                name
+            );
+        }
+        let data = self
+            .module_declaration_data_arena
+            .alloc(ModuleDeclarationData {
+                name,
+                defined: Cell::new(false),
+            });
+        ModuleDeclaration::new(data)
+    }
+
+    /// Returns an iterator over the names of the top-level [`Module`]s created in this `Context` and the modules themselves, in the order they were created.
+    ///
+    /// Only modules created directly in this `Context` (with [`ModuleParent::module`] or [`module_once`](Self::module_once)) are included; their instances can be enumerated with [`Module::instances`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let _ = c.module("a", "Adder");
+    /// let _ = c.module("m", "Multiplier");
+    ///
+    /// let names = c.modules().map(|(name, _)| name).collect::<Vec<_>>();
+    /// assert_eq!(names, ["Adder", "Multiplier"]);
+    /// ```
+    pub fn modules(&self) -> impl Iterator<Item = (&'a str, &'a Module<'a>)> {
+        self.modules
+            .borrow()
+            .iter()
+            .map(|&m| (m.name.as_str(), m))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    /// Returns the first top-level [`Module`] called `name` created in this `Context`, if any.
+    ///
+    /// As with [`modules`](Self::modules), modules that are only instantiated in other modules aren't considered.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// assert!(std::ptr::eq(c.module_by_name("MyModule").unwrap(), m));
+    /// assert!(c.module_by_name("OtherModule").is_none());
+    /// ```
+    pub fn module_by_name(&self, name: &str) -> Option<&'a Module<'a>> {
+        self.modules
+            .borrow()
+            .iter()
+            .find(|m| m.name == name)
+            .copied()
     }
 }
 
@@ -122,5 +607,168 @@ mod tests {
         let c = Context::new();
 
         assert!(c.modules.borrow().is_empty());
+        assert_eq!(c.modules().count(), 0);
+    }
+
+    #[test]
+    fn modules_and_module_by_name() {
+        let c = Context::new();
+
+        let a1 = c.module("a1", "A");
+        let b = c.module("b", "B");
+        let _ = c.module("a2", "A");
+        let _ = b.module("inner", "Inner");
+
+        assert_eq!(
+            c.modules()
+                .map(|(name, m)| (name, m.instance_name()))
+                .collect::<Vec<_>>(),
+            [("A", "a1"), ("B", "b"), ("A", "a2")]
+        );
+        assert!(std::ptr::eq(c.module_by_name("A").unwrap(), a1));
+        assert!(std::ptr::eq(c.module_by_name("B").unwrap(), b));
+        assert!(c.module_by_name("Inner").is_none());
+    }
+
+    #[test]
+    fn module_once_same_params() {
+        let c = Context::new();
+
+        let mut num_builds = 0;
+        let a = c.module_once("a", "A", ModuleParams::new().with("w", 8), |m, _| {
+            num_builds += 1;
+            m.output("o", m.input("i", 8));
+        });
+        let b = c.module_once("b", "A", ModuleParams::new().with("w", 8), |_, _| {
+            num_builds += 1;
+        });
+
+        assert!(std::ptr::eq(a, b));
+        assert_eq!(num_builds, 1);
+        assert_eq!(a.instance_name, "a");
+        assert_eq!(a.name, "A_w8");
+        assert_eq!(c.modules.borrow().len(), 1);
+    }
+
+    #[test]
+    fn module_once_different_params() {
+        let c = Context::new();
+
+        let a = c.module_once("a", "A", ModuleParams::new().with("w", 8), |_, _| {});
+        let b = c.module_once("b", "A", ModuleParams::new().with("w", 16), |_, _| {});
+        let a_no_params = c.module_once("a", "A", ModuleParams::new(), |_, _| {});
+
+        assert!(!std::ptr::eq(a, b));
+        assert!(!std::ptr::eq(a, a_no_params));
+        assert_eq!(a.name, "A_w8");
+        assert_eq!(b.name, "A_w16");
+        assert_eq!(a_no_params.name, "A");
+        assert_eq!(c.modules.borrow().len(), 3);
+    }
+
+    #[test]
+    fn module_once_nested() {
+        let c = Context::new();
+
+        let mut inner = None;
+        let outer = c.module_once("outer", "Outer", ModuleParams::new(), |_, _| {
+            inner = Some(c.module_once("inner", "Inner", ModuleParams::new(), |_, _| {}));
+            // Re-entering with the module that's currently being built returns it as-is
+            let _ = c.module_once("outer", "Outer", ModuleParams::new(), |_, _| unreachable!());
+        });
+
+        assert_eq!(outer.name, "Outer");
+        assert_eq!(inner.unwrap().name, "Inner");
+        assert!(std::ptr::eq(
+            c.module_once("inner", "Inner", ModuleParams::new(), |_, _| unreachable!()),
+            inner.unwrap()
+        ));
+    }
+
+    #[test]
+    fn suppressed_warnings() {
+        let c = Context::new();
+        c.deny_warnings();
+        c.suppress("wide_shift_amount", "Shift*", "Amount is a shared bus");
+
+        let a = c.module("a", "ShiftA");
+        let b = c.module("b", "ShiftB");
+        let _ = a.input("i", 8) << a.input("amount", 32);
+        let _ = b.input("i", 8) >> b.input("amount", 32);
+
+        // Suppressed warnings don't panic when warnings are denied, and are kept with the reason
+        assert!(c.take_warnings().is_empty());
+        let suppressed = c.take_suppressed_warnings();
+        assert_eq!(
+            suppressed
+                .iter()
+                .map(|s| (s.warning.path(), s.reason.as_str()))
+                .collect::<Vec<_>>(),
+            [
+                ("ShiftA", "Amount is a shared bus"),
+                ("ShiftB", "Amount is a shared bus")
+            ]
+        );
+        assert_eq!(
+            suppressed[0].to_string(),
+            format!(
+                "{} (suppressed: Amount is a shared bus)",
+                suppressed[0].warning
+            )
+        );
+        assert!(c.take_suppressed_warnings().is_empty());
+    }
+
+    #[test]
+    fn unused_suppressions() {
+        let c = Context::new();
+        c.suppress("wide_shift_amount", "A", "Used");
+        c.suppress("wide_shift_amount", "B*", "Stale");
+
+        let a = c.module("a", "A");
+        let _ = a.input("i", 8) << a.input("amount", 32);
+
+        let warnings = c.take_warnings();
+        assert_eq!(
+            warnings,
+            vec![Warning::UnusedSuppression {
+                code: "wide_shift_amount".into(),
+                path_glob: "B*".into(),
+                reason: "Stale".into(),
+            }]
+        );
+        assert_eq!(warnings[0].code(), "unused_suppression");
+        assert_eq!(
+            warnings[0].to_string(),
+            "The suppression of \"wide_shift_amount\" warnings in \"B*\" (\"Stale\") didn't match any warning, so it may be stale."
+        );
+        assert_eq!(c.take_suppressed_warnings().len(), 1);
+
+        // Each unused suppression is only reported once
+        assert!(c.take_warnings().is_empty());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "The suppression of \"wide_shift_amount\" warnings in \"B\" (\"Stale\") didn't match any warning, so it may be stale."
+    )]
+    fn unused_suppression_denied() {
+        let c = Context::new();
+        c.deny_warnings();
+        c.suppress("wide_shift_amount", "B", "Stale");
+
+        // Panic
+        let _ = c.take_warnings();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot suppress warnings with code \"wide_shift\", because it's not the code of a kind of warning that can be suppressed."
+    )]
+    fn suppress_unknown_code_error() {
+        let c = Context::new();
+
+        // Panic
+        c.suppress("wide_shift", "*", "Typo");
     }
 }
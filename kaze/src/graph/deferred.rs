@@ -0,0 +1,454 @@
+use super::constant::*;
+use super::module::*;
+use super::register::*;
+use super::reset_domain::*;
+use super::signal::*;
+
+use std::cell::{Cell, RefCell};
+use std::ptr;
+
+type PendingUses<'a, T> = RefCell<Vec<Box<dyn FnOnce(&'a T) + 'a>>>;
+
+/// A [`Register`] whose bit width is inferred from the first signal it's resolved with, created by the [`Module::reg_deferred`] method.
+///
+/// The underlying [`Register`] is created when this `DeferredRegister`'s bit width is resolved, either by driving its next value with [`drive_next`], or explicitly with [`infer_width_from`], after which it's available from [`register`]. Until then, [`register`] panics, as does any other operation that depends on its bit width. Such operations can instead be deferred with [`on_resolved`], in which case they're performed as soon as the bit width is resolved. [`default_value`], [`reset_domain`], and [`attribute`] are always deferred this way, so they can be specified at any time.
+///
+/// Code generation panics if any `DeferredRegister` in the module hierarchy was never resolved.
+///
+/// # Examples
+///
+/// ```
+/// use kaze::*;
+///
+/// let c = Context::new();
+///
+/// let m = c.module("m", "MyModule");
+///
+/// // Consumer logic is built before the register's producer
+/// let my_reg = m.reg_deferred("my_reg");
+/// my_reg.default_value(0u32);
+/// my_reg.on_resolved(move |r| {
+///     m.output("my_output", !r);
+/// });
+///
+/// // The register's bit width is resolved here (as 32 bits), and the deferred operations are performed
+/// my_reg.drive_next(m.input("i", 32));
+/// ```
+///
+/// [`attribute`]: Self::attribute
+/// [`default_value`]: Self::default_value
+/// [`drive_next`]: Self::drive_next
+/// [`infer_width_from`]: Self::infer_width_from
+/// [`on_resolved`]: Self::on_resolved
+/// [`register`]: Self::register
+/// [`reset_domain`]: Self::reset_domain
+#[must_use]
+pub struct DeferredRegister<'a> {
+    pub(crate) data: &'a DeferredRegisterData<'a>,
+    pending: PendingUses<'a, Register<'a>>,
+}
+
+impl<'a> DeferredRegister<'a> {
+    pub(super) fn new(data: &'a DeferredRegisterData<'a>) -> DeferredRegister<'a> {
+        DeferredRegister {
+            data,
+            pending: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Returns this `DeferredRegister`'s bit width, or `None` if it hasn't been resolved yet.
+    pub fn resolved_bit_width(&self) -> Option<u32> {
+        self.data.resolved_bit_width()
+    }
+
+    /// Returns the underlying [`Register`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if this `DeferredRegister`'s bit width hasn't been resolved yet.
+    pub fn register(&self) -> &'a Register<'a> {
+        match self.data.register.get() {
+            Some((register, _)) => register,
+            None => panic!("Cannot use deferred register \"{}\" in module \"{}\" before its bit width is resolved. Resolve it with `drive_next` or `infer_width_from` first, or defer this use with `on_resolved`.", self.data.name, self.data.module.name),
+        }
+    }
+
+    /// Resolves this `DeferredRegister`'s bit width to `source`'s bit width, without driving its next value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this `DeferredRegister`'s bit width was already resolved to a different bit width.
+    pub fn infer_width_from(&self, source: &'a dyn Signal<'a>) {
+        self.resolve(source.bit_width(), "infer_width_from");
+    }
+
+    /// Resolves this `DeferredRegister`'s bit width to `n`'s bit width (if it hasn't been resolved already), and specifies `n` as the next value of the underlying [`Register`], like [`Register::drive_next`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` belongs to a different [`Module`] than this `DeferredRegister`, if this `DeferredRegister`'s bit width was already resolved to a different bit width, or if its next value is already driven.
+    pub fn drive_next(&self, n: &'a dyn Signal<'a>) {
+        let n_module = n.internal_signal().module;
+        if !ptr::eq(self.data.module, n_module) {
+            panic!(
+                "Attempted to drive register \"{}\"'s next value in module \"{}\" with a signal from another module (\"{}\").",
+                self.data.name, self.data.module.name, n_module.name
+            );
+        }
+        self.resolve(n.bit_width(), "drive_next").drive_next(n);
+    }
+
+    /// Calls `f` with the underlying [`Register`] as soon as this `DeferredRegister`'s bit width is resolved, or immediately if it's already resolved.
+    ///
+    /// Deferred calls are performed in the order they were specified.
+    pub fn on_resolved(&self, f: impl FnOnce(&'a Register<'a>) + 'a) {
+        match self.data.register.get().map(|(register, _)| register) {
+            Some(register) => f(register),
+            None => self.pending.borrow_mut().push(Box::new(f)),
+        }
+    }
+
+    /// Specifies the default value for the underlying [`Register`], like [`Register::default_value`], once this `DeferredRegister`'s bit width is resolved.
+    ///
+    /// # Panics
+    ///
+    /// Panics (when the bit width is resolved, if it isn't already) under the same conditions as [`Register::default_value`].
+    pub fn default_value(&self, value: impl Into<Constant>) {
+        let value = value.into();
+        self.on_resolved(move |register| register.default_value(value));
+    }
+
+    /// Assigns the underlying [`Register`] to `domain`, like [`Register::reset_domain`], once this `DeferredRegister`'s bit width is resolved.
+    ///
+    /// # Panics
+    ///
+    /// Panics (when the bit width is resolved, if it isn't already) under the same conditions as [`Register::reset_domain`].
+    pub fn reset_domain(&self, domain: &'a ResetDomain<'a>) {
+        self.on_resolved(move |register| register.reset_domain(domain));
+    }
+
+    /// Attaches an [`Attribute`](crate::Attribute) to the underlying [`Register`], like [`Register::attribute`], once this `DeferredRegister`'s bit width is resolved.
+    ///
+    /// # Panics
+    ///
+    /// Panics (when the bit width is resolved, if it isn't already) under the same conditions as [`Register::attribute`].
+    pub fn attribute(
+        &self,
+        namespace: impl Into<String>,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) {
+        let namespace = namespace.into();
+        let key = key.into();
+        let value = value.into();
+        self.on_resolved(move |register| register.attribute(namespace, key, value));
+    }
+
+    fn resolve(&self, bit_width: u32, source: &'static str) -> &'a Register<'a> {
+        if let Some((register, resolved_source)) = self.data.register.get() {
+            if register.data.bit_width != bit_width {
+                panic!("Cannot infer a bit width of {} bit(s) for deferred register \"{}\" in module \"{}\" from `{}`, because its bit width was already inferred as {} bit(s) from `{}`.", bit_width, self.data.name, self.data.module.name, source, register.data.bit_width, resolved_source);
+            }
+            return register;
+        }
+
+        let register = self.data.module.reg(self.data.name.clone(), bit_width);
+        self.data.register.set(Some((register, source)));
+        let pending = self.pending.take();
+        for f in pending {
+            f(register);
+        }
+        register
+    }
+}
+
+pub(crate) struct DeferredRegisterData<'a> {
+    pub module: &'a Module<'a>,
+
+    pub name: String,
+    pub register: Cell<Option<(&'a Register<'a>, &'static str)>>,
+}
+
+impl<'a> DeferredRegisterData<'a> {
+    pub fn resolved_bit_width(&self) -> Option<u32> {
+        self.register
+            .get()
+            .map(|(register, _)| register.data.bit_width)
+    }
+}
+
+/// An [`Output`] whose bit width is inferred from the signal that drives it, created by the [`Module::output_deferred`] method.
+///
+/// The underlying [`Output`] is created when this `DeferredOutput` is driven with [`drive`], after which it's available from [`output`]. Until then, [`output`] panics, but uses of the [`Output`] can instead be deferred with [`on_resolved`], in which case they're performed as soon as it's driven.
+///
+/// Code generation panics if any `DeferredOutput` in the module hierarchy was never driven.
+///
+/// # Examples
+///
+/// ```
+/// use kaze::*;
+///
+/// let c = Context::new();
+///
+/// let outer = c.module("outer", "Outer");
+/// let inner = outer.module("inner", "Inner");
+///
+/// // The parent can use the output before the child's logic is built
+/// let inner_o = inner.output_deferred("o");
+/// inner_o.on_resolved(move |o| {
+///     outer.output("o", o);
+/// });
+///
+/// // The output's bit width is resolved here (as 16 bits), and the deferred operations are performed
+/// inner_o.drive(inner.lit(0xabcdu32, 16));
+/// ```
+///
+/// [`drive`]: Self::drive
+/// [`on_resolved`]: Self::on_resolved
+/// [`output`]: Self::output
+#[must_use]
+pub struct DeferredOutput<'a> {
+    pub(crate) data: &'a DeferredOutputData<'a>,
+    pending: PendingUses<'a, Output<'a>>,
+}
+
+impl<'a> DeferredOutput<'a> {
+    pub(super) fn new(data: &'a DeferredOutputData<'a>) -> DeferredOutput<'a> {
+        DeferredOutput {
+            data,
+            pending: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Returns this `DeferredOutput`'s bit width, or `None` if it hasn't been driven yet.
+    pub fn resolved_bit_width(&self) -> Option<u32> {
+        self.data.resolved_bit_width()
+    }
+
+    /// Returns the underlying [`Output`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if this `DeferredOutput` hasn't been driven yet.
+    pub fn output(&self) -> &'a Output<'a> {
+        match self.data.output.get() {
+            Some(output) => output,
+            None => panic!("Cannot use deferred output \"{}\" in module \"{}\" before it's driven. Drive it with `drive` first, or defer this use with `on_resolved`.", self.data.name, self.data.module.name),
+        }
+    }
+
+    /// Creates the underlying [`Output`] with the same number of bits as `source`, and drives it with `source`, like [`Module::output`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if this `DeferredOutput` is already driven, or under the same conditions as [`Module::output`].
+    pub fn drive(&self, source: &'a dyn Signal<'a>) {
+        if self.data.output.get().is_some() {
+            panic!(
+                "Attempted to drive deferred output \"{}\" in module \"{}\", but this output is already driven.",
+                self.data.name, self.data.module.name
+            );
+        }
+
+        let output = self.data.module.output(self.data.name.clone(), source);
+        self.data.output.set(Some(output));
+        let pending = self.pending.take();
+        for f in pending {
+            f(output);
+        }
+    }
+
+    /// Calls `f` with the underlying [`Output`] as soon as this `DeferredOutput` is driven, or immediately if it's already driven.
+    ///
+    /// Deferred calls are performed in the order they were specified.
+    pub fn on_resolved(&self, f: impl FnOnce(&'a Output<'a>) + 'a) {
+        match self.data.output.get() {
+            Some(output) => f(output),
+            None => self.pending.borrow_mut().push(Box::new(f)),
+        }
+    }
+}
+
+pub(crate) struct DeferredOutputData<'a> {
+    pub module: &'a Module<'a>,
+
+    pub name: String,
+    pub output: Cell<Option<&'a Output<'a>>>,
+}
+
+impl<'a> DeferredOutputData<'a> {
+    pub fn resolved_bit_width(&self) -> Option<u32> {
+        self.output.get().map(|output| output.data.bit_width)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn reg_inferred_from_drive_next() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let r = m.reg_deferred("r");
+        r.default_value(0x12u32);
+        r.attribute("xilinx", "async_reg", "true");
+        r.on_resolved(move |r| {
+            m.output("o", !r);
+        });
+        assert_eq!(r.resolved_bit_width(), None);
+        assert!(m.outputs.borrow().is_empty());
+
+        r.drive_next(m.input("i", 8));
+
+        assert_eq!(r.resolved_bit_width(), Some(8));
+        assert_eq!(m.outputs.borrow()["o"].data.bit_width, 8);
+        let register = r.register();
+        assert_eq!(register.data.name, "r");
+        assert_eq!(
+            register
+                .data
+                .initial_value
+                .borrow()
+                .clone()
+                .unwrap()
+                .numeric_value(),
+            0x12
+        );
+        assert_eq!(register.data.attributes.borrow().len(), 1);
+        assert!(register.data.next.borrow().is_some());
+
+        // Deferred uses after resolution are performed immediately
+        r.on_resolved(move |r| {
+            m.output("o2", r);
+        });
+        assert_eq!(m.outputs.borrow()["o2"].data.bit_width, 8);
+    }
+
+    #[test]
+    fn reg_inferred_from_infer_width_from() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let i = m.input("i", 4);
+        let r = m.reg_deferred("r");
+        r.infer_width_from(i);
+        // Feedback is allowed once the bit width is resolved
+        r.drive_next(r.register() + i);
+        m.output("o", r.register());
+
+        assert_eq!(r.resolved_bit_width(), Some(4));
+        assert_eq!(m.registers.borrow().len(), 1);
+    }
+
+    #[test]
+    fn output_inferred_from_drive() {
+        let c = Context::new();
+
+        let outer = c.module("outer", "Outer");
+        let inner = outer.module("inner", "Inner");
+        let o = inner.output_deferred("o");
+        o.on_resolved(move |o| {
+            outer.output("o", o);
+        });
+        assert_eq!(o.resolved_bit_width(), None);
+
+        o.drive(inner.lit(0xabcdu32, 16));
+
+        assert_eq!(o.resolved_bit_width(), Some(16));
+        assert_eq!(o.output().bit_width(), 16);
+        assert_eq!(inner.outputs.borrow()["o"].data.bit_width, 16);
+        assert_eq!(outer.outputs.borrow()["o"].data.bit_width, 16);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot infer a bit width of 16 bit(s) for deferred register \"r\" in module \"A\" from `drive_next`, because its bit width was already inferred as 8 bit(s) from `infer_width_from`."
+    )]
+    fn reg_conflicting_widths_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let r = m.reg_deferred("r");
+        r.infer_width_from(m.input("a", 8));
+
+        // Panic
+        r.drive_next(m.input("b", 16));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot use deferred register \"r\" in module \"A\" before its bit width is resolved. Resolve it with `drive_next` or `infer_width_from` first, or defer this use with `on_resolved`."
+    )]
+    fn reg_use_before_resolution_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let r = m.reg_deferred("r");
+
+        // Panic
+        m.output("o", r.register());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot fit the specified value '256' into register \"r\"'s bit width '8'. The value '256' requires a bit width of at least 9 bit(s)."
+    )]
+    fn reg_deferred_default_value_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let r = m.reg_deferred("r");
+        r.default_value(256u32);
+
+        // Panic
+        r.drive_next(m.input("i", 8));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Attempted to drive register \"r\"'s next value in module \"A\" with a signal from another module (\"B\")."
+    )]
+    fn reg_drive_next_separate_module_error() {
+        let c = Context::new();
+
+        let m1 = c.module("a", "A");
+        let r = m1.reg_deferred("r");
+
+        let m2 = c.module("b", "B");
+        let i = m2.input("i", 1);
+
+        // Panic
+        r.drive_next(i);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot use deferred output \"o\" in module \"A\" before it's driven. Drive it with `drive` first, or defer this use with `on_resolved`."
+    )]
+    fn output_use_before_resolution_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let o = m.output_deferred("o");
+
+        // Panic
+        let _ = o.output();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Attempted to drive deferred output \"o\" in module \"A\", but this output is already driven."
+    )]
+    fn output_already_driven_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let o = m.output_deferred("o");
+        o.drive(m.high());
+
+        // Panic
+        o.drive(m.low());
+    }
+}
@@ -0,0 +1,205 @@
+use super::internal_signal::*;
+use super::module::*;
+use super::signal::*;
+
+use std::cell::RefCell;
+use std::ptr;
+
+/// A bidirectional (tri-state) port of a top-level [`Module`], created by the [`Module::inout`] method.
+///
+/// An `InOut` models a pad at a chip boundary, such as a bidirectional data bus. It can be read like an input, and is optionally driven with a value and an output enable using the [`drive`](Self::drive) method. While the output enable is high, the pad is driven with the value; otherwise, it's left floating (high-impedance) so that it can be driven externally.
+///
+/// In generated Verilog code, an `InOut` is an `inout` port called `<name>`, which is assigned `<output enable> ? <value> : 'z`.
+/// Rust simulators can't model high-impedance values, so in generated simulator code, an `InOut` is instead split into an input called `<name>` (which holds the pad's value as seen by the design, and must be set by the simulation's user, typically to reflect the value driven by the design whenever the output enable is high) and two outputs: `<name>_out` for the value, and `<name>_oe` for the output enable.
+///
+/// # Examples
+///
+/// ```
+/// use kaze::*;
+///
+/// let c = Context::new();
+///
+/// let m = c.module("m", "MyModule");
+///
+/// let data = m.inout("data", 8);
+/// let write = m.input("write", 1);
+///
+/// // Latch the pad's value while it's not driven
+/// let latch = m.reg("latch", 8);
+/// latch.drive_next(write.mux(latch, data));
+/// m.output("latched", latch);
+///
+/// // Drive the pad with the latched value + 1
+/// data.drive(latch + m.lit(1u32, 8), write);
+/// ```
+#[must_use]
+pub struct InOut<'a> {
+    pub(crate) module: &'a Module<'a>,
+
+    pub(crate) name: String,
+    pub(crate) input: &'a Input<'a>,
+    /// The outputs for the driven value and output enable, respectively, once this `InOut` is driven.
+    pub(crate) outputs: RefCell<Option<(&'a Output<'a>, &'a Output<'a>)>>,
+}
+
+impl<'a> InOut<'a> {
+    /// Drives this `InOut` with `value` while `output_enable` is high.
+    ///
+    /// This creates the `<name>_out` and `<name>_oe` outputs used in generated simulator code (see [`InOut`]). If this `InOut` is never driven, it's never driven by this `Module`, and behaves like an input.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` or `output_enable` belong to a different [`Module`] than `self`, if `value` doesn't have the same bit width as `self`, if `output_enable` isn't 1 bit wide, if this `InOut` is already driven, or if its [`Module`] already contains an input or output called `<name>_out` or `<name>_oe`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let sda = m.inout("sda", 1);
+    /// // Open-drain: only ever drive the pad low
+    /// sda.drive(m.low(), m.input("pull_low", 1));
+    /// m.output("sda_in", sda);
+    /// ```
+    pub fn drive(&'a self, value: &'a dyn Signal<'a>, output_enable: &'a dyn Signal<'a>) {
+        let value = value.internal_signal();
+        let output_enable = output_enable.internal_signal();
+        if !ptr::eq(self.module, value.module) || !ptr::eq(self.module, output_enable.module) {
+            panic!(
+                "Attempted to drive inout \"{}\" in module \"{}\" with a signal from another module.",
+                self.name, self.module.name
+            );
+        }
+        if value.bit_width() != self.input.data.bit_width {
+            panic!("Attempted to drive inout \"{}\" in module \"{}\" with a value that has a different bit width than the inout ({} and {}, respectively).", self.name, self.module.name, value.bit_width(), self.input.data.bit_width);
+        }
+        if output_enable.bit_width() != 1 {
+            panic!("Attempted to drive inout \"{}\" in module \"{}\" with an output enable that has {} bit(s), but output enables must be 1 bit wide.", self.name, self.module.name, output_enable.bit_width());
+        }
+        if self.outputs.borrow().is_some() {
+            panic!(
+                "Attempted to drive inout \"{}\" in module \"{}\", but this inout is already driven.",
+                self.name, self.module.name
+            );
+        }
+        let value = self.module.output(format!("{}_out", self.name), value);
+        let output_enable = self
+            .module
+            .output(format!("{}_oe", self.name), output_enable);
+        *self.outputs.borrow_mut() = Some((value, output_enable));
+    }
+
+    /// Returns `true` if this `InOut` has been driven with [`drive`](Self::drive).
+    pub fn is_driven(&self) -> bool {
+        self.outputs.borrow().is_some()
+    }
+}
+
+impl<'a> GetInternalSignal<'a> for InOut<'a> {
+    fn internal_signal(&'a self) -> &'a InternalSignal<'a> {
+        self.input.value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    #[should_panic(
+        expected = "Attempted to drive inout \"io\" in module \"B\" with a signal from another module."
+    )]
+    fn drive_separate_module_error() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let l = a.lit(0u32, 8);
+
+        let b = c.module("b", "B");
+        let io = b.inout("io", 8);
+
+        // Panic
+        io.drive(l, b.high());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Attempted to drive inout \"io\" in module \"A\" with a value that has a different bit width than the inout (4 and 8, respectively)."
+    )]
+    fn drive_value_bit_width_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let io = m.inout("io", 8);
+
+        // Panic
+        io.drive(m.lit(0u32, 4), m.high());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Attempted to drive inout \"io\" in module \"A\" with an output enable that has 2 bit(s), but output enables must be 1 bit wide."
+    )]
+    fn drive_output_enable_bit_width_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let io = m.inout("io", 8);
+
+        // Panic
+        io.drive(m.lit(0u32, 8), m.lit(0u32, 2));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Attempted to drive inout \"io\" in module \"A\", but this inout is already driven."
+    )]
+    fn drive_already_driven_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let io = m.inout("io", 8);
+        io.drive(m.lit(0u32, 8), m.high());
+
+        // Panic
+        io.drive(m.lit(0u32, 8), m.high());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create an output called \"io_oe\" with 1 bit(s) in module \"A\", because this module already contains an input called \"io_oe\" with 1 bit(s)."
+    )]
+    fn drive_port_name_collision_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let io = m.inout("io", 8);
+        let oe = m.input("io_oe", 1);
+
+        // Panic
+        io.drive(m.lit(0u32, 8), oe);
+    }
+
+    #[test]
+    fn drive_creates_ports() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let io = m.inout("io", 8);
+        assert!(!io.is_driven());
+        assert_eq!(m.inputs().collect::<Vec<_>>(), vec![("io", 8)]);
+        assert_eq!(m.outputs().count(), 0);
+
+        io.drive(!io, m.high());
+        assert!(io.is_driven());
+        assert_eq!(m.inputs().collect::<Vec<_>>(), vec![("io", 8)]);
+        assert_eq!(
+            m.outputs().collect::<Vec<_>>(),
+            vec![("io_oe", 1), ("io_out", 8)]
+        );
+    }
+}
@@ -0,0 +1,217 @@
+use super::module::*;
+use super::signal::*;
+
+/// A fixed number of instances of the same [declared](super::ModuleDeclaration) module, created by the [`ModuleParent::instance_array`](super::ModuleParent::instance_array) method.
+///
+/// An `InstanceArray` is a convenience for describing regular structures such as SIMD lanes, where the same module is instantiated several times and each instance is connected to a slice of a wider bus. Its instances are ordinary [`Module`]s called `<name>_0`, `<name>_1`, and so on, which is how they're named in traces and generated Verilog code as well.
+///
+/// # Examples
+///
+/// ```
+/// use kaze::*;
+///
+/// let c = Context::new();
+///
+/// let lane = c.declare_module("Lane");
+/// lane.define(|m| {
+///     m.output("o", !m.input("i", 8));
+/// });
+///
+/// let m = c.module("m", "MyModule");
+/// let bus = m.input("bus", 32);
+///
+/// // Instances are called "lane_0" through "lane_3"
+/// let lanes = m.instance_array("lane", &lane, 4);
+/// lanes.drive_input_each("i", |i| bus.bits(i as u32 * 8 + 7, i as u32 * 8));
+/// m.output("o", lanes.collect_outputs_concat("o")); // 32 bits, with lane_0's output in the lower bits
+/// ```
+#[must_use]
+pub struct InstanceArray<'a> {
+    name: String,
+    instances: Vec<&'a Module<'a>>,
+}
+
+impl<'a> InstanceArray<'a> {
+    pub(super) fn new(name: String, instances: Vec<&'a Module<'a>>) -> InstanceArray<'a> {
+        InstanceArray { name, instances }
+    }
+
+    /// Returns the number of instances in this `InstanceArray`.
+    pub fn len(&self) -> usize {
+        self.instances.len()
+    }
+
+    /// Returns `true` if this `InstanceArray` has no instances.
+    ///
+    /// Since an `InstanceArray` can't be empty, this always returns `false`, and is only provided for consistency with other collections.
+    pub fn is_empty(&self) -> bool {
+        self.instances.is_empty()
+    }
+
+    /// Returns the instances in this `InstanceArray`, in order.
+    pub fn instances(&self) -> &[&'a Module<'a>] {
+        &self.instances
+    }
+
+    /// Returns the instance at index `index`, which is called `<name>_<index>`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than or equal to [`len`](Self::len).
+    pub fn get(&self, index: usize) -> &'a Module<'a> {
+        if index >= self.instances.len() {
+            panic!(
+                "Attempted to get instance {} of instance array \"{}\" with {} instance(s).",
+                index,
+                self.name,
+                self.instances.len()
+            );
+        }
+        self.instances[index]
+    }
+
+    /// Returns an iterator over the instances in this `InstanceArray`, in order.
+    pub fn iter(&self) -> impl Iterator<Item = &'a Module<'a>> + '_ {
+        self.instances.iter().copied()
+    }
+
+    /// Drives the input called `name` of each instance in this `InstanceArray` with the signal returned by calling `f` with that instance's index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the module hasn't been defined yet (so its instances don't have any inputs), or under the same conditions as [`Input::drive`] for any of the instances (for example, if a signal returned by `f` doesn't belong to the module containing this `InstanceArray`, or has a different bit width than the input).
+    pub fn drive_input_each(&self, name: &str, mut f: impl FnMut(usize) -> &'a dyn Signal<'a>) {
+        for (i, instance) in self.instances.iter().enumerate() {
+            instance.get_input(name).drive(f(i));
+        }
+    }
+
+    /// Creates a [`Signal`] in the module containing this `InstanceArray` that represents the outputs called `name` of all of its instances concatenated together.
+    ///
+    /// Unlike [`Module::concat_all`], the first instance's output occupies the lower bits of the result, so that the result has the same layout as a bus whose slices were used to drive the instances' inputs with [`drive_input_each`](Self::drive_input_each).
+    ///
+    /// # Panics
+    ///
+    /// Panics if this `InstanceArray` was created in a [`Context`](super::Context) rather than in a [`Module`], if the module hasn't been defined yet (so its instances don't have any outputs), or if the total bit width of the outputs is greater than [`MAX_SIGNAL_BIT_WIDTH`].
+    pub fn collect_outputs_concat(&self, name: &str) -> &'a dyn Signal<'a> {
+        let parent = match self.instances[0].parent {
+            Some(parent) => parent,
+            None => panic!("Attempted to collect the outputs called \"{}\" of instance array \"{}\", but its instances are top-level modules, whose outputs can't be used as signals.", name, self.name),
+        };
+        let outputs = self
+            .instances
+            .iter()
+            .rev()
+            .map(|instance| instance.get_output(name) as &dyn Signal<'a>)
+            .collect::<Vec<_>>();
+        parent.concat_all(&outputs)
+    }
+}
+
+impl<'a, 'b> IntoIterator for &'b InstanceArray<'a> {
+    type Item = &'a Module<'a>;
+    type IntoIter = std::iter::Copied<std::slice::Iter<'b, &'a Module<'a>>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.instances.iter().copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn instance_names_and_order() {
+        let c = Context::new();
+
+        let lane = c.declare_module("Lane");
+        lane.define(|m| {
+            m.output("o", m.input("i", 4));
+        });
+
+        let m = c.module("m", "M");
+        let lanes = m.instance_array("lane", &lane, 3);
+
+        assert_eq!(lanes.len(), 3);
+        assert!(!lanes.is_empty());
+        assert_eq!(
+            lanes
+                .iter()
+                .map(|instance| instance.instance_name.as_str())
+                .collect::<Vec<_>>(),
+            ["lane_0", "lane_1", "lane_2"]
+        );
+        assert!(std::ptr::eq(lanes.get(1), lanes.instances()[1]));
+        for (instance, &child) in (&lanes).into_iter().zip(m.modules.borrow().iter()) {
+            assert!(std::ptr::eq(instance, child));
+            assert_eq!(instance.name, "Lane");
+        }
+    }
+
+    #[test]
+    fn collect_outputs_concat_lane_order() {
+        let c = Context::new();
+
+        let lane = c.declare_module("Lane");
+        lane.define(|m| {
+            m.output("o", m.input("i", 4));
+        });
+
+        let m = c.module("m", "M");
+        let bus = m.input("bus", 12);
+        let lanes = m.instance_array("lane", &lane, 3);
+        lanes.drive_input_each("i", |i| bus.bits(i as u32 * 4 + 3, i as u32 * 4));
+        let o = lanes.collect_outputs_concat("o");
+
+        assert_eq!(o.bit_width(), 12);
+        assert!(std::ptr::eq(o.internal_signal().module, m));
+        assert_eq!(validate(m), Ok(()));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Attempted to create an instance array called \"lane\" with 0 instances."
+    )]
+    fn empty_error() {
+        let c = Context::new();
+
+        let lane = c.declare_module("Lane");
+        let m = c.module("m", "M");
+
+        // Panic
+        let _ = m.instance_array("lane", &lane, 0);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Attempted to get instance 2 of instance array \"lane\" with 2 instance(s)."
+    )]
+    fn get_out_of_range_error() {
+        let c = Context::new();
+
+        let lane = c.declare_module("Lane");
+        let m = c.module("m", "M");
+        let lanes = m.instance_array("lane", &lane, 2);
+
+        // Panic
+        let _ = lanes.get(2);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Attempted to collect the outputs called \"o\" of instance array \"lane\", but its instances are top-level modules, whose outputs can't be used as signals."
+    )]
+    fn collect_outputs_concat_top_level_error() {
+        let c = Context::new();
+
+        let lane = c.declare_module("Lane");
+        lane.define(|m| {
+            m.output("o", m.high());
+        });
+        let lanes = c.instance_array("lane", &lane, 1);
+
+        // Panic
+        let _ = lanes.collect_outputs_concat("o");
+    }
+}
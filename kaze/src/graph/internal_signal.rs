@@ -4,6 +4,9 @@ use super::mem::*;
 use super::module::*;
 use super::register::*;
 
+use crate::identifiers::*;
+
+use std::collections::HashSet;
 use std::hash::{Hash, Hasher};
 use std::ptr;
 
@@ -37,10 +40,208 @@ impl<'a> InternalSignal<'a> {
             SignalData::Repeat { bit_width, .. } => bit_width,
             SignalData::Concat { bit_width, .. } => bit_width,
             SignalData::Mux { bit_width, .. } => bit_width,
-            SignalData::MemReadPortOutput { mem, .. } => mem.element_bit_width,
+            SignalData::Case { bit_width, .. } => bit_width,
+            SignalData::MemReadPortOutput { mem, .. }
+            | SignalData::MemCombReadPortOutput { mem, .. } => mem.element_bit_width,
+            SignalData::NamedWire { source, .. } => source.bit_width(),
+            SignalData::Reinterpret { source, .. } => source.bit_width(),
+        }
+    }
+
+    /// Allocates a new signal with the specified `data` in the same [`Context`] and [`Module`] as `self`.
+    pub(crate) fn alloc_sibling(&'a self, data: SignalData<'a>) -> &'a InternalSignal<'a> {
+        self.context.signal_arena.alloc(InternalSignal {
+            context: self.context,
+            module: self.module,
+
+            data,
+        })
+    }
+
+    /// Returns the signals whose values this signal is computed from within its own module; inputs, outputs, registers, and mem read ports are leaves, as their sources are listed with the ports, registers, and mems they belong to.
+    pub(crate) fn operands(&'a self) -> Vec<&'a InternalSignal<'a>> {
+        match self.data {
+            SignalData::Lit { .. }
+            | SignalData::Input { .. }
+            | SignalData::Output { .. }
+            | SignalData::Reg { .. } => Vec::new(),
+
+            SignalData::UnOp { source, .. }
+            | SignalData::Bits { source, .. }
+            | SignalData::Repeat { source, .. }
+            | SignalData::NamedWire { source, .. }
+            | SignalData::Reinterpret { source, .. } => vec![source],
+
+            SignalData::SimpleBinOp { lhs, rhs, .. }
+            | SignalData::AdditiveBinOp { lhs, rhs, .. }
+            | SignalData::ComparisonBinOp { lhs, rhs, .. }
+            | SignalData::ShiftBinOp { lhs, rhs, .. }
+            | SignalData::Mul { lhs, rhs, .. }
+            | SignalData::MulSigned { lhs, rhs, .. }
+            | SignalData::Concat { lhs, rhs, .. } => vec![lhs, rhs],
+
+            SignalData::Mux {
+                cond,
+                when_true,
+                when_false,
+                ..
+            } => vec![cond, when_true, when_false],
+            SignalData::Case {
+                sel,
+                ref cases,
+                default,
+                ..
+            } => case_operands(sel, cases, default),
+
+            SignalData::MemReadPortOutput {
+                address, enable, ..
+            } => vec![address, enable],
+            SignalData::MemCombReadPortOutput { address, .. } => vec![address],
+        }
+    }
+
+    /// Returns `true` if this signal and `other` are structurally equivalent; see [`Signal::structural_eq`](super::Signal::structural_eq).
+    pub(crate) fn structural_eq(&'a self, other: &'a InternalSignal<'a>) -> bool {
+        // Pairs that have already been compared (or are on the stack to be compared) don't need to be compared again, which keeps shared subexpressions from being compared more than once
+        let mut visited = HashSet::new();
+        let mut stack = vec![(self, other)];
+        while let Some((a, b)) = stack.pop() {
+            if ptr::eq(a, b) || !visited.insert((a as *const _, b as *const _)) {
+                continue;
+            }
+            if !ptr::eq(a.module, b.module) || !a.node_eq(b) {
+                return false;
+            }
+            stack.extend(a.operands().into_iter().zip(b.operands()));
         }
+        true
     }
 
+    /// Returns `true` if this signal and `other` have the same op and parameters, disregarding their operands. Inputs, outputs, registers, and mem read ports are never equal to other signals.
+    fn node_eq(&self, other: &InternalSignal<'a>) -> bool {
+        match (&self.data, &other.data) {
+            (
+                SignalData::Lit {
+                    value: a_value,
+                    bit_width: a_bit_width,
+                },
+                SignalData::Lit { value, bit_width },
+            ) => a_bit_width == bit_width && a_value.numeric_value() == value.numeric_value(),
+            (
+                SignalData::UnOp {
+                    op: a_op,
+                    bit_width: a_bit_width,
+                    ..
+                },
+                SignalData::UnOp { op, bit_width, .. },
+            ) => a_op == op && a_bit_width == bit_width,
+            (
+                SignalData::SimpleBinOp {
+                    op: a_op,
+                    bit_width: a_bit_width,
+                    ..
+                },
+                SignalData::SimpleBinOp { op, bit_width, .. },
+            ) => a_op == op && a_bit_width == bit_width,
+            (
+                SignalData::AdditiveBinOp {
+                    op: a_op,
+                    bit_width: a_bit_width,
+                    ..
+                },
+                SignalData::AdditiveBinOp { op, bit_width, .. },
+            ) => a_op == op && a_bit_width == bit_width,
+            (
+                SignalData::ComparisonBinOp { op: a_op, .. },
+                SignalData::ComparisonBinOp { op, .. },
+            ) => a_op == op,
+            (
+                SignalData::ShiftBinOp {
+                    op: a_op,
+                    bit_width: a_bit_width,
+                    ..
+                },
+                SignalData::ShiftBinOp { op, bit_width, .. },
+            ) => a_op == op && a_bit_width == bit_width,
+            (
+                SignalData::Mul {
+                    bit_width: a_bit_width,
+                    ..
+                },
+                SignalData::Mul { bit_width, .. },
+            )
+            | (
+                SignalData::MulSigned {
+                    bit_width: a_bit_width,
+                    ..
+                },
+                SignalData::MulSigned { bit_width, .. },
+            )
+            | (
+                SignalData::Concat {
+                    bit_width: a_bit_width,
+                    ..
+                },
+                SignalData::Concat { bit_width, .. },
+            )
+            | (
+                SignalData::Mux {
+                    bit_width: a_bit_width,
+                    ..
+                },
+                SignalData::Mux { bit_width, .. },
+            ) => a_bit_width == bit_width,
+            (
+                SignalData::Case {
+                    cases: a_cases,
+                    bit_width: a_bit_width,
+                    ..
+                },
+                SignalData::Case {
+                    cases, bit_width, ..
+                },
+            ) => {
+                a_bit_width == bit_width
+                    && a_cases.len() == cases.len()
+                    && a_cases
+                        .iter()
+                        .zip(cases.iter())
+                        .all(|((a_value, _), (value, _))| a_value == value)
+            }
+            (
+                SignalData::Bits {
+                    range_high: a_range_high,
+                    range_low: a_range_low,
+                    ..
+                },
+                SignalData::Bits {
+                    range_high,
+                    range_low,
+                    ..
+                },
+            ) => a_range_high == range_high && a_range_low == range_low,
+            (
+                SignalData::Repeat {
+                    count: a_count,
+                    bit_width: a_bit_width,
+                    ..
+                },
+                SignalData::Repeat {
+                    count, bit_width, ..
+                },
+            ) => a_count == count && a_bit_width == bit_width,
+            (SignalData::NamedWire { name: a_name, .. }, SignalData::NamedWire { name, .. }) => {
+                a_name == name
+            }
+            (
+                SignalData::Reinterpret { label: a_label, .. },
+                SignalData::Reinterpret { label, .. },
+            ) => a_label == label,
+            _ => false,
+        }
+    }
+
+    /// Returns the instance names of this signal's module and its ancestors, joined with `_`, with characters that can't appear in an identifier replaced with `_`, for use in generated names.
     pub(crate) fn module_instance_name_prefix(&self) -> String {
         let mut stack = Vec::new();
         let mut module = Some(self.module);
@@ -51,10 +252,11 @@ impl<'a> InternalSignal<'a> {
 
         let mut ret = String::new();
         while let Some(m) = stack.pop() {
+            let instance_name = sanitize(&m.instance_name);
             ret = if ret.is_empty() {
-                m.instance_name.clone()
+                instance_name
             } else {
-                format!("{}_{}", ret, m.instance_name)
+                format!("{}_{}", ret, instance_name)
             };
         }
 
@@ -157,27 +359,50 @@ pub(crate) enum SignalData<'a> {
         when_false: &'a InternalSignal<'a>,
         bit_width: u32,
     },
+    /// Selects the value of the first case whose constant matches `sel`, or `default` if none of them match; see [`Module::case`](super::Module::case).
+    Case {
+        sel: &'a InternalSignal<'a>,
+        cases: Vec<(u128, &'a InternalSignal<'a>)>,
+        default: &'a InternalSignal<'a>,
+        bit_width: u32,
+    },
 
     MemReadPortOutput {
         mem: &'a Mem<'a>,
         address: &'a InternalSignal<'a>,
         enable: &'a InternalSignal<'a>,
     },
+    MemCombReadPortOutput {
+        mem: &'a Mem<'a>,
+        address: &'a InternalSignal<'a>,
+    },
+
+    NamedWire {
+        name: String,
+        source: &'a InternalSignal<'a>,
+    },
+
+    Reinterpret {
+        label: String,
+        source: &'a InternalSignal<'a>,
+    },
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq)]
 pub(crate) enum UnOp {
     Not,
+    ReverseBits,
+    SwapBytes,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq)]
 pub(crate) enum SimpleBinOp {
     BitAnd,
     BitOr,
     BitXor,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq)]
 pub(crate) enum ComparisonBinOp {
     Equal,
     GreaterThan,
@@ -191,17 +416,19 @@ pub(crate) enum ComparisonBinOp {
     NotEqual,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq)]
 pub(crate) enum AdditiveBinOp {
     Add,
     Sub,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq)]
 pub(crate) enum ShiftBinOp {
     Shl,
     Shr,
     ShrArithmetic,
+    RotateLeft,
+    RotateRight,
 }
 
 pub trait GetInternalSignal<'a> {
@@ -214,3 +441,16 @@ impl<'a> GetInternalSignal<'a> for InternalSignal<'a> {
         self
     }
 }
+
+/// Returns the operands of a [`SignalData::Case`]: its selector, followed by the value of each case, followed by its default value.
+pub(crate) fn case_operands<'a>(
+    sel: &'a InternalSignal<'a>,
+    cases: &[(u128, &'a InternalSignal<'a>)],
+    default: &'a InternalSignal<'a>,
+) -> Vec<&'a InternalSignal<'a>> {
+    let mut ret = Vec::with_capacity(cases.len() + 2);
+    ret.push(sel);
+    ret.extend(cases.iter().map(|&(_, value)| value));
+    ret.push(default);
+    ret
+}
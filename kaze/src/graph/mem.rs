@@ -4,22 +4,30 @@ use super::internal_signal::*;
 use super::module::*;
 use super::signal::*;
 
+use crate::identifiers::is_clock_or_reset_name;
+
 use std::cell::RefCell;
+use std::fs;
 use std::hash::{Hash, Hasher};
+use std::io;
+use std::path::Path;
 use std::ptr;
 
 /// A synchronous memory, created by the [`Module::mem`] method.
 ///
-/// Memories in kaze are always sequential/synchronous-read, sequential/synchronous-write memories.
+/// Memories in kaze are sequential/synchronous-write memories, and are typically sequential/synchronous-read as well.
 /// This means that when a read and/or write is asserted, the read/write will be visible on the cycle immediately following the cycle in which it's asserted.
 /// If both a write and a read to the same location occurs within the same cycle, the read will return the previous value at the memory location, **not** the newly-written value.
+/// Memories can also have [combinational read ports](Self::read_port_comb), which return the value at the memory location within the same cycle.
 ///
-/// Memories must have at least one read port specified.
+/// Memories must have at least one (synchronous or combinational) read port specified.
 /// Multiple reads to the same location within the same cycle will return the same value.
 ///
 /// Memories may optionally have initial contents and/or a write port specified.
 /// If either of these are missing, the contents of the memory can't be determined, so this is a logical error.
 ///
+/// By default, all ports are clocked by the containing [`Module`]'s implicit clock. A memory's read and write ports can instead be clocked by two separate, named clocks with [`dual_clock`](Self::dual_clock).
+///
 /// # Examples
 ///
 /// ```
@@ -48,6 +56,7 @@ pub struct Mem<'a> {
     pub(crate) initial_contents: RefCell<Option<Vec<Constant>>>,
 
     pub(crate) read_ports: RefCell<Vec<(&'a InternalSignal<'a>, &'a InternalSignal<'a>)>>,
+    pub(crate) comb_read_ports: RefCell<Vec<&'a InternalSignal<'a>>>,
     pub(crate) write_port: RefCell<
         Option<(
             &'a InternalSignal<'a>,
@@ -55,9 +64,32 @@ pub struct Mem<'a> {
             &'a InternalSignal<'a>,
         )>,
     >,
+
+    pub(crate) clocks: RefCell<Option<MemClocks>>,
+}
+
+/// The names of the clocks of a [dual-clock](Mem::dual_clock) [`Mem`]'s read and write ports.
+pub(crate) struct MemClocks {
+    pub read: String,
+    pub write: String,
 }
 
 impl<'a> Mem<'a> {
+    /// Returns this `Mem`'s name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the number of bits in this `Mem`'s addresses.
+    pub fn address_bit_width(&self) -> u32 {
+        self.address_bit_width
+    }
+
+    /// Returns the number of bits in each of this `Mem`'s elements.
+    pub fn element_bit_width(&self) -> u32 {
+        self.element_bit_width
+    }
+
     /// Specifies the initial contents for this `Mem`.
     ///
     /// Reads from this `Mem` will reflect the values specified unless writes have overwritten them (if the `Mem` has a write port).
@@ -65,7 +97,7 @@ impl<'a> Mem<'a> {
     /// By default, a `Mem` does not have initial contents, and it is not required to specify them unless the `Mem` does not have a write port.
     /// If initial contents are not specified, then this `Mem`'s contents will be undefined initially.
     ///
-    /// Note that these contents are **not** restored when the containing [`Module`]'s implicit reset is asserted.
+    /// Note that these contents are **not** restored when the containing [`Module`]'s implicit reset is asserted, except in generated Rust simulators with [`reset_mems`](crate::sim::GenerationOptions::reset_mems) enabled.
     ///
     /// # Panics
     ///
@@ -88,9 +120,7 @@ impl<'a> Mem<'a> {
     /// m.output("my_output", my_mem.read_port(m.high(), m.high()));
     /// ```
     pub fn initial_contents<C: Clone + Into<Constant>>(&'a self, contents: &[C]) {
-        if self.initial_contents.borrow().is_some() {
-            panic!("Attempted to specify initial contents for memory \"{}\" in module \"{}\", but this memory already has initial contents.", self.name, self.module.name);
-        }
+        self.validate_no_initial_contents();
         let expected_contents_len = 1 << self.address_bit_width;
         if contents.len() != expected_contents_len {
             panic!("Attempted to specify initial contents for memory \"{}\" in module \"{}\" that contains {} element(s), but this memory has {} address bit(s), and requires {} element(s).", self.name, self.module.name, contents.len(), self.address_bit_width, expected_contents_len);
@@ -98,12 +128,196 @@ impl<'a> Mem<'a> {
         *self.initial_contents.borrow_mut() = Some(contents.iter().cloned().enumerate().map(|(i, x)| {
             let ret = x.into();
             if ret.required_bits() > self.element_bit_width {
-                panic!("Attempted to specify initial contents for memory \"{}\" in module \"{}\", but this memory has an element width of {} bit(s), and these initial contents specify element {} with value {} which requires {} bit(s).", self.name, self.module.name, self.element_bit_width, i, ret.numeric_value(), ret.required_bits());
+                panic!("Attempted to specify initial contents for memory \"{}\" in module \"{}\", but this memory has an element width of {} bit(s), and these initial contents specify element {} with value {} which requires {} bit(s).", self.name, self.module.name, self.element_bit_width, i, ret.describe(), ret.required_bits());
             }
-            ret
+            ret.to_bit_pattern(self.element_bit_width)
         }).collect());
     }
 
+    /// Specifies the initial contents for this `Mem` from the values produced by `contents`, in address order.
+    ///
+    /// This is equivalent to [`initial_contents`](Self::initial_contents), except that `contents` may produce fewer elements than this `Mem` contains, in which case the remaining elements are initialized to `0`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this `Mem` already has initial contents specified, if `contents` produces more elements than this `Mem` contains, or if any of the specified element values don't fit into this `Mem`'s element bit width.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let squares = m.mem("squares", 4, 8);
+    /// squares.initial_contents_iter((0..16u32).map(|x| x * x));
+    ///
+    /// let rom = m.mem("rom", 10, 32);
+    /// rom.initial_contents_iter(vec![0x00000013u32, 0x00100093u32]); // Remaining elements are 0
+    /// ```
+    pub fn initial_contents_iter<C: Into<Constant>>(
+        &'a self,
+        contents: impl IntoIterator<Item = C>,
+    ) {
+        self.validate_no_initial_contents();
+        let expected_contents_len = 1 << self.address_bit_width;
+        let mut ret = Vec::with_capacity(expected_contents_len);
+        for (i, x) in contents.into_iter().enumerate() {
+            if i == expected_contents_len {
+                panic!("Attempted to specify initial contents for memory \"{}\" in module \"{}\" that contain more than {} element(s), but this memory has {} address bit(s), and contains {} element(s).", self.name, self.module.name, expected_contents_len, self.address_bit_width, expected_contents_len);
+            }
+            let x = x.into();
+            if x.required_bits() > self.element_bit_width {
+                panic!("Attempted to specify initial contents for memory \"{}\" in module \"{}\", but this memory has an element width of {} bit(s), and these initial contents specify element {} with value {} which requires {} bit(s).", self.name, self.module.name, self.element_bit_width, i, x.describe(), x.required_bits());
+            }
+            ret.push(x.to_bit_pattern(self.element_bit_width));
+        }
+        ret.resize(expected_contents_len, Constant::U128(0));
+        *self.initial_contents.borrow_mut() = Some(ret);
+    }
+
+    /// Specifies the initial contents for this `Mem` from the text file at `path`, which contains one hexadecimal value per element in address order, similar to Verilog's `$readmemh`.
+    ///
+    /// Values are separated by whitespace, may contain `_` characters between digits, and must not have a prefix such as `0x`. Everything following `//` on a line is treated as a comment. If the file contains fewer values than this `Mem` contains elements, the remaining elements are initialized to `0`.
+    ///
+    /// This is meant to be called from build scripts, so problems with the file (including its contents) are reported as errors rather than panics.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read, or an error of kind [`io::ErrorKind::InvalidData`] if it contains a value that isn't valid hexadecimal, a value that doesn't fit into this `Mem`'s element bit width, or more values than this `Mem` contains elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this `Mem` already has initial contents specified.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use kaze::*;
+    ///
+    /// # fn main() -> std::io::Result<()> {
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let rom = m.mem("rom", 10, 32);
+    /// rom.initial_contents_from_hex_file("program.hex")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn initial_contents_from_hex_file(&'a self, path: impl AsRef<Path>) -> io::Result<()> {
+        self.validate_no_initial_contents();
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)?;
+        let mut contents = Vec::new();
+        for (line_index, line) in text.lines().enumerate() {
+            let line = line.split("//").next().unwrap();
+            for word in line.split_whitespace() {
+                let digits = word.replace('_', "");
+                let value = match u128::from_str_radix(&digits, 16) {
+                    Ok(value) if !digits.starts_with('+') => value,
+                    _ => {
+                        return Err(invalid_data(format!(
+                            "Cannot parse \"{}\" on line {} of \"{}\" as a hexadecimal value.",
+                            word,
+                            line_index + 1,
+                            path.display()
+                        )))
+                    }
+                };
+                contents.push(value);
+            }
+        }
+        self.initial_contents_from_file_contents(contents, path)
+    }
+
+    /// Specifies the initial contents for this `Mem` from the binary file at `path`, which contains one `element_bytes`-byte little-endian value per element in address order.
+    ///
+    /// If the file contains fewer values than this `Mem` contains elements, the remaining elements are initialized to `0`.
+    ///
+    /// This is meant to be called from build scripts, so problems with the file (including its contents) are reported as errors rather than panics.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read, or an error of kind [`io::ErrorKind::InvalidData`] if its length isn't a multiple of `element_bytes`, if it contains a value that doesn't fit into this `Mem`'s element bit width, or if it contains more values than this `Mem` contains elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this `Mem` already has initial contents specified, or if `element_bytes` is `0` or greater than `16`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use kaze::*;
+    ///
+    /// # fn main() -> std::io::Result<()> {
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let rom = m.mem("rom", 10, 32);
+    /// rom.initial_contents_from_bin_file("program.bin", 4)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn initial_contents_from_bin_file(
+        &'a self,
+        path: impl AsRef<Path>,
+        element_bytes: u32,
+    ) -> io::Result<()> {
+        self.validate_no_initial_contents();
+        if element_bytes == 0 || element_bytes > 16 {
+            panic!("Attempted to specify initial contents for memory \"{}\" in module \"{}\" with {} byte(s) per element, but elements must be between 1 and 16 bytes.", self.name, self.module.name, element_bytes);
+        }
+        let path = path.as_ref();
+        let bytes = fs::read(path)?;
+        if bytes.len() % element_bytes as usize != 0 {
+            return Err(invalid_data(format!(
+                "The length of \"{}\" ({} byte(s)) is not a multiple of the element size ({} byte(s)).",
+                path.display(),
+                bytes.len(),
+                element_bytes
+            )));
+        }
+        let contents = bytes
+            .chunks(element_bytes as usize)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .rev()
+                    .fold(0, |acc, &byte| (acc << 8) | byte as u128)
+            })
+            .collect();
+        self.initial_contents_from_file_contents(contents, path)
+    }
+
+    fn validate_no_initial_contents(&self) {
+        if self.initial_contents.borrow().is_some() {
+            panic!("Attempted to specify initial contents for memory \"{}\" in module \"{}\", but this memory already has initial contents.", self.name, self.module.name);
+        }
+    }
+
+    fn initial_contents_from_file_contents(
+        &'a self,
+        contents: Vec<u128>,
+        path: &Path,
+    ) -> io::Result<()> {
+        let expected_contents_len = 1 << self.address_bit_width;
+        if contents.len() > expected_contents_len {
+            return Err(invalid_data(format!("\"{}\" contains {} element(s), but memory \"{}\" in module \"{}\" has {} address bit(s), and contains {} element(s).", path.display(), contents.len(), self.name, self.module.name, self.address_bit_width, expected_contents_len)));
+        }
+        for (i, &value) in contents.iter().enumerate() {
+            let required_bits = 128 - value.leading_zeros();
+            if required_bits > self.element_bit_width {
+                return Err(invalid_data(format!("\"{}\" specifies element {} with value {} which requires {} bit(s), but memory \"{}\" in module \"{}\" has an element width of {} bit(s).", path.display(), i, value, required_bits, self.name, self.module.name, self.element_bit_width)));
+            }
+        }
+        self.initial_contents_iter(contents);
+        Ok(())
+    }
+
     /// Specifies a read port for this `Mem` and returns a [`Signal`] representing the data read from this port.
     ///
     /// `Mem`s are required to have at least one read port, otherwise the memory contents could never be read, which would be a logical error.
@@ -161,6 +375,72 @@ impl<'a> Mem<'a> {
         ret
     }
 
+    /// Specifies a read port for this `Mem` that's always enabled, and returns a [`Signal`] representing the data read from this port.
+    ///
+    /// This is equivalent to calling [`read_port`](Self::read_port) with an `enable` signal that's always high, so the returned [`Signal`] reflects the data read from the location specified by `address` on the previous cycle.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `address`'s bit width doesn't match this `Mem`'s address bit width.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let my_mem = m.mem("my_mem", 1, 32);
+    /// my_mem.initial_contents(&[0xfadebabeu32, 0xdeadbeefu32]);
+    /// m.output("my_output", my_mem.read_port_always(m.input("my_address", 1)));
+    /// ```
+    pub fn read_port_always(&'a self, address: &'a dyn Signal<'a>) -> &'a dyn Signal<'a> {
+        self.read_port(address, self.module.high())
+    }
+
+    /// Specifies a combinational (asynchronous) read port for this `Mem` and returns a [`Signal`] representing the data read from this port.
+    ///
+    /// Unlike [`read_port`](Self::read_port), the returned [`Signal`] reflects the data at the location specified by `address` within the same cycle, and has no enable signal.
+    /// Writes still only update the memory contents on the following cycle, so a combinational read of a location that's written in the same cycle returns the previous value at that location, and a read in any later cycle returns the newly-written value.
+    ///
+    /// In generated Rust simulator code, the memory is indexed directly in `prop`, and in generated Verilog code, the read is a continuous assignment that indexes the memory.
+    /// Note that many target devices can't implement combinational reads with block RAM, so they may be synthesized with distributed RAM or flip-flops instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `address`'s bit width doesn't match this `Mem`'s address bit width.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let my_mem = m.mem("my_mem", 2, 8);
+    /// my_mem.initial_contents(&[0x01u8, 0x02u8, 0x04u8, 0x08u8]);
+    /// my_mem.write_port(m.input("write_address", 2), m.input("write_value", 8), m.input("write_enable", 1));
+    /// m.output("my_output", my_mem.read_port_comb(m.input("read_address", 2)));
+    /// ```
+    pub fn read_port_comb(&'a self, address: &'a dyn Signal<'a>) -> &'a dyn Signal<'a> {
+        let address = address.internal_signal();
+        if address.bit_width() != self.address_bit_width {
+            panic!("Attempted to specify a combinational read port for memory \"{}\" in module \"{}\" with an address signal with {} bit(s), but this memory has {} address bit(s).", self.name, self.module.name, address.bit_width(), self.address_bit_width);
+        }
+        let ret = self.context.signal_arena.alloc(InternalSignal {
+            context: self.context,
+            module: self.module,
+
+            data: SignalData::MemCombReadPortOutput { mem: self, address },
+        });
+        self.comb_read_ports.borrow_mut().push(address);
+        ret
+    }
+
     /// Specifies a write port for this `Mem`.
     ///
     /// By default, a `Mem` does not have any write ports, and it is not required to specify one unless the `Mem` does not have initial contents.
@@ -213,6 +493,57 @@ impl<'a> Mem<'a> {
         }
         *self.write_port.borrow_mut() = Some((address, value, enable));
     }
+
+    /// Specifies that this `Mem`'s read ports are clocked by a clock called `read_clock`, and its write port is clocked by a clock called `write_clock`, rather than by the containing [`Module`]'s implicit clock.
+    ///
+    /// Clocks are identified by their name; dual-clock memories with the same clock names in different [`Module`]s in a hierarchy share the same clocks.
+    /// In generated Rust simulator code, each clock gets its own `posedge_<name>` method alongside `posedge_clk` (which only updates registers and single-clock memories), and in generated Verilog code, each clock gets its own `<name>` port alongside `clk`.
+    ///
+    /// Each port behaves as usual, but relative to its own clock: a read returns the value that was stored at the location specified by its address when its clock's positive edge occurred, and a write updates the memory contents when the write clock's positive edge occurs.
+    /// As with real dual-clock memories, the result of reading a location that's written "at the same time" depends on the order of the clock edges; in generated simulator code, that's the order of the `posedge_<name>` method calls.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this `Mem` already has clocks specified, if `read_clock` and `write_clock` are the same, or if either name isn't a legal identifier (non-empty, consisting only of ASCII alphanumeric characters and underscores, not starting with a digit, and not a Verilog or SystemVerilog keyword).
+    /// Additionally, code generation panics if either name is the same as the implicit clock or reset's name, or the name of an input or output of any module in the hierarchy.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let fifo_mem = m.mem("fifo_mem", 4, 8);
+    /// fifo_mem.dual_clock("read_clk", "write_clk");
+    /// fifo_mem.write_port(m.input("write_addr", 4), m.input("write_data", 8), m.input("write_en", 1));
+    /// m.output("read_data", fifo_mem.read_port(m.input("read_addr", 4), m.input("read_en", 1)));
+    /// ```
+    pub fn dual_clock(&'a self, read_clock: impl Into<String>, write_clock: impl Into<String>) {
+        let read_clock = read_clock.into();
+        let write_clock = write_clock.into();
+        if self.clocks.borrow().is_some() {
+            panic!("Attempted to specify clocks for memory \"{}\" in module \"{}\", but this memory already has clocks specified.", self.name, self.module.name);
+        }
+        for name in [&read_clock, &write_clock].iter() {
+            if !is_clock_or_reset_name(name) {
+                panic!("Attempted to specify a clock called \"{}\" for memory \"{}\" in module \"{}\". Clock names must be non-empty, consist only of ASCII alphanumeric characters and underscores, must not start with a digit, and must not be Verilog or SystemVerilog keywords.", name, self.name, self.module.name);
+            }
+        }
+        if read_clock == write_clock {
+            panic!("Attempted to specify clocks for memory \"{}\" in module \"{}\", but the read and write clocks are both called \"{}\". The read and write clocks must be different.", self.name, self.module.name, read_clock);
+        }
+        *self.clocks.borrow_mut() = Some(MemClocks {
+            read: read_clock,
+            write: write_clock,
+        });
+    }
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
 }
 
 impl<'a> Eq for &'a Mem<'a> {}
@@ -277,6 +608,249 @@ mod tests {
         mem.initial_contents(&[2u32, 0u32]);
     }
 
+    fn contents_values(mem: &Mem) -> Vec<u128> {
+        mem.initial_contents
+            .borrow()
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|x| x.numeric_value())
+            .collect()
+    }
+
+    /// A directory for the files written by a single test, which is removed along with its contents when it's dropped, even if the test fails.
+    struct TempDir(std::path::PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> TempDir {
+            let path =
+                std::env::temp_dir().join(format!("kaze_mem_test_{}_{}", std::process::id(), name));
+            std::fs::create_dir_all(&path).unwrap();
+            TempDir(path)
+        }
+
+        fn file(&self, name: &str, contents: &[u8]) -> std::path::PathBuf {
+            let path = self.0.join(name);
+            std::fs::write(&path, contents).unwrap();
+            path
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn initial_contents_iter() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+
+        // Exact length
+        let mem = m.mem("mem1", 2, 8);
+        mem.initial_contents_iter((1..5u32).map(|x| x * 0x11));
+        assert_eq!(contents_values(mem), vec![0x11, 0x22, 0x33, 0x44]);
+
+        // Short, zero-padded
+        let mem = m.mem("mem2", 2, 8);
+        mem.initial_contents_iter(vec![0xffu32]);
+        assert_eq!(contents_values(mem), vec![0xff, 0, 0, 0]);
+
+        // Empty, zero-padded
+        let mem = m.mem("mem3", 1, 1);
+        mem.initial_contents_iter(Vec::<bool>::new());
+        assert_eq!(contents_values(mem), vec![0, 0]);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Attempted to specify initial contents for memory \"mem\" in module \"A\" that contain more than 4 element(s), but this memory has 2 address bit(s), and contains 4 element(s)."
+    )]
+    fn initial_contents_iter_length_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let mem = m.mem("mem", 2, 8);
+
+        // Panic
+        mem.initial_contents_iter(0u32..);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Attempted to specify initial contents for memory \"mem\" in module \"A\", but this memory has an element width of 4 bit(s), and these initial contents specify element 1 with value 16 which requires 5 bit(s)."
+    )]
+    fn initial_contents_iter_element_bit_width_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let mem = m.mem("mem", 2, 4);
+
+        // Panic
+        mem.initial_contents_iter(vec![15u32, 16u32]);
+    }
+
+    #[test]
+    fn initial_contents_from_hex_file() {
+        let dir = TempDir::new("initial_contents_from_hex_file");
+
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+
+        // Exact length, with comments and separators
+        let path = dir.file(
+            "exact",
+            b"// Header\ndead_beef 00000013\n  ffffffff // Trailing comment\n\n1\n",
+        );
+        let mem = m.mem("mem1", 2, 32);
+        mem.initial_contents_from_hex_file(&path).unwrap();
+        assert_eq!(
+            contents_values(mem),
+            vec![0xdeadbeef, 0x00000013, 0xffffffff, 1]
+        );
+
+        // Short, zero-padded
+        let path = dir.file("short", b"AB\ncd");
+        let mem = m.mem("mem2", 2, 8);
+        mem.initial_contents_from_hex_file(&path).unwrap();
+        assert_eq!(contents_values(mem), vec![0xab, 0xcd, 0, 0]);
+
+        // Oversized
+        let path = dir.file("oversized", b"1 2 3 4 5");
+        let mem = m.mem("mem3", 2, 8);
+        let error = mem.initial_contents_from_hex_file(&path).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(
+            error.to_string(),
+            format!("\"{}\" contains 5 element(s), but memory \"mem3\" in module \"A\" has 2 address bit(s), and contains 4 element(s).", path.display())
+        );
+        assert!(mem.initial_contents.borrow().is_none());
+
+        // Value too wide
+        let path = dir.file("too_wide", b"ff 100");
+        let mem = m.mem("mem4", 2, 8);
+        let error = mem.initial_contents_from_hex_file(&path).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(
+            error.to_string(),
+            format!("\"{}\" specifies element 1 with value 256 which requires 9 bit(s), but memory \"mem4\" in module \"A\" has an element width of 8 bit(s).", path.display())
+        );
+
+        // Invalid values
+        for (i, &(contents, word)) in [
+            (&b"12\n0x34"[..], "0x34"),
+            (b"12\n+34", "+34"),
+            (b"12\n3g", "3g"),
+        ]
+        .iter()
+        .enumerate()
+        {
+            let path = dir.file(&format!("invalid_{}", i), contents);
+            let mem = m.mem(format!("mem_invalid_{}", i), 2, 8);
+            let error = mem.initial_contents_from_hex_file(&path).unwrap_err();
+            assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+            assert_eq!(
+                error.to_string(),
+                format!(
+                    "Cannot parse \"{}\" on line 2 of \"{}\" as a hexadecimal value.",
+                    word,
+                    path.display()
+                )
+            );
+        }
+
+        // Missing file
+        let mem = m.mem("mem5", 2, 8);
+        let error = mem
+            .initial_contents_from_hex_file(dir.0.join("missing"))
+            .unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn initial_contents_from_bin_file() {
+        let dir = TempDir::new("initial_contents_from_bin_file");
+
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+
+        // Exact length
+        let path = dir.file("exact", &[0x13, 0x00, 0x00, 0x00, 0xef, 0xbe, 0xad, 0xde]);
+        let mem = m.mem("mem1", 1, 32);
+        mem.initial_contents_from_bin_file(&path, 4).unwrap();
+        assert_eq!(contents_values(mem), vec![0x00000013, 0xdeadbeef]);
+
+        // Short, zero-padded
+        let path = dir.file("short", &[0x34, 0x12]);
+        let mem = m.mem("mem2", 2, 16);
+        mem.initial_contents_from_bin_file(&path, 2).unwrap();
+        assert_eq!(contents_values(mem), vec![0x1234, 0, 0, 0]);
+
+        // Oversized
+        let path = dir.file("oversized", &[1, 2, 3]);
+        let mem = m.mem("mem3", 1, 8);
+        let error = mem.initial_contents_from_bin_file(&path, 1).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(
+            error.to_string(),
+            format!("\"{}\" contains 3 element(s), but memory \"mem3\" in module \"A\" has 1 address bit(s), and contains 2 element(s).", path.display())
+        );
+
+        // Value too wide
+        let path = dir.file("too_wide", &[0xff, 0x0f, 0x00, 0x10]);
+        let mem = m.mem("mem4", 1, 12);
+        let error = mem.initial_contents_from_bin_file(&path, 2).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(
+            error.to_string(),
+            format!("\"{}\" specifies element 1 with value 4096 which requires 13 bit(s), but memory \"mem4\" in module \"A\" has an element width of 12 bit(s).", path.display())
+        );
+
+        // Length not a multiple of the element size
+        let path = dir.file("length", &[1, 2, 3]);
+        let mem = m.mem("mem5", 1, 16);
+        let error = mem.initial_contents_from_bin_file(&path, 2).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(
+            error.to_string(),
+            format!("The length of \"{}\" (3 byte(s)) is not a multiple of the element size (2 byte(s)).", path.display())
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Attempted to specify initial contents for memory \"mem\" in module \"A\" with 0 byte(s) per element, but elements must be between 1 and 16 bytes."
+    )]
+    fn initial_contents_from_bin_file_element_bytes_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let mem = m.mem("mem", 1, 8);
+
+        // Panic
+        let _ = mem.initial_contents_from_bin_file("unused.bin", 0);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Attempted to specify initial contents for memory \"mem\" in module \"A\", but this memory already has initial contents."
+    )]
+    fn initial_contents_from_hex_file_already_specified_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let mem = m.mem("mem", 1, 1);
+
+        mem.initial_contents(&[true, false]);
+
+        // Panic
+        let _ = mem.initial_contents_from_hex_file("unused.hex");
+    }
+
     #[test]
     #[should_panic(
         expected = "Attempted to specify a read port for memory \"mem\" in module \"A\" with an address signal with 2 bit(s), but this memory has 1 address bit(s)."
@@ -305,6 +879,34 @@ mod tests {
         let _ = mem.read_port(m.low(), m.lit(0u32, 2));
     }
 
+    #[test]
+    #[should_panic(
+        expected = "Attempted to specify a read port for memory \"mem\" in module \"A\" with an address signal with 2 bit(s), but this memory has 1 address bit(s)."
+    )]
+    fn read_port_always_address_bit_width_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let mem = m.mem("mem", 1, 1);
+
+        // Panic
+        let _ = mem.read_port_always(m.lit(0u32, 2));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Attempted to specify a combinational read port for memory \"mem\" in module \"A\" with an address signal with 2 bit(s), but this memory has 1 address bit(s)."
+    )]
+    fn read_port_comb_address_bit_width_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let mem = m.mem("mem", 1, 1);
+
+        // Panic
+        let _ = mem.read_port_comb(m.lit(0u32, 2));
+    }
+
     #[test]
     #[should_panic(
         expected = "Attempted to specify a write port for memory \"mem\" in module \"A\", but this memory already has a write port."
@@ -362,4 +964,62 @@ mod tests {
         // Panic
         mem.write_port(m.low(), m.low(), m.lit(0u32, 2));
     }
+
+    #[test]
+    #[should_panic(
+        expected = "Attempted to specify clocks for memory \"mem\" in module \"A\", but this memory already has clocks specified."
+    )]
+    fn dual_clock_already_specified_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let mem = m.mem("mem", 1, 1);
+
+        mem.dual_clock("read_clk", "write_clk");
+
+        // Panic
+        mem.dual_clock("read_clk", "write_clk");
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Attempted to specify a clock called \"0clk\" for memory \"mem\" in module \"A\". Clock names must be non-empty, consist only of ASCII alphanumeric characters and underscores, must not start with a digit, and must not be Verilog or SystemVerilog keywords."
+    )]
+    fn dual_clock_invalid_name_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let mem = m.mem("mem", 1, 1);
+
+        // Panic
+        mem.dual_clock("read_clk", "0clk");
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Attempted to specify a clock called \"always\" for memory \"mem\" in module \"A\". Clock names must be non-empty, consist only of ASCII alphanumeric characters and underscores, must not start with a digit, and must not be Verilog or SystemVerilog keywords."
+    )]
+    fn dual_clock_keyword_name_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let mem = m.mem("mem", 1, 1);
+
+        // Panic
+        mem.dual_clock("always", "write_clk");
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Attempted to specify clocks for memory \"mem\" in module \"A\", but the read and write clocks are both called \"mem_clk\". The read and write clocks must be different."
+    )]
+    fn dual_clock_same_names_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let mem = m.mem("mem", 1, 1);
+
+        // Panic
+        mem.dual_clock("mem_clk", "mem_clk");
+    }
 }
@@ -1,12 +1,23 @@
+use super::attribute::*;
+use super::bundle::*;
+use super::clock_divider::*;
 use super::constant::*;
 use super::context::*;
+use super::deferred::*;
+use super::inout::*;
 use super::internal_signal::*;
 use super::mem::*;
+use super::module_declaration::*;
 use super::register::*;
+use super::reset_domain::*;
 use super::signal::*;
+use super::signal_vec::*;
 
-use std::cell::RefCell;
-use std::collections::BTreeMap;
+use crate::identifiers::{is_clock_or_reset_name, is_identifier};
+
+use std::cell::{Cell, RefCell};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap, HashMap};
 use std::hash::{Hash, Hasher};
 use std::ptr;
 
@@ -14,7 +25,7 @@ use std::ptr;
 ///
 /// Once a `Module` is specified, it can be [instantiated](Self::instance) in another `Module` to form a hierarchy, or it can be used to generate [Rust simulator code](crate::sim::generate) or a [Verilog module](crate::verilog::generate).
 ///
-/// All `Module`s in kaze have an implicit reset and clock. These are only visible in generated code. It's assumed that all kaze modules operate in the same clock domain.
+/// All `Module`s in kaze have an implicit reset and clock. These are only visible in generated code. It's assumed that all kaze modules operate in the same clock domain, with the exception of [dual-clock](Mem::dual_clock) memories and registers created with [`reg_in_domain`](Self::reg_in_domain).
 ///
 /// # Examples
 ///
@@ -40,9 +51,21 @@ pub struct Module<'a> {
     // TODO: Do we need to duplicate the input/output names here?
     pub(crate) inputs: RefCell<BTreeMap<String, &'a Input<'a>>>,
     pub(crate) outputs: RefCell<BTreeMap<String, &'a Output<'a>>>,
-    pub(crate) registers: RefCell<Vec<&'a InternalSignal<'a>>>,
+    pub(crate) inouts: RefCell<BTreeMap<String, &'a InOut<'a>>>,
+    pub(crate) registers: RefCell<Vec<&'a Register<'a>>>,
     pub(crate) modules: RefCell<Vec<&'a Module<'a>>>,
     pub(crate) mems: RefCell<Vec<&'a Mem<'a>>>,
+    pub(crate) reset_domains: RefCell<Vec<&'a ResetDomain<'a>>>,
+    pub(crate) clock_dividers: RefCell<Vec<&'a ClockDivider<'a>>>,
+    pub(crate) named_wires: RefCell<BTreeMap<String, &'a InternalSignal<'a>>>,
+    pub(crate) assertions: RefCell<BTreeMap<String, AssertionData<'a>>>,
+    pub(crate) cover_points: RefCell<BTreeMap<String, &'a InternalSignal<'a>>>,
+    pub(crate) deferred_registers: RefCell<Vec<&'a DeferredRegisterData<'a>>>,
+    pub(crate) deferred_outputs: RefCell<Vec<&'a DeferredOutputData<'a>>>,
+
+    pub(crate) declaration: Cell<Option<&'a ModuleDeclarationData>>,
+
+    finalized: Cell<bool>,
 }
 
 impl<'a> Module<'a> {
@@ -62,15 +85,27 @@ impl<'a> Module<'a> {
 
             inputs: RefCell::new(BTreeMap::new()),
             outputs: RefCell::new(BTreeMap::new()),
+            inouts: RefCell::new(BTreeMap::new()),
             registers: RefCell::new(Vec::new()),
             modules: RefCell::new(Vec::new()),
             mems: RefCell::new(Vec::new()),
+            reset_domains: RefCell::new(Vec::new()),
+            clock_dividers: RefCell::new(Vec::new()),
+            named_wires: RefCell::new(BTreeMap::new()),
+            assertions: RefCell::new(BTreeMap::new()),
+            cover_points: RefCell::new(BTreeMap::new()),
+            deferred_registers: RefCell::new(Vec::new()),
+            deferred_outputs: RefCell::new(Vec::new()),
+
+            declaration: Cell::new(None),
+
+            finalized: Cell::new(false),
         }
     }
 
     /// Creates a [`Signal`] that represents the constant literal specified by `value` with `bit_width` bits.
     ///
-    /// The bit width of the type provided by `value` doesn't need to match `bit_width`, but the value represented by `value` must fit into `bit_width` bits.
+    /// The bit width of the type provided by `value` doesn't need to match `bit_width`, but the value represented by `value` must fit into `bit_width` bits. Signed values are represented by their two's complement bit patterns, so `m.lit(-1i32, 8)` is equivalent to `m.lit(0xffu32, 8)`; see [`Constant`] for the exact rules.
     ///
     /// # Panics
     ///
@@ -88,6 +123,7 @@ impl<'a> Module<'a> {
     /// let eight_bit_const = m.lit(0xffu32, 8);
     /// let one_bit_const = m.lit(0u32, 1);
     /// let twenty_seven_bit_const = m.lit(true, 27);
+    /// let minus_one = m.lit(-1i8, 4); // Equivalent to m.lit(0xfu32, 4)
     /// ```
     pub fn lit(&'a self, value: impl Into<Constant>, bit_width: u32) -> &dyn Signal<'a> {
         if bit_width < MIN_SIGNAL_BIT_WIDTH {
@@ -105,17 +141,90 @@ impl<'a> Module<'a> {
         let value = value.into();
         let required_bits = value.required_bits();
         if required_bits > bit_width {
-            let numeric_value = value.numeric_value();
-            panic!("Cannot fit the specified value '{}' into the specified bit width '{}'. The value '{}' requires a bit width of at least {} bit(s).", numeric_value, bit_width, numeric_value, required_bits);
+            let value = value.describe();
+            panic!("Cannot fit the specified value '{}' into the specified bit width '{}'. The value '{}' requires a bit width of at least {} bit(s).", value, bit_width, value, required_bits);
         }
         self.context.signal_arena.alloc(InternalSignal {
             context: self.context,
             module: self,
 
-            data: SignalData::Lit { value, bit_width },
+            data: SignalData::Lit {
+                value: value.to_bit_pattern(bit_width),
+                bit_width,
+            },
         })
     }
 
+    /// Creates a [`Signal`] that represents the constant literal specified by parsing `s` as an unsigned integer in base `radix` with `bit_width` bits.
+    ///
+    /// This is shorthand for `m.lit(Constant::from_str_radix(s, radix, bit_width), bit_width)`; see [`Constant::from_str_radix`] for the accepted format.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as [`Constant::from_str_radix`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let key = m.lit_from_str("2b7e1516_28aed2a6_abf71588_09cf4f3c", 16, 128);
+    /// let mask = m.lit_from_str("1111_0000", 2, 8);
+    /// ```
+    pub fn lit_from_str(&'a self, s: &str, radix: u32, bit_width: u32) -> &'a dyn Signal<'a> {
+        self.lit(Constant::from_str_radix(s, radix, bit_width), bit_width)
+    }
+
+    /// Creates a [`Signal`] that represents the constant literal specified by `bytes` in little-endian order (least significant byte first) with `bit_width` bits.
+    ///
+    /// This is shorthand for `m.lit(Constant::from_bytes_le(bytes, bit_width), bit_width)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as [`Constant::from_bytes_le`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let magic = m.lit_from_bytes_le(b"kaze", 32); // 0x657a616b
+    /// ```
+    pub fn lit_from_bytes_le(&'a self, bytes: &[u8], bit_width: u32) -> &'a dyn Signal<'a> {
+        self.lit(Constant::from_bytes_le(bytes, bit_width), bit_width)
+    }
+
+    /// Creates a [`Signal`] that represents the constant literal specified by `bytes` in big-endian order (most significant byte first) with `bit_width` bits.
+    ///
+    /// This is shorthand for `m.lit(Constant::from_bytes_be(bytes, bit_width), bit_width)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as [`Constant::from_bytes_be`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let magic = m.lit_from_bytes_be(b"kaze", 32); // 0x6b617a65
+    /// ```
+    pub fn lit_from_bytes_be(&'a self, bytes: &[u8], bit_width: u32) -> &'a dyn Signal<'a> {
+        self.lit(Constant::from_bytes_be(bytes, bit_width), bit_width)
+    }
+
     /// Convenience method to create a [`Signal`] that represents a single `0` bit.
     ///
     /// # Examples
@@ -158,7 +267,7 @@ impl<'a> Module<'a> {
     ///
     /// # Panics
     ///
-    /// Panics if `bit_width` is less than [`MIN_SIGNAL_BIT_WIDTH`] or greater than [`MAX_SIGNAL_BIT_WIDTH`], respectively.
+    /// Panics if `bit_width` is less than [`MIN_SIGNAL_BIT_WIDTH`] or greater than [`MAX_SIGNAL_BIT_WIDTH`], respectively, or if this `Module` already contains an input or output called `name`.
     ///
     /// # Examples
     ///
@@ -173,7 +282,7 @@ impl<'a> Module<'a> {
     /// ```
     pub fn input(&'a self, name: impl Into<String>, bit_width: u32) -> &Input<'a> {
         let name = name.into();
-        // TODO: Error if name already exists in this context
+        self.validate_not_finalized("an input", &name);
         if bit_width < MIN_SIGNAL_BIT_WIDTH {
             panic!(
                 "Cannot create an input with {} bit(s). Signals must not be narrower than {} bit(s).",
@@ -186,10 +295,12 @@ impl<'a> Module<'a> {
                 bit_width, MAX_SIGNAL_BIT_WIDTH
             );
         }
+        self.validate_unique_port_name("an input", &name, bit_width);
         let data = self.context.input_data_arena.alloc(InputData {
             name: name.clone(),
             bit_width,
             driven_value: RefCell::new(None),
+            attributes: RefCell::new(Vec::new()),
         });
         let value = self.context.signal_arena.alloc(InternalSignal {
             context: self.context,
@@ -211,7 +322,7 @@ impl<'a> Module<'a> {
     ///
     /// # Panics
     ///
-    /// Panics of `source` doesn't belong to this `Module`.
+    /// Panics if `source` doesn't belong to this `Module`, or if this `Module` already contains an input or output called `name`.
     ///
     /// # Examples
     ///
@@ -226,29 +337,19 @@ impl<'a> Module<'a> {
     /// m.output("my_output", some_signal);
     /// ```
     pub fn output(&'a self, name: impl Into<String>, source: &'a dyn Signal<'a>) -> &Output<'a> {
-        let name = name.into();
-        let source = source.internal_signal();
-        if !ptr::eq(self, source.module) {
-            panic!("Cannot output a signal from another module.");
-        }
-        // TODO: Error if name already exists in this context
-        let data = self.context.output_data_arena.alloc(OutputData {
-            module: self,
-
-            name: name.clone(),
-            source,
-            bit_width: source.bit_width(),
-        });
-        let output = self.context.output_arena.alloc(Output { data });
-        self.outputs.borrow_mut().insert(name, output);
-        output
+        self.create_output(name.into(), source, None)
     }
 
-    /// Creates a [`Register`] in this `Module` called `name` with `bit_width` bits.
+    /// Creates an output for this `Module` called `name` like [`output`](Self::output), and records that this output's value is unspecified ("don't care") whenever `dont_care_cond` is high.
+    ///
+    /// This is meant for outputs that are meaningless under some configurations or in some states (eg. debug data when debugging is disabled), but must still be driven with something.
+    /// When optimization is enabled in [`sim::GenerationOptions`](crate::sim::GenerationOptions::optimize) or [`verilog::GenerationOptions`](crate::verilog::GenerationOptions::optimize), the logic driving this output may be simplified under this assumption: if `dont_care_cond` is constant high, the output is driven with `0`, and if `source` is a [`mux`](Signal::mux) whose condition is `dont_care_cond` (or its inverse), the output is driven directly by the arm that's selected while `dont_care_cond` is low.
+    ///
+    /// Otherwise, generated code drives this output with `source`, just like [`output`](Self::output), unless the simulator is generated with [`poison_dont_care_outputs`](crate::sim::GenerationOptions::poison_dont_care_outputs), which makes it drive the inverse of `source` while `dont_care_cond` is high, so that logic that (illegally) depends on this output's value during these cycles can be caught in tests.
     ///
     /// # Panics
     ///
-    /// Panics if `bit_width` is less than [`MIN_SIGNAL_BIT_WIDTH`] or greater than [`MAX_SIGNAL_BIT_WIDTH`], respectively.
+    /// Panics if `source` or `dont_care_cond` don't belong to this `Module`, if `dont_care_cond` isn't 1 bit wide, or if this `Module` already contains an input or output called `name`.
     ///
     /// # Examples
     ///
@@ -259,48 +360,40 @@ impl<'a> Module<'a> {
     ///
     /// let m = c.module("m", "MyModule");
     ///
-    /// let my_reg = m.reg("my_reg", 32);
-    /// my_reg.default_value(0xfadebabeu32); // Optional
-    /// my_reg.drive_next(!my_reg);
-    /// m.output("my_output", my_reg);
+    /// let debug_enable = m.input("debug_enable", 1);
+    /// let data = m.input("data", 32);
+    /// m.output_dont_care_when("debug_data", debug_enable.mux(data, m.lit(0u32, 32)), !debug_enable);
     /// ```
-    pub fn reg(&'a self, name: impl Into<String>, bit_width: u32) -> &Register<'a> {
-        // TODO: Error if name already exists in this context and update docs for Signal::reg_next and Signal::reg_next_with_default to reflect this
-        if bit_width < MIN_SIGNAL_BIT_WIDTH {
+    pub fn output_dont_care_when(
+        &'a self,
+        name: impl Into<String>,
+        source: &'a dyn Signal<'a>,
+        dont_care_cond: &'a dyn Signal<'a>,
+    ) -> &'a Output<'a> {
+        let name = name.into();
+        let dont_care_cond = dont_care_cond.internal_signal();
+        if !ptr::eq(self, dont_care_cond.module) {
             panic!(
-                "Cannot create a register with {} bit(s). Signals must not be narrower than {} bit(s).",
-                bit_width, MIN_SIGNAL_BIT_WIDTH
+                "Cannot create an output called \"{}\" in module \"{}\" with a don't care condition from another module.",
+                name, self.name
             );
         }
-        if bit_width > MAX_SIGNAL_BIT_WIDTH {
-            panic!(
-                "Cannot create a register with {} bit(s). Signals must not be wider than {} bit(s).",
-                bit_width, MAX_SIGNAL_BIT_WIDTH
-            );
+        if dont_care_cond.bit_width() != 1 {
+            panic!("Cannot create an output called \"{}\" in module \"{}\" with a don't care condition with {} bit(s). Don't care conditions must be 1 bit wide.", name, self.name, dont_care_cond.bit_width());
         }
-        let data = self.context.register_data_arena.alloc(RegisterData {
-            module: self,
-
-            name: name.into(),
-            initial_value: RefCell::new(None),
-            bit_width,
-            next: RefCell::new(None),
-        });
-        let value = self.context.signal_arena.alloc(InternalSignal {
-            context: self.context,
-            module: self,
-
-            data: SignalData::Reg { data },
-        });
-        self.registers.borrow_mut().push(value);
-        self.context.register_arena.alloc(Register { data, value })
+        self.create_output(name, source, Some(dont_care_cond))
     }
 
-    /// Creates a 2:1 [multiplexer](https://en.wikipedia.org/wiki/Multiplexer) that represents `when_true`'s value when `cond` is high, and `when_false`'s value when `cond` is low.
+    /// Creates a [`Register`] called `<name>_reg` in this `Module` with the same number of bits as `source`, drives its next value with `source`, and creates an output called `name` that's driven by the register, like [`output`](Self::output).
+    ///
+    /// If this `Module` already contains a register called `<name>_reg`, the register is called `<name>_reg_<n>` instead, where `n` is the smallest positive integer that makes its name unique.
+    ///
+    /// The output's value is therefore `source`'s value delayed by one cycle, which is a common way to keep the logic that drives an output from limiting the timing of the logic that uses it.
+    /// As with [`reg_next`](Signal::reg_next), the register has no default value, so its value is undefined until the first clock edge, and it isn't affected by this `Module`'s implicit reset. The register is traced under its name when tracing is enabled.
     ///
     /// # Panics
     ///
-    /// Panics if `cond`, `when_true`, or `when_false` belong to a different `Module` than `self`, if `cond`'s bit width is not 1, or if the bit widths of `when_true` and `when_false` aren't equal.
+    /// Panics if `source` doesn't belong to this `Module`, or if this `Module` already contains an input or output called `name`.
     ///
     /// # Examples
     ///
@@ -311,65 +404,105 @@ impl<'a> Module<'a> {
     ///
     /// let m = c.module("m", "MyModule");
     ///
-    /// let cond = m.input("cond", 1);
     /// let a = m.input("a", 8);
     /// let b = m.input("b", 8);
-    /// m.output("my_output", m.mux(cond, a, b)); // Outputs a when cond is high, b otherwise
+    /// m.output_registered("sum", a + b); // Driven by a register called "sum_reg"
     /// ```
-    pub fn mux(
+    pub fn output_registered(
         &'a self,
-        cond: &'a dyn Signal<'a>,
-        when_true: &'a dyn Signal<'a>,
-        when_false: &'a dyn Signal<'a>,
-    ) -> &dyn Signal<'a> {
-        let cond = cond.internal_signal();
-        let when_true = when_true.internal_signal();
-        let when_false = when_false.internal_signal();
-
-        // TODO: This is an optimization to support sugar; if that doesn't go well, remove this
-        if when_true == when_false {
-            return when_true;
+        name: impl Into<String>,
+        source: &'a dyn Signal<'a>,
+    ) -> &'a Output<'a> {
+        let name = name.into();
+        self.validate_not_finalized("an output", &name);
+        if !ptr::eq(self, source.internal_signal().module) {
+            panic!("Cannot output a signal from another module.");
         }
+        self.validate_unique_port_name("an output", &name, source.bit_width());
+        let register = self.reg(self.unique_register_name(&name, "reg"), source.bit_width());
+        register.drive_next(source);
+        self.create_output(name, register, None)
+    }
 
-        if !ptr::eq(self, cond.module) {
-            panic!("Attempted to combine signals from different modules.");
-        }
-        if !ptr::eq(self, when_true.module) {
-            panic!("Attempted to combine signals from different modules.");
-        }
-        if !ptr::eq(self, when_false.module) {
-            panic!("Attempted to combine signals from different modules.");
-        }
-        if cond.bit_width() != 1 {
-            panic!("Multiplexer conditionals can only be 1 bit wide.");
-        }
-        if when_true.bit_width() != when_false.bit_width() {
-            panic!(
-                "Cannot multiplex signals with different bit widths ({} and {}, respectively).",
-                when_true.bit_width(),
-                when_false.bit_width()
+    /// Delays each output that this `Module` contains so far by one cycle, by creating a [`Register`] called `<name>_reg` for each output called `name` (named uniquely like [`output_registered`](Self::output_registered)'s registers), which is driven by the output's source, and driving the output with the register instead.
+    ///
+    /// This applies to all outputs that were created before this method is called (including outputs created with [`output_registered`](Self::output_registered), which are then delayed by two cycles), but not to outputs that are created afterwards, or [`DeferredOutput`]s that haven't been driven yet.
+    /// Outputs created with [`output_dont_care_when`](Self::output_dont_care_when) get a second register called `<name>_dont_care_reg` (also named uniquely), which delays their don't care conditions by one cycle as well.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let a = m.input("a", 8);
+    /// let b = m.input("b", 8);
+    /// m.output("sum", a + b);
+    /// m.output("difference", a - b);
+    /// m.register_all_outputs(); // Both outputs are driven by registers ("sum_reg" and "difference_reg")
+    /// ```
+    pub fn register_all_outputs(&'a self) {
+        for (name, output) in self.outputs.borrow().iter() {
+            let source: &'a dyn Signal<'a> = output.data.source.get();
+            let register = self.reg(
+                self.unique_register_name(name, "reg"),
+                output.data.bit_width,
             );
+            register.drive_next(source);
+            output.data.source.set(register.value);
+            if let Some(dont_care_cond) = output.data.dont_care_when.get() {
+                let dont_care_cond: &'a dyn Signal<'a> = dont_care_cond;
+                let dont_care_register =
+                    self.reg(self.unique_register_name(name, "dont_care_reg"), 1);
+                dont_care_register.drive_next(dont_care_cond);
+                output
+                    .data
+                    .dont_care_when
+                    .set(Some(dont_care_register.value));
+            }
         }
-        self.context.signal_arena.alloc(InternalSignal {
-            context: self.context,
-            module: self,
+    }
 
-            data: SignalData::Mux {
-                cond,
-                when_true,
-                when_false,
-                bit_width: when_true.bit_width(),
-            },
-        })
+    /// Creates a [`DeferredOutput`] for this `Module` called `name`, whose bit width is inferred from the signal it's eventually driven with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let my_output = m.output_deferred("my_output");
+    /// my_output.drive(m.input("i", 8)); // Creates an 8-bit output
+    /// ```
+    pub fn output_deferred(&'a self, name: impl Into<String>) -> DeferredOutput<'a> {
+        let name = name.into();
+        self.validate_not_finalized("an output", &name);
+        let data = self
+            .context
+            .deferred_output_data_arena
+            .alloc(DeferredOutputData {
+                module: self,
+
+                name,
+                output: Cell::new(None),
+            });
+        self.deferred_outputs.borrow_mut().push(data);
+        DeferredOutput::new(data)
     }
 
-    /// Creates a [`Mem`] in this `Module` called `name` with `address_bit_width` address bits and `element_bit_width` element bits.
+    /// Creates an [`InOut`] (a bidirectional, tri-state port) for this `Module` called `name` with `bit_width` bits.
     ///
-    /// The size of this memory will be `1 << address_bit_width` elements, each `element_bit_width` bits wide.
+    /// In generated simulator code, an `InOut` is split into an input called `name` and two outputs created when it's [driven](InOut::drive), so `name` must not collide with any of this `Module`'s inputs or outputs. See [`InOut`] for details.
     ///
     /// # Panics
     ///
-    /// Panics if `address_bit_width` or `element_bit_width` is less than [`MIN_SIGNAL_BIT_WIDTH`] or greater than [`MAX_SIGNAL_BIT_WIDTH`], respectively.
+    /// Panics if this `Module` isn't a top-level module (`InOut`s model pads at chip boundaries, so they can't be used in instances), if `bit_width` is less than [`MIN_SIGNAL_BIT_WIDTH`] or greater than [`MAX_SIGNAL_BIT_WIDTH`], respectively, or if this `Module` already contains an input or output called `name`.
     ///
     /// # Examples
     ///
@@ -380,503 +513,3044 @@ impl<'a> Module<'a> {
     ///
     /// let m = c.module("m", "MyModule");
     ///
-    /// let my_mem = m.mem("my_mem", 1, 32);
-    /// // Optional, unless no write port is specified
-    /// my_mem.initial_contents(&[0xfadebabeu32, 0xdeadbeefu32]);
-    /// // Optional, unless no initial contents are specified
-    /// my_mem.write_port(m.high(), m.lit(0xabad1deau32, 32), m.high());
-    /// m.output("my_output", my_mem.read_port(m.high(), m.high()));
+    /// let data = m.inout("data", 8);
+    /// data.drive(m.lit(0xa5u32, 8), m.input("drive_data", 1));
+    /// m.output("data_in", data);
     /// ```
-    pub fn mem(
-        &'a self,
-        name: impl Into<String>,
-        address_bit_width: u32,
-        element_bit_width: u32,
-    ) -> &Mem<'a> {
-        // TODO: Error if name already exists in this context
-        if address_bit_width < MIN_SIGNAL_BIT_WIDTH {
-            panic!(
-                "Cannot create a memory with {} address bit(s). Signals must not be narrower than {} bit(s).",
-                address_bit_width, MIN_SIGNAL_BIT_WIDTH
-            );
-        }
-        if address_bit_width > MAX_SIGNAL_BIT_WIDTH {
-            panic!(
-                "Cannot create a memory with {} address bit(s). Signals must not be wider than {} bit(s).",
-                address_bit_width, MAX_SIGNAL_BIT_WIDTH
-            );
+    pub fn inout(&'a self, name: impl Into<String>, bit_width: u32) -> &'a InOut<'a> {
+        let name = name.into();
+        self.validate_not_finalized("an inout", &name);
+        if self.parent.is_some() {
+            panic!("Cannot create an inout called \"{}\" in module \"{}\", because inouts can only be created in top-level modules.", name, self.name);
         }
-        if element_bit_width < MIN_SIGNAL_BIT_WIDTH {
+        if bit_width < MIN_SIGNAL_BIT_WIDTH {
             panic!(
-                "Cannot create a memory with {} element bit(s). Signals must not be narrower than {} bit(s).",
-                element_bit_width, MIN_SIGNAL_BIT_WIDTH
+                "Cannot create an inout with {} bit(s). Signals must not be narrower than {} bit(s).",
+                bit_width, MIN_SIGNAL_BIT_WIDTH
             );
         }
-        if element_bit_width > MAX_SIGNAL_BIT_WIDTH {
+        if bit_width > MAX_SIGNAL_BIT_WIDTH {
             panic!(
-                "Cannot create a memory with {} element bit(s). Signals must not be wider than {} bit(s).",
-                element_bit_width, MAX_SIGNAL_BIT_WIDTH
+                "Cannot create an inout with {} bit(s). Signals must not be wider than {} bit(s).",
+                bit_width, MAX_SIGNAL_BIT_WIDTH
             );
         }
-        let ret = self.context.mem_arena.alloc(Mem {
-            context: self.context,
+        self.validate_unique_port_name("an inout", &name, bit_width);
+        let input = self.input(name.clone(), bit_width);
+        let inout = self.context.inout_arena.alloc(InOut {
             module: self,
 
-            name: name.into(),
-            address_bit_width,
-            element_bit_width,
-
-            initial_contents: RefCell::new(None),
-
-            read_ports: RefCell::new(Vec::new()),
-            write_port: RefCell::new(None),
+            name: name.clone(),
+            input,
+            outputs: RefCell::new(None),
         });
-        self.mems.borrow_mut().push(ret);
-        ret
+        self.inouts.borrow_mut().insert(name, inout);
+        inout
     }
-}
 
-impl<'a> ModuleParent<'a> for Module<'a> {
-    // TODO: Docs, error handling
-    fn module(&'a self, instance_name: impl Into<String>, name: impl Into<String>) -> &Module {
-        let instance_name = instance_name.into();
-        let name = name.into();
-        let module = self.context.module_arena.alloc(Module::new(
-            self.context,
-            Some(self),
-            instance_name,
-            name,
-        ));
-        self.modules.borrow_mut().push(module);
-        module
+    /// Returns this `Module`'s input called `name`.
+    ///
+    /// This is mostly useful for connecting instances of [declared](ModuleDeclaration) modules, whose inputs are created by their definitions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this `Module` doesn't contain an input called `name`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let my_input = m.input("my_input", 8);
+    /// assert!(std::ptr::eq(m.get_input("my_input"), my_input));
+    /// ```
+    pub fn get_input(&self, name: &str) -> &'a Input<'a> {
+        match self.inputs.borrow().get(name) {
+            Some(&input) => input,
+            None => panic!(
+                "Module \"{}\" doesn't contain an input called \"{}\".",
+                self.name, name
+            ),
+        }
     }
-}
 
-impl<'a> Eq for &'a Module<'a> {}
+    /// Returns this `Module`'s output called `name`.
+    ///
+    /// This is mostly useful for connecting instances of [declared](ModuleDeclaration) modules, whose outputs are created by their definitions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this `Module` doesn't contain an output called `name`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let my_output = m.output("my_output", m.high());
+    /// assert!(std::ptr::eq(m.get_output("my_output"), my_output));
+    /// ```
+    pub fn get_output(&self, name: &str) -> &'a Output<'a> {
+        match self.outputs.borrow().get(name) {
+            Some(&output) => output,
+            None => panic!(
+                "Module \"{}\" doesn't contain an output called \"{}\".",
+                self.name, name
+            ),
+        }
+    }
 
-impl<'a> Hash for &'a Module<'a> {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        state.write_usize(*self as *const _ as usize)
+    /// Returns this `Module`'s name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    /// assert_eq!(m.name(), "MyModule");
+    /// ```
+    pub fn name(&self) -> &str {
+        &self.name
     }
-}
 
-impl<'a> PartialEq for &'a Module<'a> {
-    fn eq(&self, other: &Self) -> bool {
-        ptr::eq(*self, *other)
+    /// Returns the name that identifies this `Module` instance in its parent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    /// assert_eq!(m.instance_name(), "m");
+    /// ```
+    pub fn instance_name(&self) -> &str {
+        &self.instance_name
     }
-}
 
-// TODO: Move?
-// TODO: Doc
-// TODO: bit width as const generic param?
-#[must_use]
-pub struct Input<'a> {
-    // TODO: Double-check that we need this (I think we need it for error checking in drive)
-    pub(crate) module: &'a Module<'a>,
+    /// Marks this `Module` as finalized, so that any later attempt to add inputs, outputs, registers, memories, instances, or other contents to it panics.
+    ///
+    /// Modules built with [`ModuleParent::module_with`] are finalized automatically once they're built. Finalizing a `Module` doesn't affect its instances, and doesn't prevent connecting its existing ports (eg. with [`Input::drive`]) or generating code for it.
+    ///
+    /// # Examples
+    ///
+    /// ```should_panic
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    /// m.output("o", m.input("i", 1));
+    /// m.finalize();
+    ///
+    /// let _ = m.input("j", 1); // Module is finalized, panic!
+    /// ```
+    pub fn finalize(&self) {
+        self.finalized.set(true);
+    }
 
-    pub(crate) data: &'a InputData<'a>,
-    pub(crate) value: &'a InternalSignal<'a>,
-}
+    /// Returns `true` if this `Module` has been [finalized](Self::finalize).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    /// assert!(!m.is_finalized());
+    ///
+    /// let n = c.module_with("n", "OtherModule", |n| {
+    ///     n.output("o", n.input("i", 1));
+    /// });
+    /// assert!(n.is_finalized());
+    /// ```
+    pub fn is_finalized(&self) -> bool {
+        self.finalized.get()
+    }
 
-impl<'a> Input<'a> {
-    // TODO: Doc
-    // TODO: Merge error cases with Instance::drive_input?
-    // TODO: Rename i?
-    pub fn drive(&'a self, i: &'a dyn Signal<'a>) {
-        let i = i.internal_signal();
-        // TODO: Change text from instance -> module in appropriate places?
-        if let Some(parent) = self.module.parent {
-            if !ptr::eq(parent, i.module) {
-                // TODO: Clarify?
-                panic!("Attempted to drive an instance input with a signal from a different module than that instance's parent module.");
-            }
-        } else {
-            // TODO: Proper panic + test!
-            panic!("OH NOES");
+    /// Returns an iterator over the [`Register`]s created in this `Module`, in the order they were created.
+    ///
+    /// This includes registers created by [`reg_in_domain`](Self::reg_in_domain), [`ClockDivider::reg`], and resolved [`DeferredRegister`]s, as well as the registers that [clock dividers](Self::clock_divider) use internally, but not registers in this `Module`'s [instances](Self::instances).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    /// let _ = m.reg("status", 8);
+    /// let _ = m.reg("control", 1);
+    ///
+    /// let registers = m
+    ///     .registers()
+    ///     .map(|r| (r.name().to_owned(), r.bit_width()))
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(registers, [("status".to_owned(), 8), ("control".to_owned(), 1)]);
+    /// ```
+    pub fn registers(&self) -> impl Iterator<Item = &'a Register<'a>> {
+        self.registers.borrow().clone().into_iter()
+    }
+
+    /// Returns an iterator over the [`Mem`]s created in this `Module`, in the order they were created.
+    ///
+    /// This doesn't include memories in this `Module`'s [instances](Self::instances).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    /// let _ = m.mem("palette", 4, 15);
+    ///
+    /// let mem = m.memories().next().unwrap();
+    /// assert_eq!(mem.name(), "palette");
+    /// assert_eq!(mem.address_bit_width(), 4);
+    /// assert_eq!(mem.element_bit_width(), 15);
+    /// ```
+    pub fn memories(&self) -> impl Iterator<Item = &'a Mem<'a>> {
+        self.mems.borrow().clone().into_iter()
+    }
+
+    /// Returns an iterator over this `Module`'s child instances, in the order they were created.
+    ///
+    /// Only direct children are included; their own instances can be enumerated by calling this method on them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    /// let _ = m.module("inner", "Inner");
+    ///
+    /// let instances = m
+    ///     .instances()
+    ///     .map(|i| (i.instance_name().to_owned(), i.name().to_owned()))
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(instances, [("inner".to_owned(), "Inner".to_owned())]);
+    /// ```
+    pub fn instances(&self) -> impl Iterator<Item = &'a Module<'a>> {
+        self.modules.borrow().clone().into_iter()
+    }
+
+    /// Returns an iterator over the names and bit widths of this `Module`'s inputs, in order of their names.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    /// let _ = m.input("valid", 1);
+    /// let _ = m.input("data", 32);
+    ///
+    /// assert_eq!(m.inputs().collect::<Vec<_>>(), [("data", 32), ("valid", 1)]);
+    /// ```
+    pub fn inputs(&self) -> impl Iterator<Item = (&'a str, u32)> {
+        self.inputs
+            .borrow()
+            .values()
+            .map(|i| (i.data.name.as_str(), i.data.bit_width))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    /// Returns an iterator over the names and bit widths of this `Module`'s outputs, in order of their names.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    /// let i = m.input("i", 8);
+    /// m.output("o", i);
+    /// m.output("is_zero", i.eq(m.lit(0u32, 8)));
+    ///
+    /// assert_eq!(m.outputs().collect::<Vec<_>>(), [("is_zero", 1), ("o", 8)]);
+    /// ```
+    pub fn outputs(&self) -> impl Iterator<Item = (&'a str, u32)> {
+        self.outputs
+            .borrow()
+            .values()
+            .map(|o| (o.data.name.as_str(), o.data.bit_width))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    /// Returns the minimum number of clock cycles it takes for a change in `from_input` to affect `to_output`, or `None` if `to_output` doesn't depend on `from_input` at all.
+    ///
+    /// This is the smallest number of registers on any path from `from_input` to `to_output`, following signals through this `Module`'s [instances](Self::instances). A purely combinational path has a latency of `0`.
+    /// Paths through a [`Mem`] count the registers implied by its ports: a [write](Mem::write_port) takes effect on the next clock edge, and a [synchronous read](Mem::read_port) takes another clock edge to produce its value, while a [combinational read](Mem::read_port_comb) doesn't add any latency.
+    ///
+    /// Each register counts as one cycle, regardless of which clock or [clock edge](Register::clock_edge) it's updated on. This doesn't affect code generation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `from_input` or `to_output` belongs to a different `Module` than `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    /// let i = m.input("i", 8);
+    /// let unused = m.input("unused", 8);
+    ///
+    /// let stage1 = (i + m.lit(1u32, 8)).reg_next("stage1");
+    /// let stage2 = (stage1 ^ i).reg_next("stage2");
+    /// let o = m.output("o", stage2);
+    ///
+    /// // The shortest path from i bypasses stage1
+    /// assert_eq!(m.latency(i, o), Some(1));
+    /// assert_eq!(m.latency(unused, o), None);
+    /// ```
+    pub fn latency(&'a self, from_input: &'a Input<'a>, to_output: &'a Output<'a>) -> Option<u32> {
+        if !ptr::eq(self, from_input.module) {
+            panic!("Attempted to query the latency from input \"{}\" in module \"{}\", but this input belongs to module \"{}\".", from_input.data.name, self.name, from_input.module.name);
         }
-        let mut driven_value = self.data.driven_value.borrow_mut();
-        if driven_value.is_some() {
-            panic!("Attempted to drive an input called \"{}\" on an instance of \"{}\", but this input is already driven for this instance.", self.data.name, self.module.name);
+        if !ptr::eq(self, to_output.data.module) {
+            panic!("Attempted to query the latency to output \"{}\" in module \"{}\", but this output belongs to module \"{}\".", to_output.data.name, self.name, to_output.data.module.name);
         }
-        if self.data.bit_width != i.bit_width() {
-            panic!("Attempted to drive an input called \"{}\" on an instance of \"{}\", but this input and the provided signal have different bit widths ({} and {}, respectively).", self.data.name, self.module.name, self.data.bit_width, i.bit_width());
+
+        // Dijkstra's algorithm, searching backwards from the output's source, where registers are the only edges with a nonzero cost.
+        //  The queue refers to signals by their indices in `signals`, since signals themselves aren't ordered.
+        let mut signals = vec![to_output.data.source.get()];
+        let mut latencies = HashMap::new();
+        latencies.insert(to_output.data.source.get() as *const _, 0);
+        let mut queue = BinaryHeap::new();
+        queue.push(Reverse((0, 0)));
+
+        while let Some(Reverse((latency, index))) = queue.pop() {
+            let signal = signals[index];
+            if latencies[&(signal as *const _)] < latency {
+                // Already reached with a lower latency
+                continue;
+            }
+
+            let mut sources = Vec::new();
+            match signal.data {
+                SignalData::Input { data } => {
+                    if ptr::eq(data, from_input.data) {
+                        return Some(latency);
+                    }
+                    if let Some(driven_value) = *data.driven_value.borrow() {
+                        sources.push((driven_value, 0));
+                    }
+                }
+                SignalData::Output { data } => sources.push((data.source.get(), 0)),
+                SignalData::Reg { data } => {
+                    if let Some(next) = *data.next.borrow() {
+                        sources.push((next, 1));
+                    }
+                }
+                SignalData::MemReadPortOutput {
+                    mem,
+                    address,
+                    enable,
+                } => {
+                    sources.push((address, 1));
+                    sources.push((enable, 1));
+                    if let Some((address, value, enable)) = *mem.write_port.borrow() {
+                        sources.extend_from_slice(&[(address, 2), (value, 2), (enable, 2)]);
+                    }
+                }
+                SignalData::MemCombReadPortOutput { mem, address } => {
+                    sources.push((address, 0));
+                    if let Some((address, value, enable)) = *mem.write_port.borrow() {
+                        sources.extend_from_slice(&[(address, 1), (value, 1), (enable, 1)]);
+                    }
+                }
+                _ => sources.extend(signal.operands().into_iter().map(|operand| (operand, 0))),
+            }
+
+            for (source, cost) in sources {
+                let latency = latency + cost;
+                let best = latencies.entry(source as *const _).or_insert(u32::MAX);
+                if latency < *best {
+                    *best = latency;
+                    signals.push(source);
+                    queue.push(Reverse((latency, signals.len() - 1)));
+                }
+            }
         }
-        *driven_value = Some(i);
-    }
-}
 
-impl<'a> GetInternalSignal<'a> for Input<'a> {
-    fn internal_signal(&'a self) -> &'a InternalSignal<'a> {
-        self.value
+        None
     }
-}
 
-impl<'a> GetInternalSignal<'a> for Output<'a> {
-    fn internal_signal(&'a self) -> &'a InternalSignal<'a> {
-        let parent = self.data.module.parent.expect("TODO better error pls");
-        self.data.module.context.signal_arena.alloc(InternalSignal {
-            context: self.data.module.context,
-            module: parent,
+    /// Creates an [`InputBundle`] for this `Module` called `name`, with an input called `<name>_<field name>` for each field in `schema`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let req = m.input_bundle("req", &BundleSchema::new().field("valid", 1).field("data", 32));
+    /// let valid = req.field("valid"); // Input called `req_valid`
+    /// ```
+    pub fn input_bundle(
+        &'a self,
+        name: impl Into<String>,
+        schema: &BundleSchema,
+    ) -> InputBundle<'a> {
+        let name = name.into();
+        let inputs = schema
+            .fields()
+            .iter()
+            .map(|(field_name, bit_width)| {
+                self.input(format!("{}_{}", name, field_name), *bit_width)
+            })
+            .collect();
+        InputBundle {
+            module: self,
+            name,
+            schema: schema.clone(),
+            inputs,
+        }
+    }
 
-            data: SignalData::Output { data: self.data },
-        })
+    /// Creates an [`OutputBundle`] for this `Module` called `name`, with an output called `<name>_<field name>` for each field in `schema`, driven by the field of the same name in `source`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `source` doesn't have exactly the same fields as `schema`, if any of `source`'s fields have a different bit width than specified in `schema`, or if any of `source`'s signals don't belong to this `Module`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let schema = BundleSchema::new().field("valid", 1).field("data", 32);
+    /// let req = m.input_bundle("req", &schema);
+    /// let resp = m.output_bundle("resp", &schema, &req.signals());
+    /// ```
+    pub fn output_bundle(
+        &'a self,
+        name: impl Into<String>,
+        schema: &BundleSchema,
+        source: &Bundle<'a>,
+    ) -> OutputBundle<'a> {
+        let name = name.into();
+        source.validate(
+            &format!(
+                "create output bundle \"{}\" in module \"{}\"",
+                name, self.name
+            ),
+            schema,
+            self,
+        );
+        let outputs = schema
+            .fields()
+            .iter()
+            .map(|(field_name, _)| {
+                self.output(format!("{}_{}", name, field_name), source.field(field_name))
+            })
+            .collect();
+        OutputBundle {
+            schema: schema.clone(),
+            outputs,
+        }
     }
-}
 
-pub(crate) struct InputData<'a> {
-    // TODO: Do we need this stored here too?
-    pub name: String,
-    pub bit_width: u32,
-    // TODO: Rename?
-    pub driven_value: RefCell<Option<&'a InternalSignal<'a>>>,
-}
+    /// Creates a named wire in this `Module` called `name`, driven by `source`, and returns a [`Signal`] that represents its value.
+    ///
+    /// The returned [`Signal`] is functionally identical to `source`, but generated Verilog code declares a wire called `name` for it with a `(* keep = "true" *)` attribute, instead of inlining its expression into the signals that use it.
+    /// This allows the signal to survive synthesis optimizations as a probe point with a recognizable name.
+    /// If this `Module` is flattened into a parent module, the wire's name is prefixed with the module's instance path to keep it unique.
+    /// Generated Rust simulator code assigns the wire's value to a local called `__wire_<instance path>_<name>` (with a `_<n>` suffix if another named wire's local would have the same name), and traces it under `name` when tracing is enabled.
+    ///
+    /// [`Named::named`] is a shorthand for this method.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `source` doesn't belong to this `Module`, or if this `Module` already contains an input, output, or named wire called `name`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let a = m.input("a", 8);
+    /// let b = m.input("b", 8);
+    /// let sum = m.named_wire("sum", a + b); // Emitted as `(* keep = "true" *) wire [7:0] sum;` in Verilog
+    /// m.output("o", sum ^ a);
+    /// ```
+    pub fn named_wire(
+        &'a self,
+        name: impl Into<String>,
+        source: &'a dyn Signal<'a>,
+    ) -> &'a dyn Signal<'a> {
+        let name = name.into();
+        self.validate_not_finalized("a named wire", &name);
+        let source = source.internal_signal();
+        if !ptr::eq(self, source.module) {
+            panic!("Cannot create a named wire from a signal from another module.");
+        }
+        if self.inputs.borrow().contains_key(&name)
+            || self.outputs.borrow().contains_key(&name)
+            || self.named_wires.borrow().contains_key(&name)
+        {
+            panic!("Cannot create a named wire called \"{}\" in module \"{}\", because this module already contains an input, output, or named wire with the same name.", name, self.name);
+        }
+        let wire = self.context.signal_arena.alloc(InternalSignal {
+            context: self.context,
+            module: self,
+
+            data: SignalData::NamedWire {
+                name: name.clone(),
+                source,
+            },
+        });
+        self.named_wires.borrow_mut().insert(name, wire);
+        wire
+    }
+
+    /// Creates an assertion in this `Module` called `name`, which checks that `cond` is high in every cycle.
+    ///
+    /// Assertions don't affect the behavior of generated code; they're meant for embedding invariants directly in a design, rather than duplicating them in test code.
+    /// Generated Rust simulator code has a `check_assertions(&self) -> Result<(), &'static str>` method, which returns the instance path of the first assertion whose condition is low (eg. `"m.inner.my_assertion"`), if any. Like outputs, conditions reflect the values computed by the last call to `prop`, so this method is typically called after each call to `prop`.
+    /// Generated Verilog code checks each assertion on each positive edge of the implicit clock while the implicit reset isn't asserted, with an `assert property` statement that calls `$fatal` when the assertion fails if the [language standard](crate::verilog::LanguageStandard) is SystemVerilog, or with an `always` block that calls `$display` and `$finish` otherwise.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cond` doesn't belong to this `Module`, if `cond`'s bit width isn't `1`, if `name` is not a valid identifier (it must be non-empty, consist only of ASCII alphanumeric characters and underscores, and not start with a digit), or if this `Module` already contains an assertion called `name`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let count = m.reg("count", 4);
+    /// count.default_value(0u32);
+    /// count.drive_next(if_(count.eq(m.lit(9u32, 4)), m.lit(0u32, 4)).else_(count + m.lit(1u32, 4)));
+    /// m.assert("count_in_range", count.lt(m.lit(10u32, 4)));
+    /// m.output("count", count);
+    /// ```
+    pub fn assert(&'a self, name: impl Into<String>, cond: &'a dyn Signal<'a>) {
+        self.add_assertion(name.into(), cond, None);
+    }
+
+    /// Creates an assertion in this `Module` called `name`, which checks that `cond` is high in every cycle, like [`assert`](Self::assert), but which is also checked automatically by generated simulator code and reported with `message` when it fails.
+    ///
+    /// Generated Rust simulator code checks the assertion at the end of each call to `prop`, and panics with a message containing the assertion's instance path and `message` if its condition is low. If [`sim::GenerationOptions::collect_assertion_failures`](crate::sim::GenerationOptions::collect_assertion_failures) is set, the message is instead pushed into the generated `assertion_failures: Vec<&'static str>` field, so that a test bench can inspect failures without unwinding. The assertion is also reported by `check_assertions`, like any other assertion.
+    /// Generated Verilog code checks the assertion like any other assertion, including `message` in the failure message. If the [language standard](crate::verilog::LanguageStandard) is Verilog-2001, an `assert property` statement is additionally emitted inside an `` `ifdef FORMAL `` block, so that formal verification tools can prove the assertion.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cond` doesn't belong to this `Module`, if `cond`'s bit width isn't `1`, if `name` is not a valid identifier (it must be non-empty, consist only of ASCII alphanumeric characters and underscores, and not start with a digit), or if this `Module` already contains an assertion called `name`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let count = m.reg("count", 4);
+    /// count.default_value(0u32);
+    /// let push = m.input("push", 1);
+    /// count.drive_next(if_(push, count + m.lit(1u32, 4)).else_(count));
+    /// m.assert_always("no_overflow", !(push & count.eq(m.lit(15u32, 4))), "FIFO overflowed");
+    /// m.output("count", count);
+    /// ```
+    pub fn assert_always(
+        &'a self,
+        name: impl Into<String>,
+        cond: &'a dyn Signal<'a>,
+        message: impl Into<String>,
+    ) {
+        self.add_assertion(name.into(), cond, Some(message.into()));
+    }
+
+    fn add_assertion(&'a self, name: String, cond: &'a dyn Signal<'a>, message: Option<String>) {
+        self.validate_not_finalized("an assertion", &name);
+        let cond = cond.internal_signal();
+        if !ptr::eq(self, cond.module) {
+            panic!("Cannot create an assertion called \"{}\" in module \"{}\" with a condition from another module.", name, self.name);
+        }
+        if cond.bit_width() != 1 {
+            panic!("Cannot create an assertion called \"{}\" in module \"{}\" with a condition with {} bit(s). Assertion conditions must be 1 bit wide.", name, self.name, cond.bit_width());
+        }
+        if !is_identifier(&name) {
+            panic!("Cannot create an assertion called \"{}\" in module \"{}\". Assertion names must be non-empty, consist only of ASCII alphanumeric characters and underscores, and must not start with a digit.", name, self.name);
+        }
+        if self.assertions.borrow().contains_key(&name) {
+            panic!("Cannot create an assertion called \"{}\" in module \"{}\", because this module already contains an assertion with the same name.", name, self.name);
+        }
+        self.assertions
+            .borrow_mut()
+            .insert(name, AssertionData { cond, message });
+    }
+
+    /// Creates a coverage point in this `Module` called `name`, which counts the number of times `cond` is high.
+    ///
+    /// Coverage points don't affect the behavior of generated code; they're meant for measuring how thoroughly a design is exercised by a test bench, for example by checking that interesting conditions were hit at least once.
+    /// Generated Rust simulator code has a counter for each coverage point, which is incremented by each call to `prop` in which its condition is high. The counters start at `0`, aren't affected by the reset methods, and can be cleared with the generated `reset_coverage(&mut self)` method. Unless [`no_std`](crate::sim::GenerationOptions::no_std) is enabled, the generated `coverage(&self) -> BTreeMap<&'static str, u64>` method returns the counters by the instance paths of their coverage points (eg. `"m.inner.my_cover_point"`), and the generated `coverage_report(&self) -> String` method formats them one per line, marking coverage points that haven't been hit, followed by the number of coverage points that have been hit.
+    /// Generated Verilog code has a `cover property` statement for each coverage point, which is sampled on each positive edge of the implicit clock while the implicit reset isn't asserted. If the [language standard](crate::verilog::LanguageStandard) is Verilog-2001, these statements are emitted inside an `` `ifdef FORMAL `` block, so that they're only visible to formal verification tools.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cond` doesn't belong to this `Module`, if `cond`'s bit width isn't `1`, if `name` is not a valid identifier (it must be non-empty, consist only of ASCII alphanumeric characters and underscores, and not start with a digit), or if this `Module` already contains a coverage point called `name`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let full = m.input("full", 1);
+    /// let push = m.input("push", 1);
+    /// m.cover("push_while_full", push & full);
+    /// ```
+    pub fn cover(&'a self, name: impl Into<String>, cond: &'a dyn Signal<'a>) {
+        let name = name.into();
+        self.validate_not_finalized("a coverage point", &name);
+        let cond = cond.internal_signal();
+        if !ptr::eq(self, cond.module) {
+            panic!("Cannot create a coverage point called \"{}\" in module \"{}\" with a condition from another module.", name, self.name);
+        }
+        if cond.bit_width() != 1 {
+            panic!("Cannot create a coverage point called \"{}\" in module \"{}\" with a condition with {} bit(s). Coverage point conditions must be 1 bit wide.", name, self.name, cond.bit_width());
+        }
+        if !is_identifier(&name) {
+            panic!("Cannot create a coverage point called \"{}\" in module \"{}\". Coverage point names must be non-empty, consist only of ASCII alphanumeric characters and underscores, and must not start with a digit.", name, self.name);
+        }
+        if self.cover_points.borrow().contains_key(&name) {
+            panic!("Cannot create a coverage point called \"{}\" in module \"{}\", because this module already contains a coverage point with the same name.", name, self.name);
+        }
+        self.cover_points.borrow_mut().insert(name, cond);
+    }
+
+    /// Creates a [`Register`] in this `Module` called `name` with `bit_width` bits.
+    ///
+    /// Registers don't share a namespace with this `Module`'s inputs and outputs, as generated code always refers to registers by unique, mangled names (eg. `__reg_m_my_reg_0` for a register called `my_reg` in a top-level module instance called `m`). This means a register can have the same name as another register or as the output it drives.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bit_width` is less than [`MIN_SIGNAL_BIT_WIDTH`] or greater than [`MAX_SIGNAL_BIT_WIDTH`], respectively.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let my_reg = m.reg("my_reg", 32);
+    /// my_reg.default_value(0xfadebabeu32); // Optional
+    /// my_reg.drive_next(!my_reg);
+    /// m.output("my_output", my_reg);
+    /// ```
+    pub fn reg(&'a self, name: impl Into<String>, bit_width: u32) -> &Register<'a> {
+        self.alloc_reg(name.into(), bit_width, None, None)
+    }
+
+    /// Creates a [`Register`] in this `Module` called `name` with `bit_width` bits, which is clocked by a clock called `clock` rather than by this `Module`'s implicit clock.
+    ///
+    /// Clocks are identified by their name; registers with the same clock name in different [`Module`]s in a hierarchy (as well as [dual-clock](Mem::dual_clock) memories using a clock with that name) share the same clock.
+    /// In generated Rust simulator code, each clock gets its own `posedge_<name>` method alongside `posedge_clk`, which only updates the registers and memory ports clocked by it, and in generated Verilog code, each clock gets its own `<name>` port alongside `clk`.
+    /// Combinational logic isn't affected by clocks, so a single `prop` method still updates all combinational signals.
+    ///
+    /// Resets are independent of clocks, so a register in a clock domain is still reset by its [`Module`]'s implicit reset (or a [`ResetDomain`], if it's assigned to one).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bit_width` is less than [`MIN_SIGNAL_BIT_WIDTH`] or greater than [`MAX_SIGNAL_BIT_WIDTH`], respectively, or if `clock` isn't a legal identifier (non-empty, consisting only of ASCII alphanumeric characters and underscores, not starting with a digit, and not a Verilog or SystemVerilog keyword).
+    /// Additionally, code generation panics if `clock` is the same as the implicit clock or reset's name, or the name of an input or output of any module in the hierarchy.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let fast_counter = m.reg_in_domain("fast_counter", 8, "clk_fast");
+    /// fast_counter.default_value(0u32);
+    /// fast_counter.drive_next(fast_counter + m.lit(1u32, 8));
+    /// m.output("fast_count", fast_counter);
+    /// ```
+    pub fn reg_in_domain(
+        &'a self,
+        name: impl Into<String>,
+        bit_width: u32,
+        clock: impl Into<String>,
+    ) -> &'a Register<'a> {
+        let name = name.into();
+        let clock = clock.into();
+        if !is_clock_or_reset_name(&clock) {
+            panic!("Cannot create a register called \"{}\" in module \"{}\" with a clock called \"{}\". Clock names must be non-empty, consist only of ASCII alphanumeric characters and underscores, must not start with a digit, and must not be Verilog or SystemVerilog keywords.", name, self.name, clock);
+        }
+        self.alloc_reg(name, bit_width, Some(clock), None)
+    }
+
+    pub(crate) fn instance_path(&self) -> String {
+        match self.parent {
+            Some(parent) => format!("{}.{}", parent.instance_path(), self.instance_name),
+            None => self.instance_name.clone(),
+        }
+    }
+
+    pub(super) fn alloc_reg(
+        &'a self,
+        name: String,
+        bit_width: u32,
+        clock: Option<String>,
+        divided_clock: Option<&'a ClockDivider<'a>>,
+    ) -> &'a Register<'a> {
+        self.validate_not_finalized("a register", &name);
+        if bit_width < MIN_SIGNAL_BIT_WIDTH {
+            panic!(
+                "Cannot create a register with {} bit(s). Signals must not be narrower than {} bit(s).",
+                bit_width, MIN_SIGNAL_BIT_WIDTH
+            );
+        }
+        if bit_width > MAX_SIGNAL_BIT_WIDTH {
+            panic!(
+                "Cannot create a register with {} bit(s). Signals must not be wider than {} bit(s).",
+                bit_width, MAX_SIGNAL_BIT_WIDTH
+            );
+        }
+        let data = self.context.register_data_arena.alloc(RegisterData {
+            module: self,
+
+            name,
+            initial_value: RefCell::new(None),
+            reset_domain: RefCell::new(None),
+            clock,
+            divided_clock,
+            edge: Cell::new(Edge::Pos),
+            bit_width,
+            next: RefCell::new(None),
+            attributes: RefCell::new(Vec::new()),
+        });
+        let value = self.context.signal_arena.alloc(InternalSignal {
+            context: self.context,
+            module: self,
+
+            data: SignalData::Reg { data },
+        });
+        let register = self.context.register_arena.alloc(Register { data, value });
+        self.registers.borrow_mut().push(register);
+        register
+    }
+
+    /// Creates a [`DeferredRegister`] in this `Module` called `name`, whose bit width is inferred from the first signal it's resolved with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let my_reg = m.reg_deferred("my_reg");
+    /// my_reg.drive_next(m.input("i", 32)); // Creates a 32-bit register
+    /// m.output("my_output", my_reg.register());
+    /// ```
+    pub fn reg_deferred(&'a self, name: impl Into<String>) -> DeferredRegister<'a> {
+        let name = name.into();
+        self.validate_not_finalized("a register", &name);
+        let data = self
+            .context
+            .deferred_register_data_arena
+            .alloc(DeferredRegisterData {
+                module: self,
+
+                name,
+                register: Cell::new(None),
+            });
+        self.deferred_registers.borrow_mut().push(data);
+        DeferredRegister::new(data)
+    }
+
+    /// Creates a [`ResetDomain`] in this `Module` called `name`.
+    ///
+    /// [`Register`]s in this `Module` can be assigned to the returned domain with [`Register::reset_domain`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is not a valid identifier (it must be non-empty, consist only of ASCII alphanumeric characters and underscores, and not start with a digit), or if this `Module` already contains a reset domain called `name`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let soft = m.reset_domain("soft");
+    ///
+    /// let my_reg = m.reg("my_reg", 32);
+    /// my_reg.default_value(0u32);
+    /// my_reg.reset_domain(soft);
+    /// my_reg.drive_next(!my_reg);
+    /// m.output("my_output", my_reg);
+    /// ```
+    pub fn reset_domain(&'a self, name: impl Into<String>) -> &'a ResetDomain<'a> {
+        let name = name.into();
+        self.validate_not_finalized("a reset domain", &name);
+        if !is_identifier(&name) {
+            panic!("Cannot create a reset domain called \"{}\" in module \"{}\". Reset domain names must be non-empty, consist only of ASCII alphanumeric characters and underscores, and must not start with a digit.", name, self.name);
+        }
+        if self.reset_domains.borrow().iter().any(|d| d.name == name) {
+            panic!("Cannot create a reset domain called \"{}\" in module \"{}\", because this module already contains a reset domain with the same name.", name, self.name);
+        }
+        let ret = self
+            .context
+            .reset_domain_arena
+            .alloc(ResetDomain { module: self, name });
+        self.reset_domains.borrow_mut().push(ret);
+        ret
+    }
+
+    /// Creates a [`ClockDivider`] in this `Module` called `name`, which divides this `Module`'s implicit clock by `divide_by`.
+    ///
+    /// [`Register`]s clocked by the divided clock are created with [`ClockDivider::reg`].
+    /// The divider is built from two [`Register`]s in this `Module`, which are reset by its implicit reset: a counter called `<name>_count`, and the divided clock itself, called `name`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `divide_by` is less than `2`, if `name` is not a valid identifier (it must be non-empty, consist only of ASCII alphanumeric characters and underscores, and not start with a digit), or if this `Module` already contains a clock divider called `name`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let div2 = m.clock_divider("div2", 2);
+    ///
+    /// let toggle = div2.reg("toggle", 1);
+    /// toggle.default_value(false);
+    /// toggle.drive_next(!toggle); // Toggles every other cycle
+    /// m.output("toggle", toggle);
+    /// ```
+    pub fn clock_divider(
+        &'a self,
+        name: impl Into<String>,
+        divide_by: u32,
+    ) -> &'a ClockDivider<'a> {
+        let name = name.into();
+        self.validate_not_finalized("a clock divider", &name);
+        if divide_by < 2 {
+            panic!("Cannot create a clock divider called \"{}\" in module \"{}\" which divides by {}. Clock dividers must divide by at least 2.", name, self.name, divide_by);
+        }
+        if !is_identifier(&name) {
+            panic!("Cannot create a clock divider called \"{}\" in module \"{}\". Clock divider names must be non-empty, consist only of ASCII alphanumeric characters and underscores, and must not start with a digit.", name, self.name);
+        }
+        if self.clock_dividers.borrow().iter().any(|d| d.name == name) {
+            panic!("Cannot create a clock divider called \"{}\" in module \"{}\", because this module already contains a clock divider with the same name.", name, self.name);
+        }
+
+        let last = divide_by - 1;
+        let count_bit_width = 32 - last.leading_zeros();
+        let count = self.reg(format!("{}_count", name), count_bit_width);
+        // Starting at the end of a period makes the first positive edge of the divided clock come with the first positive edge after reset
+        count.default_value(last);
+        let enable = count.eq(self.lit(last, count_bit_width));
+        let count_next = enable.mux(
+            self.lit(0u32, count_bit_width),
+            count + self.lit(1u32, count_bit_width),
+        );
+        count.drive_next(count_next);
+
+        let clock = self.reg(name.clone(), 1);
+        clock.default_value(false);
+        clock.drive_next(count_next.lt(self.lit(divide_by / 2, count_bit_width)));
+        add_attribute(
+            &clock.data.attributes,
+            RESERVED_ATTRIBUTE_NAMESPACE.into(),
+            "generated_clock".into(),
+            format!("divide_by_{}", divide_by),
+            || unreachable!(),
+        );
+
+        let ret = self.context.clock_divider_arena.alloc(ClockDivider {
+            module: self,
+
+            name,
+            divide_by,
+            clock,
+            enable: enable.internal_signal(),
+        });
+        self.clock_dividers.borrow_mut().push(ret);
+        ret
+    }
+
+    /// Attaches an [`Attribute`] with the given `namespace`, `key`, and `value` to every [`Register`] that currently exists in this `Module`, as if by calling [`Register::attribute`] on each of them.
+    ///
+    /// [`Register`]s in child modules, as well as [`Register`]s created in this `Module` after this call, are not affected.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `namespace` is [`RESERVED_ATTRIBUTE_NAMESPACE`], or if `namespace` or `key` is not a valid identifier (it must be non-empty, consist only of ASCII alphanumeric characters and underscores, and not start with a digit).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let sync_0 = m.reg("sync_0", 1);
+    /// sync_0.drive_next(m.input("i", 1));
+    /// let sync_1 = m.reg("sync_1", 1);
+    /// sync_1.drive_next(sync_0);
+    /// m.output("o", sync_1);
+    ///
+    /// m.apply_attribute_to_registers("xilinx", "async_reg", "true");
+    /// ```
+    pub fn apply_attribute_to_registers(
+        &'a self,
+        namespace: impl Into<String>,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) {
+        let namespace = namespace.into();
+        let key = key.into();
+        let value = value.into();
+        for register in self.registers.borrow().iter() {
+            let data = register.data;
+            add_user_attribute(
+                &data.attributes,
+                namespace.clone(),
+                key.clone(),
+                value.clone(),
+                || format!("register \"{}\" in module \"{}\"", data.name, self.name),
+            );
+        }
+    }
+
+    /// Creates a [`Signal`] that represents all of the `Signal`s in `signals` concatenated together.
+    ///
+    /// `signals` is ordered MSB-first: `signals[0]` represents the upper bits in the resulting `Signal`, and the last element represents the lower bits.
+    /// This is equivalent to a left fold of [`concat`](Signal::concat) over `signals`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `signals` is empty, if any of `signals` belong to a different `Module` than `self`, or if the sum of their bit widths is greater than [`MAX_SIGNAL_BIT_WIDTH`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let opcode = m.input("opcode", 7);
+    /// let rd = m.input("rd", 5);
+    /// let funct3 = m.input("funct3", 3);
+    /// let rs1 = m.input("rs1", 5);
+    /// let imm = m.input("imm", 12);
+    /// let insn = m.concat_all(&[imm, rs1, funct3, rd, opcode]); // 32 bits, with imm in the upper bits
+    /// ```
+    pub fn concat_all(&'a self, signals: &[&'a dyn Signal<'a>]) -> &'a dyn Signal<'a> {
+        if signals.is_empty() {
+            panic!("Attempted to concatenate an empty list of signals.");
+        }
+        let mut bit_width = 0;
+        for (i, signal) in signals.iter().enumerate() {
+            let signal = signal.internal_signal();
+            check_same_module(
+                "Module::concat_all",
+                ("self", self),
+                (&format!("signals[{}]", i), signal.module),
+            );
+            bit_width += signal.bit_width();
+        }
+        if bit_width > MAX_SIGNAL_BIT_WIDTH {
+            panic!("Attempted to concatenate signals with {} bit(s) in total, which is greater than the maximum signal bit width of {} bit(s).", bit_width, MAX_SIGNAL_BIT_WIDTH);
+        }
+        signals[1..]
+            .iter()
+            .fold(signals[0], |acc, &signal| acc.concat(signal))
+    }
+
+    /// Creates a [`SignalVec`] in this `Module` with the elements in `signals`, in order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `signals` is empty, if any element belongs to a different `Module` than `self`, or if the bit widths of the elements aren't all equal.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let i = m.input("i", 8);
+    /// let stages = (0..4).fold(vec![i as &dyn Signal], |mut stages, n| {
+    ///     let r = m.reg(format!("stage{}", n), 8);
+    ///     r.drive_next(stages[stages.len() - 1]);
+    ///     stages.push(r);
+    ///     stages
+    /// });
+    /// let delay_line = m.signal_vec(stages);
+    /// m.output("delayed", delay_line.select(m.input("delay", 3)));
+    /// ```
+    pub fn signal_vec<S: Into<&'a dyn Signal<'a>>>(
+        &'a self,
+        signals: impl IntoIterator<Item = S>,
+    ) -> SignalVec<'a> {
+        SignalVec::new(self, signals.into_iter().map(Into::into).collect())
+    }
+
+    /// Creates a 2:1 [multiplexer](https://en.wikipedia.org/wiki/Multiplexer) that represents `when_true`'s value when `cond` is high, and `when_false`'s value when `cond` is low.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cond`, `when_true`, or `when_false` belong to a different `Module` than `self`, if `cond`'s bit width is not 1, or if the bit widths of `when_true` and `when_false` aren't equal.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let cond = m.input("cond", 1);
+    /// let a = m.input("a", 8);
+    /// let b = m.input("b", 8);
+    /// m.output("my_output", m.mux(cond, a, b)); // Outputs a when cond is high, b otherwise
+    /// ```
+    pub fn mux(
+        &'a self,
+        cond: &'a dyn Signal<'a>,
+        when_true: &'a dyn Signal<'a>,
+        when_false: &'a dyn Signal<'a>,
+    ) -> &dyn Signal<'a> {
+        let cond = cond.internal_signal();
+        let when_true = when_true.internal_signal();
+        let when_false = when_false.internal_signal();
+
+        check_same_module("Module::mux", ("self", self), ("cond", cond.module));
+        check_same_module(
+            "Module::mux",
+            ("self", self),
+            ("when_true", when_true.module),
+        );
+        check_same_module(
+            "Module::mux",
+            ("self", self),
+            ("when_false", when_false.module),
+        );
+        if cond.bit_width() != 1 {
+            panic!("Multiplexer conditionals can only be 1 bit wide.");
+        }
+        if when_true.bit_width() != when_false.bit_width() {
+            panic!(
+                "Cannot multiplex signals with different bit widths ({} and {}, respectively).",
+                when_true.bit_width(),
+                when_false.bit_width()
+            );
+        }
+
+        // TODO: This is an optimization to support sugar; if that doesn't go well, remove this
+        //  Note that it's only performed after validation, so that invalid muxes are always rejected
+        if when_true == when_false {
+            return when_true;
+        }
+
+        self.context.signal_arena.alloc(InternalSignal {
+            context: self.context,
+            module: self,
+
+            data: SignalData::Mux {
+                cond,
+                when_true,
+                when_false,
+                bit_width: when_true.bit_width(),
+            },
+        })
+    }
+
+    /// Creates a [`Signal`] that represents the value paired with the first constant in `cases` that's equal to `sel`'s value, or `default`'s value if none of them are.
+    ///
+    /// This is equivalent to a chain of [`mux`](Self::mux)es that compare `sel` against each constant in turn, but it's kept as a single construct in generated code: generated Verilog code uses a `case` statement, which is easier to read and gives synthesis tools more freedom than nested conditionals.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sel`, `default`, or any of the values in `cases` belong to a different `Module` than `self`, if any of the values in `cases` has a different bit width than `default`, if any of the constants in `cases` doesn't fit into `sel`'s bit width, or if `cases` contains the same constant more than once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let state = m.reg("state", 2);
+    /// state.default_value(0u32);
+    /// let start = m.input("start", 1);
+    /// let done = m.input("done", 1);
+    /// state.drive_next(m.case(
+    ///     state,
+    ///     &[
+    ///         (0u32, start.mux(m.lit(1u32, 2), state)), // Idle
+    ///         (1u32, m.lit(2u32, 2)),                  // Busy
+    ///         (2u32, done.mux(m.lit(0u32, 2), state)), // Finishing
+    ///     ],
+    ///     m.lit(0u32, 2),
+    /// ));
+    /// m.output("busy", state.eq(m.lit(1u32, 2)));
+    /// ```
+    pub fn case<C: Clone + Into<Constant>>(
+        &'a self,
+        sel: &'a dyn Signal<'a>,
+        cases: &[(C, &'a dyn Signal<'a>)],
+        default: &'a dyn Signal<'a>,
+    ) -> &'a dyn Signal<'a> {
+        let sel = sel.internal_signal();
+        let default = default.internal_signal();
+
+        check_same_module("Module::case", ("self", self), ("sel", sel.module));
+        check_same_module("Module::case", ("self", self), ("default", default.module));
+        let mut internal_cases: Vec<(u128, &'a InternalSignal<'a>)> =
+            Vec::with_capacity(cases.len());
+        for (i, (value, signal)) in cases.iter().enumerate() {
+            let value = value.clone().into();
+            let signal = signal.internal_signal();
+            check_same_module(
+                "Module::case",
+                ("self", self),
+                (&format!("cases[{}]", i), signal.module),
+            );
+            if signal.bit_width() != default.bit_width() {
+                panic!("Cannot create a case whose value for case {} has {} bit(s), but whose default value has {} bit(s).", i, signal.bit_width(), default.bit_width());
+            }
+            let required_bits = value.required_bits();
+            if required_bits > sel.bit_width() {
+                panic!("Cannot create a case whose constant for case {} is {}, which requires {} bit(s), but whose selector has {} bit(s).", i, value.describe(), required_bits, sel.bit_width());
+            }
+            let value = value.to_bit_pattern(sel.bit_width()).numeric_value();
+            if let Some(other) = internal_cases.iter().position(|&(v, _)| v == value) {
+                panic!(
+                    "Cannot create a case whose constants for cases {} and {} are both {:#x}.",
+                    other, i, value
+                );
+            }
+            internal_cases.push((value, signal));
+        }
+
+        if internal_cases.is_empty() {
+            return default;
+        }
+
+        self.context.signal_arena.alloc(InternalSignal {
+            context: self.context,
+            module: self,
+
+            data: SignalData::Case {
+                sel,
+                cases: internal_cases,
+                default,
+                bit_width: default.bit_width(),
+            },
+        })
+    }
+
+    /// Creates a [`Mem`] in this `Module` called `name` with `address_bit_width` address bits and `element_bit_width` element bits.
+    ///
+    /// The size of this memory will be `1 << address_bit_width` elements, each `element_bit_width` bits wide.
+    ///
+    /// Like registers, memories don't share a namespace with this `Module`'s inputs and outputs, as generated code and traces always refer to memories and their ports by unique, mangled names (eg. `__mem_m_my_mem_0` for a memory called `my_mem` in a top-level module instance called `m`).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `address_bit_width` or `element_bit_width` is less than [`MIN_SIGNAL_BIT_WIDTH`] or greater than [`MAX_SIGNAL_BIT_WIDTH`], respectively.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let my_mem = m.mem("my_mem", 1, 32);
+    /// // Optional, unless no write port is specified
+    /// my_mem.initial_contents(&[0xfadebabeu32, 0xdeadbeefu32]);
+    /// // Optional, unless no initial contents are specified
+    /// my_mem.write_port(m.high(), m.lit(0xabad1deau32, 32), m.high());
+    /// m.output("my_output", my_mem.read_port(m.high(), m.high()));
+    /// ```
+    pub fn mem(
+        &'a self,
+        name: impl Into<String>,
+        address_bit_width: u32,
+        element_bit_width: u32,
+    ) -> &Mem<'a> {
+        let name = name.into();
+        self.validate_not_finalized("a memory", &name);
+        if address_bit_width < MIN_SIGNAL_BIT_WIDTH {
+            panic!(
+                "Cannot create a memory with {} address bit(s). Signals must not be narrower than {} bit(s).",
+                address_bit_width, MIN_SIGNAL_BIT_WIDTH
+            );
+        }
+        if address_bit_width > MAX_SIGNAL_BIT_WIDTH {
+            panic!(
+                "Cannot create a memory with {} address bit(s). Signals must not be wider than {} bit(s).",
+                address_bit_width, MAX_SIGNAL_BIT_WIDTH
+            );
+        }
+        if element_bit_width < MIN_SIGNAL_BIT_WIDTH {
+            panic!(
+                "Cannot create a memory with {} element bit(s). Signals must not be narrower than {} bit(s).",
+                element_bit_width, MIN_SIGNAL_BIT_WIDTH
+            );
+        }
+        if element_bit_width > MAX_SIGNAL_BIT_WIDTH {
+            panic!(
+                "Cannot create a memory with {} element bit(s). Signals must not be wider than {} bit(s).",
+                element_bit_width, MAX_SIGNAL_BIT_WIDTH
+            );
+        }
+        let ret = self.context.mem_arena.alloc(Mem {
+            context: self.context,
+            module: self,
+
+            name,
+            address_bit_width,
+            element_bit_width,
+
+            initial_contents: RefCell::new(None),
+
+            read_ports: RefCell::new(Vec::new()),
+            comb_read_ports: RefCell::new(Vec::new()),
+            write_port: RefCell::new(None),
+
+            clocks: RefCell::new(None),
+        });
+        self.mems.borrow_mut().push(ret);
+        ret
+    }
+
+    fn create_output(
+        &'a self,
+        name: String,
+        source: &'a dyn Signal<'a>,
+        dont_care_when: Option<&'a InternalSignal<'a>>,
+    ) -> &'a Output<'a> {
+        self.validate_not_finalized("an output", &name);
+        let source = source.internal_signal();
+        if !ptr::eq(self, source.module) {
+            panic!("Cannot output a signal from another module.");
+        }
+        self.validate_unique_port_name("an output", &name, source.bit_width());
+        let data = self.context.output_data_arena.alloc(OutputData {
+            module: self,
+
+            name: name.clone(),
+            source: Cell::new(source),
+            bit_width: source.bit_width(),
+            dont_care_when: Cell::new(dont_care_when),
+            attributes: RefCell::new(Vec::new()),
+        });
+        let output = self.context.output_arena.alloc(Output { data });
+        self.outputs.borrow_mut().insert(name, output);
+        output
+    }
+
+    /// Returns `<name>_<suffix>`, or `<name>_<suffix>_<n>` with the smallest positive `n` that doesn't match the name of any of this `Module`'s registers if there's already a register called `<name>_<suffix>`.
+    fn unique_register_name(&self, name: &str, suffix: &str) -> String {
+        let base = format!("{}_{}", name, suffix);
+        let registers = self.registers.borrow();
+        let mut ret = base.clone();
+        let mut n = 1;
+        while registers.iter().any(|r| r.data.name == ret) {
+            ret = format!("{}_{}", base, n);
+            n += 1;
+        }
+        ret
+    }
+
+    fn validate_not_finalized(&self, desc: &str, name: &str) {
+        if self.finalized.get() {
+            panic!("Cannot create {} called \"{}\" in module \"{}\", because this module is finalized.", desc, name, self.name);
+        }
+    }
+
+    fn validate_unique_port_name(&'a self, desc: &str, name: &str, bit_width: u32) {
+        let existing = if let Some(input) = self.inputs.borrow().get(name) {
+            Some(("an input", input.data.bit_width))
+        } else {
+            self.outputs
+                .borrow()
+                .get(name)
+                .map(|output| ("an output", output.data.bit_width))
+        };
+        if let Some((existing_desc, existing_bit_width)) = existing {
+            panic!("Cannot create {} called \"{}\" with {} bit(s) in module \"{}\", because this module already contains {} called \"{}\" with {} bit(s).", desc, name, bit_width, self.name, existing_desc, name, existing_bit_width);
+        }
+    }
+}
+
+impl<'a> ModuleParent<'a> for Module<'a> {
+    // TODO: Docs
+    fn module(&'a self, instance_name: impl Into<String>, name: impl Into<String>) -> &Module {
+        let instance_name = instance_name.into();
+        let name = name.into();
+        self.validate_not_finalized(
+            &format!("an instance of module \"{}\"", name),
+            &instance_name,
+        );
+        if let Some(existing) = self
+            .modules
+            .borrow()
+            .iter()
+            .find(|m| m.instance_name == instance_name)
+        {
+            panic!("Cannot create an instance of module \"{}\" called \"{}\" in module \"{}\", because this module already contains an instance of module \"{}\" with the same name.", name, instance_name, self.name, existing.name);
+        }
+        let module = self.context.module_arena.alloc(Module::new(
+            self.context,
+            Some(self),
+            instance_name,
+            name,
+        ));
+        self.modules.borrow_mut().push(module);
+        module
+    }
+}
+
+impl<'a> Eq for &'a Module<'a> {}
+
+impl<'a> Hash for &'a Module<'a> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(*self as *const _ as usize)
+    }
+}
+
+impl<'a> PartialEq for &'a Module<'a> {
+    fn eq(&self, other: &Self) -> bool {
+        ptr::eq(*self, *other)
+    }
+}
+
+pub(crate) struct AssertionData<'a> {
+    pub(crate) cond: &'a InternalSignal<'a>,
+    /// The failure message of an assertion created with [`Module::assert_always`], which generated simulator code checks automatically.
+    pub(crate) message: Option<String>,
+}
+
+// TODO: Move?
+// TODO: Doc
+// TODO: bit width as const generic param?
+#[must_use]
+pub struct Input<'a> {
+    // TODO: Double-check that we need this (I think we need it for error checking in drive)
+    pub(crate) module: &'a Module<'a>,
+
+    pub(crate) data: &'a InputData<'a>,
+    pub(crate) value: &'a InternalSignal<'a>,
+}
+
+impl<'a> Input<'a> {
+    // TODO: Doc
+    // TODO: Merge error cases with Instance::drive_input?
+    // TODO: Rename i?
+    pub fn drive(&'a self, i: &'a dyn Signal<'a>) {
+        let i = i.internal_signal();
+        // TODO: Change text from instance -> module in appropriate places?
+        if let Some(parent) = self.module.parent {
+            if !ptr::eq(parent, i.module) {
+                // TODO: Clarify?
+                panic!("Attempted to drive an instance input with a signal from a different module than that instance's parent module.");
+            }
+        } else {
+            // TODO: Proper panic + test!
+            panic!("OH NOES");
+        }
+        let mut driven_value = self.data.driven_value.borrow_mut();
+        if driven_value.is_some() {
+            panic!("Attempted to drive an input called \"{}\" on an instance of \"{}\", but this input is already driven for this instance.", self.data.name, self.module.name);
+        }
+        if self.data.bit_width != i.bit_width() {
+            panic!("Attempted to drive an input called \"{}\" on an instance of \"{}\", but this input and the provided signal have different bit widths ({} and {}, respectively).", self.data.name, self.module.name, self.data.bit_width, i.bit_width());
+        }
+        *driven_value = Some(i);
+    }
+
+    /// Attaches an [`Attribute`] with the given `namespace`, `key`, and `value` to this `Input`, replacing the value of any existing attribute with the same `namespace` and `key`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `namespace` is [`RESERVED_ATTRIBUTE_NAMESPACE`], or if `namespace` or `key` is not a valid identifier (it must be non-empty, consist only of ASCII alphanumeric characters and underscores, and not start with a digit).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let i = m.input("i", 8);
+    /// i.attribute("my_tool", "pin", "A7");
+    /// ```
+    pub fn attribute(
+        &'a self,
+        namespace: impl Into<String>,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) {
+        add_user_attribute(
+            &self.data.attributes,
+            namespace.into(),
+            key.into(),
+            value.into(),
+            || {
+                format!(
+                    "input \"{}\" in module \"{}\"",
+                    self.data.name, self.module.name
+                )
+            },
+        );
+    }
+}
+
+impl<'a> GetInternalSignal<'a> for Input<'a> {
+    fn internal_signal(&'a self) -> &'a InternalSignal<'a> {
+        self.value
+    }
+}
+
+impl<'a> GetInternalSignal<'a> for Output<'a> {
+    fn internal_signal(&'a self) -> &'a InternalSignal<'a> {
+        let parent = self.data.module.parent.expect("TODO better error pls");
+        self.data.module.context.signal_arena.alloc(InternalSignal {
+            context: self.data.module.context,
+            module: parent,
+
+            data: SignalData::Output { data: self.data },
+        })
+    }
+}
+
+pub(crate) struct InputData<'a> {
+    // TODO: Do we need this stored here too?
+    pub name: String,
+    pub bit_width: u32,
+    // TODO: Rename?
+    pub driven_value: RefCell<Option<&'a InternalSignal<'a>>>,
+    pub attributes: RefCell<Vec<Attribute>>,
+}
+
+// TODO: Move?
+// TODO: Doc
+// TODO: must_use?
+// TODO: bit width as const generic param?
+pub struct Output<'a> {
+    pub(crate) data: &'a OutputData<'a>,
+}
+
+impl<'a> Output<'a> {
+    /// Attaches an [`Attribute`] with the given `namespace`, `key`, and `value` to this `Output`, replacing the value of any existing attribute with the same `namespace` and `key`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `namespace` is [`RESERVED_ATTRIBUTE_NAMESPACE`], or if `namespace` or `key` is not a valid identifier (it must be non-empty, consist only of ASCII alphanumeric characters and underscores, and not start with a digit).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let o = m.output("o", m.input("i", 8));
+    /// o.attribute("my_tool", "pin", "B3");
+    /// ```
+    pub fn attribute(
+        &'a self,
+        namespace: impl Into<String>,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) {
+        add_user_attribute(
+            &self.data.attributes,
+            namespace.into(),
+            key.into(),
+            value.into(),
+            || {
+                format!(
+                    "output \"{}\" in module \"{}\"",
+                    self.data.name, self.data.module.name
+                )
+            },
+        );
+    }
+
+    /// Returns the [`Attribute`]s attached to this `Output`, in the order they were first added.
+    ///
+    /// Unlike [`Signal::attributes`], this can be used on outputs of top-level [`Module`]s.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let o = m.output("o", m.input("i", 8));
+    /// o.attribute("my_tool", "pin", "B3");
+    ///
+    /// assert_eq!(o.attributes()[0].value, "B3");
+    /// ```
+    pub fn attributes(&'a self) -> Vec<Attribute> {
+        self.data.attributes.borrow().clone()
+    }
+}
+
+pub(crate) struct OutputData<'a> {
+    // TODO: Do we need this?
+    pub module: &'a Module<'a>,
+
+    // TODO: Do we need this stored here too?
+    pub name: String,
+    pub source: Cell<&'a InternalSignal<'a>>,
+    pub bit_width: u32,
+    pub dont_care_when: Cell<Option<&'a InternalSignal<'a>>>,
+    pub attributes: RefCell<Vec<Attribute>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[should_panic(expected = "Module \"A\" doesn't contain an input called \"i\".")]
+    fn get_input_nonexistent_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let _ = m.output("i", m.high());
+
+        // Panic
+        let _ = m.get_input("i");
+    }
+
+    #[test]
+    #[should_panic(expected = "Module \"A\" doesn't contain an output called \"o\".")]
+    fn get_output_nonexistent_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let _ = m.input("o", 1);
+
+        // Panic
+        let _ = m.get_output("o");
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create a literal with 0 bit(s). Signals must not be narrower than 1 bit(s)."
+    )]
+    fn lit_bit_width_lt_min_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+
+        // Panic
+        let _ = m.lit(false, 0);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create a literal with 129 bit(s). Signals must not be wider than 128 bit(s)."
+    )]
+    fn lit_bit_width_gt_max_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+
+        // Panic
+        let _ = m.lit(false, 129);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot fit the specified value '128' into the specified bit width '7'. The value '128' requires a bit width of at least 8 bit(s)."
+    )]
+    fn lit_value_cannot_bit_into_bit_width_error_1() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+
+        // Panic
+        let _ = m.lit(128u32, 7);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot fit the specified value '128' into the specified bit width '2'. The value '128' requires a bit width of at least 8 bit(s)."
+    )]
+    fn lit_value_cannot_bit_into_bit_width_error_2() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+
+        // Panic
+        let _ = m.lit(128u64, 2);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot fit the specified value '1023' into the specified bit width '4'. The value '1023' requires a bit width of at least 10 bit(s)."
+    )]
+    fn lit_value_cannot_bit_into_bit_width_error_3() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+
+        // Panic
+        let _ = m.lit(1023u128, 4);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot fit the specified value '65536' into the specified bit width '1'. The value '65536' requires a bit width of at least 17 bit(s)."
+    )]
+    fn lit_value_cannot_bit_into_bit_width_error_4() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+
+        // Panic
+        let _ = m.lit(65536u32, 1);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot fit the specified value '-9' into the specified bit width '4'. The value '-9' requires a bit width of at least 5 bit(s)."
+    )]
+    fn lit_signed_value_cannot_fit_into_bit_width_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+
+        // Panic
+        let _ = m.lit(-9i8, 4);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create an input with 0 bit(s). Signals must not be narrower than 1 bit(s)."
+    )]
+    fn input_width_lt_min_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+
+        // Panic
+        let _ = m.input("i", 0);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create an input with 129 bit(s). Signals must not be wider than 128 bit(s)."
+    )]
+    fn input_width_gt_max_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+
+        // Panic
+        let _ = m.input("i", 129);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create an inout with 0 bit(s). Signals must not be narrower than 1 bit(s)."
+    )]
+    fn inout_width_lt_min_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+
+        // Panic
+        let _ = m.inout("io", 0);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create an inout with 129 bit(s). Signals must not be wider than 128 bit(s)."
+    )]
+    fn inout_width_gt_max_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+
+        // Panic
+        let _ = m.inout("io", 129);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create an inout called \"io\" in module \"Inner\", because inouts can only be created in top-level modules."
+    )]
+    fn inout_in_instance_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let inner = m.module("inner", "Inner");
+
+        // Panic
+        let _ = inner.inout("io", 1);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create an inout called \"io\" with 4 bit(s) in module \"A\", because this module already contains an output called \"io\" with 1 bit(s)."
+    )]
+    fn inout_name_collision_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        m.output("io", m.high());
+
+        // Panic
+        let _ = m.inout("io", 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot output a signal from another module.")]
+    fn output_separate_module_error() {
+        let c = Context::new();
+
+        let m1 = c.module("a", "A");
+
+        let m2 = c.module("b", "B");
+        let i = m2.high();
+
+        // Panic
+        m1.output("a", i);
+    }
+
+    #[test]
+    fn output_registered() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let i = m.input("i", 8);
+        let o = m.output_registered("o", i);
+
+        let registers = m.registers().collect::<Vec<_>>();
+        assert_eq!(registers.len(), 1);
+        assert_eq!(registers[0].name(), "o_reg");
+        assert_eq!(registers[0].bit_width(), 8);
+        assert!(ptr::eq(o.data.source.get(), registers[0].value));
+        assert!(ptr::eq(registers[0].data.next.borrow().unwrap(), i.value));
+        assert_eq!(m.latency(i, o), Some(1));
+        assert_eq!(crate::validate(m), Ok(()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot output a signal from another module.")]
+    fn output_registered_separate_module_error() {
+        let c = Context::new();
+
+        let m1 = c.module("a", "A");
+
+        let m2 = c.module("b", "B");
+        let i = m2.input("i", 1);
+
+        // Panic
+        m1.output_registered("o", i);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create an output called \"i\" with 1 bit(s) in module \"A\", because this module already contains an input called \"i\" with 16 bit(s)."
+    )]
+    fn output_registered_input_name_collision_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let _ = m.input("i", 16);
+
+        // Panic
+        m.output_registered("i", m.low());
+    }
+
+    #[test]
+    fn output_registered_name_collision_doesnt_create_register() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        m.output("o", m.input("i", 1));
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            m.output_registered("o", m.low());
+        }));
+
+        assert!(result.is_err());
+        assert_eq!(m.registers().count(), 0);
+    }
+
+    #[test]
+    fn register_all_outputs() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let i = m.input("i", 8);
+        let en = m.input("en", 1);
+        let a = m.output("a", i);
+        let b = m.output_registered("b", !i);
+        let d = m.output_dont_care_when("d", i, !en);
+        m.register_all_outputs();
+        let e = m.output("e", i);
+
+        let registers = m
+            .registers()
+            .map(|r| (r.name().to_owned(), r.bit_width()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            registers,
+            [
+                ("b_reg".to_owned(), 8),
+                ("a_reg".to_owned(), 8),
+                ("b_reg_1".to_owned(), 8),
+                ("d_reg".to_owned(), 8),
+                ("d_dont_care_reg".to_owned(), 1),
+            ]
+        );
+        assert_eq!(m.latency(i, a), Some(1));
+        assert_eq!(m.latency(i, b), Some(2));
+        assert_eq!(m.latency(i, d), Some(1));
+        assert_eq!(m.latency(i, e), Some(0));
+        assert_eq!(m.latency(en, d), None);
+        let dont_care_cond = d.data.dont_care_when.get().unwrap();
+        assert!(
+            matches!(dont_care_cond.data, SignalData::Reg { data } if data.name == "d_dont_care_reg")
+        );
+        assert_eq!(crate::validate(m), Ok(()));
+    }
+
+    #[test]
+    fn register_all_outputs_instance() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let i = m.input("i", 8);
+        let inner = m.module("inner", "Inner");
+        let inner_i = inner.input("i", 8);
+        inner.output("o", inner_i + inner_i);
+        inner_i.drive(i);
+        let o = m.output("o", inner.get_output("o"));
+
+        // The instance's outputs are registered after they're connected to the parent
+        inner.register_all_outputs();
+
+        assert_eq!(m.latency(i, o), Some(1));
+        assert_eq!(crate::validate(m), Ok(()));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create an output called \"o\" in module \"A\" with a don't care condition from another module."
+    )]
+    fn output_dont_care_when_separate_module_error() {
+        let c = Context::new();
+
+        let m1 = c.module("a", "A");
+
+        let m2 = c.module("b", "B");
+        let cond = m2.high();
+
+        // Panic
+        m1.output_dont_care_when("o", m1.high(), cond);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create an output called \"o\" in module \"A\" with a don't care condition with 2 bit(s). Don't care conditions must be 1 bit wide."
+    )]
+    fn output_dont_care_when_cond_bit_width_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+
+        // Panic
+        m.output_dont_care_when("o", m.high(), m.lit(0u32, 2));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create an input called \"i\" with 8 bit(s) in module \"A\", because this module already contains an input called \"i\" with 4 bit(s)."
+    )]
+    fn input_duplicate_name_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let _ = m.input("i", 4);
+
+        // Panic
+        let _ = m.input("i", 8);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create an input called \"o\" with 3 bit(s) in module \"A\", because this module already contains an output called \"o\" with 1 bit(s)."
+    )]
+    fn input_output_name_collision_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        m.output("o", m.high());
+
+        // Panic
+        let _ = m.input("o", 3);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create an output called \"o\" with 32 bit(s) in module \"A\", because this module already contains an output called \"o\" with 1 bit(s)."
+    )]
+    fn output_duplicate_name_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        m.output("o", m.high());
+
+        // Panic
+        m.output("o", m.lit(0u32, 32));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create an output called \"i\" with 1 bit(s) in module \"A\", because this module already contains an input called \"i\" with 16 bit(s)."
+    )]
+    fn output_input_name_collision_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let _ = m.input("i", 16);
+
+        // Panic
+        m.output("i", m.low());
+    }
+
+    #[test]
+    fn reg_output_same_name() {
+        let c = Context::new();
+
+        // Registers are in a separate namespace from inputs and outputs
+        let m = c.module("a", "A");
+        let r = m.reg("o", 1);
+        r.drive_next(m.input("i", 1));
+        m.output("o", r);
+
+        assert_eq!(crate::validate(m), Ok(()));
+    }
+
+    #[test]
+    fn state_element_and_instance_accessors() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let _ = m.reg("r1", 8);
+        let _ = m.reg_in_domain("r2", 4, "other_clk");
+        let d = m.reg_deferred("r3");
+        d.infer_width_from(m.input("i", 16));
+        let _ = m.clock_divider("div", 4).reg("r4", 1);
+        let _ = m.mem("mem1", 2, 32);
+        let _ = m.mem("mem2", 8, 1);
+        let b = m.module("b", "B");
+        let _ = b.reg("inner_r", 3);
+        let _ = b.module("d", "D");
+        let _ = m.module("c", "C");
+
+        let registers = m
+            .registers()
+            .map(|r| (r.name().to_owned(), r.bit_width()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            registers,
+            [
+                ("r1".to_owned(), 8),
+                ("r2".to_owned(), 4),
+                ("r3".to_owned(), 16),
+                ("div_count".to_owned(), 2),
+                ("div".to_owned(), 1),
+                ("r4".to_owned(), 1),
+            ]
+        );
+
+        let memories = m
+            .memories()
+            .map(|mem| {
+                (
+                    mem.name().to_owned(),
+                    mem.address_bit_width(),
+                    mem.element_bit_width(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            memories,
+            [("mem1".to_owned(), 2, 32), ("mem2".to_owned(), 8, 1)]
+        );
+
+        // Only direct children are included
+        let instances = m
+            .instances()
+            .map(|i| (i.instance_name().to_owned(), i.name().to_owned()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            instances,
+            [
+                ("b".to_owned(), "B".to_owned()),
+                ("c".to_owned(), "C".to_owned()),
+            ]
+        );
+        assert_eq!(b.registers().count(), 1);
+        assert_eq!(b.instances().count(), 1);
+    }
+
+    #[test]
+    fn port_accessors() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let i = m.input("z", 3);
+        let _ = m.input("a", 128);
+        m.output("o", i);
+        let deferred = m.output_deferred("d");
+
+        // Deferred outputs are only included once they're driven
+        assert_eq!(m.inputs().collect::<Vec<_>>(), [("a", 128), ("z", 3)]);
+        assert_eq!(m.outputs().collect::<Vec<_>>(), [("o", 3)]);
+        deferred.drive(m.high());
+        assert_eq!(m.outputs().collect::<Vec<_>>(), [("d", 1), ("o", 3)]);
+
+        // Instance ports are included too, once they're created
+        let inner = m.module("inner", "Inner");
+        assert_eq!(inner.inputs().count(), 0);
+        let _ = inner.input("x", 2);
+        assert_eq!(inner.inputs().collect::<Vec<_>>(), [("x", 2)]);
+    }
+
+    #[test]
+    fn state_elements_may_share_port_names() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let i = m.input("i", 8);
+        let r1 = m.reg("o", 8);
+        r1.drive_next(i);
+        let r2 = m.reg("o", 8);
+        r2.drive_next(r1);
+        let mem = m.mem("i", 1, 8);
+        mem.write_port(m.low(), r2, m.high());
+        m.output("o", mem.read_port(m.low(), m.high()));
+
+        assert_eq!(m.inputs.borrow().len(), 1);
+        assert_eq!(m.outputs.borrow().len(), 1);
+        assert_eq!(m.registers.borrow().len(), 2);
+        assert_eq!(m.mems.borrow().len(), 1);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create a register with 0 bit(s). Signals must not be narrower than 1 bit(s)."
+    )]
+    fn reg_bit_width_lt_min_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+
+        // Panic
+        let _ = m.reg("r", 0);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create a register with 129 bit(s). Signals must not be wider than 128 bit(s)."
+    )]
+    fn reg_bit_width_gt_max_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+
+        // Panic
+        let _ = m.reg("r", 129);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create a register with 0 bit(s). Signals must not be narrower than 1 bit(s)."
+    )]
+    fn reg_in_domain_bit_width_lt_min_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+
+        // Panic
+        let _ = m.reg_in_domain("r", 0, "clk_fast");
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create a register with 200 bit(s). Signals must not be wider than 128 bit(s)."
+    )]
+    fn reg_in_domain_bit_width_gt_max_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+
+        // Panic
+        let _ = m.reg_in_domain("r", 200, "clk_fast");
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create a register called \"r\" in module \"A\" with a clock called \"0clk\". Clock names must be non-empty, consist only of ASCII alphanumeric characters and underscores, must not start with a digit, and must not be Verilog or SystemVerilog keywords."
+    )]
+    fn reg_in_domain_invalid_clock_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+
+        // Panic
+        let _ = m.reg_in_domain("r", 1, "0clk");
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create a register called \"r\" in module \"A\" with a clock called \"always\". Clock names must be non-empty, consist only of ASCII alphanumeric characters and underscores, must not start with a digit, and must not be Verilog or SystemVerilog keywords."
+    )]
+    fn reg_in_domain_keyword_clock_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+
+        // Panic
+        let _ = m.reg_in_domain("r", 8, "always");
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create a reset domain called \"0soft\" in module \"A\". Reset domain names must be non-empty, consist only of ASCII alphanumeric characters and underscores, and must not start with a digit."
+    )]
+    fn reset_domain_invalid_name_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+
+        // Panic
+        let _ = m.reset_domain("0soft");
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create a reset domain called \"soft\" in module \"A\", because this module already contains a reset domain with the same name."
+    )]
+    fn reset_domain_duplicate_name_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let _ = m.reset_domain("soft");
+
+        // Panic
+        let _ = m.reset_domain("soft");
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create an instance of module \"C\" called \"b\" in module \"A\", because this module already contains an instance of module \"B\" with the same name."
+    )]
+    fn module_duplicate_instance_name_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let _ = m.module("b", "B");
+
+        // Panic
+        let _ = m.module("b", "C");
+    }
+
+    #[test]
+    #[should_panic(expected = "Attempted to concatenate an empty list of signals.")]
+    fn concat_all_empty_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+
+        // Panic
+        let _ = m.concat_all(&[]);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Attempted to combine signals from different modules: self belongs to \"a\", signals[1] belongs to \"b\" (while building Module::concat_all)."
+    )]
+    fn concat_all_separate_module_error() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let b = c.module("b", "B");
+
+        // Panic
+        let _ = a.concat_all(&[a.input("i", 1), b.input("i", 1)]);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Attempted to concatenate signals with 129 bit(s) in total, which is greater than the maximum signal bit width of 128 bit(s)."
+    )]
+    fn concat_all_bit_width_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let i = m.input("i", 64);
+
+        // Panic
+        let _ = m.concat_all(&[i, i, m.high()]);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Attempted to combine signals from different modules: self belongs to \"b\", cond belongs to \"a\" (while building Module::mux)."
+    )]
+    fn mux_cond_separate_module_error() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let l1 = a.lit(false, 1);
+
+        let b = c.module("b", "B");
+        let l2 = b.lit(32u8, 8);
+        let l3 = b.lit(32u8, 8);
+
+        // Panic
+        let _ = b.mux(l1, l2, l3);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Attempted to combine signals from different modules: self belongs to \"b\", when_true belongs to \"a\" (while building Module::mux)."
+    )]
+    fn mux_when_true_separate_module_error() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let l1 = a.lit(32u8, 8);
+
+        let b = c.module("b", "B");
+        let l2 = b.lit(true, 1);
+        let l3 = b.lit(32u8, 8);
+
+        // Panic
+        let _ = b.mux(l2, l1, l3);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Attempted to combine signals from different modules: self belongs to \"b\", when_false belongs to \"a\" (while building Module::mux)."
+    )]
+    fn mux_when_false_separate_module_error() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let l1 = a.lit(32u8, 8);
+
+        let b = c.module("b", "B");
+        let l2 = b.lit(true, 1);
+        let l3 = b.lit(32u8, 8);
+
+        // Panic
+        let _ = b.mux(l2, l3, l1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Multiplexer conditionals can only be 1 bit wide.")]
+    fn mux_cond_bit_width_error() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let l1 = a.lit(2u8, 2);
+        let l2 = a.lit(32u8, 8);
+        let l3 = a.lit(32u8, 8);
+
+        // Panic
+        let _ = a.mux(l1, l2, l3);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot multiplex signals with different bit widths (3 and 5, respectively)."
+    )]
+    fn mux_true_false_bit_width_error() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let l1 = a.lit(false, 1);
+        let l2 = a.lit(3u8, 3);
+        let l3 = a.lit(3u8, 5);
+
+        // Panic
+        let _ = a.mux(l1, l2, l3);
+    }
+
+    #[test]
+    #[should_panic(expected = "Multiplexer conditionals can only be 1 bit wide.")]
+    fn mux_same_signals_cond_bit_width_error() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let l1 = a.lit(2u8, 2);
+        let l2 = a.lit(32u8, 8);
+
+        // Panic
+        let _ = a.mux(l1, l2, l2);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Attempted to combine signals from different modules: self belongs to \"b\", cond belongs to \"a\" (while building Module::mux)."
+    )]
+    fn mux_same_signals_cond_separate_module_error() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let l1 = a.lit(false, 1);
+
+        let b = c.module("b", "B");
+        let l2 = b.lit(32u8, 8);
+
+        // Panic
+        let _ = b.mux(l1, l2, l2);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Attempted to combine signals from different modules: self belongs to \"b\", sel belongs to \"a\" (while building Module::case)."
+    )]
+    fn case_sel_separate_module_error() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let l1 = a.lit(0u32, 2);
+
+        let b = c.module("b", "B");
+        let l2 = b.lit(32u8, 8);
+
+        // Panic
+        let _ = b.case(l1, &[(0u32, l2)], l2);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Attempted to combine signals from different modules: self belongs to \"b\", cases[1] belongs to \"a\" (while building Module::case)."
+    )]
+    fn case_value_separate_module_error() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let l1 = a.lit(32u8, 8);
+
+        let b = c.module("b", "B");
+        let l2 = b.lit(0u32, 2);
+        let l3 = b.lit(32u8, 8);
+
+        // Panic
+        let _ = b.case(l2, &[(0u32, l3), (1u32, l1)], l3);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create a case whose value for case 1 has 5 bit(s), but whose default value has 8 bit(s)."
+    )]
+    fn case_value_bit_width_error() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let sel = a.input("sel", 2);
+        let l1 = a.lit(3u8, 8);
+        let l2 = a.lit(3u8, 5);
+
+        // Panic
+        let _ = a.case(sel, &[(0u32, l1), (1u32, l2)], l1);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create a case whose constant for case 0 is 4, which requires 3 bit(s), but whose selector has 2 bit(s)."
+    )]
+    fn case_constant_bit_width_error() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let sel = a.input("sel", 2);
+        let l = a.lit(3u8, 8);
+
+        // Panic
+        let _ = a.case(sel, &[(4u32, l)], l);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create a case whose constants for cases 0 and 2 are both 0x3."
+    )]
+    fn case_duplicate_constant_error() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let sel = a.input("sel", 2);
+        let l = a.lit(3u8, 8);
+
+        // Panic
+        let _ = a.case(sel, &[(3u32, l), (1u32, l), (3u32, l)], l);
+    }
+
+    #[test]
+    fn case_no_cases() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let sel = a.input("sel", 2);
+        let l = a.lit(3u8, 8);
+
+        let cases: &[(u32, &dyn Signal)] = &[];
+        assert!(ptr::eq(
+            a.case(sel, cases, l).internal_signal(),
+            l.internal_signal()
+        ));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create a memory with 0 address bit(s). Signals must not be narrower than 1 bit(s)."
+    )]
+    fn mem_address_bit_width_lt_min_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
 
-// TODO: Move?
-// TODO: Doc
-// TODO: must_use?
-// TODO: bit width as const generic param?
-pub struct Output<'a> {
-    pub(crate) data: &'a OutputData<'a>,
-}
+        // Panic
+        let _ = m.mem("mem", 0, 1);
+    }
 
-pub(crate) struct OutputData<'a> {
-    // TODO: Do we need this?
-    pub module: &'a Module<'a>,
+    #[test]
+    #[should_panic(
+        expected = "Cannot create a memory with 129 address bit(s). Signals must not be wider than 128 bit(s)."
+    )]
+    fn mem_address_bit_width_gt_max_error() {
+        let c = Context::new();
 
-    // TODO: Do we need this stored here too?
-    pub name: String,
-    pub source: &'a InternalSignal<'a>,
-    pub bit_width: u32,
-}
+        let m = c.module("a", "A");
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        // Panic
+        let _ = m.mem("mem", 129, 1);
+    }
 
     #[test]
     #[should_panic(
-        expected = "Cannot create a literal with 0 bit(s). Signals must not be narrower than 1 bit(s)."
+        expected = "Cannot create a memory with 0 element bit(s). Signals must not be narrower than 1 bit(s)."
     )]
-    fn lit_bit_width_lt_min_error() {
+    fn mem_element_bit_width_lt_min_error() {
         let c = Context::new();
 
         let m = c.module("a", "A");
 
         // Panic
-        let _ = m.lit(false, 0);
+        let _ = m.mem("mem", 1, 0);
     }
 
     #[test]
     #[should_panic(
-        expected = "Cannot create a literal with 129 bit(s). Signals must not be wider than 128 bit(s)."
+        expected = "Cannot create a memory with 129 element bit(s). Signals must not be wider than 128 bit(s)."
     )]
-    fn lit_bit_width_gt_max_error() {
+    fn mem_element_bit_width_gt_max_error() {
         let c = Context::new();
 
         let m = c.module("a", "A");
 
         // Panic
-        let _ = m.lit(false, 129);
+        let _ = m.mem("mem", 1, 129);
     }
 
     #[test]
     #[should_panic(
-        expected = "Cannot fit the specified value '128' into the specified bit width '7'. The value '128' requires a bit width of at least 8 bit(s)."
+        expected = "Attempted to drive an instance input with a signal from a different module than that instance's parent module."
     )]
-    fn lit_value_cannot_bit_into_bit_width_error_1() {
+    fn input_drive_different_module_than_parent_module_error() {
         let c = Context::new();
 
-        let m = c.module("a", "A");
+        let m1 = c.module("a", "A");
+        let i1 = m1.input("a", 1);
+
+        let m2 = c.module("b", "B");
+
+        let inner = m2.module("inner", "Inner");
+        let a = inner.input("a", 1);
 
         // Panic
-        let _ = m.lit(128u32, 7);
+        a.drive(i1);
     }
 
     #[test]
     #[should_panic(
-        expected = "Cannot fit the specified value '128' into the specified bit width '2'. The value '128' requires a bit width of at least 8 bit(s)."
+        expected = "Attempted to drive an input called \"a\" on an instance of \"Inner\", but this input is already driven for this instance."
     )]
-    fn lit_value_cannot_bit_into_bit_width_error_2() {
+    fn input_drive_already_driven_error() {
         let c = Context::new();
 
         let m = c.module("a", "A");
 
+        let inner = m.module("inner", "Inner");
+        let a = inner.input("a", 1);
+
+        a.drive(m.input("i1", 1));
+
         // Panic
-        let _ = m.lit(128u64, 2);
+        a.drive(m.input("i2", 1));
     }
 
     #[test]
     #[should_panic(
-        expected = "Cannot fit the specified value '1023' into the specified bit width '4'. The value '1023' requires a bit width of at least 10 bit(s)."
+        expected = "Attempted to drive an input called \"a\" on an instance of \"Inner\", but this input and the provided signal have different bit widths (1 and 32, respectively)."
     )]
-    fn lit_value_cannot_bit_into_bit_width_error_3() {
+    fn input_drive_incompatible_bit_widths_error() {
         let c = Context::new();
 
         let m = c.module("a", "A");
 
+        let inner = m.module("inner", "Inner");
+        let a = inner.input("a", 1);
+
         // Panic
-        let _ = m.lit(1023u128, 4);
+        a.drive(m.input("i1", 32));
     }
 
     #[test]
     #[should_panic(
-        expected = "Cannot fit the specified value '65536' into the specified bit width '1'. The value '65536' requires a bit width of at least 17 bit(s)."
+        expected = "Cannot add attribute \"kaze.pin\" to input \"i\" in module \"A\", because the \"kaze\" attribute namespace is reserved for kaze's internal use."
     )]
-    fn lit_value_cannot_bit_into_bit_width_error_4() {
+    fn input_attribute_reserved_namespace_error() {
         let c = Context::new();
 
         let m = c.module("a", "A");
+        let i = m.input("i", 1);
 
         // Panic
-        let _ = m.lit(65536u32, 1);
+        i.attribute("kaze", "pin", "A7");
     }
 
     #[test]
     #[should_panic(
-        expected = "Cannot create an input with 0 bit(s). Signals must not be narrower than 1 bit(s)."
+        expected = "Cannot add attribute \"kaze.pin\" to output \"o\" in module \"A\", because the \"kaze\" attribute namespace is reserved for kaze's internal use."
     )]
-    fn input_width_lt_min_error() {
+    fn output_attribute_reserved_namespace_error() {
         let c = Context::new();
 
         let m = c.module("a", "A");
+        let o = m.output("o", m.input("i", 1));
 
         // Panic
-        let _ = m.input("i", 0);
+        o.attribute("kaze", "pin", "A7");
+    }
+
+    #[test]
+    fn apply_attribute_to_registers() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let r1 = m.reg("r1", 1);
+        let r2 = m.reg("r2", 1);
+        r2.attribute("my_tool", "group", "ctrl");
+        let inner = m.module("inner", "Inner");
+        let inner_r = inner.reg("r", 1);
+
+        m.apply_attribute_to_registers("xilinx", "async_reg", "true");
+
+        let r3 = m.reg("r3", 1);
+
+        let async_reg = Attribute {
+            namespace: "xilinx".into(),
+            key: "async_reg".into(),
+            value: "true".into(),
+        };
+        assert_eq!(r1.attributes(), vec![async_reg.clone()]);
+        assert_eq!(r2.attributes().len(), 2);
+        assert_eq!(r2.attributes()[1], async_reg);
+        assert!(r3.attributes().is_empty());
+        assert!(inner_r.attributes().is_empty());
     }
 
     #[test]
     #[should_panic(
-        expected = "Cannot create an input with 129 bit(s). Signals must not be wider than 128 bit(s)."
+        expected = "Cannot add attribute \"kaze.keep\" to register \"r\" in module \"A\", because the \"kaze\" attribute namespace is reserved for kaze's internal use."
     )]
-    fn input_width_gt_max_error() {
+    fn apply_attribute_to_registers_reserved_namespace_error() {
         let c = Context::new();
 
         let m = c.module("a", "A");
+        let _ = m.reg("r", 1);
 
         // Panic
-        let _ = m.input("i", 129);
+        m.apply_attribute_to_registers("kaze", "keep", "true");
     }
 
     #[test]
-    #[should_panic(expected = "Cannot output a signal from another module.")]
-    fn output_separate_module_error() {
+    #[should_panic(expected = "Cannot create a named wire from a signal from another module.")]
+    fn named_wire_separate_module_error() {
         let c = Context::new();
 
         let m1 = c.module("a", "A");
+        let i = m1.input("i", 1);
 
         let m2 = c.module("b", "B");
-        let i = m2.high();
 
         // Panic
-        m1.output("a", i);
+        let _ = m2.named_wire("w", i);
     }
 
     #[test]
     #[should_panic(
-        expected = "Cannot create a register with 0 bit(s). Signals must not be narrower than 1 bit(s)."
+        expected = "Cannot create a named wire called \"w\" in module \"A\", because this module already contains an input, output, or named wire with the same name."
     )]
-    fn reg_bit_width_lt_min_error() {
+    fn named_wire_duplicate_name_error() {
         let c = Context::new();
 
         let m = c.module("a", "A");
+        let i = m.input("i", 1);
+        let _ = m.named_wire("w", i);
 
         // Panic
-        let _ = m.reg("r", 0);
+        let _ = m.named_wire("w", !i);
     }
 
     #[test]
     #[should_panic(
-        expected = "Cannot create a register with 129 bit(s). Signals must not be wider than 128 bit(s)."
+        expected = "Cannot create a named wire called \"i\" in module \"A\", because this module already contains an input, output, or named wire with the same name."
     )]
-    fn reg_bit_width_gt_max_error() {
+    fn named_wire_port_name_error() {
         let c = Context::new();
 
         let m = c.module("a", "A");
+        let i = m.input("i", 1);
 
         // Panic
-        let _ = m.reg("r", 129);
+        let _ = m.named_wire("i", !i);
     }
 
     #[test]
-    #[should_panic(expected = "Attempted to combine signals from different modules.")]
-    fn mux_cond_separate_module_error() {
+    #[should_panic(
+        expected = "Cannot create an assertion called \"a\" in module \"B\" with a condition from another module."
+    )]
+    fn assert_separate_module_error() {
         let c = Context::new();
 
-        let a = c.module("a", "A");
-        let l1 = a.lit(false, 1);
+        let m1 = c.module("a", "A");
+        let i = m1.input("i", 1);
 
-        let b = c.module("b", "B");
-        let l2 = b.lit(32u8, 8);
-        let l3 = b.lit(32u8, 8);
+        let m2 = c.module("b", "B");
 
         // Panic
-        let _ = b.mux(l1, l2, l3);
+        m2.assert("a", i);
     }
 
     #[test]
-    #[should_panic(expected = "Attempted to combine signals from different modules.")]
-    fn mux_when_true_separate_module_error() {
+    #[should_panic(
+        expected = "Cannot create an assertion called \"a\" in module \"A\" with a condition with 2 bit(s). Assertion conditions must be 1 bit wide."
+    )]
+    fn assert_bit_width_error() {
         let c = Context::new();
 
-        let a = c.module("a", "A");
-        let l1 = a.lit(32u8, 8);
-
-        let b = c.module("b", "B");
-        let l2 = b.lit(true, 1);
-        let l3 = b.lit(32u8, 8);
+        let m = c.module("a", "A");
+        let i = m.input("i", 2);
 
         // Panic
-        let _ = b.mux(l2, l1, l3);
+        m.assert("a", i);
     }
 
     #[test]
-    #[should_panic(expected = "Attempted to combine signals from different modules.")]
-    fn mux_when_false_separate_module_error() {
+    #[should_panic(
+        expected = "Cannot create an assertion called \"0a\" in module \"A\". Assertion names must be non-empty, consist only of ASCII alphanumeric characters and underscores, and must not start with a digit."
+    )]
+    fn assert_invalid_name_error() {
         let c = Context::new();
 
-        let a = c.module("a", "A");
-        let l1 = a.lit(32u8, 8);
+        let m = c.module("a", "A");
+        let i = m.input("i", 1);
 
-        let b = c.module("b", "B");
-        let l2 = b.lit(true, 1);
-        let l3 = b.lit(32u8, 8);
+        // Panic
+        m.assert("0a", i);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create an assertion called \"a\" in module \"A\", because this module already contains an assertion with the same name."
+    )]
+    fn assert_duplicate_name_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let i = m.input("i", 1);
+        m.assert("a", i);
 
         // Panic
-        let _ = b.mux(l2, l3, l1);
+        m.assert("a", !i);
     }
 
     #[test]
-    #[should_panic(expected = "Multiplexer conditionals can only be 1 bit wide.")]
-    fn mux_cond_bit_width_error() {
+    #[should_panic(
+        expected = "Cannot create an assertion called \"a\" in module \"A\", because this module already contains an assertion with the same name."
+    )]
+    fn assert_always_duplicate_name_error() {
         let c = Context::new();
 
-        let a = c.module("a", "A");
-        let l1 = a.lit(2u8, 2);
-        let l2 = a.lit(32u8, 8);
-        let l3 = a.lit(32u8, 8);
+        let m = c.module("a", "A");
+        let i = m.input("i", 1);
+        m.assert("a", i);
 
         // Panic
-        let _ = a.mux(l1, l2, l3);
+        m.assert_always("a", !i, "message");
     }
 
     #[test]
     #[should_panic(
-        expected = "Cannot multiplex signals with different bit widths (3 and 5, respectively)."
+        expected = "Cannot create a coverage point called \"a\" in module \"B\" with a condition from another module."
     )]
-    fn mux_true_false_bit_width_error() {
+    fn cover_separate_module_error() {
         let c = Context::new();
 
-        let a = c.module("a", "A");
-        let l1 = a.lit(false, 1);
-        let l2 = a.lit(3u8, 3);
-        let l3 = a.lit(3u8, 5);
+        let m1 = c.module("a", "A");
+        let i = m1.input("i", 1);
+
+        let m2 = c.module("b", "B");
 
         // Panic
-        let _ = a.mux(l1, l2, l3);
+        m2.cover("a", i);
     }
 
     #[test]
     #[should_panic(
-        expected = "Cannot create a memory with 0 address bit(s). Signals must not be narrower than 1 bit(s)."
+        expected = "Cannot create a coverage point called \"a\" in module \"A\" with a condition with 2 bit(s). Coverage point conditions must be 1 bit wide."
     )]
-    fn mem_address_bit_width_lt_min_error() {
+    fn cover_bit_width_error() {
         let c = Context::new();
 
         let m = c.module("a", "A");
+        let i = m.input("i", 2);
 
         // Panic
-        let _ = m.mem("mem", 0, 1);
+        m.cover("a", i);
     }
 
     #[test]
     #[should_panic(
-        expected = "Cannot create a memory with 129 address bit(s). Signals must not be wider than 128 bit(s)."
+        expected = "Cannot create a coverage point called \"0a\" in module \"A\". Coverage point names must be non-empty, consist only of ASCII alphanumeric characters and underscores, and must not start with a digit."
     )]
-    fn mem_address_bit_width_gt_max_error() {
+    fn cover_invalid_name_error() {
         let c = Context::new();
 
         let m = c.module("a", "A");
+        let i = m.input("i", 1);
 
         // Panic
-        let _ = m.mem("mem", 129, 1);
+        m.cover("0a", i);
     }
 
     #[test]
     #[should_panic(
-        expected = "Cannot create a memory with 0 element bit(s). Signals must not be narrower than 1 bit(s)."
+        expected = "Cannot create a coverage point called \"a\" in module \"A\", because this module already contains a coverage point with the same name."
     )]
-    fn mem_element_bit_width_lt_min_error() {
+    fn cover_duplicate_name_error() {
         let c = Context::new();
 
         let m = c.module("a", "A");
+        let i = m.input("i", 1);
+        m.cover("a", i);
 
         // Panic
-        let _ = m.mem("mem", 1, 0);
+        m.cover("a", !i);
     }
 
     #[test]
     #[should_panic(
-        expected = "Cannot create a memory with 129 element bit(s). Signals must not be wider than 128 bit(s)."
+        expected = "Attempted to query the latency from input \"i\" in module \"B\", but this input belongs to module \"A\"."
     )]
-    fn mem_element_bit_width_gt_max_error() {
+    fn latency_input_separate_module_error() {
         let c = Context::new();
 
-        let m = c.module("a", "A");
+        let a = c.module("a", "A");
+        let i = a.input("i", 1);
+
+        let b = c.module("b", "B");
+        let o = b.output("o", b.input("i", 1));
 
         // Panic
-        let _ = m.mem("mem", 1, 129);
+        let _ = b.latency(i, o);
     }
 
     #[test]
     #[should_panic(
-        expected = "Attempted to drive an instance input with a signal from a different module than that instance's parent module."
+        expected = "Attempted to query the latency to output \"o\" in module \"B\", but this output belongs to module \"A\"."
     )]
-    fn input_drive_different_module_than_parent_module_error() {
+    fn latency_output_separate_module_error() {
         let c = Context::new();
 
-        let m1 = c.module("a", "A");
-        let i1 = m1.input("a", 1);
+        let a = c.module("a", "A");
+        let o = a.output("o", a.input("i", 1));
 
-        let m2 = c.module("b", "B");
+        let b = c.module("b", "B");
+        let i = b.input("i", 1);
 
-        let inner = m2.module("inner", "Inner");
-        let a = inner.input("a", 1);
+        // Panic
+        let _ = b.latency(i, o);
+    }
+
+    #[test]
+    fn latency() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        let a = m.input("a", 8);
+        let b = m.input("b", 8);
+        let unused = m.input("unused", 8);
+
+        // Combinational
+        let comb = m.output("comb", a + b);
+        assert_eq!(m.latency(a, comb), Some(0));
+        assert_eq!(m.latency(b, comb), Some(0));
+        assert_eq!(m.latency(unused, comb), None);
+
+        // Pipeline, where b enters at the last stage
+        let stage1 = a.reg_next("stage1");
+        let stage2 = stage1.reg_next("stage2");
+        let stage3 = (stage2 ^ b).reg_next("stage3");
+        let pipelined = m.output("pipelined", stage3);
+        assert_eq!(m.latency(a, pipelined), Some(3));
+        assert_eq!(m.latency(b, pipelined), Some(1));
+
+        // Feedback loops don't add paths with lower latency
+        let acc = m.reg("acc", 8);
+        acc.drive_next(acc + stage1);
+        let accumulated = m.output("accumulated", acc);
+        assert_eq!(m.latency(a, accumulated), Some(2));
+        assert_eq!(m.latency(b, accumulated), None);
+
+        // Through instances
+        let inner = m.module("inner", "Inner");
+        let inner_i = inner.input("i", 8);
+        inner.output("o", inner_i.reg_next("r"));
+        inner_i.drive(b);
+        let through_inner = m.output("through_inner", inner.outputs.borrow()["o"]);
+        assert_eq!(m.latency(b, through_inner), Some(1));
+        assert_eq!(m.latency(a, through_inner), None);
+
+        // Through mems
+        let mem = m.mem("mem", 2, 8);
+        mem.write_port(a.bits(1, 0), b, m.high());
+        let read_address = m.input("read_address", 2);
+        let sync_read = m.output("sync_read", mem.read_port(read_address, m.high()));
+        let comb_read = m.output("comb_read", mem.read_port_comb(read_address));
+        assert_eq!(m.latency(read_address, sync_read), Some(1));
+        assert_eq!(m.latency(b, sync_read), Some(2));
+        assert_eq!(m.latency(read_address, comb_read), Some(0));
+        assert_eq!(m.latency(a, comb_read), Some(1));
+    }
+
+    #[test]
+    fn module_with() {
+        let c = Context::new();
+
+        let mut built = false;
+        let m = c.module_with("a", "A", |m| {
+            assert!(!m.is_finalized());
+            let inner = m.module_with("inner", "Inner", |inner| {
+                inner.output("o", !inner.input("i", 1));
+            });
+            inner.get_input("i").drive(m.input("i", 1));
+            m.output("o", inner.get_output("o"));
+            built = true;
+        });
+
+        assert!(built);
+        assert!(m.is_finalized());
+        assert!(m.instances().all(|inner| inner.is_finalized()));
+
+        // Finalized modules can still be generated
+        assert_eq!(crate::validate(m), Ok(()));
+        crate::sim::generate(m, Default::default(), Vec::new()).unwrap();
+        crate::verilog::generate(m, Default::default(), Vec::new()).unwrap();
+    }
+
+    #[test]
+    fn finalize_doesnt_affect_instances() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let inner = m.module("inner", "Inner");
+        m.finalize();
+
+        assert!(m.is_finalized());
+        assert!(!inner.is_finalized());
+        inner.output("o", inner.input("i", 1));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create an input called \"i\" in module \"A\", because this module is finalized."
+    )]
+    fn finalized_input_error() {
+        let c = Context::new();
+
+        let m = c.module_with("a", "A", |_| {});
 
         // Panic
-        a.drive(i1);
+        let _ = m.input("i", 1);
     }
 
     #[test]
     #[should_panic(
-        expected = "Attempted to drive an input called \"a\" on an instance of \"Inner\", but this input is already driven for this instance."
+        expected = "Cannot create an output called \"o\" in module \"A\", because this module is finalized."
     )]
-    fn input_drive_already_driven_error() {
+    fn finalized_output_error() {
         let c = Context::new();
 
-        let m = c.module("a", "A");
+        let mut high = None;
+        let m = c.module_with("a", "A", |m| {
+            high = Some(m.high());
+        });
 
-        let inner = m.module("inner", "Inner");
-        let a = inner.input("a", 1);
+        // Panic
+        m.output("o", high.unwrap());
+    }
 
-        a.drive(m.input("i1", 1));
+    #[test]
+    #[should_panic(
+        expected = "Cannot create a register called \"r\" in module \"A\", because this module is finalized."
+    )]
+    fn finalized_reg_error() {
+        let c = Context::new();
+
+        let m = c.module_with("a", "A", |_| {});
 
         // Panic
-        a.drive(m.input("i2", 1));
+        let _ = m.reg("r", 1);
     }
 
     #[test]
     #[should_panic(
-        expected = "Attempted to drive an input called \"a\" on an instance of \"Inner\", but this input and the provided signal have different bit widths (1 and 32, respectively)."
+        expected = "Cannot create a register called \"r\" in module \"A\", because this module is finalized."
     )]
-    fn input_drive_incompatible_bit_widths_error() {
+    fn finalized_deferred_reg_error() {
         let c = Context::new();
 
         let m = c.module("a", "A");
+        let r = m.reg_deferred("r");
+        m.finalize();
 
-        let inner = m.module("inner", "Inner");
-        let a = inner.input("a", 1);
+        // Panic
+        r.drive_next(m.high());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create a memory called \"mem\" in module \"A\", because this module is finalized."
+    )]
+    fn finalized_mem_error() {
+        let c = Context::new();
+
+        let m = c.module_with("a", "A", |_| {});
 
         // Panic
-        a.drive(m.input("i1", 32));
+        let _ = m.mem("mem", 1, 1);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create an instance of module \"Inner\" called \"inner\" in module \"A\", because this module is finalized."
+    )]
+    fn finalized_instance_error() {
+        let c = Context::new();
+
+        let m = c.module_with("a", "A", |_| {});
+
+        // Panic
+        let _ = m.module("inner", "Inner");
     }
 }
@@ -0,0 +1,209 @@
+use super::module::*;
+
+use std::cell::{Cell, RefCell};
+
+type Definition<'a> = Box<dyn Fn(&'a Module<'a>) + 'a>;
+
+/// A [`Module`] whose contents are specified separately from its instances, created by the [`Context::declare_module`](super::Context::declare_module) method.
+///
+/// Instances of a `ModuleDeclaration` can be created with [`ModuleParent::instance`](super::ModuleParent::instance) before the module is defined with [`define`], so modules that depend on each other don't need to be built in any particular order. Each instance is an empty [`Module`] until the module is defined, at which point the definition is used to build the contents of all existing instances. Instances created after that are built as soon as they're created.
+///
+/// Since an instance's inputs and outputs don't exist until the module is defined, uses of them (and the bit width checks that go with them) can be deferred with [`on_defined`].
+///
+/// Code generation panics if any instance of a `ModuleDeclaration` in the module hierarchy was never defined.
+///
+/// # Examples
+///
+/// ```
+/// use kaze::*;
+///
+/// let c = Context::new();
+///
+/// let inverter = c.declare_module("Inverter");
+///
+/// // The parent module is built before the module it instantiates is defined
+/// let m = c.module("m", "MyModule");
+/// let i = m.input("i", 8);
+/// let inv = m.instance("inv", &inverter);
+/// inverter.on_defined(move || {
+///     inv.get_input("i").drive(i);
+///     m.output("o", inv.get_output("o"));
+/// });
+///
+/// // Builds `inv`, then performs the deferred operations
+/// inverter.define(|m| {
+///     m.output("o", !m.input("i", 8));
+/// });
+/// ```
+///
+/// [`define`]: Self::define
+/// [`on_defined`]: Self::on_defined
+#[must_use]
+pub struct ModuleDeclaration<'a> {
+    pub(crate) data: &'a ModuleDeclarationData,
+
+    build: RefCell<Option<Definition<'a>>>,
+    undefined_instances: RefCell<Vec<&'a Module<'a>>>,
+    pending: RefCell<Vec<Box<dyn FnOnce() + 'a>>>,
+}
+
+impl<'a> ModuleDeclaration<'a> {
+    pub(super) fn new(data: &'a ModuleDeclarationData) -> ModuleDeclaration<'a> {
+        ModuleDeclaration {
+            data,
+
+            build: RefCell::new(None),
+            undefined_instances: RefCell::new(Vec::new()),
+            pending: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Returns `true` if this module has been defined with [`define`](Self::define).
+    pub fn is_defined(&self) -> bool {
+        self.data.defined.get()
+    }
+
+    /// Defines the contents of this module.
+    ///
+    /// `build` is called with each instance of this module created so far, and with each instance created later as soon as it's created. Then, all uses of this module deferred with [`on_defined`](Self::on_defined) are performed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this module is already defined.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let inverter = c.declare_module("Inverter");
+    /// inverter.define(|m| {
+    ///     m.output("o", !m.input("i", 1));
+    /// });
+    /// ```
+    pub fn define(&self, build: impl Fn(&'a Module<'a>) + 'a) {
+        if self.is_defined() {
+            panic!(
+                "Cannot define module \"{}\", because it's already defined.",
+                self.data.name
+            );
+        }
+        self.data.defined.set(true);
+        *self.build.borrow_mut() = Some(Box::new(build));
+
+        let instances = self.undefined_instances.replace(Vec::new());
+        if let Some(ref build) = *self.build.borrow() {
+            for instance in instances {
+                build(instance);
+            }
+        }
+
+        let pending = self.pending.replace(Vec::new());
+        for f in pending {
+            f();
+        }
+    }
+
+    /// Calls `f` as soon as this module is defined, or immediately if it's already defined.
+    ///
+    /// Deferred calls are performed in the order they were specified, after all existing instances of this module have been built.
+    pub fn on_defined(&self, f: impl FnOnce() + 'a) {
+        if self.is_defined() {
+            f();
+        } else {
+            self.pending.borrow_mut().push(Box::new(f));
+        }
+    }
+
+    pub(super) fn add_instance(&self, instance: &'a Module<'a>) {
+        instance.declaration.set(Some(self.data));
+        match *self.build.borrow() {
+            Some(ref build) => build(instance),
+            None => self.undefined_instances.borrow_mut().push(instance),
+        }
+    }
+}
+
+pub(crate) struct ModuleDeclarationData {
+    pub name: String,
+    pub defined: Cell<bool>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn instances_built_when_defined() {
+        let c = Context::new();
+
+        // The parent is built entirely before the module it instantiates is defined
+        let b = c.declare_module("B");
+        let a = c.module("a", "A");
+        let i = a.input("i", 8);
+        let b1 = a.instance("b1", &b);
+        let b2 = a.instance("b2", &b);
+        b.on_defined(move || {
+            b1.get_input("i").drive(i);
+            b2.get_input("i").drive(b1.get_output("o"));
+            a.output("o", b2.get_output("o"));
+        });
+        assert!(!b.is_defined());
+        assert!(b1.inputs.borrow().is_empty());
+        assert!(a.outputs.borrow().is_empty());
+
+        b.define(|m| {
+            m.output("o", !m.input("i", 8));
+        });
+
+        assert!(b.is_defined());
+        assert_eq!(b1.name, "B");
+        assert_eq!(b2.outputs.borrow()["o"].data.bit_width, 8);
+        assert_eq!(a.outputs.borrow()["o"].data.bit_width, 8);
+        assert_eq!(validate(a), Ok(()));
+    }
+
+    #[test]
+    fn instance_built_immediately_when_already_defined() {
+        let c = Context::new();
+
+        let b = c.declare_module("B");
+        b.define(|m| {
+            m.output("o", m.input("i", 4));
+        });
+
+        let a = c.module("a", "A");
+        let b1 = a.instance("b1", &b);
+        assert_eq!(b1.outputs.borrow()["o"].data.bit_width, 4);
+
+        b.on_defined(move || {
+            a.output("o", b1.get_output("o"));
+        });
+        assert_eq!(a.outputs.borrow()["o"].data.bit_width, 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot declare module \"B\", because it's already declared.")]
+    fn declare_twice_error() {
+        let c = Context::new();
+
+        let _ = c.declare_module("B");
+
+        // Panic
+        let _ = c.declare_module("B");
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot define module \"B\", because it's already defined.")]
+    fn define_twice_error() {
+        let c = Context::new();
+
+        let b = c.declare_module("B");
+        b.define(|_| {});
+
+        // Panic
+        b.define(|_| {});
+    }
+}
@@ -0,0 +1,171 @@
+/// A set of named integer parameters for a [`Module`], used with [`ModuleParent::module_parameterized`].
+///
+/// Parameters are kept in the order they're added, which is also the order in which they appear in derived module names.
+///
+/// # Examples
+///
+/// ```
+/// use kaze::*;
+///
+/// let params = ModuleParams::new().with("w", 32).with("depth", 16);
+///
+/// assert_eq!(params.get("w"), 32);
+/// assert_eq!(params.module_name("Fifo"), "Fifo_w32_depth16");
+/// ```
+///
+/// [`Module`]: super::Module
+/// [`ModuleParent::module_parameterized`]: super::ModuleParent::module_parameterized
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ModuleParams {
+    values: Vec<(String, u64)>,
+}
+
+impl ModuleParams {
+    /// Creates a new, empty set of `ModuleParams`.
+    pub fn new() -> ModuleParams {
+        ModuleParams::default()
+    }
+
+    /// Adds a parameter called `name` with the given `value`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is empty or contains characters other than ASCII letters and underscores, or if a parameter called `name` was already added. Names can't contain digits, so that each parameter's name and value can be told apart in [derived module names](Self::module_name).
+    pub fn with(mut self, name: impl Into<String>, value: u64) -> ModuleParams {
+        let name = name.into();
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphabetic() || c == '_') {
+            panic!("Cannot add a module parameter called \"{}\". Module parameter names must be non-empty, and consist only of ASCII letters and underscores.", name);
+        }
+        if self.values.iter().any(|(n, _)| *n == name) {
+            panic!(
+                "Cannot add a module parameter called \"{}\", because a parameter with the same name was already added.",
+                name
+            );
+        }
+        self.values.push((name, value));
+        self
+    }
+
+    /// Returns the value of the parameter called `name`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there's no parameter called `name`.
+    pub fn get(&self, name: &str) -> u64 {
+        self.values
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|&(_, value)| value)
+            .unwrap_or_else(|| {
+                panic!(
+                    "Cannot get module parameter \"{}\", because no parameter with this name was added.",
+                    name
+                )
+            })
+    }
+
+    /// Returns the module name derived from `name` and these parameters, which is `name` followed by `_<parameter name><value>` for each parameter.
+    ///
+    /// Since parameter names can't contain digits, each name ends where its value starts, and each value ends where the next `_` starts. Distinct parameter sets therefore result in distinct module names, so each set gets its own definition in generated Verilog code.
+    pub fn module_name(&self, name: &str) -> String {
+        let mut ret = name.to_string();
+        for (param_name, value) in self.values.iter() {
+            ret.push_str(&format!("_{}{}", param_name, value));
+        }
+        ret
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    fn adder<'a>(
+        instance_name: &str,
+        p: &'a impl ModuleParent<'a>,
+        bit_width: u32,
+    ) -> &'a Module<'a> {
+        p.module_parameterized(
+            instance_name,
+            "Adder",
+            ModuleParams::new().with("w", bit_width as _),
+            |m, params| {
+                let bit_width = params.get("w") as u32;
+                let a = m.input("a", bit_width);
+                let b = m.input("b", bit_width);
+                m.output("o", a + b);
+            },
+        )
+    }
+
+    #[test]
+    fn module_names() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        let a = adder("a", m, 8);
+        let b = adder("b", m, 8);
+        let c = adder("c", m, 16);
+
+        assert_eq!(a.name, "Adder_w8");
+        assert_eq!(b.name, "Adder_w8");
+        assert_eq!(c.name, "Adder_w16");
+        assert_eq!(c.outputs.borrow()["o"].data.bit_width, 16);
+    }
+
+    #[test]
+    fn empty_params_keep_name() {
+        assert_eq!(ModuleParams::new().module_name("Fifo"), "Fifo");
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot add a module parameter called \"w\", because a parameter with the same name was already added."
+    )]
+    fn duplicate_param_error() {
+        // Panic
+        let _ = ModuleParams::new().with("w", 8).with("w", 16);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot add a module parameter called \"8w\". Module parameter names must be non-empty, and consist only of ASCII letters and underscores."
+    )]
+    fn invalid_param_name_error() {
+        // Panic
+        let _ = ModuleParams::new().with("8w", 8);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot add a module parameter called \"a1\". Module parameter names must be non-empty, and consist only of ASCII letters and underscores."
+    )]
+    fn param_name_with_digit_error() {
+        // Otherwise, ("a1", 2) would have the same module name as ("a", 12)
+        assert_eq!(ModuleParams::new().with("a", 12).module_name("X"), "X_a12");
+
+        // Panic
+        let _ = ModuleParams::new().with("a1", 2);
+    }
+
+    #[test]
+    fn distinct_module_names() {
+        let a = ModuleParams::new().with("a", 1).with("b", 2);
+        let a_b = ModuleParams::new().with("a_b", 12);
+        let a_ = ModuleParams::new().with("a_", 1).with("_b", 2);
+        assert_eq!(a.module_name("X"), "X_a1_b2");
+        assert_eq!(a_b.module_name("X"), "X_a_b12");
+        assert_eq!(a_.module_name("X"), "X_a_1__b2");
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot get module parameter \"depth\", because no parameter with this name was added."
+    )]
+    fn missing_param_error() {
+        let params = ModuleParams::new().with("w", 8);
+
+        // Panic
+        let _ = params.get("depth");
+    }
+}
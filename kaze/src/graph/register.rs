@@ -1,9 +1,12 @@
+use super::attribute::*;
+use super::clock_divider::*;
 use super::constant::*;
 use super::internal_signal::*;
 use super::module::*;
+use super::reset_domain::*;
 use super::signal::*;
 
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::ptr;
 
 /// A hardware register, created by the [`Module::reg`] method.
@@ -12,8 +15,11 @@ use std::ptr;
 ///
 /// It always has a current value represented by the [`value`] field (often referred to as `Q`) and a next value specified by the [`drive_next`] method (often referred to as `D`).
 /// It will hold its [`value`] until a positive edge of its [`Module`]'s implicit clock occurs, at which point [`value`] will be updated to reflect the next value.
+/// A register created with [`Module::reg_in_domain`] is instead clocked by a named clock, and a register created with [`ClockDivider::reg`] is clocked by a divided clock.
+/// A register can be updated on negative clock edges instead using the [`clock_edge`] method.
 ///
 /// Optionally, it also has a default value specified by the [`default_value`] method. If at any time its [`Module`]'s implicit reset is driven low, the register's [`value`] will reflect the default value.
+/// A register can instead be reset by a named [`ResetDomain`] using the [`reset_domain`] method.
 /// Default values are used to provide a known register state on system power-on and reset, but are often omitted to reduce combinational logic (which ultimately is how default values are typically implemented), especially for registers on timing-critical data paths.
 ///
 /// # Examples
@@ -31,8 +37,10 @@ use std::ptr;
 /// m.output("my_output", my_reg);
 /// ```
 ///
+/// [`clock_edge`]: Self::clock_edge
 /// [`default_value`]: Self::default_value
 /// [`drive_next`]: Self::drive_next
+/// [`reset_domain`]: Self::reset_domain
 /// [`value`]: Self::value
 #[must_use]
 pub struct Register<'a> {
@@ -42,9 +50,27 @@ pub struct Register<'a> {
 }
 
 impl<'a> Register<'a> {
+    /// Returns this `Register`'s name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let my_reg = m.reg("my_reg", 32);
+    /// assert_eq!(my_reg.name(), "my_reg");
+    /// ```
+    pub fn name(&self) -> &str {
+        &self.data.name
+    }
+
     /// Specifies the default value for this `Register`.
     ///
-    /// This `Register`'s [`value`] will reflect this default value when this `Register`'s [`Module`]'s implicit reset is asserted.
+    /// This `Register`'s [`value`] will reflect this default value when this `Register`'s [`Module`]'s implicit reset is asserted (or, if this `Register` was assigned to a named reset domain with [`reset_domain`](Self::reset_domain), when that domain's reset is asserted).
     ///
     /// By default, a `Register` does not have a default value, and it is not required to specify one. If a default value is not specified, then this `Register`'s [`value`] will not change when its [`Module`]'s implicit reset is asserted.
     ///
@@ -75,10 +101,114 @@ impl<'a> Register<'a> {
         let value = value.into();
         let required_bits = value.required_bits();
         if required_bits > self.data.bit_width {
-            let numeric_value = value.numeric_value();
-            panic!("Cannot fit the specified value '{}' into register \"{}\"'s bit width '{}'. The value '{}' requires a bit width of at least {} bit(s).", numeric_value, self.data.name, self.data.bit_width, numeric_value, required_bits);
+            let value = value.describe();
+            panic!("Cannot fit the specified value '{}' into register \"{}\"'s bit width '{}'. The value '{}' requires a bit width of at least {} bit(s).", value, self.data.name, self.data.bit_width, value, required_bits);
         }
-        *self.data.initial_value.borrow_mut() = Some(value);
+        *self.data.initial_value.borrow_mut() = Some(value.to_bit_pattern(self.data.bit_width));
+    }
+
+    /// Returns this `Register`'s default value, or `None` if it doesn't have one.
+    ///
+    /// The value is returned as the bit pattern stored in this `Register` on reset, so negative default values are returned in two's complement form, truncated to this `Register`'s bit width.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let my_reg = m.reg("my_reg", 8);
+    /// assert_eq!(my_reg.default_value_get(), None);
+    /// my_reg.default_value(-1i32);
+    /// assert_eq!(my_reg.default_value_get(), Some(0xff));
+    /// ```
+    pub fn default_value_get(&self) -> Option<u128> {
+        self.data
+            .initial_value
+            .borrow()
+            .as_ref()
+            .map(|value| value.numeric_value())
+    }
+
+    /// Assigns this `Register` to the reset domain specified by `domain`.
+    ///
+    /// By default, a `Register` belongs to its [`Module`]'s implicit reset domain. Once assigned to `domain`, this `Register`'s [`value`] will reflect its default value only when `domain`'s reset is asserted, and it will no longer be affected by the implicit reset.
+    ///
+    /// Note that this only has an effect if this `Register` also has a [default value](Self::default_value).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` and `domain` belong to different [`Module`]s, or if this `Register` has already been assigned to a reset domain.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let soft = m.reset_domain("soft");
+    ///
+    /// let my_reg = m.reg("my_reg", 32);
+    /// my_reg.default_value(0xfadebabeu32);
+    /// my_reg.reset_domain(soft); // my_reg is only reset when the "soft" reset is asserted
+    /// my_reg.drive_next(!my_reg);
+    /// m.output("my_output", my_reg);
+    /// ```
+    ///
+    /// [`value`]: Self::value
+    pub fn reset_domain(&'a self, domain: &'a ResetDomain<'a>) {
+        if !ptr::eq(self.data.module, domain.module) {
+            panic!("Attempted to assign register \"{}\" in module \"{}\" to reset domain \"{}\" from another module (\"{}\").", self.data.name, self.data.module.name, domain.name, domain.module.name);
+        }
+        if self.data.reset_domain.borrow().is_some() {
+            panic!("Attempted to assign register \"{}\" in module \"{}\" to a reset domain, but this register has already been assigned to a reset domain.", self.data.name, self.data.module.name);
+        }
+        *self.data.reset_domain.borrow_mut() = Some(domain);
+    }
+
+    /// Specifies the clock edge this `Register` is updated on.
+    ///
+    /// By default, a `Register` is updated on positive edges of its clock ([`Edge::Pos`]). A `Register` updated on negative edges ([`Edge::Neg`]) takes its next value halfway through each clock cycle, which allows data to be transferred between registers on opposite edges within a single cycle (two-phase clocking).
+    ///
+    /// In generated Verilog code, such registers are updated in `always @(negedge ...)` blocks. Generated simulators have a separate `negedge_<clock>` method for them (alongside the usual `posedge_<clock>` method), which only updates registers on negative edges of that clock. A full clock cycle is simulated by calling `prop`, `posedge_<clock>`, `prop`, `negedge_<clock>`, and then `prop` again.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `edge` is [`Edge::Neg`] and this `Register` is clocked by a divided clock (see [`ClockDivider::reg`]).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let i = m.input("i", 8);
+    ///
+    /// // Captures `i` on positive clock edges
+    /// let pos = m.reg("pos", 8);
+    /// pos.drive_next(i);
+    ///
+    /// // Captures `pos` half a cycle later, on negative clock edges
+    /// let neg = m.reg("neg", 8);
+    /// neg.clock_edge(Edge::Neg);
+    /// neg.drive_next(pos);
+    ///
+    /// m.output("o", neg);
+    /// ```
+    pub fn clock_edge(&self, edge: Edge) {
+        if edge == Edge::Neg && self.data.divided_clock.is_some() {
+            panic!("Attempted to update register \"{}\" in module \"{}\" on negative clock edges, but this register is clocked by a divided clock.", self.data.name, self.data.module.name);
+        }
+        self.data.edge.set(edge);
     }
 
     /// Specifies the next value for this `Register`.
@@ -108,16 +238,76 @@ impl<'a> Register<'a> {
     pub fn drive_next(&'a self, n: &'a dyn Signal<'a>) {
         let n = n.internal_signal();
         if !ptr::eq(self.data.module, n.module) {
-            panic!("Attempted to drive register \"{}\"'s next value with a signal from another module.", self.data.name);
+            panic!("Attempted to drive register \"{}\"'s next value in module \"{}\" with a signal from another module (\"{}\").", self.data.name, self.data.module.name, n.module.name);
         }
         if n.bit_width() != self.data.bit_width {
-            panic!("Attempted to drive register \"{}\"'s next value with a signal that has a different bit width than the register ({} and {}, respectively).", self.data.name, n.bit_width(), self.data.bit_width);
+            panic!("Attempted to drive register \"{}\"'s next value in module \"{}\" with a signal that has a different bit width than the register ({} and {}, respectively).", self.data.name, self.data.module.name, n.bit_width(), self.data.bit_width);
         }
         if self.data.next.borrow().is_some() {
             panic!("Attempted to drive register \"{}\"'s next value in module \"{}\", but this register's next value is already driven.", self.data.name, self.data.module.name);
         }
         *self.data.next.borrow_mut() = Some(n);
     }
+
+    /// Returns `true` if this `Register`'s next value has been driven with [`drive_next`](Self::drive_next).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let my_reg = m.reg("my_reg", 32);
+    /// assert!(!my_reg.is_driven());
+    /// my_reg.drive_next(!my_reg);
+    /// assert!(my_reg.is_driven());
+    /// ```
+    pub fn is_driven(&self) -> bool {
+        self.data.next.borrow().is_some()
+    }
+
+    /// Attaches an [`Attribute`] with the given `namespace`, `key`, and `value` to this `Register`, replacing the value of any existing attribute with the same `namespace` and `key`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `namespace` is [`RESERVED_ATTRIBUTE_NAMESPACE`], or if `namespace` or `key` is not a valid identifier (it must be non-empty, consist only of ASCII alphanumeric characters and underscores, and not start with a digit).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let my_reg = m.reg("my_reg", 32);
+    /// my_reg.attribute("xilinx", "shreg_extract", "no");
+    /// my_reg.drive_next(m.input("i", 32));
+    /// m.output("my_output", my_reg);
+    /// ```
+    pub fn attribute(
+        &'a self,
+        namespace: impl Into<String>,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) {
+        add_user_attribute(
+            &self.data.attributes,
+            namespace.into(),
+            key.into(),
+            value.into(),
+            || {
+                format!(
+                    "register \"{}\" in module \"{}\"",
+                    self.data.name, self.data.module.name
+                )
+            },
+        );
+    }
 }
 
 pub(crate) struct RegisterData<'a> {
@@ -125,8 +315,25 @@ pub(crate) struct RegisterData<'a> {
 
     pub name: String,
     pub initial_value: RefCell<Option<Constant>>,
+    pub reset_domain: RefCell<Option<&'a ResetDomain<'a>>>,
+    /// The name of the clock this register is clocked by, or `None` for the implicit clock.
+    pub clock: Option<String>,
+    /// The divider whose divided clock this register is clocked by, if any.
+    pub divided_clock: Option<&'a ClockDivider<'a>>,
+    pub edge: Cell<Edge>,
     pub bit_width: u32,
     pub next: RefCell<Option<&'a InternalSignal<'a>>>,
+    pub attributes: RefCell<Vec<Attribute>>,
+}
+
+/// A clock edge, specified with [`Register::clock_edge`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Edge {
+    /// The positive (rising) edge.
+    #[default]
+    Pos,
+    /// The negative (falling) edge.
+    Neg,
 }
 
 impl<'a> GetInternalSignal<'a> for Register<'a> {
@@ -213,7 +420,21 @@ mod tests {
 
     #[test]
     #[should_panic(
-        expected = "Attempted to drive register \"r\"'s next value with a signal from another module."
+        expected = "Cannot fit the specified value '-129' into register \"r\"'s bit width '8'. The value '-129' requires a bit width of at least 9 bit(s)."
+    )]
+    fn default_value_signed_value_cannot_fit_into_bit_width_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let r = m.reg("r", 8);
+
+        // Panic
+        r.default_value(-129i32);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Attempted to drive register \"r\"'s next value in module \"B\" with a signal from another module (\"A\")."
     )]
     fn drive_next_separate_module_error() {
         let c = Context::new();
@@ -230,7 +451,7 @@ mod tests {
 
     #[test]
     #[should_panic(
-        expected = "Attempted to drive register \"r\"'s next value with a signal that has a different bit width than the register (5 and 3, respectively)."
+        expected = "Attempted to drive register \"r\"'s next value in module \"A\" with a signal that has a different bit width than the register (5 and 3, respectively)."
     )]
     fn drive_next_incompatible_bit_width_error() {
         let c = Context::new();
@@ -259,4 +480,141 @@ mod tests {
         // Panic
         r.drive_next(i);
     }
+
+    #[test]
+    fn default_value_get() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let r1 = m.reg("r1", 32);
+        let r2 = m.reg("r2", 1);
+        let r3 = m.reg("r3", 4);
+
+        assert_eq!(r1.default_value_get(), None);
+        r1.default_value(0xfadebabeu32);
+        r2.default_value(true);
+        r3.default_value(-8i8);
+
+        assert_eq!(r1.default_value_get(), Some(0xfadebabe));
+        assert_eq!(r2.default_value_get(), Some(1));
+        assert_eq!(r3.default_value_get(), Some(0x8));
+    }
+
+    #[test]
+    fn is_driven() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let r = m.reg("r", 32);
+        let i = m.input("i", 32);
+
+        assert!(!r.is_driven());
+        r.drive_next(i);
+        assert!(r.is_driven());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Attempted to assign register \"r\" in module \"A\" to reset domain \"soft\" from another module (\"B\")."
+    )]
+    fn reset_domain_separate_module_error() {
+        let c = Context::new();
+
+        let m1 = c.module("a", "A");
+        let r = m1.reg("r", 32);
+
+        let m2 = c.module("b", "B");
+        let soft = m2.reset_domain("soft");
+
+        // Panic
+        r.reset_domain(soft);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Attempted to assign register \"r\" in module \"A\" to a reset domain, but this register has already been assigned to a reset domain."
+    )]
+    fn reset_domain_already_specified_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let r = m.reg("r", 32);
+        let soft = m.reset_domain("soft");
+
+        r.reset_domain(soft);
+
+        // Panic
+        r.reset_domain(soft);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Attempted to update register \"r\" in module \"A\" on negative clock edges, but this register is clocked by a divided clock."
+    )]
+    fn clock_edge_divided_clock_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let div2 = m.clock_divider("div2", 2);
+        let r = div2.reg("r", 32);
+
+        r.clock_edge(Edge::Pos);
+
+        // Panic
+        r.clock_edge(Edge::Neg);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot add attribute \"kaze.keep\" to register \"r\" in module \"A\", because the \"kaze\" attribute namespace is reserved for kaze's internal use."
+    )]
+    fn attribute_reserved_namespace_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let r = m.reg("r", 32);
+
+        // Panic
+        r.attribute("kaze", "keep", "true");
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot add attribute \"my_tool.1st\" to register \"r\" in module \"A\". Attribute namespaces and keys must be non-empty, consist only of ASCII alphanumeric characters and underscores, and must not start with a digit."
+    )]
+    fn attribute_invalid_key_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let r = m.reg("r", 32);
+
+        // Panic
+        r.attribute("my_tool", "1st", "true");
+    }
+
+    #[test]
+    fn attribute_replaces_existing_value() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let r = m.reg("r", 32);
+
+        r.attribute("my_tool", "a", "1");
+        r.attribute("my_tool", "b", "2");
+        r.attribute("other_tool", "a", "3");
+        r.attribute("my_tool", "a", "4");
+
+        let attributes = r.attributes();
+        assert_eq!(attributes.len(), 3);
+        assert_eq!(
+            attributes[0],
+            Attribute {
+                namespace: "my_tool".into(),
+                key: "a".into(),
+                value: "4".into(),
+            }
+        );
+        assert_eq!(attributes[1].key, "b");
+        assert_eq!(attributes[2].namespace, "other_tool");
+    }
 }
@@ -0,0 +1,43 @@
+use super::module::*;
+
+/// A named reset domain, created by the [`Module::reset_domain`] method.
+///
+/// By default, all [`Register`]s with a [default value](super::Register::default_value) belong to their [`Module`]'s implicit reset domain.
+/// A [`Register`] can be moved into a named reset domain with the [`Register::reset_domain`] method, in which case it will only be reset when that domain's reset is asserted.
+/// This allows parts of a design (for example, configuration registers in an always-on domain) to keep their values when the rest of the design is reset.
+///
+/// Reset domains are identified by their name; domains with the same name in different [`Module`]s in a hierarchy share the same reset, just like all [`Module`]s share the same implicit reset.
+/// In generated Rust simulator code, each named domain gets its own `reset_<name>` method alongside `reset` (which only resets the implicit domain), and in generated Verilog code, each named domain gets its own active-low `reset_<name>_n` port alongside `reset_n`.
+///
+/// # Examples
+///
+/// ```
+/// use kaze::*;
+///
+/// let c = Context::new();
+///
+/// let m = c.module("m", "MyModule");
+///
+/// let soft = m.reset_domain("soft");
+///
+/// let config = m.reg("config", 8);
+/// config.default_value(0u32); // Reset by the implicit reset
+/// config.drive_next(m.input("config_in", 8));
+///
+/// let state = m.reg("state", 8);
+/// state.default_value(0u32);
+/// state.reset_domain(soft); // Reset only by the "soft" reset
+/// state.drive_next(m.input("state_in", 8));
+///
+/// m.output("config_out", config);
+/// m.output("state_out", state);
+/// ```
+///
+/// [`Register`]: super::Register
+/// [`Register::reset_domain`]: super::Register::reset_domain
+#[must_use]
+pub struct ResetDomain<'a> {
+    pub(crate) module: &'a Module<'a>,
+
+    pub(crate) name: String,
+}
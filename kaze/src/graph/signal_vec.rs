@@ -0,0 +1,332 @@
+use super::module::*;
+use super::signal::*;
+
+use std::ptr;
+
+/// An ordered collection of [`Signal`]s with the same bit width, created by the [`Module::signal_vec`] method.
+///
+/// A `SignalVec` is a convenience for describing regular structures such as shift register chains or banked storage. It doesn't correspond to any construct in generated code; all of its operations are built from existing [`Signal`] operations.
+///
+/// # Examples
+///
+/// ```
+/// use kaze::*;
+///
+/// let c = Context::new();
+///
+/// let m = c.module("m", "MyModule");
+///
+/// let banks = m.signal_vec((0..4).map(|i| m.input(format!("bank{}", i), 8)));
+/// let inverted = banks.map(|bank| !bank);
+/// m.output("selected", inverted.select(m.input("bank_index", 2)));
+/// m.output("all", banks.concat_all()); // 32 bits, with bank0 in the upper bits
+/// ```
+#[must_use]
+pub struct SignalVec<'a> {
+    module: &'a Module<'a>,
+    signals: Vec<&'a dyn Signal<'a>>,
+}
+
+impl<'a> SignalVec<'a> {
+    pub(super) fn new(module: &'a Module<'a>, signals: Vec<&'a dyn Signal<'a>>) -> SignalVec<'a> {
+        if signals.is_empty() {
+            panic!("Attempted to create an empty signal vector.");
+        }
+        let bit_width = signals[0].bit_width();
+        for (i, signal) in signals.iter().enumerate() {
+            check_same_module(
+                "SignalVec",
+                ("self", module),
+                (&format!("signals[{}]", i), signal.internal_signal().module),
+            );
+            if signal.bit_width() != bit_width {
+                panic!("Attempted to create a signal vector with elements with different bit widths (element 0 has {} bit(s), but element {} has {} bit(s)). All elements of a signal vector must have the same bit width.", bit_width, i, signal.bit_width());
+            }
+        }
+        SignalVec { module, signals }
+    }
+
+    /// Returns the number of elements in this `SignalVec`.
+    pub fn len(&self) -> usize {
+        self.signals.len()
+    }
+
+    /// Returns `true` if this `SignalVec` has no elements.
+    ///
+    /// Since a `SignalVec` can't be empty, this always returns `false`, and is only provided for consistency with other collections.
+    pub fn is_empty(&self) -> bool {
+        self.signals.is_empty()
+    }
+
+    /// Returns the bit width of each element in this `SignalVec`.
+    pub fn bit_width(&self) -> u32 {
+        self.signals[0].bit_width()
+    }
+
+    /// Returns the elements of this `SignalVec`.
+    pub fn signals(&self) -> &[&'a dyn Signal<'a>] {
+        &self.signals
+    }
+
+    /// Returns the element at index `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than or equal to [`len`](Self::len).
+    pub fn get_const(&self, index: usize) -> &'a dyn Signal<'a> {
+        if index >= self.signals.len() {
+            panic!(
+                "Attempted to get element {} of a signal vector with {} element(s).",
+                index,
+                self.signals.len()
+            );
+        }
+        self.signals[index]
+    }
+
+    /// Creates a [`Signal`] that represents the value of the element at the index represented by `index`.
+    ///
+    /// The selection is implemented as a balanced tree of [`mux`](Signal::mux)es, where each level of the tree is controlled by one bit of `index`, so its depth grows logarithmically with [`len`](Self::len). If `index` represents an index that's greater than or equal to [`len`](Self::len), the last element is selected.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` belongs to a different [`Module`] than this `SignalVec`, or if `index` doesn't have enough bits to represent the index of each element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kaze::*;
+    ///
+    /// let c = Context::new();
+    ///
+    /// let m = c.module("m", "MyModule");
+    ///
+    /// let taps = m.signal_vec((0..5).map(|i| m.input(format!("tap{}", i), 16)));
+    /// m.output("tap", taps.select(m.input("tap_index", 3))); // tap4 if tap_index is greater than 4
+    /// ```
+    pub fn select(&self, index: &'a dyn Signal<'a>) -> &'a dyn Signal<'a> {
+        check_same_module(
+            "SignalVec::select",
+            ("self", self.module),
+            ("index", index.internal_signal().module),
+        );
+        let num_index_bits = usize::BITS - (self.signals.len() - 1).leading_zeros();
+        if index.bit_width() < num_index_bits {
+            panic!("Attempted to select an element of a signal vector with {} element(s) with an index signal with {} bit(s), but at least {} bit(s) are required to represent the index of each element.", self.signals.len(), index.bit_width(), num_index_bits);
+        }
+
+        // Padding with the last element makes the tree complete, and makes out-of-range indices select the last element
+        let last = self.signals[self.signals.len() - 1];
+        let mut level = (0..1usize << num_index_bits)
+            .map(|i| self.signals.get(i).copied().unwrap_or(last))
+            .collect::<Vec<_>>();
+        for bit in 0..num_index_bits {
+            let cond = index.bit(bit);
+            level = level
+                .chunks(2)
+                .map(|pair| select_between(cond, pair[1], pair[0]))
+                .collect();
+        }
+        let mut ret = level[0];
+        if index.bit_width() > num_index_bits {
+            let high_bits = index.bits(index.bit_width() - 1, num_index_bits);
+            let is_out_of_range = high_bits.ne(self.module.lit(0u32, high_bits.bit_width()));
+            ret = select_between(is_out_of_range, last, ret);
+        }
+        ret
+    }
+
+    /// Creates a new `SignalVec` whose elements are the results of calling `f` with each element of this `SignalVec`, in order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the signals returned by `f` belong to a different [`Module`] than this `SignalVec`, or if their bit widths aren't all equal.
+    pub fn map(&self, f: impl FnMut(&'a dyn Signal<'a>) -> &'a dyn Signal<'a>) -> SignalVec<'a> {
+        SignalVec::new(self.module, self.signals.iter().copied().map(f).collect())
+    }
+
+    /// Concatenates all elements of this `SignalVec`, like [`Module::concat_all`], so that the first element occupies the upper bits of the result.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the total bit width of all elements is greater than [`MAX_SIGNAL_BIT_WIDTH`].
+    pub fn concat_all(&self) -> &'a dyn Signal<'a> {
+        self.module.concat_all(&self.signals)
+    }
+}
+
+/// Creates a [`mux`](Signal::mux) between `when_true` and `when_false`, unless they're the same signal, in which case no mux is needed.
+fn select_between<'a>(
+    cond: &'a dyn Signal<'a>,
+    when_true: &'a dyn Signal<'a>,
+    when_false: &'a dyn Signal<'a>,
+) -> &'a dyn Signal<'a> {
+    if ptr::eq(when_true.internal_signal(), when_false.internal_signal()) {
+        when_true
+    } else {
+        cond.mux(when_true, when_false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::internal_signal::SignalData;
+    use crate::*;
+
+    fn lit_value<'a>(s: &'a dyn Signal<'a>) -> u128 {
+        let optimizer = crate::optimizer::Optimizer::new(true);
+        match optimizer.optimize(s.internal_signal()).data {
+            SignalData::Lit { ref value, .. } => value.numeric_value(),
+            _ => panic!("Expected a constant signal."),
+        }
+    }
+
+    fn mux_depth<'a>(s: &'a dyn Signal<'a>) -> u32 {
+        fn depth<'a>(s: &'a graph::internal_signal::InternalSignal<'a>) -> u32 {
+            match s.data {
+                SignalData::Mux {
+                    when_true,
+                    when_false,
+                    ..
+                } => 1 + depth(when_true).max(depth(when_false)),
+                _ => 0,
+            }
+        }
+        depth(s.internal_signal())
+    }
+
+    fn elements<'a>(m: &'a Module<'a>, len: u32) -> SignalVec<'a> {
+        m.signal_vec((0..len).map(|i| m.lit(0x10 + i, 8)))
+    }
+
+    #[test]
+    fn select_7() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let v = elements(m, 7);
+
+        assert_eq!(mux_depth(v.select(m.input("i", 3))), 3);
+        for index in 0..8u32 {
+            let expected = 0x10 + index.min(6) as u128;
+            assert_eq!(lit_value(v.select(m.lit(index, 3))), expected);
+        }
+        // Wider indices are compared against the number of elements as a whole
+        for index in 0..32u32 {
+            let expected = 0x10 + index.min(6) as u128;
+            assert_eq!(lit_value(v.select(m.lit(index, 5))), expected);
+        }
+    }
+
+    #[test]
+    fn select_16() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let v = elements(m, 16);
+
+        assert_eq!(mux_depth(v.select(m.input("i", 4))), 4);
+        for index in 0..16u32 {
+            assert_eq!(lit_value(v.select(m.lit(index, 4))), 0x10 + index as u128);
+        }
+    }
+
+    #[test]
+    fn select_1() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let v = elements(m, 1);
+
+        let i = m.input("i", 2);
+        assert!(std::ptr::eq(
+            v.select(i).internal_signal(),
+            v.get_const(0).internal_signal()
+        ));
+    }
+
+    #[test]
+    fn get_const_map_concat_all() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let v = elements(m, 3);
+
+        assert_eq!(v.len(), 3);
+        assert!(!v.is_empty());
+        assert_eq!(v.bit_width(), 8);
+        assert_eq!(lit_value(v.get_const(2)), 0x12);
+        assert_eq!(lit_value(v.concat_all()), 0x101112);
+
+        let mapped = v.map(|s| s.concat(m.low()));
+        assert_eq!(mapped.bit_width(), 9);
+        assert_eq!(
+            lit_value(mapped.concat_all()),
+            0x10 << 19 | 0x11 << 10 | 0x12 << 1
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Attempted to create an empty signal vector.")]
+    fn empty_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+
+        // Panic
+        let _ = m.signal_vec(Vec::<&dyn Signal>::new());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Attempted to create a signal vector with elements with different bit widths (element 0 has 8 bit(s), but element 1 has 4 bit(s)). All elements of a signal vector must have the same bit width."
+    )]
+    fn bit_width_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+
+        // Panic
+        let _ = m.signal_vec([m.lit(0u32, 8), m.lit(0u32, 4)]);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Attempted to combine signals from different modules: self belongs to \"a\", signals[1] belongs to \"b\" (while building SignalVec)."
+    )]
+    fn separate_module_error() {
+        let c = Context::new();
+
+        let m1 = c.module("a", "A");
+        let m2 = c.module("b", "B");
+
+        // Panic
+        let _ = m1.signal_vec([m1.lit(0u32, 8), m2.lit(0u32, 8)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Attempted to get element 3 of a signal vector with 3 element(s).")]
+    fn get_const_oob_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let v = elements(m, 3);
+
+        // Panic
+        let _ = v.get_const(3);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Attempted to select an element of a signal vector with 5 element(s) with an index signal with 2 bit(s), but at least 3 bit(s) are required to represent the index of each element."
+    )]
+    fn select_index_bit_width_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let v = elements(m, 5);
+
+        // Panic
+        let _ = v.select(m.input("i", 2));
+    }
+}
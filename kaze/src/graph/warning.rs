@@ -0,0 +1,140 @@
+use std::fmt;
+
+/// A non-fatal problem detected while building a [`Module`](super::Module), recorded in its [`Context`](super::Context) and returned by [`Context::take_warnings`](super::Context::take_warnings).
+///
+/// Unlike errors, warnings describe constructs that are valid, but likely to be mistakes. The [`Display`](fmt::Display) implementation describes the warning as a sentence, which is also the panic message when warnings are [denied](super::Context::deny_warnings).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Warning {
+    /// A shift (built by `op`) whose amount has more than `max_amount_bit_width` bits, which is enough to shift all of the shifted signal's bits out (`ceil(log2(lhs_bit_width)) + 1`).
+    ///
+    /// Amounts wider than this don't change the result, but can generate unnecessarily large shifters.
+    WideShiftAmount {
+        module: String,
+        op: String,
+        lhs_bit_width: u32,
+        amount_bit_width: u32,
+        max_amount_bit_width: u32,
+    },
+    /// A [suppression](super::Context::suppress) for warnings with code `code` in modules matching `path_glob` that didn't match any warning before warnings were [taken](super::Context::take_warnings).
+    ///
+    /// Suppressions that don't match anything are usually stale, and can hide new warnings if they're left in place.
+    UnusedSuppression {
+        code: String,
+        path_glob: String,
+        reason: String,
+    },
+}
+
+/// The codes of all [`Warning`] kinds, as returned by [`Warning::code`].
+pub(super) const WARNING_CODES: &[&str] = &["unused_suppression", "wide_shift_amount"];
+
+impl Warning {
+    /// Returns a short `snake_case` code that identifies the kind of this warning, which is used to [suppress](super::Context::suppress) it.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Warning::WideShiftAmount { .. } => "wide_shift_amount",
+            Warning::UnusedSuppression { .. } => "unused_suppression",
+        }
+    }
+
+    /// Returns the path of the item this warning was detected in, which is matched against the path globs of [suppressions](super::Context::suppress).
+    ///
+    /// For warnings detected while building a module, this is the module's name.
+    pub fn path(&self) -> &str {
+        match self {
+            Warning::WideShiftAmount { module, .. } => module,
+            Warning::UnusedSuppression { path_glob, .. } => path_glob,
+        }
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Warning::WideShiftAmount {
+                module,
+                op,
+                lhs_bit_width,
+                amount_bit_width,
+                max_amount_bit_width,
+            } => write!(f, "Module \"{}\" shifts a signal with {} bit(s) by an amount with {} bit(s) (while building {}), but amounts wider than {} bit(s) can only shift out all of the signal's bits, and may generate an unnecessarily large shifter. Consider truncating the amount with .bits({}, 0).", module, lhs_bit_width, amount_bit_width, op, max_amount_bit_width, max_amount_bit_width - 1),
+            Warning::UnusedSuppression {
+                code,
+                path_glob,
+                reason,
+            } => write!(f, "The suppression of \"{}\" warnings in \"{}\" (\"{}\") didn't match any warning, so it may be stale.", code, path_glob, reason),
+        }
+    }
+}
+
+/// A [`Warning`] that matched a [suppression](super::Context::suppress), returned by [`Context::take_suppressed_warnings`](super::Context::take_suppressed_warnings) rather than [`Context::take_warnings`](super::Context::take_warnings).
+///
+/// The [`Display`](fmt::Display) implementation describes the warning followed by the reason it was suppressed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SuppressedWarning {
+    pub warning: Warning,
+    pub reason: String,
+}
+
+impl fmt::Display for SuppressedWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (suppressed: {})", self.warning, self.reason)
+    }
+}
+
+pub(super) struct Suppression {
+    pub code: String,
+    pub path_glob: String,
+    pub reason: String,
+    pub used: bool,
+}
+
+impl Suppression {
+    pub fn matches(&self, warning: &Warning) -> bool {
+        self.code == warning.code() && glob_matches(&self.path_glob, warning.path())
+    }
+}
+
+/// Returns `true` if `path` matches `glob`, where `*` matches any (possibly empty) sequence of characters and `?` matches any single character.
+fn glob_matches(glob: &str, path: &str) -> bool {
+    let glob = glob.chars().collect::<Vec<_>>();
+    let path = path.chars().collect::<Vec<_>>();
+    // Backtracking to the last `*` is sufficient, as a later `*` can always match anything an earlier one could
+    let (mut g, mut p) = (0, 0);
+    let mut star = None;
+    while p < path.len() {
+        if g < glob.len() && (glob[g] == '?' || glob[g] == path[p]) {
+            g += 1;
+            p += 1;
+        } else if g < glob.len() && glob[g] == '*' {
+            star = Some((g, p));
+            g += 1;
+        } else if let Some((star_g, star_p)) = star {
+            g = star_g + 1;
+            p = star_p + 1;
+            star = Some((star_g, star_p + 1));
+        } else {
+            return false;
+        }
+    }
+    glob[g..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_matching() {
+        assert!(glob_matches("A", "A"));
+        assert!(!glob_matches("A", "AB"));
+        assert!(glob_matches("*", ""));
+        assert!(glob_matches("*", "Fifo"));
+        assert!(glob_matches("Fifo*", "Fifo_8"));
+        assert!(glob_matches("*_8", "Fifo_8"));
+        assert!(glob_matches("F*o*8", "Fifo_8"));
+        assert!(!glob_matches("F*o*9", "Fifo_8"));
+        assert!(glob_matches("Fif?_8", "Fifo_8"));
+        assert!(!glob_matches("Fif?", "Fif"));
+    }
+}
@@ -0,0 +1,64 @@
+use crate::verilog;
+
+/// Returns `true` if `name` is non-empty, consists only of ASCII alphanumeric characters and underscores, and doesn't start with a digit.
+///
+/// Names like this are valid identifiers in both Rust and Verilog, as long as they're not keywords.
+pub(crate) fn is_identifier(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Returns `true` if `name` can be used as the name of a clock or reset, which is emitted as-is in generated code (eg. as a Verilog port name): it must be an identifier (see [`is_identifier`]) that isn't a Verilog or SystemVerilog keyword.
+pub(crate) fn is_clock_or_reset_name(name: &str) -> bool {
+    is_identifier(name) && !verilog::is_keyword(name)
+}
+
+/// Replaces each character in `name` that can't appear in an identifier with `_`, and prefixes the result with `_` if it's empty or starts with a digit.
+///
+/// Returns `name` unchanged if it's already an identifier (see [`is_identifier`]). Note that different names can be sanitized to the same identifier.
+pub(crate) fn sanitize(name: &str) -> String {
+    let mut ret = String::with_capacity(name.len());
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        ret.push('_');
+    }
+    ret.extend(name.chars().map(|c| {
+        if c.is_ascii_alphanumeric() || c == '_' {
+            c
+        } else {
+            '_'
+        }
+    }));
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanitize_names() {
+        assert_eq!(sanitize("a"), "a");
+        assert_eq!(sanitize("_a0"), "_a0");
+        assert_eq!(sanitize("fifo<8>"), "fifo_8_");
+        assert_eq!(sanitize("a b"), "a_b");
+        assert_eq!(sanitize("8bit"), "_8bit");
+        assert_eq!(sanitize(""), "_");
+        assert_eq!(sanitize("é"), "_");
+
+        for name in ["a", "fifo<8>", "a b", "8bit", "", "é"].iter() {
+            assert!(is_identifier(&sanitize(name)));
+        }
+        assert!(!is_identifier("a.b"));
+        assert!(!is_identifier("0"));
+    }
+
+    #[test]
+    fn clock_or_reset_names() {
+        assert!(is_clock_or_reset_name("clk"));
+        assert!(is_clock_or_reset_name("always_"));
+        assert!(!is_clock_or_reset_name("always"));
+        assert!(!is_clock_or_reset_name("logic"));
+        assert!(!is_clock_or_reset_name("0clk"));
+    }
+}
@@ -0,0 +1,691 @@
+//! Canonical textual dumps of module graphs.
+//!
+//! The output of [`generate`] describes everything that affects generated code: the module hierarchy, ports, registers (including default values, reset domains, clocks, divided clocks, and attributes), mems (including initial contents, clocks, and ports), named wires, assertions, coverage points, and every node that's reachable from them, including bit widths, op kinds, and constants.
+//! It's deterministic: child instances, ports, registers, mems, named wires, assertions, and coverage points are listed in name order, and nodes are numbered in the order they're first reached from these, so it doesn't depend on the order in which a graph was built, and can be hashed (eg. to cache generated code).
+//!
+//! The format is meant for diffing and hashing only; it can't be parsed back into a graph, and it may change between versions of this library.
+
+use crate::code_writer;
+use crate::graph;
+use crate::graph::internal_signal::*;
+
+use std::collections::HashMap;
+use std::io::{Result, Write};
+use std::ptr;
+
+/// Writes a canonical textual dump of `m` and its hierarchy to `w`.
+///
+/// # Examples
+///
+/// ```
+/// use kaze::*;
+///
+/// # fn main() -> std::io::Result<()> {
+/// let c = Context::new();
+///
+/// let m = c.module("m", "MyModule");
+/// let a = m.input("a", 8);
+/// let b = m.input("b", 8);
+/// m.output("o", a + b);
+///
+/// let mut ir = Vec::new();
+/// ir::generate(m, &mut ir)?;
+///
+/// assert_eq!(
+///     String::from_utf8(ir).unwrap(),
+///     "module m: MyModule\n    input a: 8\n    input b: 8\n    output o: 8 = %2\nnodes\n    %0: 8 = input m.a\n    %1: 8 = input m.b\n    %2: 8 = add %0, %1\n"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn generate<'a, W: Write>(m: &'a graph::Module<'a>, w: W) -> Result<()> {
+    write_dump(m, Paths::Absolute, w)
+}
+
+/// Returns a dump of `m` and its hierarchy in the same format as [`generate`]'s output, except that instance paths are relative to `m` (which is called `self`), and the values driving `m`'s inputs are omitted.
+///
+/// The result only depends on the contents of `m`'s hierarchy, so it's the same for two instances if and only if they're structurally identical.
+pub(crate) fn generate_relative<'a>(m: &'a graph::Module<'a>) -> String {
+    let mut ir = Vec::new();
+    write_dump(m, Paths::RelativeTo(m), &mut ir).unwrap();
+    String::from_utf8(ir).unwrap()
+}
+
+/// How instance paths are written in a dump.
+#[derive(Clone, Copy)]
+enum Paths<'a> {
+    Absolute,
+    RelativeTo(&'a graph::Module<'a>),
+}
+
+impl<'a> Paths<'a> {
+    fn path(&self, module: &graph::Module<'a>) -> String {
+        let path = module.instance_path();
+        match *self {
+            Paths::Absolute => path,
+            Paths::RelativeTo(root) => match path.strip_prefix(&root.instance_path()) {
+                Some(relative_path) => format!("self{}", relative_path),
+                None => path,
+            },
+        }
+    }
+}
+
+fn write_dump<'a, W: Write>(m: &'a graph::Module<'a>, paths: Paths<'a>, w: W) -> Result<()> {
+    let mut modules = Vec::new();
+    collect_modules(m, &mut modules);
+
+    let mut nodes = Nodes {
+        paths,
+        ids: HashMap::new(),
+        lines: Vec::new(),
+    };
+
+    let mut module_lines = Vec::new();
+    for &module in modules.iter() {
+        let mut lines = Vec::new();
+        // The root's inputs are driven from outside of the dumped hierarchy when paths are relative
+        let is_relative_root = matches!(paths, Paths::RelativeTo(root) if ptr::eq(root, module));
+        for (name, &input) in module.inputs.borrow().iter() {
+            let mut line = format!("input {}: {}", name, input.data.bit_width);
+            if let Some(driven_value) = *input.data.driven_value.borrow() {
+                if !is_relative_root {
+                    line.push_str(&format!(" = %{}", nodes.id(driven_value)));
+                }
+            }
+            line.push_str(&attributes(&input.data.attributes.borrow()));
+            lines.push(line);
+        }
+        for (name, &output) in module.outputs.borrow().iter() {
+            let mut line = format!(
+                "output {}: {} = %{}",
+                name,
+                output.data.bit_width,
+                nodes.id(output.data.source.get())
+            );
+            if let Some(dont_care_cond) = output.data.dont_care_when.get() {
+                line.push_str(&format!(" dont_care_when %{}", nodes.id(dont_care_cond)));
+            }
+            line.push_str(&attributes(&output.data.attributes.borrow()));
+            lines.push(line);
+        }
+        let mut registers = module
+            .registers
+            .borrow()
+            .iter()
+            .map(|register| register.data)
+            .collect::<Vec<_>>();
+        registers.sort_by(|a, b| a.name.cmp(&b.name));
+        for data in registers {
+            let mut line = format!("reg {}: {}", data.name, data.bit_width);
+            if let Some(next) = *data.next.borrow() {
+                line.push_str(&format!(" = %{}", nodes.id(next)));
+            }
+            if let Some(ref clock) = data.clock {
+                line.push_str(&format!(" clock {}", clock));
+            }
+            if let Some(ref initial_value) = *data.initial_value.borrow() {
+                line.push_str(&format!(" default {:#x}", initial_value.numeric_value()));
+            }
+            if let Some(reset_domain) = *data.reset_domain.borrow() {
+                line.push_str(&format!(" reset_domain {}", reset_domain.name));
+            }
+            if data.edge.get() == graph::Edge::Neg {
+                line.push_str(" negedge");
+            }
+            if let Some(clock_divider) = data.divided_clock {
+                line.push_str(&format!(
+                    " divided_clock {} / {}",
+                    clock_divider.name, clock_divider.divide_by
+                ));
+            }
+            line.push_str(&attributes(&data.attributes.borrow()));
+            lines.push(line);
+        }
+        let mut mems = module.mems.borrow().clone();
+        mems.sort_by(|a, b| a.name.cmp(&b.name));
+        for mem in mems {
+            let mut line = format!(
+                "mem {}: {} x {}",
+                mem.name,
+                1u64 << mem.address_bit_width,
+                mem.element_bit_width
+            );
+            if let Some(ref initial_contents) = *mem.initial_contents.borrow() {
+                line.push_str(&format!(
+                    " contents [{}]",
+                    initial_contents
+                        .iter()
+                        .map(|value| format!("{:#x}", value.numeric_value()))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
+            }
+            if let Some(ref clocks) = *mem.clocks.borrow() {
+                line.push_str(&format!(" clocks {}, {}", clocks.read, clocks.write));
+            }
+            lines.push(line);
+            for &(address, enable) in mem.read_ports.borrow().iter() {
+                lines.push(format!(
+                    "    read_port %{}, %{}",
+                    nodes.id(address),
+                    nodes.id(enable)
+                ));
+            }
+            for &address in mem.comb_read_ports.borrow().iter() {
+                lines.push(format!("    read_port_comb %{}", nodes.id(address)));
+            }
+            if let Some((address, value, enable)) = *mem.write_port.borrow() {
+                lines.push(format!(
+                    "    write_port %{}, %{}, %{}",
+                    nodes.id(address),
+                    nodes.id(value),
+                    nodes.id(enable)
+                ));
+            }
+        }
+        for (name, &named_wire) in module.named_wires.borrow().iter() {
+            lines.push(format!("wire {} = %{}", name, nodes.id(named_wire)));
+        }
+        for (name, assertion) in module.assertions.borrow().iter() {
+            lines.push(match assertion.message {
+                Some(ref message) => format!(
+                    "assert_always {} = %{} {:?}",
+                    name,
+                    nodes.id(assertion.cond),
+                    message
+                ),
+                None => format!("assert {} = %{}", name, nodes.id(assertion.cond)),
+            });
+        }
+        for (name, &cond) in module.cover_points.borrow().iter() {
+            lines.push(format!("cover {} = %{}", name, nodes.id(cond)));
+        }
+        for child in sorted_children(module) {
+            lines.push(format!("instance {}: {}", child.instance_name, child.name));
+        }
+        module_lines.push((module, lines));
+    }
+
+    let mut w = code_writer::CodeWriter::new(w);
+
+    for (module, lines) in module_lines {
+        w.append_line(&format!("module {}: {}", paths.path(module), module.name))?;
+        w.indent();
+        for line in lines {
+            w.append_line(&line)?;
+        }
+        w.unindent();
+    }
+    w.append_line("nodes")?;
+    w.indent();
+    for (id, line) in nodes.lines.iter().enumerate() {
+        w.append_line(&format!("%{}: {}", id, line))?;
+    }
+    w.unindent();
+
+    Ok(())
+}
+
+/// Returns the numbered nodes that `signal` is computed from, in the same format as the `nodes` section of [`generate`]'s output.
+pub(crate) fn describe<'a>(signal: &'a InternalSignal<'a>) -> String {
+    let mut nodes = Nodes {
+        paths: Paths::Absolute,
+        ids: HashMap::new(),
+        lines: Vec::new(),
+    };
+    nodes.id(signal);
+    nodes
+        .lines
+        .iter()
+        .enumerate()
+        .map(|(id, line)| format!("%{}: {}\n", id, line))
+        .collect()
+}
+
+struct Nodes<'a> {
+    paths: Paths<'a>,
+    ids: HashMap<&'a InternalSignal<'a>, usize>,
+    lines: Vec<String>,
+}
+
+impl<'a> Nodes<'a> {
+    /// Returns the ID of `signal`, first numbering it and any of its (transitive) operands that haven't been numbered yet.
+    fn id(&mut self, signal: &'a InternalSignal<'a>) -> usize {
+        enum Frame<'a> {
+            Enter(&'a InternalSignal<'a>),
+            Leave(&'a InternalSignal<'a>),
+        }
+
+        let mut frames = vec![Frame::Enter(signal)];
+
+        while let Some(frame) = frames.pop() {
+            match frame {
+                Frame::Enter(signal) => {
+                    if self.ids.contains_key(&signal) {
+                        continue;
+                    }
+                    frames.push(Frame::Leave(signal));
+                    // Operands are pushed in reverse so they're numbered from left to right
+                    for &operand in signal.operands().iter().rev() {
+                        frames.push(Frame::Enter(operand));
+                    }
+                }
+                Frame::Leave(signal) => {
+                    if self.ids.contains_key(&signal) {
+                        continue;
+                    }
+                    let operand_ids = signal
+                        .operands()
+                        .iter()
+                        .map(|operand| format!("%{}", self.ids[operand]))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    let line = format!(
+                        "{} = {}",
+                        signal.bit_width(),
+                        node(signal, &operand_ids, self.paths).trim_end()
+                    );
+                    self.ids.insert(signal, self.lines.len());
+                    self.lines.push(line);
+                }
+            }
+        }
+
+        self.ids[&signal]
+    }
+}
+
+fn node<'a>(signal: &'a InternalSignal<'a>, operand_ids: &str, paths: Paths<'a>) -> String {
+    let path = |name: &str| format!("{}.{}", paths.path(signal.module), name);
+    match signal.data {
+        SignalData::Lit { ref value, .. } => format!("lit {:#x}", value.numeric_value()),
+        SignalData::Input { data } => format!("input {}", path(&data.name)),
+        SignalData::Output { data } => {
+            format!("output {}.{}", paths.path(data.module), data.name)
+        }
+        SignalData::Reg { data } => format!("reg {}", path(&data.name)),
+
+        SignalData::UnOp { op, .. } => format!(
+            "{} {}",
+            match op {
+                UnOp::Not => "not",
+                UnOp::ReverseBits => "reverse_bits",
+                UnOp::SwapBytes => "swap_bytes",
+            },
+            operand_ids
+        ),
+        SignalData::SimpleBinOp { op, .. } => format!(
+            "{} {}",
+            match op {
+                SimpleBinOp::BitAnd => "and",
+                SimpleBinOp::BitOr => "or",
+                SimpleBinOp::BitXor => "xor",
+            },
+            operand_ids
+        ),
+        SignalData::AdditiveBinOp { op, .. } => format!(
+            "{} {}",
+            match op {
+                AdditiveBinOp::Add => "add",
+                AdditiveBinOp::Sub => "sub",
+            },
+            operand_ids
+        ),
+        SignalData::ComparisonBinOp { op, .. } => format!(
+            "{} {}",
+            match op {
+                ComparisonBinOp::Equal => "eq",
+                ComparisonBinOp::NotEqual => "ne",
+                ComparisonBinOp::LessThan => "lt",
+                ComparisonBinOp::LessThanEqual => "le",
+                ComparisonBinOp::GreaterThan => "gt",
+                ComparisonBinOp::GreaterThanEqual => "ge",
+                ComparisonBinOp::LessThanSigned => "lt_signed",
+                ComparisonBinOp::LessThanEqualSigned => "le_signed",
+                ComparisonBinOp::GreaterThanSigned => "gt_signed",
+                ComparisonBinOp::GreaterThanEqualSigned => "ge_signed",
+            },
+            operand_ids
+        ),
+        SignalData::ShiftBinOp { op, .. } => format!(
+            "{} {}",
+            match op {
+                ShiftBinOp::Shl => "shl",
+                ShiftBinOp::Shr => "shr",
+                ShiftBinOp::ShrArithmetic => "shr_arithmetic",
+                ShiftBinOp::RotateLeft => "rotate_left",
+                ShiftBinOp::RotateRight => "rotate_right",
+            },
+            operand_ids
+        ),
+        SignalData::Mul { .. } => format!("mul {}", operand_ids),
+        SignalData::MulSigned { .. } => format!("mul_signed {}", operand_ids),
+        SignalData::Bits {
+            range_high,
+            range_low,
+            ..
+        } => format!("bits {} [{}:{}]", operand_ids, range_high, range_low),
+        SignalData::Repeat { count, .. } => format!("repeat {} x {}", operand_ids, count),
+        SignalData::Concat { .. } => format!("concat {}", operand_ids),
+        SignalData::Mux { .. } => format!("mux {}", operand_ids),
+        SignalData::Case { ref cases, .. } => format!(
+            "case {} [{}]",
+            operand_ids,
+            cases
+                .iter()
+                .map(|(value, _)| format!("{:#x}", value))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        SignalData::MemReadPortOutput { mem, .. } => {
+            format!("read {} {}", path(&mem.name), operand_ids)
+        }
+        SignalData::MemCombReadPortOutput { mem, .. } => {
+            format!("read_comb {} {}", path(&mem.name), operand_ids)
+        }
+        SignalData::NamedWire { ref name, .. } => format!("wire {} {}", path(name), operand_ids),
+        SignalData::Reinterpret { ref label, .. } => {
+            format!("reinterpret {:?} {}", label, operand_ids)
+        }
+    }
+}
+
+fn attributes(attributes: &[graph::Attribute]) -> String {
+    attributes
+        .iter()
+        .map(|attribute| {
+            format!(
+                " [{}.{} = {:?}]",
+                attribute.namespace, attribute.key, attribute.value
+            )
+        })
+        .collect()
+}
+
+fn collect_modules<'a>(m: &'a graph::Module<'a>, modules: &mut Vec<&'a graph::Module<'a>>) {
+    modules.push(m);
+    for child in sorted_children(m) {
+        collect_modules(child, modules);
+    }
+}
+
+fn sorted_children<'a>(m: &'a graph::Module<'a>) -> Vec<&'a graph::Module<'a>> {
+    let mut children = m.modules.borrow().clone();
+    children.sort_by(|a, b| a.instance_name.cmp(&b.instance_name));
+    children
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::*;
+
+    fn ir<'a>(m: &'a Module<'a>) -> String {
+        let mut ir = Vec::new();
+        generate(m, &mut ir).unwrap();
+        String::from_utf8(ir).unwrap()
+    }
+
+    #[test]
+    fn hierarchy() {
+        let c = Context::new();
+
+        let m = c.module("m", "A");
+        let i = m.input("i", 4);
+        let inner = m.module("inner", "B");
+        let inner_i = inner.input("i", 4);
+        let inner_o = inner.output("o", inner_i.repeat(2).bits(5, 2));
+        inner_i.drive(i);
+        let r = m.reg("r", 4);
+        r.default_value(3u32);
+        r.attribute("xilinx", "async_reg", "true");
+        r.drive_next(i.eq(m.lit(0u32, 4)).mux(inner_o, r));
+        let mem = m.mem("mem", 1, 4);
+        mem.initial_contents(&[1u32, 2u32]);
+        m.output("o", mem.read_port(r.bit(0), m.high()));
+
+        assert_eq!(
+            ir(m),
+            "module m: A
+    input i: 4
+    output o: 4 = %3
+    reg r: 4 = %8 default 0x3 [xilinx.async_reg = \"true\"]
+    mem mem: 2 x 4 contents [0x1, 0x2]
+        read_port %1, %2
+    instance inner: B
+module m.inner: B
+    input i: 4 = %4
+    output o: 4 = %11
+nodes
+    %0: 4 = reg m.r
+    %1: 1 = bits %0 [0:0]
+    %2: 1 = lit 0x1
+    %3: 4 = read m.mem %1, %2
+    %4: 4 = input m.i
+    %5: 4 = lit 0x0
+    %6: 1 = eq %4, %5
+    %7: 4 = output m.inner.o
+    %8: 4 = mux %6, %7, %0
+    %9: 4 = input m.inner.i
+    %10: 8 = repeat %9 x 2
+    %11: 4 = bits %10 [5:2]
+"
+        );
+    }
+
+    #[test]
+    fn dont_care_outputs() {
+        let c = Context::new();
+
+        let m = c.module("m", "A");
+        let i = m.input("i", 4);
+        m.output_dont_care_when("o", i, i.bit(0));
+
+        assert_eq!(
+            ir(m),
+            "module m: A
+    input i: 4
+    output o: 4 = %0 dont_care_when %1
+nodes
+    %0: 4 = input m.i
+    %1: 1 = bits %0 [0:0]
+"
+        );
+    }
+
+    #[test]
+    fn reinterpret() {
+        let c = Context::new();
+
+        let m = c.module("m", "A");
+        let i = m.input("i", 32);
+        m.output("o", i.reinterpret("IEEE-754 float"));
+
+        assert_eq!(
+            ir(m),
+            "module m: A
+    input i: 32
+    output o: 32 = %1
+nodes
+    %0: 32 = input m.i
+    %1: 32 = reinterpret \"IEEE-754 float\" %0
+"
+        );
+    }
+
+    #[test]
+    fn assertions_and_cover_points() {
+        let c = Context::new();
+
+        let m = c.module("m", "A");
+        let i = m.input("i", 4);
+        m.assert("nonzero", i.ne(m.lit(0u32, 4)));
+        m.assert("lsb", i.bit(0));
+        m.assert_always("not_all_ones", i.ne(m.lit(0xfu32, 4)), "i was \"all ones\"");
+        m.cover("msb", i.bit(3));
+
+        assert_eq!(
+            ir(m),
+            "module m: A
+    input i: 4
+    assert lsb = %1
+    assert nonzero = %3
+    assert_always not_all_ones = %5 \"i was \\\"all ones\\\"\"
+    cover msb = %6
+nodes
+    %0: 4 = input m.i
+    %1: 1 = bits %0 [0:0]
+    %2: 4 = lit 0x0
+    %3: 1 = ne %0, %2
+    %4: 4 = lit 0xf
+    %5: 1 = ne %0, %4
+    %6: 1 = bits %0 [3:3]
+"
+        );
+    }
+
+    #[test]
+    fn describe() {
+        let c = Context::new();
+
+        let m = c.module("m", "A");
+        let inner = m.module("inner", "B");
+        let inner_i = inner.input("i", 4);
+        inner.output("o", !inner_i);
+        let i = m.input("i", 4);
+        inner_i.drive(i);
+        let mem = m.mem("mem", 4, 8);
+        let read = mem.read_port(i, m.high());
+        let o = inner.outputs.borrow()["o"];
+        let s = i.eq(m.lit(3u32, 4)).mux(read.bits(3, 0), o);
+
+        assert_eq!(
+            s.describe(),
+            "%0: 4 = input m.i
+%1: 4 = lit 0x3
+%2: 1 = eq %0, %1
+%3: 1 = lit 0x1
+%4: 8 = read m.mem %0, %3
+%5: 4 = bits %4 [3:0]
+%6: 4 = output m.inner.o
+%7: 4 = mux %2, %5, %6
+"
+        );
+        // Shared operands are only described once
+        assert_eq!(i.describe(), "%0: 4 = input m.i\n");
+        assert_eq!(
+            (i ^ i).describe(),
+            "%0: 4 = input m.i\n%1: 4 = xor %0, %0\n"
+        );
+    }
+
+    #[test]
+    fn clocks() {
+        let c = Context::new();
+
+        let m = c.module("m", "A");
+        let r = m.reg_in_domain("r", 4, "clk_fast");
+        r.drive_next(m.input("i", 4));
+        let mem = m.mem("mem", 1, 4);
+        mem.dual_clock("read_clk", "write_clk");
+        m.output("o", mem.read_port(r.bit(0), m.high()) ^ r);
+
+        assert_eq!(
+            ir(m),
+            "module m: A
+    input i: 4
+    output o: 4 = %4
+    reg r: 4 = %5 clock clk_fast
+    mem mem: 2 x 4 clocks read_clk, write_clk
+        read_port %1, %2
+nodes
+    %0: 4 = reg m.r
+    %1: 1 = bits %0 [0:0]
+    %2: 1 = lit 0x1
+    %3: 4 = read m.mem %1, %2
+    %4: 4 = xor %3, %0
+    %5: 4 = input m.i
+"
+        );
+    }
+
+    #[test]
+    fn relative_paths() {
+        let c = Context::new();
+
+        fn instance<'a>(
+            m: &'a Module<'a>,
+            instance_name: &str,
+            value: u32,
+            i: &'a dyn Signal<'a>,
+        ) -> &'a Module<'a> {
+            let inner = m.module(instance_name, "B");
+            let inner_i = inner.input("i", 4);
+            inner_i.drive(i);
+            inner.output("o", inner_i ^ inner.lit(value, 4));
+            inner
+        }
+
+        let m = c.module("m", "A");
+        let b1 = instance(m, "b1", 1, m.input("i", 4));
+        let b2 = instance(m, "b2", 1, b1.outputs.borrow()["o"]);
+        let b3 = instance(m, "b3", 2, b2.outputs.borrow()["o"]);
+        m.output("o", b3.outputs.borrow()["o"]);
+
+        assert_eq!(
+            generate_relative(b1),
+            "module self: B
+    input i: 4
+    output o: 4 = %2
+nodes
+    %0: 4 = input self.i
+    %1: 4 = lit 0x1
+    %2: 4 = xor %0, %1
+"
+        );
+        // Instances with identical contents have identical dumps, regardless of how their inputs are driven
+        assert_eq!(generate_relative(b1), generate_relative(b2));
+        assert_ne!(generate_relative(b1), generate_relative(b3));
+    }
+
+    fn build<'a>(c: &'a Context<'a>, reverse: bool, value: u32) -> &'a Module<'a> {
+        let m = c.module("m", "A");
+        let (a, b) = if reverse {
+            let b = m.input("b", 8);
+            let a = m.input("a", 8);
+            (a, b)
+        } else {
+            let a = m.input("a", 8);
+            let b = m.input("b", 8);
+            (a, b)
+        };
+        let sum = a + b;
+        let masked = sum & m.lit(value, 8);
+        if reverse {
+            m.output("y", !masked);
+            m.output("x", masked);
+        } else {
+            m.output("x", masked);
+            m.output("y", !masked);
+        }
+        m
+    }
+
+    #[test]
+    fn independent_of_construction_order() {
+        let c1 = Context::new();
+        let c2 = Context::new();
+
+        assert_eq!(ir(build(&c1, false, 0x0f)), ir(build(&c2, true, 0x0f)));
+    }
+
+    #[test]
+    fn literal_changes_output() {
+        let c1 = Context::new();
+        let c2 = Context::new();
+
+        assert_ne!(ir(build(&c1, false, 0x0f)), ir(build(&c2, false, 0x1f)));
+    }
+}
@@ -31,7 +31,7 @@
 //! sim::generate(inverter, sim::GenerationOptions::default(), std::io::stdout())?;
 //!
 //! // Generate Verilog code
-//! //verilog::generate(inverter, std::io::stdout())?;
+//! //verilog::generate(inverter, verilog::GenerationOptions::default(), std::io::stdout())?;
 //! # Ok(())
 //! # }
 //! ```
@@ -40,11 +40,18 @@
 #![doc(html_root_url = "https://docs.rs/kaze/0.1.19")]
 
 mod code_writer;
+pub mod components;
 mod graph;
+mod identifiers;
+pub mod ir;
+mod optimizer;
 pub mod runtime;
 pub mod sim;
 mod state_elements;
+#[cfg(test)]
+mod test_util;
 mod validation;
 pub mod verilog;
 
 pub use graph::*;
+pub use validation::{validate, ValidationError};
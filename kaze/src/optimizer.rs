@@ -0,0 +1,603 @@
+use crate::graph;
+use crate::graph::internal_signal::*;
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+/// Simplifies the combinational logic between state elements before code generation.
+///
+/// Signals are simplified lazily, one root at a time; generators (and the passes they depend on) must run every signal they find at a graph boundary (output sources, instance input drivers, register next values, and mem port signals) through [`Optimizer::optimize`] before visiting it, so that they all see the same simplified graph.
+/// Inputs, outputs, and mems themselves are never removed or renamed, so port structure is unaffected.
+///
+/// Registers are only removed when they're provably constant: a register whose next value is a literal (or another such register, transitively) and whose default value equals that literal is replaced by the literal, since its value can never differ from it once reset.
+/// Registers without a default value, with a different default value (which would be observable during the first cycles after reset), or with any attributes are always kept.
+/// Removed registers are recorded in [`Optimizer::folded_registers`] so generators can report them.
+///
+/// Outputs may be driven with different logic than their sources when they have a don't care condition (see [`Optimizer::optimize_output`]), so generators must use that method rather than optimizing output sources directly.
+pub(crate) struct Optimizer<'a> {
+    enabled: bool,
+    poison_dont_care_outputs: bool,
+    optimized_signals: RefCell<HashMap<*const InternalSignal<'a>, &'a InternalSignal<'a>>>,
+    optimized_outputs: RefCell<HashMap<*const graph::OutputData<'a>, &'a InternalSignal<'a>>>,
+    folded_registers: RefCell<Vec<&'a graph::RegisterData<'a>>>,
+}
+
+impl<'a> Optimizer<'a> {
+    pub fn new(enabled: bool) -> Optimizer<'a> {
+        Optimizer {
+            enabled,
+            poison_dont_care_outputs: false,
+            optimized_signals: RefCell::new(HashMap::new()),
+            optimized_outputs: RefCell::new(HashMap::new()),
+            folded_registers: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Makes [`Optimizer::optimize_output`] drive outputs with the inverse of their sources while their don't care conditions are high, regardless of whether optimization is enabled.
+    pub fn with_dont_care_poisoning(mut self, poison_dont_care_outputs: bool) -> Optimizer<'a> {
+        self.poison_dont_care_outputs = poison_dont_care_outputs;
+        self
+    }
+
+    /// Returns the registers that have been replaced by constants so far, in the order they were found.
+    pub fn folded_registers(&self) -> Vec<&'a graph::RegisterData<'a>> {
+        self.folded_registers.borrow().clone()
+    }
+
+    pub fn optimize(&self, signal: &'a InternalSignal<'a>) -> &'a InternalSignal<'a> {
+        if !self.enabled {
+            return signal;
+        }
+
+        enum Frame<'a> {
+            Enter(&'a InternalSignal<'a>),
+            Leave(&'a InternalSignal<'a>),
+        }
+
+        let mut optimized_signals = self.optimized_signals.borrow_mut();
+
+        let mut frames = Vec::new();
+        frames.push(Frame::Enter(signal));
+
+        while let Some(frame) = frames.pop() {
+            match frame {
+                Frame::Enter(signal) => {
+                    if optimized_signals.contains_key(&(signal as *const _)) {
+                        continue;
+                    }
+
+                    let operands = operands(signal);
+                    if operands.is_empty() {
+                        if let SignalData::Reg { .. } = signal.data {
+                            self.fold_constant_registers(signal, &mut optimized_signals);
+                        } else {
+                            optimized_signals.insert(signal as *const _, signal);
+                        }
+                    } else {
+                        frames.push(Frame::Leave(signal));
+                        for operand in operands {
+                            frames.push(Frame::Enter(operand));
+                        }
+                    }
+                }
+                Frame::Leave(signal) => {
+                    let optimized_signal =
+                        optimize_node(signal, |s| optimized_signals[&(s as *const _)]);
+                    optimized_signals.insert(signal as *const _, optimized_signal);
+                    // Simplified signals are final, so optimizing them again is a no-op
+                    optimized_signals
+                        .entry(optimized_signal as *const _)
+                        .or_insert(optimized_signal);
+                }
+            }
+        }
+
+        optimized_signals[&(signal as *const _)]
+    }
+
+    /// Returns the signal that drives `output`, which is its optimized source, unless the output has a don't care condition.
+    ///
+    /// In that case, if optimization is enabled, the source is replaced by `0` if the condition is constant high, or by one of its arms if it's a mux whose condition is the don't care condition (or its inverse). If poisoning is enabled, the result is then inverted while the condition is high.
+    pub fn optimize_output(&self, output: &'a graph::OutputData<'a>) -> &'a InternalSignal<'a> {
+        if let Some(&signal) = self.optimized_outputs.borrow().get(&(output as *const _)) {
+            return signal;
+        }
+
+        let source = self.optimize(output.source.get());
+        let signal = match output.dont_care_when.get() {
+            Some(dont_care_cond) => {
+                let dont_care_cond = self.optimize(dont_care_cond);
+                let source = if self.enabled {
+                    simplify_dont_care(source, dont_care_cond)
+                } else {
+                    source
+                };
+                if self.poison_dont_care_outputs {
+                    let bit_width = source.bit_width();
+                    source.alloc_sibling(SignalData::Mux {
+                        cond: dont_care_cond,
+                        when_true: source.alloc_sibling(SignalData::UnOp {
+                            source,
+                            op: UnOp::Not,
+                            bit_width,
+                        }),
+                        when_false: source,
+                        bit_width,
+                    })
+                } else {
+                    source
+                }
+            }
+            None => source,
+        };
+        self.optimized_outputs
+            .borrow_mut()
+            .insert(output as *const _, signal);
+        signal
+    }
+
+    fn fold_constant_registers(
+        &self,
+        signal: &'a InternalSignal<'a>,
+        optimized_signals: &mut HashMap<*const InternalSignal<'a>, &'a InternalSignal<'a>>,
+    ) {
+        // Follow the chain of registers driven directly by other registers until we reach a literal
+        let mut chain = Vec::new();
+        let mut current = signal;
+        let value = loop {
+            match current.data {
+                SignalData::Lit { ref value, .. } => break Some(value.numeric_value()),
+                SignalData::Reg { data } if !chain.iter().any(|&(reg, _)| reg == current) => {
+                    chain.push((current, data));
+                    current = data.next.borrow().unwrap();
+                }
+                _ => break None,
+            }
+        };
+
+        // Every register in the chain must reset to the same value it's driven with; otherwise, its value differs for
+        //  some cycles after reset. Registers with attributes are kept, as the attributes would be lost otherwise.
+        let is_foldable = |value| {
+            chain.iter().all(|(_, data)| {
+                data.attributes.borrow().is_empty()
+                    && matches!(*data.initial_value.borrow(), Some(ref initial_value) if initial_value.numeric_value() == value)
+            })
+        };
+        match value {
+            Some(value) if is_foldable(value) => {
+                let mut folded_registers = self.folded_registers.borrow_mut();
+                for (reg, data) in chain {
+                    if optimized_signals.contains_key(&(reg as *const _)) {
+                        continue;
+                    }
+                    let lit = reg.alloc_sibling(SignalData::Lit {
+                        value: graph::Constant::U128(value),
+                        bit_width: data.bit_width,
+                    });
+                    optimized_signals.insert(reg as *const _, lit);
+                    optimized_signals.insert(lit as *const _, lit);
+                    folded_registers.push(data);
+                }
+            }
+            _ => {
+                optimized_signals.insert(signal as *const _, signal);
+            }
+        }
+    }
+}
+
+/// Returns the operands of `signal` that are optimized along with it. Unlike [`InternalSignal::operands`], synchronous mem read ports are leaves, as their address and enable are optimized with the mem's ports.
+fn operands<'a>(signal: &'a InternalSignal<'a>) -> Vec<&'a InternalSignal<'a>> {
+    match signal.data {
+        SignalData::MemReadPortOutput { .. } => Vec::new(),
+        _ => signal.operands(),
+    }
+}
+
+fn optimize_node<'a>(
+    signal: &'a InternalSignal<'a>,
+    o: impl Fn(&'a InternalSignal<'a>) -> &'a InternalSignal<'a>,
+) -> &'a InternalSignal<'a> {
+    let bit_width = signal.bit_width();
+    let lit = |value: u128| {
+        signal.alloc_sibling(SignalData::Lit {
+            value: graph::Constant::U128(value & mask(bit_width)),
+            bit_width,
+        })
+    };
+
+    match signal.data {
+        SignalData::Lit { .. }
+        | SignalData::Input { .. }
+        | SignalData::Output { .. }
+        | SignalData::Reg { .. }
+        | SignalData::MemReadPortOutput { .. } => unreachable!(),
+
+        SignalData::UnOp { source, op, .. } => {
+            let source = o(source);
+            if let Some(value) = lit_value(source) {
+                return lit(match op {
+                    UnOp::Not => !value,
+                    UnOp::ReverseBits => value.reverse_bits() >> (128 - bit_width),
+                    UnOp::SwapBytes => value.swap_bytes() >> (128 - bit_width),
+                });
+            }
+            if let SignalData::UnOp {
+                source,
+                op: source_op,
+                ..
+            } = source.data
+            {
+                // Each of these ops is its own inverse
+                if source_op == op {
+                    return source;
+                }
+            }
+            rebuild(signal, &[source], || SignalData::UnOp {
+                source,
+                op,
+                bit_width,
+            })
+        }
+        SignalData::SimpleBinOp { lhs, rhs, op, .. } => {
+            let lhs = o(lhs);
+            let rhs = o(rhs);
+            let all_ones = mask(bit_width);
+            match (lit_value(lhs), lit_value(rhs), op) {
+                (Some(lhs), Some(rhs), _) => {
+                    return lit(match op {
+                        SimpleBinOp::BitAnd => lhs & rhs,
+                        SimpleBinOp::BitOr => lhs | rhs,
+                        SimpleBinOp::BitXor => lhs ^ rhs,
+                    });
+                }
+                (Some(0), _, SimpleBinOp::BitAnd) | (_, Some(0), SimpleBinOp::BitAnd) => {
+                    return lit(0);
+                }
+                (Some(value), _, SimpleBinOp::BitAnd) if value == all_ones => return rhs,
+                (_, Some(value), SimpleBinOp::BitAnd) if value == all_ones => return lhs,
+                (Some(value), _, SimpleBinOp::BitOr) | (_, Some(value), SimpleBinOp::BitOr)
+                    if value == all_ones =>
+                {
+                    return lit(all_ones);
+                }
+                (Some(0), _, SimpleBinOp::BitOr) | (Some(0), _, SimpleBinOp::BitXor) => {
+                    return rhs;
+                }
+                (_, Some(0), SimpleBinOp::BitOr) | (_, Some(0), SimpleBinOp::BitXor) => {
+                    return lhs;
+                }
+                _ => (),
+            }
+            rebuild(signal, &[lhs, rhs], || SignalData::SimpleBinOp {
+                lhs,
+                rhs,
+                op,
+                bit_width,
+            })
+        }
+        SignalData::AdditiveBinOp { lhs, rhs, op, .. } => {
+            let lhs = o(lhs);
+            let rhs = o(rhs);
+            match (lit_value(lhs), lit_value(rhs), op) {
+                (Some(lhs), Some(rhs), _) => {
+                    return lit(match op {
+                        AdditiveBinOp::Add => lhs.wrapping_add(rhs),
+                        AdditiveBinOp::Sub => lhs.wrapping_sub(rhs),
+                    });
+                }
+                (Some(0), _, AdditiveBinOp::Add) => return rhs,
+                (_, Some(0), _) => return lhs,
+                _ => (),
+            }
+            rebuild(signal, &[lhs, rhs], || SignalData::AdditiveBinOp {
+                lhs,
+                rhs,
+                op,
+                bit_width,
+            })
+        }
+        SignalData::ComparisonBinOp { lhs, rhs, op } => {
+            let source_bit_width = lhs.bit_width();
+            let lhs = o(lhs);
+            let rhs = o(rhs);
+            if let (Some(lhs), Some(rhs)) = (lit_value(lhs), lit_value(rhs)) {
+                let lhs_signed = sign_extend(lhs, source_bit_width);
+                let rhs_signed = sign_extend(rhs, source_bit_width);
+                return lit(match op {
+                    ComparisonBinOp::Equal => lhs == rhs,
+                    ComparisonBinOp::NotEqual => lhs != rhs,
+                    ComparisonBinOp::LessThan => lhs < rhs,
+                    ComparisonBinOp::LessThanEqual => lhs <= rhs,
+                    ComparisonBinOp::GreaterThan => lhs > rhs,
+                    ComparisonBinOp::GreaterThanEqual => lhs >= rhs,
+                    ComparisonBinOp::LessThanSigned => lhs_signed < rhs_signed,
+                    ComparisonBinOp::LessThanEqualSigned => lhs_signed <= rhs_signed,
+                    ComparisonBinOp::GreaterThanSigned => lhs_signed > rhs_signed,
+                    ComparisonBinOp::GreaterThanEqualSigned => lhs_signed >= rhs_signed,
+                } as u128);
+            }
+            rebuild(signal, &[lhs, rhs], || SignalData::ComparisonBinOp {
+                lhs,
+                rhs,
+                op,
+            })
+        }
+        SignalData::ShiftBinOp { lhs, rhs, op, .. } => {
+            let lhs = o(lhs);
+            let rhs = o(rhs);
+            match (lit_value(lhs), lit_value(rhs)) {
+                (Some(lhs), Some(rhs)) => {
+                    return lit(match op {
+                        ShiftBinOp::Shl if rhs < bit_width as u128 => lhs << rhs,
+                        ShiftBinOp::Shr if rhs < bit_width as u128 => lhs >> rhs,
+                        ShiftBinOp::Shl | ShiftBinOp::Shr => 0,
+                        ShiftBinOp::ShrArithmetic => {
+                            (sign_extend(lhs, bit_width) >> rhs.min(127)) as u128
+                        }
+                        ShiftBinOp::RotateLeft | ShiftBinOp::RotateRight => {
+                            let amount = (rhs % bit_width as u128) as u32;
+                            let amount = match op {
+                                ShiftBinOp::RotateLeft => amount,
+                                _ => (bit_width - amount) % bit_width,
+                            };
+                            (lhs << amount) | lhs.checked_shr(bit_width - amount).unwrap_or(0)
+                        }
+                    });
+                }
+                (_, Some(0)) => return lhs,
+                _ => (),
+            }
+            rebuild(signal, &[lhs, rhs], || SignalData::ShiftBinOp {
+                lhs,
+                rhs,
+                op,
+                bit_width,
+            })
+        }
+
+        SignalData::Mul { lhs, rhs, .. } => {
+            let lhs = o(lhs);
+            let rhs = o(rhs);
+            if let (Some(lhs), Some(rhs)) = (lit_value(lhs), lit_value(rhs)) {
+                return lit(lhs.wrapping_mul(rhs));
+            }
+            rebuild(signal, &[lhs, rhs], || SignalData::Mul {
+                lhs,
+                rhs,
+                bit_width,
+            })
+        }
+        SignalData::MulSigned { lhs, rhs, .. } => {
+            let lhs_bit_width = lhs.bit_width();
+            let rhs_bit_width = rhs.bit_width();
+            let lhs = o(lhs);
+            let rhs = o(rhs);
+            if let (Some(lhs), Some(rhs)) = (lit_value(lhs), lit_value(rhs)) {
+                return lit(sign_extend(lhs, lhs_bit_width)
+                    .wrapping_mul(sign_extend(rhs, rhs_bit_width))
+                    as u128);
+            }
+            rebuild(signal, &[lhs, rhs], || SignalData::MulSigned {
+                lhs,
+                rhs,
+                bit_width,
+            })
+        }
+
+        SignalData::Bits {
+            source,
+            range_high,
+            range_low,
+        } => {
+            let source = o(source);
+            if let Some(value) = lit_value(source) {
+                return lit(value >> range_low);
+            }
+            if range_low == 0 && range_high == source.bit_width() - 1 {
+                return source;
+            }
+            if let SignalData::Bits {
+                source: inner_source,
+                range_low: inner_range_low,
+                ..
+            } = source.data
+            {
+                return signal.alloc_sibling(SignalData::Bits {
+                    source: inner_source,
+                    range_high: inner_range_low + range_high,
+                    range_low: inner_range_low + range_low,
+                });
+            }
+            rebuild(signal, &[source], || SignalData::Bits {
+                source,
+                range_high,
+                range_low,
+            })
+        }
+
+        SignalData::Repeat { source, count, .. } => {
+            let source = o(source);
+            if count == 1 {
+                return source;
+            }
+            if let Some(value) = lit_value(source) {
+                let source_bit_width = source.bit_width();
+                return lit((0..count).fold(0, |acc, i| acc | (value << (i * source_bit_width))));
+            }
+            rebuild(signal, &[source], || SignalData::Repeat {
+                source,
+                count,
+                bit_width,
+            })
+        }
+        SignalData::Concat { lhs, rhs, .. } => {
+            let rhs_bit_width = rhs.bit_width();
+            let lhs = o(lhs);
+            let rhs = o(rhs);
+            if let (Some(lhs), Some(rhs)) = (lit_value(lhs), lit_value(rhs)) {
+                return lit((lhs << rhs_bit_width) | rhs);
+            }
+            rebuild(signal, &[lhs, rhs], || SignalData::Concat {
+                lhs,
+                rhs,
+                bit_width,
+            })
+        }
+
+        SignalData::MemCombReadPortOutput { mem, address } => {
+            let address = o(address);
+            rebuild(signal, &[address], || SignalData::MemCombReadPortOutput {
+                mem,
+                address,
+            })
+        }
+
+        SignalData::Case {
+            sel,
+            ref cases,
+            default,
+            ..
+        } => {
+            let sel = o(sel);
+            let cases = cases
+                .iter()
+                .map(|&(value, signal)| (value, o(signal)))
+                .collect::<Vec<_>>();
+            let default = o(default);
+            if let Some(sel) = lit_value(sel) {
+                return cases
+                    .iter()
+                    .find(|&&(value, _)| value == sel)
+                    .map_or(default, |&(_, signal)| signal);
+            }
+            if cases.iter().all(|&(_, signal)| signal == default) {
+                return default;
+            }
+            rebuild(signal, &case_operands(sel, &cases, default), || {
+                SignalData::Case {
+                    sel,
+                    cases,
+                    default,
+                    bit_width,
+                }
+            })
+        }
+
+        // Reinterpretations are purely descriptive
+        SignalData::Reinterpret { source, .. } => o(source),
+
+        // Named wires are kept even if their source is simplified, as they're explicitly requested probe points
+        SignalData::NamedWire { ref name, source } => {
+            let source = o(source);
+            rebuild(signal, &[source], || SignalData::NamedWire {
+                name: name.clone(),
+                source,
+            })
+        }
+
+        SignalData::Mux {
+            cond,
+            when_true,
+            when_false,
+            ..
+        } => {
+            let cond = o(cond);
+            let when_true = o(when_true);
+            let when_false = o(when_false);
+            if let Some(cond) = lit_value(cond) {
+                return if cond != 0 { when_true } else { when_false };
+            }
+            if when_true == when_false {
+                return when_true;
+            }
+            match (lit_value(when_true), lit_value(when_false)) {
+                (Some(1), Some(0)) if bit_width == 1 => return cond,
+                (Some(0), Some(1)) if bit_width == 1 => {
+                    return signal.alloc_sibling(SignalData::UnOp {
+                        source: cond,
+                        op: UnOp::Not,
+                        bit_width,
+                    });
+                }
+                _ => (),
+            }
+            rebuild(signal, &[cond, when_true, when_false], || SignalData::Mux {
+                cond,
+                when_true,
+                when_false,
+                bit_width,
+            })
+        }
+    }
+}
+
+/// Simplifies `source` (which is already optimized) under the assumption that its value is irrelevant whenever `dont_care_cond` is high.
+fn simplify_dont_care<'a>(
+    source: &'a InternalSignal<'a>,
+    dont_care_cond: &'a InternalSignal<'a>,
+) -> &'a InternalSignal<'a> {
+    match lit_value(dont_care_cond) {
+        Some(0) => return source,
+        Some(_) => {
+            return source.alloc_sibling(SignalData::Lit {
+                value: graph::Constant::U128(0),
+                bit_width: source.bit_width(),
+            });
+        }
+        None => (),
+    }
+    let is_inverse = |a: &'a InternalSignal<'a>, b: &'a InternalSignal<'a>| matches!(a.data, SignalData::UnOp { source, op: UnOp::Not, .. } if source == b);
+    if let SignalData::Mux {
+        cond,
+        when_true,
+        when_false,
+        ..
+    } = source.data
+    {
+        if cond == dont_care_cond {
+            return when_false;
+        }
+        if is_inverse(cond, dont_care_cond) || is_inverse(dont_care_cond, cond) {
+            return when_true;
+        }
+    }
+    source
+}
+
+/// Returns `signal` itself if `optimized_operands` are identical to its original operands, otherwise allocates a new signal with the data produced by `data`.
+fn rebuild<'a>(
+    signal: &'a InternalSignal<'a>,
+    optimized_operands: &[&'a InternalSignal<'a>],
+    data: impl FnOnce() -> SignalData<'a>,
+) -> &'a InternalSignal<'a> {
+    if operands(signal)
+        .iter()
+        .zip(optimized_operands.iter())
+        .all(|(a, b)| a == b)
+    {
+        signal
+    } else {
+        signal.alloc_sibling(data())
+    }
+}
+
+fn lit_value(signal: &InternalSignal) -> Option<u128> {
+    match signal.data {
+        SignalData::Lit { ref value, .. } => Some(value.numeric_value()),
+        _ => None,
+    }
+}
+
+fn mask(bit_width: u32) -> u128 {
+    if bit_width == 128 {
+        !0
+    } else {
+        (1 << bit_width) - 1
+    }
+}
+
+fn sign_extend(value: u128, bit_width: u32) -> i128 {
+    let shift = 128 - bit_width;
+    ((value << shift) as i128) >> shift
+}
@@ -1,3 +1,9 @@
-//! Rust simulator runtime dependencies. These are only required for simulators with tracing enabled.
+//! Rust simulator runtime dependencies. [`tracing`] is only required for simulators with tracing enabled, [`fuzz`] provides utilities for property testing generated simulators, [`scoreboard`] provides expected-vs-actual checking for test benches, [`cosim`] compares generated simulators against reference models cycle by cycle, [`csv`] exports sampled simulator values for offline analysis, [`debug`] drives debug ports through generated simulators, and [`suite`] runs named regression scenarios against generated simulators.
 
+pub mod cosim;
+pub mod csv;
+pub mod debug;
+pub mod fuzz;
+pub mod scoreboard;
+pub mod suite;
 pub mod tracing;
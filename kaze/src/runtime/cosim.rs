@@ -0,0 +1,348 @@
+//! Cycle-by-cycle co-simulation of a generated simulator against a reference model.
+//!
+//! [`Cosim`] drives a generated simulator and a golden model written in plain Rust with the same stimulus, one cycle at a time, compares their outputs after each cycle, and stops at the first [`Divergence`], which reports the cycle it occurred in, the outputs that differ, and (optionally) the outputs of the last few cycles leading up to it.
+//!
+//! Both sides implement [`CosimDut`], typically with a thin wrapper that maps a stimulus value onto a generated simulator's input fields and collects its output fields.
+//!
+//! # Examples
+//!
+//! ```
+//! use kaze::runtime::cosim::*;
+//!
+//! // Stand-in for a generated simulator: an accumulator with an 8-bit output
+//! #[derive(Default)]
+//! struct Sim {
+//!     i: u32,
+//!     acc: u32,
+//! }
+//!
+//! impl CosimDut for Sim {
+//!     type Inputs = u32;
+//!
+//!     fn set_inputs(&mut self, inputs: &u32) {
+//!         self.i = *inputs;
+//!     }
+//!
+//!     fn cycle(&mut self) {
+//!         // For a generated simulator, eg. `self.prop(); self.posedge_clk(); self.prop();`
+//!         self.acc = (self.acc + self.i) & 0xff;
+//!     }
+//!
+//!     fn outputs(&self) -> Vec<(&'static str, u128)> {
+//!         vec![("acc", self.acc as _)]
+//!     }
+//! }
+//!
+//! // Reference model
+//! #[derive(Default)]
+//! struct Model {
+//!     acc: u8,
+//!     next: u8,
+//! }
+//!
+//! impl CosimDut for Model {
+//!     type Inputs = u32;
+//!
+//!     fn set_inputs(&mut self, inputs: &u32) {
+//!         self.next = self.acc.wrapping_add(*inputs as u8);
+//!     }
+//!
+//!     fn cycle(&mut self) {
+//!         self.acc = self.next;
+//!     }
+//!
+//!     fn outputs(&self) -> Vec<(&'static str, u128)> {
+//!         vec![("acc", self.acc as _)]
+//!     }
+//! }
+//!
+//! let result = Cosim::new().run(&mut Sim::default(), &mut Model::default(), vec![1, 2, 0xff, 3]);
+//! assert_eq!(result, Ok(4));
+//! ```
+
+use std::collections::VecDeque;
+use std::fmt;
+
+/// A design (or reference model) that can be co-simulated with [`Cosim`].
+pub trait CosimDut {
+    /// The stimulus applied in each cycle.
+    type Inputs;
+
+    /// Applies `inputs` for the next cycle.
+    fn set_inputs(&mut self, inputs: &Self::Inputs);
+
+    /// Simulates a single cycle with the inputs applied by the last call to [`set_inputs`](Self::set_inputs).
+    ///
+    /// For a generated simulator, this typically calls `prop`, the clock methods, and `prop` again, so that [`outputs`](Self::outputs) reflects the state after the cycle. Wrappers for simulators generated with tracing enabled can also call `update_trace` here.
+    fn cycle(&mut self);
+
+    /// Returns the names and values of the outputs to compare after each cycle.
+    fn outputs(&self) -> Vec<(&'static str, u128)>;
+}
+
+/// An output whose value differed between the design and the reference model in a [`Divergence`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OutputDifference {
+    /// The output's name.
+    pub name: &'static str,
+    /// The design's value.
+    pub dut: u128,
+    /// The reference model's value.
+    pub model: u128,
+}
+
+/// The outputs of the design and the reference model after a single cycle, kept in a [`Divergence`]'s history.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CycleOutputs {
+    /// The cycle number, starting at `0` for the first cycle.
+    pub cycle: u64,
+    /// The design's outputs.
+    pub dut: Vec<(&'static str, u128)>,
+    /// The reference model's outputs.
+    pub model: Vec<(&'static str, u128)>,
+}
+
+/// The first cycle in which the design's outputs differed from the reference model's, returned by [`Cosim::run`].
+///
+/// The [`Display`](fmt::Display) implementation lists the differing outputs followed by the design's outputs in each cycle of the history, which makes it suitable as an assertion message.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Divergence {
+    /// The cycle number, starting at `0` for the first cycle.
+    pub cycle: u64,
+    /// The outputs that differed, in the order the reference model returned them.
+    pub differences: Vec<OutputDifference>,
+    /// The outputs of up to [`history_len`](Cosim::history_len) cycles before the divergence, oldest first.
+    pub history: Vec<CycleOutputs>,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "outputs diverged in cycle {}", self.cycle)?;
+        for difference in self.differences.iter() {
+            writeln!(
+                f,
+                "  {}: dut {:#x}, model {:#x}",
+                difference.name, difference.dut, difference.model
+            )?;
+        }
+        for cycle_outputs in self.history.iter() {
+            write!(f, "  cycle {}:", cycle_outputs.cycle)?;
+            for (name, value) in cycle_outputs.dut.iter() {
+                write!(f, " {} = {:#x}", name, value)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs a design and a reference model side by side and compares their outputs after each cycle.
+#[derive(Clone, Debug, Default)]
+pub struct Cosim {
+    history_len: usize,
+}
+
+impl Cosim {
+    /// Creates a new `Cosim` that doesn't keep any history.
+    pub fn new() -> Cosim {
+        Cosim::default()
+    }
+
+    /// Keeps the outputs of the last `history_len` cycles before a divergence, which are reported in [`Divergence::history`].
+    pub fn history(mut self, history_len: usize) -> Cosim {
+        self.history_len = history_len;
+        self
+    }
+
+    /// Returns the number of cycles kept before a divergence.
+    pub fn history_len(&self) -> usize {
+        self.history_len
+    }
+
+    /// Applies each value produced by `stimulus` to both `dut` and `model`, simulates a cycle on each, and compares their outputs.
+    ///
+    /// Each output returned by `model` is compared against the output of `dut` with the same name, and `dut` may return additional outputs, which aren't compared. Returns the number of cycles simulated if no outputs differed, or the first [`Divergence`] otherwise.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `dut` doesn't return an output with the same name as one returned by `model`.
+    pub fn run<I, D: CosimDut<Inputs = I>, M: CosimDut<Inputs = I>>(
+        &self,
+        dut: &mut D,
+        model: &mut M,
+        stimulus: impl IntoIterator<Item = I>,
+    ) -> Result<u64, Divergence> {
+        let mut history = VecDeque::with_capacity(self.history_len);
+        let mut num_cycles = 0;
+        for inputs in stimulus {
+            let cycle = num_cycles;
+            dut.set_inputs(&inputs);
+            model.set_inputs(&inputs);
+            dut.cycle();
+            model.cycle();
+            num_cycles += 1;
+
+            let dut_outputs = dut.outputs();
+            let model_outputs = model.outputs();
+            let mut differences = Vec::new();
+            for &(name, model_value) in model_outputs.iter() {
+                let dut_value = match dut_outputs.iter().find(|(n, _)| *n == name) {
+                    Some(&(_, value)) => value,
+                    None => panic!("Cannot compare output \"{}\" in cycle {}, because the design doesn't have an output with that name.", name, cycle),
+                };
+                if dut_value != model_value {
+                    differences.push(OutputDifference {
+                        name,
+                        dut: dut_value,
+                        model: model_value,
+                    });
+                }
+            }
+
+            if !differences.is_empty() {
+                return Err(Divergence {
+                    cycle,
+                    differences,
+                    history: history.into_iter().collect(),
+                });
+            }
+
+            if self.history_len > 0 {
+                if history.len() == self.history_len {
+                    history.pop_front();
+                }
+                history.push_back(CycleOutputs {
+                    cycle,
+                    dut: dut_outputs,
+                    model: model_outputs,
+                });
+            }
+        }
+
+        Ok(num_cycles)
+    }
+}
+
+/// Runs `dut` and `model` with `stimulus` using a [`Cosim`] without history. See [`Cosim::run`].
+pub fn cosim<I, D: CosimDut<Inputs = I>, M: CosimDut<Inputs = I>>(
+    dut: &mut D,
+    model: &mut M,
+    stimulus: impl IntoIterator<Item = I>,
+) -> Result<u64, Divergence> {
+    Cosim::new().run(dut, model, stimulus)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Outputs the sum of its inputs so far, plus `bug` once the sum reaches `bug_at`.
+    struct Summer {
+        sum: u128,
+        bug: u128,
+        bug_at: u128,
+        extra_output: bool,
+    }
+
+    impl Summer {
+        fn new(bug: u128, bug_at: u128) -> Summer {
+            Summer {
+                sum: 0,
+                bug,
+                bug_at,
+                extra_output: false,
+            }
+        }
+    }
+
+    impl CosimDut for Summer {
+        type Inputs = u128;
+
+        fn set_inputs(&mut self, inputs: &u128) {
+            self.sum += *inputs;
+        }
+
+        fn cycle(&mut self) {}
+
+        fn outputs(&self) -> Vec<(&'static str, u128)> {
+            let bug = if self.sum >= self.bug_at { self.bug } else { 0 };
+            let mut outputs = vec![("sum", self.sum + bug), ("parity", self.sum & 1)];
+            if self.extra_output {
+                outputs.push(("extra", 0));
+            }
+            outputs
+        }
+    }
+
+    #[test]
+    fn matching() {
+        let mut dut = Summer::new(0, 0);
+        dut.extra_output = true;
+        assert_eq!(
+            cosim(&mut dut, &mut Summer::new(0, 0), vec![1, 2, 3]),
+            Ok(3)
+        );
+    }
+
+    #[test]
+    fn divergence_with_history() {
+        let divergence = Cosim::new()
+            .history(2)
+            .run(
+                &mut Summer::new(2, 6),
+                &mut Summer::new(0, 0),
+                vec![1, 2, 3, 4],
+            )
+            .unwrap_err();
+
+        assert_eq!(divergence.cycle, 2);
+        assert_eq!(
+            divergence.differences,
+            vec![OutputDifference {
+                name: "sum",
+                dut: 8,
+                model: 6,
+            }]
+        );
+        assert_eq!(
+            divergence
+                .history
+                .iter()
+                .map(|cycle_outputs| cycle_outputs.cycle)
+                .collect::<Vec<_>>(),
+            vec![0, 1]
+        );
+        assert_eq!(
+            divergence.to_string(),
+            "outputs diverged in cycle 2\n  \
+             sum: dut 0x8, model 0x6\n  \
+             cycle 0: sum = 0x1 parity = 0x1\n  \
+             cycle 1: sum = 0x3 parity = 0x1\n"
+        );
+    }
+
+    #[test]
+    fn history_keeps_last_cycles() {
+        let divergence = Cosim::new()
+            .history(1)
+            .run(&mut Summer::new(1, 10), &mut Summer::new(0, 0), vec![1; 20])
+            .unwrap_err();
+
+        assert_eq!(divergence.cycle, 9);
+        assert_eq!(divergence.history.len(), 1);
+        assert_eq!(divergence.history[0].cycle, 8);
+        assert_eq!(divergence.history[0].model, vec![("sum", 9), ("parity", 1)]);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot compare output \"extra\" in cycle 0, because the design doesn't have an output with that name."
+    )]
+    fn missing_dut_output_error() {
+        let mut model = Summer::new(0, 0);
+        model.extra_output = true;
+
+        // Panic
+        let _ = cosim(&mut Summer::new(0, 0), &mut model, vec![1]);
+    }
+}
@@ -0,0 +1,343 @@
+//! CSV export of simulator values for offline analysis.
+//!
+//! An [`OutputLogger`] samples a set of columns from a generated simulator and appends one CSV row per sample, starting with the cycle number, so that results can be loaded into tools like pandas or spreadsheets without writing a sampling loop by hand.
+//!
+//! # Examples
+//!
+//! ```
+//! use kaze::runtime::csv::*;
+//!
+//! // Stand-in for a generated simulator
+//! struct Sim {
+//!     valid: bool,
+//!     data: u32,
+//! }
+//!
+//! let mut logger = OutputLogger::new(Vec::new())
+//!     .column("data", 12, ColumnFormat::Hex, |sim: &Sim| sim.data.into())
+//!     .column("data_dec", 12, ColumnFormat::Decimal, |sim: &Sim| sim.data.into())
+//!     .sample_when(|sim: &Sim| sim.valid);
+//!
+//! let mut sim = Sim { valid: false, data: 0 };
+//! for cycle in 0..4 {
+//!     sim.valid = cycle % 2 == 1;
+//!     sim.data = cycle * 0x101;
+//!     logger.sample(&sim, cycle as _).unwrap();
+//! }
+//!
+//! let csv = String::from_utf8(logger.into_inner()).unwrap();
+//! assert_eq!(csv, "cycle,data,data_dec\n1,101,0257\n3,303,0771\n");
+//! ```
+
+use std::io::{self, Write};
+
+/// The format of the values in a column of an [`OutputLogger`].
+///
+/// Values are always zero-padded to the number of digits required to represent any value of the column's bit width.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ColumnFormat {
+    /// Base 10, eg. `0255` for a 10-bit column.
+    Decimal,
+    /// Base 16 without a prefix, eg. `0ff` for a 10-bit column.
+    Hex,
+}
+
+type Accessor<S> = Box<dyn Fn(&S) -> u128>;
+
+struct Column<S> {
+    name: String,
+    bit_width: u32,
+    format: ColumnFormat,
+    accessor: Accessor<S>,
+}
+
+enum SampleCondition<S> {
+    EveryCycle,
+    EveryNthCycle(u64),
+    When(Box<dyn Fn(&S) -> bool>),
+}
+
+/// Samples values from a simulator of type `S` and writes them to `W` as CSV.
+///
+/// The first line written is a header row containing `cycle` followed by each column's name. Each sample then appends a row with the cycle number passed to [`sample`] followed by each column's value.
+///
+/// By default, every call to [`sample`] appends a row. This can be changed with [`every_nth_cycle`] or [`sample_when`].
+///
+/// [`every_nth_cycle`]: Self::every_nth_cycle
+/// [`sample`]: Self::sample
+/// [`sample_when`]: Self::sample_when
+pub struct OutputLogger<S, W: Write> {
+    writer: W,
+    columns: Vec<Column<S>>,
+    condition: SampleCondition<S>,
+    has_written_header: bool,
+}
+
+impl<S, W: Write> OutputLogger<S, W> {
+    /// Creates a new `OutputLogger` without any columns that writes to `writer`.
+    pub fn new(writer: W) -> OutputLogger<S, W> {
+        OutputLogger {
+            writer,
+            columns: Vec::new(),
+            condition: SampleCondition::EveryCycle,
+            has_written_header: false,
+        }
+    }
+
+    /// Adds a column called `name` whose values are `bit_width` bits wide, formatted with `format`, and read from a simulator with `accessor`.
+    ///
+    /// Values returned by `accessor` are truncated to `bit_width` bits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bit_width` is less than [`MIN_SIGNAL_BIT_WIDTH`](crate::MIN_SIGNAL_BIT_WIDTH) or greater than [`MAX_SIGNAL_BIT_WIDTH`](crate::MAX_SIGNAL_BIT_WIDTH), respectively, if `name` is empty or contains commas, quotes, or line breaks, or if this `OutputLogger` already has a column called `name` (including the implicit `cycle` column).
+    pub fn column(
+        mut self,
+        name: impl Into<String>,
+        bit_width: u32,
+        format: ColumnFormat,
+        accessor: impl Fn(&S) -> u128 + 'static,
+    ) -> OutputLogger<S, W> {
+        let name = name.into();
+        if bit_width < crate::MIN_SIGNAL_BIT_WIDTH {
+            panic!(
+                "Cannot add a column called \"{}\" with {} bit(s). Columns must not be narrower than {} bit(s).",
+                name,
+                bit_width,
+                crate::MIN_SIGNAL_BIT_WIDTH
+            );
+        }
+        if bit_width > crate::MAX_SIGNAL_BIT_WIDTH {
+            panic!(
+                "Cannot add a column called \"{}\" with {} bit(s). Columns must not be wider than {} bit(s).",
+                name,
+                bit_width,
+                crate::MAX_SIGNAL_BIT_WIDTH
+            );
+        }
+        if name.is_empty() || name.contains(&[',', '"', '\n', '\r'][..]) {
+            panic!("Cannot add a column called \"{}\". Column names must be non-empty and must not contain commas, quotes, or line breaks.", name);
+        }
+        if name == "cycle" || self.columns.iter().any(|column| column.name == name) {
+            panic!(
+                "Cannot add a column called \"{}\", because a column with the same name already exists.",
+                name
+            );
+        }
+        self.columns.push(Column {
+            name,
+            bit_width,
+            format,
+            accessor: Box::new(accessor),
+        });
+        self
+    }
+
+    /// Only appends a row when the cycle number passed to [`sample`](Self::sample) is a multiple of `n`, replacing any previously-specified sampling condition.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is `0`.
+    pub fn every_nth_cycle(mut self, n: u64) -> OutputLogger<S, W> {
+        if n == 0 {
+            panic!(
+                "Cannot sample every 0th cycle. The sampling interval must be at least 1 cycle."
+            );
+        }
+        self.condition = SampleCondition::EveryNthCycle(n);
+        self
+    }
+
+    /// Only appends a row when `condition` returns `true` for the simulator passed to [`sample`](Self::sample), replacing any previously-specified sampling condition.
+    ///
+    /// This is typically used with a 1-bit valid or strobe output, eg. `|sim: &MySim| sim.valid`.
+    pub fn sample_when(mut self, condition: impl Fn(&S) -> bool + 'static) -> OutputLogger<S, W> {
+        self.condition = SampleCondition::When(Box::new(condition));
+        self
+    }
+
+    /// Samples `sim` in cycle `cycle`, appending a row if the sampling condition is met.
+    ///
+    /// This is meant to be called once per cycle, after the simulator's outputs have been updated with `prop` (and before the next `posedge_clk`), so that each row reflects the outputs visible during that cycle. The header row is written on the first call, regardless of whether a row is appended.
+    pub fn sample(&mut self, sim: &S, cycle: u64) -> io::Result<()> {
+        if !self.has_written_header {
+            write!(self.writer, "cycle")?;
+            for column in self.columns.iter() {
+                write!(self.writer, ",{}", column.name)?;
+            }
+            writeln!(self.writer)?;
+            self.has_written_header = true;
+        }
+
+        let is_sampled = match self.condition {
+            SampleCondition::EveryCycle => true,
+            SampleCondition::EveryNthCycle(n) => cycle.is_multiple_of(n),
+            SampleCondition::When(ref condition) => condition(sim),
+        };
+        if !is_sampled {
+            return Ok(());
+        }
+
+        write!(self.writer, "{}", cycle)?;
+        for column in self.columns.iter() {
+            let value = (column.accessor)(sim);
+            let max_value = if column.bit_width == 128 {
+                u128::MAX
+            } else {
+                (1 << column.bit_width) - 1
+            };
+            let value = value & max_value;
+            match column.format {
+                ColumnFormat::Decimal => {
+                    let digits = max_value.to_string().len();
+                    write!(self.writer, ",{:0digits$}", value, digits = digits)?;
+                }
+                ColumnFormat::Hex => {
+                    let digits = column.bit_width.div_ceil(4) as usize;
+                    write!(self.writer, ",{:0digits$x}", value, digits = digits)?;
+                }
+            }
+        }
+        writeln!(self.writer)
+    }
+
+    /// Consumes this `OutputLogger`, returning the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestSim {
+        valid: bool,
+        a: u32,
+        b: u128,
+    }
+
+    fn run(logger: OutputLogger<TestSim, Vec<u8>>) -> String {
+        let mut logger = logger;
+        for cycle in 0..5u32 {
+            let sim = TestSim {
+                valid: cycle == 1 || cycle == 4,
+                a: cycle * 3,
+                b: u128::MAX - cycle as u128,
+            };
+            logger.sample(&sim, cycle as _).unwrap();
+        }
+        String::from_utf8(logger.into_inner()).unwrap()
+    }
+
+    #[test]
+    fn every_cycle() {
+        let logger = OutputLogger::new(Vec::new())
+            .column("valid", 1, ColumnFormat::Decimal, |sim: &TestSim| {
+                sim.valid.into()
+            })
+            .column("a", 10, ColumnFormat::Hex, |sim: &TestSim| sim.a.into())
+            .column("a_dec", 10, ColumnFormat::Decimal, |sim: &TestSim| {
+                sim.a.into()
+            });
+
+        assert_eq!(
+            run(logger),
+            "cycle,valid,a,a_dec\n\
+             0,0,000,0000\n\
+             1,1,003,0003\n\
+             2,0,006,0006\n\
+             3,0,009,0009\n\
+             4,1,00c,0012\n"
+        );
+    }
+
+    #[test]
+    fn every_nth_cycle() {
+        let logger = OutputLogger::new(Vec::new())
+            .column("a", 3, ColumnFormat::Decimal, |sim: &TestSim| sim.a.into())
+            .every_nth_cycle(2);
+
+        // Values are truncated to the column's bit width
+        assert_eq!(run(logger), "cycle,a\n0,0\n2,6\n4,4\n");
+    }
+
+    #[test]
+    fn sample_when() {
+        let logger = OutputLogger::new(Vec::new())
+            .column("b", 128, ColumnFormat::Hex, |sim: &TestSim| sim.b)
+            .column("b_low", 3, ColumnFormat::Hex, |sim: &TestSim| sim.b)
+            .sample_when(|sim: &TestSim| sim.valid);
+
+        assert_eq!(
+            run(logger),
+            "cycle,b,b_low\n\
+             1,fffffffffffffffffffffffffffffffe,6\n\
+             4,fffffffffffffffffffffffffffffffb,3\n"
+        );
+    }
+
+    #[test]
+    fn header_only() {
+        let mut logger = OutputLogger::new(Vec::new())
+            .column("a", 8, ColumnFormat::Hex, |sim: &TestSim| sim.a.into())
+            .sample_when(|_: &TestSim| false);
+        let sim = TestSim {
+            valid: true,
+            a: 0,
+            b: 0,
+        };
+        logger.sample(&sim, 0).unwrap();
+        logger.sample(&sim, 1).unwrap();
+
+        assert_eq!(String::from_utf8(logger.into_inner()).unwrap(), "cycle,a\n");
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot add a column called \"a\" with 0 bit(s). Columns must not be narrower than 1 bit(s)."
+    )]
+    fn column_bit_width_error() {
+        // Panic
+        let _ =
+            OutputLogger::<TestSim, _>::new(Vec::new())
+                .column("a", 0, ColumnFormat::Hex, |sim| sim.a.into());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot add a column called \"a,b\". Column names must be non-empty and must not contain commas, quotes, or line breaks."
+    )]
+    fn column_invalid_name_error() {
+        // Panic
+        let _ = OutputLogger::<TestSim, _>::new(Vec::new()).column(
+            "a,b",
+            1,
+            ColumnFormat::Hex,
+            |sim| sim.a.into(),
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot add a column called \"cycle\", because a column with the same name already exists."
+    )]
+    fn column_duplicate_name_error() {
+        // Panic
+        let _ = OutputLogger::<TestSim, _>::new(Vec::new()).column(
+            "cycle",
+            1,
+            ColumnFormat::Hex,
+            |sim| sim.a.into(),
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot sample every 0th cycle. The sampling interval must be at least 1 cycle."
+    )]
+    fn every_nth_cycle_zero_error() {
+        // Panic
+        let _ = OutputLogger::<TestSim, _>::new(Vec::new()).every_nth_cycle(0);
+    }
+}
@@ -0,0 +1,266 @@
+//! Host-side driver for debug ports created by [`components::debug_port`](crate::components::debug_port).
+//!
+//! A [`DebugPortDriver`] bit-bangs a debug port's serial protocol through a generated simulator's input and output fields, so that registers can be read and written in simulation exactly like they would be on hardware.
+//!
+//! # Examples
+//!
+//! ```
+//! use kaze::runtime::debug::*;
+//!
+//! // Stand-in for a generated simulator with a debug port whose ports are exposed as
+//! //  `dbg_shift`, `dbg_update`, `dbg_tdi`, and `dbg_tdo`
+//! struct Sim {
+//!     dbg_shift: bool,
+//!     dbg_update: bool,
+//!     dbg_tdi: bool,
+//!     dbg_tdo: bool,
+//! }
+//!
+//! impl Sim {
+//!     fn prop(&mut self) {}
+//!     fn posedge_clk(&mut self) {}
+//! }
+//!
+//! impl DebugPortPins for Sim {
+//!     fn set_shift(&mut self, value: bool) {
+//!         self.dbg_shift = value;
+//!     }
+//!
+//!     fn set_update(&mut self, value: bool) {
+//!         self.dbg_update = value;
+//!     }
+//!
+//!     fn set_tdi(&mut self, value: bool) {
+//!         self.dbg_tdi = value;
+//!     }
+//!
+//!     fn tdo(&self) -> bool {
+//!         self.dbg_tdo
+//!     }
+//!
+//!     fn cycle(&mut self) {
+//!         self.prop();
+//!         self.posedge_clk();
+//!         self.prop();
+//!     }
+//! }
+//!
+//! fn poke_control<S: DebugPortPins>(sim: &mut S) {
+//!     // Matches components::debug_port(m, "debug_port", "DebugPort", 4, 32)
+//!     let driver = DebugPortDriver::new(4, 32);
+//!     driver.write_reg(sim, 0, 0xfadebabe);
+//!     let _ = driver.read_reg(sim, 0);
+//! }
+//! ```
+
+/// Access to the ports of a debug port in a simulator, typically implemented by forwarding to a generated simulator's fields.
+pub trait DebugPortPins {
+    /// Sets the value of the debug port's `shift` input.
+    fn set_shift(&mut self, value: bool);
+    /// Sets the value of the debug port's `update` input.
+    fn set_update(&mut self, value: bool);
+    /// Sets the value of the debug port's `tdi` input.
+    fn set_tdi(&mut self, value: bool);
+    /// Returns the value of the debug port's `tdo` output.
+    fn tdo(&self) -> bool;
+    /// Advances the simulator by one cycle with the current input values, such that its outputs reflect its new state afterwards; for generated simulators, this is `prop`, followed by `posedge_clk`, followed by `prop`.
+    fn cycle(&mut self);
+}
+
+/// Reads and writes registers through a debug port with the given address and data bit widths.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct DebugPortDriver {
+    address_bit_width: u32,
+    data_bit_width: u32,
+}
+
+impl DebugPortDriver {
+    /// Creates a new `DebugPortDriver` for a debug port with `address_bit_width` address bits and `data_bit_width` data bits, which must match the values passed to [`components::debug_port`](crate::components::debug_port).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `address_bit_width` or `data_bit_width` is `0`, or if the resulting frame is wider than 128 bits.
+    pub fn new(address_bit_width: u32, data_bit_width: u32) -> DebugPortDriver {
+        if address_bit_width == 0
+            || data_bit_width == 0
+            || 1 + address_bit_width + data_bit_width > 128
+        {
+            panic!(
+                "Cannot create a debug port driver with {} address bit(s) and {} data bit(s). Debug ports must have at least 1 address bit and 1 data bit, and their frames (1 opcode bit, plus the address and data bits) must not be wider than 128 bit(s).",
+                address_bit_width, data_bit_width
+            );
+        }
+        DebugPortDriver {
+            address_bit_width,
+            data_bit_width,
+        }
+    }
+
+    /// Reads the register at `address` through the debug port in `sim`, and returns its value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `address` doesn't fit into this driver's address bit width.
+    pub fn read_reg<S: DebugPortPins>(&self, sim: &mut S, address: u128) -> u128 {
+        self.validate(address, "address", self.address_bit_width);
+        self.shift_frame(sim, address << 1);
+        self.update(sim);
+        // The captured value is shifted out while shifting in an (unexecuted) read of address 0
+        self.shift_frame(sim, 0) >> (1 + self.address_bit_width)
+    }
+
+    /// Writes `value` to the register at `address` through the debug port in `sim`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `address` or `value` don't fit into this driver's address or data bit widths, respectively.
+    pub fn write_reg<S: DebugPortPins>(&self, sim: &mut S, address: u128, value: u128) {
+        self.validate(address, "address", self.address_bit_width);
+        self.validate(value, "value", self.data_bit_width);
+        self.shift_frame(
+            sim,
+            (value << (1 + self.address_bit_width)) | (address << 1) | 1,
+        );
+        self.update(sim);
+    }
+
+    fn validate(&self, value: u128, description: &str, bit_width: u32) {
+        if bit_width < 128 && value >> bit_width != 0 {
+            panic!(
+                "Cannot access a debug port with {} {:#x}, because it doesn't fit into {} bit(s).",
+                description, value, bit_width
+            );
+        }
+    }
+
+    // Shifts `frame` in LSB-first, and returns the frame that was shifted out
+    fn shift_frame<S: DebugPortPins>(&self, sim: &mut S, frame: u128) -> u128 {
+        let frame_bit_width = 1 + self.address_bit_width + self.data_bit_width;
+        let mut shifted_out = 0;
+        sim.set_update(false);
+        sim.set_shift(true);
+        for i in 0..frame_bit_width {
+            shifted_out |= (sim.tdo() as u128) << i;
+            sim.set_tdi((frame >> i) & 1 != 0);
+            sim.cycle();
+        }
+        sim.set_shift(false);
+        sim.set_tdi(false);
+        shifted_out
+    }
+
+    fn update<S: DebugPortPins>(&self, sim: &mut S) {
+        sim.set_update(true);
+        sim.cycle();
+        sim.set_update(false);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Software model of a debug port with 2 address bits and 8 data bits in front of 4 registers
+    struct ModelSim {
+        shift: bool,
+        update: bool,
+        tdi: bool,
+        frame: u128,
+        registers: [u128; 4],
+    }
+
+    impl DebugPortPins for ModelSim {
+        fn set_shift(&mut self, value: bool) {
+            self.shift = value;
+        }
+
+        fn set_update(&mut self, value: bool) {
+            self.update = value;
+        }
+
+        fn set_tdi(&mut self, value: bool) {
+            self.tdi = value;
+        }
+
+        fn tdo(&self) -> bool {
+            self.frame & 1 != 0
+        }
+
+        fn cycle(&mut self) {
+            let address = ((self.frame >> 1) & 3) as usize;
+            if self.shift {
+                self.frame = (self.frame >> 1) | ((self.tdi as u128) << 10);
+            } else if self.update {
+                if self.frame & 1 != 0 {
+                    self.registers[address] = self.frame >> 3;
+                } else {
+                    self.frame = (self.frame & 7) | (self.registers[address] << 3);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn read_write() {
+        let mut sim = ModelSim {
+            shift: false,
+            update: false,
+            tdi: false,
+            frame: 0,
+            registers: [0x12, 0x34, 0x56, 0x78],
+        };
+        let driver = DebugPortDriver::new(2, 8);
+
+        assert_eq!(driver.read_reg(&mut sim, 2), 0x56);
+        driver.write_reg(&mut sim, 1, 0xab);
+        driver.write_reg(&mut sim, 3, 0xff);
+        assert_eq!(sim.registers, [0x12, 0xab, 0x56, 0xff]);
+        assert_eq!(driver.read_reg(&mut sim, 1), 0xab);
+        assert_eq!(driver.read_reg(&mut sim, 0), 0x12);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create a debug port driver with 64 address bit(s) and 64 data bit(s). Debug ports must have at least 1 address bit and 1 data bit, and their frames (1 opcode bit, plus the address and data bits) must not be wider than 128 bit(s)."
+    )]
+    fn new_frame_bit_width_error() {
+        // Panic
+        let _ = DebugPortDriver::new(64, 64);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot access a debug port with address 0x4, because it doesn't fit into 2 bit(s)."
+    )]
+    fn read_reg_address_error() {
+        let mut sim = ModelSim {
+            shift: false,
+            update: false,
+            tdi: false,
+            frame: 0,
+            registers: [0; 4],
+        };
+        let driver = DebugPortDriver::new(2, 8);
+
+        // Panic
+        let _ = driver.read_reg(&mut sim, 4);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot access a debug port with value 0x100, because it doesn't fit into 8 bit(s)."
+    )]
+    fn write_reg_value_error() {
+        let mut sim = ModelSim {
+            shift: false,
+            update: false,
+            tdi: false,
+            frame: 0,
+            registers: [0; 4],
+        };
+        let driver = DebugPortDriver::new(2, 8);
+
+        // Panic
+        driver.write_reg(&mut sim, 0, 0x100);
+    }
+}
@@ -0,0 +1,436 @@
+//! Seed-driven value spaces for property testing generated simulators.
+//!
+//! These utilities enumerate input values that are likely to exercise boundary conditions (zero, one, all ones, single-bit patterns, etc.) along with a deterministic set of pseudo-random fills, without depending on a particular property testing framework.
+//! The same seed always produces the same values in the same order, so failures are reproducible.
+//!
+//! # Examples
+//!
+//! ```
+//! use kaze::runtime::fuzz::*;
+//!
+//! let space = PortSpace::for_ports(vec![("a", 8), ("b", 32), ("s", 1)], 1024);
+//! for combination in space.combinations() {
+//!     let (a, b, s) = (combination[0] as u32, combination[1] as u32, combination[2] != 0);
+//!     // Drive a generated simulator's inputs with `a`, `b`, and `s`, call `prop`, and check its outputs
+//! }
+//! ```
+
+/// The maximum bit width for which a [`ValueSpace`] enumerates every possible value.
+pub const EXHAUSTIVE_MAX_BIT_WIDTH: u32 = 8;
+
+/// The seed used by [`ValueSpace::for_width`] and [`PortSpace::for_ports`].
+pub const DEFAULT_SEED: u64 = 0x6b617a65;
+
+const RANDOM_FILL_COUNT: usize = 16;
+
+/// An ordered, duplicate-free set of values for a signal of a given bit width.
+///
+/// For bit widths up to [`EXHAUSTIVE_MAX_BIT_WIDTH`], this contains every possible value in ascending order.
+/// For wider signals, it contains `0`, `1`, the maximum value, the maximum value minus one, the maximum signed value, each single-bit value, alternating bit patterns, and a number of pseudo-random fills derived from a seed, in that order.
+///
+/// # Examples
+///
+/// ```
+/// use kaze::runtime::fuzz::*;
+///
+/// assert_eq!(ValueSpace::for_width(2).values(), &[0, 1, 2, 3]);
+///
+/// let space = ValueSpace::for_width(32);
+/// assert_eq!(&space.values()[..4], &[0, 1, 0xffffffff, 0xfffffffe]);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ValueSpace {
+    bit_width: u32,
+    values: Vec<u128>,
+}
+
+impl ValueSpace {
+    /// Creates a `ValueSpace` for values with the given `bit_width`, using [`DEFAULT_SEED`] for any pseudo-random fills.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bit_width` is `0` or greater than `128`.
+    pub fn for_width(bit_width: u32) -> ValueSpace {
+        ValueSpace::for_width_with_seed(bit_width, DEFAULT_SEED)
+    }
+
+    /// Creates a `ValueSpace` for values with the given `bit_width`, using `seed` for any pseudo-random fills.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bit_width` is `0` or greater than `128`.
+    pub fn for_width_with_seed(bit_width: u32, seed: u64) -> ValueSpace {
+        if bit_width == 0 || bit_width > 128 {
+            panic!("Cannot create a value space for a bit width of {}. Bit widths must be in the range [1, 128].", bit_width);
+        }
+
+        let max = u128::MAX >> (128 - bit_width);
+
+        if bit_width <= EXHAUSTIVE_MAX_BIT_WIDTH {
+            return ValueSpace {
+                bit_width,
+                values: (0..=max).collect(),
+            };
+        }
+
+        let mut values = vec![0, 1, max, max - 1, max >> 1];
+        values.extend((1..bit_width).map(|i| 1 << i));
+        values.push(0x5555_5555_5555_5555_5555_5555_5555_5555 & max);
+        values.push(0xaaaa_aaaa_aaaa_aaaa_aaaa_aaaa_aaaa_aaaa & max);
+        let mut rng = Rng::new(seed);
+        values.extend((0..RANDOM_FILL_COUNT).map(|_| rng.next_u128() & max));
+
+        let mut unique_values = Vec::with_capacity(values.len());
+        for value in values {
+            if !unique_values.contains(&value) {
+                unique_values.push(value);
+            }
+        }
+
+        ValueSpace {
+            bit_width,
+            values: unique_values,
+        }
+    }
+
+    /// Returns the bit width of the values in this `ValueSpace`.
+    pub fn bit_width(&self) -> u32 {
+        self.bit_width
+    }
+
+    /// Returns the values in this `ValueSpace`, in a deterministic order.
+    pub fn values(&self) -> &[u128] {
+        &self.values
+    }
+
+    /// Returns `true` if this `ValueSpace` contains every possible value for its bit width.
+    pub fn is_exhaustive(&self) -> bool {
+        self.bit_width <= EXHAUSTIVE_MAX_BIT_WIDTH
+    }
+}
+
+/// A bounded set of value combinations for a list of named ports, composed from one [`ValueSpace`] per port.
+///
+/// If the full cross product of the ports' value spaces contains at most `max_combinations` combinations, [`PortSpace::combinations`] returns all of them, with the last port varying fastest.
+/// Otherwise, it first returns enough combinations that every value of every port's value space appears at least once (as far as `max_combinations` allows), then fills the remaining combinations by pseudo-random sampling from each port's value space.
+///
+/// # Examples
+///
+/// ```
+/// use kaze::runtime::fuzz::*;
+///
+/// let space = PortSpace::for_ports(vec![("a", 1), ("b", 2)], 16);
+/// assert_eq!(space.port_names(), &["a", "b"]);
+/// assert!(space.is_full_cross_product());
+/// assert_eq!(space.combinations().len(), 8);
+/// assert_eq!(space.combinations()[1], vec![0, 1]);
+///
+/// let space = PortSpace::for_ports(vec![("a", 32), ("b", 32)], 100);
+/// assert!(!space.is_full_cross_product());
+/// assert_eq!(space.combinations().len(), 100);
+/// ```
+#[derive(Clone, Debug)]
+pub struct PortSpace {
+    port_names: Vec<String>,
+    value_spaces: Vec<ValueSpace>,
+    max_combinations: usize,
+    seed: u64,
+}
+
+impl PortSpace {
+    /// Creates a `PortSpace` for `ports`, given as `(name, bit_width)` pairs, producing at most `max_combinations` combinations and using [`DEFAULT_SEED`] for any pseudo-random values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_combinations` is `0`, or if any port's bit width is `0` or greater than `128`.
+    pub fn for_ports<S: Into<String>>(
+        ports: impl IntoIterator<Item = (S, u32)>,
+        max_combinations: usize,
+    ) -> PortSpace {
+        PortSpace::for_ports_with_seed(ports, max_combinations, DEFAULT_SEED)
+    }
+
+    /// Creates a `PortSpace` for `ports`, given as `(name, bit_width)` pairs, producing at most `max_combinations` combinations and using `seed` for any pseudo-random values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_combinations` is `0`, or if any port's bit width is `0` or greater than `128`.
+    pub fn for_ports_with_seed<S: Into<String>>(
+        ports: impl IntoIterator<Item = (S, u32)>,
+        max_combinations: usize,
+        seed: u64,
+    ) -> PortSpace {
+        if max_combinations == 0 {
+            panic!("Cannot create a port space with a maximum of 0 combinations.");
+        }
+
+        let mut port_names = Vec::new();
+        let mut value_spaces = Vec::new();
+        for (index, (name, bit_width)) in ports.into_iter().enumerate() {
+            port_names.push(name.into());
+            // Derive a distinct seed per port so that ports with the same width don't receive identical random fills
+            value_spaces.push(ValueSpace::for_width_with_seed(
+                bit_width,
+                seed.wrapping_add(index as u64),
+            ));
+        }
+
+        PortSpace {
+            port_names,
+            value_spaces,
+            max_combinations,
+            seed,
+        }
+    }
+
+    /// Returns the names of the ports in this `PortSpace`, in the order their values appear in each combination.
+    pub fn port_names(&self) -> &[String] {
+        &self.port_names
+    }
+
+    /// Returns the [`ValueSpace`] for each port in this `PortSpace`.
+    pub fn value_spaces(&self) -> &[ValueSpace] {
+        &self.value_spaces
+    }
+
+    /// Returns `true` if [`PortSpace::combinations`] returns the full cross product of the ports' value spaces.
+    pub fn is_full_cross_product(&self) -> bool {
+        matches!(self.cross_product_len(), Some(len) if len <= self.max_combinations)
+    }
+
+    /// Returns the combinations in this `PortSpace`, each containing one value per port, in a deterministic order.
+    pub fn combinations(&self) -> Vec<Vec<u128>> {
+        if let Some(len) = self
+            .cross_product_len()
+            .filter(|&len| len <= self.max_combinations)
+        {
+            return (0..len)
+                .map(|mut index| {
+                    let mut combination = vec![0; self.value_spaces.len()];
+                    for (value, space) in combination.iter_mut().zip(self.value_spaces.iter()).rev()
+                    {
+                        let values = space.values();
+                        *value = values[index % values.len()];
+                        index /= values.len();
+                    }
+                    combination
+                })
+                .collect();
+        }
+
+        let covering_len = self
+            .value_spaces
+            .iter()
+            .map(|space| space.values().len())
+            .max()
+            .unwrap()
+            .min(self.max_combinations);
+        let mut combinations: Vec<Vec<u128>> = (0..covering_len)
+            .map(|index| {
+                self.value_spaces
+                    .iter()
+                    .map(|space| space.values()[index % space.values().len()])
+                    .collect()
+            })
+            .collect();
+
+        let mut rng = Rng::new(self.seed);
+        while combinations.len() < self.max_combinations {
+            combinations.push(
+                self.value_spaces
+                    .iter()
+                    .map(|space| {
+                        space.values()[(rng.next_u64() % space.values().len() as u64) as usize]
+                    })
+                    .collect(),
+            );
+        }
+
+        combinations
+    }
+
+    fn cross_product_len(&self) -> Option<usize> {
+        self.value_spaces
+            .iter()
+            .try_fold(1usize, |len, space| len.checked_mul(space.values().len()))
+    }
+}
+
+// SplitMix64; simple, fast, and well-behaved for any seed, including 0
+struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    fn next_u128(&mut self) -> u128 {
+        ((self.next_u64() as u128) << 64) | self.next_u64() as u128
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn value_space_width_1() {
+        let space = ValueSpace::for_width(1);
+
+        assert!(space.is_exhaustive());
+        assert_eq!(space.values(), &[0, 1]);
+    }
+
+    #[test]
+    fn value_space_width_2() {
+        let space = ValueSpace::for_width(2);
+
+        assert!(space.is_exhaustive());
+        assert_eq!(space.values(), &[0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn value_space_width_32() {
+        let space = ValueSpace::for_width(32);
+
+        assert!(!space.is_exhaustive());
+        assert_eq!(
+            &space.values()[..5],
+            &[0, 1, 0xffffffff, 0xfffffffe, 0x7fffffff]
+        );
+        for i in 1..32 {
+            assert!(space.values().contains(&(1 << i)));
+        }
+        assert!(space.values().contains(&0x55555555));
+        assert!(space.values().contains(&0xaaaaaaaa));
+        assert!(space.values().iter().all(|&value| value <= 0xffffffff));
+        assert!(space.values().len() > 5 + 31 + 2);
+    }
+
+    #[test]
+    fn value_space_width_33() {
+        let space = ValueSpace::for_width(33);
+
+        assert_eq!(
+            &space.values()[..5],
+            &[0, 1, 0x1ffffffff, 0x1fffffffe, 0xffffffff]
+        );
+        assert!(space.values().contains(&0x100000000));
+        assert!(space.values().contains(&0x155555555));
+        assert!(space.values().contains(&0x0aaaaaaaa));
+        assert!(space.values().iter().all(|&value| value <= 0x1ffffffff));
+    }
+
+    #[test]
+    fn value_space_width_128() {
+        let space = ValueSpace::for_width(128);
+
+        assert_eq!(
+            &space.values()[..5],
+            &[0, 1, u128::MAX, u128::MAX - 1, u128::MAX >> 1]
+        );
+        for i in 1..128 {
+            assert!(space.values().contains(&(1 << i)));
+        }
+        assert!(space
+            .values()
+            .contains(&0x5555_5555_5555_5555_5555_5555_5555_5555));
+        assert!(space
+            .values()
+            .contains(&0xaaaa_aaaa_aaaa_aaaa_aaaa_aaaa_aaaa_aaaa));
+    }
+
+    #[test]
+    fn value_space_is_deterministic_and_unique() {
+        assert_eq!(ValueSpace::for_width(64), ValueSpace::for_width(64));
+        assert_ne!(
+            ValueSpace::for_width_with_seed(64, 1),
+            ValueSpace::for_width_with_seed(64, 2)
+        );
+
+        let space = ValueSpace::for_width(16);
+        let mut values = space.values().to_vec();
+        values.sort();
+        values.dedup();
+        assert_eq!(values.len(), space.values().len());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create a value space for a bit width of 129. Bit widths must be in the range [1, 128]."
+    )]
+    fn value_space_width_oob_error() {
+        let _ = ValueSpace::for_width(129);
+    }
+
+    #[test]
+    fn port_space_full_cross_product() {
+        let space = PortSpace::for_ports(vec![("a", 1), ("b", 2)], 8);
+
+        assert!(space.is_full_cross_product());
+        assert_eq!(
+            space.combinations(),
+            vec![
+                vec![0, 0],
+                vec![0, 1],
+                vec![0, 2],
+                vec![0, 3],
+                vec![1, 0],
+                vec![1, 1],
+                vec![1, 2],
+                vec![1, 3],
+            ]
+        );
+    }
+
+    #[test]
+    fn port_space_no_ports() {
+        let space = PortSpace::for_ports(Vec::<(&str, u32)>::new(), 8);
+
+        assert!(space.is_full_cross_product());
+        assert_eq!(space.combinations(), vec![Vec::<u128>::new()]);
+    }
+
+    #[test]
+    fn port_space_bounded_sampling() {
+        let space = PortSpace::for_ports(vec![("a", 32), ("b", 2), ("c", 128)], 500);
+
+        assert!(!space.is_full_cross_product());
+
+        let combinations = space.combinations();
+        assert_eq!(combinations.len(), 500);
+        assert_eq!(combinations, space.combinations());
+
+        // Every value of every port appears at least once
+        for (index, value_space) in space.value_spaces().iter().enumerate() {
+            for value in value_space.values() {
+                assert!(combinations.iter().any(|c| c[index] == *value));
+            }
+        }
+    }
+
+    #[test]
+    fn port_space_bounded_sampling_truncates_covering_pass() {
+        let space = PortSpace::for_ports(vec![("a", 8), ("b", 8)], 10);
+
+        let combinations = space.combinations();
+        assert_eq!(combinations.len(), 10);
+        assert_eq!(combinations[9], vec![9, 9]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot create a port space with a maximum of 0 combinations.")]
+    fn port_space_zero_max_combinations_error() {
+        let _ = PortSpace::for_ports(vec![("a", 1)], 0);
+    }
+}
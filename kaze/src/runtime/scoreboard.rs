@@ -0,0 +1,339 @@
+//! In-order expected-vs-actual comparison for self-checking test benches.
+//!
+//! A [`Scoreboard`] queues values that a host-side model expects a generated simulator to produce, compares them against the values that are actually observed (typically whenever an output's valid strobe is high), and summarizes any differences in a [`Report`].
+//!
+//! # Examples
+//!
+//! ```
+//! use kaze::runtime::scoreboard::*;
+//!
+//! let mut scoreboard = Scoreboard::new();
+//!
+//! // Expected values, eg. from a reference model
+//! for value in &[1, 2, 3] {
+//!     scoreboard.expect(*value);
+//! }
+//!
+//! // Observed values, eg. sampled from a generated simulator's outputs each cycle
+//! for (cycle, &(valid, value)) in [(false, 0), (true, 1), (true, 2), (false, 0), (true, 3)]
+//!     .iter()
+//!     .enumerate()
+//! {
+//!     scoreboard.observe_if(valid, value, cycle as _);
+//! }
+//!
+//! let report = scoreboard.drain_report();
+//! assert!(report.is_clean(), "{}", report);
+//! ```
+
+use std::collections::VecDeque;
+use std::fmt;
+
+/// A value that didn't match the expected value it was compared against.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Mismatch<T> {
+    /// The cycle in which the value was observed.
+    pub cycle: u64,
+    /// The expected value.
+    pub expected: T,
+    /// The observed value.
+    pub actual: T,
+}
+
+/// A value that was observed while no values were expected.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UnexpectedObservation<T> {
+    /// The cycle in which the value was observed.
+    pub cycle: u64,
+    /// The observed value.
+    pub value: T,
+}
+
+/// A queue of expected values that observed values are compared against.
+///
+/// By default, values must be observed in the same order they're expected. Some designs legitimately reorder their results (eg. out-of-order completion of independent requests), so a scoreboard can instead be created with a reorder window of `n` values with [`with_reorder_window`](Self::with_reorder_window), in which case an observed value matches the first equal value among the `n` oldest outstanding expectations.
+///
+/// When an observed value doesn't match, it's recorded as a [`Mismatch`] against the oldest outstanding expectation, which is then discarded so that a single mismatch doesn't cause every subsequent comparison to fail.
+#[derive(Clone, Debug)]
+pub struct Scoreboard<T: Eq + fmt::Debug> {
+    reorder_window: usize,
+    expected: VecDeque<T>,
+    num_matched: usize,
+    mismatches: Vec<Mismatch<T>>,
+    unexpected_observations: Vec<UnexpectedObservation<T>>,
+}
+
+impl<T: Eq + fmt::Debug> Scoreboard<T> {
+    /// Creates a new, empty `Scoreboard` that requires values to be observed in order.
+    pub fn new() -> Scoreboard<T> {
+        Scoreboard::with_reorder_window(1)
+    }
+
+    /// Creates a new, empty `Scoreboard` where each observed value may match any of the `reorder_window` oldest outstanding expectations.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `reorder_window` is `0`.
+    pub fn with_reorder_window(reorder_window: usize) -> Scoreboard<T> {
+        if reorder_window == 0 {
+            panic!("Cannot create a scoreboard with a reorder window of 0. Reorder windows must contain at least 1 value.");
+        }
+
+        Scoreboard {
+            reorder_window,
+            expected: VecDeque::new(),
+            num_matched: 0,
+            mismatches: Vec::new(),
+            unexpected_observations: Vec::new(),
+        }
+    }
+
+    /// Adds `value` to the back of the queue of expected values.
+    pub fn expect(&mut self, value: T) {
+        self.expected.push_back(value);
+    }
+
+    /// Compares `value`, observed in `cycle`, against the outstanding expectations, and records the result.
+    pub fn observe(&mut self, value: T, cycle: u64) {
+        if self.expected.is_empty() {
+            self.unexpected_observations
+                .push(UnexpectedObservation { cycle, value });
+            return;
+        }
+
+        let window = self.reorder_window.min(self.expected.len());
+        match self.expected.iter().take(window).position(|v| *v == value) {
+            Some(index) => {
+                self.expected.remove(index);
+                self.num_matched += 1;
+            }
+            None => {
+                let expected = self.expected.pop_front().unwrap();
+                self.mismatches.push(Mismatch {
+                    cycle,
+                    expected,
+                    actual: value,
+                });
+            }
+        }
+    }
+
+    /// Calls [`observe`](Self::observe) with `value` and `cycle` if `valid` is `true`, and does nothing otherwise.
+    ///
+    /// This is meant to be called once per cycle with an output's valid strobe and data, so that values are only compared when they're valid.
+    pub fn observe_if(&mut self, valid: bool, value: T, cycle: u64) {
+        if valid {
+            self.observe(value, cycle);
+        }
+    }
+
+    /// Returns the expected values that haven't been observed yet, oldest first.
+    pub fn outstanding(&self) -> impl Iterator<Item = &T> {
+        self.expected.iter()
+    }
+
+    /// Returns the mismatches recorded so far.
+    pub fn mismatches(&self) -> &[Mismatch<T>] {
+        &self.mismatches
+    }
+
+    /// Returns the values that were observed while no values were expected so far.
+    pub fn unexpected_observations(&self) -> &[UnexpectedObservation<T>] {
+        &self.unexpected_observations
+    }
+
+    /// Returns a [`Report`] of everything recorded so far, including any outstanding expectations, and resets this `Scoreboard` to its initial state (keeping its reorder window).
+    pub fn drain_report(&mut self) -> Report<T> {
+        let report = Report {
+            num_matched: self.num_matched,
+            mismatches: std::mem::take(&mut self.mismatches),
+            outstanding: self.expected.drain(..).collect(),
+            unexpected_observations: std::mem::take(&mut self.unexpected_observations),
+        };
+        self.num_matched = 0;
+        report
+    }
+}
+
+impl<T: Eq + fmt::Debug> Default for Scoreboard<T> {
+    fn default() -> Scoreboard<T> {
+        Scoreboard::new()
+    }
+}
+
+/// A summary of a [`Scoreboard`]'s results, returned by [`Scoreboard::drain_report`].
+///
+/// The [`Display`](fmt::Display) implementation lists all problems along with the cycles they occurred in, which makes it suitable as an assertion message.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Report<T> {
+    /// The number of observed values that matched an expected value.
+    pub num_matched: usize,
+    /// All recorded mismatches, in the order they were observed.
+    pub mismatches: Vec<Mismatch<T>>,
+    /// All expected values that were never observed, oldest first.
+    pub outstanding: Vec<T>,
+    /// All values that were observed while no values were expected, in the order they were observed.
+    pub unexpected_observations: Vec<UnexpectedObservation<T>>,
+}
+
+impl<T> Report<T> {
+    /// Returns `true` if every expected value was observed, and nothing else was.
+    pub fn is_clean(&self) -> bool {
+        self.mismatches.is_empty()
+            && self.outstanding.is_empty()
+            && self.unexpected_observations.is_empty()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Display for Report<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} matched, {} mismatched, {} outstanding, {} unexpected",
+            self.num_matched,
+            self.mismatches.len(),
+            self.outstanding.len(),
+            self.unexpected_observations.len()
+        )?;
+        for mismatch in self.mismatches.iter() {
+            writeln!(
+                f,
+                "  mismatch in cycle {}: expected {:?}, observed {:?}",
+                mismatch.cycle, mismatch.expected, mismatch.actual
+            )?;
+        }
+        for value in self.outstanding.iter() {
+            writeln!(f, "  outstanding: expected {:?}", value)?;
+        }
+        for observation in self.unexpected_observations.iter() {
+            writeln!(
+                f,
+                "  unexpected in cycle {}: observed {:?}",
+                observation.cycle, observation.value
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn in_order() {
+        let mut scoreboard = Scoreboard::new();
+        for value in 0..4u32 {
+            scoreboard.expect(value);
+        }
+        for (cycle, value) in (0..4u32).enumerate() {
+            scoreboard.observe_if(false, 100, cycle as _);
+            scoreboard.observe(value, cycle as _);
+        }
+
+        let report = scoreboard.drain_report();
+        assert!(report.is_clean());
+        assert_eq!(report.num_matched, 4);
+        assert_eq!(
+            report.to_string(),
+            "4 matched, 0 mismatched, 0 outstanding, 0 unexpected\n"
+        );
+    }
+
+    #[test]
+    fn mismatch() {
+        let mut scoreboard = Scoreboard::new();
+        for value in &[1u32, 2, 3] {
+            scoreboard.expect(*value);
+        }
+        scoreboard.observe(1, 10);
+        scoreboard.observe(5, 11);
+        // The mismatched expectation is discarded, so later values still line up
+        scoreboard.observe(3, 12);
+
+        let report = scoreboard.drain_report();
+        assert!(!report.is_clean());
+        assert_eq!(
+            report.mismatches,
+            vec![Mismatch {
+                cycle: 11,
+                expected: 2,
+                actual: 5,
+            }]
+        );
+        assert_eq!(
+            report.to_string(),
+            "2 matched, 1 mismatched, 0 outstanding, 0 unexpected\n  \
+             mismatch in cycle 11: expected 2, observed 5\n"
+        );
+    }
+
+    #[test]
+    fn outstanding_and_unexpected() {
+        let mut scoreboard = Scoreboard::new();
+        scoreboard.observe(7u32, 0);
+        scoreboard.expect(8);
+        scoreboard.expect(9);
+        scoreboard.observe(8, 1);
+
+        assert_eq!(scoreboard.outstanding().collect::<Vec<_>>(), vec![&9]);
+        let report = scoreboard.drain_report();
+        assert_eq!(report.outstanding, vec![9]);
+        assert_eq!(
+            report.unexpected_observations,
+            vec![UnexpectedObservation { cycle: 0, value: 7 }]
+        );
+        assert_eq!(
+            report.to_string(),
+            "1 matched, 0 mismatched, 1 outstanding, 1 unexpected\n  \
+             outstanding: expected 9\n  \
+             unexpected in cycle 0: observed 7\n"
+        );
+
+        // Draining resets the scoreboard
+        assert!(scoreboard.drain_report().is_clean());
+    }
+
+    #[test]
+    fn reorder_window() {
+        let mut scoreboard = Scoreboard::with_reorder_window(2);
+        for value in &[1u32, 2, 3, 4] {
+            scoreboard.expect(*value);
+        }
+        // Swapped pairs are within the window
+        scoreboard.observe(2, 0);
+        scoreboard.observe(1, 1);
+        // 4 is within the window [3, 4], leaving 3 outstanding
+        scoreboard.observe(4, 2);
+        let report = scoreboard.drain_report();
+        assert_eq!(report.num_matched, 3);
+        assert!(report.mismatches.is_empty());
+        assert_eq!(report.outstanding, vec![3]);
+
+        let mut scoreboard = Scoreboard::with_reorder_window(2);
+        for value in &[1u32, 2, 3] {
+            scoreboard.expect(*value);
+        }
+        // 3 is outside the window [1, 2]
+        scoreboard.observe(3, 0);
+        let report = scoreboard.drain_report();
+        assert_eq!(
+            report.mismatches,
+            vec![Mismatch {
+                cycle: 0,
+                expected: 1,
+                actual: 3,
+            }]
+        );
+        assert_eq!(report.outstanding, vec![2, 3]);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot create a scoreboard with a reorder window of 0. Reorder windows must contain at least 1 value."
+    )]
+    fn reorder_window_zero_error() {
+        // Panic
+        let _ = Scoreboard::<u32>::with_reorder_window(0);
+    }
+}
@@ -0,0 +1,418 @@
+//! Orchestration of named regression scenarios against a generated simulator.
+//!
+//! A [`Suite`] runs a set of named scenarios, each against a fresh simulator instance, and aggregates their outcomes into a [`Summary`]. When a scenario fails, it's automatically re-run with tracing enabled, so that the failure can be inspected with a [`Recorder`] without paying for tracing in passing scenarios.
+//!
+//! # Examples
+//!
+//! ```
+//! use kaze::runtime::suite::*;
+//!
+//! // Stand-in for a generated simulator
+//! #[derive(Default)]
+//! struct Sim {
+//!     i: u32,
+//!     o: u32,
+//! }
+//!
+//! impl Sim {
+//!     fn prop(&mut self) {
+//!         self.o = self.i * 2;
+//!     }
+//! }
+//!
+//! let summary = Suite::new(|_| Sim::default())
+//!     .scenario("double", |sim| {
+//!         sim.i = 3;
+//!         sim.prop();
+//!         if sim.o == 6 {
+//!             Ok(())
+//!         } else {
+//!             Err(format!("expected 6, got {}", sim.o))
+//!         }
+//!     })
+//!     .scenario("zero", |sim| {
+//!         sim.prop();
+//!         if sim.o == 0 {
+//!             Ok(())
+//!         } else {
+//!             Err(format!("expected 0, got {}", sim.o))
+//!         }
+//!     })
+//!     .run();
+//!
+//! assert!(summary.is_success(), "{}", summary);
+//! assert_eq!(summary.num_passed(), 2);
+//! ```
+
+use super::tracing::recorder::Recorder;
+
+use std::env;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// The name of the environment variable read by [`Suite::filter_from_env`].
+pub const FILTER_ENV_VAR: &str = "KAZE_SUITE_FILTER";
+
+type SimFactory<S> = Box<dyn Fn(Option<Recorder>) -> S>;
+type ScenarioFn<S> = Box<dyn Fn(&mut S) -> Result<(), String>>;
+
+struct Scenario<S> {
+    name: String,
+    run: ScenarioFn<S>,
+}
+
+/// A set of named scenarios that are run against fresh instances of a simulator of type `S`.
+///
+/// Each scenario is a closure that drives a simulator and returns `Err` with a failure message if the simulator didn't behave as expected. Scenarios are run sequentially, in the order they were added.
+///
+/// Simulators are created by the factory passed to [`new`](Self::new), which receives `None` for normal runs, and a [`Recorder`] when a failed scenario is re-run to capture a trace. For simulators generated with tracing enabled, the factory typically passes the recorder (or a fresh one, if it's `None`) to the simulator's `new` constructor; simulators without tracing can ignore it.
+pub struct Suite<S> {
+    new_sim: SimFactory<S>,
+    scenarios: Vec<Scenario<S>>,
+    fail_fast: bool,
+    filter: Option<String>,
+}
+
+impl<S> Suite<S> {
+    /// Creates a new `Suite` without any scenarios that creates simulators with `new_sim`.
+    pub fn new(new_sim: impl Fn(Option<Recorder>) -> S + 'static) -> Suite<S> {
+        Suite {
+            new_sim: Box::new(new_sim),
+            scenarios: Vec::new(),
+            fail_fast: false,
+            filter: None,
+        }
+    }
+
+    /// Adds a scenario called `name` that's run by calling `run` with a fresh simulator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this `Suite` already has a scenario called `name`.
+    pub fn scenario(
+        mut self,
+        name: impl Into<String>,
+        run: impl Fn(&mut S) -> Result<(), String> + 'static,
+    ) -> Suite<S> {
+        let name = name.into();
+        if self.scenarios.iter().any(|scenario| scenario.name == name) {
+            panic!(
+                "Cannot add a scenario called \"{}\", because a scenario with the same name already exists.",
+                name
+            );
+        }
+        self.scenarios.push(Scenario {
+            name,
+            run: Box::new(run),
+        });
+        self
+    }
+
+    /// Skips all remaining scenarios after the first failure if `fail_fast` is `true`.
+    pub fn fail_fast(mut self, fail_fast: bool) -> Suite<S> {
+        self.fail_fast = fail_fast;
+        self
+    }
+
+    /// Only runs scenarios whose names contain `filter`, skipping all others. This replaces any previously-specified filter.
+    pub fn filter(mut self, filter: impl Into<String>) -> Suite<S> {
+        self.filter = Some(filter.into());
+        self
+    }
+
+    /// Like [`filter`](Self::filter), but uses the value of the [`FILTER_ENV_VAR`] environment variable, if it's set. Otherwise, this `Suite` is returned unchanged.
+    pub fn filter_from_env(self) -> Suite<S> {
+        match env::var(FILTER_ENV_VAR) {
+            Ok(filter) => self.filter(filter),
+            Err(_) => self,
+        }
+    }
+
+    /// Runs all scenarios and returns a [`Summary`] of their outcomes.
+    ///
+    /// Each failed scenario is run a second time with a [`Recorder`], which is stored in its [`ScenarioResult::trace`]. The failure message and duration are those of the first run.
+    pub fn run(&self) -> Summary {
+        let mut results = Vec::new();
+        let mut has_failed = false;
+        for scenario in self.scenarios.iter() {
+            let is_selected = match self.filter {
+                Some(ref filter) => scenario.name.contains(filter.as_str()),
+                None => true,
+            };
+            if !is_selected || (self.fail_fast && has_failed) {
+                results.push(ScenarioResult {
+                    name: scenario.name.clone(),
+                    outcome: Outcome::Skipped,
+                    duration: Duration::default(),
+                    trace: None,
+                });
+                continue;
+            }
+
+            let mut sim = (self.new_sim)(None);
+            let start = Instant::now();
+            let result = (scenario.run)(&mut sim);
+            let duration = start.elapsed();
+
+            let (outcome, trace) = match result {
+                Ok(()) => (Outcome::Passed, None),
+                Err(message) => {
+                    has_failed = true;
+                    let recorder = Recorder::new();
+                    let mut sim = (self.new_sim)(Some(recorder.clone()));
+                    // The outcome of the traced run is irrelevant; we only want its trace
+                    let _ = (scenario.run)(&mut sim);
+                    (Outcome::Failed(message), Some(recorder))
+                }
+            };
+            results.push(ScenarioResult {
+                name: scenario.name.clone(),
+                outcome,
+                duration,
+                trace,
+            });
+        }
+
+        Summary { results }
+    }
+}
+
+/// The outcome of a single scenario in a [`Summary`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Outcome {
+    /// The scenario returned `Ok`.
+    Passed,
+    /// The scenario returned `Err` with the contained message.
+    Failed(String),
+    /// The scenario wasn't run, because it didn't match the suite's filter, or because an earlier scenario failed in a fail-fast suite.
+    Skipped,
+}
+
+/// The result of a single scenario in a [`Summary`].
+#[derive(Clone)]
+pub struct ScenarioResult {
+    /// The scenario's name.
+    pub name: String,
+    /// The scenario's outcome.
+    pub outcome: Outcome,
+    /// How long the scenario took to run, or zero if it was skipped.
+    pub duration: Duration,
+    /// The trace captured by re-running the scenario if it failed, or `None` otherwise.
+    pub trace: Option<Recorder>,
+}
+
+/// A summary of a [`Suite`]'s results, returned by [`Suite::run`].
+///
+/// The [`Display`](fmt::Display) implementation lists every scenario's outcome and duration along with any failure messages, which makes it suitable as an assertion message or for printing at the end of a regression run.
+#[derive(Clone)]
+pub struct Summary {
+    /// The results of all scenarios, in the order they were added.
+    pub results: Vec<ScenarioResult>,
+}
+
+impl Summary {
+    /// Returns the number of scenarios that passed.
+    pub fn num_passed(&self) -> usize {
+        self.num_with_outcome(|outcome| *outcome == Outcome::Passed)
+    }
+
+    /// Returns the number of scenarios that failed.
+    pub fn num_failed(&self) -> usize {
+        self.num_with_outcome(|outcome| matches!(outcome, Outcome::Failed(_)))
+    }
+
+    /// Returns the number of scenarios that were skipped.
+    pub fn num_skipped(&self) -> usize {
+        self.num_with_outcome(|outcome| *outcome == Outcome::Skipped)
+    }
+
+    /// Returns `true` if no scenario failed, eg. for choosing a process exit code.
+    pub fn is_success(&self) -> bool {
+        self.num_failed() == 0
+    }
+
+    /// Returns the result of the scenario called `name`, or `None` if there's no such scenario.
+    pub fn result(&self, name: &str) -> Option<&ScenarioResult> {
+        self.results.iter().find(|result| result.name == name)
+    }
+
+    fn num_with_outcome(&self, predicate: impl Fn(&Outcome) -> bool) -> usize {
+        self.results
+            .iter()
+            .filter(|result| predicate(&result.outcome))
+            .count()
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} passed, {} failed, {} skipped",
+            self.num_passed(),
+            self.num_failed(),
+            self.num_skipped()
+        )?;
+        for result in self.results.iter() {
+            match result.outcome {
+                Outcome::Passed => {
+                    writeln!(f, "  passed  {} ({:?})", result.name, result.duration)?
+                }
+                Outcome::Failed(ref message) => writeln!(
+                    f,
+                    "  FAILED  {} ({:?}): {}",
+                    result.name, result.duration, message
+                )?,
+                Outcome::Skipped => writeln!(f, "  skipped {}", result.name)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tracing::*;
+    use super::*;
+
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    struct TestSim {
+        count: u32,
+        trace: Option<(Recorder, usize)>,
+    }
+
+    impl TestSim {
+        fn new(recorder: Option<Recorder>) -> TestSim {
+            let trace = recorder.map(|mut recorder| {
+                recorder.push_module("top").unwrap();
+                let count = recorder
+                    .add_signal("count", 32, TraceValueType::U32)
+                    .unwrap();
+                recorder.pop_module().unwrap();
+                (recorder, count)
+            });
+            TestSim { count: 0, trace }
+        }
+
+        fn posedge_clk(&mut self) {
+            self.count += 1;
+            if let Some((ref mut recorder, ref count)) = self.trace {
+                recorder.update_time_stamp(self.count as _).unwrap();
+                recorder
+                    .update_signal(count, TraceValue::U32(self.count))
+                    .unwrap();
+            }
+        }
+    }
+
+    fn expect_count(sim: &TestSim, expected: u32) -> Result<(), String> {
+        if sim.count == expected {
+            Ok(())
+        } else {
+            Err(format!("expected count {}, got {}", expected, sim.count))
+        }
+    }
+
+    fn suite(num_sims: Rc<Cell<u32>>) -> Suite<TestSim> {
+        Suite::new(move |recorder| {
+            num_sims.set(num_sims.get() + 1);
+            TestSim::new(recorder)
+        })
+        .scenario("count_2", |sim| {
+            sim.posedge_clk();
+            sim.posedge_clk();
+            expect_count(sim, 2)
+        })
+        .scenario("count_3_wrong", |sim| {
+            for _ in 0..3 {
+                sim.posedge_clk();
+            }
+            expect_count(sim, 4)
+        })
+        .scenario("count_0", |sim| expect_count(sim, 0))
+    }
+
+    #[test]
+    fn summary() {
+        let num_sims = Rc::new(Cell::new(0));
+        let summary = suite(num_sims.clone()).run();
+
+        assert_eq!(summary.num_passed(), 2);
+        assert_eq!(summary.num_failed(), 1);
+        assert_eq!(summary.num_skipped(), 0);
+        assert!(!summary.is_success());
+        assert_eq!(
+            summary
+                .results
+                .iter()
+                .map(|result| result.name.as_str())
+                .collect::<Vec<_>>(),
+            ["count_2", "count_3_wrong", "count_0"]
+        );
+        assert_eq!(summary.result("count_2").unwrap().outcome, Outcome::Passed);
+        assert_eq!(
+            summary.result("count_3_wrong").unwrap().outcome,
+            Outcome::Failed("expected count 4, got 3".into())
+        );
+        assert_eq!(summary.result("count_0").unwrap().outcome, Outcome::Passed);
+        assert!(summary.result("missing").is_none());
+        assert!(summary.to_string().contains("FAILED  count_3_wrong"));
+
+        // One sim per scenario, plus one for re-running the failed scenario
+        assert_eq!(num_sims.get(), 4);
+    }
+
+    #[test]
+    fn trace_on_failure() {
+        let summary = suite(Rc::new(Cell::new(0))).run();
+
+        assert!(summary.result("count_2").unwrap().trace.is_none());
+        assert!(summary.result("count_0").unwrap().trace.is_none());
+        let trace = summary
+            .result("count_3_wrong")
+            .unwrap()
+            .trace
+            .clone()
+            .unwrap();
+        assert_eq!(trace.signal_paths(), ["top.count"]);
+        assert_eq!(trace.value_at("top.count", 1), Some(1));
+        assert_eq!(trace.value_at("top.count", 3), Some(3));
+    }
+
+    #[test]
+    fn fail_fast() {
+        let summary = suite(Rc::new(Cell::new(0))).fail_fast(true).run();
+
+        assert_eq!(summary.num_passed(), 1);
+        assert_eq!(summary.num_failed(), 1);
+        assert_eq!(summary.num_skipped(), 1);
+        assert_eq!(summary.result("count_0").unwrap().outcome, Outcome::Skipped);
+        assert_eq!(
+            summary.result("count_0").unwrap().duration,
+            Duration::default()
+        );
+    }
+
+    #[test]
+    fn filter() {
+        let num_sims = Rc::new(Cell::new(0));
+        let summary = suite(num_sims.clone()).filter("count_").filter("_0").run();
+
+        assert!(summary.is_success());
+        assert_eq!(summary.num_passed(), 1);
+        assert_eq!(summary.num_skipped(), 2);
+        assert_eq!(summary.result("count_0").unwrap().outcome, Outcome::Passed);
+        assert_eq!(num_sims.get(), 1);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot add a scenario called \"count_0\", because a scenario with the same name already exists."
+    )]
+    fn scenario_duplicate_name_error() {
+        // Panic
+        let _ = suite(Rc::new(Cell::new(0))).scenario("count_0", |_| Ok(()));
+    }
+}
@@ -1,10 +1,14 @@
 //! Rust simulator runtime dependencies for tracing.
 
+pub mod recorder;
 pub mod vcd;
 
 use std::io;
 
 // TODO: Do we want to re-use graph::Constant for this? They're equivalent but currently distinct in their usage, so I'm not sure it's the right API design decision.
+/// A traced signal's value, passed to [`Trace::update_signal`].
+///
+/// The variant only describes how the value is stored, which is the smallest of these types that can hold the signal's bits (see [`TraceValueType`]). A signal's actual bit width is the `bit_width` passed to [`Trace::add_signal`], and bits above it are always `0`.
 #[derive(Debug, Eq, PartialEq)]
 pub enum TraceValue {
     /// Contains a boolean value
@@ -17,6 +21,31 @@ pub enum TraceValue {
     U128(u128),
 }
 
+impl TraceValue {
+    /// Returns this value zero-extended to a `u128`, regardless of how it's stored.
+    pub fn to_u128(&self) -> u128 {
+        match *self {
+            TraceValue::Bool(value) => value as _,
+            TraceValue::U32(value) => value as _,
+            TraceValue::U64(value) => value as _,
+            TraceValue::U128(value) => value,
+        }
+    }
+
+    /// Returns the [`TraceValueType`] that describes how this value is stored.
+    pub fn type_(&self) -> TraceValueType {
+        match self {
+            TraceValue::Bool(_) => TraceValueType::Bool,
+            TraceValue::U32(_) => TraceValueType::U32,
+            TraceValue::U64(_) => TraceValueType::U64,
+            TraceValue::U128(_) => TraceValueType::U128,
+        }
+    }
+}
+
+/// Describes how a traced signal's values are stored, passed to [`Trace::add_signal`].
+///
+/// Signals are stored in the smallest type that can hold all of their bits, so this is only a hint about a signal's bit width; use the `bit_width` passed to [`Trace::add_signal`] instead.
 #[derive(Debug, Eq, PartialEq)]
 pub enum TraceValueType {
     Bool,
@@ -39,6 +68,16 @@ impl TraceValueType {
             unreachable!()
         }
     }
+
+    /// Returns the number of bits available to store values of this type, which is an upper bound for the bit width of any signal with this type.
+    pub fn storage_bit_width(&self) -> u32 {
+        match self {
+            TraceValueType::Bool => 1,
+            TraceValueType::U32 => 32,
+            TraceValueType::U64 => 64,
+            TraceValueType::U128 => 128,
+        }
+    }
 }
 
 pub trait Trace {
@@ -46,6 +85,9 @@ pub trait Trace {
 
     fn push_module(&mut self, name: &'static str) -> io::Result<()>;
     fn pop_module(&mut self) -> io::Result<()>;
+    /// Adds a signal called `name` with `bit_width` bits to the current module.
+    ///
+    /// `bit_width` is authoritative: `type_` only describes how the signal's values will be stored when passed to [`update_signal`](Self::update_signal), which may have more bits (for example, a 4-bit signal is stored as [`TraceValueType::U32`]).
     fn add_signal(
         &mut self,
         name: &'static str,
@@ -54,5 +96,40 @@ pub trait Trace {
     ) -> io::Result<Self::SignalId>;
 
     fn update_time_stamp(&mut self, time_stamp: u64) -> io::Result<()>;
+    /// Updates the value of the signal with `signal_id`. `value`'s type always matches the `type_` the signal was added with, and its bits above the signal's `bit_width` are always `0`.
     fn update_signal(&mut self, signal_id: &Self::SignalId, value: TraceValue) -> io::Result<()>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_u128() {
+        assert_eq!(TraceValue::Bool(false).to_u128(), 0);
+        assert_eq!(TraceValue::Bool(true).to_u128(), 1);
+        assert_eq!(TraceValue::U32(0xf).to_u128(), 0xf);
+        assert_eq!(
+            TraceValue::U64(0xdead_beef_cafe).to_u128(),
+            0xdead_beef_cafe
+        );
+        assert_eq!(TraceValue::U128(u128::MAX).to_u128(), u128::MAX);
+    }
+
+    #[test]
+    fn types() {
+        for &bit_width in &[1, 2, 4, 31, 32, 33, 63, 64, 65, 127, 128] {
+            let type_ = TraceValueType::from_bit_width(bit_width);
+            assert!(bit_width <= type_.storage_bit_width());
+            let value = match type_ {
+                TraceValueType::Bool => TraceValue::Bool(true),
+                TraceValueType::U32 => TraceValue::U32(1),
+                TraceValueType::U64 => TraceValue::U64(1),
+                TraceValueType::U128 => TraceValue::U128(1),
+            };
+            assert_eq!(value.type_(), type_);
+        }
+        assert_eq!(TraceValueType::from_bit_width(4), TraceValueType::U32);
+        assert_eq!(TraceValueType::U32.storage_bit_width(), 32);
+    }
+}
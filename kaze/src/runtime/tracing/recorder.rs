@@ -0,0 +1,386 @@
+//! In-memory tracing implementation, with plain-text waveform rendering for quick debugging.
+
+use super::*;
+
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::io;
+use std::ops::Range;
+use std::rc::Rc;
+
+/// The maximum number of waveform columns produced by [`Recorder::render`], not including labels. Longer ranges are truncated with an ellipsis (`…`).
+pub const MAX_RENDER_COLUMNS: usize = 120;
+
+/// A [`Trace`] implementation that records all signal values in memory.
+///
+/// A `Recorder` is a cheap handle to shared state, so a clone can be passed to a generated simulator while the original is kept for inspecting or [rendering](Self::render) the recorded values, eg. in a failing assertion's message.
+/// Signals are identified by their path, which consists of the names of their enclosing modules and their own name, separated by `.` (eg. `top.inner.o`).
+///
+/// # Examples
+///
+/// ```
+/// use kaze::runtime::tracing::recorder::*;
+/// use kaze::runtime::tracing::*;
+///
+/// # fn main() -> std::io::Result<()> {
+/// let mut recorder = Recorder::new();
+///
+/// // Normally a generated simulator would drive the trace; we drive it by hand here
+/// let mut trace = recorder.clone();
+/// trace.push_module("m")?;
+/// let o = trace.add_signal("o", 1, TraceValueType::Bool)?;
+/// trace.pop_module()?;
+/// for time_stamp in 0..4 {
+///     trace.update_time_stamp(time_stamp)?;
+///     trace.update_signal(&o, TraceValue::Bool(time_stamp >= 2))?;
+/// }
+///
+/// assert_eq!(recorder.value_at("m.o", 1), Some(0));
+/// assert_eq!(recorder.value_at("m.o", 3), Some(1));
+/// assert_eq!(recorder.render(&["m.o"], 0..4), "time 0 2\nm.o  ▁▁▆▆\n");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct Recorder {
+    state: Rc<RefCell<RecorderState>>,
+}
+
+#[derive(Default)]
+struct RecorderState {
+    module_path: Vec<&'static str>,
+    signals: Vec<RecordedSignal>,
+    time_stamps: BTreeSet<u64>,
+    time_stamp: u64,
+}
+
+struct RecordedSignal {
+    path: String,
+    bit_width: u32,
+    samples: Vec<(u64, u128)>,
+}
+
+impl RecordedSignal {
+    fn value_at(&self, time_stamp: u64) -> Option<u128> {
+        self.samples
+            .iter()
+            .rev()
+            .find(|&&(sample_time_stamp, _)| sample_time_stamp <= time_stamp)
+            .map(|&(_, value)| value)
+    }
+}
+
+impl Recorder {
+    /// Creates a new, empty `Recorder`.
+    pub fn new() -> Recorder {
+        Recorder::default()
+    }
+
+    /// Returns the paths of all recorded signals, in the order they were added.
+    pub fn signal_paths(&self) -> Vec<String> {
+        self.state
+            .borrow()
+            .signals
+            .iter()
+            .map(|signal| signal.path.clone())
+            .collect()
+    }
+
+    /// Returns the value of the signal at `path` at `time_stamp`, which is the value of the latest sample at or before `time_stamp`, or `None` if there's no such sample.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no signal with the specified `path` was recorded.
+    pub fn value_at(&self, path: &str, time_stamp: u64) -> Option<u128> {
+        let state = self.state.borrow();
+        state.signal(path).value_at(time_stamp)
+    }
+
+    /// Renders the signals at `paths` over the time stamps in `range` as aligned text lanes, one per signal, below a time axis.
+    ///
+    /// Each time stamp occupies the same number of columns in every lane, which is determined by the widest signal.
+    /// Single-bit signals are drawn as level traces (`▁` for low, `▆` for high), and multi-bit signals are drawn as hex values that are only repeated when they change, each preceded by a transition marker (`│`).
+    /// Time stamps that weren't recorded (eg. because the trace was only updated every few cycles) and time stamps before a signal's first sample are drawn as gaps (`·`).
+    /// If the range doesn't fit into [`MAX_RENDER_COLUMNS`] columns, it's truncated, and each lane ends with an ellipsis (`…`).
+    ///
+    /// # Panics
+    ///
+    /// Panics if no signal with one of the specified `paths` was recorded.
+    pub fn render(&self, paths: &[&str], range: Range<u64>) -> String {
+        let state = self.state.borrow();
+        let signals = paths
+            .iter()
+            .map(|&path| state.signal(path))
+            .collect::<Vec<_>>();
+
+        let label_width = paths
+            .iter()
+            .map(|path| path.chars().count())
+            .chain(std::iter::once(TIME_LABEL.len()))
+            .max()
+            .unwrap();
+        let column_width = signals
+            .iter()
+            .map(|signal| match signal.bit_width {
+                1 => 1,
+                bit_width => num_hex_digits(bit_width) + 1,
+            })
+            .max()
+            .unwrap_or(1);
+
+        let max_steps = (MAX_RENDER_COLUMNS / column_width).max(1) as u64;
+        let num_steps = range.end.saturating_sub(range.start);
+        let (end, is_truncated) = if num_steps > max_steps {
+            (range.start + max_steps, true)
+        } else {
+            (range.end, false)
+        };
+        let steps = range.start..end;
+
+        let mut lines = Vec::new();
+
+        let mut axis = Vec::new();
+        for (index, time_stamp) in steps.clone().enumerate() {
+            let label = time_stamp.to_string();
+            let start = index * column_width;
+            // Labels are only written where they don't overlap the previous label
+            if start < axis.len() {
+                continue;
+            }
+            axis.resize(start, ' ');
+            axis.extend(label.chars());
+            axis.push(' ');
+        }
+        axis.truncate(steps.clone().count() * column_width);
+        lines.push((TIME_LABEL.to_string(), axis.into_iter().collect::<String>()));
+
+        for (&path, signal) in paths.iter().zip(signals.iter()) {
+            let mut lane = String::new();
+            let mut previous_value = None;
+            for time_stamp in steps.clone() {
+                let value = if state.time_stamps.contains(&time_stamp) {
+                    signal.value_at(time_stamp)
+                } else {
+                    None
+                };
+                match value {
+                    None => lane.push_str(&"·".repeat(column_width)),
+                    Some(value) if signal.bit_width == 1 => {
+                        let level = if value != 0 { '▆' } else { '▁' };
+                        lane.push_str(&level.to_string().repeat(column_width));
+                    }
+                    Some(value) if previous_value != Some(value) => {
+                        let digits = format!(
+                            "{:0width$x}",
+                            value,
+                            width = num_hex_digits(signal.bit_width)
+                        );
+                        lane.push_str(&format!("│{:<width$}", digits, width = column_width - 1));
+                    }
+                    Some(_) => lane.push_str(&" ".repeat(column_width)),
+                }
+                previous_value = value;
+            }
+            if is_truncated {
+                lane.push('…');
+            }
+            lines.push((path.to_string(), lane));
+        }
+
+        let mut ret = String::new();
+        for (label, lane) in lines {
+            let line = format!(
+                "{}{} {}",
+                label,
+                " ".repeat(label_width - label.chars().count()),
+                lane
+            );
+            ret.push_str(line.trim_end());
+            ret.push('\n');
+        }
+        ret
+    }
+}
+
+const TIME_LABEL: &str = "time";
+
+fn num_hex_digits(bit_width: u32) -> usize {
+    bit_width.div_ceil(4) as usize
+}
+
+impl RecorderState {
+    fn signal(&self, path: &str) -> &RecordedSignal {
+        self.signals
+            .iter()
+            .find(|signal| signal.path == path)
+            .unwrap_or_else(|| {
+                panic!(
+                    "Cannot find recorded signal \"{}\". Recorded signals: {:?}.",
+                    path,
+                    self.signals
+                        .iter()
+                        .map(|signal| signal.path.as_str())
+                        .collect::<Vec<_>>()
+                )
+            })
+    }
+}
+
+impl Trace for Recorder {
+    type SignalId = usize;
+
+    fn push_module(&mut self, name: &'static str) -> io::Result<()> {
+        self.state.borrow_mut().module_path.push(name);
+
+        Ok(())
+    }
+
+    fn pop_module(&mut self) -> io::Result<()> {
+        self.state.borrow_mut().module_path.pop();
+
+        Ok(())
+    }
+
+    fn add_signal(
+        &mut self,
+        name: &'static str,
+        bit_width: u32,
+        _type_: TraceValueType,
+    ) -> io::Result<Self::SignalId> {
+        let mut state = self.state.borrow_mut();
+        let mut path = state.module_path.join(".");
+        if !path.is_empty() {
+            path.push('.');
+        }
+        path.push_str(name);
+
+        let id = state.signals.len();
+        state.signals.push(RecordedSignal {
+            path,
+            bit_width,
+            samples: Vec::new(),
+        });
+
+        Ok(id)
+    }
+
+    fn update_time_stamp(&mut self, time_stamp: u64) -> io::Result<()> {
+        let mut state = self.state.borrow_mut();
+        state.time_stamp = time_stamp;
+        state.time_stamps.insert(time_stamp);
+
+        Ok(())
+    }
+
+    fn update_signal(&mut self, signal_id: &Self::SignalId, value: TraceValue) -> io::Result<()> {
+        let mut state = self.state.borrow_mut();
+        let time_stamp = state.time_stamp;
+        let value = value.to_u128();
+
+        let samples = &mut state.signals[*signal_id].samples;
+        match samples.last_mut() {
+            Some(sample) if sample.0 == time_stamp => sample.1 = value,
+            _ => samples.push((time_stamp, value)),
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record_scenario() -> io::Result<Recorder> {
+        let recorder = Recorder::new();
+
+        let mut trace = recorder.clone();
+        trace.push_module("m")?;
+        let o1 = trace.add_signal("o1", 1, TraceValueType::Bool)?;
+        let r1 = trace.add_signal("r1", 8, TraceValueType::U32)?;
+        trace.pop_module()?;
+
+        // Time stamp 4 is never recorded
+        for &(time_stamp, o1_value, r1_value) in &[
+            (0, false, 0x00),
+            (1, false, 0x00),
+            (2, true, 0x1f),
+            (3, true, 0x1f),
+            (5, false, 0x0a),
+            (6, false, 0x0a),
+        ] {
+            trace.update_time_stamp(time_stamp)?;
+            trace.update_signal(&o1, TraceValue::Bool(o1_value))?;
+            trace.update_signal(&r1, TraceValue::U32(r1_value))?;
+        }
+
+        Ok(recorder)
+    }
+
+    #[test]
+    fn render() -> io::Result<()> {
+        let recorder = record_scenario()?;
+
+        assert_eq!(
+            recorder.render(&["m.o1", "m.r1"], 0..7),
+            "time 0  1  2  3  4  5  6\n\
+             m.o1 ▁▁▁▁▁▁▆▆▆▆▆▆···▁▁▁▁▁▁\n\
+             m.r1 │00   │1f   ···│0a\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn render_single_bit_columns() -> io::Result<()> {
+        let recorder = record_scenario()?;
+
+        assert_eq!(
+            recorder.render(&["m.o1"], 1..6),
+            "time 1 3 5\n\
+             m.o1 ▁▆▆·▁\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn render_truncates_long_ranges() -> io::Result<()> {
+        let recorder = record_scenario()?;
+
+        let rendered = recorder.render(&["m.o1"], 0..1000);
+        let lane = rendered.lines().nth(1).unwrap();
+        assert!(lane.starts_with("m.o1 ▁▁▆▆·▁▁"));
+        assert!(lane.ends_with('…'));
+        assert_eq!(lane.chars().count(), "m.o1 ".len() + MAX_RENDER_COLUMNS + 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn value_at() -> io::Result<()> {
+        let recorder = record_scenario()?;
+
+        assert_eq!(
+            recorder.signal_paths(),
+            vec!["m.o1".to_string(), "m.r1".to_string()]
+        );
+        assert_eq!(recorder.value_at("m.r1", 3), Some(0x1f));
+        // Unrecorded time stamps hold the previous value
+        assert_eq!(recorder.value_at("m.r1", 4), Some(0x1f));
+        assert_eq!(recorder.value_at("m.r1", 100), Some(0x0a));
+
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot find recorded signal \"m.o2\". Recorded signals: [\"m.o1\", \"m.r1\"]."
+    )]
+    fn render_unknown_signal_error() {
+        let recorder = record_scenario().unwrap();
+
+        // Panic
+        let _ = recorder.render(&["m.o1", "m.o2"], 0..7);
+    }
+}
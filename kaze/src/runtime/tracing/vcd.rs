@@ -101,21 +101,11 @@ impl<W: io::Write> Trace for VcdTrace<W> {
         // TODO: Type check incoming value!
         let signal = &self.signals[*signal_id];
 
+        // The signal's bit width is authoritative; the value's type only describes how it's stored
+        let value = value.to_u128();
         if let TraceValueType::Bool = signal.type_ {
-            self.w.change_scalar(
-                signal.id,
-                match value {
-                    TraceValue::Bool(value) => value,
-                    TraceValue::U32(_) | TraceValue::U64(_) | TraceValue::U128(_) => unreachable!(),
-                },
-            )?;
+            self.w.change_scalar(signal.id, value != 0)?;
         } else {
-            let value = match value {
-                TraceValue::Bool(_) => unreachable!(),
-                TraceValue::U32(value) => value as _,
-                TraceValue::U64(value) => value as _,
-                TraceValue::U128(value) => value,
-            };
             let mut scalar_values = [vcd::Value::V0; 128];
             for i in 0..signal.bit_width as usize {
                 scalar_values[i] = ((value >> (signal.bit_width as usize - 1 - i)) & 1 != 0).into();
@@ -1,28 +1,154 @@
 //! Rust simulator code generation.
+//!
+//! The generated struct is named after the top-level module, and has a public field for each of its inputs and outputs. Names that are Rust keywords are emitted as raw identifiers (eg. an input called `match` becomes the field `r#match`), except for keywords that can't be raw identifiers, which have `_` appended (eg. `self` becomes `self_`). Characters that can't appear in an identifier are replaced with `_` (eg. a module called `fifo<8>` becomes the struct `fifo_8_`). Code generation panics if two inputs or outputs would end up with the same field name, or if an input or output's field name would start with `__`, which is reserved for the generated struct's private fields.
+//!
+//! If the design contains any [`Mem`](crate::Mem)s, the generated struct also has a `load_mem_contents(&mut self, name: &str, contents: &[u128])` method, which replaces the contents of the memory called `name` at runtime (for example, to load different programs into the same generated CPU model), and a `with_mem_contents(self, name: &str, contents: &[u128]) -> Self` method that does the same for a newly-constructed struct (eg. `Cpu::new().with_mem_contents("cpu.rom", &program)`). Memories are named by their module's instance path and their own name, separated by `.`. `contents` is in address order, and may contain fewer elements than the memory, in which case the remaining elements are set to `0`. Both methods panic if there's no memory called `name`, if `contents` contains more elements than the memory, or if any element doesn't fit into the memory's element bit width.
 
 mod compiler;
+mod identifiers;
 mod ir;
 
 use compiler::*;
+use identifiers::*;
 use ir::*;
 
 use typed_arena::Arena;
 
 use crate::code_writer;
 use crate::graph;
+use crate::identifiers::{is_identifier, sanitize};
+use crate::optimizer::*;
 use crate::runtime::tracing::*;
 use crate::state_elements::*;
 use crate::validation::*;
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::io::{Result, Write};
 
+/// The visibility of the items in generated code, as selected by [`GenerationOptions::module_visibility`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Visibility {
+    /// `pub`
+    #[default]
+    Public,
+    /// `pub(crate)`
+    Crate,
+    /// `pub(super)`
+    Super,
+    /// Private (no visibility qualifier).
+    Private,
+}
+
+impl Visibility {
+    /// Returns the qualifier for this visibility, followed by a space if it's not empty.
+    fn qualifier(&self) -> &'static str {
+        match self {
+            Visibility::Public => "pub ",
+            Visibility::Crate => "pub(crate) ",
+            Visibility::Super => "pub(super) ",
+            Visibility::Private => "",
+        }
+    }
+}
+
+/// Options that control the Rust simulator code produced by [`generate`].
 #[derive(Default)]
 pub struct GenerationOptions {
+    /// If `Some`, the generated struct is called `name` instead of being named after the module.
     pub override_module_name: Option<String>,
+    /// Determines the generated constructors.
+    ///
+    /// If `false`, the generated struct has a `new()` constructor and implements [`Default`].
+    /// If `true`, it's generic over a [`Trace`](crate::runtime::tracing::Trace) implementation and has a `with_trace(name, trace)` constructor, where `name` is the name of the top-level module in the trace, as well as a `new(trace)` constructor that uses the module's instance name.
     pub tracing: bool,
+    /// If `true`, constant subexpressions are folded and trivial logic (such as `x & 0`, `x | 0`, or a [`mux`](crate::Signal::mux) with a constant condition) is simplified before code is generated.
+    ///
+    /// Registers whose next value is a constant (directly, or through a chain of other such registers) and whose default value is equal to that constant are replaced by the constant as well, and a comment is emitted for each folded register. Registers without a matching default value or with attributes are never folded.
+    pub optimize: bool,
+    /// If `Some`, the clock method is called `posedge_<name>` instead of `posedge_clk` (and the method for [registers updated on negative clock edges](crate::Register::clock_edge) is called `negedge_<name>` instead of `negedge_clk`).
+    pub override_clock_name: Option<String>,
+    /// If `Some`, the reset method is called `<name>` instead of `reset`, and the reset method for each named reset domain is called `<name>_<domain>` instead of `reset_<domain>`. [`generate`] panics if any of these methods would be named after a Rust keyword or have the same name as another generated method.
+    pub override_reset_name: Option<String>,
+    /// If `true`, the reset method (but not the reset methods for named reset domains) also restores the contents of all [`Mem`](crate::Mem)s: memories with [initial contents](crate::Mem::initial_contents) are restored to those contents, and all others are cleared to `0`.
+    ///
+    /// This also discards any contents loaded at runtime with the generated `load_mem_contents` or `with_mem_contents` methods.
+    ///
+    /// If `false`, memories are only initialized when the generated struct is constructed, and keep their contents across resets.
+    /// This matches generated Verilog code, where memories are never reset; enabling this option is meant for test benches that reuse a simulator instance, and makes the simulator diverge from the generated Verilog code.
+    pub reset_mems: bool,
+    /// If `true`, registers without [default values](crate::Register::default_value) start at pseudo-random values instead of `0`, and are assigned new pseudo-random values each time the reset method (but not the reset methods for named reset domains) is called.
+    ///
+    /// In generated Verilog code, such registers have undefined values until they're first driven, so a design that accidentally relies on them being `0` can behave correctly in simulation and fail in hardware. Poisoning them makes these bugs visible in simulation.
+    ///
+    /// The values are derived from a seed, which is `0` for newly-constructed structs and can be changed with the generated `set_poison_seed(&mut self, seed: u64)` method. Setting the seed assigns new values to these registers immediately, so a test bench can sweep a range of seeds, and reproduce any failure by reusing the same seed.
+    pub poison_uninitialized_regs: bool,
+    /// If `true`, outputs created with [`output_dont_care_when`](crate::Module::output_dont_care_when) are driven with the inverse of their (possibly [optimized](Self::optimize)) sources while their don't care conditions are high, so that logic that depends on their values while they're unspecified produces different results than it would otherwise, which can be caught in tests.
+    ///
+    /// If `false`, such outputs are driven with their (possibly optimized) sources, just like other outputs.
+    pub poison_dont_care_outputs: bool,
+    /// If `true`, the generated code only depends on `core`, so that it can be used in `#![no_std]` crates (for example, as a golden model on an embedded target).
+    ///
+    /// In this case, [`Mem`](crate::Mem) contents are stored in fixed-size arrays instead of heap-allocated slices (so large memories make the generated struct correspondingly large), and the `dump_state` method (which returns a `String`) is not generated.
+    ///
+    /// If `false`, the generated code may depend on `std`.
+    pub no_std: bool,
+    /// If `true`, the generated `update_trace` method keeps a copy of the last value it passed to the [`Trace`](crate::runtime::tracing::Trace) for each traced signal, and only calls [`update_signal`](crate::runtime::tracing::Trace::update_signal) for signals whose values have changed since then.
+    ///
+    /// All signals are still updated on the first call after construction and on the first call after any reset method is called, so that traces always start from a complete set of values.
+    ///
+    /// If `false`, `update_trace` updates every traced signal on each call. This option has no effect unless [`tracing`](Self::tracing) is enabled.
+    pub trace_on_change_only: bool,
+    /// If `true`, the generated `prop` method checks that each of the top-level [`Module`](crate::Module)'s inputs has no bits set above its declared bit width, and panics with a message naming the offending input if it does.
+    ///
+    /// These checks are generated with `debug_assert!`, so they're only performed in debug builds.
+    ///
+    /// If `false`, any bits above an input's declared bit width are silently masked off when the input is read.
+    pub strict_inputs: bool,
+    /// If `true`, assertions created with [`Module::assert_always`](crate::Module::assert_always) that fail in a call to the generated `prop` method push their failure messages into the generated struct's `assertion_failures: Vec<&'static str>` field, rather than panicking. The field is never cleared by generated code, so a test bench can check it (and clear it) whenever it's convenient.
+    ///
+    /// This option can't be combined with [`no_std`](Self::no_std), since it requires `Vec`, and can't be used if the top-level module has an input or output called `assertion_failures`.
+    pub collect_assertion_failures: bool,
+    /// If `true`, the values of 1-bit registers (along with their next values), as well as the previous values of 1-bit traced signals kept when [`trace_on_change_only`](Self::trace_on_change_only) is enabled, are packed into the bits of a private `[u64; N]` field instead of each being stored in a separate `bool` field. This makes the generated struct considerably smaller for designs with many 1-bit registers, which can improve cache behavior in large simulations, at the cost of a few bit operations per access.
+    ///
+    /// Public fields (the top-level module's inputs and outputs) are still `bool`s, and the generated struct behaves identically either way.
+    pub pack_bools: bool,
+    /// If `true`, `m`'s hierarchy must not contain any registers, memories, assertions, or coverage points, and code generation panics if it does. This guarantees that the generated struct has no clock or reset methods, so it can be driven by only setting its inputs and calling `prop`.
+    ///
+    /// The clock and reset names aren't used in this case, so they aren't validated (and inputs and outputs can have the names they'd otherwise have). This option can't be combined with [`poison_uninitialized_regs`](Self::poison_uninitialized_regs).
+    pub combinational_only: bool,
+    /// If `true`, a `<Name>Inputs` struct and a `<Name>Outputs` struct are generated alongside the generated struct (where `<Name>` is the generated struct's name), with a public field for each of the top-level module's inputs and outputs respectively, using the same field names and types. Both implement `Clone`, `Copy`, `Debug`, `Default`, `Eq`, and `PartialEq`, so that whole sets of inputs and outputs can be written as table-driven test vectors and compared with a single `assert_eq!`.
+    ///
+    /// The generated struct also gets a `set_inputs(&mut self, inputs: &<Name>Inputs)` method that sets all of its inputs at once, and an `outputs(&self) -> <Name>Outputs` method that returns a snapshot of all of its outputs (as of the last call to `prop`).
+    pub io_structs: bool,
+    /// If `Some`, expressions that would make a line of generated code longer than `max_line_width` columns are wrapped onto continuation lines (indented one level deeper) at operator boundaries, so that large expressions produce small diffs when the design changes. Lines may still exceed `max_line_width` when they contain a single long token (such as an identifier).
+    ///
+    /// Wrapped code is always valid Rust, but isn't necessarily formatted the way `rustfmt` would format it.
+    ///
+    /// If `None`, each expression is written on a single line.
+    pub max_line_width: Option<u32>,
+    /// The visibility of the generated struct (as well as the structs generated by [`io_structs`](Self::io_structs)). Their fields and methods are always `pub`, so they're accessible wherever the structs are.
+    pub module_visibility: Visibility,
+    /// If `Some`, the generated code is wrapped in a private module called `name`, and the generated struct (as well as the structs generated by [`io_structs`](Self::io_structs)) is re-exported from it with a `use` declaration with the [`module_visibility`](Self::module_visibility) visibility, so that generated code can be included in a module that contains other items without name collisions.
+    ///
+    /// Code generation panics if `name` isn't a valid Rust identifier (it must be non-empty, consist only of ASCII alphanumeric characters and underscores, not start with a digit, and not be a keyword), or if it's the same as the name of one of the re-exported structs.
+    pub wrapper_module: Option<String>,
+    /// If `true`, the generated struct and its input and output fields (as well as the structs generated by [`io_structs`](Self::io_structs) and their fields) get doc comments with the names of the corresponding module, inputs, and outputs, and their bit widths, so that generated code can be used in crates that deny the `missing_docs` lint.
+    ///
+    /// If `false`, only [attributes](crate::Attribute) are emitted as doc comments.
+    pub doc_comments: bool,
 }
 
+/// Generates a Rust simulator for `m` and writes it to `w`.
+///
+/// Only `m` and the modules instantiated (transitively) from it are validated and included in the generated code, so other modules in the same [`Context`](crate::Context) (including incomplete or invalid ones) don't affect it.
+///
+/// Unless [`no_std`](GenerationOptions::no_std) is enabled, the generated struct has a `dump_state(&self) -> String` method that formats its inputs, outputs, and registers one per line, in a stable order.
+///
+/// Inputs and outputs wider than 64 bits get `set_<input>_from_le_bytes(&mut self, bytes: &[u8; N])` and `<output>_to_le_bytes(&self) -> [u8; N]` methods on the generated struct respectively, where `N` is the number of bytes needed to hold the signal's bit width. Bits in the last byte above an input's bit width are ignored.
+///
+/// # Panics
+///
+/// Panics if `m`'s module hierarchy fails [validation](crate::validate), or if `options` are invalid for it.
 // TODO: Note that mutable writer reference can be passed, see https://rust-lang.github.io/api-guidelines/interoperability.html#c-rw-value
 pub fn generate<'a, W: Write>(
     m: &'a graph::Module<'a>,
@@ -31,6 +157,30 @@ pub fn generate<'a, W: Write>(
 ) -> Result<()> {
     validate_module_hierarchy(m);
 
+    let clock_name = options
+        .override_clock_name
+        .clone()
+        .unwrap_or_else(|| "clk".into());
+    let reset_name = options
+        .override_reset_name
+        .clone()
+        .unwrap_or_else(|| "reset".into());
+    if options.combinational_only {
+        if options.poison_uninitialized_regs {
+            panic!("Cannot generate code for module \"{}\" with both combinational_only and poison_uninitialized_regs enabled, because combinational-only modules don't contain any registers.", m.name);
+        }
+        validate_combinational_only(m);
+    } else {
+        validate_clock_and_reset_names(m, &clock_name, &reset_name);
+    }
+    validate_port_identifiers(m, &options);
+    if options.no_std && options.tracing {
+        panic!("Cannot generate code for module \"{}\" with both no_std and tracing enabled, because tracing requires std.", m.name);
+    }
+    if options.no_std && options.collect_assertion_failures {
+        panic!("Cannot generate code for module \"{}\" with both no_std and collect_assertion_failures enabled, because collecting assertion failures requires std.", m.name);
+    }
+
     // TODO: Consider exposing as a codegen option (and testing both variants)
     let included_ports = if options.tracing {
         IncludedPorts::All
@@ -38,13 +188,27 @@ pub fn generate<'a, W: Write>(
         IncludedPorts::ReachableFromTopLevelOutputs
     };
 
+    let optimizer =
+        Optimizer::new(options.optimize).with_dont_care_poisoning(options.poison_dont_care_outputs);
+
     let mut signal_reference_counts = HashMap::new();
-    let state_elements = StateElements::new(m, included_ports, &mut signal_reference_counts);
+    let state_elements = StateElements::new(
+        m,
+        included_ports,
+        Hierarchy::Flattened,
+        &optimizer,
+        &mut signal_reference_counts,
+    );
+    if !options.combinational_only {
+        validate_reset_method_names(m, &state_elements, &reset_name);
+    }
 
     struct TraceSignal {
+        index: u32,
         name: String,
         member_name: String,
         value_name: String,
+        prev_value_name: String,
         bit_width: u32,
         type_: TraceValueType,
     }
@@ -52,12 +216,19 @@ pub fn generate<'a, W: Write>(
     let mut num_trace_signals = 0;
     let mut add_trace_signal = |module, name, value_name, bit_width| {
         if options.tracing {
-            let member_name = format!("__trace_signal_id_{}_{}", name, num_trace_signals);
+            let member_name =
+                sanitize(&format!("__trace_signal_id_{}_{}", name, num_trace_signals));
+            let prev_value_name = sanitize(&format!(
+                "__trace_prev_value_{}_{}",
+                name, num_trace_signals
+            ));
             let module_trace_signals = trace_signals.entry(module).or_insert(Vec::new());
             module_trace_signals.push(TraceSignal {
+                index: num_trace_signals,
                 name,
                 member_name,
                 value_name,
+                prev_value_name,
                 bit_width,
                 type_: TraceValueType::from_bit_width(bit_width),
             });
@@ -67,21 +238,36 @@ pub fn generate<'a, W: Write>(
 
     let expr_arena = Arena::new();
     let mut prop_context = AssignmentContext::new(&expr_arena);
-    let mut c = Compiler::new(&state_elements, &signal_reference_counts, &expr_arena);
+    let mut packed_bools = PackedBools::new();
+    if options.pack_bools {
+        for reg in state_elements.sorted_regs() {
+            if reg.data.bit_width == 1 {
+                packed_bools.add(&reg.value_name);
+                packed_bools.add(&reg.next_name);
+            }
+        }
+    }
+    let mut c = Compiler::new(
+        &state_elements,
+        &optimizer,
+        &signal_reference_counts,
+        &packed_bools,
+        &expr_arena,
+    );
     for (name, input) in m.inputs.borrow().iter() {
-        add_trace_signal(m, name.clone(), name.clone(), input.data.bit_width);
+        add_trace_signal(m, name.clone(), identifier(name), input.data.bit_width);
     }
     for (name, output) in m.outputs.borrow().iter() {
-        let expr = c.compile_signal(output.data.source, &mut prop_context);
+        let expr = c.compile_output(output.data, &mut prop_context);
         prop_context.push(Assignment {
             target: expr_arena.alloc(Expr::Ref {
-                name: name.clone(),
+                name: identifier(name),
                 scope: Scope::Member,
             }),
             expr,
         });
 
-        add_trace_signal(m, name.clone(), name.clone(), output.data.bit_width);
+        add_trace_signal(m, name.clone(), identifier(name), output.data.bit_width);
     }
     struct InnerField {
         name: String,
@@ -100,7 +286,7 @@ pub fn generate<'a, W: Write>(
             // TODO: Identify and fix duplicate signals in traces
             for (name, &input) in module.inputs.borrow().iter() {
                 // TODO: De-dupe inner field allocs
-                let field_name = format!("__inner_{}_{}", name, inner_fields.len());
+                let field_name = sanitize(&format!("__inner_{}_{}", name, inner_fields.len()));
                 inner_fields.push(InnerField {
                     name: field_name.clone(),
                     bit_width: input.data.bit_width,
@@ -119,12 +305,12 @@ pub fn generate<'a, W: Write>(
             }
             for (name, &output) in module.outputs.borrow().iter() {
                 // TODO: De-dupe inner field allocs
-                let field_name = format!("__inner_{}_{}", name, inner_fields.len());
+                let field_name = sanitize(&format!("__inner_{}_{}", name, inner_fields.len()));
                 inner_fields.push(InnerField {
                     name: field_name.clone(),
                     bit_width: output.data.bit_width,
                 });
-                let expr = c.compile_signal(output.data.source, prop_context);
+                let expr = c.compile_output(output.data, prop_context);
                 prop_context.push(Assignment {
                     target: expr_arena.alloc(Expr::Ref {
                         name: field_name.clone(),
@@ -135,6 +321,14 @@ pub fn generate<'a, W: Write>(
 
                 add_trace_signal(module, name.clone(), field_name, output.data.bit_width);
             }
+            visit_named_wires(
+                module,
+                c,
+                inner_fields,
+                prop_context,
+                expr_arena,
+                add_trace_signal,
+            );
             for child in module.modules.borrow().iter() {
                 visit_module(
                     child,
@@ -148,6 +342,41 @@ pub fn generate<'a, W: Write>(
 
             Ok(())
         }
+        fn visit_named_wires<'graph, 'context, 'expr_arena>(
+            module: &'graph graph::Module<'graph>,
+            c: &mut Compiler<'graph, 'context, 'expr_arena>,
+            inner_fields: &mut Vec<InnerField>,
+            prop_context: &mut AssignmentContext<'expr_arena>,
+            expr_arena: &'expr_arena Arena<Expr>,
+            add_trace_signal: &mut impl FnMut(&'graph graph::Module<'graph>, String, String, u32),
+        ) {
+            for (name, &named_wire) in module.named_wires.borrow().iter() {
+                let field_name = sanitize(&format!("__inner_{}_{}", name, inner_fields.len()));
+                let bit_width = named_wire.bit_width();
+                inner_fields.push(InnerField {
+                    name: field_name.clone(),
+                    bit_width,
+                });
+                let expr = c.compile_signal(named_wire, prop_context);
+                prop_context.push(Assignment {
+                    target: expr_arena.alloc(Expr::Ref {
+                        name: field_name.clone(),
+                        scope: Scope::Member,
+                    }),
+                    expr,
+                });
+
+                add_trace_signal(module, name.clone(), field_name, bit_width);
+            }
+        }
+        visit_named_wires(
+            m,
+            &mut c,
+            &mut inner_fields,
+            &mut prop_context,
+            &expr_arena,
+            &mut add_trace_signal,
+        );
         for child in m.modules.borrow().iter() {
             visit_module(
                 child,
@@ -159,8 +388,115 @@ pub fn generate<'a, W: Write>(
             )?;
         }
     }
-    for (graph_mem, mem) in state_elements.mems.iter() {
-        for ((address, enable), read_signal_names) in mem.read_signal_names.iter() {
+    struct Assertion {
+        path: String,
+        field_name: String,
+        message: Option<String>,
+    }
+    let mut assertions = Vec::new();
+    fn visit_assertions<'graph, 'context, 'expr_arena>(
+        module: &'graph graph::Module<'graph>,
+        c: &mut Compiler<'graph, 'context, 'expr_arena>,
+        inner_fields: &mut Vec<InnerField>,
+        prop_context: &mut AssignmentContext<'expr_arena>,
+        expr_arena: &'expr_arena Arena<Expr>,
+        assertions: &mut Vec<Assertion>,
+    ) {
+        for (name, assertion) in module.assertions.borrow().iter() {
+            let field_name = format!("__assertion_{}_{}", name, assertions.len());
+            inner_fields.push(InnerField {
+                name: field_name.clone(),
+                bit_width: 1,
+            });
+            let expr = c.compile_signal(assertion.cond, prop_context);
+            prop_context.push(Assignment {
+                target: expr_arena.alloc(Expr::Ref {
+                    name: field_name.clone(),
+                    scope: Scope::Member,
+                }),
+                expr,
+            });
+
+            assertions.push(Assertion {
+                path: format!("{}.{}", module.instance_path(), name),
+                field_name,
+                message: assertion.message.clone(),
+            });
+        }
+        for child in module.modules.borrow().iter() {
+            visit_assertions(child, c, inner_fields, prop_context, expr_arena, assertions);
+        }
+    }
+    visit_assertions(
+        m,
+        &mut c,
+        &mut inner_fields,
+        &mut prop_context,
+        &expr_arena,
+        &mut assertions,
+    );
+    struct CoverPoint {
+        path: String,
+        field_name: String,
+        count_name: String,
+    }
+    let mut cover_points = Vec::new();
+    fn visit_cover_points<'graph, 'context, 'expr_arena>(
+        module: &'graph graph::Module<'graph>,
+        c: &mut Compiler<'graph, 'context, 'expr_arena>,
+        inner_fields: &mut Vec<InnerField>,
+        prop_context: &mut AssignmentContext<'expr_arena>,
+        expr_arena: &'expr_arena Arena<Expr>,
+        cover_points: &mut Vec<CoverPoint>,
+    ) {
+        for (name, &cond) in module.cover_points.borrow().iter() {
+            let field_name = format!("__cover_{}_{}", name, cover_points.len());
+            inner_fields.push(InnerField {
+                name: field_name.clone(),
+                bit_width: 1,
+            });
+            let count_name = format!("__cover_count_{}_{}", name, cover_points.len());
+            inner_fields.push(InnerField {
+                name: count_name.clone(),
+                bit_width: 64,
+            });
+            let expr = c.compile_signal(cond, prop_context);
+            prop_context.push(Assignment {
+                target: expr_arena.alloc(Expr::Ref {
+                    name: field_name.clone(),
+                    scope: Scope::Member,
+                }),
+                expr,
+            });
+
+            cover_points.push(CoverPoint {
+                path: format!("{}.{}", module.instance_path(), name),
+                field_name,
+                count_name,
+            });
+        }
+        for child in module.modules.borrow().iter() {
+            visit_cover_points(
+                child,
+                c,
+                inner_fields,
+                prop_context,
+                expr_arena,
+                cover_points,
+            );
+        }
+    }
+    visit_cover_points(
+        m,
+        &mut c,
+        &mut inner_fields,
+        &mut prop_context,
+        &expr_arena,
+        &mut cover_points,
+    );
+    for mem in state_elements.sorted_mems() {
+        let graph_mem = mem.mem;
+        for ((address, enable), read_signal_names) in mem.sorted_read_signal_names() {
             let address = c.compile_signal(address, &mut prop_context);
             prop_context.push(Assignment {
                 target: expr_arena.alloc(Expr::Ref {
@@ -237,14 +573,19 @@ pub fn generate<'a, W: Write>(
             );
         }
     }
-    for (_, reg) in state_elements.regs.iter() {
+    for reg in state_elements.sorted_regs() {
         let signal = reg.data.next.borrow().unwrap();
-        let expr = c.compile_signal(signal, &mut prop_context);
+        let mut expr = c.compile_signal(signal, &mut prop_context);
+        // Registers on a divided clock are clocked by the implicit clock, but only take their next values in the cycles before the divided clock's positive edges
+        if let Some(divider) = reg.data.divided_clock {
+            expr = expr_arena.alloc(Expr::Ternary {
+                cond: c.compile_signal(divider.enable, &mut prop_context),
+                when_true: expr,
+                when_false: packed_bools.member(&reg.value_name, &expr_arena),
+            });
+        }
         prop_context.push(Assignment {
-            target: expr_arena.alloc(Expr::Ref {
-                name: reg.next_name.clone(),
-                scope: Scope::Member,
-            }),
+            target: packed_bools.member(&reg.next_name, &expr_arena),
             expr,
         });
 
@@ -256,18 +597,61 @@ pub fn generate<'a, W: Write>(
         );
     }
 
+    // `trace_signals` is keyed by pointers, so trace signal fields are emitted in the order they were added
+    let mut sorted_trace_signals = trace_signals.values().flatten().collect::<Vec<_>>();
+    sorted_trace_signals.sort_by_key(|trace_signal| trace_signal.index);
+
+    if options.pack_bools && options.trace_on_change_only {
+        for trace_signal in sorted_trace_signals.iter() {
+            if trace_signal.bit_width == 1 {
+                packed_bools.add(&trace_signal.prev_value_name);
+            }
+        }
+    }
+
     let mut w = code_writer::CodeWriter::new(w);
+    w.set_max_line_width(options.max_line_width);
 
     let module_name = options
         .override_module_name
-        .unwrap_or_else(|| m.name.clone());
+        .unwrap_or_else(|| identifier(&m.name));
+    let mut exported_names = vec![module_name.clone()];
+    if options.io_structs {
+        exported_names.push(format!("{}Inputs", module_name));
+        exported_names.push(format!("{}Outputs", module_name));
+    }
+
+    let item_visibility = match options.wrapper_module {
+        Some(ref wrapper_module) => {
+            if !is_identifier(wrapper_module) || identifier(wrapper_module) != *wrapper_module {
+                panic!("Cannot generate code for module \"{}\" with wrapper module name \"{}\". Wrapper module names must be non-empty, consist only of ASCII alphanumeric characters and underscores, must not start with a digit, and must not be Rust keywords.", m.name, wrapper_module);
+            }
+            if exported_names.contains(wrapper_module) {
+                panic!("Cannot generate code for module \"{}\" with wrapper module name \"{}\", because the generated code contains a struct with the same name.", m.name, wrapper_module);
+            }
+            w.append_line(&format!("mod {} {{", wrapper_module))?;
+            w.indent();
+            Visibility::Public.qualifier()
+        }
+        None => options.module_visibility.qualifier(),
+    };
+
+    for reg in optimizer.folded_registers() {
+        w.append_line(&format!(
+            "// Folded constant register \"{}\" in module \"{}\"",
+            reg.name, reg.module.name
+        ))?;
+    }
 
+    if options.doc_comments {
+        w.append_line(&format!("/// Simulator for module {:?}.", m.name))?;
+    }
     w.append_indent()?;
-    w.append(&format!("pub struct {}", module_name))?;
+    w.append(&format!("{}struct {}", item_visibility, module_name))?;
     if options.tracing {
         w.append("<T: kaze::runtime::tracing::Trace>")?;
     }
-    w.append("{")?;
+    w.append(" {")?;
     w.append_newline()?;
     w.indent();
 
@@ -275,9 +659,13 @@ pub fn generate<'a, W: Write>(
     if !inputs.is_empty() {
         w.append_line("// Inputs")?;
         for (name, input) in inputs.iter() {
+            if options.doc_comments {
+                write_port_doc("Input", name, input.data.bit_width, &mut w)?;
+            }
+            write_attribute_docs(&input.data.attributes.borrow(), &mut w)?;
             w.append_line(&format!(
                 "pub {}: {}, // {} bit(s)",
-                name,
+                identifier(name),
                 ValueType::from_bit_width(input.data.bit_width).name(),
                 input.data.bit_width
             ))?;
@@ -288,20 +676,36 @@ pub fn generate<'a, W: Write>(
     if !outputs.is_empty() {
         w.append_line("// Outputs")?;
         for (name, output) in outputs.iter() {
+            if options.doc_comments {
+                write_port_doc("Output", name, output.data.bit_width, &mut w)?;
+            }
+            write_attribute_docs(&output.data.attributes.borrow(), &mut w)?;
             w.append_line(&format!(
                 "pub {}: {}, // {} bit(s)",
-                name,
+                identifier(name),
                 ValueType::from_bit_width(output.data.bit_width).name(),
                 output.data.bit_width
             ))?;
         }
     }
 
+    if options.collect_assertion_failures {
+        w.append_line("// Assertion failures")?;
+        if options.doc_comments {
+            w.append_line("/// Messages of the assertions that failed in calls to `prop`, in the order they failed.")?;
+        }
+        w.append_line("pub assertion_failures: Vec<&'static str>,")?;
+    }
+
     if !state_elements.regs.is_empty() {
         w.append_newline()?;
         w.append_line("// Regs")?;
-        for (_, reg) in state_elements.regs.iter() {
+        for reg in state_elements.sorted_regs() {
+            if packed_bools.contains(&reg.value_name) {
+                continue;
+            }
             let type_name = ValueType::from_bit_width(reg.data.bit_width).name();
+            write_attribute_docs(&reg.data.attributes.borrow(), &mut w)?;
             w.append_line(&format!(
                 "{}: {}, // {} bit(s)",
                 reg.value_name, type_name, reg.data.bit_width
@@ -313,14 +717,24 @@ pub fn generate<'a, W: Write>(
     if !state_elements.mems.is_empty() {
         w.append_newline()?;
         w.append_line("// Mems")?;
-        for (_, mem) in state_elements.mems.iter() {
+        for mem in state_elements.sorted_mems() {
             let address_type_name = ValueType::from_bit_width(mem.mem.address_bit_width).name();
             let element_type_name = ValueType::from_bit_width(mem.mem.element_bit_width).name();
-            w.append_line(&format!(
-                "{}: Box<[{}]>, // {} bit elements",
-                mem.mem_name, element_type_name, mem.mem.element_bit_width
-            ))?;
-            for (_, read_signal_names) in mem.read_signal_names.iter() {
+            if options.no_std {
+                w.append_line(&format!(
+                    "{}: [{}; {}], // {} bit elements",
+                    mem.mem_name,
+                    element_type_name,
+                    1u128 << mem.mem.address_bit_width,
+                    mem.mem.element_bit_width
+                ))?;
+            } else {
+                w.append_line(&format!(
+                    "{}: Box<[{}]>, // {} bit elements",
+                    mem.mem_name, element_type_name, mem.mem.element_bit_width
+                ))?;
+            }
+            for (_, read_signal_names) in mem.sorted_read_signal_names() {
                 w.append_line(&format!(
                     "{}: {},",
                     read_signal_names.address_name, address_type_name
@@ -362,12 +776,36 @@ pub fn generate<'a, W: Write>(
         }
     }
 
+    if packed_bools.num_words() > 0 {
+        w.append_newline()?;
+        w.append_line(&format!(
+            "__packed_bools: [u64; {}],",
+            packed_bools.num_words()
+        ))?;
+    }
+
+    if options.poison_uninitialized_regs {
+        w.append_newline()?;
+        w.append_line("__poison_state: u64,")?;
+    }
+
     if options.tracing {
         w.append_newline()?;
         w.append_line("__trace: T,")?;
-        for module_trace_signals in trace_signals.values() {
-            for trace_signal in module_trace_signals.iter() {
-                w.append_line(&format!("{}: T::SignalId,", trace_signal.member_name))?;
+        for trace_signal in sorted_trace_signals.iter() {
+            w.append_line(&format!("{}: T::SignalId,", trace_signal.member_name))?;
+        }
+        if options.trace_on_change_only {
+            w.append_line("__trace_force_update: bool,")?;
+            for trace_signal in sorted_trace_signals.iter() {
+                if packed_bools.contains(&trace_signal.prev_value_name) {
+                    continue;
+                }
+                w.append_line(&format!(
+                    "{}: {},",
+                    trace_signal.prev_value_name,
+                    ValueType::from_bit_width(trace_signal.bit_width).name()
+                ))?;
             }
         }
     }
@@ -391,29 +829,34 @@ pub fn generate<'a, W: Write>(
     w.append_newline()?;
     w.indent();
 
-    w.append_indent()?;
-    w.append("pub fn new(")?;
     if options.tracing {
-        w.append(&format!(
-            "mut trace: T) -> std::io::Result<{}<T>> {{",
+        w.append_line(&format!(
+            "pub fn new(trace: T) -> std::io::Result<{}<T>> {{",
+            module_name
+        ))?;
+        w.indent();
+        w.append_line(&format!("Self::with_trace(\"{}\", trace)", m.instance_name))?;
+        w.unindent();
+        w.append_line("}")?;
+        w.append_newline()?;
+
+        w.append_line(&format!(
+            "pub fn with_trace(name: &'static str, mut trace: T) -> std::io::Result<{}<T>> {{",
             module_name
         ))?;
     } else {
-        w.append(&format!(") -> {} {{", module_name))?;
+        w.append_line(&format!("pub fn new() -> {} {{", module_name))?;
     }
-    w.append_newline()?;
     w.indent();
 
     if options.tracing {
         fn visit_module<'a, W: Write>(
             module: &'a graph::Module<'a>,
+            module_name: &str,
             trace_signals: &HashMap<&'a graph::Module<'a>, Vec<TraceSignal>>,
             w: &mut code_writer::CodeWriter<W>,
         ) -> Result<()> {
-            w.append_line(&format!(
-                "trace.push_module(\"{}\")?;",
-                module.instance_name
-            ))?;
+            w.append_line(&format!("trace.push_module({})?;", module_name))?;
 
             if let Some(module_trace_signals) = trace_signals.get(&module) {
                 for trace_signal in module_trace_signals.iter() {
@@ -427,19 +870,27 @@ pub fn generate<'a, W: Write>(
             }
 
             for child in module.modules.borrow().iter() {
-                visit_module(child, trace_signals, w)?;
+                visit_module(
+                    child,
+                    &format!("\"{}\"", child.instance_name),
+                    trace_signals,
+                    w,
+                )?;
             }
 
             w.append_line("trace.pop_module()?;")?;
 
             Ok(())
         }
-        visit_module(m, &trace_signals, &mut w)?;
+        // The top-level module's name in the trace is chosen by the caller
+        visit_module(m, "name", &trace_signals, &mut w)?;
         w.append_newline()?;
     }
 
     w.append_indent()?;
-    if options.tracing {
+    if options.poison_uninitialized_regs {
+        w.append("let mut sim = ")?;
+    } else if options.tracing {
         w.append("Ok(")?;
     }
     w.append(&format!("{} {{", module_name))?;
@@ -451,7 +902,7 @@ pub fn generate<'a, W: Write>(
         for (name, input) in inputs.iter() {
             w.append_line(&format!(
                 "{}: {}, // {} bit(s)",
-                name,
+                identifier(name),
                 ValueType::from_bit_width(input.data.bit_width).zero_str(),
                 input.data.bit_width
             ))?;
@@ -463,17 +914,25 @@ pub fn generate<'a, W: Write>(
         for (name, output) in outputs.iter() {
             w.append_line(&format!(
                 "{}: {}, // {} bit(s)",
-                name,
+                identifier(name),
                 ValueType::from_bit_width(output.data.bit_width).zero_str(),
                 output.data.bit_width
             ))?;
         }
     }
 
+    if options.collect_assertion_failures {
+        w.append_line("// Assertion failures")?;
+        w.append_line("assertion_failures: Vec::new(),")?;
+    }
+
     if !state_elements.regs.is_empty() {
         w.append_newline()?;
         w.append_line("// Regs")?;
-        for (_, reg) in state_elements.regs.iter() {
+        for reg in state_elements.sorted_regs() {
+            if packed_bools.contains(&reg.value_name) {
+                continue;
+            }
             w.append_line(&format!(
                 "{}: {}, // {} bit(s)",
                 reg.value_name,
@@ -491,22 +950,35 @@ pub fn generate<'a, W: Write>(
     if !state_elements.mems.is_empty() {
         w.append_newline()?;
         w.append_line("// Mems")?;
-        for (_, mem) in state_elements.mems.iter() {
+        for mem in state_elements.sorted_mems() {
             let address_type = ValueType::from_bit_width(mem.mem.address_bit_width);
             let element_type = ValueType::from_bit_width(mem.mem.element_bit_width);
             if let Some(ref initial_contents) = *mem.mem.initial_contents.borrow() {
-                w.append_line(&format!("{}: vec![", mem.mem_name))?;
+                w.append_line(&format!(
+                    "{}: {}[",
+                    mem.mem_name,
+                    if options.no_std { "" } else { "vec!" }
+                ))?;
                 w.indent();
                 for element in initial_contents.iter() {
-                    w.append_line(&match *element {
-                        graph::Constant::Bool(value) => format!("{},", value),
-                        graph::Constant::U32(value) => format!("0x{:x},", value),
-                        graph::Constant::U64(value) => format!("0x{:x},", value),
-                        graph::Constant::U128(value) => format!("0x{:x},", value),
-                    })?;
+                    w.append_line(&format!(
+                        "{},",
+                        mem_element_str(element, mem.mem.element_bit_width)
+                    ))?;
                 }
                 w.unindent();
-                w.append_line("].into_boxed_slice(),")?;
+                w.append_line(if options.no_std {
+                    "],"
+                } else {
+                    "].into_boxed_slice(),"
+                })?;
+            } else if options.no_std {
+                w.append_line(&format!(
+                    "{}: [{}; {}],",
+                    mem.mem_name,
+                    element_type.zero_str(),
+                    1u128 << mem.mem.address_bit_width
+                ))?;
             } else {
                 w.append_line(&format!(
                     "{}: vec![{}; {}].into_boxed_slice(),",
@@ -515,7 +987,7 @@ pub fn generate<'a, W: Write>(
                     1 << mem.mem.address_bit_width
                 ))?;
             }
-            for (_, read_signal_names) in mem.read_signal_names.iter() {
+            for (_, read_signal_names) in mem.sorted_read_signal_names() {
                 w.append_line(&format!(
                     "{}: {},",
                     read_signal_names.address_name,
@@ -564,12 +1036,36 @@ pub fn generate<'a, W: Write>(
         }
     }
 
+    if packed_bools.num_words() > 0 {
+        w.append_newline()?;
+        w.append_line(&format!(
+            "__packed_bools: [0; {}],",
+            packed_bools.num_words()
+        ))?;
+    }
+
+    if options.poison_uninitialized_regs {
+        w.append_newline()?;
+        w.append_line("__poison_state: 0,")?;
+    }
+
     if options.tracing {
         w.append_newline()?;
         w.append_line("__trace: trace,")?;
-        for module_trace_signals in trace_signals.values() {
-            for trace_signal in module_trace_signals.iter() {
-                w.append_line(&format!("{},", trace_signal.member_name))?;
+        for trace_signal in sorted_trace_signals.iter() {
+            w.append_line(&format!("{},", trace_signal.member_name))?;
+        }
+        if options.trace_on_change_only {
+            w.append_line("__trace_force_update: true,")?;
+            for trace_signal in sorted_trace_signals.iter() {
+                if packed_bools.contains(&trace_signal.prev_value_name) {
+                    continue;
+                }
+                w.append_line(&format!(
+                    "{}: {},",
+                    trace_signal.prev_value_name,
+                    ValueType::from_bit_width(trace_signal.bit_width).zero_str()
+                ))?;
             }
         }
     }
@@ -577,40 +1073,68 @@ pub fn generate<'a, W: Write>(
     w.unindent();
     w.append_indent()?;
     w.append("}")?;
-    if options.tracing {
-        w.append(")")?;
+    if options.poison_uninitialized_regs {
+        w.append(";")?;
+        w.append_newline()?;
+        w.append_line("sim.__poison_regs();")?;
+        w.append_line(if options.tracing { "Ok(sim)" } else { "sim" })?;
+    } else {
+        if options.tracing {
+            w.append(")")?;
+        }
+        w.append_newline()?;
     }
-    w.append_newline()?;
     w.unindent();
     w.append_line("}")?;
 
     let mut reset_context = AssignmentContext::new(&expr_arena);
+    let mut reset_domain_contexts = BTreeMap::new();
     let mut posedge_clk_context = AssignmentContext::new(&expr_arena);
+    let mut clock_contexts = BTreeMap::new();
+    let mut negedge_clk_context = AssignmentContext::new(&expr_arena);
+    let mut negedge_clock_contexts = BTreeMap::new();
 
-    for (_, reg) in state_elements.regs.iter() {
-        let target = expr_arena.alloc(Expr::Ref {
-            name: reg.value_name.clone(),
-            scope: Scope::Member,
-        });
+    for reg in state_elements.sorted_regs() {
+        let target = packed_bools.member(&reg.value_name, &expr_arena);
 
         if let Some(ref initial_value) = *reg.data.initial_value.borrow() {
-            reset_context.push(Assignment {
+            let context = match *reg.data.reset_domain.borrow() {
+                Some(domain) => reset_domain_contexts
+                    .entry(domain.name.clone())
+                    .or_insert_with(|| AssignmentContext::new(&expr_arena)),
+                None => &mut reset_context,
+            };
+            context.push(Assignment {
                 target,
                 expr: Expr::from_constant(initial_value, reg.data.bit_width, &expr_arena),
             });
         }
 
-        posedge_clk_context.push(Assignment {
+        let (contexts, implicit_context) = match reg.data.edge.get() {
+            graph::Edge::Pos => (&mut clock_contexts, &mut posedge_clk_context),
+            graph::Edge::Neg => (&mut negedge_clock_contexts, &mut negedge_clk_context),
+        };
+        let context = match reg.data.clock {
+            Some(ref clock) => contexts
+                .entry(clock.clone())
+                .or_insert_with(|| AssignmentContext::new(&expr_arena)),
+            None => implicit_context,
+        };
+        context.push(Assignment {
             target,
-            expr: expr_arena.alloc(Expr::Ref {
-                name: reg.next_name.clone(),
-                scope: Scope::Member,
-            }),
+            expr: packed_bools.member(&reg.next_name, &expr_arena),
         });
     }
 
-    for (_, mem) in state_elements.mems.iter() {
-        for (_, read_signal_names) in mem.read_signal_names.iter() {
+    for mem in state_elements.sorted_mems() {
+        let clocks = mem.mem.clocks.borrow();
+        for (_, read_signal_names) in mem.sorted_read_signal_names() {
+            let context = match *clocks {
+                Some(ref clocks) => clock_contexts
+                    .entry(clocks.read.clone())
+                    .or_insert_with(|| AssignmentContext::new(&expr_arena)),
+                None => &mut posedge_clk_context,
+            };
             let address = expr_arena.alloc(Expr::Ref {
                 name: read_signal_names.address_name.clone(),
                 scope: Scope::Member,
@@ -631,7 +1155,7 @@ pub fn generate<'a, W: Write>(
                 index: address,
             });
             // TODO: Conditional assign statement instead of always writing ternary
-            posedge_clk_context.push(Assignment {
+            context.push(Assignment {
                 target: value,
                 expr: expr_arena.alloc(Expr::Ternary {
                     cond: enable,
@@ -641,6 +1165,12 @@ pub fn generate<'a, W: Write>(
             });
         }
         if mem.mem.write_port.borrow().is_some() {
+            let context = match *clocks {
+                Some(ref clocks) => clock_contexts
+                    .entry(clocks.write.clone())
+                    .or_insert_with(|| AssignmentContext::new(&expr_arena)),
+                None => &mut posedge_clk_context,
+            };
             let address = expr_arena.alloc(Expr::Ref {
                 name: mem.write_address_name.clone(),
                 scope: Scope::Member,
@@ -661,7 +1191,7 @@ pub fn generate<'a, W: Write>(
                 index: address,
             });
             // TODO: Conditional assign statement instead of always writing ternary
-            posedge_clk_context.push(Assignment {
+            context.push(Assignment {
                 target: element,
                 expr: expr_arena.alloc(Expr::Ternary {
                     cond: enable,
@@ -672,20 +1202,78 @@ pub fn generate<'a, W: Write>(
         }
     }
 
-    if !reset_context.is_empty() {
+    let reset_mems = if options.reset_mems {
+        state_elements.sorted_mems()
+    } else {
+        Vec::new()
+    };
+
+    if options.poison_uninitialized_regs {
+        write_poison_methods(&state_elements, &packed_bools, &mut w)?;
+    }
+
+    if !reset_context.is_empty() || !reset_mems.is_empty() || options.poison_uninitialized_regs {
         w.append_newline()?;
-        w.append_line("pub fn reset(&mut self) {")?;
+        w.append_line(&format!("pub fn {}(&mut self) {{", reset_name))?;
         w.indent();
 
         reset_context.write(&mut w)?;
 
+        if options.tracing && options.trace_on_change_only {
+            w.append_line("self.__trace_force_update = true;")?;
+        }
+
+        if options.poison_uninitialized_regs {
+            w.append_line("self.__poison_regs();")?;
+        }
+
+        for mem in reset_mems {
+            if let Some(ref initial_contents) = *mem.mem.initial_contents.borrow() {
+                w.append_line(&format!("self.{}.copy_from_slice(&[", mem.mem_name))?;
+                w.indent();
+                for element in initial_contents.iter() {
+                    w.append_line(&format!(
+                        "{},",
+                        mem_element_str(element, mem.mem.element_bit_width)
+                    ))?;
+                }
+                w.unindent();
+                w.append_line("]);")?;
+            } else {
+                let element_type = ValueType::from_bit_width(mem.mem.element_bit_width);
+                w.append_line(&format!(
+                    "for element in self.{}.iter_mut() {{",
+                    mem.mem_name
+                ))?;
+                w.indent();
+                w.append_line(&format!("*element = {};", element_type.zero_str()))?;
+                w.unindent();
+                w.append_line("}")?;
+            }
+        }
+
+        w.unindent();
+        w.append_line("}")?;
+    }
+
+    for (name, reset_domain_context) in reset_domain_contexts.iter() {
+        w.append_newline()?;
+        w.append_line(&format!("pub fn {}_{}(&mut self) {{", reset_name, name))?;
+        w.indent();
+
+        reset_domain_context.write(&mut w)?;
+
+        if options.tracing && options.trace_on_change_only {
+            w.append_line("self.__trace_force_update = true;")?;
+        }
+
         w.unindent();
         w.append_line("}")?;
     }
 
     if !posedge_clk_context.is_empty() {
         w.append_newline()?;
-        w.append_line("pub fn posedge_clk(&mut self) {")?;
+        w.append_line(&format!("pub fn posedge_{}(&mut self) {{", clock_name))?;
         w.indent();
 
         posedge_clk_context.write(&mut w)?;
@@ -694,32 +1282,99 @@ pub fn generate<'a, W: Write>(
         w.append_line("}")?;
     }
 
+    for (name, clock_context) in clock_contexts.iter() {
+        w.append_newline()?;
+        w.append_line(&format!("pub fn posedge_{}(&mut self) {{", name))?;
+        w.indent();
+
+        clock_context.write(&mut w)?;
+
+        w.unindent();
+        w.append_line("}")?;
+    }
+
+    if !negedge_clk_context.is_empty() {
+        w.append_newline()?;
+        w.append_line(&format!("pub fn negedge_{}(&mut self) {{", clock_name))?;
+        w.indent();
+
+        negedge_clk_context.write(&mut w)?;
+
+        w.unindent();
+        w.append_line("}")?;
+    }
+
+    for (name, clock_context) in negedge_clock_contexts.iter() {
+        w.append_newline()?;
+        w.append_line(&format!("pub fn negedge_{}(&mut self) {{", name))?;
+        w.indent();
+
+        clock_context.write(&mut w)?;
+
+        w.unindent();
+        w.append_line("}")?;
+    }
+
     w.append_newline()?;
     w.append_line("pub fn prop(&mut self) {")?;
     w.indent();
 
+    if options.strict_inputs {
+        for (name, input) in inputs.iter() {
+            let bit_width = input.data.bit_width;
+            let type_ = ValueType::from_bit_width(bit_width);
+            // Note that this also covers bool values, which can't have extra bits
+            if bit_width == type_.bit_width() {
+                continue;
+            }
+            w.append_line(&format!(
+                "debug_assert!(self.{} >> {} == 0, \"Input \\\"{}\\\" has bits set above its declared bit width of {} bit(s).\");",
+                identifier(name), bit_width, name, bit_width
+            ))?;
+        }
+    }
+
     prop_context.write(&mut w)?;
 
+    for cover_point in cover_points.iter() {
+        w.append_line(&format!("if self.{} {{", cover_point.field_name))?;
+        w.indent();
+        w.append_line(&format!("self.{} += 1;", cover_point.count_name))?;
+        w.unindent();
+        w.append_line("}")?;
+    }
+
+    for assertion in assertions.iter() {
+        if let Some(ref message) = assertion.message {
+            let failure = format!("Assertion \"{}\" failed: {}", assertion.path, message);
+            w.append_line(&format!("if !self.{} {{", assertion.field_name))?;
+            w.indent();
+            w.append_line(&if options.collect_assertion_failures {
+                format!("self.assertion_failures.push({:?});", failure)
+            } else {
+                format!("panic!(\"{{}}\", {:?});", failure)
+            })?;
+            w.unindent();
+            w.append_line("}")?;
+        }
+    }
+
     w.unindent();
     w.append_line("}")?;
 
-    if options.tracing {
+    if !assertions.is_empty() {
         w.append_newline()?;
-        w.append_line("pub fn update_trace(&mut self, time_stamp: u64) -> std::io::Result<()> {")?;
+        w.append_line(
+            "pub fn check_assertions(&self) -> core::result::Result<(), &'static str> {",
+        )?;
         w.indent();
 
-        w.append_line("self.__trace.update_time_stamp(time_stamp)?;")?;
-        w.append_newline()?;
-
-        for module_trace_signals in trace_signals.values() {
-            for trace_signal in module_trace_signals.iter() {
-                w.append_line(&format!("self.__trace.update_signal(&self.{}, kaze::runtime::tracing::TraceValue::{}(self.{}))?;", trace_signal.member_name, match trace_signal.type_ {
-                    TraceValueType::Bool => "Bool",
-                    TraceValueType::U32 => "U32",
-                    TraceValueType::U64 => "U64",
-                    TraceValueType::U128 => "U128",
-                }, trace_signal.value_name))?;
-            }
+        for assertion in assertions.iter() {
+            w.append_line(&format!("if !self.{} {{", assertion.field_name))?;
+            w.indent();
+            w.append_line(&format!("return Err(\"{}\");", assertion.path))?;
+            w.unindent();
+            w.append_line("}")?;
         }
         w.append_newline()?;
 
@@ -729,19 +1384,716 @@ pub fn generate<'a, W: Write>(
         w.append_line("}")?;
     }
 
-    w.unindent();
-    w.append_line("}")?;
-    w.append_newline()?;
+    if !cover_points.is_empty() {
+        if !options.no_std {
+            w.append_newline()?;
+            w.append_line(
+                "pub fn coverage(&self) -> std::collections::BTreeMap<&'static str, u64> {",
+            )?;
+            w.indent();
 
-    Ok(())
-}
+            w.append_line("let mut coverage = std::collections::BTreeMap::new();")?;
+            for cover_point in cover_points.iter() {
+                w.append_line(&format!(
+                    "coverage.insert(\"{}\", self.{});",
+                    cover_point.path, cover_point.count_name
+                ))?;
+            }
+            w.append_line("coverage")?;
+
+            w.unindent();
+            w.append_line("}")?;
+
+            w.append_newline()?;
+            w.append_line("pub fn coverage_report(&self) -> String {")?;
+            w.indent();
+
+            w.append_line("let coverage = self.coverage();")?;
+            w.append_line("let mut report = String::new();")?;
+            w.append_line("for (path, &count) in coverage.iter() {")?;
+            w.indent();
+            w.append_line("let not_hit = if count == 0 { \" (not hit)\" } else { \"\" };")?;
+            w.append_line("report.push_str(&format!(\"{}: {}{}\\n\", path, count, not_hit));")?;
+            w.unindent();
+            w.append_line("}")?;
+            w.append_line("let num_hit = coverage.values().filter(|&&count| count > 0).count();")?;
+            w.append_line("report.push_str(&format!(\"{}/{} coverage point(s) hit\\n\", num_hit, coverage.len()));")?;
+            w.append_line("report")?;
+
+            w.unindent();
+            w.append_line("}")?;
+        }
 
-#[cfg(test)]
-mod tests {
+        w.append_newline()?;
+        w.append_line("pub fn reset_coverage(&mut self) {")?;
+        w.indent();
+
+        for cover_point in cover_points.iter() {
+            w.append_line(&format!("self.{} = 0;", cover_point.count_name))?;
+        }
+
+        w.unindent();
+        w.append_line("}")?;
+    }
+
+    for (name, input) in inputs.iter() {
+        let bit_width = input.data.bit_width;
+        if bit_width <= 64 {
+            continue;
+        }
+        let num_bytes = bit_width.div_ceil(8);
+        let name = identifier(name);
+        w.append_newline()?;
+        w.append_line(&format!(
+            "pub fn set_{}_from_le_bytes(&mut self, bytes: &[u8; {}]) {{",
+            unraw(&name),
+            num_bytes
+        ))?;
+        w.indent();
+        if num_bytes == 16 {
+            w.append_line("let value = u128::from_le_bytes(*bytes);")?;
+        } else {
+            w.append_line("let mut value = [0u8; 16];")?;
+            w.append_line(&format!("value[..{}].copy_from_slice(bytes);", num_bytes))?;
+            w.append_line("let value = u128::from_le_bytes(value);")?;
+        }
+        if bit_width == 128 {
+            w.append_line(&format!("self.{} = value;", name))?;
+        } else {
+            // Bits in the last byte above the input's bit width are ignored
+            w.append_line(&format!(
+                "self.{} = value & ((1u128 << {}) - 1);",
+                name, bit_width
+            ))?;
+        }
+        w.unindent();
+        w.append_line("}")?;
+    }
+
+    for (name, output) in outputs.iter() {
+        let bit_width = output.data.bit_width;
+        if bit_width <= 64 {
+            continue;
+        }
+        let num_bytes = bit_width.div_ceil(8);
+        let name = identifier(name);
+        w.append_newline()?;
+        w.append_line(&format!(
+            "pub fn {}_to_le_bytes(&self) -> [u8; {}] {{",
+            unraw(&name),
+            num_bytes
+        ))?;
+        w.indent();
+        if num_bytes == 16 {
+            w.append_line(&format!("self.{}.to_le_bytes()", name))?;
+        } else {
+            w.append_line(&format!("let mut bytes = [0u8; {}];", num_bytes))?;
+            w.append_line(&format!(
+                "bytes.copy_from_slice(&self.{}.to_le_bytes()[..{}]);",
+                name, num_bytes
+            ))?;
+            w.append_line("bytes")?;
+        }
+        w.unindent();
+        w.append_line("}")?;
+    }
+
+    if options.tracing {
+        w.append_newline()?;
+        w.append_line("pub fn update_trace(&mut self, time_stamp: u64) -> std::io::Result<()> {")?;
+        w.indent();
+
+        w.append_line("self.__trace.update_time_stamp(time_stamp)?;")?;
+        w.append_newline()?;
+
+        let trace_on_change_only = options.trace_on_change_only;
+        if trace_on_change_only {
+            w.append_line("let force_update = self.__trace_force_update;")?;
+            w.append_line("self.__trace_force_update = false;")?;
+            w.append_newline()?;
+        }
+
+        for trace_signal in sorted_trace_signals.iter() {
+            if trace_on_change_only {
+                w.append_line(&format!(
+                    "if force_update || {} != {} {{",
+                    packed_bools.read(&trace_signal.value_name),
+                    packed_bools.read(&trace_signal.prev_value_name)
+                ))?;
+                w.indent();
+            }
+            w.append_line(&format!("self.__trace.update_signal(&self.{}, kaze::runtime::tracing::TraceValue::{}({}))?;", trace_signal.member_name, match trace_signal.type_ {
+                TraceValueType::Bool => "Bool",
+                TraceValueType::U32 => "U32",
+                TraceValueType::U64 => "U64",
+                TraceValueType::U128 => "U128",
+            }, packed_bools.read(&trace_signal.value_name)))?;
+            if trace_on_change_only {
+                w.append_line(&packed_bools.write(
+                    &trace_signal.prev_value_name,
+                    &packed_bools.read(&trace_signal.value_name),
+                ))?;
+                w.unindent();
+                w.append_line("}")?;
+            }
+        }
+        w.append_newline()?;
+
+        w.append_line("Ok(())")?;
+
+        w.unindent();
+        w.append_line("}")?;
+    }
+
+    if !state_elements.mems.is_empty() {
+        write_mem_contents_methods(&state_elements, &mut w)?;
+    }
+
+    if options.io_structs {
+        w.append_newline()?;
+        w.append_line(&format!(
+            "pub fn set_inputs(&mut self, inputs: &{}Inputs) {{",
+            module_name
+        ))?;
+        w.indent();
+        for name in inputs.keys() {
+            w.append_line(&format!(
+                "self.{} = inputs.{};",
+                identifier(name),
+                identifier(name)
+            ))?;
+        }
+        w.unindent();
+        w.append_line("}")?;
+
+        w.append_newline()?;
+        w.append_line(&format!(
+            "pub fn outputs(&self) -> {}Outputs {{",
+            module_name
+        ))?;
+        w.indent();
+        w.append_line(&format!("{}Outputs {{", module_name))?;
+        w.indent();
+        for name in outputs.keys() {
+            w.append_line(&format!("{}: self.{},", identifier(name), identifier(name)))?;
+        }
+        w.unindent();
+        w.append_line("}")?;
+        w.unindent();
+        w.append_line("}")?;
+    }
+
+    if !options.no_std {
+        w.append_newline()?;
+        w.append_line("pub fn dump_state(&self) -> String {")?;
+        w.indent();
+
+        w.append_line("let mut state = String::new();")?;
+        let mut append_state_line = |label: &str, value_name: &str, bit_width: u32| {
+            let format = if bit_width == 1 { "{}" } else { "{:#x}" };
+            w.append_line(&format!(
+                "state.push_str(&format!(\"{} = {}\\n\", {}));",
+                label,
+                format,
+                packed_bools.read(value_name)
+            ))
+        };
+        for (name, input) in inputs.iter() {
+            append_state_line(name, &identifier(name), input.data.bit_width)?;
+        }
+        for (name, output) in outputs.iter() {
+            append_state_line(name, &identifier(name), output.data.bit_width)?;
+        }
+        let mut regs = state_elements
+            .regs
+            .values()
+            .map(|reg| {
+                (
+                    format!("{}.{}", reg.data.module.instance_path(), reg.data.name),
+                    reg,
+                )
+            })
+            .collect::<Vec<_>>();
+        regs.sort_by(|(a_label, a), (b_label, b)| {
+            a_label
+                .cmp(b_label)
+                .then_with(|| a.value_name.cmp(&b.value_name))
+        });
+        for (label, reg) in regs {
+            append_state_line(&label, &reg.value_name, reg.data.bit_width)?;
+        }
+        w.append_line("state")?;
+
+        w.unindent();
+        w.append_line("}")?;
+    }
+
+    w.unindent();
+    w.append_line("}")?;
+    w.append_newline()?;
+
+    if !options.tracing {
+        w.append_line("#[automatically_derived]")?;
+        w.append_line(&format!("impl Default for {} {{", module_name))?;
+        w.indent();
+        w.append_line("fn default() -> Self {")?;
+        w.indent();
+        w.append_line("Self::new()")?;
+        w.unindent();
+        w.append_line("}")?;
+        w.unindent();
+        w.append_line("}")?;
+        w.append_newline()?;
+    }
+
+    if options.io_structs {
+        for (kind, ports) in [
+            (
+                "Inputs",
+                inputs
+                    .iter()
+                    .map(|(name, input)| (name, input.data.bit_width))
+                    .collect::<Vec<_>>(),
+            ),
+            (
+                "Outputs",
+                outputs
+                    .iter()
+                    .map(|(name, output)| (name, output.data.bit_width))
+                    .collect(),
+            ),
+        ]
+        .iter()
+        {
+            if options.doc_comments {
+                w.append_line(&format!(
+                    "/// Values of all of the {} of `{}`.",
+                    kind.to_lowercase(),
+                    module_name
+                ))?;
+            }
+            w.append_line("#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]")?;
+            w.append_line(&format!(
+                "{}struct {}{} {{",
+                item_visibility, module_name, kind
+            ))?;
+            w.indent();
+            for (name, bit_width) in ports.iter() {
+                if options.doc_comments {
+                    write_port_doc(&kind[..kind.len() - 1], name, *bit_width, &mut w)?;
+                }
+                w.append_line(&format!(
+                    "pub {}: {}, // {} bit(s)",
+                    identifier(name),
+                    ValueType::from_bit_width(*bit_width).name(),
+                    bit_width
+                ))?;
+            }
+            w.unindent();
+            w.append_line("}")?;
+            w.append_newline()?;
+        }
+    }
+
+    if let Some(wrapper_module) = options.wrapper_module {
+        w.unindent();
+        w.append_line("}")?;
+        w.append_newline()?;
+
+        let exported_names = if exported_names.len() == 1 {
+            exported_names[0].clone()
+        } else {
+            format!("{{{}}}", exported_names.join(", "))
+        };
+        w.append_line(&format!(
+            "{}use {}::{};",
+            options.module_visibility.qualifier(),
+            wrapper_module,
+            exported_names
+        ))?;
+    }
+
+    Ok(())
+}
+
+/// Writes the `load_mem_contents` and `with_mem_contents` methods, which replace the contents of a memory (identified by its module's instance path and its name, eg. `"cpu.rom"`) at runtime.
+fn write_mem_contents_methods<W: Write>(
+    state_elements: &StateElements,
+    w: &mut code_writer::CodeWriter<W>,
+) -> Result<()> {
+    w.append_newline()?;
+    w.append_line("pub fn load_mem_contents(&mut self, name: &str, contents: &[u128]) {")?;
+    w.indent();
+    w.append_line("match name {")?;
+    w.indent();
+    for mem in state_elements.sorted_mems() {
+        let label = format!("{}.{}", mem.mem.module.instance_path(), mem.mem.name);
+        let num_elements = 1u128 << mem.mem.address_bit_width;
+        let element_bit_width = mem.mem.element_bit_width;
+        let element_type = ValueType::from_bit_width(element_bit_width);
+        w.append_line(&format!("{:?} => {{", label))?;
+        w.indent();
+        w.append_line(&format!("if contents.len() as u128 > {} {{", num_elements))?;
+        w.indent();
+        w.append_line(&format!(
+            "panic!(\"Attempted to load {{}} element(s) into memory \\\"{{}}\\\", but this memory contains {} element(s).\", contents.len(), name);",
+            num_elements
+        ))?;
+        w.unindent();
+        w.append_line("}")?;
+        if element_bit_width < 128 {
+            w.append_line("for (i, &element) in contents.iter().enumerate() {")?;
+            w.indent();
+            w.append_line(&format!("if element >> {} != 0 {{", element_bit_width))?;
+            w.indent();
+            w.append_line(&format!(
+                "panic!(\"Attempted to load element {{}} with value {{:#x}} into memory \\\"{{}}\\\", but this memory has an element width of {} bit(s).\", i, element, name);",
+                element_bit_width
+            ))?;
+            w.unindent();
+            w.append_line("}")?;
+            w.unindent();
+            w.append_line("}")?;
+        }
+        let convert = match element_type {
+            ValueType::Bool => "element != 0".to_string(),
+            _ => format!("element as {}", element_type.name()),
+        };
+        w.append_line(&format!(
+            "for (i, element) in self.{}.iter_mut().enumerate() {{",
+            mem.mem_name
+        ))?;
+        w.indent();
+        w.append_line(&format!(
+            "*element = contents.get(i).map_or({}, |&element| {});",
+            element_type.zero_str(),
+            convert
+        ))?;
+        w.unindent();
+        w.append_line("}")?;
+        w.unindent();
+        w.append_line("}")?;
+    }
+    w.append_line(
+        "_ => panic!(\"Attempted to load the contents of memory \\\"{}\\\", but there's no memory with that name.\", name),",
+    )?;
+    w.unindent();
+    w.append_line("}")?;
+    w.unindent();
+    w.append_line("}")?;
+
+    w.append_newline()?;
+    w.append_line("pub fn with_mem_contents(mut self, name: &str, contents: &[u128]) -> Self {")?;
+    w.indent();
+    w.append_line("self.load_mem_contents(name, contents);")?;
+    w.append_line("self")?;
+    w.unindent();
+    w.append_line("}")?;
+
+    Ok(())
+}
+
+/// Panics if two of `m`'s inputs or outputs would have the same field name in the generated simulator, or if one of them would collide with a generated field.
+fn validate_port_identifiers<'a>(m: &'a graph::Module<'a>, options: &GenerationOptions) {
+    let mut identifiers = HashMap::new();
+    for name in m.inputs.borrow().keys().chain(m.outputs.borrow().keys()) {
+        let identifier = identifier(name);
+        // Registers, mems, and other generated fields are all named `__<something>`
+        if identifier.starts_with("__") {
+            panic!("Cannot generate code for module \"{}\" because its input or output \"{}\" would be called \"{}\" in the generated simulator, and names starting with \"__\" are reserved for generated fields.", m.name, name, identifier);
+        }
+        if options.collect_assertion_failures && identifier == "assertion_failures" {
+            panic!("Cannot generate code for module \"{}\" with collect_assertion_failures enabled because its input or output \"{}\" would have the same name as the generated \"assertion_failures\" field.", m.name, name);
+        }
+        if let Some(other) = identifiers.insert(identifier.clone(), name.clone()) {
+            panic!("Cannot generate code for module \"{}\" because its inputs or outputs \"{}\" and \"{}\" would both be called \"{}\" in the generated simulator.", m.name, other, name, identifier);
+        }
+    }
+}
+
+/// Names of the public methods that generated simulators can have, other than the reset, clock edge, and byte conversion methods, whose names depend on the module being generated.
+const GENERATED_METHOD_NAMES: &[&str] = &[
+    "check_assertions",
+    "coverage",
+    "coverage_report",
+    "dump_state",
+    "load_mem_contents",
+    "new",
+    "outputs",
+    "prop",
+    "reset_coverage",
+    "set_inputs",
+    "set_poison_seed",
+    "update_trace",
+    "with_mem_contents",
+    "with_trace",
+];
+
+/// Returns `true` if a generated simulator can have a method called `name` other than a reset method.
+fn is_generated_method_name(name: &str) -> bool {
+    GENERATED_METHOD_NAMES.contains(&name)
+        || name.starts_with("__")
+        || name.starts_with("posedge_")
+        || name.starts_with("negedge_")
+        || (name.starts_with("set_") && name.ends_with("_from_le_bytes"))
+        || name.ends_with("_to_le_bytes")
+}
+
+/// Panics if a reset method of the generated simulator (one for the implicit reset, and one for each [reset domain](crate::Module::reset_domain)) would be named after a Rust keyword, or would have the same name as another generated method.
+fn validate_reset_method_names<'a>(
+    m: &'a graph::Module<'a>,
+    state_elements: &StateElements<'a>,
+    reset_name: &str,
+) {
+    let mut method_names = vec![reset_name.to_string()];
+    for reg in state_elements.sorted_regs() {
+        if let Some(domain) = *reg.data.reset_domain.borrow() {
+            method_names.push(format!("{}_{}", reset_name, domain.name));
+        }
+    }
+    for method_name in method_names {
+        if is_keyword(&method_name) {
+            panic!("Cannot generate code for module \"{}\" with reset name \"{}\" (see GenerationOptions::override_reset_name), because the generated reset method \"{}\" would be named after a Rust keyword.", m.name, reset_name, method_name);
+        }
+        if is_generated_method_name(&method_name) {
+            panic!("Cannot generate code for module \"{}\" with reset name \"{}\" (see GenerationOptions::override_reset_name), because the generated reset method \"{}\" would have the same name as another generated method.", m.name, reset_name, method_name);
+        }
+    }
+}
+
+fn write_poison_methods<W: Write>(
+    state_elements: &StateElements,
+    packed_bools: &PackedBools,
+    w: &mut code_writer::CodeWriter<W>,
+) -> Result<()> {
+    let poisoned_regs = state_elements
+        .sorted_regs()
+        .into_iter()
+        .filter(|reg| reg.data.initial_value.borrow().is_none())
+        .collect::<Vec<_>>();
+
+    w.append_newline()?;
+    w.append_line("pub fn set_poison_seed(&mut self, seed: u64) {")?;
+    w.indent();
+    w.append_line("self.__poison_state = seed;")?;
+    w.append_line("self.__poison_regs();")?;
+    w.unindent();
+    w.append_line("}")?;
+
+    if !poisoned_regs.is_empty() {
+        // SplitMix64, which is small, fast, and good enough to exercise unreset registers
+        w.append_newline()?;
+        w.append_line("fn __poison_next(&mut self) -> u64 {")?;
+        w.indent();
+        w.append_line(
+            "self.__poison_state = self.__poison_state.wrapping_add(0x9e3779b97f4a7c15);",
+        )?;
+        w.append_line("let mut z = self.__poison_state;")?;
+        w.append_line("z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);")?;
+        w.append_line("z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);")?;
+        w.append_line("z ^ (z >> 31)")?;
+        w.unindent();
+        w.append_line("}")?;
+    }
+
+    w.append_newline()?;
+    w.append_line("fn __poison_regs(&mut self) {")?;
+    w.indent();
+    for reg in poisoned_regs {
+        let bit_width = reg.data.bit_width;
+        let value_type = ValueType::from_bit_width(bit_width);
+        let value = match value_type {
+            ValueType::Bool => "self.__poison_next() & 1 != 0".to_string(),
+            ValueType::U128 => {
+                "((self.__poison_next() as u128) << 64) | self.__poison_next() as u128".to_string()
+            }
+            _ => "self.__poison_next()".to_string(),
+        };
+        let value = if value_type == ValueType::Bool || bit_width == value_type.bit_width() {
+            value
+        } else {
+            format!("({}) & 0x{:x}", value, (1u128 << bit_width) - 1)
+        };
+        let value = if value_type == ValueType::U32 {
+            format!("({}) as u32", value)
+        } else {
+            value
+        };
+        w.append_line(&packed_bools.write(&reg.value_name, &value))?;
+    }
+    w.unindent();
+    w.append_line("}")?;
+
+    Ok(())
+}
+
+fn write_port_doc<W: Write>(
+    kind: &str,
+    name: &str,
+    bit_width: u32,
+    w: &mut code_writer::CodeWriter<W>,
+) -> Result<()> {
+    w.append_line(&format!("/// {} {:?} ({} bit(s)).", kind, name, bit_width))
+}
+
+fn write_attribute_docs<W: Write>(
+    attributes: &[graph::Attribute],
+    w: &mut code_writer::CodeWriter<W>,
+) -> Result<()> {
+    for attribute in attributes {
+        w.append_line(&format!(
+            "/// {}_{} = {:?}",
+            attribute.namespace, attribute.key, attribute.value
+        ))?;
+    }
+
+    Ok(())
+}
+
+/// Returns the Rust literal for a memory element with the value `element`, which must match the memory's element type regardless of the type `element` was specified with.
+fn mem_element_str(element: &graph::Constant, element_bit_width: u32) -> String {
+    let value = element.numeric_value();
+    match ValueType::from_bit_width(element_bit_width) {
+        ValueType::Bool => format!("{}", value != 0),
+        _ => format!("0x{:x}", value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
     use super::*;
 
+    use crate::test_util::*;
     use crate::*;
 
+    #[test]
+    fn generation_is_repeatable() {
+        for &tracing in &[false, true] {
+            assert_repeatable(|| {
+                let c = Context::new();
+                let m = repeatability_module(&c);
+
+                let mut v = Vec::new();
+                generate(
+                    m,
+                    GenerationOptions {
+                        tracing,
+                        ..GenerationOptions::default()
+                    },
+                    &mut v,
+                )
+                .unwrap();
+                String::from_utf8(v).unwrap()
+            });
+        }
+    }
+
+    #[test]
+    fn reset_mems() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        let mem = m.mem("mem", 1, 8);
+        mem.initial_contents(&[0x12u32, 0x34u32]);
+        mem.write_port(m.input("wa", 1), m.input("wv", 8), m.input("we", 1));
+        m.output("o", mem.read_port(m.input("ra", 1), m.high()));
+
+        let generate_with_reset_mems = |reset_mems| {
+            let mut v = Vec::new();
+            generate(
+                m,
+                GenerationOptions {
+                    reset_mems,
+                    ..GenerationOptions::default()
+                },
+                &mut v,
+            )
+            .unwrap();
+            String::from_utf8(v).unwrap()
+        };
+
+        // Without registers, there's only a reset method if mems are reset
+        assert!(!generate_with_reset_mems(false).contains("pub fn reset"));
+        assert!(generate_with_reset_mems(true)
+            .contains("pub fn reset(&mut self) {\n        self.__mem_m_mem_0.copy_from_slice(&[\n            0x12,\n            0x34,\n        ]);\n    }"));
+    }
+
+    #[test]
+    fn instance_names() {
+        let c = Context::new();
+
+        let cpu = c.module("cpu", "Cpu");
+        let i = cpu.input("i", 8);
+        let mut o: &dyn Signal = i;
+        for instance_name in &["decode_stage", "execute_stage"] {
+            let decoder = cpu.module(*instance_name, "Decoder");
+            let decoder_i = decoder.input("i", 8);
+            let r = decoder.reg("r", 8);
+            r.drive_next(decoder_i);
+            decoder_i.drive(o);
+            o = decoder.output("o", r);
+        }
+        cpu.output("o", o);
+
+        let mut v = Vec::new();
+        generate(
+            cpu,
+            GenerationOptions {
+                tracing: true,
+                ..GenerationOptions::default()
+            },
+            &mut v,
+        )
+        .unwrap();
+        let code = String::from_utf8(v).unwrap();
+
+        // Instance names are used for trace scopes and register names
+        assert!(code.contains("trace.push_module(\"decode_stage\")?;"));
+        assert!(code.contains("trace.push_module(\"execute_stage\")?;"));
+        assert!(code.contains("__reg_cpu_decode_stage_r_"));
+        assert!(code.contains("__reg_cpu_execute_stage_r_"));
+    }
+
+    #[test]
+    fn poison_uninitialized_regs() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        let a = m.reg("a", 12);
+        a.drive_next(m.input("i", 12));
+        m.output("a", a);
+        let b = m.reg("b", 12);
+        b.default_value(0u32);
+        b.drive_next(a);
+        m.output("b", b);
+
+        let generate_with_poison = |poison_uninitialized_regs| {
+            let mut v = Vec::new();
+            generate(
+                m,
+                GenerationOptions {
+                    poison_uninitialized_regs,
+                    ..GenerationOptions::default()
+                },
+                &mut v,
+            )
+            .unwrap();
+            String::from_utf8(v).unwrap()
+        };
+
+        assert!(!generate_with_poison(false).contains("poison"));
+        let code = generate_with_poison(true);
+        assert!(code.contains("pub fn set_poison_seed(&mut self, seed: u64) {"));
+        // Only the register without a default value is poisoned, on construction and on reset
+        assert!(code.contains(
+            "fn __poison_regs(&mut self) {\n        self.__reg_m_a_0 = ((self.__poison_next()) & 0xfff) as u32;\n    }"
+        ));
+        assert!(code.contains("sim.__poison_regs();\n        sim\n"));
+        assert!(code.contains(
+            "pub fn reset(&mut self) {\n        self.__reg_m_b_1 = 0x0u32;\n        self.__poison_regs();\n    }"
+        ));
+    }
+
     #[test]
     #[should_panic(
         expected = "Cannot generate code for module \"A\" because module \"A\" contains an instance of module \"B\" called \"b\" whose input \"i\" is not driven."
@@ -788,13 +2140,14 @@ mod tests {
 
     #[test]
     #[should_panic(
-        expected = "Cannot generate code for module \"A\" because module \"A\" contains a memory called \"m\" which doesn't have any read ports."
+        expected = "Cannot generate code for module \"A\" because module \"B\" contains a deferred register called \"r\" whose bit width was never resolved."
     )]
-    fn mem_without_read_ports_error1() {
+    fn unresolved_deferred_register_error() {
         let c = Context::new();
 
         let a = c.module("a", "A");
-        let _ = a.mem("m", 1, 1);
+        let b = a.module("b", "B");
+        let _ = b.reg_deferred("r");
 
         // Panic
         generate(a, GenerationOptions::default(), Vec::new()).unwrap();
@@ -802,14 +2155,13 @@ mod tests {
 
     #[test]
     #[should_panic(
-        expected = "Cannot generate code for module \"A\" because module \"B\" contains a memory called \"m\" which doesn't have any read ports."
+        expected = "Cannot generate code for module \"A\" because module \"A\" contains a deferred output called \"o\" which is not driven."
     )]
-    fn mem_without_read_ports_error2() {
+    fn undriven_deferred_output_error() {
         let c = Context::new();
 
         let a = c.module("a", "A");
-        let b = a.module("b", "B");
-        let _ = b.mem("m", 1, 1);
+        let _ = a.output_deferred("o");
 
         // Panic
         generate(a, GenerationOptions::default(), Vec::new()).unwrap();
@@ -817,14 +2169,43 @@ mod tests {
 
     #[test]
     #[should_panic(
-        expected = "Cannot generate code for module \"A\" because module \"A\" contains a memory called \"m\" which doesn't have initial contents or a write port specified. At least one of the two is required."
+        expected = "Cannot generate code for module \"A\" because module \"A\" contains a memory called \"m\" which doesn't have any read ports."
     )]
-    fn mem_without_initial_contents_or_write_port_error1() {
+    fn mem_without_read_ports_error1() {
         let c = Context::new();
 
         let a = c.module("a", "A");
-        let m = a.mem("m", 1, 1);
-        let _ = m.read_port(a.low(), a.low());
+        let _ = a.mem("m", 1, 1);
+
+        // Panic
+        generate(a, GenerationOptions::default(), Vec::new()).unwrap();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot generate code for module \"A\" because module \"B\" contains a memory called \"m\" which doesn't have any read ports."
+    )]
+    fn mem_without_read_ports_error2() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let b = a.module("b", "B");
+        let _ = b.mem("m", 1, 1);
+
+        // Panic
+        generate(a, GenerationOptions::default(), Vec::new()).unwrap();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot generate code for module \"A\" because module \"A\" contains a memory called \"m\" which doesn't have initial contents or a write port specified. At least one of the two is required."
+    )]
+    fn mem_without_initial_contents_or_write_port_error1() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let m = a.mem("m", 1, 1);
+        let _ = m.read_port(a.low(), a.low());
 
         // Panic
         generate(a, GenerationOptions::default(), Vec::new()).unwrap();
@@ -862,4 +2243,1153 @@ mod tests {
         // Panic
         generate(b, GenerationOptions::default(), Vec::new()).unwrap();
     }
+
+    #[test]
+    fn bool_network_has_no_casts() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        let a = m.input("a", 1);
+        let b = m.input("b", 1);
+        let cond = m.input("cond", 1);
+        let s = m.input("s", 3);
+        let r = m.reg("r", 1);
+        r.default_value(false);
+        r.drive_next(m.mux(cond, a & !b, (a ^ r) | b));
+        m.output("r", r);
+        m.output("add", a + b);
+        m.output("sub", a - b);
+        m.output("shl", a << b);
+        m.output("shr", a >> s);
+        m.output("shr_arithmetic", a.shr_arithmetic(s));
+        m.output("eq", a.eq(b));
+
+        let mut v = Vec::new();
+        generate(m, GenerationOptions::default(), &mut v).unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        assert_eq!(v.matches(" as ").count(), 0);
+        assert_eq!(v.matches("wrapping_").count(), 0);
+        assert_eq!(v.matches("checked_").count(), 0);
+    }
+
+    #[test]
+    fn attribute_docs() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        let i = m.input("i", 8);
+        i.attribute("my_tool", "pin", "A7");
+        let r = m.reg("r", 8);
+        r.attribute("xilinx", "async_reg", "true");
+        r.drive_next(i);
+        m.output("o", r);
+
+        let mut v = Vec::new();
+        generate(m, GenerationOptions::default(), &mut v).unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        assert!(v.contains("    /// my_tool_pin = \"A7\"\n    pub i: u32,"));
+        assert!(v.contains("    /// xilinx_async_reg = \"true\"\n    __reg_m_r_"));
+    }
+
+    #[test]
+    fn override_clock_and_reset_names() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        let soft = m.reset_domain("soft");
+        let r1 = m.reg("r1", 8);
+        r1.default_value(0u32);
+        r1.drive_next(m.input("i1", 8));
+        m.output("o1", r1);
+        let r2 = m.reg("r2", 8);
+        r2.default_value(0u32);
+        r2.reset_domain(soft);
+        r2.drive_next(m.input("i2", 8));
+        m.output("o2", r2);
+
+        let mut v = Vec::new();
+        generate(
+            m,
+            GenerationOptions {
+                override_clock_name: Some("clk_i".into()),
+                override_reset_name: Some("rst".into()),
+                ..GenerationOptions::default()
+            },
+            &mut v,
+        )
+        .unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        assert!(v.contains("pub fn rst(&mut self) {"));
+        assert!(v.contains("pub fn rst_soft(&mut self) {"));
+        assert!(v.contains("pub fn posedge_clk_i(&mut self) {"));
+        assert!(!v.contains("pub fn reset"));
+        assert!(!v.contains("pub fn posedge_clk("));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot generate code for module \"M\" with reset name \"match\" (see GenerationOptions::override_reset_name), because the generated reset method \"match\" would be named after a Rust keyword."
+    )]
+    fn override_reset_name_keyword_error() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        let r = m.reg("r", 1);
+        r.default_value(false);
+        r.drive_next(m.input("i", 1));
+        m.output("o", r);
+
+        // Panic
+        generate(
+            m,
+            GenerationOptions {
+                override_reset_name: Some("match".into()),
+                ..GenerationOptions::default()
+            },
+            Vec::new(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot generate code for module \"M\" with reset name \"prop\" (see GenerationOptions::override_reset_name), because the generated reset method \"prop\" would have the same name as another generated method."
+    )]
+    fn override_reset_name_method_collision_error() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        let r = m.reg("r", 1);
+        r.default_value(false);
+        r.drive_next(m.input("i", 1));
+        m.output("o", r);
+
+        // Panic
+        generate(
+            m,
+            GenerationOptions {
+                override_reset_name: Some("prop".into()),
+                ..GenerationOptions::default()
+            },
+            Vec::new(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot generate code for module \"M\" with reset name \"reset\" (see GenerationOptions::override_reset_name), because the generated reset method \"reset_coverage\" would have the same name as another generated method."
+    )]
+    fn reset_domain_method_collision_error() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        let coverage = m.reset_domain("coverage");
+        let r = m.reg("r", 1);
+        r.default_value(false);
+        r.reset_domain(coverage);
+        r.drive_next(m.input("i", 1));
+        m.output("o", r);
+
+        // Panic
+        generate(m, GenerationOptions::default(), Vec::new()).unwrap();
+    }
+
+    #[test]
+    fn ports_named_like_clock_and_reset() {
+        let c = Context::new();
+
+        // Fields can have the same names as the clock and reset methods, and child module ports aren't fields at all
+        let m = c.module("m", "M");
+        let ch = m.module("ch", "Ch");
+        let ch_clk = ch.input("clk", 1);
+        ch_clk.drive(m.input("reset", 1));
+        let r = m.reg("r", 1);
+        r.default_value(false);
+        r.drive_next(ch.output("reset", ch_clk));
+        m.output("clk", r);
+
+        let mut v = Vec::new();
+        generate(m, GenerationOptions::default(), &mut v).unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        assert!(v.contains("pub reset: bool,"));
+        assert!(v.contains("pub clk: bool,"));
+        assert!(v.contains("pub fn reset(&mut self) {"));
+        assert!(v.contains("pub fn posedge_clk(&mut self) {"));
+    }
+
+    #[test]
+    fn optimize_folds_constant_logic() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        let i = m.input("i", 8);
+        let zero = m.lit(0u32, 8);
+        m.output("o", (i & m.lit(0xffu32, 8)) | (zero + zero));
+        m.output(
+            "k",
+            m.mux(m.high(), m.lit(0x12u32, 8) ^ m.lit(0x34u32, 8), i),
+        );
+
+        let mut v = Vec::new();
+        generate(
+            m,
+            GenerationOptions {
+                optimize: true,
+                ..GenerationOptions::default()
+            },
+            &mut v,
+        )
+        .unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        // Only the input mask remains
+        assert!(v.contains("self.o = (self.i & 0xffu32);"));
+        assert!(v.contains("self.k = 0x26u32;"));
+        assert_eq!(v.matches("__temp").count(), 0);
+    }
+
+    #[test]
+    fn named_wire_local() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let i = a.input("i", 8);
+        let b = a.module("b", "B");
+        let b_i = b.input("i", 8);
+        b.output("o", (!b_i).named("inverted"));
+        b_i.drive(a.named_wire("w", i ^ a.lit(0x0fu32, 8)));
+        a.output("o", b.outputs.borrow()["o"]);
+
+        let mut v = Vec::new();
+        generate(a, GenerationOptions::default(), &mut v).unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        assert!(v.contains("let __wire_a_w = "));
+        assert!(v.contains("let __wire_a_b_inverted = "));
+        assert!(v.contains("self.o = __wire_a_b_inverted;"));
+    }
+
+    #[test]
+    fn state_elements_sharing_port_names() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        let i = m.input("i", 8);
+        let r = m.reg("o", 8);
+        r.drive_next(i);
+        let mem = m.mem("i", 1, 8);
+        mem.write_port(m.low(), r, m.high());
+        m.output("o", mem.read_port(m.low(), m.high()));
+
+        let mut v = Vec::new();
+        generate(
+            m,
+            GenerationOptions {
+                tracing: true,
+                ..GenerationOptions::default()
+            },
+            &mut v,
+        )
+        .unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        // Ports keep their names, while state elements and trace signal IDs are always referred to by
+        //  mangled names, so sharing names doesn't cause any member collisions
+        assert_eq!(v.matches("pub i: u32,").count(), 1);
+        assert_eq!(v.matches("pub o: u32,").count(), 1);
+        assert!(v.contains("__reg_m_o_0: u32,"));
+        assert!(v.contains("__mem_m_i_0: Box<[u32]>,"));
+        assert!(v.contains("__trace_signal_id_o_1: T::SignalId,"));
+        assert!(v.contains("__trace_signal_id_o_7: T::SignalId,"));
+    }
+
+    #[test]
+    fn trace_on_change_only() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        let r = m.reg("r", 8);
+        r.default_value(0u32);
+        r.drive_next(m.input("i", 8));
+        m.output("o", r);
+
+        let generate_with_trace_on_change_only = |trace_on_change_only| {
+            let mut v = Vec::new();
+            generate(
+                m,
+                GenerationOptions {
+                    tracing: true,
+                    trace_on_change_only,
+                    ..GenerationOptions::default()
+                },
+                &mut v,
+            )
+            .unwrap();
+            String::from_utf8(v).unwrap()
+        };
+
+        let v = generate_with_trace_on_change_only(true);
+        assert!(v.contains("__trace_force_update: bool,"));
+        assert!(v.contains("__trace_prev_value_i_0: u32,"));
+        assert!(v.contains("if force_update || self.i != self.__trace_prev_value_i_0 {"));
+        assert!(v.contains("self.__trace_prev_value_i_0 = self.i;"));
+        assert!(v.contains("self.__trace_force_update = true;"));
+
+        let v = generate_with_trace_on_change_only(false);
+        assert!(!v.contains("__trace_force_update"));
+        assert!(!v.contains("__trace_prev_value"));
+    }
+
+    fn reinterpret_module<'a>(c: &'a Context<'a>, reinterpret: bool) -> &'a Module<'a> {
+        let m = c.module("m", "A");
+        let a = m.input("a", 32);
+        let b = m.input("b", 32);
+        let sum = a + b;
+        let sum = if reinterpret {
+            sum.reinterpret("f32").reinterpret("raw")
+        } else {
+            sum
+        };
+        m.output("o1", sum & a);
+        m.output("o2", sum ^ b);
+        let r = m.reg("r", 32);
+        r.drive_next(sum);
+        m.output("o3", r);
+        let inner = m.module("inner", "Inner");
+        let inner_i = inner.input("i", 32);
+        inner_i.drive(if reinterpret { a.reinterpret("f32") } else { a });
+        m.output("o4", inner.output("o", !inner_i));
+        m
+    }
+
+    #[test]
+    fn reinterpret_has_no_effect() {
+        for &optimize in &[false, true] {
+            let generate_with_reinterpret = |reinterpret| {
+                let c = Context::new();
+                let mut v = Vec::new();
+                generate(
+                    reinterpret_module(&c, reinterpret),
+                    GenerationOptions {
+                        optimize,
+                        ..GenerationOptions::default()
+                    },
+                    &mut v,
+                )
+                .unwrap();
+                String::from_utf8(v).unwrap()
+            };
+
+            assert_eq!(
+                generate_with_reinterpret(true),
+                generate_with_reinterpret(false)
+            );
+        }
+    }
+
+    #[test]
+    fn dual_clock_mem_methods() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        let r = m.reg("r", 1);
+        r.drive_next(m.input("i", 1));
+        m.output("o", r);
+        let mem = m.mem("mem", 1, 1);
+        mem.dual_clock("read_clk", "write_clk");
+        mem.write_port(m.low(), m.input("i2", 1), m.high());
+        m.output("o2", mem.read_port(m.low(), m.high()));
+
+        let mut v = Vec::new();
+        generate(m, GenerationOptions::default(), &mut v).unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        assert!(v.contains("pub fn posedge_clk(&mut self) {"));
+        assert!(v.contains("pub fn posedge_read_clk(&mut self) {"));
+        assert!(v.contains("pub fn posedge_write_clk(&mut self) {"));
+    }
+
+    #[test]
+    fn reg_clock_domain_methods() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        let r = m.reg("r", 1);
+        r.drive_next(m.input("i", 1));
+        m.output("o", r);
+        let fast = m.reg_in_domain("fast", 1, "clk_fast");
+        fast.drive_next(!fast);
+        m.output("o2", fast);
+
+        let mut v = Vec::new();
+        generate(m, GenerationOptions::default(), &mut v).unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        let posedge_clk = v.find("pub fn posedge_clk(&mut self) {").unwrap();
+        let posedge_clk_fast = v.find("pub fn posedge_clk_fast(&mut self) {").unwrap();
+        let prop = v.find("pub fn prop(&mut self) {").unwrap();
+        // Each register is only updated by its own clock's method
+        assert!(v[posedge_clk..posedge_clk_fast].contains("self.__reg_m_r_0 = "));
+        assert!(!v[posedge_clk..posedge_clk_fast].contains("self.__reg_m_fast_1 = "));
+        assert!(v[posedge_clk_fast..prop].contains("self.__reg_m_fast_1 = "));
+        assert!(!v[posedge_clk_fast..prop].contains("self.__reg_m_r_0 = "));
+    }
+
+    #[test]
+    fn combinational_only() {
+        let c = Context::new();
+
+        // `clk` and `reset` are ordinary inputs, since there's no implicit clock or reset to collide with
+        let m = c.module("m", "M");
+        m.output("o", m.input("clk", 1) & m.input("reset", 1));
+
+        let mut v = Vec::new();
+        let options = GenerationOptions {
+            combinational_only: true,
+            ..GenerationOptions::default()
+        };
+        generate(m, options, &mut v).unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        assert!(v.contains("pub fn prop(&mut self) {"));
+        assert!(!v.contains("pub fn posedge_"));
+        assert!(!v.contains("pub fn reset"));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot generate combinational-only code for module \"M\" because module \"M\" contains a memory called \"mem\". Combinational-only modules can't contain registers, memories, assertions, or coverage points."
+    )]
+    fn combinational_only_mem_error() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        let mem = m.mem("mem", 1, 1);
+        mem.initial_contents(&[false, true]);
+        m.output("o", mem.read_port(m.input("a", 1), m.high()));
+
+        let options = GenerationOptions {
+            combinational_only: true,
+            ..GenerationOptions::default()
+        };
+
+        // Panic
+        generate(m, options, Vec::new()).unwrap();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot generate code for module \"M\" with both combinational_only and poison_uninitialized_regs enabled, because combinational-only modules don't contain any registers."
+    )]
+    fn combinational_only_poison_uninitialized_regs_error() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        m.output("o", !m.input("i", 1));
+
+        let options = GenerationOptions {
+            combinational_only: true,
+            poison_uninitialized_regs: true,
+            ..GenerationOptions::default()
+        };
+
+        // Panic
+        generate(m, options, Vec::new()).unwrap();
+    }
+
+    #[test]
+    fn io_structs() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        m.output("o", m.input("type", 1) & m.input("i", 1));
+        m.output("wide", m.input("wide_i", 100));
+
+        let generate_with = |io_structs| {
+            let mut v = Vec::new();
+            generate(
+                m,
+                GenerationOptions {
+                    io_structs,
+                    ..GenerationOptions::default()
+                },
+                &mut v,
+            )
+            .unwrap();
+            String::from_utf8(v).unwrap()
+        };
+
+        let v = generate_with(true);
+        assert!(v.contains(
+            "#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct MInputs {
+    pub i: bool, // 1 bit(s)
+    pub r#type: bool, // 1 bit(s)
+    pub wide_i: u128, // 100 bit(s)
+}"
+        ));
+        assert!(v.contains(
+            "pub struct MOutputs {
+    pub o: bool, // 1 bit(s)
+    pub wide: u128, // 100 bit(s)
+}"
+        ));
+        assert!(v.contains("pub fn set_inputs(&mut self, inputs: &MInputs) {"));
+        assert!(v.contains("self.r#type = inputs.r#type;"));
+        assert!(v.contains("pub fn outputs(&self) -> MOutputs {"));
+
+        let v = generate_with(false);
+        assert!(!v.contains("MInputs"));
+        assert!(!v.contains("MOutputs"));
+    }
+
+    #[test]
+    fn reg_negedge_methods() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        let r = m.reg("r", 1);
+        r.drive_next(m.input("i", 1));
+        let neg = m.reg("neg", 1);
+        neg.clock_edge(Edge::Neg);
+        neg.drive_next(r);
+        let fast = m.reg_in_domain("fast", 1, "clk_fast");
+        fast.clock_edge(Edge::Neg);
+        fast.drive_next(neg);
+        m.output("o", fast);
+
+        let mut v = Vec::new();
+        generate(m, GenerationOptions::default(), &mut v).unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        assert!(!v.contains("pub fn posedge_clk_fast(&mut self) {"));
+        let posedge_clk = v.find("pub fn posedge_clk(&mut self) {").unwrap();
+        let negedge_clk = v.find("pub fn negedge_clk(&mut self) {").unwrap();
+        let negedge_clk_fast = v.find("pub fn negedge_clk_fast(&mut self) {").unwrap();
+        let prop = v.find("pub fn prop(&mut self) {").unwrap();
+        // Each register is only updated by its own clock edge's method
+        assert!(v[posedge_clk..negedge_clk].contains("self.__reg_m_r_2 = "));
+        assert!(!v[posedge_clk..negedge_clk].contains("self.__reg_m_neg_1 = "));
+        assert!(v[negedge_clk..negedge_clk_fast].contains("self.__reg_m_neg_1 = "));
+        assert!(!v[negedge_clk..negedge_clk_fast].contains("self.__reg_m_r_2 = "));
+        assert!(v[negedge_clk_fast..prop].contains("self.__reg_m_fast_0 = "));
+        assert!(!v[negedge_clk_fast..prop].contains("self.__reg_m_neg_1 = "));
+    }
+
+    #[test]
+    fn case_lowered_to_conditionals() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        let sel = m.input("sel", 2);
+        let a = m.input("a", 8);
+        let b = m.input("b", 8);
+        m.output(
+            "o",
+            m.case(
+                sel + m.lit(1u32, 2),
+                &[(0u32, a), (2u32, b)],
+                m.lit(0xffu32, 8),
+            ),
+        );
+
+        let mut v = Vec::new();
+        generate(m, GenerationOptions::default(), &mut v).unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        // The selector is only evaluated once, and cases are tested in order
+        assert!(v.contains("let __temp_0 = ((self.sel & 0x3u32).wrapping_add(0x1u32) & 0x3u32);"));
+        assert!(v.contains("self.o = if (__temp_0 == 0x0u32) { (self.a & 0xffu32) } else { if (__temp_0 == 0x2u32) { (self.b & 0xffu32) } else { 0xffu32 } };"));
+    }
+
+    #[test]
+    fn mem_comb_read_port() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        let mem = m.mem("mem", 2, 8);
+        mem.initial_contents(&[1u8, 2u8, 3u8, 4u8]);
+        let addr = m.input("addr", 2);
+        m.output("o", mem.read_port_comb(addr + m.lit(1u32, 2)));
+        m.output("o_always", mem.read_port_always(addr));
+
+        let mut v = Vec::new();
+        generate(m, GenerationOptions::default(), &mut v).unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        let posedge_clk = v.find("pub fn posedge_clk(&mut self) {").unwrap();
+        let prop = v.find("pub fn prop(&mut self) {").unwrap();
+        // Combinational reads index the mem directly in prop, with their address in a temp
+        assert!(v[prop..].contains("self.o = self.__mem_m_mem_1[__temp_1 as usize];"));
+        assert!(!v[posedge_clk..prop].contains("__temp_1"));
+        // Always-enabled reads are ordinary registered reads
+        assert!(v[prop..].contains("self.__mem_m_mem_1_read_port_0_enable = true;"));
+        assert!(v[prop..].contains("self.o_always = self.__mem_m_mem_1_read_port_0_value;"));
+    }
+
+    #[test]
+    fn strict_inputs() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        m.output("a", m.input("i1", 1));
+        m.output("b", m.input("i8", 8));
+        m.output("c", m.input("i32", 32));
+        m.output("d", m.input("i100", 100));
+
+        let generate_with = |strict_inputs| {
+            let mut v = Vec::new();
+            generate(
+                m,
+                GenerationOptions {
+                    strict_inputs,
+                    ..GenerationOptions::default()
+                },
+                &mut v,
+            )
+            .unwrap();
+            String::from_utf8(v).unwrap()
+        };
+
+        let v = generate_with(true);
+        assert!(v.contains("debug_assert!(self.i8 >> 8 == 0, \"Input \\\"i8\\\" has bits set above its declared bit width of 8 bit(s).\");"));
+        assert!(v.contains("debug_assert!(self.i100 >> 100 == 0, \"Input \\\"i100\\\" has bits set above its declared bit width of 100 bit(s).\");"));
+        // Inputs that fill their native types can't have extra bits
+        assert_eq!(v.matches("debug_assert!").count(), 2);
+
+        let v = generate_with(false);
+        assert!(!v.contains("debug_assert!"));
+    }
+
+    #[test]
+    fn le_bytes_accessors() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        m.output("o64", m.input("i64", 64));
+        m.output("o100", m.input("i100", 100));
+        m.output("o128", m.input("i128", 128));
+
+        let mut v = Vec::new();
+        generate(m, GenerationOptions::default(), &mut v).unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        assert!(v.contains("pub fn set_i100_from_le_bytes(&mut self, bytes: &[u8; 13]) {"));
+        assert!(v.contains("self.i100 = value & ((1u128 << 100) - 1);"));
+        assert!(v.contains("pub fn set_i128_from_le_bytes(&mut self, bytes: &[u8; 16]) {"));
+        assert!(v.contains("pub fn o100_to_le_bytes(&self) -> [u8; 13] {"));
+        assert!(v.contains("pub fn o128_to_le_bytes(&self) -> [u8; 16] {"));
+        // Signals that fit in 64 bits don't get byte accessors
+        assert_eq!(v.matches("_le_bytes(&").count(), 4);
+    }
+
+    #[test]
+    fn no_std() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        let mem = m.mem("mem", 1, 8);
+        mem.initial_contents(&[0x12u32, 0x34u32]);
+        m.output("o", mem.read_port(m.input("i", 1), m.high()));
+
+        let mut v = Vec::new();
+        generate(
+            m,
+            GenerationOptions {
+                no_std: true,
+                ..GenerationOptions::default()
+            },
+            &mut v,
+        )
+        .unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        assert!(v.contains("__mem_m_mem_0: [u32; 2], // 8 bit elements"));
+        let lines = v.lines().map(str::trim).collect::<Vec<_>>();
+        assert!(lines
+            .windows(4)
+            .any(|w| w == ["__mem_m_mem_0: [", "0x12,", "0x34,", "],"]));
+        assert!(!v.contains("Box"));
+        assert!(!v.contains("vec!"));
+        assert!(!v.contains("dump_state"));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot generate code for module \"M\" with both no_std and tracing enabled, because tracing requires std."
+    )]
+    fn no_std_tracing_error() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        m.output("o", m.input("i", 1));
+
+        // Panic
+        generate(
+            m,
+            GenerationOptions {
+                tracing: true,
+                no_std: true,
+                ..GenerationOptions::default()
+            },
+            Vec::new(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot generate code for module \"M\" with both no_std and collect_assertion_failures enabled, because collecting assertion failures requires std."
+    )]
+    fn no_std_collect_assertion_failures_error() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        m.output("o", m.input("i", 1));
+
+        // Panic
+        generate(
+            m,
+            GenerationOptions {
+                collect_assertion_failures: true,
+                no_std: true,
+                ..GenerationOptions::default()
+            },
+            Vec::new(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot generate code for module \"M\" with collect_assertion_failures enabled because its input or output \"assertion_failures\" would have the same name as the generated \"assertion_failures\" field."
+    )]
+    fn collect_assertion_failures_port_name_error() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        let i = m.input("assertion_failures", 1);
+        m.output("o", i);
+
+        // Panic
+        generate(
+            m,
+            GenerationOptions {
+                collect_assertion_failures: true,
+                ..GenerationOptions::default()
+            },
+            Vec::new(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn keyword_identifiers() {
+        let c = Context::new();
+
+        let m = c.module("m", "fifo<8>");
+        let i = m.input("match", 100);
+        m.output("self", i);
+        m.output("a b", m.input("type", 1));
+
+        let mut v = Vec::new();
+        generate(
+            m,
+            GenerationOptions {
+                strict_inputs: true,
+                ..GenerationOptions::default()
+            },
+            &mut v,
+        )
+        .unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        assert!(v.contains("pub struct fifo_8_ {"));
+        assert!(v.contains("pub r#match: u128, // 100 bit(s)"));
+        assert!(v.contains("pub r#type: bool, // 1 bit(s)"));
+        assert!(v.contains("pub self_: u128, // 100 bit(s)"));
+        assert!(v.contains("pub a_b: bool, // 1 bit(s)"));
+        assert!(v.contains("debug_assert!(self.r#match >> 100 == 0, \"Input \\\"match\\\" has bits set above its declared bit width of 100 bit(s).\");"));
+        assert!(v.contains("pub fn set_match_from_le_bytes(&mut self, bytes: &[u8; 13]) {"));
+        assert!(v.contains("pub fn self__to_le_bytes(&self) -> [u8; 13] {"));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot generate code for module \"A\" because its inputs or outputs \"a b\" and \"a_b\" would both be called \"a_b\" in the generated simulator."
+    )]
+    fn port_identifier_collision_error() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        a.output("a_b", a.input("a b", 1));
+
+        // Panic
+        generate(a, GenerationOptions::default(), Vec::new()).unwrap();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot generate code for module \"A\" because its input or output \"__reg_a_r_0\" would be called \"__reg_a_r_0\" in the generated simulator, and names starting with \"__\" are reserved for generated fields."
+    )]
+    fn port_identifier_reserved_error() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let r = a.reg("r", 1);
+        r.drive_next(a.input("i", 1));
+        a.output("__reg_a_r_0", r);
+
+        // Panic
+        generate(a, GenerationOptions::default(), Vec::new()).unwrap();
+    }
+
+    #[test]
+    fn port_and_state_element_same_name() {
+        let c = Context::new();
+
+        // Registers and mems are private fields with generated names, so they can share names with ports
+        let a = c.module("a", "A");
+        let o = a.reg("o", 8);
+        o.drive_next(a.input("i", 8));
+        let i = a.mem("i", 1, 8);
+        i.initial_contents(&[0u8, 1u8]);
+        a.output(
+            "o",
+            o.bits(0, 0).mux(i.read_port_comb(a.input("addr", 1)), o),
+        );
+
+        let mut v = Vec::new();
+        generate(a, GenerationOptions::default(), &mut v).unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        assert!(v.contains("pub i: u32, // 8 bit(s)"));
+        assert!(v.contains("pub o: u32, // 8 bit(s)"));
+        assert!(v.contains("__reg_a_o_0: u32,"));
+        assert!(v.contains("__mem_a_i_0: Box<[u32]>,"));
+    }
+
+    #[test]
+    fn unreachable_modules_ignored() {
+        let c = Context::new();
+
+        // Neither of these modules is reachable from `top`, and both are invalid
+        let unrelated = c.module("unrelated", "Unrelated");
+        let _ = unrelated.reg("undriven", 1);
+        unrelated.output("o", unrelated.input("i", 1));
+        let undefined = c.declare_module("Undefined");
+        let _ = unrelated.instance("undefined", &undefined);
+        let other = c.module("other", "Other");
+        let _ = other.module("inner", "OtherInner").input("undriven", 8);
+
+        let top = c.module("top", "Top");
+        let inner = top.module("inner", "TopInner");
+        let i = inner.input("i", 8);
+        inner.output("o", i.reg_next_with_default("r", 0u32));
+        i.drive(top.input("i", 8));
+        top.output("o", inner.get_output("o"));
+
+        let mut v = Vec::new();
+        generate(top, GenerationOptions::default(), &mut v).unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        assert!(v.contains("pub struct Top {"));
+        assert!(!v.contains("nrelated"));
+        assert!(!v.contains("ndefined"));
+        assert!(!v.contains("Other"));
+    }
+
+    #[test]
+    fn packed_bools() {
+        let c = Context::new();
+
+        let m = c.module("m", "A");
+        let i = m.input("i", 1);
+        let mut next = i.value;
+        for n in 0..40 {
+            let r = m.reg(format!("r{}", n), 1);
+            r.drive_next(next);
+            next = r.value;
+        }
+        let wide = m.reg("wide", 8);
+        wide.drive_next(m.lit(0xffu32, 8));
+        m.output("o", next);
+        m.output("wide", wide);
+
+        let generate_with = |pack_bools| {
+            let mut v = Vec::new();
+            generate(
+                m,
+                GenerationOptions {
+                    pack_bools,
+                    ..GenerationOptions::default()
+                },
+                &mut v,
+            )
+            .unwrap();
+            String::from_utf8(v).unwrap()
+        };
+
+        let unpacked = generate_with(false);
+        assert!(!unpacked.contains("__packed_bools"));
+        assert!(unpacked.contains("__reg_m_r0_"));
+
+        // Each 1-bit register needs a bit for its value and a bit for its next value
+        let packed = generate_with(true);
+        assert!(packed.contains("__packed_bools: [u64; 2],"));
+        assert!(!packed.contains("__reg_m_r0_"));
+        assert!(packed.contains("pub i: bool, // 1 bit(s)"));
+        assert!(packed.contains("pub o: bool, // 1 bit(s)"));
+        assert!(packed.contains(": u32, // 8 bit(s)"));
+    }
+
+    #[test]
+    fn packed_bools_code() {
+        let c = Context::new();
+
+        let m = c.module("m", "A");
+        let i = m.input("i", 1);
+        let r0 = m.reg("r0", 1);
+        r0.default_value(true);
+        r0.drive_next(i);
+        let r1 = m.reg("r1", 1);
+        r1.default_value(false);
+        r1.drive_next(r0);
+        m.output("o", r1);
+
+        let mut v = Vec::new();
+        generate(
+            m,
+            GenerationOptions {
+                pack_bools: true,
+                tracing: true,
+                trace_on_change_only: true,
+                ..GenerationOptions::default()
+            },
+            &mut v,
+        )
+        .unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        // Bit 0 is accessed without shifts, and constants are assigned by setting or clearing bits
+        assert!(v.contains("self.__packed_bools[0] |= 1;"));
+        assert!(v.contains("self.__packed_bools[0] &= !(1u64 << 2);"));
+        assert!(v.contains("(self.__packed_bools[0] & 1 != 0)"));
+        assert!(v.contains(
+            "self.__packed_bools[0] = (self.__packed_bools[0] & !1) | (((((self.__packed_bools[0] >> 1) & 1 != 0)) as u64));"
+        ));
+        assert!(!v.contains("<< 0)"));
+        assert!(!v.contains(">> 0)"));
+        assert!(!v.contains("(true) as u64"));
+        assert!(!v.contains("(false) as u64"));
+    }
+
+    #[test]
+    fn max_line_width() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        let inputs = (0..12)
+            .map(|i| m.input(format!("some_long_input_name_{}", i), 16))
+            .collect::<Vec<_>>();
+        let sum = inputs[1..]
+            .iter()
+            .fold(inputs[0].into(), |acc: &dyn Signal, &i| acc + i);
+        let r = m.reg("r", 16);
+        r.default_value(0u32);
+        r.drive_next(if_(sum.bit(0), sum ^ r).else_(sum & !r));
+        let mem = m.mem("mem", 4, 16);
+        mem.write_port(sum.bits(3, 0), r, inputs[0].bit(1));
+        m.output(
+            "o",
+            mem.read_port(r.bits(3, 0), m.high()).concat(sum).concat(r),
+        );
+
+        for &tracing in &[false, true] {
+            let generate_with = |max_line_width| {
+                let mut v = Vec::new();
+                generate(
+                    m,
+                    GenerationOptions {
+                        tracing,
+                        max_line_width,
+                        ..GenerationOptions::default()
+                    },
+                    &mut v,
+                )
+                .unwrap();
+                String::from_utf8(v).unwrap()
+            };
+            let prop_lines = |v: &str| {
+                v.lines()
+                    .skip_while(|line| !line.contains("pub fn prop(&mut self)"))
+                    .take_while(|line| *line != "    }")
+                    .map(|line| line.len())
+                    .collect::<Vec<_>>()
+            };
+            let strip_whitespace = |v: &str| v.split_whitespace().collect::<String>();
+
+            let unwrapped = generate_with(None);
+            let wrapped = generate_with(Some(60));
+            syn::parse_file(&unwrapped).unwrap();
+            syn::parse_file(&wrapped).unwrap();
+
+            // Wrapping only changes whitespace
+            assert!(prop_lines(&unwrapped).iter().any(|&len| len > 60));
+            assert!(prop_lines(&wrapped).iter().all(|&len| len <= 60));
+            assert!(prop_lines(&wrapped).len() > prop_lines(&unwrapped).len());
+            assert_eq!(strip_whitespace(&wrapped), strip_whitespace(&unwrapped));
+            assert!(!wrapped.lines().any(|line| line.ends_with(' ')));
+        }
+    }
+
+    #[test]
+    fn module_visibility_and_wrapper_module() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        m.output("o", m.input("i", 8));
+
+        let generate_with = |module_visibility, wrapper_module: Option<&str>| {
+            let mut v = Vec::new();
+            generate(
+                m,
+                GenerationOptions {
+                    io_structs: true,
+                    module_visibility,
+                    wrapper_module: wrapper_module.map(Into::into),
+                    ..GenerationOptions::default()
+                },
+                &mut v,
+            )
+            .unwrap();
+            let v = String::from_utf8(v).unwrap();
+            syn::parse_file(&v).unwrap();
+            v
+        };
+
+        let v = generate_with(Visibility::Public, None);
+        assert!(v.starts_with("pub struct M {"));
+        assert!(v.contains("\npub struct MInputs {"));
+        assert!(!v.contains("mod "));
+
+        let v = generate_with(Visibility::Crate, None);
+        assert!(v.starts_with("pub(crate) struct M {"));
+        assert!(v.contains("\npub(crate) struct MOutputs {"));
+        assert!(v.contains("    pub i: u32, // 8 bit(s)"));
+        assert!(v.contains("    pub fn prop(&mut self) {"));
+
+        let v = generate_with(Visibility::Private, None);
+        assert!(v.starts_with("struct M {"));
+        assert!(v.contains("\nstruct MInputs {"));
+
+        let v = generate_with(Visibility::Super, Some("m_sim"));
+        assert!(v.starts_with("mod m_sim {\n    pub struct M {\n        // Inputs\n"));
+        assert!(v.contains("\n    pub struct MInputs {"));
+        assert!(v.ends_with("}\n\npub(super) use m_sim::{M, MInputs, MOutputs};\n"));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot generate code for module \"M\" with wrapper module name \"match\". Wrapper module names must be non-empty, consist only of ASCII alphanumeric characters and underscores, must not start with a digit, and must not be Rust keywords."
+    )]
+    fn wrapper_module_keyword_error() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        m.output("o", m.input("i", 1));
+
+        let options = GenerationOptions {
+            wrapper_module: Some("match".into()),
+            ..GenerationOptions::default()
+        };
+
+        // Panic
+        generate(m, options, Vec::new()).unwrap();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot generate code for module \"M\" with wrapper module name \"MOutputs\", because the generated code contains a struct with the same name."
+    )]
+    fn wrapper_module_name_collision_error() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        m.output("o", m.input("i", 1));
+
+        let options = GenerationOptions {
+            io_structs: true,
+            wrapper_module: Some("MOutputs".into()),
+            ..GenerationOptions::default()
+        };
+
+        // Panic
+        generate(m, options, Vec::new()).unwrap();
+    }
+
+    #[test]
+    fn doc_comments() {
+        let c = Context::new();
+
+        let m = c.module("m", "fifo<8>");
+        m.output("o", m.input("type", 1) & m.input("i", 1));
+        m.output("wide", m.input("wide_i", 100));
+        let o = m.output("attr", m.input("a", 1));
+        o.attribute("my_tool", "note", "x");
+
+        let generate_with = |doc_comments| {
+            let mut v = Vec::new();
+            generate(
+                m,
+                GenerationOptions {
+                    io_structs: true,
+                    collect_assertion_failures: true,
+                    doc_comments,
+                    ..GenerationOptions::default()
+                },
+                &mut v,
+            )
+            .unwrap();
+            let v = String::from_utf8(v).unwrap();
+            syn::parse_file(&v).unwrap();
+            v
+        };
+
+        let v = generate_with(true);
+        assert!(v.starts_with("/// Simulator for module \"fifo<8>\".\npub struct fifo_8_ {"));
+        assert!(v.contains(
+            "    /// Input \"type\" (1 bit(s)).
+    pub r#type: bool, // 1 bit(s)"
+        ));
+        assert!(v.contains(
+            "    /// Output \"wide\" (100 bit(s)).
+    pub wide: u128, // 100 bit(s)"
+        ));
+        assert!(v.contains(
+            "    /// Output \"attr\" (1 bit(s)).
+    /// my_tool_note = \"x\"
+    pub attr: bool, // 1 bit(s)"
+        ));
+        assert!(v.contains("    /// Messages of the assertions that failed in calls to `prop`, in the order they failed.\n    pub assertion_failures: Vec<&'static str>,"));
+        assert!(v.contains(
+            "/// Values of all of the inputs of `fifo_8_`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct fifo_8_Inputs {
+    /// Input \"a\" (1 bit(s)).
+    pub a: bool, // 1 bit(s)"
+        ));
+        assert!(v.contains("/// Values of all of the outputs of `fifo_8_`."));
+
+        let v = generate_with(false);
+        assert!(!v.contains("/// Simulator"));
+        assert!(!v.contains("/// Input"));
+        assert!(!v.contains("/// Output"));
+        assert!(v.contains("/// my_tool_note = \"x\""));
+    }
 }
@@ -1,41 +1,76 @@
+use super::identifiers::*;
 use super::ir::*;
 
+use crate::graph;
 use crate::graph::internal_signal;
+use crate::identifiers::sanitize;
+use crate::optimizer::*;
 use crate::state_elements::*;
 
 use typed_arena::Arena;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 // TODO: Can we merge the context and expr_arena lifetimes?
 pub(super) struct Compiler<'graph, 'context, 'expr_arena> {
     state_elements: &'context StateElements<'graph>,
+    optimizer: &'context Optimizer<'graph>,
     signal_reference_counts:
         &'context HashMap<&'graph internal_signal::InternalSignal<'graph>, u32>,
+    packed_bools: &'context PackedBools,
     expr_arena: &'expr_arena Arena<Expr<'expr_arena>>,
 
     signal_exprs:
         HashMap<&'graph internal_signal::InternalSignal<'graph>, &'expr_arena Expr<'expr_arena>>,
+    named_wire_names: HashSet<String>,
 }
 
 impl<'graph, 'context, 'expr_arena> Compiler<'graph, 'context, 'expr_arena> {
     pub fn new(
         state_elements: &'context StateElements<'graph>,
+        optimizer: &'context Optimizer<'graph>,
         signal_reference_counts: &'context HashMap<
             &'graph internal_signal::InternalSignal<'graph>,
             u32,
         >,
+        packed_bools: &'context PackedBools,
         expr_arena: &'expr_arena Arena<Expr<'expr_arena>>,
     ) -> Compiler<'graph, 'context, 'expr_arena> {
         Compiler {
             state_elements,
+            optimizer,
             signal_reference_counts,
+            packed_bools,
             expr_arena,
 
             signal_exprs: HashMap::new(),
+            named_wire_names: HashSet::new(),
         }
     }
 
+    /// Compiles the signal that drives `output`, which may differ from its source (see [`Optimizer::optimize_output`]).
+    pub fn compile_output(
+        &mut self,
+        output: &'graph graph::OutputData<'graph>,
+        a: &mut AssignmentContext<'expr_arena>,
+    ) -> &'expr_arena Expr<'expr_arena> {
+        let signal = self.optimizer.optimize_output(output);
+        self.compile_signal(signal, a)
+    }
+
+    /// Returns `base`, or `base` with a `_<n>` suffix if `base` was already returned for another named wire.
+    ///
+    /// Instance path prefixes are joined with `_`, so named wires in different modules (or whose names only differ in characters that are sanitized) can map to the same `base`, and their locals would shadow each other otherwise.
+    fn named_wire_name(&mut self, base: &str) -> String {
+        let mut name = base.to_string();
+        let mut suffix = 1;
+        while !self.named_wire_names.insert(name.clone()) {
+            name = format!("{}_{}", base, suffix);
+            suffix += 1;
+        }
+        name
+    }
+
     pub fn compile_signal(
         &mut self,
         signal: &'graph internal_signal::InternalSignal<'graph>,
@@ -47,7 +82,7 @@ impl<'graph, 'context, 'expr_arena> Compiler<'graph, 'context, 'expr_arena> {
         }
 
         let mut frames = Vec::new();
-        frames.push(Frame::Enter(signal));
+        frames.push(Frame::Enter(self.optimizer.optimize(signal)));
 
         let mut results = Vec::new();
 
@@ -68,29 +103,29 @@ impl<'graph, 'context, 'expr_arena> Compiler<'graph, 'context, 'expr_arena> {
 
                         internal_signal::SignalData::Input { data } => {
                             if let Some(driven_value) = data.driven_value.borrow().clone() {
-                                frames.push(Frame::Enter(driven_value));
+                                frames.push(Frame::Enter(self.optimizer.optimize(driven_value)));
                                 None
                             } else {
                                 let bit_width = data.bit_width;
                                 let target_type = ValueType::from_bit_width(bit_width);
                                 let expr = self.expr_arena.alloc(Expr::Ref {
-                                    name: data.name.clone(),
+                                    name: identifier(&data.name),
                                     scope: Scope::Member,
                                 });
                                 Some((key, self.gen_mask(expr, bit_width, target_type)))
                             }
                         }
                         internal_signal::SignalData::Output { data } => {
-                            frames.push(Frame::Enter(data.source));
+                            frames.push(Frame::Enter(self.optimizer.optimize_output(data)));
                             None
                         }
 
                         internal_signal::SignalData::Reg { .. } => Some((
                             key,
-                            &*self.expr_arena.alloc(Expr::Ref {
-                                name: self.state_elements.regs[&key].value_name.clone(),
-                                scope: Scope::Member,
-                            }),
+                            self.packed_bools.member(
+                                &self.state_elements.regs[&key].value_name,
+                                self.expr_arena,
+                            ),
                         )),
 
                         internal_signal::SignalData::UnOp { source, .. } => {
@@ -167,6 +202,32 @@ impl<'graph, 'context, 'expr_arena> Compiler<'graph, 'context, 'expr_arena> {
                             None
                         }
 
+                        internal_signal::SignalData::Case {
+                            sel,
+                            ref cases,
+                            default,
+                            ..
+                        } => {
+                            frames.push(Frame::Leave(signal));
+                            frames.push(Frame::Enter(sel));
+                            for &(_, value) in cases.iter() {
+                                frames.push(Frame::Enter(value));
+                            }
+                            frames.push(Frame::Enter(default));
+                            None
+                        }
+
+                        internal_signal::SignalData::NamedWire { source, .. } => {
+                            frames.push(Frame::Leave(signal));
+                            frames.push(Frame::Enter(source));
+                            None
+                        }
+
+                        internal_signal::SignalData::Reinterpret { source, .. } => {
+                            frames.push(Frame::Enter(source));
+                            None
+                        }
+
                         internal_signal::SignalData::MemReadPortOutput {
                             mem,
                             address,
@@ -182,6 +243,11 @@ impl<'graph, 'context, 'expr_arena> Compiler<'graph, 'context, 'expr_arena> {
                                 }),
                             ))
                         }
+                        internal_signal::SignalData::MemCombReadPortOutput { address, .. } => {
+                            frames.push(Frame::Leave(signal));
+                            frames.push(Frame::Enter(address));
+                            None
+                        }
                     }
                 }
                 Frame::Leave(signal) => {
@@ -197,15 +263,39 @@ impl<'graph, 'context, 'expr_arena> Compiler<'graph, 'context, 'expr_arena> {
 
                         internal_signal::SignalData::UnOp { op, bit_width, .. } => {
                             let expr = results.pop().unwrap();
-                            let expr = self.expr_arena.alloc(Expr::UnOp {
-                                source: expr,
-                                op: match op {
-                                    internal_signal::UnOp::Not => UnOp::Not,
-                                },
-                            });
-
                             let target_type = ValueType::from_bit_width(bit_width);
-                            Some((key, self.gen_mask(expr, bit_width, target_type)))
+                            let name = match op {
+                                internal_signal::UnOp::Not => None,
+                                internal_signal::UnOp::ReverseBits => Some("reverse_bits"),
+                                internal_signal::UnOp::SwapBytes => Some("swap_bytes"),
+                            };
+                            Some((
+                                key,
+                                match name {
+                                    None => {
+                                        let expr = self.expr_arena.alloc(Expr::UnOp {
+                                            source: expr,
+                                            op: UnOp::Not,
+                                        });
+                                        self.gen_mask(expr, bit_width, target_type)
+                                    }
+                                    // Reordering a single bit is a no-op
+                                    Some(_) if target_type == ValueType::Bool => expr,
+                                    Some(name) => {
+                                        // Reordering within the full native type moves our bits to
+                                        //  the top, so we shift them back down, which also clears
+                                        //  any bits above our width
+                                        let expr = self.expr_arena.alloc(Expr::NullaryMemberCall {
+                                            target: expr,
+                                            name: name.to_string(),
+                                        });
+                                        self.gen_shift_right(
+                                            expr,
+                                            target_type.bit_width() - bit_width,
+                                        )
+                                    }
+                                },
+                            ))
                         }
                         internal_signal::SignalData::SimpleBinOp { op, .. } => {
                             let lhs = results.pop().unwrap();
@@ -223,6 +313,22 @@ impl<'graph, 'context, 'expr_arena> Compiler<'graph, 'context, 'expr_arena> {
                                 }),
                             ))
                         }
+                        internal_signal::SignalData::AdditiveBinOp { lhs, .. }
+                            if lhs.bit_width() == 1 =>
+                        {
+                            // 1-bit addition and subtraction are both equivalent to xor, so we can
+                            //  stay in bool instead of round-tripping through u32
+                            let lhs = results.pop().unwrap();
+                            let rhs = results.pop().unwrap();
+                            Some((
+                                key,
+                                &*self.expr_arena.alloc(Expr::InfixBinOp {
+                                    lhs,
+                                    rhs,
+                                    op: InfixBinOp::BitXor,
+                                }),
+                            ))
+                        }
                         internal_signal::SignalData::AdditiveBinOp { lhs, op, .. } => {
                             let source_bit_width = lhs.bit_width();
                             let source_type = ValueType::from_bit_width(source_bit_width);
@@ -302,6 +408,132 @@ impl<'graph, 'context, 'expr_arena> Compiler<'graph, 'context, 'expr_arena> {
                                 }),
                             ))
                         }
+                        internal_signal::SignalData::ShiftBinOp { lhs, rhs, op, .. }
+                            if lhs.bit_width() == 1 =>
+                        {
+                            // Shifting a 1-bit value logically by any non-zero amount always
+                            //  results in 0, and shifting it arithmetically always results in the
+                            //  original value (its sign bit), so we can stay in bool here as well
+                            let rhs_type = ValueType::from_bit_width(rhs.bit_width());
+                            let lhs = results.pop().unwrap();
+                            let rhs = results.pop().unwrap();
+                            match op {
+                                internal_signal::ShiftBinOp::Shl
+                                | internal_signal::ShiftBinOp::Shr => {
+                                    let rhs_is_zero = match rhs_type {
+                                        ValueType::Bool => &*self.expr_arena.alloc(Expr::UnOp {
+                                            source: rhs,
+                                            op: UnOp::Not,
+                                        }),
+                                        _ => self.expr_arena.alloc(Expr::InfixBinOp {
+                                            lhs: rhs,
+                                            rhs: self.expr_arena.alloc(Expr::Constant {
+                                                value: match rhs_type {
+                                                    ValueType::Bool
+                                                    | ValueType::I32
+                                                    | ValueType::I64
+                                                    | ValueType::I128 => unreachable!(),
+                                                    ValueType::U32 => Constant::U32(0),
+                                                    ValueType::U64 => Constant::U64(0),
+                                                    ValueType::U128 => Constant::U128(0),
+                                                },
+                                            }),
+                                            op: InfixBinOp::Equal,
+                                        }),
+                                    };
+                                    Some((
+                                        key,
+                                        &*self.expr_arena.alloc(Expr::InfixBinOp {
+                                            lhs,
+                                            rhs: rhs_is_zero,
+                                            op: InfixBinOp::BitAnd,
+                                        }),
+                                    ))
+                                }
+                                internal_signal::ShiftBinOp::ShrArithmetic
+                                | internal_signal::ShiftBinOp::RotateLeft
+                                | internal_signal::ShiftBinOp::RotateRight => Some((key, lhs)),
+                            }
+                        }
+                        internal_signal::SignalData::ShiftBinOp {
+                            rhs,
+                            op:
+                                op @ (internal_signal::ShiftBinOp::RotateLeft
+                                | internal_signal::ShiftBinOp::RotateRight),
+                            bit_width,
+                            ..
+                        } => {
+                            let lhs_type = ValueType::from_bit_width(bit_width);
+                            let rhs_source_type = ValueType::from_bit_width(rhs.bit_width());
+                            let lhs = results.pop().unwrap();
+                            let rhs = results.pop().unwrap();
+                            let rhs_op_input_type = match rhs_source_type {
+                                ValueType::Bool => ValueType::U32,
+                                _ => rhs_source_type,
+                            };
+                            let rhs = self.gen_cast(rhs, rhs_source_type, rhs_op_input_type);
+                            let amount = self.expr_arena.alloc(Expr::UnaryMemberCall {
+                                target: rhs,
+                                name: "wrapping_rem".into(),
+                                arg: self.expr_arena.alloc(Expr::Constant {
+                                    value: match rhs_op_input_type {
+                                        ValueType::Bool
+                                        | ValueType::I32
+                                        | ValueType::I64
+                                        | ValueType::I128 => unreachable!(),
+                                        ValueType::U32 => Constant::U32(bit_width),
+                                        ValueType::U64 => Constant::U64(bit_width as _),
+                                        ValueType::U128 => Constant::U128(bit_width as _),
+                                    },
+                                }),
+                            });
+                            let amount = self.gen_cast(amount, rhs_op_input_type, ValueType::U32);
+                            if bit_width == lhs_type.bit_width() {
+                                Some((
+                                    key,
+                                    &*self.expr_arena.alloc(Expr::UnaryMemberCall {
+                                        target: lhs,
+                                        name: match op {
+                                            internal_signal::ShiftBinOp::RotateLeft => {
+                                                "rotate_left".into()
+                                            }
+                                            _ => "rotate_right".into(),
+                                        },
+                                        arg: amount,
+                                    }),
+                                ))
+                            } else {
+                                // Narrower values are rotated by combining two shifts; since the amount is
+                                //  less than the bit width, neither shift can overflow
+                                let lhs = a.gen_temp(lhs);
+                                let amount = a.gen_temp(amount);
+                                let complement = &*self.expr_arena.alloc(Expr::UnaryMemberCall {
+                                    target: self.expr_arena.alloc(Expr::Constant {
+                                        value: Constant::U32(bit_width),
+                                    }),
+                                    name: "wrapping_sub".into(),
+                                    arg: amount,
+                                });
+                                let (shl_amount, shr_amount) = match op {
+                                    internal_signal::ShiftBinOp::RotateLeft => (amount, complement),
+                                    _ => (complement, amount),
+                                };
+                                let expr = self.expr_arena.alloc(Expr::InfixBinOp {
+                                    lhs: self.expr_arena.alloc(Expr::InfixBinOp {
+                                        lhs,
+                                        rhs: shl_amount,
+                                        op: InfixBinOp::Shl,
+                                    }),
+                                    rhs: self.expr_arena.alloc(Expr::InfixBinOp {
+                                        lhs,
+                                        rhs: shr_amount,
+                                        op: InfixBinOp::Shr,
+                                    }),
+                                    op: InfixBinOp::BitOr,
+                                });
+                                Some((key, self.gen_mask(expr, bit_width, lhs_type)))
+                            }
+                        }
                         internal_signal::SignalData::ShiftBinOp {
                             lhs,
                             rhs,
@@ -322,6 +554,8 @@ impl<'graph, 'context, 'expr_arena> Compiler<'graph, 'context, 'expr_arena> {
                             let lhs = match op {
                                 internal_signal::ShiftBinOp::Shl
                                 | internal_signal::ShiftBinOp::Shr => lhs,
+                                internal_signal::ShiftBinOp::RotateLeft
+                                | internal_signal::ShiftBinOp::RotateRight => unreachable!(),
                                 internal_signal::ShiftBinOp::ShrArithmetic => {
                                     let lhs_op_input_type_signed = lhs_op_input_type.to_signed();
                                     let lhs = self.gen_cast(
@@ -342,7 +576,7 @@ impl<'graph, 'context, 'expr_arena> Compiler<'graph, 'context, 'expr_arena> {
                             };
                             let rhs = self.gen_cast(rhs, rhs_source_type, rhs_op_input_type);
                             let rhs = self.expr_arena.alloc(Expr::BinaryFunctionCall {
-                                name: "std::cmp::min".into(),
+                                name: "core::cmp::min".into(),
                                 lhs: rhs,
                                 rhs: self.expr_arena.alloc(Expr::Constant {
                                     value: match rhs_op_input_type {
@@ -365,6 +599,8 @@ impl<'graph, 'context, 'expr_arena> Compiler<'graph, 'context, 'expr_arena> {
                                     | internal_signal::ShiftBinOp::ShrArithmetic => {
                                         "checked_shr".into()
                                     }
+                                    internal_signal::ShiftBinOp::RotateLeft
+                                    | internal_signal::ShiftBinOp::RotateRight => unreachable!(),
                                 },
                                 arg: rhs,
                             });
@@ -397,6 +633,8 @@ impl<'graph, 'context, 'expr_arena> Compiler<'graph, 'context, 'expr_arena> {
                                             op: InfixBinOp::Shr,
                                         })
                                     }
+                                    internal_signal::ShiftBinOp::RotateLeft
+                                    | internal_signal::ShiftBinOp::RotateRight => unreachable!(),
                                 },
                             });
                             let op_output_type = lhs_op_input_type;
@@ -407,6 +645,8 @@ impl<'graph, 'context, 'expr_arena> Compiler<'graph, 'context, 'expr_arena> {
                                     let lhs_op_output_type_signed = op_output_type.to_signed();
                                     self.gen_cast(expr, lhs_op_output_type_signed, op_output_type)
                                 }
+                                internal_signal::ShiftBinOp::RotateLeft
+                                | internal_signal::ShiftBinOp::RotateRight => unreachable!(),
                             };
                             let target_bit_width = bit_width;
                             let target_type = ValueType::from_bit_width(target_bit_width);
@@ -545,7 +785,65 @@ impl<'graph, 'context, 'expr_arena> Compiler<'graph, 'context, 'expr_arena> {
                             ))
                         }
 
-                        internal_signal::SignalData::MemReadPortOutput { .. } => unreachable!(),
+                        internal_signal::SignalData::Case { sel, ref cases, .. } => {
+                            // The selector is compared against each case's constant, so it's only evaluated once
+                            let sel_bit_width = sel.bit_width();
+                            let sel = a.gen_temp(results.pop().unwrap());
+                            let values = cases
+                                .iter()
+                                .map(|&(value, _)| (value, results.pop().unwrap()))
+                                .collect::<Vec<_>>();
+                            let default = results.pop().unwrap();
+                            // Lowered to a chain of conditionals, tested in the same order as the cases
+                            let expr = values.into_iter().rev().fold(
+                                default,
+                                |when_false, (value, when_true)| {
+                                    &*self.expr_arena.alloc(Expr::Ternary {
+                                        cond: self.expr_arena.alloc(Expr::InfixBinOp {
+                                            lhs: sel,
+                                            rhs: Expr::from_constant(
+                                                &graph::Constant::U128(value),
+                                                sel_bit_width,
+                                                self.expr_arena,
+                                            ),
+                                            op: InfixBinOp::Equal,
+                                        }),
+                                        when_true,
+                                        when_false,
+                                    })
+                                },
+                            );
+                            Some((key, expr))
+                        }
+
+                        internal_signal::SignalData::NamedWire { ref name, .. } => {
+                            let source = results.pop().unwrap();
+                            let name = self.named_wire_name(&sanitize(&format!(
+                                "__wire_{}_{}",
+                                signal.module_instance_name_prefix(),
+                                name
+                            )));
+                            Some((key, a.gen_named_temp(source, name)))
+                        }
+
+                        internal_signal::SignalData::MemCombReadPortOutput { mem, .. } => {
+                            // The address is indexed with an `as usize` cast, so it needs to be a simple expression
+                            let address = a.gen_temp(results.pop().unwrap());
+                            let mem = &self.state_elements.mems[&mem];
+                            Some((
+                                key,
+                                &*self.expr_arena.alloc(Expr::ArrayIndex {
+                                    target: self.expr_arena.alloc(Expr::Ref {
+                                        name: mem.mem_name.clone(),
+                                        scope: Scope::Member,
+                                    }),
+                                    index: address,
+                                }),
+                            ))
+                        }
+
+                        internal_signal::SignalData::MemReadPortOutput { .. }
+                        | internal_signal::SignalData::Reinterpret { .. } => unreachable!(),
                     }
                 }
             } {
@@ -567,6 +865,7 @@ impl<'graph, 'context, 'expr_arena> Compiler<'graph, 'context, 'expr_arena> {
         bit_width: u32,
         target_type: ValueType,
     ) -> &'expr_arena Expr<'expr_arena> {
+        // Note that this also covers bool values, which never need masking
         if bit_width == target_type.bit_width() {
             return expr;
         }
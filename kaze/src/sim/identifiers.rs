@@ -0,0 +1,63 @@
+use crate::identifiers::*;
+
+/// Strict and reserved Rust keywords (as of the 2024 edition), as well as `_`, sorted so they can be binary searched.
+const KEYWORDS: &[&str] = &[
+    "Self", "_", "abstract", "as", "async", "await", "become", "box", "break", "const", "continue",
+    "crate", "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if",
+    "impl", "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub",
+    "ref", "return", "self", "static", "struct", "super", "trait", "true", "try", "type", "typeof",
+    "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// Keywords that can't be used as raw identifiers.
+const NON_RAW_KEYWORDS: &[&str] = &["Self", "_", "crate", "self", "super"];
+
+/// Returns `true` if `name` is a strict or reserved Rust keyword, or `_`.
+pub(super) fn is_keyword(name: &str) -> bool {
+    KEYWORDS.binary_search(&name).is_ok()
+}
+
+/// Returns the Rust identifier that's used for `name` in generated code.
+///
+/// Characters that can't appear in an identifier are replaced with `_`. Keywords are emitted as raw identifiers (eg. `match` is emitted as `r#match`), except for keywords that can't be raw identifiers, which have `_` appended instead (eg. `self` is emitted as `self_`).
+pub(super) fn identifier(name: &str) -> String {
+    let name = sanitize(name);
+    if !is_keyword(&name) {
+        name
+    } else if NON_RAW_KEYWORDS.contains(&name.as_str()) {
+        format!("{}_", name)
+    } else {
+        format!("r#{}", name)
+    }
+}
+
+/// Returns `identifier` without the `r#` prefix if it's a raw identifier, so that it can be used as part of another identifier.
+pub(super) fn unraw(identifier: &str) -> &str {
+    identifier.strip_prefix("r#").unwrap_or(identifier)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keywords_sorted() {
+        let mut sorted = KEYWORDS.to_vec();
+        sorted.sort_unstable();
+        assert_eq!(KEYWORDS, sorted.as_slice());
+    }
+
+    #[test]
+    fn identifiers() {
+        assert_eq!(identifier("a"), "a");
+        assert_eq!(identifier("match"), "r#match");
+        assert_eq!(identifier("self"), "self_");
+        assert_eq!(identifier("_"), "__");
+        assert_eq!(identifier("fifo<8>"), "fifo_8_");
+        assert_eq!(identifier("a b"), "a_b");
+        assert_eq!(unraw("r#match"), "match");
+        assert_eq!(unraw("a"), "a");
+        assert!(is_keyword("match"));
+        assert!(!is_keyword("reset"));
+    }
+}
@@ -3,6 +3,7 @@ use crate::graph;
 
 use typed_arena::Arena;
 
+use std::collections::HashMap;
 use std::io::{Result, Write};
 
 pub struct AssignmentContext<'arena> {
@@ -28,22 +29,30 @@ impl<'arena> AssignmentContext<'arena> {
                 let name = format!("__temp_{}", self.local_count);
                 self.local_count += 1;
 
-                self.assignments.push(Assignment {
-                    target: self.arena.alloc(Expr::Ref {
-                        name: name.clone(),
-                        scope: Scope::Local,
-                    }),
-                    expr,
-                });
-
-                self.arena.alloc(Expr::Ref {
-                    name,
-                    scope: Scope::Local,
-                })
+                self.gen_named_temp(expr, name)
             }
         }
     }
 
+    pub fn gen_named_temp(
+        &mut self,
+        expr: &'arena Expr<'arena>,
+        name: String,
+    ) -> &'arena Expr<'arena> {
+        self.assignments.push(Assignment {
+            target: self.arena.alloc(Expr::Ref {
+                name: name.clone(),
+                scope: Scope::Local,
+            }),
+            expr,
+        });
+
+        self.arena.alloc(Expr::Ref {
+            name,
+            scope: Scope::Local,
+        })
+    }
+
     pub fn is_empty(&self) -> bool {
         self.assignments.is_empty()
     }
@@ -69,6 +78,21 @@ pub struct Assignment<'arena> {
 impl<'arena> Assignment<'arena> {
     pub fn write<W: Write>(&self, w: &mut code_writer::CodeWriter<W>) -> Result<()> {
         w.append_indent()?;
+        if let Expr::PackedBool { word, bit } = *self.target {
+            if let Expr::Constant {
+                value: Constant::Bool(value),
+            } = *self.expr
+            {
+                w.append(&write_packed_bool_constant(word, bit, value))?;
+            } else {
+                let (prefix, suffix) = write_packed_bool(word, bit);
+                w.append(&prefix)?;
+                self.expr.write(w)?;
+                w.append(&suffix)?;
+            }
+            w.append_newline()?;
+            return Ok(());
+        }
         // TODO: I hate these kind of conditionals...
         if let Expr::Ref { ref scope, .. } = self.target {
             match scope {
@@ -79,7 +103,8 @@ impl<'arena> Assignment<'arena> {
             }
         }
         self.target.write(w)?;
-        w.append(" = ")?;
+        w.append(" =")?;
+        w.append_break()?;
         self.expr.write(w)?;
         w.append(";")?;
         w.append_newline()?;
@@ -110,6 +135,15 @@ pub enum Expr<'arena> {
         rhs: &'arena Expr<'arena>,
         op: InfixBinOp,
     },
+    NullaryMemberCall {
+        target: &'arena Expr<'arena>,
+        name: String,
+    },
+    /// A 1-bit member field that's packed into bit `bit` of word `word` of the `__packed_bools` field.
+    PackedBool {
+        word: u32,
+        bit: u32,
+    },
     Ref {
         name: String,
         scope: Scope,
@@ -154,6 +188,23 @@ impl<'arena> Expr<'arena> {
         enum Command<'arena> {
             Expr { expr: &'arena Expr<'arena> },
             Str { s: &'arena str },
+            Break,
+            SoftBreak,
+        }
+
+        // Prefix operators and `if` expressions bind more loosely than method calls, so they must be parenthesized when used as receivers
+        fn push_receiver<'arena>(
+            commands: &mut Vec<Command<'arena>>,
+            target: &'arena Expr<'arena>,
+        ) {
+            match *target {
+                Expr::Ternary { .. } | Expr::UnOp { .. } => {
+                    commands.push(Command::Str { s: ")" });
+                    commands.push(Command::Expr { expr: target });
+                    commands.push(Command::Str { s: "(" });
+                }
+                _ => commands.push(Command::Expr { expr: target }),
+            }
         }
 
         let mut commands = Vec::new();
@@ -178,8 +229,10 @@ impl<'arena> Expr<'arena> {
                     } => {
                         commands.push(Command::Str { s: ")" });
                         commands.push(Command::Expr { expr: rhs });
-                        commands.push(Command::Str { s: ", " });
+                        commands.push(Command::Break);
+                        commands.push(Command::Str { s: "," });
                         commands.push(Command::Expr { expr: lhs });
+                        commands.push(Command::SoftBreak);
                         w.append(&format!("{}(", name))?;
                     }
                     Expr::Cast {
@@ -209,7 +262,7 @@ impl<'arena> Expr<'arena> {
                     } => {
                         commands.push(Command::Str { s: ")" });
                         commands.push(Command::Expr { expr: rhs });
-                        commands.push(Command::Str { s: " " });
+                        commands.push(Command::Break);
                         commands.push(Command::Str {
                             s: match op {
                                 InfixBinOp::BitAnd => "&",
@@ -230,6 +283,15 @@ impl<'arena> Expr<'arena> {
                         commands.push(Command::Expr { expr: lhs });
                         w.append("(")?;
                     }
+                    Expr::NullaryMemberCall { target, ref name } => {
+                        commands.push(Command::Str { s: "()" });
+                        commands.push(Command::Str { s: name });
+                        commands.push(Command::Str { s: "." });
+                        push_receiver(&mut commands, target);
+                    }
+                    Expr::PackedBool { word, bit } => {
+                        w.append(&read_packed_bool(word, bit))?;
+                    }
                     Expr::Ref { ref name, scope } => {
                         if let Scope::Member = scope {
                             w.append("self.")?;
@@ -242,10 +304,14 @@ impl<'arena> Expr<'arena> {
                         ref when_false,
                     } => {
                         commands.push(Command::Str { s: "}" });
+                        commands.push(Command::Break);
                         commands.push(Command::Expr { expr: when_false });
-                        commands.push(Command::Str { s: " } else { " });
+                        commands.push(Command::Break);
+                        commands.push(Command::Str { s: "} else {" });
+                        commands.push(Command::Break);
                         commands.push(Command::Expr { expr: when_true });
-                        commands.push(Command::Str { s: " { " });
+                        commands.push(Command::Break);
+                        commands.push(Command::Str { s: " {" });
                         commands.push(Command::Expr { expr: cond });
                         w.append("if ")?;
                     }
@@ -256,10 +322,11 @@ impl<'arena> Expr<'arena> {
                     } => {
                         commands.push(Command::Str { s: ")" });
                         commands.push(Command::Expr { expr: arg });
+                        commands.push(Command::SoftBreak);
                         commands.push(Command::Str { s: "(" });
                         commands.push(Command::Str { s: name });
                         commands.push(Command::Str { s: "." });
-                        commands.push(Command::Expr { expr: target });
+                        push_receiver(&mut commands, target);
                     }
                     Expr::UnOp { ref source, op } => {
                         w.append(match op {
@@ -271,6 +338,12 @@ impl<'arena> Expr<'arena> {
                 Command::Str { s } => {
                     w.append(s)?;
                 }
+                Command::Break => {
+                    w.append_break()?;
+                }
+                Command::SoftBreak => {
+                    w.append_soft_break()?;
+                }
             }
         }
 
@@ -278,6 +351,118 @@ impl<'arena> Expr<'arena> {
     }
 }
 
+fn packed_bool_word(word: u32) -> String {
+    format!("self.__packed_bools[{}]", word)
+}
+
+/// Returns the mask for bit `bit` of a packed bool word. Shifts by 0 are omitted, so generated code doesn't contain operations that have no effect.
+fn packed_bool_mask(bit: u32) -> String {
+    if bit == 0 {
+        "1".into()
+    } else {
+        format!("(1u64 << {})", bit)
+    }
+}
+
+/// Returns an expression that reads bit `bit` of packed bool word `word`.
+fn read_packed_bool(word: u32, bit: u32) -> String {
+    if bit == 0 {
+        format!("({} & 1 != 0)", packed_bool_word(word))
+    } else {
+        format!("(({} >> {}) & 1 != 0)", packed_bool_word(word), bit)
+    }
+}
+
+/// Returns the code that goes before and after a `bool` expression in a statement that assigns it to bit `bit` of packed bool word `word`.
+///
+/// Only the target bit is replaced; the other bits in the word belong to other fields.
+fn write_packed_bool(word: u32, bit: u32) -> (String, String) {
+    let word = packed_bool_word(word);
+    let shift = if bit == 0 {
+        String::new()
+    } else {
+        format!(" << {}", bit)
+    };
+    (
+        format!("{} = ({} & !{}) | (((", word, word, packed_bool_mask(bit)),
+        format!(") as u64){});", shift),
+    )
+}
+
+/// Returns a statement that sets (if `value` is `true`) or clears bit `bit` of packed bool word `word`.
+fn write_packed_bool_constant(word: u32, bit: u32, value: bool) -> String {
+    if value {
+        format!("{} |= {};", packed_bool_word(word), packed_bool_mask(bit))
+    } else {
+        format!("{} &= !{};", packed_bool_word(word), packed_bool_mask(bit))
+    }
+}
+
+/// The 1-bit member fields that are packed into the `__packed_bools: [u64; N]` field of a generated struct, rather than being stored as separate `bool` fields.
+pub struct PackedBools {
+    bits: HashMap<String, u32>,
+}
+
+impl PackedBools {
+    pub fn new() -> PackedBools {
+        PackedBools {
+            bits: HashMap::new(),
+        }
+    }
+
+    /// Packs the member field called `name` into the next unused bit.
+    pub fn add(&mut self, name: &str) {
+        let index = self.bits.len() as u32;
+        self.bits.insert(name.into(), index);
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.bits.contains_key(name)
+    }
+
+    /// Returns the number of `u64` words needed to hold all packed fields.
+    pub fn num_words(&self) -> u32 {
+        (self.bits.len() as u32).div_ceil(64)
+    }
+
+    /// Returns an expression referring to the member field called `name`, whether it's packed or not.
+    pub fn member<'arena>(
+        &self,
+        name: &str,
+        arena: &'arena Arena<Expr<'arena>>,
+    ) -> &'arena Expr<'arena> {
+        arena.alloc(match self.bits.get(name) {
+            Some(&index) => Expr::PackedBool {
+                word: index / 64,
+                bit: index % 64,
+            },
+            None => Expr::Ref {
+                name: name.into(),
+                scope: Scope::Member,
+            },
+        })
+    }
+
+    /// Returns Rust code that reads the member field called `name`, for use in code that's written directly rather than built from [`Expr`]s.
+    pub fn read(&self, name: &str) -> String {
+        match self.bits.get(name) {
+            Some(&index) => read_packed_bool(index / 64, index % 64),
+            None => format!("self.{}", name),
+        }
+    }
+
+    /// Returns a Rust statement that assigns `value` to the member field called `name`, for use in code that's written directly rather than built from [`Expr`]s.
+    pub fn write(&self, name: &str, value: &str) -> String {
+        match self.bits.get(name) {
+            Some(&index) => {
+                let (prefix, suffix) = write_packed_bool(index / 64, index % 64);
+                format!("{}{}{}", prefix, value, suffix)
+            }
+            None => format!("self.{} = {};", name, value),
+        }
+    }
+}
+
 pub enum Constant {
     Bool(bool),
     U32(u32),
@@ -1,5 +1,7 @@
 use crate::graph;
 use crate::graph::internal_signal;
+use crate::identifiers::*;
+use crate::optimizer::*;
 
 use std::collections::HashMap;
 
@@ -24,6 +26,29 @@ pub(super) struct Mem<'a> {
     pub write_enable_name: String,
 }
 
+impl<'a> Mem<'a> {
+    /// Returns this mem's read port names, sorted by value name.
+    ///
+    /// `read_signal_names` is keyed by pointers, so its iteration order isn't stable between runs, and must not leak into generated code.
+    pub fn sorted_read_signal_names(
+        &self,
+    ) -> Vec<(
+        (
+            &'a internal_signal::InternalSignal<'a>,
+            &'a internal_signal::InternalSignal<'a>,
+        ),
+        &ReadSignalNames,
+    )> {
+        let mut ret = self
+            .read_signal_names
+            .iter()
+            .map(|(&key, read_signal_names)| (key, read_signal_names))
+            .collect::<Vec<_>>();
+        ret.sort_by(|(_, a), (_, b)| a.value_name.cmp(&b.value_name));
+        ret
+    }
+}
+
 pub struct ReadSignalNames {
     pub address_name: String,
     pub enable_name: String,
@@ -38,6 +63,15 @@ pub(super) enum IncludedPorts {
     ReachableFromTopLevelOutputs,
 }
 
+/// Whether instances of child modules are flattened into their parent, or kept as opaque instances.
+///
+/// When hierarchy is preserved, only the state elements that belong to the module itself are collected; outputs of child instances are treated as leaves, and the signals driving their inputs are visited as roots instead.
+#[derive(Clone, Copy)]
+pub(super) enum Hierarchy {
+    Flattened,
+    Preserved,
+}
+
 pub(super) struct StateElements<'a> {
     pub mems: HashMap<&'a graph::Mem<'a>, Mem<'a>>,
     pub regs: HashMap<&'a internal_signal::InternalSignal<'a>, Register<'a>>,
@@ -48,6 +82,8 @@ impl<'a> StateElements<'a> {
         m: &'a graph::Module<'a>,
         // TODO: Cover registers as well
         included_ports: IncludedPorts,
+        hierarchy: Hierarchy,
+        optimizer: &Optimizer<'a>,
         signal_reference_counts: &mut HashMap<&'a internal_signal::InternalSignal<'a>, u32>,
     ) -> StateElements<'a> {
         let mut mems = HashMap::new();
@@ -56,6 +92,8 @@ impl<'a> StateElements<'a> {
         visit_module(
             m,
             included_ports,
+            hierarchy,
+            optimizer,
             &mut mems,
             &mut regs,
             signal_reference_counts,
@@ -63,11 +101,31 @@ impl<'a> StateElements<'a> {
 
         StateElements { mems, regs }
     }
+
+    /// Returns all mems, sorted by mem name.
+    ///
+    /// `mems` is keyed by pointers, so its iteration order isn't stable between runs, and must not leak into generated code.
+    pub fn sorted_mems(&self) -> Vec<&Mem<'a>> {
+        let mut ret = self.mems.values().collect::<Vec<_>>();
+        ret.sort_by(|a, b| a.mem_name.cmp(&b.mem_name));
+        ret
+    }
+
+    /// Returns all registers, sorted by value name.
+    ///
+    /// `regs` is keyed by pointers, so its iteration order isn't stable between runs, and must not leak into generated code.
+    pub fn sorted_regs(&self) -> Vec<&Register<'a>> {
+        let mut ret = self.regs.values().collect::<Vec<_>>();
+        ret.sort_by(|a, b| a.value_name.cmp(&b.value_name));
+        ret
+    }
 }
 
 fn visit_module<'a>(
     m: &'a graph::Module<'a>,
     included_ports: IncludedPorts,
+    hierarchy: Hierarchy,
+    optimizer: &Optimizer<'a>,
     mems: &mut HashMap<&'a graph::Mem<'a>, Mem<'a>>,
     regs: &mut HashMap<&'a internal_signal::InternalSignal<'a>, Register<'a>>,
     signal_reference_counts: &mut HashMap<&'a internal_signal::InternalSignal<'a>, u32>,
@@ -77,55 +135,165 @@ fn visit_module<'a>(
         // TODO: Test
         IncludedPorts::All => {
             for (_, &input) in m.inputs.borrow().iter() {
-                visit_signal(input.value, mems, regs, signal_reference_counts);
+                visit_signal(
+                    input.value,
+                    hierarchy,
+                    optimizer,
+                    mems,
+                    regs,
+                    signal_reference_counts,
+                );
             }
             for (_, &output) in m.outputs.borrow().iter() {
-                visit_signal(output.data.source, mems, regs, signal_reference_counts);
+                visit_signal(
+                    optimizer.optimize_output(output.data),
+                    hierarchy,
+                    optimizer,
+                    mems,
+                    regs,
+                    signal_reference_counts,
+                );
             }
-            for &register in m.registers.borrow().iter() {
-                match register.data {
-                    internal_signal::SignalData::Reg { ref data } => {
-                        visit_signal(
-                            data.next.borrow().unwrap(),
-                            mems,
-                            regs,
-                            signal_reference_counts,
-                        );
-                    }
-                    _ => unreachable!(),
-                }
+            for register in m.registers.borrow().iter() {
+                visit_signal(
+                    optimizer.optimize(register.data.next.borrow().unwrap()),
+                    hierarchy,
+                    optimizer,
+                    mems,
+                    regs,
+                    signal_reference_counts,
+                );
             }
-            for &module in m.modules.borrow().iter() {
-                visit_module(module, included_ports, mems, regs, signal_reference_counts);
+            for (_, &named_wire) in m.named_wires.borrow().iter() {
+                visit_signal(
+                    optimizer.optimize(named_wire),
+                    hierarchy,
+                    optimizer,
+                    mems,
+                    regs,
+                    signal_reference_counts,
+                );
+            }
+            for &cond in m
+                .assertions
+                .borrow()
+                .values()
+                .map(|assertion| &assertion.cond)
+                .chain(m.cover_points.borrow().values())
+            {
+                visit_signal(
+                    optimizer.optimize(cond),
+                    hierarchy,
+                    optimizer,
+                    mems,
+                    regs,
+                    signal_reference_counts,
+                );
+            }
+            if let Hierarchy::Flattened = hierarchy {
+                for &module in m.modules.borrow().iter() {
+                    visit_module(
+                        module,
+                        included_ports,
+                        hierarchy,
+                        optimizer,
+                        mems,
+                        regs,
+                        signal_reference_counts,
+                    );
+                }
             }
             // TODO: Cover all mems as well
         }
         IncludedPorts::ReachableFromTopLevelOutputs => {
             for (_, &output) in m.outputs.borrow().iter() {
-                visit_signal(output.data.source, mems, regs, signal_reference_counts);
+                visit_signal(
+                    optimizer.optimize_output(output.data),
+                    hierarchy,
+                    optimizer,
+                    mems,
+                    regs,
+                    signal_reference_counts,
+                );
+            }
+            // Assertions and coverage points aren't reachable from outputs, but generated code still has to evaluate them
+            let mut conds = Vec::new();
+            collect_verification_conds(m, hierarchy, &mut conds);
+            for cond in conds {
+                visit_signal(
+                    optimizer.optimize(cond),
+                    hierarchy,
+                    optimizer,
+                    mems,
+                    regs,
+                    signal_reference_counts,
+                );
+            }
+        }
+    }
+
+    if let Hierarchy::Preserved = hierarchy {
+        for &module in m.modules.borrow().iter() {
+            for (_, &input) in module.inputs.borrow().iter() {
+                visit_signal(
+                    optimizer.optimize(input.data.driven_value.borrow().unwrap()),
+                    hierarchy,
+                    optimizer,
+                    mems,
+                    regs,
+                    signal_reference_counts,
+                );
             }
         }
     }
 }
 
+/// Collects the conditions of the assertions and coverage points in `m` into `conds`, as well as those of its descendants if `hierarchy` is flattened.
+fn collect_verification_conds<'a>(
+    m: &'a graph::Module<'a>,
+    hierarchy: Hierarchy,
+    conds: &mut Vec<&'a internal_signal::InternalSignal<'a>>,
+) {
+    conds.extend(
+        m.assertions
+            .borrow()
+            .values()
+            .map(|assertion| assertion.cond),
+    );
+    conds.extend(m.cover_points.borrow().values());
+    if let Hierarchy::Flattened = hierarchy {
+        for &module in m.modules.borrow().iter() {
+            collect_verification_conds(module, hierarchy, conds);
+        }
+    }
+}
+
+// TODO: Do we even need this with just the one member?
+struct Frame<'a> {
+    signal: &'a internal_signal::InternalSignal<'a>,
+}
+
 // TODO: Move this to ctor and iterate over input module outputs there?
 fn visit_signal<'a>(
     signal: &'a internal_signal::InternalSignal<'a>,
+    hierarchy: Hierarchy,
+    optimizer: &Optimizer<'a>,
     mems: &mut HashMap<&'a graph::Mem<'a>, Mem<'a>>,
     regs: &mut HashMap<&'a internal_signal::InternalSignal<'a>, Register<'a>>,
     signal_reference_counts: &mut HashMap<&'a internal_signal::InternalSignal<'a>, u32>,
 ) {
-    // TODO: Do we even need this with just the one member?
-    struct Frame<'a> {
-        signal: &'a internal_signal::InternalSignal<'a>,
-    }
-
     let mut frames = Vec::new();
     frames.push(Frame { signal });
 
     while let Some(frame) = frames.pop() {
         let signal = frame.signal;
 
+        // Reinterpretations are transparent in generated code, so each reference counts as a reference to their source
+        if let internal_signal::SignalData::Reinterpret { source, .. } = signal.data {
+            frames.push(Frame { signal: source });
+            continue;
+        }
+
         let reference_count = signal_reference_counts.entry(signal).or_insert(0);
         *reference_count += 1;
 
@@ -137,26 +305,30 @@ fn visit_signal<'a>(
             internal_signal::SignalData::Lit { .. } => (),
 
             internal_signal::SignalData::Input { data } => {
-                if let Some(driven_value) = data.driven_value.borrow().clone() {
-                    frames.push(Frame {
-                        signal: driven_value,
-                    });
+                if let Hierarchy::Flattened = hierarchy {
+                    if let Some(driven_value) = data.driven_value.borrow().clone() {
+                        frames.push(Frame {
+                            signal: optimizer.optimize(driven_value),
+                        });
+                    }
                 }
             }
             internal_signal::SignalData::Output { data } => {
-                frames.push(Frame {
-                    signal: data.source,
-                });
+                if let Hierarchy::Flattened = hierarchy {
+                    frames.push(Frame {
+                        signal: optimizer.optimize_output(data),
+                    });
+                }
             }
 
             internal_signal::SignalData::Reg { data } => {
                 let key = signal;
-                let value_name = format!(
+                let value_name = sanitize(&format!(
                     "__reg_{}_{}_{}",
                     signal.module_instance_name_prefix(),
                     data.name,
                     regs.len()
-                );
+                ));
                 let next_name = format!("{}_next", value_name);
                 regs.insert(
                     key,
@@ -167,8 +339,14 @@ fn visit_signal<'a>(
                     },
                 );
                 frames.push(Frame {
-                    signal: data.next.borrow().unwrap(),
+                    signal: optimizer.optimize(data.next.borrow().unwrap()),
                 });
+                // The divided clock's counter (which the enable pulse that models it in simulation is computed from) is reached through the clock's next value
+                if let Some(divider) = data.divided_clock {
+                    frames.push(Frame {
+                        signal: divider.clock.value,
+                    });
+                }
             }
 
             internal_signal::SignalData::UnOp { source, .. } => {
@@ -207,6 +385,10 @@ fn visit_signal<'a>(
             internal_signal::SignalData::Repeat { source, .. } => {
                 frames.push(Frame { signal: source });
             }
+            internal_signal::SignalData::NamedWire { source, .. } => {
+                frames.push(Frame { signal: source });
+            }
+            internal_signal::SignalData::Reinterpret { .. } => unreachable!(),
             internal_signal::SignalData::Concat { lhs, rhs, .. } => {
                 frames.push(Frame { signal: lhs });
                 frames.push(Frame { signal: rhs });
@@ -223,55 +405,94 @@ fn visit_signal<'a>(
                 frames.push(Frame { signal: when_false });
             }
 
-            internal_signal::SignalData::MemReadPortOutput { mem, .. } => {
-                let key = mem;
-                let mem_name = format!(
-                    "__mem_{}_{}_{}",
-                    signal.module_instance_name_prefix(),
-                    mem.name,
-                    mems.len()
-                );
-                // TODO: It might actually be too conservative to trace all read ports,
-                //  as we only know that the write port and _this_ read port are reachable
-                //  at this point, but we have to keep some extra state to know whether or
-                //  not we've hit each read port otherwise.
-                let mut read_signal_names = HashMap::new();
-                for (index, (address, enable)) in mem.read_ports.borrow().iter().enumerate() {
-                    let name_prefix = format!("{}_read_port_{}_", mem_name, index);
-                    read_signal_names.insert(
-                        (*address, *enable),
-                        ReadSignalNames {
-                            address_name: format!("{}address", name_prefix),
-                            enable_name: format!("{}enable", name_prefix),
-                            value_name: format!("{}value", name_prefix),
-                        },
-                    );
-                }
-                let name_prefix = format!("{}_write_port_", mem_name);
-                let write_address_name = format!("{}address", name_prefix);
-                let write_value_name = format!("{}value", name_prefix);
-                let write_enable_name = format!("{}enable", name_prefix);
-                mems.insert(
-                    key,
-                    Mem {
-                        mem,
-                        mem_name,
-                        write_address_name,
-                        write_value_name,
-                        write_enable_name,
-                        read_signal_names,
-                    },
-                );
-                for (address, enable) in mem.read_ports.borrow().iter() {
-                    frames.push(Frame { signal: address });
-                    frames.push(Frame { signal: enable });
-                }
-                if let Some((address, value, enable)) = *mem.write_port.borrow() {
-                    frames.push(Frame { signal: address });
+            internal_signal::SignalData::Case {
+                sel,
+                ref cases,
+                default,
+                ..
+            } => {
+                frames.push(Frame { signal: sel });
+                for &(_, value) in cases.iter() {
                     frames.push(Frame { signal: value });
-                    frames.push(Frame { signal: enable });
                 }
+                frames.push(Frame { signal: default });
+            }
+
+            internal_signal::SignalData::MemReadPortOutput { mem, .. } => {
+                let state_element = visit_mem(signal, mem, optimizer, mems.len(), &mut frames);
+                mems.insert(mem, state_element);
+            }
+            internal_signal::SignalData::MemCombReadPortOutput { mem, address } => {
+                if !mems.contains_key(&mem) {
+                    let state_element = visit_mem(signal, mem, optimizer, mems.len(), &mut frames);
+                    mems.insert(mem, state_element);
+                }
+                frames.push(Frame { signal: address });
             }
         }
     }
 }
+
+/// Returns the state element for `mem` (reached through one of its read ports, `signal`), which is the `index`th mem found, and pushes the signals driving its synchronous read ports and write port onto `frames`.
+fn visit_mem<'a>(
+    signal: &'a internal_signal::InternalSignal<'a>,
+    mem: &'a graph::Mem<'a>,
+    optimizer: &Optimizer<'a>,
+    index: usize,
+    frames: &mut Vec<Frame<'a>>,
+) -> Mem<'a> {
+    let mem_name = sanitize(&format!(
+        "__mem_{}_{}_{}",
+        signal.module_instance_name_prefix(),
+        mem.name,
+        index
+    ));
+    // TODO: It might actually be too conservative to trace all read ports,
+    //  as we only know that the write port and _this_ read port are reachable
+    //  at this point, but we have to keep some extra state to know whether or
+    //  not we've hit each read port otherwise.
+    let mut read_signal_names = HashMap::new();
+    for (index, (address, enable)) in mem.read_ports.borrow().iter().enumerate() {
+        let name_prefix = format!("{}_read_port_{}_", mem_name, index);
+        read_signal_names.insert(
+            (*address, *enable),
+            ReadSignalNames {
+                address_name: format!("{}address", name_prefix),
+                enable_name: format!("{}enable", name_prefix),
+                value_name: format!("{}value", name_prefix),
+            },
+        );
+    }
+    let name_prefix = format!("{}_write_port_", mem_name);
+    let write_address_name = format!("{}address", name_prefix);
+    let write_value_name = format!("{}value", name_prefix);
+    let write_enable_name = format!("{}enable", name_prefix);
+    for (address, enable) in mem.read_ports.borrow().iter() {
+        frames.push(Frame {
+            signal: optimizer.optimize(address),
+        });
+        frames.push(Frame {
+            signal: optimizer.optimize(enable),
+        });
+    }
+    if let Some((address, value, enable)) = *mem.write_port.borrow() {
+        frames.push(Frame {
+            signal: optimizer.optimize(address),
+        });
+        frames.push(Frame {
+            signal: optimizer.optimize(value),
+        });
+        frames.push(Frame {
+            signal: optimizer.optimize(enable),
+        });
+    }
+
+    Mem {
+        mem,
+        mem_name,
+        write_address_name,
+        write_value_name,
+        write_enable_name,
+        read_signal_names,
+    }
+}
@@ -0,0 +1,47 @@
+//! Fixtures shared by the tests of several modules.
+
+use crate::*;
+
+/// Number of times [`assert_repeatable`] produces its output.
+const NUM_REPETITIONS: usize = 10;
+
+/// Asserts that `produce` returns the same output every time it's called.
+///
+/// `produce` should build its input in a fresh [`Context`] on each call (eg. with [`repeatability_module`]), so that all signals live at new addresses, and any maps it uses are newly created, so that each gets a newly-seeded hasher. Together, these shuffle the iteration order of any pointer-keyed or hashed collection, so any such order that leaks into the output makes it differ between calls.
+pub(crate) fn assert_repeatable(produce: impl Fn() -> String) {
+    let expected = produce();
+    for _ in 0..NUM_REPETITIONS {
+        assert_eq!(produce(), expected);
+    }
+}
+
+/// Builds a module with enough registers, reset domains, memory read ports, and instances to expose any nondeterministic ordering in code generated for it.
+pub(crate) fn repeatability_module<'a>(c: &'a Context<'a>) -> &'a Module<'a> {
+    let m = c.module("m", "M");
+    let soft = m.reset_domain("soft");
+    let mut sum: &dyn Signal = m.lit(0u32, 8);
+    for index in 0..8 {
+        let r = m.reg(format!("r{}", index), 8);
+        r.default_value(index as u32);
+        if index % 2 == 0 {
+            r.reset_domain(soft);
+        }
+        r.drive_next(m.input(format!("i{}", index), 8));
+        sum = sum + r;
+    }
+    let mem = m.mem("mem", 4, 8);
+    mem.initial_contents(&[0x12u32; 16]);
+    mem.write_port(m.input("wa", 4), sum.named("sum"), m.input("we", 1));
+    for index in 0..4 {
+        let value = mem.read_port(m.input(format!("ra{}", index), 4), m.high());
+        m.output(format!("o{}", index), value);
+    }
+    let inner = m.module("inner", "Inner");
+    let inner_r = inner.reg("r", 8);
+    inner_r.default_value(0u32);
+    inner_r.drive_next(inner.input("i", 8));
+    inner.output("o", inner_r);
+    inner.inputs.borrow()["i"].drive(sum);
+    m.output("inner_o", inner.outputs.borrow()["o"]);
+    m
+}
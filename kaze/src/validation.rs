@@ -1,65 +1,468 @@
 use crate::graph;
 use crate::graph::internal_signal;
+use crate::identifiers::is_clock_or_reset_name;
+
+use std::collections::HashSet;
+use std::fmt;
+
+/// An error in a [`Module`](crate::Module) hierarchy that prevents code from being generated for it, returned by [`validate`].
+///
+/// Each error carries the name of the module that contains the offending signal, memory, or instance (which may be any module in the hierarchy), as well as the names needed to locate it in that module. The [`Display`](fmt::Display) implementation describes the error in the same terms as the panic messages of the code generators.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ValidationError {
+    /// A module [declared](crate::Context::declare_module) with instances in the hierarchy, which was never [defined](crate::ModuleDeclaration::define).
+    UndefinedModule { module: String },
+    /// A [deferred register](crate::Module::reg_deferred) whose bit width was never resolved.
+    UnresolvedDeferredRegister { module: String, name: String },
+    /// A [deferred output](crate::Module::output_deferred) which is not driven.
+    UndrivenDeferredOutput { module: String, name: String },
+    /// A register which is not driven.
+    UndrivenRegister { module: String, name: String },
+    /// An input of a module instance called `instance_name` (of module `instance_module`) which is not driven.
+    UndrivenInstanceInput {
+        module: String,
+        instance_name: String,
+        instance_module: String,
+        name: String,
+    },
+    /// A memory which doesn't have any read ports.
+    MemWithoutReadPorts { module: String, name: String },
+    /// A memory which doesn't have initial contents or a write port.
+    MemWithoutInitialContentsOrWritePort { module: String, name: String },
+    /// An output which forms a combinational loop with itself.
+    CombinationalLoop { module: String, name: String },
+    /// A [clock divider](crate::Module::clock_divider) whose divided clock is used as data.
+    DividedClockUsedAsData { module: String, name: String },
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::UndefinedModule { module } => write!(f, "module \"{}\" was declared with Context::declare_module, but never defined with ModuleDeclaration::define.", module),
+            ValidationError::UnresolvedDeferredRegister { module, name } => write!(f, "module \"{}\" contains a deferred register called \"{}\" whose bit width was never resolved.", module, name),
+            ValidationError::UndrivenDeferredOutput { module, name } => write!(f, "module \"{}\" contains a deferred output called \"{}\" which is not driven.", module, name),
+            ValidationError::UndrivenRegister { module, name } => write!(f, "module \"{}\" contains a register called \"{}\" which is not driven.", module, name),
+            ValidationError::UndrivenInstanceInput {
+                module,
+                instance_name,
+                instance_module,
+                name,
+            } => write!(f, "module \"{}\" contains an instance of module \"{}\" called \"{}\" whose input \"{}\" is not driven.", module, instance_module, instance_name, name),
+            ValidationError::MemWithoutReadPorts { module, name } => write!(f, "module \"{}\" contains a memory called \"{}\" which doesn't have any read ports.", module, name),
+            ValidationError::MemWithoutInitialContentsOrWritePort { module, name } => write!(f, "module \"{}\" contains a memory called \"{}\" which doesn't have initial contents or a write port specified. At least one of the two is required.", module, name),
+            ValidationError::CombinationalLoop { module, name } => write!(f, "module \"{}\" contains an output called \"{}\" which forms a combinational loop with itself.", module, name),
+            ValidationError::DividedClockUsedAsData { module, name } => write!(f, "module \"{}\" contains a clock divider called \"{}\" whose divided clock is used as data. Divided clocks can only be used to clock registers created with ClockDivider::reg.", module, name),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Checks `m` and all of its descendants for errors that would prevent code from being generated for `m`, and returns all of them, rather than only the first one.
+///
+/// [`sim::generate`](crate::sim::generate) and [`verilog::generate`](crate::verilog::generate) perform the same checks, and panic with a message describing each error if any are found.
+/// Note that other errors, such as driving a register or an instance input more than once, or creating two outputs with the same name, are detected (and panic) as soon as they're made, so they're never returned by this function.
+///
+/// Errors are returned in a stable order, which depends only on the structure of the hierarchy (and the names in it), and never on where its signals happen to live in memory.
+///
+/// # Examples
+///
+/// ```
+/// use kaze::*;
+///
+/// let c = Context::new();
+///
+/// let m = c.module("m", "M");
+/// let a = m.reg("a", 1);
+/// let b = m.reg("b", 1);
+/// m.output("o", a | b);
+///
+/// assert_eq!(
+///     validate(m),
+///     Err(vec![
+///         ValidationError::UndrivenRegister { module: "M".into(), name: "a".into() },
+///         ValidationError::UndrivenRegister { module: "M".into(), name: "b".into() },
+///     ])
+/// );
+///
+/// a.drive_next(!a);
+/// b.drive_next(!b);
+/// assert_eq!(validate(m), Ok(()));
+/// ```
+pub fn validate<'a>(m: &'a graph::Module<'a>) -> Result<(), Vec<ValidationError>> {
+    let mut errors = Vec::new();
+    detect_undefined_modules(m, &mut errors);
+    detect_unresolved_deferred_signals(m, &mut errors);
+    detect_undriven_registers_and_inputs(m, &mut errors);
+    detect_mem_errors(m, &mut errors);
+    detect_combinational_loops(m, &mut errors);
+    detect_divided_clocks_used_as_data(m, &mut errors);
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
 
 pub fn validate_module_hierarchy<'a>(m: &'a graph::Module<'a>) {
-    detect_undriven_registers_and_inputs(m, m);
-    detect_mem_errors(m, m);
-    detect_combinational_loops(m, m);
+    if let Err(errors) = validate(m) {
+        let messages = errors
+            .iter()
+            .map(|error| {
+                format!(
+                    "Cannot generate code for module \"{}\" because {}",
+                    m.name, error
+                )
+            })
+            .collect::<Vec<_>>();
+        panic!("{}", messages.join("\n"));
+    }
 }
 
-fn detect_undriven_registers_and_inputs<'a>(m: &graph::Module<'a>, root: &graph::Module<'a>) {
+/// Validates user-specified clock and reset names, which must be legal identifiers that aren't Verilog or SystemVerilog keywords, and that are distinct from each other.
+///
+/// The clocks of [dual-clock](crate::Mem::dual_clock) memories and [clock domain](crate::Module::reg_in_domain) registers in `m`'s hierarchy must be distinct from the clock and reset as well, as they share a namespace with them.
+pub fn validate_clock_and_reset_names<'a>(
+    m: &'a graph::Module<'a>,
+    clock_name: &str,
+    reset_name: &str,
+) {
+    for (kind, name) in [("clock", clock_name), ("reset", reset_name)].iter() {
+        if !is_clock_or_reset_name(name) {
+            panic!("Cannot generate code for module \"{}\" with {} name \"{}\". Clock and reset names must be non-empty, consist only of ASCII alphanumeric characters and underscores, must not start with a digit, and must not be Verilog or SystemVerilog keywords.", m.name, kind, name);
+        }
+    }
+    if clock_name == reset_name {
+        panic!("Cannot generate code for module \"{}\" because the clock and reset names are both \"{}\". Clock and reset names must be different.", m.name, clock_name);
+    }
+    detect_clock_domain_name_collisions(m, m, clock_name, reset_name);
+}
+
+/// Validates that the clock and reset ports of generated Verilog modules don't collide with each other, or with the inputs and outputs of the modules they're added to.
+///
+/// `reset_domain_ports` contains the name of each [reset domain](crate::Module::reset_domain) used in `m`'s hierarchy along with the name of its reset port. The ports are always added to `m`, but they're only added to the other modules in its hierarchy if `hierarchy_preserved` is `true` (otherwise, those modules' inputs and outputs become internal nets), so only those modules' inputs and outputs are checked.
+pub fn validate_clock_and_reset_ports<'a>(
+    m: &'a graph::Module<'a>,
+    clock_name: &str,
+    reset_name: &str,
+    reset_domain_ports: &[(String, String)],
+    hierarchy_preserved: bool,
+) {
+    let mut port_names = vec![
+        ("clock", clock_name.to_string()),
+        ("reset", reset_name.to_string()),
+    ];
+    for (domain, port) in reset_domain_ports.iter() {
+        if port == clock_name || port == reset_name {
+            panic!("Cannot generate code for module \"{}\" because the reset port of reset domain \"{}\" is called \"{}\", which is the same as the name of the {}.", m.name, domain, port, if port == clock_name { "implicit clock" } else { "implicit reset" });
+        }
+        detect_reset_domain_port_collisions(m, m, domain, port);
+        port_names.push(("reset domain reset", port.clone()));
+    }
+    collect_clock_domain_names(m, &mut port_names);
+    for (kind, name) in port_names.iter() {
+        detect_port_name_collisions(m, m, kind, name, hierarchy_preserved);
+    }
+}
+
+fn detect_port_name_collisions<'a>(
+    m: &graph::Module<'a>,
+    root: &graph::Module<'a>,
+    kind: &str,
+    name: &str,
+    hierarchy_preserved: bool,
+) {
+    if m.inputs.borrow().contains_key(name) || m.outputs.borrow().contains_key(name) {
+        panic!("Cannot generate code for module \"{}\" with {} name \"{}\" because module \"{}\" contains an input or output with the same name.", root.name, kind, name, m.name);
+    }
+
+    if hierarchy_preserved {
+        for module in m.modules.borrow().iter() {
+            detect_port_name_collisions(module, root, kind, name, hierarchy_preserved);
+        }
+    }
+}
+
+fn detect_clock_domain_name_collisions<'a>(
+    m: &graph::Module<'a>,
+    root: &graph::Module<'a>,
+    clock_name: &str,
+    reset_name: &str,
+) {
+    for mem in m.mems.borrow().iter() {
+        if let Some(ref clocks) = *mem.clocks.borrow() {
+            for name in [&clocks.read, &clocks.write].iter() {
+                if *name == clock_name || *name == reset_name {
+                    panic!("Cannot generate code for module \"{}\" because module \"{}\" contains a memory called \"{}\" with a clock called \"{}\", which is the same as the name of the {}.", root.name, m.name, mem.name, name, if *name == clock_name { "implicit clock" } else { "implicit reset" });
+                }
+            }
+        }
+    }
     for register in m.registers.borrow().iter() {
-        match register.data {
-            internal_signal::SignalData::Reg { ref data } => {
-                if data.next.borrow().is_none() {
-                    panic!("Cannot generate code for module \"{}\" because module \"{}\" contains a register called \"{}\" which is not driven.", root.name, m.name, data.name);
+        let data = register.data;
+        if let Some(ref name) = data.clock {
+            if name == clock_name || name == reset_name {
+                panic!("Cannot generate code for module \"{}\" because module \"{}\" contains a register called \"{}\" with a clock called \"{}\", which is the same as the name of the {}.", root.name, m.name, data.name, name, if name == clock_name { "implicit clock" } else { "implicit reset" });
+            }
+        }
+    }
+
+    for module in m.modules.borrow().iter() {
+        detect_clock_domain_name_collisions(module, root, clock_name, reset_name);
+    }
+}
+
+fn detect_reset_domain_port_collisions<'a>(
+    m: &graph::Module<'a>,
+    root: &graph::Module<'a>,
+    domain: &str,
+    port: &str,
+) {
+    for mem in m.mems.borrow().iter() {
+        if let Some(ref clocks) = *mem.clocks.borrow() {
+            for name in [&clocks.read, &clocks.write].iter() {
+                if *name == port {
+                    panic!("Cannot generate code for module \"{}\" because module \"{}\" contains a memory called \"{}\" with a clock called \"{}\", which is the same as the name of the reset port of reset domain \"{}\".", root.name, m.name, mem.name, name, domain);
                 }
             }
-            _ => unreachable!(),
+        }
+    }
+    for register in m.registers.borrow().iter() {
+        let data = register.data;
+        if data.clock.as_deref() == Some(port) {
+            panic!("Cannot generate code for module \"{}\" because module \"{}\" contains a register called \"{}\" with a clock called \"{}\", which is the same as the name of the reset port of reset domain \"{}\".", root.name, m.name, data.name, port, domain);
+        }
+    }
+
+    for module in m.modules.borrow().iter() {
+        detect_reset_domain_port_collisions(module, root, domain, port);
+    }
+}
+
+/// Collects the clocks of the dual-clock memories and clock domain registers in `m`'s hierarchy into `names`, along with the kind of name they are.
+fn collect_clock_domain_names<'a>(m: &graph::Module<'a>, names: &mut Vec<(&'static str, String)>) {
+    for mem in m.mems.borrow().iter() {
+        if let Some(ref clocks) = *mem.clocks.borrow() {
+            names.push(("memory clock", clocks.read.clone()));
+            names.push(("memory clock", clocks.write.clone()));
+        }
+    }
+    for register in m.registers.borrow().iter() {
+        if let Some(ref name) = register.data.clock {
+            names.push(("register clock", name.clone()));
+        }
+    }
+
+    for module in m.modules.borrow().iter() {
+        collect_clock_domain_names(module, names);
+    }
+}
+
+/// Validates that `m`'s hierarchy doesn't contain anything that depends on the implicit clock or reset, so that code can be generated for it without them.
+///
+/// Registers and memories are state elements, and assertions and coverage points are sampled on the implicit clock in generated Verilog code, so none of them can appear in any module in the hierarchy.
+pub fn validate_combinational_only<'a>(m: &'a graph::Module<'a>) {
+    detect_clocked_elements(m, m);
+}
+
+fn detect_clocked_elements<'a>(m: &graph::Module<'a>, root: &graph::Module<'a>) {
+    let kinds = [
+        (
+            "register",
+            m.registers.borrow().first().map(|r| r.data.name.clone()),
+        ),
+        (
+            "memory",
+            m.mems.borrow().first().map(|mem| mem.name.clone()),
+        ),
+        ("assertion", m.assertions.borrow().keys().next().cloned()),
+        (
+            "coverage point",
+            m.cover_points.borrow().keys().next().cloned(),
+        ),
+    ];
+    for (kind, name) in kinds.iter() {
+        if let Some(name) = name {
+            panic!("Cannot generate combinational-only code for module \"{}\" because module \"{}\" contains a {} called \"{}\". Combinational-only modules can't contain registers, memories, assertions, or coverage points.", root.name, m.name, kind, name);
+        }
+    }
+
+    for module in m.modules.borrow().iter() {
+        detect_clocked_elements(module, root);
+    }
+}
+
+fn detect_undefined_modules<'a>(m: &graph::Module<'a>, errors: &mut Vec<ValidationError>) {
+    if let Some(declaration) = m.declaration.get() {
+        let error = ValidationError::UndefinedModule {
+            module: m.name.clone(),
+        };
+        // Each undefined module is only reported once, regardless of how many instances it has
+        if !declaration.defined.get() && !errors.contains(&error) {
+            errors.push(error);
+        }
+    }
+
+    for module in m.modules.borrow().iter() {
+        detect_undefined_modules(module, errors);
+    }
+}
+
+fn detect_unresolved_deferred_signals<'a>(
+    m: &graph::Module<'a>,
+    errors: &mut Vec<ValidationError>,
+) {
+    for register in m.deferred_registers.borrow().iter() {
+        if register.resolved_bit_width().is_none() {
+            errors.push(ValidationError::UnresolvedDeferredRegister {
+                module: m.name.clone(),
+                name: register.name.clone(),
+            });
+        }
+    }
+    for output in m.deferred_outputs.borrow().iter() {
+        if output.resolved_bit_width().is_none() {
+            errors.push(ValidationError::UndrivenDeferredOutput {
+                module: m.name.clone(),
+                name: output.name.clone(),
+            });
+        }
+    }
+
+    for module in m.modules.borrow().iter() {
+        detect_unresolved_deferred_signals(module, errors);
+    }
+}
+
+fn detect_undriven_registers_and_inputs<'a>(
+    m: &graph::Module<'a>,
+    errors: &mut Vec<ValidationError>,
+) {
+    for register in m.registers.borrow().iter() {
+        if register.data.next.borrow().is_none() {
+            errors.push(ValidationError::UndrivenRegister {
+                module: m.name.clone(),
+                name: register.data.name.clone(),
+            });
         }
     }
 
     for module in m.modules.borrow().iter() {
         for (name, input) in module.inputs.borrow().iter() {
             if input.data.driven_value.borrow().is_none() {
-                panic!("Cannot generate code for module \"{}\" because module \"{}\" contains an instance of module \"{}\" called \"{}\" whose input \"{}\" is not driven.", root.name, m.name, module.name, module.instance_name, name);
+                errors.push(ValidationError::UndrivenInstanceInput {
+                    module: m.name.clone(),
+                    instance_name: module.instance_name.clone(),
+                    instance_module: module.name.clone(),
+                    name: name.clone(),
+                });
             }
         }
 
-        detect_undriven_registers_and_inputs(module, root);
+        detect_undriven_registers_and_inputs(module, errors);
     }
 }
 
-fn detect_mem_errors<'a>(m: &graph::Module<'a>, root: &graph::Module<'a>) {
+fn detect_mem_errors<'a>(m: &graph::Module<'a>, errors: &mut Vec<ValidationError>) {
     for mem in m.mems.borrow().iter() {
-        if mem.read_ports.borrow().is_empty() {
-            panic!("Cannot generate code for module \"{}\" because module \"{}\" contains a memory called \"{}\" which doesn't have any read ports.", root.name, m.name, mem.name);
+        if mem.read_ports.borrow().is_empty() && mem.comb_read_ports.borrow().is_empty() {
+            errors.push(ValidationError::MemWithoutReadPorts {
+                module: m.name.clone(),
+                name: mem.name.clone(),
+            });
         }
 
         if mem.initial_contents.borrow().is_none() && mem.write_port.borrow().is_none() {
-            panic!("Cannot generate code for module \"{}\" because module \"{}\" contains a memory called \"{}\" which doesn't have initial contents or a write port specified. At least one of the two is required.", root.name, m.name, mem.name);
+            errors.push(ValidationError::MemWithoutInitialContentsOrWritePort {
+                module: m.name.clone(),
+                name: mem.name.clone(),
+            });
         }
     }
 
     for module in m.modules.borrow().iter() {
-        detect_mem_errors(module, root);
+        detect_mem_errors(module, errors);
     }
 }
 
-fn detect_combinational_loops<'a>(m: &graph::Module<'a>, root: &graph::Module<'a>) {
+fn detect_combinational_loops<'a>(m: &graph::Module<'a>, errors: &mut Vec<ValidationError>) {
     for module in m.modules.borrow().iter() {
         for (_, output) in module.outputs.borrow().iter() {
-            trace_signal(output.data.source, output.data.source, root);
+            trace_signal(output.data.source.get(), output.data.source.get(), errors);
+        }
+
+        detect_combinational_loops(module, errors);
+    }
+}
+
+fn detect_divided_clocks_used_as_data<'a>(
+    m: &graph::Module<'a>,
+    errors: &mut Vec<ValidationError>,
+) {
+    let clock_dividers = m.clock_dividers.borrow();
+    if !clock_dividers.is_empty() {
+        // Divided clocks can only be reached from signals in their own module, so only this module's roots need to be traced
+        let mut signals = Vec::new();
+        for output in m.outputs.borrow().values() {
+            signals.push(output.data.source.get());
+            signals.extend(output.data.dont_care_when.get());
+        }
+        for register in m.registers.borrow().iter() {
+            signals.extend(*register.data.next.borrow());
+        }
+        for mem in m.mems.borrow().iter() {
+            for &(address, enable) in mem.read_ports.borrow().iter() {
+                signals.push(address);
+                signals.push(enable);
+            }
+            if let Some((address, value, enable)) = *mem.write_port.borrow() {
+                signals.push(address);
+                signals.push(value);
+                signals.push(enable);
+            }
+        }
+        signals.extend(m.named_wires.borrow().values());
+        signals.extend(
+            m.assertions
+                .borrow()
+                .values()
+                .map(|assertion| assertion.cond),
+        );
+        signals.extend(m.cover_points.borrow().values());
+        for module in m.modules.borrow().iter() {
+            for input in module.inputs.borrow().values() {
+                signals.extend(*input.data.driven_value.borrow());
+            }
+        }
+
+        // Only used for membership tests, so its iteration order never affects the errors reported
+        let mut visited = HashSet::new();
+        while let Some(signal) = signals.pop() {
+            if visited.insert(signal as *const _) {
+                signals.extend(signal.operands());
+            }
         }
 
-        detect_combinational_loops(module, root);
+        for clock_divider in clock_dividers.iter() {
+            if visited.contains(&(clock_divider.clock.value as *const _)) {
+                errors.push(ValidationError::DividedClockUsedAsData {
+                    module: m.name.clone(),
+                    name: clock_divider.name.clone(),
+                });
+            }
+        }
+    }
+
+    for module in m.modules.borrow().iter() {
+        detect_divided_clocks_used_as_data(module, errors);
     }
 }
 
 fn trace_signal<'a>(
     signal: &'a internal_signal::InternalSignal<'a>,
     source_output: &'a internal_signal::InternalSignal<'a>,
-    root: &graph::Module<'a>,
+    errors: &mut Vec<ValidationError>,
 ) {
     struct Frame<'a> {
         signal: &'a internal_signal::InternalSignal<'a>,
@@ -68,8 +471,14 @@ fn trace_signal<'a>(
     let mut frames = Vec::new();
     frames.push(Frame { signal });
 
+    // Since all errors are collected rather than only the first one, a trace may run into a loop that doesn't involve `source_output`, so each signal is only visited once (this set is only used for membership tests, so its iteration order never affects the errors reported)
+    let mut visited = HashSet::new();
+
     while let Some(frame) = frames.pop() {
         let signal = frame.signal;
+        if !visited.insert(signal as *const _) {
+            continue;
+        }
 
         match signal.data {
             internal_signal::SignalData::Lit { .. } => (),
@@ -82,12 +491,21 @@ fn trace_signal<'a>(
                 }
             }
             internal_signal::SignalData::Output { data } => {
-                if data.source == source_output {
-                    panic!("Cannot generate code for module \"{}\" because module \"{}\" contains an output called \"{}\" which forms a combinational loop with itself.", root.name, data.module.name, data.name);
+                if data.source.get() == source_output {
+                    errors.push(ValidationError::CombinationalLoop {
+                        module: data.module.name.clone(),
+                        name: data.name.clone(),
+                    });
+                    return;
                 }
                 frames.push(Frame {
-                    signal: data.source,
+                    signal: data.source.get(),
                 });
+                if let Some(dont_care_cond) = data.dont_care_when.get() {
+                    frames.push(Frame {
+                        signal: dont_care_cond,
+                    });
+                }
             }
 
             internal_signal::SignalData::Reg { .. } => (),
@@ -140,6 +558,10 @@ fn trace_signal<'a>(
             internal_signal::SignalData::Repeat { ref source, .. } => {
                 frames.push(Frame { signal: source });
             }
+            internal_signal::SignalData::NamedWire { ref source, .. }
+            | internal_signal::SignalData::Reinterpret { ref source, .. } => {
+                frames.push(Frame { signal: source });
+            }
             internal_signal::SignalData::Concat {
                 ref lhs, ref rhs, ..
             } => {
@@ -158,7 +580,246 @@ fn trace_signal<'a>(
                 frames.push(Frame { signal: when_false });
             }
 
+            internal_signal::SignalData::Case {
+                sel,
+                ref cases,
+                default,
+                ..
+            } => {
+                frames.push(Frame { signal: sel });
+                for &(_, value) in cases.iter() {
+                    frames.push(Frame { signal: value });
+                }
+                frames.push(Frame { signal: default });
+            }
+
             internal_signal::SignalData::MemReadPortOutput { .. } => (),
+            // Combinational reads see the address within the same cycle, so they're part of any loop through it
+            internal_signal::SignalData::MemCombReadPortOutput { address, .. } => {
+                frames.push(Frame { signal: address });
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::test_util::*;
+    use crate::*;
+
+    #[test]
+    fn validate_collects_all_errors() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let _ = a.reg("r", 1);
+        let b = a.module("b", "B");
+        let _ = b.input("i", 8);
+        let _ = b.mem("mem", 1, 8);
+        let _ = b.reg_deferred("d");
+
+        // Loop between "p" and "q", which "o" depends on but isn't part of
+        let d = a.module("d", "D");
+        let d_i = d.input("i", 1);
+        let p = d.output("p", d_i);
+        d_i.drive(p);
+        let e = a.module("e", "E");
+        let e_i = e.input("i", 1);
+        e_i.drive(p);
+        let _ = e.output("o", e_i);
+
+        assert_eq!(
+            validate(a),
+            Err(vec![
+                ValidationError::UnresolvedDeferredRegister {
+                    module: "B".into(),
+                    name: "d".into(),
+                },
+                ValidationError::UndrivenRegister {
+                    module: "A".into(),
+                    name: "r".into(),
+                },
+                ValidationError::UndrivenInstanceInput {
+                    module: "A".into(),
+                    instance_name: "b".into(),
+                    instance_module: "B".into(),
+                    name: "i".into(),
+                },
+                ValidationError::MemWithoutReadPorts {
+                    module: "B".into(),
+                    name: "mem".into(),
+                },
+                ValidationError::MemWithoutInitialContentsOrWritePort {
+                    module: "B".into(),
+                    name: "mem".into(),
+                },
+                ValidationError::CombinationalLoop {
+                    module: "D".into(),
+                    name: "p".into(),
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn validate_error_order_is_repeatable() {
+        assert_repeatable(|| {
+            let c = Context::new();
+            let m = repeatability_module(&c);
+
+            // Add errors of every kind that can be reported more than once, spread across the hierarchy
+            for index in 0..4 {
+                let _ = m.reg(format!("undriven{}", index), 1);
+                let _ = m.reg_deferred(format!("deferred{}", index));
+                let _ = m.mem(format!("unused_mem{}", index), 1, 1);
+                let child = m.module(format!("child{}", index), "Child");
+                let _ = child.input("i", 1);
+                let child_i = child.input("j", 1);
+                let child_o = child.output("o", child_i);
+                child_i.drive(child_o);
+                let div = m.clock_divider(format!("div{}", index), 2);
+                let _ = m.named_wire(format!("div_data{}", index), !div);
+            }
+
+            validate(m)
+                .unwrap_err()
+                .iter()
+                .map(|error| format!("{}\n", error))
+                .collect()
+        });
+    }
+
+    #[test]
+    fn validate_divided_clock_used_as_data() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let div2 = a.clock_divider("div2", 2);
+        let div4 = a.clock_divider("div4", 4);
+        let r = div4.reg("r", 1);
+        r.drive_next(a.input("i", 1));
+        a.output("o", r);
+        assert_eq!(validate(a), Ok(()));
+
+        let b = a.module("b", "B");
+        b.input("i", 1).drive(!div2);
+        let _ = a.named_wire("w", div4 & r);
+
+        assert_eq!(
+            validate(a),
+            Err(vec![
+                ValidationError::DividedClockUsedAsData {
+                    module: "A".into(),
+                    name: "div2".into(),
+                },
+                ValidationError::DividedClockUsedAsData {
+                    module: "A".into(),
+                    name: "div4".into(),
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn validate_mem_comb_read_port_loop() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+
+        // Synchronous reads break loops through their address
+        let b = a.module("b", "B");
+        let b_i = b.input("i", 1);
+        let b_mem = b.mem("mem", 1, 1);
+        b_mem.initial_contents(&[false, true]);
+        let b_o = b.output("o", b_mem.read_port_always(b_i));
+        b_i.drive(b_o);
+
+        // Combinational reads don't
+        let d = a.module("d", "D");
+        let d_i = d.input("i", 1);
+        let d_mem = d.mem("mem", 1, 1);
+        d_mem.initial_contents(&[false, true]);
+        let d_o = d.output("o", d_mem.read_port_comb(d_i));
+        d_i.drive(d_o);
+
+        assert_eq!(
+            validate(a),
+            Err(vec![ValidationError::CombinationalLoop {
+                module: "D".into(),
+                name: "o".into(),
+            }])
+        );
+    }
+
+    #[test]
+    fn validate_undefined_modules() {
+        let c = Context::new();
+
+        let b = c.declare_module("B");
+        let d = c.declare_module("D");
+        let e = c.declare_module("E");
+        e.define(|_| {});
+
+        let a = c.module("a", "A");
+        let _ = a.instance("b1", &b);
+        let _ = a.instance("b2", &b);
+        let d1 = a.instance("d1", &d);
+        let _ = d1.instance("b3", &b);
+        let _ = a.instance("e1", &e);
+
+        // Each undefined module is reported once
+        assert_eq!(
+            validate(a),
+            Err(vec![
+                ValidationError::UndefinedModule { module: "B".into() },
+                ValidationError::UndefinedModule { module: "D".into() },
+            ])
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot generate code for module \"A\" because module \"B\" was declared with Context::declare_module, but never defined with ModuleDeclaration::define."
+    )]
+    fn validate_module_hierarchy_undefined_module() {
+        let c = Context::new();
+
+        let b = c.declare_module("B");
+        let a = c.module("a", "A");
+        let _ = a.instance("b", &b);
+
+        // Panic
+        validate_module_hierarchy(a);
+    }
+
+    #[test]
+    fn validation_error_display() {
+        assert_eq!(
+            ValidationError::UndrivenInstanceInput {
+                module: "A".into(),
+                instance_name: "b".into(),
+                instance_module: "B".into(),
+                name: "i".into(),
+            }
+            .to_string(),
+            "module \"A\" contains an instance of module \"B\" called \"b\" whose input \"i\" is not driven."
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot generate code for module \"A\" because module \"A\" contains a register called \"r1\" which is not driven.\nCannot generate code for module \"A\" because module \"A\" contains a register called \"r2\" which is not driven."
+    )]
+    fn validate_module_hierarchy_reports_all_errors() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let _ = a.reg("r1", 1);
+        let _ = a.reg("r2", 1);
+
+        // Panic
+        validate_module_hierarchy(a);
+    }
+}
@@ -1,50 +1,287 @@
 //! Verilog code generation.
+//!
+//! By default, generated code is strict Verilog-2001: nets are declared as `wire` or `reg` (never SystemVerilog's `logic`), state elements are updated in plain `always @(...)` blocks (never `always_ff`/`always_comb`), and port lists only use `input wire`/`output wire`, so it's accepted by older toolchains as well as SystemVerilog tools. SystemVerilog output can be selected with [`GenerationOptions::language_standard`].
 
 mod compiler;
+mod identifiers;
 mod ir;
+mod testbench;
 
 use compiler::*;
+pub(crate) use identifiers::is_keyword;
+use identifiers::*;
+pub use identifiers::{IdentifierKind, RenamedIdentifier};
 use ir::*;
+pub use testbench::*;
 
 use crate::code_writer;
 use crate::graph;
+use crate::optimizer::*;
 use crate::state_elements::*;
 use crate::validation::*;
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::io::{Result, Write};
 
+/// The language standard that generated code conforms to.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum LanguageStandard {
+    /// Verilog-2001 (IEEE 1364-2001): nets are declared as `wire` or `reg`, ports as `input wire`/`output wire`, and state elements are updated in `always @(...)` blocks.
+    #[default]
+    Verilog2001,
+    /// SystemVerilog (IEEE 1800): nets and ports are declared as `logic`, and state elements are updated in `always_ff @(...)` blocks.
+    ///
+    /// Memories with both initial contents and a write port are still updated in an `always @(...)` block, as variables written by an `always_ff` block must not be written by any other process (including the `initial` block that loads the memory's initial contents).
+    SystemVerilog,
+}
+
+/// How names that aren't valid Verilog identifiers are emitted, as selected by [`GenerationOptions::identifier_style`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum IdentifierStyle {
+    /// Characters that can't appear in an identifier are replaced with `_` (eg. `fifo<8>` is emitted as `fifo_8_`), and a `_` is appended to keywords (eg. `reg` is emitted as `reg_`).
+    #[default]
+    Sanitize,
+    /// Names are emitted as escaped identifiers (eg. `fifo<8>` is emitted as `\fifo<8> `), which keeps them intact. Only whitespace and characters that aren't printable ASCII, which can't appear in escaped identifiers, are replaced with `_`.
+    Escape,
+}
+
+/// Options that control the Verilog code produced by [`generate`].
+#[derive(Default)]
+pub struct GenerationOptions {
+    /// If `true`, constant subexpressions are folded and trivial logic (such as `x & 0`, `x | 0`, or a [`mux`](crate::Signal::mux) with a constant condition) is simplified before code is generated.
+    ///
+    /// Registers whose next value is a constant (directly, or through a chain of other such registers) and whose default value is equal to that constant are replaced by the constant as well, and a comment is emitted for each folded register. Registers without a matching default value or with attributes are never folded.
+    pub optimize: bool,
+    /// If `true`, all child module instances are inlined into a single Verilog module.
+    ///
+    /// Otherwise (the default), the module hierarchy is preserved: a Verilog module definition is emitted once for each distinct module name in the hierarchy, and each child module instance becomes a Verilog module instantiation with named port connections.
+    /// Definitions are emitted children first, so each module is defined before it's instantiated, and modules that aren't instantiated (transitively) from the top-level module are skipped.
+    /// Since only one definition is emitted per name, all instances with the same module name must be structurally identical (ie. they must have the same inputs, outputs, logic, state elements, and child instances), and code generation panics if they aren't. Instances that are built differently (eg. with different constants) must have different module names, as with [`ModuleParent::module_parameterized`](crate::ModuleParent::module_parameterized).
+    pub flatten: bool,
+    /// If `Some`, the clock port is called `name` instead of `clk`.
+    pub override_clock_name: Option<String>,
+    /// If `Some`, the (active-low) reset port is called `name` instead of `reset_n`, and the reset port for each named reset domain is called `<name>_<domain>` instead of `reset_<domain>_n`.
+    pub override_reset_name: Option<String>,
+    /// The language standard that generated code conforms to. Literals are emitted as sized hex literals (eg. `8'hff`), which are valid in all supported standards.
+    pub language_standard: LanguageStandard,
+    /// How the names of modules, inputs, outputs, named wires, and instances that aren't valid Verilog identifiers (or are Verilog or SystemVerilog keywords) are emitted.
+    ///
+    /// Names that have to be changed are made unique within their module by appending a `_<n>` suffix if necessary, and are returned by [`generate`] as [`RenamedIdentifier`]s. Names that are only escaped aren't considered renamed, as an escaped identifier refers to the same name. The names of registers and memories only appear as part of internal identifiers, so characters that can't appear in an identifier are always replaced with `_` in them.
+    pub identifier_style: IdentifierStyle,
+    /// If `true`, the hierarchy must not contain any registers, memories, assertions, or coverage points, and code generation panics if it does. In this case, the implicit clock and reset ports are omitted from all generated modules (and from their instantiations), so purely combinational modules only have the ports they declare.
+    ///
+    /// The clock and reset names aren't used in this case, so they aren't validated (and inputs and outputs can have the names they'd otherwise have).
+    pub combinational_only: bool,
+    /// If `Some`, expressions (such as large concatenations) that would make a line of generated code longer than `max_line_width` columns are wrapped onto continuation lines (indented one level deeper) at operator and concatenation boundaries, so that large expressions produce small diffs when the design changes. Lines may still exceed `max_line_width` when they contain a single long token (such as an identifier).
+    ///
+    /// If `None`, each expression is written on a single line.
+    pub max_line_width: Option<u32>,
+}
+
 // TODO: Note that mutable writer reference can be passed, see https://rust-lang.github.io/api-guidelines/interoperability.html#c-rw-value
-pub fn generate<'a, W: Write>(m: &'a graph::Module<'a>, w: W) -> Result<()> {
+/// Generates Verilog code for `m` and writes it to `w`.
+///
+/// Returns the names that had to be changed to be used as Verilog identifiers (see [`GenerationOptions::identifier_style`]), which are typically reported as warnings.
+pub fn generate<'a, W: Write>(
+    m: &'a graph::Module<'a>,
+    options: GenerationOptions,
+    w: W,
+) -> Result<Vec<RenamedIdentifier>> {
     validate_module_hierarchy(m);
 
+    let names = ClockAndResetNames {
+        clock: options.override_clock_name.unwrap_or_else(|| "clk".into()),
+        reset: options.override_reset_name,
+        omitted: options.combinational_only,
+    };
+    if names.omitted {
+        validate_combinational_only(m);
+    } else {
+        let reset_port_name = names.reset_port_name(None);
+        validate_clock_and_reset_names(m, &names.clock, &reset_port_name);
+        let reset_domain_ports = hierarchy_reset_domain_names(m)
+            .into_iter()
+            .map(|name| {
+                let port = names.reset_port_name(Some(&name));
+                (name, port)
+            })
+            .collect::<Vec<_>>();
+        validate_clock_and_reset_ports(
+            m,
+            &names.clock,
+            &reset_port_name,
+            &reset_domain_ports,
+            !options.flatten,
+        );
+    }
+
+    let optimizer = Optimizer::new(options.optimize);
+
+    let mut w = code_writer::CodeWriter::new(w);
+    w.set_max_line_width(options.max_line_width);
+
+    if options.flatten {
+        let identifiers = Identifiers::new(&[m], Hierarchy::Flattened, options.identifier_style);
+        generate_module(
+            m,
+            Hierarchy::Flattened,
+            &names,
+            &identifiers,
+            options.language_standard,
+            &optimizer,
+            &mut w,
+        )?;
+        return Ok(identifiers.renamed);
+    }
+
+    let mut definitions = Vec::new();
+    collect_module_definitions(m, &mut definitions);
+    let identifiers =
+        Identifiers::new(&definitions, Hierarchy::Preserved, options.identifier_style);
+    for module in definitions {
+        generate_module(
+            module,
+            Hierarchy::Preserved,
+            &names,
+            &identifiers,
+            options.language_standard,
+            &optimizer,
+            &mut w,
+        )?;
+    }
+
+    Ok(identifiers.renamed)
+}
+
+/// Collects one instance of each distinct module name in `m`'s hierarchy into `definitions`, leaves first, so that each module is defined before any module that instantiates it.
+///
+/// # Panics
+///
+/// Panics if two instances with the same module name aren't structurally identical, as only one definition can be emitted per name.
+fn collect_module_definitions<'a>(
+    m: &'a graph::Module<'a>,
+    definitions: &mut Vec<&'a graph::Module<'a>>,
+) {
+    for &module in m.modules.borrow().iter() {
+        if let Some(&definition) = definitions.iter().find(|d| d.name == module.name) {
+            validate_same_definition(definition, module);
+            continue;
+        }
+        collect_module_definitions(module, definitions);
+    }
+
+    if let Some(&definition) = definitions.iter().find(|d| d.name == m.name) {
+        validate_same_definition(definition, m);
+        return;
+    }
+    definitions.push(m);
+}
+
+fn validate_same_definition<'a>(a: &'a graph::Module<'a>, b: &'a graph::Module<'a>) {
+    let ports = |m: &'a graph::Module<'a>| {
+        let inputs = m
+            .inputs
+            .borrow()
+            .iter()
+            .map(|(name, input)| (name.clone(), input.data.bit_width))
+            .collect::<Vec<_>>();
+        let outputs = m
+            .outputs
+            .borrow()
+            .iter()
+            .map(|(name, output)| (name.clone(), output.data.bit_width))
+            .collect::<Vec<_>>();
+        (inputs, outputs)
+    };
+    if ports(a) != ports(b) {
+        panic!("Cannot generate Verilog for module \"{}\", because instances \"{}\" and \"{}\" have different inputs or outputs. Only one definition is emitted for each module name, so all instances with the same module name must have the same inputs and outputs.", a.name, a.instance_path(), b.instance_path());
+    }
+    if crate::ir::generate_relative(a) != crate::ir::generate_relative(b) {
+        panic!("Cannot generate Verilog for module \"{}\", because instances \"{}\" and \"{}\" have different contents. Only one definition is emitted for each module name, so all instances with the same module name must be structurally identical.", a.name, a.instance_path(), b.instance_path());
+    }
+}
+
+fn generate_module<'a, W: Write>(
+    m: &'a graph::Module<'a>,
+    hierarchy: Hierarchy,
+    names: &ClockAndResetNames,
+    identifiers: &Identifiers,
+    language_standard: LanguageStandard,
+    optimizer: &Optimizer<'a>,
+    w: &mut code_writer::CodeWriter<W>,
+) -> Result<()> {
+    let (input_decl, output_decl, always_ff) = match language_standard {
+        LanguageStandard::Verilog2001 => ("input wire", "output wire", "always"),
+        LanguageStandard::SystemVerilog => ("input logic", "output logic", "always_ff"),
+    };
+
+    let num_folded_registers = optimizer.folded_registers().len();
+
     let mut signal_reference_counts = HashMap::new();
     let state_elements = StateElements::new(
         m,
         IncludedPorts::ReachableFromTopLevelOutputs,
+        hierarchy,
+        optimizer,
         &mut signal_reference_counts,
     );
 
-    let mut c = Compiler::new();
+    let mut c = Compiler::new(hierarchy, identifiers, optimizer);
 
     let mut assignments = AssignmentContext::new();
     for (name, &output) in m.outputs.borrow().iter() {
-        let expr = c.compile_signal(output.data.source, &state_elements, &mut assignments);
+        let expr = c.compile_signal(
+            optimizer.optimize_output(output.data),
+            &state_elements,
+            &mut assignments,
+        );
         assignments.push(Assignment {
-            target_name: name.clone(),
+            target_name: identifiers.local(m, name).into(),
             expr,
         });
     }
 
     let mut node_decls = Vec::new();
 
-    for (mem, mem_decls) in state_elements.mems.iter() {
-        for ((address, enable), read_signal_names) in mem_decls.read_signal_names.iter() {
+    // Inouts are driven through local wires for their values and output enables, which are outputs in generated simulators, but not ports here
+    let mut inout_output_names = BTreeSet::new();
+    for (name, inout) in m.inouts.borrow().iter() {
+        if let Some((value, output_enable)) = *inout.outputs.borrow() {
+            for output in [value, output_enable] {
+                inout_output_names.insert(output.data.name.as_str());
+                node_decls.push(NodeDecl {
+                    net_type: NetType::Wire,
+                    name: identifiers.local(m, &output.data.name).into(),
+                    bit_width: output.data.bit_width,
+                    attributes: output.data.attributes.borrow().clone(),
+                });
+            }
+            assignments.push(Assignment {
+                target_name: identifiers.local(m, name).into(),
+                expr: Expr::Ternary {
+                    cond: Box::new(Expr::Ref {
+                        name: identifiers.local(m, &output_enable.data.name).into(),
+                    }),
+                    when_true: Box::new(Expr::Ref {
+                        name: identifiers.local(m, &value.data.name).into(),
+                    }),
+                    when_false: Box::new(Expr::HighImpedance {
+                        bit_width: value.data.bit_width,
+                    }),
+                },
+            });
+        }
+    }
+
+    for mem_decls in state_elements.sorted_mems() {
+        let mem = mem_decls.mem;
+        for ((address, enable), read_signal_names) in mem_decls.sorted_read_signal_names() {
             let expr = c.compile_signal(address, &state_elements, &mut assignments);
             node_decls.push(NodeDecl {
                 net_type: NetType::Wire,
                 name: read_signal_names.address_name.clone(),
                 bit_width: address.bit_width(),
+                attributes: Vec::new(),
             });
             assignments.push(Assignment {
                 target_name: read_signal_names.address_name.clone(),
@@ -55,6 +292,7 @@ pub fn generate<'a, W: Write>(m: &'a graph::Module<'a>, w: W) -> Result<()> {
                 net_type: NetType::Wire,
                 name: read_signal_names.enable_name.clone(),
                 bit_width: enable.bit_width(),
+                attributes: Vec::new(),
             });
             assignments.push(Assignment {
                 target_name: read_signal_names.enable_name.clone(),
@@ -64,6 +302,7 @@ pub fn generate<'a, W: Write>(m: &'a graph::Module<'a>, w: W) -> Result<()> {
                 net_type: NetType::Reg,
                 name: read_signal_names.value_name.clone(),
                 bit_width: mem.element_bit_width,
+                attributes: Vec::new(),
             });
         }
         if let Some((address, value, enable)) = *mem.write_port.borrow() {
@@ -72,6 +311,7 @@ pub fn generate<'a, W: Write>(m: &'a graph::Module<'a>, w: W) -> Result<()> {
                 net_type: NetType::Wire,
                 name: mem_decls.write_address_name.clone(),
                 bit_width: address.bit_width(),
+                attributes: Vec::new(),
             });
             assignments.push(Assignment {
                 target_name: mem_decls.write_address_name.clone(),
@@ -82,6 +322,7 @@ pub fn generate<'a, W: Write>(m: &'a graph::Module<'a>, w: W) -> Result<()> {
                 net_type: NetType::Wire,
                 name: mem_decls.write_value_name.clone(),
                 bit_width: value.bit_width(),
+                attributes: Vec::new(),
             });
             assignments.push(Assignment {
                 target_name: mem_decls.write_value_name.clone(),
@@ -92,6 +333,7 @@ pub fn generate<'a, W: Write>(m: &'a graph::Module<'a>, w: W) -> Result<()> {
                 net_type: NetType::Wire,
                 name: mem_decls.write_enable_name.clone(),
                 bit_width: enable.bit_width(),
+                attributes: Vec::new(),
             });
             assignments.push(Assignment {
                 target_name: mem_decls.write_enable_name.clone(),
@@ -100,16 +342,21 @@ pub fn generate<'a, W: Write>(m: &'a graph::Module<'a>, w: W) -> Result<()> {
         }
     }
 
-    for reg in state_elements.regs.values() {
+    // Regs are emitted in name order so that output is stable
+    let regs = state_elements.sorted_regs();
+
+    for reg in regs.iter() {
         node_decls.push(NodeDecl {
             net_type: NetType::Reg,
             name: reg.value_name.clone(),
             bit_width: reg.data.bit_width,
+            attributes: reg.data.attributes.borrow().clone(),
         });
         node_decls.push(NodeDecl {
             net_type: NetType::Wire,
             name: reg.next_name.clone(),
             bit_width: reg.data.bit_width,
+            attributes: Vec::new(),
         });
 
         let expr = c.compile_signal(
@@ -123,44 +370,155 @@ pub fn generate<'a, W: Write>(m: &'a graph::Module<'a>, w: W) -> Result<()> {
         });
     }
 
-    let mut w = code_writer::CodeWriter::new(w);
+    // Assertions of child instances are only checked here if they're flattened into this module
+    let mut assertions = Vec::new();
+    collect_assertions(m, hierarchy, "", &mut assertions);
+    for assertion in assertions.iter() {
+        node_decls.push(NodeDecl {
+            net_type: NetType::Wire,
+            name: assertion.wire_name.clone(),
+            bit_width: 1,
+            attributes: Vec::new(),
+        });
+        let expr = c.compile_signal(assertion.cond, &state_elements, &mut assignments);
+        assignments.push(Assignment {
+            target_name: assertion.wire_name.clone(),
+            expr,
+        });
+    }
+
+    let mut cover_points = Vec::new();
+    collect_cover_points(m, hierarchy, &mut cover_points);
+    for cover_point in cover_points.iter() {
+        node_decls.push(NodeDecl {
+            net_type: NetType::Wire,
+            name: cover_point.wire_name.clone(),
+            bit_width: 1,
+            attributes: Vec::new(),
+        });
+        let expr = c.compile_signal(cover_point.cond, &state_elements, &mut assignments);
+        assignments.push(Assignment {
+            target_name: cover_point.wire_name.clone(),
+            expr,
+        });
+    }
+
+    if let Hierarchy::Preserved = hierarchy {
+        for child in m.modules.borrow().iter() {
+            for (name, &input) in child.inputs.borrow().iter() {
+                let target_name = identifiers.instance_port(child, name).to_string();
+                let expr = c.compile_signal(
+                    input.data.driven_value.borrow().unwrap(),
+                    &state_elements,
+                    &mut assignments,
+                );
+                node_decls.push(NodeDecl {
+                    net_type: NetType::Wire,
+                    name: target_name.clone(),
+                    bit_width: input.data.bit_width,
+                    attributes: Vec::new(),
+                });
+                assignments.push(Assignment { target_name, expr });
+            }
+            for (name, &output) in child.outputs.borrow().iter() {
+                node_decls.push(NodeDecl {
+                    net_type: NetType::Wire,
+                    name: identifiers.instance_port(child, name).into(),
+                    bit_width: output.data.bit_width,
+                    attributes: Vec::new(),
+                });
+            }
+        }
+    }
+
+    let reset_domain_names = match hierarchy {
+        Hierarchy::Flattened => {
+            let mut reset_domain_names = BTreeSet::new();
+            for reg in regs.iter() {
+                if reg.data.initial_value.borrow().is_some() {
+                    if let Some(domain) = *reg.data.reset_domain.borrow() {
+                        reset_domain_names.insert(domain.name.clone());
+                    }
+                }
+            }
+            reset_domain_names
+        }
+        // Child instances need every reset used anywhere below them to be passed down
+        Hierarchy::Preserved => hierarchy_reset_domain_names(m),
+    };
+
+    for reg in optimizer
+        .folded_registers()
+        .iter()
+        .skip(num_folded_registers)
+    {
+        w.append_line(&format!(
+            "// Folded constant register \"{}\" in module \"{}\"",
+            reg.name, reg.module.name
+        ))?;
+    }
 
-    w.append_line(&format!("module {}(", m.name))?;
+    w.append_line(&format!("module {}(", identifiers.module(&m.name)))?;
     w.indent();
 
     // TODO: Make conditional based on the presence of (resetable) state elements
-    w.append_line("input wire reset_n,")?;
-    w.append_indent()?;
-    w.append("input wire clk")?;
-    if !m.inputs.borrow().is_empty() || !m.outputs.borrow().is_empty() {
-        w.append(",")?;
-        w.append_newline()?;
-    }
-    w.append_newline()?;
-    let inputs = m.inputs.borrow();
-    let num_inputs = inputs.len();
-    for (i, (name, &input)) in inputs.iter().enumerate() {
-        w.append_indent()?;
-        w.append("input wire ")?;
-        if input.data.bit_width > 1 {
-            w.append(&format!("[{}:{}] ", input.data.bit_width - 1, 0))?;
+    if !names.omitted {
+        w.append_line(&format!("{} {},", input_decl, names.reset_port_name(None)))?;
+        for name in reset_domain_names.iter() {
+            w.append_line(&format!(
+                "{} {},",
+                input_decl,
+                names.reset_port_name(Some(name))
+            ))?;
+        }
+        for name in hierarchy_clock_names(m).iter() {
+            w.append_line(&format!("{} {},", input_decl, name))?;
         }
-        w.append(name)?;
-        if !m.outputs.borrow().is_empty() || i < num_inputs - 1 {
+        w.append_indent()?;
+        w.append(&format!("{} {}", input_decl, names.clock))?;
+        if !m.inputs.borrow().is_empty() || !m.outputs.borrow().is_empty() {
             w.append(",")?;
+            w.append_newline()?;
         }
         w.append_newline()?;
     }
+    let inouts = m.inouts.borrow();
+    let inputs = m.inputs.borrow();
     let outputs = m.outputs.borrow();
-    let num_outputs = outputs.len();
-    for (i, (name, &output)) in outputs.iter().enumerate() {
+    // Inouts take the place of their inputs (tri-state nets can't be variables, so they're always declared as wires)
+    let ports = inputs
+        .iter()
+        .map(|(name, input)| {
+            let decl = if inouts.contains_key(name) {
+                "inout wire"
+            } else {
+                input_decl
+            };
+            (name, decl, &input.data.attributes, input.data.bit_width)
+        })
+        .chain(
+            outputs
+                .iter()
+                .filter(|(name, _)| !inout_output_names.contains(name.as_str()))
+                .map(|(name, output)| {
+                    (
+                        name,
+                        output_decl,
+                        &output.data.attributes,
+                        output.data.bit_width,
+                    )
+                }),
+        )
+        .collect::<Vec<_>>();
+    for (i, &(name, decl, attributes, bit_width)) in ports.iter().enumerate() {
         w.append_indent()?;
-        w.append("output wire ")?;
-        if output.data.bit_width > 1 {
-            w.append(&format!("[{}:{}] ", output.data.bit_width - 1, 0))?;
+        write_attributes(&attributes.borrow(), w)?;
+        w.append(&format!("{} ", decl))?;
+        if bit_width > 1 {
+            w.append(&format!("[{}:{}] ", bit_width - 1, 0))?;
         }
-        w.append(name)?;
-        if i < num_outputs - 1 {
+        w.append(identifiers.local(m, name))?;
+        if i < ports.len() - 1 {
             w.append(",")?;
         }
         w.append_newline()?;
@@ -170,14 +528,16 @@ pub fn generate<'a, W: Write>(m: &'a graph::Module<'a>, w: W) -> Result<()> {
 
     if !node_decls.is_empty() {
         for node_decl in node_decls {
-            node_decl.write(&mut w)?;
+            node_decl.write(language_standard, w)?;
         }
         w.append_newline()?;
     }
 
-    for (mem, mem_decls) in state_elements.mems.iter() {
+    for mem_decls in state_elements.sorted_mems() {
+        let mem = mem_decls.mem;
         w.append_indent()?;
-        w.append("reg ")?;
+        NetType::Reg.write(language_standard, w)?;
+        w.append(" ")?;
         if mem.element_bit_width > 1 {
             w.append(&format!("[{}:{}] ", mem.element_bit_width - 1, 0))?;
         }
@@ -205,62 +565,120 @@ pub fn generate<'a, W: Write>(m: &'a graph::Module<'a>, w: W) -> Result<()> {
             w.append_line("end")?;
             w.append_newline()?;
         }
-        if !mem_decls.read_signal_names.is_empty() || mem.write_port.borrow().is_some() {
-            w.append_line("always @(posedge clk) begin")?;
-            w.indent();
-        }
-        for (_, read_signal_names) in mem_decls.read_signal_names.iter() {
-            w.append_line(&format!("if ({}) begin", read_signal_names.enable_name))?;
-            w.indent();
-            w.append_line(&format!(
-                "{} <= {}[{}];",
-                read_signal_names.value_name, mem_decls.mem_name, read_signal_names.address_name
-            ))?;
-            w.unindent();
-            w.append_line("end")?;
+        let clocks = mem.clocks.borrow();
+        let (read_clock, write_clock) = match *clocks {
+            Some(ref clocks) => (clocks.read.as_str(), clocks.write.as_str()),
+            None => (names.clock.as_str(), names.clock.as_str()),
+        };
+        // The memory is already written by its initial block, so it can't be written by always_ff as well
+        let write_always =
+            if mem.initial_contents.borrow().is_some() && mem.write_port.borrow().is_some() {
+                "always"
+            } else {
+                always_ff
+            };
+        // Ports on the same clock share a single always block
+        let mut blocks = Vec::new();
+        if !mem_decls.read_signal_names.is_empty() {
+            blocks.push((read_clock, always_ff, true, false));
         }
         if mem.write_port.borrow().is_some() {
-            w.append_line(&format!("if ({}) begin", mem_decls.write_enable_name))?;
-            w.indent();
-            w.append_line(&format!(
-                "{}[{}] <= {};",
-                mem_decls.mem_name, mem_decls.write_address_name, mem_decls.write_value_name
-            ))?;
-            w.unindent();
-            w.append_line("end")?;
+            match blocks.last_mut() {
+                Some(block) if block.0 == write_clock => {
+                    *block = (write_clock, write_always, true, true);
+                }
+                _ => blocks.push((write_clock, write_always, false, true)),
+            }
         }
-        if !mem_decls.read_signal_names.is_empty() || mem.write_port.borrow().is_some() {
+        for (clock, always, has_reads, has_write) in blocks {
+            w.append_line(&format!("{} @(posedge {}) begin", always, clock))?;
+            w.indent();
+            if has_reads {
+                for (_, read_signal_names) in mem_decls.sorted_read_signal_names() {
+                    w.append_line(&format!("if ({}) begin", read_signal_names.enable_name))?;
+                    w.indent();
+                    w.append_line(&format!(
+                        "{} <= {}[{}];",
+                        read_signal_names.value_name,
+                        mem_decls.mem_name,
+                        read_signal_names.address_name
+                    ))?;
+                    w.unindent();
+                    w.append_line("end")?;
+                }
+            }
+            if has_write {
+                w.append_line(&format!("if ({}) begin", mem_decls.write_enable_name))?;
+                w.indent();
+                w.append_line(&format!(
+                    "{}[{}] <= {};",
+                    mem_decls.mem_name, mem_decls.write_address_name, mem_decls.write_value_name
+                ))?;
+                w.unindent();
+                w.append_line("end")?;
+            }
             w.unindent();
             w.append_line("end")?;
             w.append_newline()?;
         }
     }
 
-    for reg in state_elements.regs.values() {
+    // Registers are grouped into one always block per clock, clock edge, and reset (or lack thereof)
+    let mut reg_groups = BTreeMap::new();
+    for reg in regs.iter() {
+        let clock_name = match (reg.data.divided_clock, reg.data.clock.as_ref()) {
+            (Some(divider), _) => &state_elements.regs[&divider.clock.value].value_name,
+            (None, Some(clock)) => clock,
+            (None, None) => &names.clock,
+        };
+        let reset_name = if reg.data.initial_value.borrow().is_some() {
+            Some(names.reset_port_name(reg.data.reset_domain.borrow().map(|d| d.name.as_str())))
+        } else {
+            None
+        };
+        let edge = match reg.data.edge.get() {
+            graph::Edge::Pos => "posedge",
+            graph::Edge::Neg => "negedge",
+        };
+        reg_groups
+            .entry((clock_name, edge, reset_name))
+            .or_insert_with(Vec::new)
+            .push(reg);
+    }
+    for ((clock_name, edge, reset_name), regs) in reg_groups.iter() {
         w.append_indent()?;
-        w.append("always @(posedge clk")?;
-        if reg.data.initial_value.borrow().is_some() {
-            w.append(", negedge reset_n")?;
+        w.append(&format!("{} @({} {}", always_ff, edge, clock_name))?;
+        if let Some(reset_name) = reset_name {
+            w.append(&format!(", negedge {}", reset_name))?;
         }
         w.append(") begin")?;
         w.append_newline()?;
         w.indent();
-        if let Some(ref initial_value) = *reg.data.initial_value.borrow() {
-            w.append_line("if (~reset_n) begin")?;
+        if let Some(reset_name) = reset_name {
+            w.append_line(&format!("if (~{}) begin", reset_name))?;
             w.indent();
-            w.append_line(&format!(
-                "{} <= {}'h{:x};",
-                reg.value_name,
-                reg.data.bit_width,
-                initial_value.numeric_value()
-            ))?;
+            for reg in regs.iter() {
+                w.append_line(&format!(
+                    "{} <= {}'h{:x};",
+                    reg.value_name,
+                    reg.data.bit_width,
+                    reg.data
+                        .initial_value
+                        .borrow()
+                        .as_ref()
+                        .unwrap()
+                        .numeric_value()
+                ))?;
+            }
             w.unindent();
             w.append_line("end")?;
             w.append_line("else begin")?;
             w.indent();
         }
-        w.append_line(&format!("{} <= {};", reg.value_name, reg.next_name))?;
-        if reg.data.initial_value.borrow().is_some() {
+        for reg in regs.iter() {
+            w.append_line(&format!("{} <= {};", reg.value_name, reg.next_name))?;
+        }
+        if reset_name.is_some() {
             w.unindent();
             w.append_line("end")?;
         }
@@ -269,8 +687,131 @@ pub fn generate<'a, W: Write>(m: &'a graph::Module<'a>, w: W) -> Result<()> {
         w.append_newline()?;
     }
 
+    if !assertions.is_empty() {
+        let reset_name = names.reset_port_name(None);
+        match language_standard {
+            LanguageStandard::Verilog2001 => {
+                w.append_line(&format!("always @(posedge {}) begin", names.clock))?;
+                w.indent();
+                for assertion in assertions.iter() {
+                    w.append_line(&format!(
+                        "if ({} && ~{}) begin",
+                        reset_name, assertion.wire_name
+                    ))?;
+                    w.indent();
+                    w.append_line(&format!("$display(\"{}\");", assertion.failure_text()))?;
+                    w.append_line("$finish;")?;
+                    w.unindent();
+                    w.append_line("end")?;
+                }
+                w.unindent();
+                w.append_line("end")?;
+
+                // Property assertions aren't part of Verilog-2001, so they're only visible to formal verification tools
+                if assertions
+                    .iter()
+                    .any(|assertion| assertion.message.is_some())
+                {
+                    w.append_newline()?;
+                    w.append_line("`ifdef FORMAL")?;
+                    for assertion in assertions
+                        .iter()
+                        .filter(|assertion| assertion.message.is_some())
+                    {
+                        w.append_line(&format!(
+                            "assert property (@(posedge {}) disable iff (~{}) {});",
+                            names.clock, reset_name, assertion.wire_name
+                        ))?;
+                    }
+                    w.append_line("`endif")?;
+                }
+            }
+            LanguageStandard::SystemVerilog => {
+                for assertion in assertions.iter() {
+                    w.append_line(&format!(
+                        "assert property (@(posedge {}) disable iff (~{}) {}) else $fatal(1, \"{}\");",
+                        names.clock,
+                        reset_name,
+                        assertion.wire_name,
+                        assertion.failure_text()
+                    ))?;
+                }
+            }
+        }
+        w.append_newline()?;
+    }
+
+    if !cover_points.is_empty() {
+        let reset_name = names.reset_port_name(None);
+        // Like property assertions, cover properties are only visible to formal verification tools in Verilog-2001
+        if let LanguageStandard::Verilog2001 = language_standard {
+            w.append_line("`ifdef FORMAL")?;
+        }
+        for cover_point in cover_points.iter() {
+            w.append_line(&format!(
+                "cover property (@(posedge {}) disable iff (~{}) {});",
+                names.clock, reset_name, cover_point.wire_name
+            ))?;
+        }
+        if let LanguageStandard::Verilog2001 = language_standard {
+            w.append_line("`endif")?;
+        }
+        w.append_newline()?;
+    }
+
+    if let Hierarchy::Preserved = hierarchy {
+        for child in m.modules.borrow().iter() {
+            let mut connections = Vec::new();
+            if !names.omitted {
+                connections.push(names.reset_port_name(None));
+                connections.extend(
+                    hierarchy_reset_domain_names(child)
+                        .iter()
+                        .map(|name| names.reset_port_name(Some(name))),
+                );
+                connections.extend(hierarchy_clock_names(child));
+                connections.push(names.clock.clone());
+            }
+            let mut port_connections = connections
+                .into_iter()
+                .map(|port| format!(".{}({})", port, port))
+                .collect::<Vec<_>>();
+            for name in child
+                .inputs
+                .borrow()
+                .keys()
+                .chain(child.outputs.borrow().keys())
+            {
+                port_connections.push(format!(
+                    ".{}({})",
+                    identifiers.local(child, name),
+                    identifiers.instance_port(child, name)
+                ));
+            }
+
+            w.append_line(&format!(
+                "{} {}(",
+                identifiers.module(&child.name),
+                identifiers.instance(child)
+            ))?;
+            w.indent();
+            let num_port_connections = port_connections.len();
+            for (i, port_connection) in port_connections.into_iter().enumerate() {
+                w.append_indent()?;
+                w.append(&port_connection)?;
+                if i < num_port_connections - 1 {
+                    w.append(",")?;
+                }
+                w.append_newline()?;
+            }
+            w.unindent();
+            w.append_line(");")?;
+            w.append_newline()?;
+        }
+    }
+
     if !assignments.is_empty() {
-        assignments.write(&mut w)?;
+        assignments.write(language_standard, w)?;
         w.append_newline()?;
     }
 
@@ -281,10 +822,163 @@ pub fn generate<'a, W: Write>(m: &'a graph::Module<'a>, w: W) -> Result<()> {
     Ok(())
 }
 
+struct Assertion<'a> {
+    /// The assertion's name, prefixed with the instance path of its module relative to the module being generated, if any.
+    name: String,
+    wire_name: String,
+    cond: &'a graph::internal_signal::InternalSignal<'a>,
+    /// The failure message of an assertion created with [`Module::assert_always`](graph::Module::assert_always).
+    message: Option<String>,
+}
+
+impl<'a> Assertion<'a> {
+    /// Returns the text that's reported when this assertion fails, as the contents of a Verilog string literal.
+    fn failure_text(&self) -> String {
+        let mut text = format!(
+            "Assertion \\\"{}\\\" failed in %m",
+            escape_string(&self.name)
+        );
+        if let Some(ref message) = self.message {
+            text.push_str(": ");
+            text.push_str(&escape_string(message));
+        }
+        text
+    }
+}
+
+struct CoverPoint<'a> {
+    wire_name: String,
+    cond: &'a graph::internal_signal::InternalSignal<'a>,
+}
+
+/// Collects the coverage points in `m` into `cover_points`, as well as the coverage points of its descendants if `hierarchy` is flattened.
+fn collect_cover_points<'a>(
+    m: &'a graph::Module<'a>,
+    hierarchy: Hierarchy,
+    cover_points: &mut Vec<CoverPoint<'a>>,
+) {
+    for (name, &cond) in m.cover_points.borrow().iter() {
+        cover_points.push(CoverPoint {
+            wire_name: format!("__cover_{}_{}", cond.module_instance_name_prefix(), name),
+            cond,
+        });
+    }
+    if let Hierarchy::Flattened = hierarchy {
+        for child in m.modules.borrow().iter() {
+            collect_cover_points(child, hierarchy, cover_points);
+        }
+    }
+}
+
+/// Escapes `s` so that it can be embedded in a Verilog string literal that's used as a `$display`-style format string.
+fn escape_string(s: &str) -> String {
+    let mut ret = String::new();
+    for c in s.chars() {
+        match c {
+            '\\' => ret.push_str("\\\\"),
+            '"' => ret.push_str("\\\""),
+            '\n' => ret.push_str("\\n"),
+            '\t' => ret.push_str("\\t"),
+            '%' => ret.push_str("%%"),
+            _ => ret.push(c),
+        }
+    }
+    ret
+}
+
+/// Collects the assertions in `m` into `assertions`, as well as the assertions of its descendants if `hierarchy` is flattened.
+fn collect_assertions<'a>(
+    m: &'a graph::Module<'a>,
+    hierarchy: Hierarchy,
+    path_prefix: &str,
+    assertions: &mut Vec<Assertion<'a>>,
+) {
+    for (name, assertion) in m.assertions.borrow().iter() {
+        assertions.push(Assertion {
+            name: format!("{}{}", path_prefix, name),
+            wire_name: format!(
+                "__assertion_{}_{}",
+                assertion.cond.module_instance_name_prefix(),
+                name
+            ),
+            cond: assertion.cond,
+            message: assertion.message.clone(),
+        });
+    }
+    if let Hierarchy::Flattened = hierarchy {
+        for child in m.modules.borrow().iter() {
+            collect_assertions(
+                child,
+                hierarchy,
+                &format!("{}{}.", path_prefix, child.instance_name),
+                assertions,
+            );
+        }
+    }
+}
+
+/// Returns the names of all clocks (other than the implicit clock) used by dual-clock memories or clock domain registers in `m` or any of its descendants.
+fn hierarchy_clock_names<'a>(m: &'a graph::Module<'a>) -> BTreeSet<String> {
+    let mut clock_names = BTreeSet::new();
+    for mem in m.mems.borrow().iter() {
+        if let Some(ref clocks) = *mem.clocks.borrow() {
+            clock_names.insert(clocks.read.clone());
+            clock_names.insert(clocks.write.clone());
+        }
+    }
+    for register in m.registers.borrow().iter() {
+        if let Some(ref clock) = register.data.clock {
+            clock_names.insert(clock.clone());
+        }
+    }
+    for child in m.modules.borrow().iter() {
+        clock_names.extend(hierarchy_clock_names(child));
+    }
+    clock_names
+}
+
+/// Returns the names of all reset domains used by resettable registers in `m` or any of its descendants.
+fn hierarchy_reset_domain_names<'a>(m: &'a graph::Module<'a>) -> BTreeSet<String> {
+    let mut reset_domain_names = BTreeSet::new();
+    for register in m.registers.borrow().iter() {
+        let data = register.data;
+        if data.initial_value.borrow().is_some() {
+            if let Some(domain) = *data.reset_domain.borrow() {
+                reset_domain_names.insert(domain.name.clone());
+            }
+        }
+    }
+    for child in m.modules.borrow().iter() {
+        reset_domain_names.extend(hierarchy_reset_domain_names(child));
+    }
+    reset_domain_names
+}
+
+/// Names of the implicit clock and reset ports, which may be overridden by [`GenerationOptions`].
+struct ClockAndResetNames {
+    clock: String,
+    reset: Option<String>,
+    /// If `true`, the implicit clock and reset ports are omitted entirely (see [`GenerationOptions::combinational_only`]).
+    omitted: bool,
+}
+
+impl ClockAndResetNames {
+    /// Returns the name of the reset port for the named reset domain `reset_domain_name`, or the implicit reset if `None`.
+    fn reset_port_name(&self, reset_domain_name: Option<&str>) -> String {
+        match (&self.reset, reset_domain_name) {
+            (None, None) => "reset_n".into(),
+            (None, Some(name)) => format!("reset_{}_n", name),
+            (Some(reset), None) => reset.clone(),
+            (Some(reset), Some(name)) => format!("{}_{}", reset, name),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use crate::test_util::*;
     use crate::*;
 
     #[test]
@@ -299,7 +993,7 @@ mod tests {
         let _ = b.input("i", 1);
 
         // Panic
-        generate(a, Vec::new()).unwrap();
+        generate(a, GenerationOptions::default(), Vec::new()).unwrap();
     }
 
     #[test]
@@ -313,7 +1007,7 @@ mod tests {
         let _ = a.reg("r", 1);
 
         // Panic
-        generate(a, Vec::new()).unwrap();
+        generate(a, GenerationOptions::default(), Vec::new()).unwrap();
     }
 
     #[test]
@@ -328,7 +1022,36 @@ mod tests {
         let _ = b.reg("r", 1);
 
         // Panic
-        generate(a, Vec::new()).unwrap();
+        generate(a, GenerationOptions::default(), Vec::new()).unwrap();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot generate code for module \"A\" because module \"B\" contains a deferred register called \"r\" whose bit width was never resolved."
+    )]
+    fn unresolved_deferred_register_error() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let b = a.module("b", "B");
+        let _ = b.reg_deferred("r");
+
+        // Panic
+        generate(a, GenerationOptions::default(), Vec::new()).unwrap();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot generate code for module \"A\" because module \"A\" contains a deferred output called \"o\" which is not driven."
+    )]
+    fn undriven_deferred_output_error() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let _ = a.output_deferred("o");
+
+        // Panic
+        generate(a, GenerationOptions::default(), Vec::new()).unwrap();
     }
 
     #[test]
@@ -342,7 +1065,7 @@ mod tests {
         let _ = a.mem("m", 1, 1);
 
         // Panic
-        generate(a, Vec::new()).unwrap();
+        generate(a, GenerationOptions::default(), Vec::new()).unwrap();
     }
 
     #[test]
@@ -357,7 +1080,125 @@ mod tests {
         let _ = b.mem("m", 1, 1);
 
         // Panic
-        generate(a, Vec::new()).unwrap();
+        generate(a, GenerationOptions::default(), Vec::new()).unwrap();
+    }
+
+    #[test]
+    fn case_statement() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        let sel = m.input("sel", 2);
+        let a = m.input("a", 8);
+        let b = m.input("b", 8);
+        m.output(
+            "o",
+            m.case(
+                sel,
+                &[(0u32, a), (2u32, b), (3u32, a & b)],
+                m.lit(0xffu32, 8),
+            ),
+        );
+
+        let mut v = Vec::new();
+        generate(m, GenerationOptions::default(), &mut v).unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        assert!(v.contains(
+            "    wire [7:0] __temp_m_0;
+    reg [7:0] __temp_m_1;
+
+    assign __temp_m_0 = a & b;
+    assign o = __temp_m_1;
+
+    always @(*) begin
+        case (sel)
+            2'h0: __temp_m_1 = a;
+            2'h2: __temp_m_1 = b;
+            2'h3: __temp_m_1 = __temp_m_0;
+            default: __temp_m_1 = 8'hff;
+        endcase
+    end
+"
+        ));
+
+        let mut v = Vec::new();
+        let options = GenerationOptions {
+            language_standard: LanguageStandard::SystemVerilog,
+            ..GenerationOptions::default()
+        };
+        generate(m, options, &mut v).unwrap();
+        let v = String::from_utf8(v).unwrap();
+        assert!(v.contains("    logic [7:0] __temp_m_1;"));
+        assert!(v.contains("    always_comb begin\n        case (sel)"));
+    }
+
+    #[test]
+    fn inout_ports() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        let data = m.inout("data", 8);
+        let oe = m.input("oe", 1);
+        data.drive(!data, oe);
+        let sda = m.inout("sda", 1);
+        m.output("sda_in", sda);
+
+        let mut v = Vec::new();
+        generate(m, GenerationOptions::default(), &mut v).unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        assert!(v.contains(
+            "    inout wire [7:0] data,
+    input wire oe,
+    inout wire sda,
+    output wire sda_in
+    );
+
+    wire [7:0] data_out;
+    wire data_oe;
+"
+        ));
+        assert!(v.contains("    assign data_out = __temp_m_0;\n"));
+        assert!(v.contains("    assign data = data_oe ? data_out : 8'bz;\n"));
+        // Undriven inouts are never assigned
+        assert!(!v.contains("assign sda ="));
+
+        let mut v = Vec::new();
+        let options = GenerationOptions {
+            language_standard: LanguageStandard::SystemVerilog,
+            ..GenerationOptions::default()
+        };
+        generate(m, options, &mut v).unwrap();
+        let v = String::from_utf8(v).unwrap();
+        assert!(v.contains("    inout wire [7:0] data,\n"));
+        assert!(v.contains("    logic [7:0] data_out;\n"));
+    }
+
+    #[test]
+    fn mem_comb_read_port() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        let mem = m.mem("mem", 2, 8);
+        mem.initial_contents(&[1u8, 2u8, 3u8, 4u8]);
+        mem.write_port(m.input("wa", 2), m.input("wv", 8), m.input("we", 1));
+        m.output("o", mem.read_port_comb(m.input("ra", 2)));
+
+        let mut v = Vec::new();
+        generate(m, GenerationOptions::default(), &mut v).unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        assert!(v.contains(
+            "    wire [7:0] __temp_m_0;
+
+    assign __temp_m_0 = __mem_m_mem_0[ra];
+    assign o = __temp_m_0;
+"
+        ));
+        // Only the write port is clocked
+        assert_eq!(v.matches("always @(posedge clk)").count(), 1);
+        assert!(!v.contains("read_port"));
     }
 
     #[test]
@@ -372,7 +1213,7 @@ mod tests {
         let _ = m.read_port(a.low(), a.low());
 
         // Panic
-        generate(a, Vec::new()).unwrap();
+        generate(a, GenerationOptions::default(), Vec::new()).unwrap();
     }
 
     #[test]
@@ -388,7 +1229,7 @@ mod tests {
         let _ = m.read_port(b.low(), b.low());
 
         // Panic
-        generate(a, Vec::new()).unwrap();
+        generate(a, GenerationOptions::default(), Vec::new()).unwrap();
     }
 
     #[test]
@@ -405,6 +1246,2797 @@ mod tests {
         a_i.drive(a_o);
 
         // Panic
-        generate(b, Vec::new()).unwrap();
+        generate(b, GenerationOptions::default(), Vec::new()).unwrap();
+    }
+
+    #[test]
+    fn reset_domain_ports() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let a_soft = a.reset_domain("soft");
+        let a_r = a.reg("r", 8);
+        a_r.default_value(0u32);
+        a_r.reset_domain(a_soft);
+        a_r.drive_next(a.input("a_i", 8));
+        a.output("a_o", a_r);
+        let a_always_on = a.reg("always_on", 8);
+        a_always_on.default_value(0u32);
+        a_always_on.drive_next(a.input("always_on_i", 8));
+        a.output("always_on_o", a_always_on);
+
+        let b = a.module("b", "B");
+        let b_soft = b.reset_domain("soft");
+        let b_r = b.reg("r", 8);
+        b_r.default_value(0u32);
+        b_r.reset_domain(b_soft);
+        let b_i = b.input("i", 8);
+        b_r.drive_next(b_i);
+        let b_o = b.output("o", b_r);
+        b_i.drive(a.input("b_i", 8));
+        a.output("b_o", b_o);
+
+        let mut v = Vec::new();
+        generate(
+            a,
+            GenerationOptions {
+                flatten: true,
+                ..GenerationOptions::default()
+            },
+            &mut v,
+        )
+        .unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        // Both "soft" domains share a single port, alongside the implicit reset
+        assert_eq!(v.matches("input wire reset_n,").count(), 1);
+        assert_eq!(v.matches("input wire reset_soft_n,").count(), 1);
+
+        // Registers in the same domain share a single always block, even across modules
+        assert_eq!(
+            v.matches("always @(posedge clk, negedge reset_soft_n) begin")
+                .count(),
+            1
+        );
+        assert_eq!(v.matches("if (~reset_soft_n) begin").count(), 1);
+        assert_eq!(
+            v.matches("always @(posedge clk, negedge reset_n) begin")
+                .count(),
+            1
+        );
+        assert_eq!(v.matches("if (~reset_n) begin").count(), 1);
+    }
+
+    #[test]
+    fn reg_always_blocks() {
+        let c = Context::new();
+
+        let m = c.module("reg_test_module", "RegTestModule");
+
+        let r1 = m.reg("r1", 32);
+        r1.default_value(0u32);
+        r1.drive_next(m.input("i1", 32));
+        m.output("o1", r1);
+
+        let r2 = m.reg("r2", 32);
+        r2.drive_next(m.input("i2", 32));
+        m.output("o2", r2);
+
+        let r3 = m.reg("r3", 1);
+        r3.default_value(true);
+        r3.drive_next(m.input("i3", 1));
+        m.output("o3", r3);
+
+        let mut v = Vec::new();
+        generate(m, GenerationOptions::default(), &mut v).unwrap();
+
+        assert_eq!(
+            String::from_utf8(v).unwrap(),
+            r#"module RegTestModule(
+    input wire reset_n,
+    input wire clk,
+
+    input wire [31:0] i1,
+    input wire [31:0] i2,
+    input wire i3,
+    output wire [31:0] o1,
+    output wire [31:0] o2,
+    output wire o3
+    );
+
+    reg [31:0] __reg_reg_test_module_r1_0;
+    wire [31:0] __reg_reg_test_module_r1_0_next;
+    reg [31:0] __reg_reg_test_module_r2_1;
+    wire [31:0] __reg_reg_test_module_r2_1_next;
+    reg __reg_reg_test_module_r3_2;
+    wire __reg_reg_test_module_r3_2_next;
+
+    always @(posedge clk) begin
+        __reg_reg_test_module_r2_1 <= __reg_reg_test_module_r2_1_next;
+    end
+
+    always @(posedge clk, negedge reset_n) begin
+        if (~reset_n) begin
+            __reg_reg_test_module_r1_0 <= 32'h0;
+            __reg_reg_test_module_r3_2 <= 1'h1;
+        end
+        else begin
+            __reg_reg_test_module_r1_0 <= __reg_reg_test_module_r1_0_next;
+            __reg_reg_test_module_r3_2 <= __reg_reg_test_module_r3_2_next;
+        end
+    end
+
+    assign o1 = __reg_reg_test_module_r1_0;
+    assign o2 = __reg_reg_test_module_r2_1;
+    assign o3 = __reg_reg_test_module_r3_2;
+    assign __reg_reg_test_module_r1_0_next = i1;
+    assign __reg_reg_test_module_r2_1_next = i2;
+    assign __reg_reg_test_module_r3_2_next = i3;
+
+endmodule
+
+"#
+        );
+    }
+
+    #[test]
+    fn negedge_reg_always_blocks() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+
+        let pos = m.reg("pos", 8);
+        pos.default_value(0u32);
+        pos.drive_next(m.input("i", 8));
+
+        let neg = m.reg("neg", 8);
+        neg.clock_edge(Edge::Neg);
+        neg.default_value(0u32);
+        neg.drive_next(pos);
+
+        let neg_domain = m.reg_in_domain("neg_domain", 8, "clk_b");
+        neg_domain.clock_edge(Edge::Neg);
+        neg_domain.drive_next(neg);
+        m.output("o", neg_domain);
+
+        let mut v = Vec::new();
+        generate(m, GenerationOptions::default(), &mut v).unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        assert!(v.contains(
+            "    always @(posedge clk, negedge reset_n) begin
+        if (~reset_n) begin
+            __reg_m_pos_2 <= 8'h0;
+        end"
+        ));
+        assert!(v.contains(
+            "    always @(negedge clk, negedge reset_n) begin
+        if (~reset_n) begin
+            __reg_m_neg_1 <= 8'h0;
+        end"
+        ));
+        assert!(v.contains(
+            "    always @(negedge clk_b) begin
+        __reg_m_neg_domain_0 <= __reg_m_neg_domain_0_next;
+    end"
+        ));
+
+        let mut v = Vec::new();
+        let options = GenerationOptions {
+            language_standard: LanguageStandard::SystemVerilog,
+            ..GenerationOptions::default()
+        };
+        generate(m, options, &mut v).unwrap();
+        let v = String::from_utf8(v).unwrap();
+        assert!(v.contains("always_ff @(negedge clk, negedge reset_n) begin"));
+        assert!(v.contains("always_ff @(negedge clk_b) begin"));
+    }
+
+    #[test]
+    fn combinational_only() {
+        let c = Context::new();
+
+        // `clk` is an ordinary input, since there's no implicit clock to collide with
+        let m = c.module("m", "M");
+        let inv = m.module("inv", "Inverter");
+        let i = inv.input("i", 1);
+        inv.output("o", !i);
+        i.drive(m.input("clk", 1));
+        m.output("o", inv.get_output("o"));
+
+        let mut v = Vec::new();
+        let options = GenerationOptions {
+            combinational_only: true,
+            ..GenerationOptions::default()
+        };
+        generate(m, options, &mut v).unwrap();
+
+        assert_eq!(
+            String::from_utf8(v).unwrap(),
+            r#"module Inverter(
+    input wire i,
+    output wire o
+    );
+
+    wire __temp_m_inv_0;
+
+    assign __temp_m_inv_0 = ~i;
+    assign o = __temp_m_inv_0;
+
+endmodule
+
+module M(
+    input wire clk,
+    output wire o
+    );
+
+    wire __inst_inv_i;
+    wire __inst_inv_o;
+
+    Inverter inv(
+        .i(__inst_inv_i),
+        .o(__inst_inv_o)
+    );
+
+    assign o = __inst_inv_o;
+    assign __inst_inv_i = clk;
+
+endmodule
+
+"#
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot generate combinational-only code for module \"M\" because module \"Inner\" contains a register called \"r\". Combinational-only modules can't contain registers, memories, assertions, or coverage points."
+    )]
+    fn combinational_only_reg_error() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        let inner = m.module("inner", "Inner");
+        let i = inner.input("i", 1);
+        let r = inner.reg("r", 1);
+        r.drive_next(i);
+        inner.output("o", r);
+        i.drive(m.input("i", 1));
+        m.output("o", inner.get_output("o"));
+
+        let options = GenerationOptions {
+            combinational_only: true,
+            ..GenerationOptions::default()
+        };
+
+        // Panic
+        generate(m, options, Vec::new()).unwrap();
+    }
+
+    #[test]
+    fn instance_array_names() {
+        let c = Context::new();
+
+        let lane = c.declare_module("Lane");
+        lane.define(|m| {
+            m.output("o", !m.input("i", 2));
+        });
+
+        let m = c.module("m", "M");
+        let i = m.input("i", 6);
+        let lanes = m.instance_array("lane", &lane, 3);
+        lanes.drive_input_each("i", |n| i.bits(n as u32 * 2 + 1, n as u32 * 2));
+        m.output("o", lanes.collect_outputs_concat("o"));
+
+        let mut v = Vec::new();
+        generate(m, GenerationOptions::default(), &mut v).unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        // A single definition is shared by all instances
+        assert_eq!(v.matches("module Lane(").count(), 1);
+        for n in 0..3 {
+            assert!(v.contains(&format!("    Lane lane_{}(\n", n)));
+            assert!(v.contains(&format!("        .o(__inst_lane_{}_o)\n", n)));
+        }
+        // The first instance's output is in the lower bits
+        assert!(v.contains("{__inst_lane_2_o, __inst_lane_1_o}"));
+    }
+
+    #[test]
+    fn attribute_pass_through() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let i = a.input("i", 8);
+        i.attribute("my_tool", "pin", "A7");
+        let r = a.reg("r", 8);
+        r.attribute("xilinx", "async_reg", "true");
+        r.attribute("my_tool", "note", "say \"hi\"");
+        r.drive_next(i);
+        let o = a.output("o", r);
+        o.attribute("my_tool", "pin", "B3");
+
+        let b = a.module("b", "B");
+        let b_i = b.input("i", 8);
+        b_i.attribute("my_tool", "probe", "yes");
+        let b_o = b.output("o", b_i);
+        b_i.drive(i);
+        a.output("b_o", b_o);
+
+        let mut v = Vec::new();
+        generate(
+            a,
+            GenerationOptions {
+                flatten: true,
+                ..GenerationOptions::default()
+            },
+            &mut v,
+        )
+        .unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        assert!(v.contains("    (* my_tool_pin = \"A7\" *) input wire [7:0] i,\n"));
+        assert!(v.contains("    (* my_tool_pin = \"B3\" *) output wire [7:0] o\n"));
+        assert!(v.contains(
+            "    (* xilinx_async_reg = \"true\", my_tool_note = \"say \\\"hi\\\"\" *) reg [7:0] __reg_a_r_"
+        ));
+        assert!(v.contains("    (* my_tool_probe = \"yes\" *) wire [7:0] __temp_a_b_i_"));
+    }
+
+    #[test]
+    fn nested_instantiation_hierarchy() {
+        let c = Context::new();
+
+        fn inner<'a>(instance_name: &str, p: &'a impl ModuleParent<'a>) -> &'a Module<'a> {
+            let m = p.module(instance_name, "NestedInstantiationTestModuleInner");
+            let inner_inner = m.module("inner", "NestedInstantiationTestModuleInnerInner");
+            let inner_inner_i = inner_inner.input("i", 32);
+            let inner_inner_o = inner_inner.output("o", inner_inner_i);
+            let i1 = m.input("i1", 32);
+            let i2 = m.input("i2", 32);
+            inner_inner_i.drive(i1 & i2);
+            m.output("o", inner_inner_o);
+            m
+        }
+
+        let m = c.module(
+            "nested_instantiation_test_module",
+            "NestedInstantiationTestModule",
+        );
+        let inner1 = inner("inner1", m);
+        inner1.inputs.borrow()["i1"].drive(m.input("i1", 32));
+        inner1.inputs.borrow()["i2"].drive(m.input("i2", 32));
+        let inner2 = inner("inner2", m);
+        inner2.inputs.borrow()["i1"].drive(m.input("i3", 32));
+        inner2.inputs.borrow()["i2"].drive(m.input("i4", 32));
+        let inner3 = inner("inner3", m);
+        inner3.inputs.borrow()["i1"].drive(inner1.outputs.borrow()["o"]);
+        inner3.inputs.borrow()["i2"].drive(inner2.outputs.borrow()["o"]);
+        m.output("o", inner3.outputs.borrow()["o"]);
+
+        let mut v = Vec::new();
+        generate(m, GenerationOptions::default(), &mut v).unwrap();
+
+        assert_eq!(
+            String::from_utf8(v).unwrap(),
+            r#"module NestedInstantiationTestModuleInnerInner(
+    input wire reset_n,
+    input wire clk,
+
+    input wire [31:0] i,
+    output wire [31:0] o
+    );
+
+    assign o = i;
+
+endmodule
+
+module NestedInstantiationTestModuleInner(
+    input wire reset_n,
+    input wire clk,
+
+    input wire [31:0] i1,
+    input wire [31:0] i2,
+    output wire [31:0] o
+    );
+
+    wire [31:0] __inst_inner_i;
+    wire [31:0] __inst_inner_o;
+
+    NestedInstantiationTestModuleInnerInner inner(
+        .reset_n(reset_n),
+        .clk(clk),
+        .i(__inst_inner_i),
+        .o(__inst_inner_o)
+    );
+
+    wire [31:0] __temp_nested_instantiation_test_module_inner1_0;
+
+    assign o = __inst_inner_o;
+    assign __temp_nested_instantiation_test_module_inner1_0 = i1 & i2;
+    assign __inst_inner_i = __temp_nested_instantiation_test_module_inner1_0;
+
+endmodule
+
+module NestedInstantiationTestModule(
+    input wire reset_n,
+    input wire clk,
+
+    input wire [31:0] i1,
+    input wire [31:0] i2,
+    input wire [31:0] i3,
+    input wire [31:0] i4,
+    output wire [31:0] o
+    );
+
+    wire [31:0] __inst_inner1_i1;
+    wire [31:0] __inst_inner1_i2;
+    wire [31:0] __inst_inner1_o;
+    wire [31:0] __inst_inner2_i1;
+    wire [31:0] __inst_inner2_i2;
+    wire [31:0] __inst_inner2_o;
+    wire [31:0] __inst_inner3_i1;
+    wire [31:0] __inst_inner3_i2;
+    wire [31:0] __inst_inner3_o;
+
+    NestedInstantiationTestModuleInner inner1(
+        .reset_n(reset_n),
+        .clk(clk),
+        .i1(__inst_inner1_i1),
+        .i2(__inst_inner1_i2),
+        .o(__inst_inner1_o)
+    );
+
+    NestedInstantiationTestModuleInner inner2(
+        .reset_n(reset_n),
+        .clk(clk),
+        .i1(__inst_inner2_i1),
+        .i2(__inst_inner2_i2),
+        .o(__inst_inner2_o)
+    );
+
+    NestedInstantiationTestModuleInner inner3(
+        .reset_n(reset_n),
+        .clk(clk),
+        .i1(__inst_inner3_i1),
+        .i2(__inst_inner3_i2),
+        .o(__inst_inner3_o)
+    );
+
+    assign o = __inst_inner3_o;
+    assign __inst_inner1_i1 = i1;
+    assign __inst_inner1_i2 = i2;
+    assign __inst_inner2_i1 = i3;
+    assign __inst_inner2_i2 = i4;
+    assign __inst_inner3_i1 = __inst_inner1_o;
+    assign __inst_inner3_i2 = __inst_inner2_o;
+
+endmodule
+
+"#
+        );
+    }
+
+    #[test]
+    fn hierarchy_passes_reset_domains_to_instances() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let b = a.module("b", "B");
+        let soft = b.reset_domain("soft");
+        let r = b.reg("r", 8);
+        r.default_value(0u32);
+        r.reset_domain(soft);
+        let b_i = b.input("i", 8);
+        r.drive_next(b_i);
+        let b_o = b.output("o", r);
+        b_i.drive(a.input("i", 8));
+        a.output("o", b_o);
+
+        let mut v = Vec::new();
+        generate(a, GenerationOptions::default(), &mut v).unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        // A has no registers of its own, but still needs the port to pass it down to B
+        assert_eq!(v.matches("input wire reset_soft_n,").count(), 2);
+        assert!(v.contains("        .reset_soft_n(reset_soft_n),\n"));
+        assert_eq!(
+            v.matches("always @(posedge clk, negedge reset_soft_n) begin")
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn shared_leaf_is_defined_once() {
+        let c = Context::new();
+
+        fn leaf<'a>(p: &'a impl ModuleParent<'a>) -> &'a Module<'a> {
+            let m = p.module("leaf", "Leaf");
+            let i = m.input("i", 4);
+            m.output("o", !i);
+            m
+        }
+
+        fn parent<'a>(
+            instance_name: &str,
+            name: &str,
+            p: &'a impl ModuleParent<'a>,
+        ) -> &'a Module<'a> {
+            let m = p.module(instance_name, name);
+            let leaf = leaf(m);
+            leaf.inputs.borrow()["i"].drive(m.input("i", 4));
+            m.output("o", leaf.outputs.borrow()["o"]);
+            m
+        }
+
+        let top = c.module("top", "Top");
+        let left = parent("left", "Left", top);
+        left.inputs.borrow()["i"].drive(top.input("i", 4));
+        let right = parent("right", "Right", top);
+        right.inputs.borrow()["i"].drive(left.outputs.borrow()["o"]);
+        top.output("o", right.outputs.borrow()["o"]);
+
+        // Not instantiated from `top`, so it shouldn't be emitted
+        let unused = c.module("unused", "Unused");
+        unused.output("o", unused.input("i", 1));
+
+        let mut v = Vec::new();
+        generate(top, GenerationOptions::default(), &mut v).unwrap();
+
+        assert_eq!(
+            String::from_utf8(v).unwrap(),
+            r#"module Leaf(
+    input wire reset_n,
+    input wire clk,
+
+    input wire [3:0] i,
+    output wire [3:0] o
+    );
+
+    wire [3:0] __temp_top_left_leaf_0;
+
+    assign __temp_top_left_leaf_0 = ~i;
+    assign o = __temp_top_left_leaf_0;
+
+endmodule
+
+module Left(
+    input wire reset_n,
+    input wire clk,
+
+    input wire [3:0] i,
+    output wire [3:0] o
+    );
+
+    wire [3:0] __inst_leaf_i;
+    wire [3:0] __inst_leaf_o;
+
+    Leaf leaf(
+        .reset_n(reset_n),
+        .clk(clk),
+        .i(__inst_leaf_i),
+        .o(__inst_leaf_o)
+    );
+
+    assign o = __inst_leaf_o;
+    assign __inst_leaf_i = i;
+
+endmodule
+
+module Right(
+    input wire reset_n,
+    input wire clk,
+
+    input wire [3:0] i,
+    output wire [3:0] o
+    );
+
+    wire [3:0] __inst_leaf_i;
+    wire [3:0] __inst_leaf_o;
+
+    Leaf leaf(
+        .reset_n(reset_n),
+        .clk(clk),
+        .i(__inst_leaf_i),
+        .o(__inst_leaf_o)
+    );
+
+    assign o = __inst_leaf_o;
+    assign __inst_leaf_i = i;
+
+endmodule
+
+module Top(
+    input wire reset_n,
+    input wire clk,
+
+    input wire [3:0] i,
+    output wire [3:0] o
+    );
+
+    wire [3:0] __inst_left_i;
+    wire [3:0] __inst_left_o;
+    wire [3:0] __inst_right_i;
+    wire [3:0] __inst_right_o;
+
+    Left left(
+        .reset_n(reset_n),
+        .clk(clk),
+        .i(__inst_left_i),
+        .o(__inst_left_o)
+    );
+
+    Right right(
+        .reset_n(reset_n),
+        .clk(clk),
+        .i(__inst_right_i),
+        .o(__inst_right_o)
+    );
+
+    assign o = __inst_right_o;
+    assign __inst_left_i = i;
+    assign __inst_right_i = __inst_left_o;
+
+endmodule
+
+"#
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot generate Verilog for module \"Leaf\", because instances \"top.left.leaf\" and \"top.right.leaf\" have different inputs or outputs. Only one definition is emitted for each module name, so all instances with the same module name must have the same inputs and outputs."
+    )]
+    fn module_name_collision_error() {
+        let c = Context::new();
+
+        let top = c.module("top", "Top");
+        let left = top.module("left", "Left");
+        let left_leaf = left.module("leaf", "Leaf");
+        left_leaf.output("o", left_leaf.input("i", 4));
+        left_leaf.inputs.borrow()["i"].drive(left.input("i", 4));
+        left.output("o", left_leaf.outputs.borrow()["o"]);
+        left.inputs.borrow()["i"].drive(top.input("i", 4));
+        let right = top.module("right", "Right");
+        let right_leaf = right.module("leaf", "Leaf");
+        right_leaf.output("o", right_leaf.input("i", 8));
+        right_leaf.inputs.borrow()["i"].drive(right.input("i", 8));
+        right.output("o", right_leaf.outputs.borrow()["o"]);
+        right.inputs.borrow()["i"].drive(top.input("j", 8));
+        top.output("o1", left.outputs.borrow()["o"]);
+        top.output("o2", right.outputs.borrow()["o"]);
+
+        let mut v = Vec::new();
+
+        // Panic
+        generate(top, GenerationOptions::default(), &mut v).unwrap();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot generate Verilog for module \"AddK\", because instances \"top.a1\" and \"top.a2\" have different contents. Only one definition is emitted for each module name, so all instances with the same module name must be structurally identical."
+    )]
+    fn module_content_collision_error() {
+        let c = Context::new();
+
+        let top = c.module("top", "Top");
+        let a1 = top.module("a1", "AddK");
+        let a1_i = a1.input("i", 8);
+        let a1_o = a1.output("o", a1_i + a1.lit(1u32, 8));
+        a1_i.drive(top.input("i", 8));
+        let a2 = top.module("a2", "AddK");
+        let a2_i = a2.input("i", 8);
+        let a2_o = a2.output("o", a2_i + a2.lit(2u32, 8));
+        a2_i.drive(a1_o);
+        top.output("o", a2_o);
+
+        // Panic
+        generate(top, GenerationOptions::default(), Vec::new()).unwrap();
+    }
+
+    #[test]
+    fn parameterized_module_definitions() {
+        let c = Context::new();
+
+        fn adder<'a>(
+            instance_name: &str,
+            p: &'a impl ModuleParent<'a>,
+            bit_width: u32,
+        ) -> &'a Module<'a> {
+            p.module_parameterized(
+                instance_name,
+                "Adder",
+                ModuleParams::new().with("w", bit_width as _),
+                |m, params| {
+                    let bit_width = params.get("w") as u32;
+                    let a = m.input("a", bit_width);
+                    let b = m.input("b", bit_width);
+                    m.output("o", a + b);
+                },
+            )
+        }
+
+        let top = c.module("top", "Top");
+        for (index, &bit_width) in [8, 8, 16].iter().enumerate() {
+            let adder = adder(&format!("adder{}", index), top, bit_width);
+            let name = |port: &str| format!("{}{}", port, index);
+            adder.inputs.borrow()["a"].drive(top.input(name("a"), bit_width));
+            adder.inputs.borrow()["b"].drive(top.input(name("b"), bit_width));
+            top.output(name("o"), adder.outputs.borrow()["o"]);
+        }
+
+        let mut v = Vec::new();
+        generate(top, GenerationOptions::default(), &mut v).unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        assert_eq!(v.matches("module Adder_w8(").count(), 1);
+        assert_eq!(v.matches("module Adder_w16(").count(), 1);
+        assert_eq!(v.matches("    Adder_w8 adder").count(), 2);
+        assert_eq!(v.matches("    Adder_w16 adder").count(), 1);
+    }
+
+    #[test]
+    fn reverse_bits_and_swap_bytes() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        let i1 = m.input("i1", 1);
+        let i4 = m.input("i4", 4);
+        let i8 = m.input("i8", 8);
+        let i24 = m.input("i24", 24);
+        m.output("reversed1", i1.reverse_bits());
+        m.output("reversed4", i4.reverse_bits());
+        m.output("swapped8", i8.swap_bytes());
+        m.output("swapped24", i24.swap_bytes());
+
+        let mut v = Vec::new();
+        generate(m, GenerationOptions::default(), &mut v).unwrap();
+
+        assert_eq!(
+            String::from_utf8(v).unwrap(),
+            r#"module M(
+    input wire reset_n,
+    input wire clk,
+
+    input wire i1,
+    input wire [23:0] i24,
+    input wire [3:0] i4,
+    input wire [7:0] i8,
+    output wire reversed1,
+    output wire [3:0] reversed4,
+    output wire [23:0] swapped24,
+    output wire [7:0] swapped8
+    );
+
+    wire [3:0] __temp_m_0;
+    wire [23:0] __temp_m_1;
+
+    assign reversed1 = i1;
+    assign __temp_m_0 = {i4[0], i4[1], i4[2], i4[3]};
+    assign reversed4 = __temp_m_0;
+    assign __temp_m_1 = {i24[7:0], i24[15:8], i24[23:16]};
+    assign swapped24 = __temp_m_1;
+    assign swapped8 = i8;
+
+endmodule
+
+"#
+        );
+    }
+
+    #[test]
+    fn generation_is_repeatable() {
+        for &flatten in &[false, true] {
+            assert_repeatable(|| {
+                let c = Context::new();
+                let m = repeatability_module(&c);
+
+                let mut v = Vec::new();
+                generate(
+                    m,
+                    GenerationOptions {
+                        flatten,
+                        ..GenerationOptions::default()
+                    },
+                    &mut v,
+                )
+                .unwrap();
+                String::from_utf8(v).unwrap()
+            });
+        }
+    }
+
+    #[test]
+    fn override_clock_and_reset_names() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let soft = a.reset_domain("soft");
+        let r1 = a.reg("r1", 8);
+        r1.default_value(0u32);
+        r1.drive_next(a.input("i1", 8));
+        a.output("o1", r1);
+        let r2 = a.reg("r2", 8);
+        r2.default_value(0u32);
+        r2.reset_domain(soft);
+        r2.drive_next(a.input("i2", 8));
+        a.output("o2", r2);
+        let b = a.module("b", "B");
+        let b_i = b.input("i", 8);
+        b_i.drive(a.input("i3", 8));
+        a.output("o3", b.output("o", b_i));
+
+        let mut v = Vec::new();
+        generate(
+            a,
+            GenerationOptions {
+                override_clock_name: Some("clk_i".into()),
+                override_reset_name: Some("rst_ni".into()),
+                ..GenerationOptions::default()
+            },
+            &mut v,
+        )
+        .unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        assert!(v.contains(
+            "    input wire rst_ni,\n    input wire rst_ni_soft,\n    input wire clk_i,\n"
+        ));
+        assert!(v.contains("always @(posedge clk_i, negedge rst_ni) begin"));
+        assert!(v.contains("always @(posedge clk_i, negedge rst_ni_soft) begin"));
+        assert!(v.contains("        .rst_ni(rst_ni),\n        .clk_i(clk_i),\n"));
+        assert!(!v.contains("reset_n"));
+        assert!(!v.contains("clk)"));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot generate code for module \"A\" with clock name \"0clk\". Clock and reset names must be non-empty, consist only of ASCII alphanumeric characters and underscores, must not start with a digit, and must not be Verilog or SystemVerilog keywords."
+    )]
+    fn invalid_clock_name_error() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        a.output("o", a.input("i", 1));
+
+        // Panic
+        generate(
+            a,
+            GenerationOptions {
+                override_clock_name: Some("0clk".into()),
+                ..GenerationOptions::default()
+            },
+            Vec::new(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot generate code for module \"A\" with clock name \"wire\". Clock and reset names must be non-empty, consist only of ASCII alphanumeric characters and underscores, must not start with a digit, and must not be Verilog or SystemVerilog keywords."
+    )]
+    fn keyword_clock_name_error() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        a.output("o", a.input("i", 1));
+
+        // Panic
+        generate(
+            a,
+            GenerationOptions {
+                override_clock_name: Some("wire".into()),
+                ..GenerationOptions::default()
+            },
+            Vec::new(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot generate code for module \"A\" with reset name \"logic\". Clock and reset names must be non-empty, consist only of ASCII alphanumeric characters and underscores, must not start with a digit, and must not be Verilog or SystemVerilog keywords."
+    )]
+    fn keyword_reset_name_error() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        a.output("o", a.input("i", 1));
+
+        // Panic
+        generate(
+            a,
+            GenerationOptions {
+                override_reset_name: Some("logic".into()),
+                ..GenerationOptions::default()
+            },
+            Vec::new(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot generate code for module \"A\" with reset name \"rst_ni\" because module \"B\" contains an input or output with the same name."
+    )]
+    fn reset_name_port_collision_error() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let b = a.module("b", "B");
+        let b_i = b.input("rst_ni", 1);
+        b_i.drive(a.input("i", 1));
+        a.output("o", b.output("o", b_i));
+
+        // Panic
+        generate(
+            a,
+            GenerationOptions {
+                override_reset_name: Some("rst_ni".into()),
+                ..GenerationOptions::default()
+            },
+            Vec::new(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot generate code for module \"A\" because the clock and reset names are both \"clk\". Clock and reset names must be different."
+    )]
+    fn same_clock_and_reset_name_error() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        a.output("o", a.input("i", 1));
+
+        // Panic
+        generate(
+            a,
+            GenerationOptions {
+                override_reset_name: Some("clk".into()),
+                ..GenerationOptions::default()
+            },
+            Vec::new(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn optimize_folds_constant_logic() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let i = a.input("i", 8);
+        let s = a.input("s", 1);
+        a.output("o", a.mux(s, i, i) ^ a.lit(0u32, 8));
+        a.output("k", !!(a.lit(0x0fu32, 8) << a.lit(2u32, 2)));
+
+        let mut v = Vec::new();
+        generate(
+            a,
+            GenerationOptions {
+                optimize: true,
+                ..GenerationOptions::default()
+            },
+            &mut v,
+        )
+        .unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        assert!(v.contains("assign o = i;"));
+        assert!(v.contains("assign k = 8'h3c;"));
+        assert_eq!(v.matches("__temp").count(), 0);
+    }
+
+    #[test]
+    fn optimize_folds_constant_case() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let i = a.input("i", 8);
+        let j = a.input("j", 8);
+        let s = a.input("s", 2);
+        // Constant selector
+        a.output(
+            "o",
+            a.case(a.lit(1u32, 2), &[(0u32, j), (1u32, i)], a.lit(0u32, 8)),
+        );
+        // Every case selects the same value
+        a.output("p", a.case(s, &[(0u32, j), (3u32, j)], j));
+
+        let mut v = Vec::new();
+        generate(
+            a,
+            GenerationOptions {
+                optimize: true,
+                ..GenerationOptions::default()
+            },
+            &mut v,
+        )
+        .unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        assert!(v.contains("assign o = i;"));
+        assert!(v.contains("assign p = j;"));
+        assert!(!v.contains("case"));
+    }
+
+    #[test]
+    fn optimize_folds_constant_registers() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let staged = a
+            .lit(0x5au32, 8)
+            .reg_next_with_default("stage0", 0x5au32)
+            .reg_next_with_default("stage1", 0x5au32);
+        a.output("o", staged);
+
+        let mut v = Vec::new();
+        generate(
+            a,
+            GenerationOptions {
+                optimize: true,
+                ..GenerationOptions::default()
+            },
+            &mut v,
+        )
+        .unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        assert!(v.contains("// Folded constant register \"stage1\" in module \"A\""));
+        assert!(v.contains("// Folded constant register \"stage0\" in module \"A\""));
+        assert!(v.contains("assign o = 8'h5a;"));
+        assert!(!v.contains("always"));
+    }
+
+    #[test]
+    fn optimize_keeps_registers_with_mismatched_default() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let staged = a
+            .lit(0x5au32, 8)
+            .reg_next_with_default("stage0", 0x5au32)
+            .reg_next_with_default("stage1", 0u32);
+        a.output("o", staged);
+
+        let mut v = Vec::new();
+        generate(
+            a,
+            GenerationOptions {
+                optimize: true,
+                ..GenerationOptions::default()
+            },
+            &mut v,
+        )
+        .unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        // stage0 is still constant, but stage1 resets to a different value, so it must be kept
+        assert!(v.contains("// Folded constant register \"stage0\" in module \"A\""));
+        assert!(!v.contains("\"stage1\""));
+        assert!(v.contains("__reg_a_stage1_0_next = 8'h5a;"));
+    }
+
+    #[test]
+    fn optimize_keeps_registers_with_attributes() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let r = a.reg("r", 8);
+        r.default_value(0x5au32);
+        r.attribute("synthesis", "keep", "true");
+        r.drive_next(a.lit(0x5au32, 8));
+        a.output("o", r);
+
+        let mut v = Vec::new();
+        generate(
+            a,
+            GenerationOptions {
+                optimize: true,
+                ..GenerationOptions::default()
+            },
+            &mut v,
+        )
+        .unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        assert!(!v.contains("Folded"));
+        assert!(v.contains("(* synthesis_keep = \"true\" *)"));
+        assert!(v.contains("assign o = __reg_a_r_0;"));
+    }
+
+    #[test]
+    fn inverter_is_verilog_2001() {
+        let c = Context::new();
+
+        let inverter = c.module("inverter", "Inverter");
+        let i = inverter.input("i", 1);
+        inverter.output("o", !i);
+
+        let mut v = Vec::new();
+        generate(inverter, GenerationOptions::default(), &mut v).unwrap();
+
+        assert_eq!(
+            String::from_utf8(v).unwrap(),
+            r#"module Inverter(
+    input wire reset_n,
+    input wire clk,
+
+    input wire i,
+    output wire o
+    );
+
+    wire __temp_inverter_0;
+
+    assign __temp_inverter_0 = ~i;
+    assign o = __temp_inverter_0;
+
+endmodule
+
+"#
+        );
+    }
+
+    #[test]
+    fn no_system_verilog_constructs() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let soft = a.reset_domain("soft");
+        let r1 = a.reg("r1", 8);
+        r1.default_value(0u32);
+        r1.drive_next(a.input("i1", 8));
+        let r2 = a.reg("r2", 8);
+        r2.default_value(0u32);
+        r2.reset_domain(soft);
+        r2.drive_next(!r1);
+        let mem = a.mem("mem", 2, 8);
+        mem.write_port(a.input("addr", 2), r2, a.input("we", 1));
+        a.output("o", mem.read_port(a.input("addr2", 2), a.high()));
+
+        for flatten in [false, true].iter().cloned() {
+            let mut v = Vec::new();
+            generate(
+                a,
+                GenerationOptions {
+                    flatten,
+                    ..GenerationOptions::default()
+                },
+                &mut v,
+            )
+            .unwrap();
+            let v = String::from_utf8(v).unwrap();
+
+            for keyword in &["logic", "always_ff", "always_comb", "always_latch"] {
+                assert!(!v.contains(keyword));
+            }
+            assert!(v.contains("always @(posedge clk, negedge reset_n) begin"));
+            assert!(v.contains("always @(posedge clk, negedge reset_soft_n) begin"));
+        }
+    }
+
+    #[test]
+    fn named_wire_keep() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let i1 = a.input("i1", 8);
+        let i2 = a.input("i2", 8);
+        let sum = a.named_wire("sum", i1 + i2);
+        a.output("o", sum ^ i1);
+
+        let b = a.module("b", "B");
+        let b_i = b.input("i", 1);
+        let b_o = b.output("o", b.named_wire("probe", !b_i));
+        b_i.drive(i1.bit(0));
+        a.output("b_o", b_o);
+
+        let mut v = Vec::new();
+        generate(a, GenerationOptions::default(), &mut v).unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        assert!(v.contains("(* keep = \"true\" *) wire [7:0] sum;"));
+        assert!(v.contains("assign sum = __temp_a_0;"));
+        assert!(v.contains("(* keep = \"true\" *) wire probe;"));
+
+        let mut v = Vec::new();
+        generate(
+            a,
+            GenerationOptions {
+                flatten: true,
+                ..GenerationOptions::default()
+            },
+            &mut v,
+        )
+        .unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        assert!(v.contains("(* keep = \"true\" *) wire [7:0] sum;"));
+        assert!(v.contains("(* keep = \"true\" *) wire a_b_probe;"));
+    }
+
+    #[test]
+    fn generated_wire_name_collisions() {
+        let c = Context::new();
+
+        let top = c.module("top", "Top");
+        let i = top.input("i", 1);
+
+        // Instance port wires would both be called `__inst_a_b_c`
+        let a = top.module("a", "A");
+        let a_i = a.input("b_c", 1);
+        a.output("o", !a_i);
+        a_i.drive(i);
+        let a_b = top.module("a_b", "AB");
+        let a_b_i = a_b.input("c", 1);
+        a_b.output("o", a_b_i);
+        a_b_i.drive(i);
+
+        // Flattened named wires would both be called `top_x_y_z`, like the top-level module's output
+        let x = top.module("x", "X");
+        x.output("o", x.named_wire("y_z", x.high()));
+        let x_y = top.module("x_y", "XY");
+        x_y.output("o", x_y.named_wire("z", x_y.low()));
+
+        top.output("a_b_c", a.get_output("o") ^ a_b.get_output("o"));
+        top.output("top_x_y_z", x.get_output("o") ^ x_y.get_output("o"));
+
+        let mut v = Vec::new();
+        generate(top, GenerationOptions::default(), &mut v).unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        assert!(v.contains("    wire __inst_a_b_c;\n"));
+        assert!(v.contains("    wire __inst_a_b_c_1;\n"));
+        assert!(v.contains("        .b_c(__inst_a_b_c),\n"));
+        assert!(v.contains("        .c(__inst_a_b_c_1),\n"));
+
+        let mut v = Vec::new();
+        generate(
+            top,
+            GenerationOptions {
+                flatten: true,
+                ..GenerationOptions::default()
+            },
+            &mut v,
+        )
+        .unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        // The top-level module's output keeps its name
+        assert!(v.contains("    output wire top_x_y_z\n"));
+        assert!(v.contains("(* keep = \"true\" *) wire top_x_y_z_1;"));
+        assert!(v.contains("(* keep = \"true\" *) wire top_x_y_z_2;"));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot generate code for module \"A\" because its named wire \"__temp_a_0\" would be called \"__temp_a_0\" in the generated Verilog code, and names starting with \"__\" are reserved for generated identifiers."
+    )]
+    fn reserved_local_name_error() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let i = a.input("i", 8);
+        a.output("o", a.named_wire("__temp_a_0", i + i));
+
+        // Panic
+        generate(a, GenerationOptions::default(), Vec::new()).unwrap();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot generate code for module \"A\" because its instance \"__inst\" would be called \"__inst\" in the generated Verilog code, and names starting with \"__\" are reserved for generated identifiers."
+    )]
+    fn reserved_instance_name_error() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let inner = a.module("__inst", "Inner");
+        inner.output("o", inner.high());
+        a.output("o", inner.get_output("o"));
+
+        // Panic
+        generate(a, GenerationOptions::default(), Vec::new()).unwrap();
+    }
+
+    #[test]
+    fn registered_outputs() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        let i = m.input("i", 8);
+        m.output("a", !i);
+        m.register_all_outputs();
+        m.output_registered("b", i);
+
+        let mut v = Vec::new();
+        generate(m, GenerationOptions::default(), &mut v).unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        assert!(v.contains("    reg [7:0] __reg_m_a_reg_0;\n"));
+        assert!(v.contains("    reg [7:0] __reg_m_b_reg_1;\n"));
+        assert!(v.contains("        __reg_m_a_reg_0 <= __reg_m_a_reg_0_next;\n"));
+        assert!(v.contains("        __reg_m_b_reg_1 <= __reg_m_b_reg_1_next;\n"));
+        assert!(v.contains("    assign a = __reg_m_a_reg_0;\n"));
+        assert!(v.contains("    assign b = __reg_m_b_reg_1;\n"));
+        assert!(v.contains("    assign __temp_m_0 = ~i;\n"));
+        assert!(v.contains("    assign __reg_m_a_reg_0_next = __temp_m_0;\n"));
+        assert!(v.contains("    assign __reg_m_b_reg_1_next = i;\n"));
+    }
+
+    #[test]
+    fn optimize_keeps_named_wires() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let i = a.input("i", 8);
+        a.output("o", a.named_wire("w", i | a.lit(0u32, 8)));
+
+        let mut v = Vec::new();
+        generate(
+            a,
+            GenerationOptions {
+                optimize: true,
+                ..GenerationOptions::default()
+            },
+            &mut v,
+        )
+        .unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        assert!(v.contains("(* keep = \"true\" *) wire [7:0] w;"));
+        assert!(v.contains("assign w = i;"));
+        assert!(v.contains("assign o = w;"));
+    }
+
+    #[test]
+    fn instance_names() {
+        let c = Context::new();
+
+        let cpu = c.module("cpu", "Cpu");
+        let i = cpu.input("i", 8);
+        let mut o: &dyn Signal = i;
+        for instance_name in &["decode_stage", "execute_stage"] {
+            let decoder = cpu.module(*instance_name, "Decoder");
+            let decoder_i = decoder.input("i", 8);
+            let r = decoder.reg("r", 8);
+            r.drive_next(decoder_i);
+            decoder_i.drive(o);
+            o = decoder.output("o", r);
+        }
+        cpu.output("o", o);
+
+        let generate_with_flatten = |flatten| {
+            let mut v = Vec::new();
+            generate(
+                cpu,
+                GenerationOptions {
+                    flatten,
+                    ..GenerationOptions::default()
+                },
+                &mut v,
+            )
+            .unwrap();
+            String::from_utf8(v).unwrap()
+        };
+
+        // Instance names are used as instance identifiers..
+        let v = generate_with_flatten(false);
+        assert!(v.contains("Decoder decode_stage("));
+        assert!(v.contains("Decoder execute_stage("));
+        // ..and for register names when instances are inlined
+        let v = generate_with_flatten(true);
+        assert!(v.contains("reg [7:0] __reg_cpu_decode_stage_r_"));
+        assert!(v.contains("reg [7:0] __reg_cpu_execute_stage_r_"));
+    }
+
+    #[test]
+    fn optimize_dont_care_outputs() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        let debug_enable = m.input("debug_enable", 1);
+        let data = m.input("data", 8);
+        let guarded = debug_enable.mux(data, m.lit(0u32, 8));
+        m.output("o", guarded);
+        m.output_dont_care_when("dont_care", guarded, !debug_enable);
+        m.output_dont_care_when("inverse_dont_care", guarded, debug_enable);
+        m.output_dont_care_when("always_dont_care", data + data, m.high());
+
+        let generate_with_optimize = |optimize| {
+            let mut v = Vec::new();
+            generate(
+                m,
+                GenerationOptions {
+                    optimize,
+                    ..GenerationOptions::default()
+                },
+                &mut v,
+            )
+            .unwrap();
+            String::from_utf8(v).unwrap()
+        };
+
+        // Don't care conditions have no effect without optimization
+        let v = generate_with_optimize(false);
+        assert!(v.contains("assign dont_care = __temp_m_1;"));
+        assert!(v.contains("assign inverse_dont_care = __temp_m_1;"));
+        assert!(!v.contains("assign always_dont_care = 8'h0;"));
+
+        // With optimization, the mux is replaced by the arm selected while the output is specified, and the output
+        //  that's never specified is replaced by a constant
+        let v = generate_with_optimize(true);
+        assert!(v.contains("assign o = __temp_m_0;"));
+        assert!(v.contains("assign dont_care = data;"));
+        assert!(v.contains("assign inverse_dont_care = 8'h0;"));
+        assert!(v.contains("assign always_dont_care = 8'h0;"));
+    }
+
+    fn reinterpret_module<'a>(c: &'a Context<'a>, reinterpret: bool) -> &'a Module<'a> {
+        let m = c.module("m", "A");
+        let a = m.input("a", 32);
+        let b = m.input("b", 32);
+        let sum = a + b;
+        let sum = if reinterpret {
+            sum.reinterpret("f32").reinterpret("raw")
+        } else {
+            sum
+        };
+        m.output("o1", sum & a);
+        m.output("o2", sum ^ b);
+        let r = m.reg("r", 32);
+        r.drive_next(sum);
+        m.output("o3", r);
+        let inner = m.module("inner", "Inner");
+        let inner_i = inner.input("i", 32);
+        inner_i.drive(if reinterpret { a.reinterpret("f32") } else { a });
+        m.output("o4", inner.output("o", !inner_i));
+        m
+    }
+
+    #[test]
+    fn reinterpret_has_no_effect() {
+        for &flatten in &[false, true] {
+            let generate_with_reinterpret = |reinterpret| {
+                let c = Context::new();
+                let mut v = Vec::new();
+                generate(
+                    reinterpret_module(&c, reinterpret),
+                    GenerationOptions {
+                        flatten,
+                        ..GenerationOptions::default()
+                    },
+                    &mut v,
+                )
+                .unwrap();
+                String::from_utf8(v).unwrap()
+            };
+
+            assert_eq!(
+                generate_with_reinterpret(true),
+                generate_with_reinterpret(false)
+            );
+        }
+    }
+
+    fn language_standard_module<'a>(c: &'a Context<'a>) -> &'a Module<'a> {
+        let m = c.module("m", "LanguageStandardTestModule");
+
+        let sel = m.input("sel", 1);
+        let a = m.input("a", 8);
+        let b = m.input("b", 8);
+
+        let r1 = m.reg("r1", 8);
+        r1.default_value(0u32);
+        r1.drive_next(sel.mux(a, b));
+        m.output("o1", r1);
+
+        let r2 = m.reg("r2", 8);
+        r2.drive_next(a);
+
+        let mem1 = m.mem("mem1", 2, 8);
+        mem1.write_port(a.bits(1, 0), r2, sel);
+        m.output("o2", mem1.read_port(b.bits(1, 0), sel));
+
+        let mem2 = m.mem("mem2", 1, 8);
+        mem2.initial_contents(&[0x12u32, 0x34u32]);
+        mem2.write_port(a.bit(0), b, !sel);
+        m.output("o3", mem2.read_port(b.bit(0), m.high()));
+
+        m
+    }
+
+    #[test]
+    fn language_standard_verilog_2001() {
+        let c = Context::new();
+
+        let mut v = Vec::new();
+        generate(
+            language_standard_module(&c),
+            GenerationOptions {
+                language_standard: LanguageStandard::Verilog2001,
+                ..GenerationOptions::default()
+            },
+            &mut v,
+        )
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(v).unwrap(),
+            r#"module LanguageStandardTestModule(
+    input wire reset_n,
+    input wire clk,
+
+    input wire [7:0] a,
+    input wire [7:0] b,
+    input wire sel,
+    output wire [7:0] o1,
+    output wire [7:0] o2,
+    output wire [7:0] o3
+    );
+
+    wire [1:0] __mem_m_mem1_0_read_port_0_address;
+    wire __mem_m_mem1_0_read_port_0_enable;
+    reg [7:0] __mem_m_mem1_0_read_port_0_value;
+    wire [1:0] __mem_m_mem1_0_write_port_address;
+    wire [7:0] __mem_m_mem1_0_write_port_value;
+    wire __mem_m_mem1_0_write_port_enable;
+    wire __mem_m_mem2_1_read_port_0_address;
+    wire __mem_m_mem2_1_read_port_0_enable;
+    reg [7:0] __mem_m_mem2_1_read_port_0_value;
+    wire __mem_m_mem2_1_write_port_address;
+    wire [7:0] __mem_m_mem2_1_write_port_value;
+    wire __mem_m_mem2_1_write_port_enable;
+    reg [7:0] __reg_m_r1_0;
+    wire [7:0] __reg_m_r1_0_next;
+    reg [7:0] __reg_m_r2_1;
+    wire [7:0] __reg_m_r2_1_next;
+
+    reg [7:0] __mem_m_mem1_0[0:3];
+
+    always @(posedge clk) begin
+        if (__mem_m_mem1_0_read_port_0_enable) begin
+            __mem_m_mem1_0_read_port_0_value <= __mem_m_mem1_0[__mem_m_mem1_0_read_port_0_address];
+        end
+        if (__mem_m_mem1_0_write_port_enable) begin
+            __mem_m_mem1_0[__mem_m_mem1_0_write_port_address] <= __mem_m_mem1_0_write_port_value;
+        end
+    end
+
+    reg [7:0] __mem_m_mem2_1[0:1];
+
+    initial begin
+        __mem_m_mem2_1[0] = 8'h12;
+        __mem_m_mem2_1[1] = 8'h34;
+    end
+
+    always @(posedge clk) begin
+        if (__mem_m_mem2_1_read_port_0_enable) begin
+            __mem_m_mem2_1_read_port_0_value <= __mem_m_mem2_1[__mem_m_mem2_1_read_port_0_address];
+        end
+        if (__mem_m_mem2_1_write_port_enable) begin
+            __mem_m_mem2_1[__mem_m_mem2_1_write_port_address] <= __mem_m_mem2_1_write_port_value;
+        end
+    end
+
+    always @(posedge clk) begin
+        __reg_m_r2_1 <= __reg_m_r2_1_next;
+    end
+
+    always @(posedge clk, negedge reset_n) begin
+        if (~reset_n) begin
+            __reg_m_r1_0 <= 8'h0;
+        end
+        else begin
+            __reg_m_r1_0 <= __reg_m_r1_0_next;
+        end
+    end
+
+    wire [1:0] __temp_m_0;
+    wire [1:0] __temp_m_1;
+    wire __temp_m_2;
+    wire __temp_m_3;
+    wire __temp_m_4;
+    wire [7:0] __temp_m_5;
+
+    assign o1 = __reg_m_r1_0;
+    assign o2 = __mem_m_mem1_0_read_port_0_value;
+    assign o3 = __mem_m_mem2_1_read_port_0_value;
+    assign __temp_m_0 = b[1:0];
+    assign __mem_m_mem1_0_read_port_0_address = __temp_m_0;
+    assign __mem_m_mem1_0_read_port_0_enable = sel;
+    assign __temp_m_1 = a[1:0];
+    assign __mem_m_mem1_0_write_port_address = __temp_m_1;
+    assign __mem_m_mem1_0_write_port_value = __reg_m_r2_1;
+    assign __mem_m_mem1_0_write_port_enable = sel;
+    assign __temp_m_2 = b[0];
+    assign __mem_m_mem2_1_read_port_0_address = __temp_m_2;
+    assign __mem_m_mem2_1_read_port_0_enable = 1'h1;
+    assign __temp_m_3 = a[0];
+    assign __mem_m_mem2_1_write_port_address = __temp_m_3;
+    assign __mem_m_mem2_1_write_port_value = b;
+    assign __temp_m_4 = ~sel;
+    assign __mem_m_mem2_1_write_port_enable = __temp_m_4;
+    assign __temp_m_5 = sel ? a : b;
+    assign __reg_m_r1_0_next = __temp_m_5;
+    assign __reg_m_r2_1_next = a;
+
+endmodule
+
+"#
+        );
+    }
+
+    #[test]
+    fn language_standard_system_verilog() {
+        let c = Context::new();
+
+        let mut v = Vec::new();
+        generate(
+            language_standard_module(&c),
+            GenerationOptions {
+                language_standard: LanguageStandard::SystemVerilog,
+                ..GenerationOptions::default()
+            },
+            &mut v,
+        )
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(v).unwrap(),
+            r#"module LanguageStandardTestModule(
+    input logic reset_n,
+    input logic clk,
+
+    input logic [7:0] a,
+    input logic [7:0] b,
+    input logic sel,
+    output logic [7:0] o1,
+    output logic [7:0] o2,
+    output logic [7:0] o3
+    );
+
+    logic [1:0] __mem_m_mem1_0_read_port_0_address;
+    logic __mem_m_mem1_0_read_port_0_enable;
+    logic [7:0] __mem_m_mem1_0_read_port_0_value;
+    logic [1:0] __mem_m_mem1_0_write_port_address;
+    logic [7:0] __mem_m_mem1_0_write_port_value;
+    logic __mem_m_mem1_0_write_port_enable;
+    logic __mem_m_mem2_1_read_port_0_address;
+    logic __mem_m_mem2_1_read_port_0_enable;
+    logic [7:0] __mem_m_mem2_1_read_port_0_value;
+    logic __mem_m_mem2_1_write_port_address;
+    logic [7:0] __mem_m_mem2_1_write_port_value;
+    logic __mem_m_mem2_1_write_port_enable;
+    logic [7:0] __reg_m_r1_0;
+    logic [7:0] __reg_m_r1_0_next;
+    logic [7:0] __reg_m_r2_1;
+    logic [7:0] __reg_m_r2_1_next;
+
+    logic [7:0] __mem_m_mem1_0[0:3];
+
+    always_ff @(posedge clk) begin
+        if (__mem_m_mem1_0_read_port_0_enable) begin
+            __mem_m_mem1_0_read_port_0_value <= __mem_m_mem1_0[__mem_m_mem1_0_read_port_0_address];
+        end
+        if (__mem_m_mem1_0_write_port_enable) begin
+            __mem_m_mem1_0[__mem_m_mem1_0_write_port_address] <= __mem_m_mem1_0_write_port_value;
+        end
+    end
+
+    logic [7:0] __mem_m_mem2_1[0:1];
+
+    initial begin
+        __mem_m_mem2_1[0] = 8'h12;
+        __mem_m_mem2_1[1] = 8'h34;
+    end
+
+    always @(posedge clk) begin
+        if (__mem_m_mem2_1_read_port_0_enable) begin
+            __mem_m_mem2_1_read_port_0_value <= __mem_m_mem2_1[__mem_m_mem2_1_read_port_0_address];
+        end
+        if (__mem_m_mem2_1_write_port_enable) begin
+            __mem_m_mem2_1[__mem_m_mem2_1_write_port_address] <= __mem_m_mem2_1_write_port_value;
+        end
+    end
+
+    always_ff @(posedge clk) begin
+        __reg_m_r2_1 <= __reg_m_r2_1_next;
+    end
+
+    always_ff @(posedge clk, negedge reset_n) begin
+        if (~reset_n) begin
+            __reg_m_r1_0 <= 8'h0;
+        end
+        else begin
+            __reg_m_r1_0 <= __reg_m_r1_0_next;
+        end
+    end
+
+    logic [1:0] __temp_m_0;
+    logic [1:0] __temp_m_1;
+    logic __temp_m_2;
+    logic __temp_m_3;
+    logic __temp_m_4;
+    logic [7:0] __temp_m_5;
+
+    assign o1 = __reg_m_r1_0;
+    assign o2 = __mem_m_mem1_0_read_port_0_value;
+    assign o3 = __mem_m_mem2_1_read_port_0_value;
+    assign __temp_m_0 = b[1:0];
+    assign __mem_m_mem1_0_read_port_0_address = __temp_m_0;
+    assign __mem_m_mem1_0_read_port_0_enable = sel;
+    assign __temp_m_1 = a[1:0];
+    assign __mem_m_mem1_0_write_port_address = __temp_m_1;
+    assign __mem_m_mem1_0_write_port_value = __reg_m_r2_1;
+    assign __mem_m_mem1_0_write_port_enable = sel;
+    assign __temp_m_2 = b[0];
+    assign __mem_m_mem2_1_read_port_0_address = __temp_m_2;
+    assign __mem_m_mem2_1_read_port_0_enable = 1'h1;
+    assign __temp_m_3 = a[0];
+    assign __mem_m_mem2_1_write_port_address = __temp_m_3;
+    assign __mem_m_mem2_1_write_port_value = b;
+    assign __temp_m_4 = ~sel;
+    assign __mem_m_mem2_1_write_port_enable = __temp_m_4;
+    assign __temp_m_5 = sel ? a : b;
+    assign __reg_m_r1_0_next = __temp_m_5;
+    assign __reg_m_r2_1_next = a;
+
+endmodule
+
+"#
+        );
+    }
+
+    #[test]
+    fn language_standard_default() {
+        assert_eq!(
+            GenerationOptions::default().language_standard,
+            LanguageStandard::Verilog2001
+        );
+    }
+
+    #[test]
+    fn dual_clock_mem() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let b = a.module("b", "B");
+
+        let write_addr = b.input("write_addr", 1);
+        let write_data = b.input("write_data", 8);
+        let read_addr = b.input("read_addr", 1);
+        let mem = b.mem("mem", 1, 8);
+        mem.dual_clock("read_clk", "write_clk");
+        mem.write_port(write_addr, write_data, b.high());
+        let read_data = b.output("read_data", mem.read_port(read_addr, b.high()));
+
+        write_addr.drive(a.input("write_addr", 1));
+        write_data.drive(a.input("write_data", 8));
+        read_addr.drive(a.input("read_addr", 1));
+        a.output("read_data", read_data);
+
+        let mut v = Vec::new();
+        generate(a, GenerationOptions::default(), &mut v).unwrap();
+
+        assert_eq!(
+            String::from_utf8(v).unwrap(),
+            r#"module B(
+    input wire reset_n,
+    input wire read_clk,
+    input wire write_clk,
+    input wire clk,
+
+    input wire read_addr,
+    input wire write_addr,
+    input wire [7:0] write_data,
+    output wire [7:0] read_data
+    );
+
+    wire __mem_a_b_mem_0_read_port_0_address;
+    wire __mem_a_b_mem_0_read_port_0_enable;
+    reg [7:0] __mem_a_b_mem_0_read_port_0_value;
+    wire __mem_a_b_mem_0_write_port_address;
+    wire [7:0] __mem_a_b_mem_0_write_port_value;
+    wire __mem_a_b_mem_0_write_port_enable;
+
+    reg [7:0] __mem_a_b_mem_0[0:1];
+
+    always @(posedge read_clk) begin
+        if (__mem_a_b_mem_0_read_port_0_enable) begin
+            __mem_a_b_mem_0_read_port_0_value <= __mem_a_b_mem_0[__mem_a_b_mem_0_read_port_0_address];
+        end
+    end
+
+    always @(posedge write_clk) begin
+        if (__mem_a_b_mem_0_write_port_enable) begin
+            __mem_a_b_mem_0[__mem_a_b_mem_0_write_port_address] <= __mem_a_b_mem_0_write_port_value;
+        end
+    end
+
+    assign read_data = __mem_a_b_mem_0_read_port_0_value;
+    assign __mem_a_b_mem_0_read_port_0_address = read_addr;
+    assign __mem_a_b_mem_0_read_port_0_enable = 1'h1;
+    assign __mem_a_b_mem_0_write_port_address = write_addr;
+    assign __mem_a_b_mem_0_write_port_value = write_data;
+    assign __mem_a_b_mem_0_write_port_enable = 1'h1;
+
+endmodule
+
+module A(
+    input wire reset_n,
+    input wire read_clk,
+    input wire write_clk,
+    input wire clk,
+
+    input wire read_addr,
+    input wire write_addr,
+    input wire [7:0] write_data,
+    output wire [7:0] read_data
+    );
+
+    wire __inst_b_read_addr;
+    wire __inst_b_write_addr;
+    wire [7:0] __inst_b_write_data;
+    wire [7:0] __inst_b_read_data;
+
+    B b(
+        .reset_n(reset_n),
+        .read_clk(read_clk),
+        .write_clk(write_clk),
+        .clk(clk),
+        .read_addr(__inst_b_read_addr),
+        .write_addr(__inst_b_write_addr),
+        .write_data(__inst_b_write_data),
+        .read_data(__inst_b_read_data)
+    );
+
+    assign read_data = __inst_b_read_data;
+    assign __inst_b_read_addr = read_addr;
+    assign __inst_b_write_addr = write_addr;
+    assign __inst_b_write_data = write_data;
+
+endmodule
+
+"#
+        );
+    }
+
+    #[test]
+    fn clock_divider() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+
+        let div3 = m.clock_divider("div3", 3);
+        let slow = div3.reg("slow", 4);
+        slow.default_value(0u32);
+        slow.drive_next(slow + m.lit(1u32, 4));
+        let sampled = div3.reg("sampled", 1);
+        sampled.drive_next(m.input("i", 1));
+        m.output("slow", slow);
+        m.output("sampled", sampled);
+
+        let mut v = Vec::new();
+        generate(m, GenerationOptions::default(), &mut v).unwrap();
+
+        assert_eq!(
+            String::from_utf8(v).unwrap(),
+            r#"module M(
+    input wire reset_n,
+    input wire clk,
+
+    input wire i,
+    output wire sampled,
+    output wire [3:0] slow
+    );
+
+    (* generated_clock = "divide_by_3" *) reg __reg_m_div3_1;
+    wire __reg_m_div3_1_next;
+    reg [1:0] __reg_m_div3_count_2;
+    wire [1:0] __reg_m_div3_count_2_next;
+    reg __reg_m_sampled_0;
+    wire __reg_m_sampled_0_next;
+    reg [3:0] __reg_m_slow_3;
+    wire [3:0] __reg_m_slow_3_next;
+
+    always @(posedge __reg_m_div3_1) begin
+        __reg_m_sampled_0 <= __reg_m_sampled_0_next;
+    end
+
+    always @(posedge __reg_m_div3_1, negedge reset_n) begin
+        if (~reset_n) begin
+            __reg_m_slow_3 <= 4'h0;
+        end
+        else begin
+            __reg_m_slow_3 <= __reg_m_slow_3_next;
+        end
+    end
+
+    always @(posedge clk, negedge reset_n) begin
+        if (~reset_n) begin
+            __reg_m_div3_1 <= 1'h0;
+            __reg_m_div3_count_2 <= 2'h2;
+        end
+        else begin
+            __reg_m_div3_1 <= __reg_m_div3_1_next;
+            __reg_m_div3_count_2 <= __reg_m_div3_count_2_next;
+        end
+    end
+
+    wire [1:0] __temp_m_0;
+    wire __temp_m_1;
+    wire [1:0] __temp_m_2;
+    wire __temp_m_3;
+    wire [1:0] __temp_m_4;
+    wire __temp_m_5;
+    wire [1:0] __temp_m_6;
+    wire [3:0] __temp_m_7;
+
+    assign sampled = __reg_m_sampled_0;
+    assign slow = __reg_m_slow_3;
+    assign __temp_m_0 = __reg_m_div3_count_2 + 2'h1;
+    assign __temp_m_1 = __reg_m_div3_count_2 == 2'h2;
+    assign __temp_m_2 = __temp_m_1 ? 2'h0 : __temp_m_0;
+    assign __temp_m_3 = __temp_m_2 < 2'h1;
+    assign __reg_m_div3_1_next = __temp_m_3;
+    assign __temp_m_4 = __reg_m_div3_count_2 + 2'h1;
+    assign __temp_m_5 = __reg_m_div3_count_2 == 2'h2;
+    assign __temp_m_6 = __temp_m_5 ? 2'h0 : __temp_m_4;
+    assign __reg_m_div3_count_2_next = __temp_m_6;
+    assign __reg_m_sampled_0_next = i;
+    assign __temp_m_7 = __reg_m_slow_3 + 4'h1;
+    assign __reg_m_slow_3_next = __temp_m_7;
+
+endmodule
+
+"#
+        );
+    }
+
+    #[test]
+    fn reg_clock_domains() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let b = a.module("b", "B");
+
+        let i = b.input("i", 1);
+        let fast = b.reg_in_domain("fast", 1, "clk_fast");
+        fast.default_value(false);
+        fast.drive_next(i);
+        let fast_no_reset = b.reg_in_domain("fast_no_reset", 1, "clk_fast");
+        fast_no_reset.drive_next(fast);
+        let slow = b.reg("slow", 1);
+        slow.default_value(false);
+        slow.drive_next(fast_no_reset);
+        let o = b.output("o", slow);
+
+        i.drive(a.input("i", 1));
+        a.output("o", o);
+
+        let mut v = Vec::new();
+        generate(a, GenerationOptions::default(), &mut v).unwrap();
+
+        assert_eq!(
+            String::from_utf8(v).unwrap(),
+            r#"module B(
+    input wire reset_n,
+    input wire clk_fast,
+    input wire clk,
+
+    input wire i,
+    output wire o
+    );
+
+    reg __reg_a_b_fast_2;
+    wire __reg_a_b_fast_2_next;
+    reg __reg_a_b_fast_no_reset_1;
+    wire __reg_a_b_fast_no_reset_1_next;
+    reg __reg_a_b_slow_0;
+    wire __reg_a_b_slow_0_next;
+
+    always @(posedge clk, negedge reset_n) begin
+        if (~reset_n) begin
+            __reg_a_b_slow_0 <= 1'h0;
+        end
+        else begin
+            __reg_a_b_slow_0 <= __reg_a_b_slow_0_next;
+        end
+    end
+
+    always @(posedge clk_fast) begin
+        __reg_a_b_fast_no_reset_1 <= __reg_a_b_fast_no_reset_1_next;
+    end
+
+    always @(posedge clk_fast, negedge reset_n) begin
+        if (~reset_n) begin
+            __reg_a_b_fast_2 <= 1'h0;
+        end
+        else begin
+            __reg_a_b_fast_2 <= __reg_a_b_fast_2_next;
+        end
+    end
+
+    assign o = __reg_a_b_slow_0;
+    assign __reg_a_b_fast_2_next = i;
+    assign __reg_a_b_fast_no_reset_1_next = __reg_a_b_fast_2;
+    assign __reg_a_b_slow_0_next = __reg_a_b_fast_no_reset_1;
+
+endmodule
+
+module A(
+    input wire reset_n,
+    input wire clk_fast,
+    input wire clk,
+
+    input wire i,
+    output wire o
+    );
+
+    wire __inst_b_i;
+    wire __inst_b_o;
+
+    B b(
+        .reset_n(reset_n),
+        .clk_fast(clk_fast),
+        .clk(clk),
+        .i(__inst_b_i),
+        .o(__inst_b_o)
+    );
+
+    assign o = __inst_b_o;
+    assign __inst_b_i = i;
+
+endmodule
+
+"#
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot generate code for module \"A\" because module \"B\" contains a register called \"r\" with a clock called \"reset_n\", which is the same as the name of the implicit reset."
+    )]
+    fn reg_clock_name_reset_collision_error() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let b = a.module("b", "B");
+        let r = b.reg_in_domain("r", 1, "reset_n");
+        r.drive_next(!r);
+        a.output("o", b.output("o", r));
+
+        // Panic
+        generate(a, GenerationOptions::default(), Vec::new()).unwrap();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot generate code for module \"A\" because module \"A\" contains a register called \"f\" with a clock called \"reset_soft_n\", which is the same as the name of the reset port of reset domain \"soft\"."
+    )]
+    fn reg_clock_name_reset_domain_collision_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let soft = m.reset_domain("soft");
+        let r = m.reg("r", 1);
+        r.default_value(false);
+        r.reset_domain(soft);
+        r.drive_next(m.input("i", 1));
+        let f = m.reg_in_domain("f", 1, "reset_soft_n");
+        f.drive_next(r);
+        m.output("o", f);
+
+        // Panic
+        generate(m, GenerationOptions::default(), Vec::new()).unwrap();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot generate code for module \"A\" with reset domain reset name \"rst_soft\" because module \"B\" contains an input or output with the same name."
+    )]
+    fn reset_domain_port_collision_error() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let b = a.module("b", "B");
+        let soft = b.reset_domain("soft");
+        let r = b.reg("r", 1);
+        r.default_value(false);
+        r.reset_domain(soft);
+        let b_i = b.input("rst_soft", 1);
+        b_i.drive(a.input("i", 1));
+        r.drive_next(b_i);
+        a.output("o", b.output("o", r));
+
+        // Panic
+        generate(
+            a,
+            GenerationOptions {
+                override_reset_name: Some("rst".into()),
+                ..GenerationOptions::default()
+            },
+            Vec::new(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn flattened_child_ports_named_like_clock_and_reset() {
+        let c = Context::new();
+
+        // Child module ports become internal nets when the hierarchy is flattened, so they can't collide with the clock and reset ports
+        let a = c.module("a", "A");
+        let b = a.module("b", "B");
+        let b_clk = b.input("clk", 1);
+        b_clk.drive(a.input("i", 1));
+        let r = b.reg("r", 1);
+        r.default_value(false);
+        r.drive_next(b_clk);
+        a.output("o", b.output("reset_n", r));
+
+        generate(
+            a,
+            GenerationOptions {
+                flatten: true,
+                ..GenerationOptions::default()
+            },
+            Vec::new(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot generate code for module \"A\" with register clock name \"clk_fast\" because module \"A\" contains an input or output with the same name."
+    )]
+    fn reg_clock_name_port_collision_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let r = m.reg_in_domain("r", 1, "clk_fast");
+        r.drive_next(m.input("clk_fast", 1));
+        m.output("o", r);
+
+        // Panic
+        generate(m, GenerationOptions::default(), Vec::new()).unwrap();
+    }
+
+    fn assertion_module<'a>(c: &'a Context<'a>) -> &'a Module<'a> {
+        let a = c.module("a", "A");
+        let b = a.module("b", "B");
+
+        let i = b.input("i", 8);
+        let r = b.reg("r", 8);
+        r.drive_next(i);
+        b.assert("r_not_ff", r.ne(b.lit(0xffu32, 8)));
+
+        let i2 = a.input("i", 8);
+        i.drive(i2);
+        a.assert("i_even", !i2.bit(0));
+        a.output("o", i2);
+
+        a
+    }
+
+    #[test]
+    fn assertions_verilog_2001() {
+        let c = Context::new();
+
+        let mut v = Vec::new();
+        generate(assertion_module(&c), GenerationOptions::default(), &mut v).unwrap();
+
+        assert_eq!(
+            String::from_utf8(v).unwrap(),
+            r#"module B(
+    input wire reset_n,
+    input wire clk,
+
+    input wire [7:0] i
+    );
+
+    reg [7:0] __reg_a_b_r_0;
+    wire [7:0] __reg_a_b_r_0_next;
+    wire __assertion_a_b_r_not_ff;
+
+    always @(posedge clk) begin
+        __reg_a_b_r_0 <= __reg_a_b_r_0_next;
+    end
+
+    always @(posedge clk) begin
+        if (reset_n && ~__assertion_a_b_r_not_ff) begin
+            $display("Assertion \"r_not_ff\" failed in %m");
+            $finish;
+        end
+    end
+
+    wire __temp_a_b_0;
+
+    assign __reg_a_b_r_0_next = i;
+    assign __temp_a_b_0 = __reg_a_b_r_0 != 8'hff;
+    assign __assertion_a_b_r_not_ff = __temp_a_b_0;
+
+endmodule
+
+module A(
+    input wire reset_n,
+    input wire clk,
+
+    input wire [7:0] i,
+    output wire [7:0] o
+    );
+
+    wire __assertion_a_i_even;
+    wire [7:0] __inst_b_i;
+
+    always @(posedge clk) begin
+        if (reset_n && ~__assertion_a_i_even) begin
+            $display("Assertion \"i_even\" failed in %m");
+            $finish;
+        end
+    end
+
+    B b(
+        .reset_n(reset_n),
+        .clk(clk),
+        .i(__inst_b_i)
+    );
+
+    wire __temp_a_0;
+    wire __temp_a_1;
+
+    assign o = i;
+    assign __temp_a_0 = i[0];
+    assign __temp_a_1 = ~__temp_a_0;
+    assign __assertion_a_i_even = __temp_a_1;
+    assign __inst_b_i = i;
+
+endmodule
+
+"#
+        );
+    }
+
+    #[test]
+    fn assertions_system_verilog_flattened() {
+        let c = Context::new();
+
+        let mut v = Vec::new();
+        generate(
+            assertion_module(&c),
+            GenerationOptions {
+                flatten: true,
+                language_standard: LanguageStandard::SystemVerilog,
+                ..GenerationOptions::default()
+            },
+            &mut v,
+        )
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(v).unwrap(),
+            r#"module A(
+    input logic reset_n,
+    input logic clk,
+
+    input logic [7:0] i,
+    output logic [7:0] o
+    );
+
+    logic [7:0] __reg_a_b_r_0;
+    logic [7:0] __reg_a_b_r_0_next;
+    logic __assertion_a_i_even;
+    logic __assertion_a_b_r_not_ff;
+
+    always_ff @(posedge clk) begin
+        __reg_a_b_r_0 <= __reg_a_b_r_0_next;
+    end
+
+    assert property (@(posedge clk) disable iff (~reset_n) __assertion_a_i_even) else $fatal(1, "Assertion \"i_even\" failed in %m");
+    assert property (@(posedge clk) disable iff (~reset_n) __assertion_a_b_r_not_ff) else $fatal(1, "Assertion \"b.r_not_ff\" failed in %m");
+
+    logic [7:0] __temp_a_b_i_0;
+    logic __temp_a_1;
+    logic __temp_a_2;
+    logic __temp_a_b_3;
+
+    assign o = i;
+    assign __temp_a_b_i_0 = i;
+    assign __reg_a_b_r_0_next = __temp_a_b_i_0;
+    assign __temp_a_1 = i[0];
+    assign __temp_a_2 = ~__temp_a_1;
+    assign __assertion_a_i_even = __temp_a_2;
+    assign __temp_a_b_3 = __reg_a_b_r_0 != 8'hff;
+    assign __assertion_a_b_r_not_ff = __temp_a_b_3;
+
+endmodule
+
+"#
+        );
+    }
+
+    fn cover_module<'a>(c: &'a Context<'a>) -> &'a Module<'a> {
+        let a = c.module("a", "A");
+        let b = a.module("b", "B");
+
+        let i = b.input("i", 8);
+        b.cover("i_ff", i.eq(b.lit(0xffu32, 8)));
+
+        let i2 = a.input("i", 8);
+        i.drive(i2);
+        a.cover("i_odd", i2.bit(0));
+        a.output("o", i2);
+
+        a
+    }
+
+    #[test]
+    fn cover_points_verilog_2001() {
+        let c = Context::new();
+
+        let mut v = Vec::new();
+        generate(cover_module(&c), GenerationOptions::default(), &mut v).unwrap();
+
+        assert_eq!(
+            String::from_utf8(v).unwrap(),
+            r#"module B(
+    input wire reset_n,
+    input wire clk,
+
+    input wire [7:0] i
+    );
+
+    wire __cover_a_b_i_ff;
+
+    `ifdef FORMAL
+    cover property (@(posedge clk) disable iff (~reset_n) __cover_a_b_i_ff);
+    `endif
+
+    wire __temp_a_b_0;
+
+    assign __temp_a_b_0 = i == 8'hff;
+    assign __cover_a_b_i_ff = __temp_a_b_0;
+
+endmodule
+
+module A(
+    input wire reset_n,
+    input wire clk,
+
+    input wire [7:0] i,
+    output wire [7:0] o
+    );
+
+    wire __cover_a_i_odd;
+    wire [7:0] __inst_b_i;
+
+    `ifdef FORMAL
+    cover property (@(posedge clk) disable iff (~reset_n) __cover_a_i_odd);
+    `endif
+
+    B b(
+        .reset_n(reset_n),
+        .clk(clk),
+        .i(__inst_b_i)
+    );
+
+    wire __temp_a_0;
+
+    assign o = i;
+    assign __temp_a_0 = i[0];
+    assign __cover_a_i_odd = __temp_a_0;
+    assign __inst_b_i = i;
+
+endmodule
+
+"#
+        );
+    }
+
+    #[test]
+    fn cover_points_system_verilog_flattened() {
+        let c = Context::new();
+
+        let mut v = Vec::new();
+        generate(
+            cover_module(&c),
+            GenerationOptions {
+                flatten: true,
+                language_standard: LanguageStandard::SystemVerilog,
+                ..GenerationOptions::default()
+            },
+            &mut v,
+        )
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(v).unwrap(),
+            r#"module A(
+    input logic reset_n,
+    input logic clk,
+
+    input logic [7:0] i,
+    output logic [7:0] o
+    );
+
+    logic __cover_a_i_odd;
+    logic __cover_a_b_i_ff;
+
+    cover property (@(posedge clk) disable iff (~reset_n) __cover_a_i_odd);
+    cover property (@(posedge clk) disable iff (~reset_n) __cover_a_b_i_ff);
+
+    logic __temp_a_0;
+    logic [7:0] __temp_a_b_i_1;
+    logic __temp_a_b_2;
+
+    assign o = i;
+    assign __temp_a_0 = i[0];
+    assign __cover_a_i_odd = __temp_a_0;
+    assign __temp_a_b_i_1 = i;
+    assign __temp_a_b_2 = __temp_a_b_i_1 == 8'hff;
+    assign __cover_a_b_i_ff = __temp_a_b_2;
+
+endmodule
+
+"#
+        );
+    }
+
+    fn assert_always_module<'a>(c: &'a Context<'a>) -> &'a Module<'a> {
+        let m = c.module("a", "A");
+
+        let i = m.input("i", 8);
+        m.assert("i_even", !i.bit(0));
+        m.assert_always(
+            "i_not_ff",
+            i.ne(m.lit(0xffu32, 8)),
+            "i was \"0xff\" (100% set)",
+        );
+        m.output("o", i);
+
+        m
+    }
+
+    #[test]
+    fn assert_always_verilog_2001() {
+        let c = Context::new();
+
+        let mut v = Vec::new();
+        generate(
+            assert_always_module(&c),
+            GenerationOptions::default(),
+            &mut v,
+        )
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(v).unwrap(),
+            r#"module A(
+    input wire reset_n,
+    input wire clk,
+
+    input wire [7:0] i,
+    output wire [7:0] o
+    );
+
+    wire __assertion_a_i_even;
+    wire __assertion_a_i_not_ff;
+
+    always @(posedge clk) begin
+        if (reset_n && ~__assertion_a_i_even) begin
+            $display("Assertion \"i_even\" failed in %m");
+            $finish;
+        end
+        if (reset_n && ~__assertion_a_i_not_ff) begin
+            $display("Assertion \"i_not_ff\" failed in %m: i was \"0xff\" (100%% set)");
+            $finish;
+        end
+    end
+
+    `ifdef FORMAL
+    assert property (@(posedge clk) disable iff (~reset_n) __assertion_a_i_not_ff);
+    `endif
+
+    wire __temp_a_0;
+    wire __temp_a_1;
+    wire __temp_a_2;
+
+    assign o = i;
+    assign __temp_a_0 = i[0];
+    assign __temp_a_1 = ~__temp_a_0;
+    assign __assertion_a_i_even = __temp_a_1;
+    assign __temp_a_2 = i != 8'hff;
+    assign __assertion_a_i_not_ff = __temp_a_2;
+
+endmodule
+
+"#
+        );
+    }
+
+    #[test]
+    fn assert_always_system_verilog() {
+        let c = Context::new();
+
+        let mut v = Vec::new();
+        generate(
+            assert_always_module(&c),
+            GenerationOptions {
+                language_standard: LanguageStandard::SystemVerilog,
+                ..GenerationOptions::default()
+            },
+            &mut v,
+        )
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(v).unwrap(),
+            r#"module A(
+    input logic reset_n,
+    input logic clk,
+
+    input logic [7:0] i,
+    output logic [7:0] o
+    );
+
+    logic __assertion_a_i_even;
+    logic __assertion_a_i_not_ff;
+
+    assert property (@(posedge clk) disable iff (~reset_n) __assertion_a_i_even) else $fatal(1, "Assertion \"i_even\" failed in %m");
+    assert property (@(posedge clk) disable iff (~reset_n) __assertion_a_i_not_ff) else $fatal(1, "Assertion \"i_not_ff\" failed in %m: i was \"0xff\" (100%% set)");
+
+    logic __temp_a_0;
+    logic __temp_a_1;
+    logic __temp_a_2;
+
+    assign o = i;
+    assign __temp_a_0 = i[0];
+    assign __temp_a_1 = ~__temp_a_0;
+    assign __assertion_a_i_even = __temp_a_1;
+    assign __temp_a_2 = i != 8'hff;
+    assign __assertion_a_i_not_ff = __temp_a_2;
+
+endmodule
+
+"#
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot generate code for module \"A\" because module \"A\" contains a memory called \"mem\" with a clock called \"clk\", which is the same as the name of the implicit clock."
+    )]
+    fn mem_clock_name_clock_collision_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let mem = m.mem("mem", 1, 1);
+        mem.dual_clock("clk", "write_clk");
+        mem.write_port(m.low(), m.low(), m.low());
+        m.output("o", mem.read_port(m.low(), m.high()));
+
+        // Panic
+        generate(m, GenerationOptions::default(), Vec::new()).unwrap();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot generate code for module \"A\" with memory clock name \"write_clk\" because module \"A\" contains an input or output with the same name."
+    )]
+    fn mem_clock_name_port_collision_error() {
+        let c = Context::new();
+
+        let m = c.module("a", "A");
+        let mem = m.mem("mem", 1, 1);
+        mem.dual_clock("read_clk", "write_clk");
+        mem.write_port(m.low(), m.input("write_clk", 1), m.low());
+        m.output("o", mem.read_port(m.low(), m.high()));
+
+        // Panic
+        generate(m, GenerationOptions::default(), Vec::new()).unwrap();
+    }
+
+    fn nasty_names_module<'a>(c: &'a Context<'a>) -> &'a Module<'a> {
+        let top = c.module("top", "Top");
+        let reg = top.input("reg", 8);
+        let a_b = top.input("a b", 8);
+        let a_b_2 = top.input("a_b", 8);
+        let fifo = top.module("my fifo", "fifo<8>");
+        let data_in = fifo.input("data in", 8);
+        let state = fifo.reg("state[0]", 8);
+        state.drive_next(data_in);
+        fifo.output("data out", fifo.named_wire("x.y", state ^ data_in));
+        data_in.drive(top.named_wire("sum (a+b)", reg + a_b));
+        top.output("wire", fifo.get_output("data out") ^ a_b_2);
+        top
+    }
+
+    #[test]
+    fn sanitized_identifiers() {
+        let c = Context::new();
+
+        let m = nasty_names_module(&c);
+
+        let mut v = Vec::new();
+        let renamed = generate(m, GenerationOptions::default(), &mut v).unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        assert!(v.contains("module fifo_8_("));
+        assert!(v.contains("input wire [7:0] data_in,"));
+        assert!(v.contains("output wire [7:0] data_out"));
+        assert!(v.contains("wire [7:0] x_y;"));
+        assert!(v.contains("reg [7:0] __reg_top_my_fifo_state_0__0;"));
+        assert!(v.contains("module Top("));
+        // Renamed names never collide with names that are already valid
+        assert!(v.contains("input wire [7:0] a_b,"));
+        assert!(v.contains("input wire [7:0] a_b_1,"));
+        assert!(v.contains("input wire [7:0] reg_,"));
+        assert!(v.contains("output wire [7:0] wire_"));
+        assert!(v.contains("assign __temp_top_1 = reg_ + a_b_1;"));
+        assert!(v.contains("assign sum__a_b_ = __temp_top_1;"));
+        assert!(v.contains("fifo_8_ my_fifo("));
+        assert!(v.contains(".data_in(__inst_my_fifo_data_in)"));
+
+        let rename = |kind, module_name: &str, name: &str, identifier: &str| RenamedIdentifier {
+            kind,
+            module_name: module_name.into(),
+            name: name.into(),
+            identifier: identifier.into(),
+        };
+        assert_eq!(
+            renamed,
+            vec![
+                rename(IdentifierKind::Module, "fifo<8>", "fifo<8>", "fifo_8_"),
+                rename(IdentifierKind::Input, "fifo<8>", "data in", "data_in"),
+                rename(IdentifierKind::Output, "fifo<8>", "data out", "data_out"),
+                rename(IdentifierKind::NamedWire, "fifo<8>", "x.y", "x_y"),
+                rename(IdentifierKind::Input, "Top", "a b", "a_b_1"),
+                rename(IdentifierKind::Input, "Top", "reg", "reg_"),
+                rename(IdentifierKind::Output, "Top", "wire", "wire_"),
+                rename(IdentifierKind::NamedWire, "Top", "sum (a+b)", "sum__a_b_"),
+                rename(IdentifierKind::Instance, "Top", "my fifo", "my_fifo"),
+            ]
+        );
+        assert_eq!(
+            renamed[5].to_string(),
+            "input \"reg\" in module \"Top\" was renamed to \"reg_\""
+        );
+        assert_eq!(
+            renamed[0].to_string(),
+            "module \"fifo<8>\" was renamed to \"fifo_8_\""
+        );
+
+        let mut v = Vec::new();
+        let renamed = generate(
+            m,
+            GenerationOptions {
+                flatten: true,
+                ..GenerationOptions::default()
+            },
+            &mut v,
+        )
+        .unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        // Only the top-level module's names are used when instances are inlined
+        assert!(v.contains("wire [7:0] top_my_fifo_x_y;"));
+        assert!(v.contains("reg [7:0] __reg_top_my_fifo_state_0__0;"));
+        assert_eq!(renamed.len(), 4);
+    }
+
+    #[test]
+    fn escaped_identifiers() {
+        let c = Context::new();
+
+        let m = nasty_names_module(&c);
+
+        let mut v = Vec::new();
+        let renamed = generate(
+            m,
+            GenerationOptions {
+                identifier_style: IdentifierStyle::Escape,
+                ..GenerationOptions::default()
+            },
+            &mut v,
+        )
+        .unwrap();
+        let v = String::from_utf8(v).unwrap();
+
+        assert!(v.contains("module \\fifo<8> ("));
+        assert!(v.contains("wire [7:0] \\x.y ;"));
+        assert!(v.contains("input wire [7:0] \\reg ,"));
+        assert!(v.contains("output wire [7:0] \\wire "));
+        assert!(v.contains("assign \\wire  = __temp_top_0;"));
+        assert!(v.contains("\\fifo<8>  my_fifo("));
+        // Whitespace can't appear in escaped identifiers, so it's still replaced
+        assert!(v.contains("input wire [7:0] a_b_1,"));
+        assert!(v.contains("assign \\sum_(a+b)  = "));
+
+        assert_eq!(
+            renamed
+                .iter()
+                .map(|renamed| (renamed.name.as_str(), renamed.identifier.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("data in", "data_in"),
+                ("data out", "data_out"),
+                ("a b", "a_b_1"),
+                ("sum (a+b)", "\\sum_(a+b) "),
+                ("my fifo", "my_fifo"),
+            ]
+        );
+    }
+
+    #[test]
+    fn rotates() {
+        let c = Context::new();
+
+        let m = c.module("top", "Top");
+        let i = m.input("i", 7);
+        m.output("l", i.rotate_left(m.input("a", 4)));
+        m.output("r", i.rotate_right(m.input("b", 2)));
+
+        let mut v = Vec::new();
+        generate(m, GenerationOptions::default(), &mut v).unwrap();
+        let v = String::from_utf8(v).unwrap();
+        // The amount is only reduced modulo the bit width if it can reach it
+        assert!(v.contains("assign __temp_top_0 = a % 4'h7;"));
+        assert!(v.contains("assign __temp_top_1 = 3'h7 - __temp_top_0;"));
+        assert!(v.contains("assign __temp_top_2 = i << __temp_top_0;"));
+        assert!(v.contains("assign __temp_top_3 = i >> __temp_top_1;"));
+        assert!(v.contains("assign __temp_top_4 = __temp_top_2 | __temp_top_3;"));
+        assert!(v.contains("assign __temp_top_5 = 3'h7 - b;"));
+        assert!(v.contains("assign __temp_top_6 = i << __temp_top_5;"));
+        assert!(v.contains("assign __temp_top_7 = i >> b;"));
+    }
+
+    #[test]
+    fn max_line_width() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        let inputs = (0..16)
+            .map(|i| m.input(format!("some_long_input_name_{}", i), 4))
+            .collect::<Vec<_>>();
+        let concat = inputs[1..]
+            .iter()
+            .fold(inputs[0].into(), |acc: &dyn Signal, &i| acc.concat(i));
+        m.output("o", concat);
+        m.output(
+            "sel",
+            inputs[0].bit(0).mux(inputs[1] + inputs[2], inputs[3]),
+        );
+
+        let generate_with = |max_line_width| {
+            let mut v = Vec::new();
+            generate(
+                m,
+                GenerationOptions {
+                    max_line_width,
+                    ..GenerationOptions::default()
+                },
+                &mut v,
+            )
+            .unwrap();
+            String::from_utf8(v).unwrap()
+        };
+        let collapse_whitespace = |v: &str| v.split_whitespace().collect::<Vec<_>>().join(" ");
+
+        let unwrapped = generate_with(None);
+        let wrapped = generate_with(Some(60));
+
+        // Wrapping only changes whitespace
+        assert!(unwrapped.lines().any(|line| line.len() > 60));
+        assert!(wrapped.lines().all(|line| line.len() <= 60));
+        assert!(wrapped.lines().count() > unwrapped.lines().count());
+        assert_eq!(
+            collapse_whitespace(&wrapped),
+            collapse_whitespace(&unwrapped)
+        );
+        assert!(!wrapped.lines().any(|line| line.ends_with(' ')));
     }
 }
@@ -1,17 +1,33 @@
+use super::identifiers::*;
 use super::ir::*;
 
+use crate::graph;
+use crate::identifiers::*;
 use crate::internal_signal;
+use crate::optimizer::*;
 use crate::state_elements::*;
 
 use std::collections::HashMap;
 
-pub(super) struct Compiler<'graph> {
+pub(super) struct Compiler<'graph, 'optimizer> {
+    hierarchy: Hierarchy,
+    identifiers: &'optimizer Identifiers,
+    optimizer: &'optimizer Optimizer<'graph>,
+
     signal_exprs: HashMap<&'graph internal_signal::InternalSignal<'graph>, Expr>,
 }
 
-impl<'graph, 'context> Compiler<'graph> {
-    pub fn new() -> Compiler<'graph> {
+impl<'graph, 'optimizer, 'context> Compiler<'graph, 'optimizer> {
+    pub fn new(
+        hierarchy: Hierarchy,
+        identifiers: &'optimizer Identifiers,
+        optimizer: &'optimizer Optimizer<'graph>,
+    ) -> Compiler<'graph, 'optimizer> {
         Compiler {
+            hierarchy,
+            identifiers,
+            optimizer,
+
             signal_exprs: HashMap::new(),
         }
     }
@@ -28,7 +44,7 @@ impl<'graph, 'context> Compiler<'graph> {
         }
 
         let mut frames = Vec::new();
-        frames.push(Frame::Enter(signal));
+        frames.push(Frame::Enter(self.optimizer.optimize(signal)));
 
         let mut results = Vec::new();
 
@@ -47,21 +63,31 @@ impl<'graph, 'context> Compiler<'graph> {
                         } => Some(Expr::from_constant(value, bit_width)),
 
                         internal_signal::SignalData::Input { data } => {
-                            if let Some(driven_value) = data.driven_value.borrow().clone() {
+                            match (self.hierarchy, data.driven_value.borrow().clone()) {
+                                (Hierarchy::Flattened, Some(driven_value)) => {
+                                    frames.push(Frame::Leave(signal));
+                                    frames
+                                        .push(Frame::Enter(self.optimizer.optimize(driven_value)));
+                                    None
+                                }
+                                _ => Some(Expr::Ref {
+                                    name: self.identifiers.local(signal.module, &data.name).into(),
+                                }),
+                            }
+                        }
+                        internal_signal::SignalData::Output { data } => match self.hierarchy {
+                            Hierarchy::Flattened => {
                                 frames.push(Frame::Leave(signal));
-                                frames.push(Frame::Enter(driven_value));
+                                frames.push(Frame::Enter(self.optimizer.optimize_output(data)));
                                 None
-                            } else {
-                                Some(Expr::Ref {
-                                    name: data.name.clone(),
-                                })
                             }
-                        }
-                        internal_signal::SignalData::Output { data } => {
-                            frames.push(Frame::Leave(signal));
-                            frames.push(Frame::Enter(data.source));
-                            None
-                        }
+                            Hierarchy::Preserved => Some(Expr::Ref {
+                                name: self
+                                    .identifiers
+                                    .instance_port(data.module, &data.name)
+                                    .into(),
+                            }),
+                        },
 
                         internal_signal::SignalData::Reg { .. } => Some(Expr::Ref {
                             name: state_elements.regs[&signal].value_name.clone(),
@@ -141,6 +167,32 @@ impl<'graph, 'context> Compiler<'graph> {
                             None
                         }
 
+                        internal_signal::SignalData::Case {
+                            sel,
+                            ref cases,
+                            default,
+                            ..
+                        } => {
+                            frames.push(Frame::Leave(signal));
+                            frames.push(Frame::Enter(sel));
+                            for &(_, value) in cases.iter() {
+                                frames.push(Frame::Enter(value));
+                            }
+                            frames.push(Frame::Enter(default));
+                            None
+                        }
+
+                        internal_signal::SignalData::NamedWire { source, .. } => {
+                            frames.push(Frame::Leave(signal));
+                            frames.push(Frame::Enter(source));
+                            None
+                        }
+
+                        internal_signal::SignalData::Reinterpret { source, .. } => {
+                            frames.push(Frame::Enter(source));
+                            None
+                        }
+
                         internal_signal::SignalData::MemReadPortOutput {
                             mem,
                             address,
@@ -152,6 +204,11 @@ impl<'graph, 'context> Compiler<'graph> {
                                 name: read_signal_names.value_name.clone(),
                             })
                         }
+                        internal_signal::SignalData::MemCombReadPortOutput { address, .. } => {
+                            frames.push(Frame::Leave(signal));
+                            frames.push(Frame::Enter(address));
+                            None
+                        }
                     }
                 }
                 Frame::Leave(signal) => {
@@ -163,36 +220,66 @@ impl<'graph, 'context> Compiler<'graph> {
                                 unreachable!();
                             }
 
-                            Some(a.gen_temp(
+                            Some(a.gen_temp_with_attributes(
+                                results.pop().unwrap(),
+                                signal.bit_width(),
+                                sanitize(&format!(
+                                    "{}_{}",
+                                    signal.module_instance_name_prefix(),
+                                    data.name
+                                )),
+                                data.attributes.borrow().clone(),
+                            ))
+                        }
+                        internal_signal::SignalData::Output { data } => {
+                            Some(a.gen_temp_with_attributes(
                                 results.pop().unwrap(),
                                 signal.bit_width(),
-                                format!("{}_{}", signal.module_instance_name_prefix(), data.name),
+                                sanitize(&format!(
+                                    "{}_{}",
+                                    data.source.get().module_instance_name_prefix(),
+                                    data.name
+                                )),
+                                data.attributes.borrow().clone(),
                             ))
                         }
-                        internal_signal::SignalData::Output { data } => Some(a.gen_temp(
-                            results.pop().unwrap(),
-                            signal.bit_width(),
-                            format!(
-                                "{}_{}",
-                                data.source.module_instance_name_prefix(),
-                                data.name
-                            ),
-                        )),
 
                         internal_signal::SignalData::Reg { .. } => unreachable!(),
 
                         internal_signal::SignalData::UnOp { op, bit_width, .. } => {
                             let source = results.pop().unwrap();
-                            Some(a.gen_temp(
-                                Expr::UnOp {
-                                    source: Box::new(source),
-                                    op: match op {
-                                        internal_signal::UnOp::Not => UnOp::Not,
+                            // Reordering bits/bytes is just wiring, so we concatenate slices of the
+                            //  source in reverse order. Single chunks don't need any reordering, and
+                            //  Verilog doesn't allow indexing scalars anyways.
+                            let chunk_bit_width = match op {
+                                internal_signal::UnOp::Not => 0,
+                                internal_signal::UnOp::ReverseBits => 1,
+                                internal_signal::UnOp::SwapBytes => 8,
+                            };
+                            Some(match op {
+                                internal_signal::UnOp::Not => a.gen_temp(
+                                    Expr::UnOp {
+                                        source: Box::new(source),
+                                        op: UnOp::Not,
                                     },
-                                },
-                                bit_width,
-                                signal.module_instance_name_prefix(),
-                            ))
+                                    bit_width,
+                                    signal.module_instance_name_prefix(),
+                                ),
+                                _ if bit_width == chunk_bit_width => source,
+                                _ => a.gen_temp(
+                                    Expr::ConcatList {
+                                        sources: (0..bit_width / chunk_bit_width)
+                                            .map(|i| Expr::Bits {
+                                                source: Box::new(source.clone()),
+                                                range_high: (i + 1) * chunk_bit_width - 1,
+                                                range_low: i * chunk_bit_width,
+                                            })
+                                            .collect(),
+                                    },
+                                    bit_width,
+                                    signal.module_instance_name_prefix(),
+                                ),
+                            })
                         }
                         internal_signal::SignalData::SimpleBinOp { op, bit_width, .. } => {
                             let lhs = results.pop().unwrap();
@@ -272,6 +359,87 @@ impl<'graph, 'context> Compiler<'graph> {
                                 signal.module_instance_name_prefix(),
                             ))
                         }
+                        internal_signal::SignalData::ShiftBinOp {
+                            rhs,
+                            op:
+                                op @ (internal_signal::ShiftBinOp::RotateLeft
+                                | internal_signal::ShiftBinOp::RotateRight),
+                            bit_width,
+                            ..
+                        } => {
+                            let lhs = results.pop().unwrap();
+                            let amount = results.pop().unwrap();
+                            if bit_width == 1 {
+                                // Rotating a 1-bit value by any amount results in the original value
+                                Some(lhs)
+                            } else {
+                                let name_prefix = signal.module_instance_name_prefix();
+                                // The amount is taken modulo the bit width, which is only necessary if it can reach it
+                                let amount_bit_width = rhs.bit_width();
+                                let amount = if amount_bit_width < 32
+                                    && (1u32 << amount_bit_width) <= bit_width
+                                {
+                                    amount
+                                } else {
+                                    a.gen_temp(
+                                        Expr::BinOp {
+                                            lhs: Box::new(amount),
+                                            rhs: Box::new(Expr::Constant {
+                                                bit_width: amount_bit_width,
+                                                value: bit_width as _,
+                                            }),
+                                            op: BinOp::Rem,
+                                        },
+                                        amount_bit_width,
+                                        name_prefix.clone(),
+                                    )
+                                };
+                                let complement_bit_width = 32 - bit_width.leading_zeros();
+                                let complement = a.gen_temp(
+                                    Expr::BinOp {
+                                        lhs: Box::new(Expr::Constant {
+                                            bit_width: complement_bit_width,
+                                            value: bit_width as _,
+                                        }),
+                                        rhs: Box::new(amount.clone()),
+                                        op: BinOp::Sub,
+                                    },
+                                    complement_bit_width,
+                                    name_prefix.clone(),
+                                );
+                                let (shl_amount, shr_amount) = match op {
+                                    internal_signal::ShiftBinOp::RotateLeft => (amount, complement),
+                                    _ => (complement, amount),
+                                };
+                                let shifted_left = a.gen_temp(
+                                    Expr::BinOp {
+                                        lhs: Box::new(lhs.clone()),
+                                        rhs: Box::new(shl_amount),
+                                        op: BinOp::Shl,
+                                    },
+                                    bit_width,
+                                    name_prefix.clone(),
+                                );
+                                let shifted_right = a.gen_temp(
+                                    Expr::BinOp {
+                                        lhs: Box::new(lhs),
+                                        rhs: Box::new(shr_amount),
+                                        op: BinOp::Shr,
+                                    },
+                                    bit_width,
+                                    name_prefix.clone(),
+                                );
+                                Some(a.gen_temp(
+                                    Expr::BinOp {
+                                        lhs: Box::new(shifted_left),
+                                        rhs: Box::new(shifted_right),
+                                        op: BinOp::BitOr,
+                                    },
+                                    bit_width,
+                                    name_prefix,
+                                ))
+                            }
+                        }
                         internal_signal::SignalData::ShiftBinOp { op, bit_width, .. } => {
                             let lhs = results.pop().unwrap();
                             let lhs = match op {
@@ -280,6 +448,8 @@ impl<'graph, 'context> Compiler<'graph> {
                                 internal_signal::ShiftBinOp::ShrArithmetic => Expr::Signed {
                                     source: Box::new(lhs),
                                 },
+                                internal_signal::ShiftBinOp::RotateLeft
+                                | internal_signal::ShiftBinOp::RotateRight => unreachable!(),
                             };
                             let rhs = results.pop().unwrap();
                             Some(a.gen_temp(
@@ -292,6 +462,10 @@ impl<'graph, 'context> Compiler<'graph> {
                                         internal_signal::ShiftBinOp::ShrArithmetic => {
                                             BinOp::ShrArithmetic
                                         }
+                                        internal_signal::ShiftBinOp::RotateLeft
+                                        | internal_signal::ShiftBinOp::RotateRight => {
+                                            unreachable!()
+                                        }
                                     },
                                 },
                                 bit_width,
@@ -397,7 +571,67 @@ impl<'graph, 'context> Compiler<'graph> {
                             ))
                         }
 
-                        internal_signal::SignalData::MemReadPortOutput { .. } => unreachable!(),
+                        internal_signal::SignalData::Case {
+                            sel,
+                            ref cases,
+                            bit_width,
+                            ..
+                        } => {
+                            let sel_bit_width = sel.bit_width();
+                            let sel = results.pop().unwrap();
+                            let cases = cases
+                                .iter()
+                                .map(|&(value, _)| (value, results.pop().unwrap()))
+                                .collect();
+                            let default = results.pop().unwrap();
+                            Some(a.gen_case(
+                                sel,
+                                sel_bit_width,
+                                cases,
+                                default,
+                                bit_width,
+                                signal.module_instance_name_prefix(),
+                            ))
+                        }
+
+                        internal_signal::SignalData::NamedWire { ref name, .. } => {
+                            // Wires in flattened child modules are prefixed with their instance path to keep them unique
+                            let name = match (self.hierarchy, signal.module.parent) {
+                                (Hierarchy::Flattened, Some(_)) => self
+                                    .identifiers
+                                    .flattened_named_wire(signal.module, name)
+                                    .into(),
+                                _ => self.identifiers.local(signal.module, name).into(),
+                            };
+                            Some(a.gen_named_wire(
+                                results.pop().unwrap(),
+                                signal.bit_width(),
+                                name,
+                                vec![graph::Attribute {
+                                    namespace: graph::RESERVED_ATTRIBUTE_NAMESPACE.into(),
+                                    key: "keep".into(),
+                                    value: "true".into(),
+                                }],
+                            ))
+                        }
+
+                        internal_signal::SignalData::MemCombReadPortOutput { mem, .. } => {
+                            let address = results.pop().unwrap();
+                            let mem = &state_elements.mems[&mem];
+                            Some(a.gen_temp(
+                                Expr::ArrayIndex {
+                                    target: Box::new(Expr::Ref {
+                                        name: mem.mem_name.clone(),
+                                    }),
+                                    index: Box::new(address),
+                                },
+                                signal.bit_width(),
+                                signal.module_instance_name_prefix(),
+                            ))
+                        }
+
+                        internal_signal::SignalData::MemReadPortOutput { .. }
+                        | internal_signal::SignalData::Reinterpret { .. } => unreachable!(),
                     }
                 }
             } {
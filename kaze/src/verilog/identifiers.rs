@@ -0,0 +1,617 @@
+use super::IdentifierStyle;
+
+use crate::graph;
+use crate::identifiers::*;
+use crate::state_elements::*;
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// The kind of item named by a [`RenamedIdentifier`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum IdentifierKind {
+    Module,
+    Input,
+    Output,
+    NamedWire,
+    Instance,
+}
+
+impl fmt::Display for IdentifierKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            IdentifierKind::Module => "module",
+            IdentifierKind::Input => "input",
+            IdentifierKind::Output => "output",
+            IdentifierKind::NamedWire => "named wire",
+            IdentifierKind::Instance => "instance",
+        })
+    }
+}
+
+/// A name that couldn't be used as-is in code generated by [`generate`](super::generate), along with the identifier that was used instead.
+///
+/// See [`GenerationOptions::identifier_style`](super::GenerationOptions::identifier_style) for details.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RenamedIdentifier {
+    /// The kind of item that was renamed.
+    pub kind: IdentifierKind,
+    /// The name of the module containing the renamed item, or the original name of the module if the renamed item is a module.
+    pub module_name: String,
+    /// The original name of the item.
+    pub name: String,
+    /// The identifier used for the item in generated code, exactly as it's emitted.
+    pub identifier: String,
+}
+
+impl fmt::Display for RenamedIdentifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            IdentifierKind::Module => write!(f, "module \"{}\"", self.name)?,
+            _ => write!(
+                f,
+                "{} \"{}\" in module \"{}\"",
+                self.kind, self.name, self.module_name
+            )?,
+        }
+        write!(f, " was renamed to \"{}\"", self.identifier)
+    }
+}
+
+/// Keywords of Verilog (IEEE 1364-2005) and SystemVerilog (IEEE 1800-2017), sorted so they can be binary searched.
+///
+/// Names are checked against both sets regardless of the language standard, so that generated code is also valid in testbenches and tools that parse Verilog as SystemVerilog.
+const KEYWORDS: &[&str] = &[
+    "accept_on",
+    "alias",
+    "always",
+    "always_comb",
+    "always_ff",
+    "always_latch",
+    "and",
+    "assert",
+    "assign",
+    "assume",
+    "automatic",
+    "before",
+    "begin",
+    "bind",
+    "bins",
+    "binsof",
+    "bit",
+    "break",
+    "buf",
+    "bufif0",
+    "bufif1",
+    "byte",
+    "case",
+    "casex",
+    "casez",
+    "cell",
+    "chandle",
+    "checker",
+    "class",
+    "clocking",
+    "cmos",
+    "config",
+    "const",
+    "constraint",
+    "context",
+    "continue",
+    "cover",
+    "covergroup",
+    "coverpoint",
+    "cross",
+    "deassign",
+    "default",
+    "defparam",
+    "design",
+    "disable",
+    "dist",
+    "do",
+    "edge",
+    "else",
+    "end",
+    "endcase",
+    "endchecker",
+    "endclass",
+    "endclocking",
+    "endconfig",
+    "endfunction",
+    "endgenerate",
+    "endgroup",
+    "endinterface",
+    "endmodule",
+    "endpackage",
+    "endprimitive",
+    "endprogram",
+    "endproperty",
+    "endsequence",
+    "endspecify",
+    "endtable",
+    "endtask",
+    "enum",
+    "event",
+    "eventually",
+    "expect",
+    "export",
+    "extends",
+    "extern",
+    "final",
+    "first_match",
+    "for",
+    "force",
+    "foreach",
+    "forever",
+    "fork",
+    "forkjoin",
+    "function",
+    "generate",
+    "genvar",
+    "global",
+    "highz0",
+    "highz1",
+    "if",
+    "iff",
+    "ifnone",
+    "ignore_bins",
+    "illegal_bins",
+    "implements",
+    "implies",
+    "import",
+    "incdir",
+    "include",
+    "initial",
+    "inout",
+    "input",
+    "inside",
+    "instance",
+    "int",
+    "integer",
+    "interconnect",
+    "interface",
+    "intersect",
+    "join",
+    "join_any",
+    "join_none",
+    "large",
+    "let",
+    "liblist",
+    "library",
+    "local",
+    "localparam",
+    "logic",
+    "longint",
+    "macromodule",
+    "matches",
+    "medium",
+    "modport",
+    "module",
+    "nand",
+    "negedge",
+    "nettype",
+    "new",
+    "nexttime",
+    "nmos",
+    "nor",
+    "noshowcancelled",
+    "not",
+    "notif0",
+    "notif1",
+    "null",
+    "or",
+    "output",
+    "package",
+    "packed",
+    "parameter",
+    "pmos",
+    "posedge",
+    "primitive",
+    "priority",
+    "program",
+    "property",
+    "protected",
+    "pull0",
+    "pull1",
+    "pulldown",
+    "pullup",
+    "pulsestyle_ondetect",
+    "pulsestyle_onevent",
+    "pure",
+    "rand",
+    "randc",
+    "randcase",
+    "randsequence",
+    "rcmos",
+    "real",
+    "realtime",
+    "ref",
+    "reg",
+    "reject_on",
+    "release",
+    "repeat",
+    "restrict",
+    "return",
+    "rnmos",
+    "rpmos",
+    "rtran",
+    "rtranif0",
+    "rtranif1",
+    "s_always",
+    "s_eventually",
+    "s_nexttime",
+    "s_until",
+    "s_until_with",
+    "scalared",
+    "sequence",
+    "shortint",
+    "shortreal",
+    "showcancelled",
+    "signed",
+    "small",
+    "soft",
+    "solve",
+    "specify",
+    "specparam",
+    "static",
+    "string",
+    "strong",
+    "strong0",
+    "strong1",
+    "struct",
+    "super",
+    "supply0",
+    "supply1",
+    "sync_accept_on",
+    "sync_reject_on",
+    "table",
+    "tagged",
+    "task",
+    "this",
+    "throughout",
+    "time",
+    "timeprecision",
+    "timeunit",
+    "tran",
+    "tranif0",
+    "tranif1",
+    "tri",
+    "tri0",
+    "tri1",
+    "triand",
+    "trior",
+    "trireg",
+    "type",
+    "typedef",
+    "union",
+    "unique",
+    "unique0",
+    "unsigned",
+    "until",
+    "until_with",
+    "untyped",
+    "use",
+    "uwire",
+    "var",
+    "vectored",
+    "virtual",
+    "void",
+    "wait",
+    "wait_order",
+    "wand",
+    "weak",
+    "weak0",
+    "weak1",
+    "while",
+    "wildcard",
+    "wire",
+    "with",
+    "within",
+    "wor",
+    "xnor",
+    "xor",
+];
+
+pub(crate) fn is_keyword(name: &str) -> bool {
+    KEYWORDS.binary_search(&name).is_ok()
+}
+
+/// The Verilog identifiers used for the user-provided names of modules, ports, named wires, and instances, as well as the generated wires that are derived from them.
+pub(super) struct Identifiers {
+    modules: HashMap<String, String>,
+    // Keyed by module name and port or named wire name, as all modules with the same name share a single definition
+    locals: HashMap<(String, String), String>,
+    // Keyed by parent module name and instance name
+    instances: HashMap<(String, String), String>,
+    // Keyed by parent module name, instance name, and port name
+    instance_ports: HashMap<(String, String, String), String>,
+    // Keyed by instance path and named wire name
+    flattened_named_wires: HashMap<(String, String), String>,
+
+    pub renamed: Vec<RenamedIdentifier>,
+}
+
+impl Identifiers {
+    /// Assigns identifiers for the module definitions in `modules`, which are emitted in the same file.
+    ///
+    /// Inputs, outputs, named wires, and (if `hierarchy` is preserved) child instances of each module share a single namespace, so identifiers that were renamed never collide with other names in the same module.
+    /// The wires connected to child instances' ports (if `hierarchy` is preserved) and the named wires of child instances (if `hierarchy` is flattened) are named after their instances, so they're added to the same namespace, with a `_<n>` suffix if their names would collide with any other name in it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any input, output, named wire, or instance would be emitted with an identifier starting with `__`, which could collide with the identifiers of generated wires and registers.
+    pub fn new<'a>(
+        modules: &[&'a graph::Module<'a>],
+        hierarchy: Hierarchy,
+        style: IdentifierStyle,
+    ) -> Identifiers {
+        let mut ret = Identifiers {
+            modules: HashMap::new(),
+            locals: HashMap::new(),
+            instances: HashMap::new(),
+            instance_ports: HashMap::new(),
+            flattened_named_wires: HashMap::new(),
+
+            renamed: Vec::new(),
+        };
+
+        let mut module_namespace = Namespace::new(style);
+        module_namespace.reserve(modules.iter().map(|m| m.name.as_str()));
+        for m in modules.iter() {
+            let identifier = module_namespace.legalize(&m.name);
+            ret.record(IdentifierKind::Module, &m.name, &m.name, &identifier);
+            ret.modules.insert(m.name.clone(), identifier);
+        }
+
+        for m in modules.iter() {
+            let mut namespace = Namespace::new(style);
+
+            let inputs = m.inputs.borrow();
+            let outputs = m.outputs.borrow();
+            let named_wires = m.named_wires.borrow();
+            let locals = inputs
+                .keys()
+                .map(|name| (IdentifierKind::Input, name))
+                .chain(outputs.keys().map(|name| (IdentifierKind::Output, name)))
+                .chain(
+                    named_wires
+                        .keys()
+                        .map(|name| (IdentifierKind::NamedWire, name)),
+                )
+                .collect::<Vec<_>>();
+            namespace.reserve(locals.iter().map(|(_, name)| name.as_str()));
+            for (kind, name) in locals {
+                let identifier = namespace.legalize(name);
+                validate_not_reserved(kind, m, name, &identifier);
+                ret.record(kind, &m.name, name, &identifier);
+                ret.locals
+                    .insert((m.name.clone(), name.clone()), identifier);
+            }
+
+            let children = m.modules.borrow();
+            match hierarchy {
+                Hierarchy::Preserved => {
+                    namespace.reserve(children.iter().map(|child| child.instance_name.as_str()));
+                    for child in children.iter() {
+                        let identifier = namespace.legalize(&child.instance_name);
+                        validate_not_reserved(
+                            IdentifierKind::Instance,
+                            m,
+                            &child.instance_name,
+                            &identifier,
+                        );
+                        ret.record(
+                            IdentifierKind::Instance,
+                            &m.name,
+                            &child.instance_name,
+                            &identifier,
+                        );
+                        ret.instances
+                            .insert((m.name.clone(), child.instance_name.clone()), identifier);
+                    }
+                    for child in children.iter() {
+                        for name in child
+                            .inputs
+                            .borrow()
+                            .keys()
+                            .chain(child.outputs.borrow().keys())
+                        {
+                            let identifier = namespace.unique(&sanitize(&format!(
+                                "__inst_{}_{}",
+                                child.instance_name, name
+                            )));
+                            ret.instance_ports.insert(
+                                (m.name.clone(), child.instance_name.clone(), name.clone()),
+                                identifier,
+                            );
+                        }
+                    }
+                }
+                Hierarchy::Flattened => {
+                    fn visit_module<'a>(
+                        module: &'a graph::Module<'a>,
+                        namespace: &mut Namespace,
+                        flattened_named_wires: &mut HashMap<(String, String), String>,
+                    ) {
+                        let instance_path = module.instance_path();
+                        for name in module.named_wires.borrow().keys() {
+                            let identifier =
+                                namespace.unique(&sanitize(&format!("{}_{}", instance_path, name)));
+                            if identifier.starts_with("__") {
+                                panic!("Cannot generate code for module \"{}\" because the named wire \"{}\" in instance \"{}\" would be called \"{}\" in the generated Verilog code, and names starting with \"__\" are reserved for generated identifiers.", module.name, name, instance_path, identifier);
+                            }
+                            flattened_named_wires
+                                .insert((instance_path.clone(), name.clone()), identifier);
+                        }
+                        for child in module.modules.borrow().iter() {
+                            visit_module(child, namespace, flattened_named_wires);
+                        }
+                    }
+                    for child in children.iter() {
+                        visit_module(child, &mut namespace, &mut ret.flattened_named_wires);
+                    }
+                }
+            }
+        }
+
+        ret
+    }
+
+    fn record(&mut self, kind: IdentifierKind, module_name: &str, name: &str, identifier: &str) {
+        if identifier != name && unescape(identifier) != name {
+            self.renamed.push(RenamedIdentifier {
+                kind,
+                module_name: module_name.into(),
+                name: name.into(),
+                identifier: identifier.into(),
+            });
+        }
+    }
+
+    /// Returns the identifier of the module called `name`.
+    pub fn module(&self, name: &str) -> &str {
+        &self.modules[name]
+    }
+
+    /// Returns the identifier of the input, output, or named wire called `name` in `m`.
+    pub fn local<'a>(&self, m: &'a graph::Module<'a>, name: &str) -> &str {
+        &self.locals[&(m.name.clone(), name.to_string())]
+    }
+
+    /// Returns the identifier of the child module instance `instance`.
+    pub fn instance<'a>(&self, instance: &'a graph::Module<'a>) -> &str {
+        let parent = instance.parent.unwrap();
+        &self.instances[&(parent.name.clone(), instance.instance_name.clone())]
+    }
+
+    /// Returns the identifier of the wire connected to port `port_name` of the child module instance `instance`.
+    pub fn instance_port<'a>(&self, instance: &'a graph::Module<'a>, port_name: &str) -> &str {
+        let parent = instance.parent.unwrap();
+        &self.instance_ports[&(
+            parent.name.clone(),
+            instance.instance_name.clone(),
+            port_name.to_string(),
+        )]
+    }
+
+    /// Returns the identifier of the named wire called `name` in `m`, which is a child instance that's flattened into the top-level module.
+    pub fn flattened_named_wire<'a>(&self, m: &'a graph::Module<'a>, name: &str) -> &str {
+        &self.flattened_named_wires[&(m.instance_path(), name.to_string())]
+    }
+}
+
+/// Panics if `identifier`, which is used for the item of kind `kind` called `name` in `m`, could collide with the identifier of a generated wire or register.
+fn validate_not_reserved<'a>(
+    kind: IdentifierKind,
+    m: &'a graph::Module<'a>,
+    name: &str,
+    identifier: &str,
+) {
+    // Generated wires and registers are all named `__<something>`
+    if unescape(identifier).starts_with("__") {
+        panic!("Cannot generate code for module \"{}\" because its {} \"{}\" would be called \"{}\" in the generated Verilog code, and names starting with \"__\" are reserved for generated identifiers.", m.name, kind, name, identifier);
+    }
+}
+
+/// Returns the name of the escaped identifier `identifier`, or `identifier` itself if it's not escaped.
+fn unescape(identifier: &str) -> &str {
+    identifier
+        .strip_prefix('\\')
+        .and_then(|identifier| identifier.strip_suffix(' '))
+        .unwrap_or(identifier)
+}
+
+/// A set of identifiers that must be distinct from each other.
+struct Namespace {
+    style: IdentifierStyle,
+    names: HashSet<String>,
+}
+
+impl Namespace {
+    fn new(style: IdentifierStyle) -> Namespace {
+        Namespace {
+            style,
+            names: HashSet::new(),
+        }
+    }
+
+    /// Reserves each name in `names` that can be used as-is, so that names that have to be changed are never changed to one of them.
+    fn reserve<'n>(&mut self, names: impl Iterator<Item = &'n str>) {
+        for name in names {
+            if is_identifier(name) && !is_keyword(name) {
+                self.names.insert(name.into());
+            }
+        }
+    }
+
+    /// Returns `base`, or `base` with a `_<n>` suffix if `base` is already used in this namespace, and marks the result as used.
+    fn unique(&mut self, base: &str) -> String {
+        let mut name = base.to_string();
+        let mut suffix = 1;
+        while self.names.contains(&name) {
+            name = format!("{}_{}", base, suffix);
+            suffix += 1;
+        }
+        self.names.insert(name.clone());
+        name
+    }
+
+    /// Returns the identifier to use for `name`, which is `name` itself if it's a valid identifier that's not a keyword.
+    ///
+    /// Otherwise, with [`IdentifierStyle::Sanitize`], characters that can't appear in an identifier are replaced with `_`, and a `_` is appended to keywords. With [`IdentifierStyle::Escape`], `name` is emitted as an escaped identifier, with whitespace and characters that aren't printable ASCII replaced with `_`. Either way, if the resulting name is already used in this namespace, a `_<n>` suffix is appended to make it unique.
+    fn legalize(&mut self, name: &str) -> String {
+        if is_identifier(name) && !is_keyword(name) {
+            self.names.insert(name.into());
+            return name.into();
+        }
+
+        let base = match self.style {
+            IdentifierStyle::Sanitize => {
+                let name = sanitize(name);
+                if is_keyword(&name) {
+                    format!("{}_", name)
+                } else {
+                    name
+                }
+            }
+            IdentifierStyle::Escape => {
+                if name.is_empty() {
+                    "_".into()
+                } else {
+                    name.chars()
+                        .map(|c| if c.is_ascii_graphic() { c } else { '_' })
+                        .collect()
+                }
+            }
+        };
+        let name = self.unique(&base);
+        if is_identifier(&name) && !is_keyword(&name) {
+            name
+        } else {
+            format!("\\{} ", name)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keywords_sorted() {
+        let mut sorted = KEYWORDS.to_vec();
+        sorted.sort_unstable();
+        assert_eq!(KEYWORDS, sorted.as_slice());
+    }
+
+    #[test]
+    fn escaped_names_are_unique() {
+        let mut namespace = Namespace::new(IdentifierStyle::Escape);
+        namespace.reserve(["a_b", "a b", "a\tb"].iter().copied());
+        assert_eq!(namespace.legalize("a_b"), "a_b");
+        assert_eq!(namespace.legalize("a b"), "a_b_1");
+        assert_eq!(namespace.legalize("a\tb"), "a_b_2");
+        assert_eq!(namespace.legalize("module"), "\\module ");
+        assert_eq!(namespace.legalize(""), "_");
+    }
+}
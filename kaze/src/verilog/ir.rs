@@ -1,3 +1,5 @@
+use super::LanguageStandard;
+
 use crate::code_writer;
 use crate::graph;
 
@@ -7,12 +9,18 @@ pub struct NodeDecl {
     pub net_type: NetType,
     pub name: String,
     pub bit_width: u32,
+    pub attributes: Vec<graph::Attribute>,
 }
 
 impl NodeDecl {
-    pub fn write<W: Write>(&self, w: &mut code_writer::CodeWriter<W>) -> Result<()> {
+    pub fn write<W: Write>(
+        &self,
+        language_standard: LanguageStandard,
+        w: &mut code_writer::CodeWriter<W>,
+    ) -> Result<()> {
         w.append_indent()?;
-        self.net_type.write(w)?;
+        write_attributes(&self.attributes, w)?;
+        self.net_type.write(language_standard, w)?;
         w.append(" ")?;
         if self.bit_width > 1 {
             w.append(&format!("[{}:{}] ", self.bit_width - 1, 0))?;
@@ -24,22 +32,56 @@ impl NodeDecl {
     }
 }
 
+/// Writes `attributes` as a single Verilog attribute instance (eg. `(* ns_key = "value" *) `), if there are any.
+///
+/// Attributes in the reserved namespace correspond to standard Verilog attributes, so they're written without a namespace prefix (eg. `(* keep = "true" *) `).
+pub fn write_attributes<W: Write>(
+    attributes: &[graph::Attribute],
+    w: &mut code_writer::CodeWriter<W>,
+) -> Result<()> {
+    if attributes.is_empty() {
+        return Ok(());
+    }
+
+    w.append("(* ")?;
+    for (i, attribute) in attributes.iter().enumerate() {
+        if i > 0 {
+            w.append(", ")?;
+        }
+        if attribute.namespace != graph::RESERVED_ATTRIBUTE_NAMESPACE {
+            w.append(&format!("{}_", attribute.namespace))?;
+        }
+        w.append(&format!(
+            "{} = \"{}\"",
+            attribute.key,
+            attribute.value.replace('\\', "\\\\").replace('"', "\\\"")
+        ))?;
+    }
+    w.append(" *) ")
+}
+
 pub enum NetType {
     Reg,
     Wire,
 }
 
 impl NetType {
-    pub fn write<W: Write>(&self, w: &mut code_writer::CodeWriter<W>) -> Result<()> {
-        w.append(match self {
-            NetType::Reg => "reg",
-            NetType::Wire => "wire",
+    pub fn write<W: Write>(
+        &self,
+        language_standard: LanguageStandard,
+        w: &mut code_writer::CodeWriter<W>,
+    ) -> Result<()> {
+        w.append(match (language_standard, self) {
+            (LanguageStandard::Verilog2001, NetType::Reg) => "reg",
+            (LanguageStandard::Verilog2001, NetType::Wire) => "wire",
+            (LanguageStandard::SystemVerilog, _) => "logic",
         })
     }
 }
 
 pub struct AssignmentContext {
     assignments: Vec<Assignment>,
+    case_blocks: Vec<CaseBlock>,
     local_decls: Vec<NodeDecl>,
 }
 
@@ -47,17 +89,39 @@ impl AssignmentContext {
     pub fn new() -> AssignmentContext {
         AssignmentContext {
             assignments: Vec::new(),
+            case_blocks: Vec::new(),
             local_decls: Vec::new(),
         }
     }
 
     pub fn gen_temp(&mut self, expr: Expr, bit_width: u32, name_prefix: String) -> Expr {
+        self.gen_temp_with_attributes(expr, bit_width, name_prefix, Vec::new())
+    }
+
+    pub fn gen_temp_with_attributes(
+        &mut self,
+        expr: Expr,
+        bit_width: u32,
+        name_prefix: String,
+        attributes: Vec<graph::Attribute>,
+    ) -> Expr {
         let name = format!("__temp_{}_{}", name_prefix, self.local_decls.len());
 
+        self.gen_named_wire(expr, bit_width, name, attributes)
+    }
+
+    pub fn gen_named_wire(
+        &mut self,
+        expr: Expr,
+        bit_width: u32,
+        name: String,
+        attributes: Vec<graph::Attribute>,
+    ) -> Expr {
         self.local_decls.push(NodeDecl {
             net_type: NetType::Wire,
             name: name.clone(),
             bit_width,
+            attributes,
         });
 
         self.assignments.push(Assignment {
@@ -68,18 +132,52 @@ impl AssignmentContext {
         Expr::Ref { name }
     }
 
+    /// Generates a temp `reg` that's assigned by a combinational `case` statement, and returns a reference to it.
+    pub fn gen_case(
+        &mut self,
+        sel: Expr,
+        sel_bit_width: u32,
+        cases: Vec<(u128, Expr)>,
+        default: Expr,
+        bit_width: u32,
+        name_prefix: String,
+    ) -> Expr {
+        let name = format!("__temp_{}_{}", name_prefix, self.local_decls.len());
+
+        self.local_decls.push(NodeDecl {
+            net_type: NetType::Reg,
+            name: name.clone(),
+            bit_width,
+            attributes: Vec::new(),
+        });
+
+        self.case_blocks.push(CaseBlock {
+            target_name: name.clone(),
+            sel,
+            sel_bit_width,
+            cases,
+            default,
+        });
+
+        Expr::Ref { name }
+    }
+
     pub fn is_empty(&self) -> bool {
-        self.assignments.is_empty()
+        self.assignments.is_empty() && self.case_blocks.is_empty()
     }
 
     pub fn push(&mut self, assignment: Assignment) {
         self.assignments.push(assignment);
     }
 
-    pub fn write<W: Write>(&self, w: &mut code_writer::CodeWriter<W>) -> Result<()> {
+    pub fn write<W: Write>(
+        &self,
+        language_standard: LanguageStandard,
+        w: &mut code_writer::CodeWriter<W>,
+    ) -> Result<()> {
         if !self.local_decls.is_empty() {
             for node_decl in self.local_decls.iter() {
-                node_decl.write(w)?;
+                node_decl.write(language_standard, w)?;
             }
             w.append_newline()?;
         }
@@ -88,6 +186,61 @@ impl AssignmentContext {
             assignment.write(w)?;
         }
 
+        for case_block in self.case_blocks.iter() {
+            w.append_newline()?;
+            case_block.write(language_standard, w)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A combinational `case` statement that assigns one of several values to `target_name`, depending on the value of `sel`.
+struct CaseBlock {
+    target_name: String,
+    sel: Expr,
+    sel_bit_width: u32,
+    cases: Vec<(u128, Expr)>,
+    default: Expr,
+}
+
+impl CaseBlock {
+    fn write<W: Write>(
+        &self,
+        language_standard: LanguageStandard,
+        w: &mut code_writer::CodeWriter<W>,
+    ) -> Result<()> {
+        w.append_line(match language_standard {
+            LanguageStandard::Verilog2001 => "always @(*) begin",
+            LanguageStandard::SystemVerilog => "always_comb begin",
+        })?;
+        w.indent();
+        w.append_indent()?;
+        w.append("case (")?;
+        self.sel.write(w)?;
+        w.append(")")?;
+        w.append_newline()?;
+        w.indent();
+        for (value, expr) in self.cases.iter() {
+            w.append_indent()?;
+            w.append(&format!(
+                "{}'h{:x}: {} = ",
+                self.sel_bit_width, value, self.target_name
+            ))?;
+            expr.write(w)?;
+            w.append(";")?;
+            w.append_newline()?;
+        }
+        w.append_indent()?;
+        w.append(&format!("default: {} = ", self.target_name))?;
+        self.default.write(w)?;
+        w.append(";")?;
+        w.append_newline()?;
+        w.unindent();
+        w.append_line("endcase")?;
+        w.unindent();
+        w.append_line("end")?;
+
         Ok(())
     }
 }
@@ -100,8 +253,8 @@ pub struct Assignment {
 impl Assignment {
     fn write<W: Write>(&self, w: &mut code_writer::CodeWriter<W>) -> Result<()> {
         w.append_indent()?;
-        w.append(&format!("assign {}", self.target_name))?;
-        w.append(" = ")?;
+        w.append(&format!("assign {} =", self.target_name))?;
+        w.append_break()?;
         self.expr.write(w)?;
         w.append(";")?;
         w.append_newline()?;
@@ -112,6 +265,10 @@ impl Assignment {
 
 #[derive(Clone)]
 pub enum Expr {
+    ArrayIndex {
+        target: Box<Expr>,
+        index: Box<Expr>,
+    },
     BinOp {
         lhs: Box<Expr>,
         rhs: Box<Expr>,
@@ -126,10 +283,16 @@ pub enum Expr {
         lhs: Box<Expr>,
         rhs: Box<Expr>,
     },
+    ConcatList {
+        sources: Vec<Expr>,
+    },
     Constant {
         bit_width: u32,
         value: u128,
     },
+    HighImpedance {
+        bit_width: u32,
+    },
     Ref {
         name: String,
     },
@@ -161,10 +324,16 @@ impl Expr {
 
     pub fn write<W: Write>(&self, w: &mut code_writer::CodeWriter<W>) -> Result<()> {
         match self {
+            Expr::ArrayIndex { target, index } => {
+                target.write(w)?;
+                w.append("[")?;
+                index.write(w)?;
+                w.append("]")?;
+            }
             Expr::BinOp { lhs, rhs, op } => {
                 lhs.write(w)?;
                 w.append(&format!(
-                    " {} ",
+                    " {}",
                     match op {
                         BinOp::Add => "+",
                         BinOp::BitAnd => "&",
@@ -176,6 +345,7 @@ impl Expr {
                         BinOp::LessThanEqual => "<=",
                         BinOp::GreaterThan => ">",
                         BinOp::GreaterThanEqual => ">=",
+                        BinOp::Rem => "%",
                         BinOp::Shl => "<<",
                         BinOp::Shr => ">>",
                         BinOp::ShrArithmetic => ">>>",
@@ -183,6 +353,7 @@ impl Expr {
                         BinOp::Mul => "*",
                     }
                 ))?;
+                w.append_break()?;
                 rhs.write(w)?;
             }
             Expr::Bits {
@@ -200,13 +371,28 @@ impl Expr {
             Expr::Concat { lhs, rhs } => {
                 w.append("{")?;
                 lhs.write(w)?;
-                w.append(", ")?;
+                w.append(",")?;
+                w.append_break()?;
                 rhs.write(w)?;
                 w.append("}")?;
             }
+            Expr::ConcatList { sources } => {
+                w.append("{")?;
+                for (i, source) in sources.iter().enumerate() {
+                    if i > 0 {
+                        w.append(",")?;
+                        w.append_break()?;
+                    }
+                    source.write(w)?;
+                }
+                w.append("}")?;
+            }
             Expr::Constant { bit_width, value } => {
                 w.append(&format!("{}'h{:x}", bit_width, value))?;
             }
+            Expr::HighImpedance { bit_width } => {
+                w.append(&format!("{}'bz", bit_width))?;
+            }
             Expr::Ref { name } => {
                 w.append(name)?;
             }
@@ -226,9 +412,11 @@ impl Expr {
                 when_false,
             } => {
                 cond.write(w)?;
-                w.append(" ? ")?;
+                w.append(" ?")?;
+                w.append_break()?;
                 when_true.write(w)?;
-                w.append(" : ")?;
+                w.append(" :")?;
+                w.append_break()?;
                 when_false.write(w)?;
             }
             Expr::UnOp { source, op } => {
@@ -255,6 +443,7 @@ pub enum BinOp {
     LessThanEqual,
     GreaterThan,
     GreaterThanEqual,
+    Rem,
     Shl,
     Shr,
     ShrArithmetic,
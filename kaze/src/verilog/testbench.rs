@@ -0,0 +1,503 @@
+use super::identifiers::*;
+use super::IdentifierStyle;
+
+use crate::code_writer;
+use crate::graph;
+use crate::identifiers::*;
+use crate::state_elements::*;
+use crate::validation::*;
+
+use std::io::{Result, Write};
+
+/// The stimulus and expected results for a single clock cycle of a testbench generated by [`generate_testbench`].
+///
+/// Input values that aren't assigned in a cycle keep their values from the previous cycle (or `0` in the first cycle), just like the input fields of a generated simulator.
+///
+/// # Examples
+///
+/// ```
+/// use kaze::verilog::Cycle;
+///
+/// let cycle = Cycle::new()
+///     .input("a", 0x12u32)
+///     .input("b", true)
+///     .expect_output("o", 0x13u32);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Cycle {
+    inputs: Vec<(String, u128)>,
+    expected_outputs: Vec<(String, u128)>,
+}
+
+impl Cycle {
+    /// Creates a new `Cycle` without any input assignments or expected outputs.
+    pub fn new() -> Cycle {
+        Cycle::default()
+    }
+
+    /// Assigns `value` to the input called `name` at the start of this cycle.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a value was already assigned to the input called `name` in this cycle.
+    pub fn input(mut self, name: impl Into<String>, value: impl Into<u128>) -> Cycle {
+        let name = name.into();
+        if self.inputs.iter().any(|(n, _)| *n == name) {
+            panic!(
+                "Cannot assign a value to input \"{}\", because a value was already assigned to this input in the same cycle.",
+                name
+            );
+        }
+        self.inputs.push((name, value.into()));
+        self
+    }
+
+    /// Expects the output called `name` to be equal to `value` once this cycle's inputs have been assigned, before the next rising clock edge.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a value was already expected for the output called `name` in this cycle.
+    pub fn expect_output(mut self, name: impl Into<String>, value: impl Into<u128>) -> Cycle {
+        let name = name.into();
+        if self.expected_outputs.iter().any(|(n, _)| *n == name) {
+            panic!(
+                "Cannot expect a value for output \"{}\", because a value was already expected for this output in the same cycle.",
+                name
+            );
+        }
+        self.expected_outputs.push((name, value.into()));
+        self
+    }
+}
+
+/// Generates a self-checking Verilog testbench for the module generated by [`generate`](super::generate) for `m`, which drives the module with the inputs in `cycles` and calls `$fatal` as soon as an output doesn't have its expected value.
+///
+/// The testbench is a module called `<module name>_tb` without any ports, which instantiates `m` (called `dut`) with the default clock and reset port names and [identifier style](super::IdentifierStyle). It first asserts all resets, then releases them and, for each cycle in order, assigns that cycle's inputs, compares the outputs with the expected values, and raises the clock. The clocks of [dual-clock](crate::Mem::dual_clock) memories and [clock domain](crate::Module::reg_in_domain) registers are raised together with the implicit clock. If all comparisons succeed, it calls `$finish`.
+///
+/// This mirrors the way a generated simulator is typically driven, so the cycles can be recorded from a simulator that's reset (with `reset()`, and the reset method for each named reset domain) before it's run, by assigning inputs, calling `prop()`, sampling outputs, and calling `posedge_clk()` each cycle. Outputs are compared with `!==`, so outputs that depend on registers without default values (which are undefined in Verilog, but `0` in a generated simulator) are reported as mismatches.
+///
+/// Note that `$fatal` is a SystemVerilog system task, so unlike the code generated by [`generate`](super::generate), the testbench requires a simulator with (at least partial) SystemVerilog support, such as Icarus Verilog.
+///
+/// # Panics
+///
+/// Panics under the same conditions as [`generate`](super::generate), if `m` has any [inouts](crate::Module::inout), which testbenches can't drive, or if any cycle assigns an input or expects an output that `m` doesn't have, or uses a value that doesn't fit in the corresponding port's bit width.
+///
+/// # Examples
+///
+/// ```
+/// use kaze::*;
+/// use kaze::verilog::Cycle;
+///
+/// let c = Context::new();
+///
+/// let m = c.module("m", "Inverter");
+/// m.output("o", !m.input("i", 1));
+///
+/// let cycles = [
+///     Cycle::new().input("i", false).expect_output("o", true),
+///     Cycle::new().input("i", true).expect_output("o", false),
+/// ];
+///
+/// let mut testbench = Vec::new();
+/// verilog::generate_testbench(m, &cycles, &mut testbench).unwrap();
+/// ```
+pub fn generate_testbench<'a, W: Write>(
+    m: &'a graph::Module<'a>,
+    cycles: &[Cycle],
+    w: W,
+) -> Result<()> {
+    validate_module_hierarchy(m);
+    validate_clock_and_reset_names(m, "clk", "reset_n");
+    let reset_domain_ports = super::hierarchy_reset_domain_names(m)
+        .into_iter()
+        .map(|name| {
+            let port = format!("reset_{}_n", name);
+            (name, port)
+        })
+        .collect::<Vec<_>>();
+    // Only the top-level module's ports are connected in the testbench
+    validate_clock_and_reset_ports(m, "clk", "reset_n", &reset_domain_ports, false);
+
+    if let Some(name) = m.inouts.borrow().keys().next() {
+        panic!("Cannot generate testbench for module \"{}\", because it has an inout called \"{}\", and testbenches don't support inouts.", m.name, name);
+    }
+
+    let inputs = m.inputs.borrow();
+    let outputs = m.outputs.borrow();
+    for (index, cycle) in cycles.iter().enumerate() {
+        for (name, value) in cycle.inputs.iter() {
+            let input = inputs.get(name).unwrap_or_else(|| panic!("Cannot generate testbench for module \"{}\", because cycle {} assigns a value to input \"{}\", which this module doesn't have.", m.name, index, name));
+            validate_value_fits(m, index, "input", name, *value, input.data.bit_width);
+        }
+        for (name, value) in cycle.expected_outputs.iter() {
+            let output = outputs.get(name).unwrap_or_else(|| panic!("Cannot generate testbench for module \"{}\", because cycle {} expects a value for output \"{}\", which this module doesn't have.", m.name, index, name));
+            validate_value_fits(m, index, "output", name, *value, output.data.bit_width);
+        }
+    }
+
+    let reset_names = std::iter::once("reset_n".to_string())
+        .chain(
+            super::hierarchy_reset_domain_names(m)
+                .iter()
+                .map(|name| format!("reset_{}_n", name)),
+        )
+        .collect::<Vec<_>>();
+    let clock_names = super::hierarchy_clock_names(m)
+        .into_iter()
+        .chain(std::iter::once("clk".to_string()))
+        .collect::<Vec<_>>();
+
+    // The module's ports are declared in the testbench with the same identifiers
+    let identifiers = Identifiers::new(&[m], Hierarchy::Flattened, IdentifierStyle::default());
+    let port = |name: &str| identifiers.local(m, name);
+
+    let mut w = code_writer::CodeWriter::new(w);
+
+    w.append_line(&format!("module {};", sanitize(&format!("{}_tb", m.name))))?;
+    w.indent();
+
+    for name in reset_names.iter() {
+        w.append_line(&format!("reg {};", name))?;
+    }
+    for name in clock_names.iter() {
+        w.append_line(&format!("reg {};", name))?;
+    }
+    for (name, input) in inputs.iter() {
+        w.append_line(&format!(
+            "reg {}{};",
+            bit_range(input.data.bit_width),
+            port(name)
+        ))?;
+    }
+    for (name, output) in outputs.iter() {
+        w.append_line(&format!(
+            "wire {}{};",
+            bit_range(output.data.bit_width),
+            port(name)
+        ))?;
+    }
+    w.append_newline()?;
+
+    let port_names = reset_names
+        .iter()
+        .chain(clock_names.iter())
+        .map(|name| name.as_str())
+        .chain(inputs.keys().chain(outputs.keys()).map(|name| port(name)))
+        .collect::<Vec<_>>();
+    w.append_line(&format!("{} dut(", identifiers.module(&m.name)))?;
+    w.indent();
+    let num_port_names = port_names.len();
+    for (i, name) in port_names.iter().enumerate() {
+        w.append_indent()?;
+        w.append(&format!(".{}({})", name, name))?;
+        if i < num_port_names - 1 {
+            w.append(",")?;
+        }
+        w.append_newline()?;
+    }
+    w.unindent();
+    w.append_line(");")?;
+    w.append_newline()?;
+
+    w.append_line("initial begin")?;
+    w.indent();
+
+    for name in reset_names.iter() {
+        w.append_line(&format!("{} = 1'b0;", name))?;
+    }
+    for name in clock_names.iter() {
+        w.append_line(&format!("{} = 1'b0;", name))?;
+    }
+    for (name, input) in inputs.iter() {
+        w.append_line(&format!("{} = {}'h0;", port(name), input.data.bit_width))?;
+    }
+    w.append_line("#1;")?;
+    for name in reset_names.iter() {
+        w.append_line(&format!("{} = 1'b1;", name))?;
+    }
+    w.append_line("#1;")?;
+
+    for (index, cycle) in cycles.iter().enumerate() {
+        w.append_newline()?;
+        w.append_line(&format!("// Cycle {}", index))?;
+        for (name, value) in cycle.inputs.iter() {
+            w.append_line(&format!(
+                "{} = {}'h{:x};",
+                port(name),
+                inputs[name].data.bit_width,
+                value
+            ))?;
+        }
+        w.append_line("#1;")?;
+        for (name, value) in cycle.expected_outputs.iter() {
+            let bit_width = outputs[name].data.bit_width;
+            w.append_line(&format!(
+                "if ({} !== {}'h{:x}) $fatal(1, \"Cycle {}: expected output {} to be {}'h{:x}, but it was {}'h%h\", {});",
+                port(name),
+                bit_width,
+                value,
+                index,
+                super::escape_string(name),
+                bit_width,
+                value,
+                bit_width,
+                port(name)
+            ))?;
+        }
+        for name in clock_names.iter() {
+            w.append_line(&format!("{} = 1'b1;", name))?;
+        }
+        w.append_line("#1;")?;
+        for name in clock_names.iter() {
+            w.append_line(&format!("{} = 1'b0;", name))?;
+        }
+    }
+
+    w.append_newline()?;
+    w.append_line("$finish;")?;
+
+    w.unindent();
+    w.append_line("end")?;
+
+    w.unindent();
+    w.append_line("endmodule")?;
+    w.append_newline()?;
+
+    Ok(())
+}
+
+fn validate_value_fits<'a>(
+    m: &'a graph::Module<'a>,
+    cycle_index: usize,
+    port_desc: &str,
+    name: &str,
+    value: u128,
+    bit_width: u32,
+) {
+    if bit_width < 128 && value >> bit_width != 0 {
+        panic!("Cannot generate testbench for module \"{}\", because cycle {} uses the value {:#x} for {} \"{}\", which doesn't fit in its bit width of {} bit(s).", m.name, cycle_index, value, port_desc, name, bit_width);
+    }
+}
+
+fn bit_range(bit_width: u32) -> String {
+    if bit_width > 1 {
+        format!("[{}:{}] ", bit_width - 1, 0)
+    } else {
+        String::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::*;
+
+    fn generate_to_string<'a>(m: &'a Module<'a>, cycles: &[Cycle]) -> String {
+        let mut v = Vec::new();
+        generate_testbench(m, cycles, &mut v).unwrap();
+        String::from_utf8(v).unwrap()
+    }
+
+    #[test]
+    fn testbench() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        let soft = m.reset_domain("soft");
+        let i = m.input("i", 8);
+        let r = m.reg("r", 8);
+        r.default_value(0x12u32);
+        r.reset_domain(soft);
+        r.drive_next(i);
+        m.output("o", r);
+        m.output("eq", r.eq(i));
+
+        let cycles = [
+            Cycle::new()
+                .input("i", 0x34u32)
+                .expect_output("o", 0x12u32)
+                .expect_output("eq", false),
+            Cycle::new()
+                .expect_output("o", 0x34u32)
+                .expect_output("eq", true),
+        ];
+
+        assert_eq!(
+            generate_to_string(m, &cycles),
+            "module M_tb;
+    reg reset_n;
+    reg reset_soft_n;
+    reg clk;
+    reg [7:0] i;
+    wire eq;
+    wire [7:0] o;
+
+    M dut(
+        .reset_n(reset_n),
+        .reset_soft_n(reset_soft_n),
+        .clk(clk),
+        .i(i),
+        .eq(eq),
+        .o(o)
+    );
+
+    initial begin
+        reset_n = 1'b0;
+        reset_soft_n = 1'b0;
+        clk = 1'b0;
+        i = 8'h0;
+        #1;
+        reset_n = 1'b1;
+        reset_soft_n = 1'b1;
+        #1;
+
+        // Cycle 0
+        i = 8'h34;
+        #1;
+        if (o !== 8'h12) $fatal(1, \"Cycle 0: expected output o to be 8'h12, but it was 8'h%h\", o);
+        if (eq !== 1'h0) $fatal(1, \"Cycle 0: expected output eq to be 1'h0, but it was 1'h%h\", eq);
+        clk = 1'b1;
+        #1;
+        clk = 1'b0;
+
+        // Cycle 1
+        #1;
+        if (o !== 8'h34) $fatal(1, \"Cycle 1: expected output o to be 8'h34, but it was 8'h%h\", o);
+        if (eq !== 1'h1) $fatal(1, \"Cycle 1: expected output eq to be 1'h1, but it was 1'h%h\", eq);
+        clk = 1'b1;
+        #1;
+        clk = 1'b0;
+
+        $finish;
+    end
+endmodule
+
+"
+        );
+    }
+
+    #[test]
+    fn testbench_dual_clock_mem() {
+        let c = Context::new();
+
+        let m = c.module("m", "M");
+        let mem = m.mem("mem", 1, 1);
+        mem.dual_clock("read_clk", "write_clk");
+        mem.write_port(m.low(), m.input("i", 1), m.high());
+        m.output("o", mem.read_port(m.low(), m.high()));
+
+        let tb = generate_to_string(m, &[Cycle::new().input("i", true)]);
+
+        assert!(tb.contains("    reg read_clk;\n    reg write_clk;\n    reg clk;\n"));
+        assert!(tb.contains(
+            "        .read_clk(read_clk),\n        .write_clk(write_clk),\n        .clk(clk),\n"
+        ));
+        assert!(tb.contains(
+            "        read_clk = 1'b1;\n        write_clk = 1'b1;\n        clk = 1'b1;\n        #1;\n        read_clk = 1'b0;\n        write_clk = 1'b0;\n        clk = 1'b0;\n"
+        ));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot assign a value to input \"i\", because a value was already assigned to this input in the same cycle."
+    )]
+    fn cycle_duplicate_input_error() {
+        // Panic
+        let _ = Cycle::new().input("i", true).input("i", false);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot expect a value for output \"o\", because a value was already expected for this output in the same cycle."
+    )]
+    fn cycle_duplicate_output_error() {
+        // Panic
+        let _ = Cycle::new()
+            .expect_output("o", true)
+            .expect_output("o", false);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot generate testbench for module \"A\", because it has an inout called \"io\", and testbenches don't support inouts."
+    )]
+    fn inout_error() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        let io = a.inout("io", 1);
+        io.drive(!io, a.input("oe", 1));
+
+        // Panic
+        generate_to_string(a, &[Cycle::new()]);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot generate testbench for module \"A\", because cycle 1 assigns a value to input \"j\", which this module doesn't have."
+    )]
+    fn unknown_input_error() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        a.output("o", a.input("i", 1));
+
+        // Panic
+        generate_to_string(a, &[Cycle::new(), Cycle::new().input("j", true)]);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot generate testbench for module \"A\", because cycle 0 expects a value for output \"p\", which this module doesn't have."
+    )]
+    fn unknown_output_error() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        a.output("o", a.input("i", 1));
+
+        // Panic
+        generate_to_string(a, &[Cycle::new().expect_output("p", true)]);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Cannot generate testbench for module \"A\", because cycle 0 uses the value 0x100 for input \"i\", which doesn't fit in its bit width of 8 bit(s)."
+    )]
+    fn input_value_too_wide_error() {
+        let c = Context::new();
+
+        let a = c.module("a", "A");
+        a.output("o", a.input("i", 8));
+
+        // Panic
+        generate_to_string(a, &[Cycle::new().input("i", 0x100u32)]);
+    }
+
+    #[test]
+    fn testbench_renamed_identifiers() {
+        let c = Context::new();
+
+        let m = c.module("m", "fifo<8>");
+        m.output("wire", m.input("data in", 8));
+
+        let v = generate_to_string(
+            m,
+            &[Cycle::new()
+                .input("data in", 0x12u32)
+                .expect_output("wire", 0x12u32)],
+        );
+
+        // The testbench uses the same identifiers as the generated module
+        assert!(v.contains("module fifo_8__tb;"));
+        assert!(v.contains("reg [7:0] data_in;"));
+        assert!(v.contains("wire [7:0] wire_;"));
+        assert!(v.contains("fifo_8_ dut("));
+        assert!(v.contains(".data_in(data_in),"));
+        assert!(v.contains("data_in = 8'h12;"));
+        assert!(v.contains(
+            "if (wire_ !== 8'h12) $fatal(1, \"Cycle 0: expected output wire to be 8'h12, but it was 8'h%h\", wire_);"
+        ));
+    }
+}
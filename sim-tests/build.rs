@@ -17,11 +17,30 @@ fn main() -> Result<()> {
         sim::GenerationOptions::default(),
         &mut file,
     )?;
+    sim::generate(
+        input_masking(&p),
+        sim::GenerationOptions {
+            override_module_name: Some("StrictInputMasking".into()),
+            strict_inputs: true,
+            ..sim::GenerationOptions::default()
+        },
+        &mut file,
+    )?;
+    sim::generate(
+        signed_lit_test_module(&p),
+        sim::GenerationOptions::default(),
+        &mut file,
+    )?;
     sim::generate(
         widest_input(&p),
         sim::GenerationOptions::default(),
         &mut file,
     )?;
+    sim::generate(
+        le_bytes_test_module(&p),
+        sim::GenerationOptions::default(),
+        &mut file,
+    )?;
     sim::generate(
         add_test_module(&p),
         sim::GenerationOptions::default(),
@@ -32,6 +51,95 @@ fn main() -> Result<()> {
         sim::GenerationOptions::default(),
         &mut file,
     )?;
+    sim::generate(
+        carry_test_module(&p),
+        sim::GenerationOptions::default(),
+        &mut file,
+    )?;
+    sim::generate(
+        priority_encode_test_module(&p),
+        sim::GenerationOptions {
+            max_line_width: Some(100),
+            ..sim::GenerationOptions::default()
+        },
+        &mut file,
+    )?;
+    sim::generate(
+        onehot_test_module(&p),
+        sim::GenerationOptions::default(),
+        &mut file,
+    )?;
+    sim::generate(
+        dual_clock_mem_test_module(&p),
+        sim::GenerationOptions::default(),
+        &mut file,
+    )?;
+    sim::generate(
+        reg_clock_domain_test_module(&p),
+        sim::GenerationOptions::default(),
+        &mut file,
+    )?;
+    let negedge_test_module = negedge_test_module(&p);
+    sim::generate(
+        negedge_test_module,
+        sim::GenerationOptions::default(),
+        &mut file,
+    )?;
+    sim::generate(
+        negedge_test_module,
+        sim::GenerationOptions {
+            override_module_name: Some("NegedgeTestModuleIo".into()),
+            io_structs: true,
+            ..sim::GenerationOptions::default()
+        },
+        &mut file,
+    )?;
+    sim::generate(
+        negedge_test_module,
+        sim::GenerationOptions {
+            override_module_name: Some("NegedgeTestModuleWrapped".into()),
+            io_structs: true,
+            module_visibility: sim::Visibility::Crate,
+            wrapper_module: Some("negedge_test_module_wrapped".into()),
+            doc_comments: true,
+            ..sim::GenerationOptions::default()
+        },
+        &mut file,
+    )?;
+    sim::generate(
+        assertion_test_module(&p),
+        sim::GenerationOptions::default(),
+        &mut file,
+    )?;
+    sim::generate(
+        assert_always_test_module(&p),
+        sim::GenerationOptions::default(),
+        &mut file,
+    )?;
+    sim::generate(
+        assert_always_test_module(&p),
+        sim::GenerationOptions {
+            override_module_name: Some("AssertAlwaysCollectTestModule".into()),
+            collect_assertion_failures: true,
+            ..sim::GenerationOptions::default()
+        },
+        &mut file,
+    )?;
+    sim::generate(
+        cover_test_module(&p),
+        sim::GenerationOptions::default(),
+        &mut file,
+    )?;
+    sim::generate(
+        clock_divider_test_module(&p),
+        sim::GenerationOptions::default(),
+        &mut file,
+    )?;
+    sim::generate(
+        signal_vec_test_module(&p),
+        sim::GenerationOptions::default(),
+        &mut file,
+    )?;
     sim::generate(
         mul_test_module(&p),
         sim::GenerationOptions::default(),
@@ -57,6 +165,25 @@ fn main() -> Result<()> {
         sim::GenerationOptions::default(),
         &mut file,
     )?;
+    sim::generate(
+        shift_fill_test_module(&p),
+        sim::GenerationOptions::default(),
+        &mut file,
+    )?;
+    sim::generate(
+        rotate_test_module(&p),
+        sim::GenerationOptions::default(),
+        &mut file,
+    )?;
+    sim::generate(
+        rotate_test_module(&p),
+        sim::GenerationOptions {
+            override_module_name: Some("RotateTestModuleOptimized".into()),
+            optimize: true,
+            ..sim::GenerationOptions::default()
+        },
+        &mut file,
+    )?;
     sim::generate(
         bit_and_test_module(&p),
         sim::GenerationOptions::default(),
@@ -212,6 +339,156 @@ fn main() -> Result<()> {
         sim::GenerationOptions::default(),
         &mut file,
     )?;
+    let mem_reset_test_module = mem_reset_test_module(&p);
+    sim::generate(
+        mem_reset_test_module,
+        sim::GenerationOptions {
+            override_module_name: Some("MemResetTestModule0".into()),
+            ..sim::GenerationOptions::default()
+        },
+        &mut file,
+    )?;
+    sim::generate(
+        mem_reset_test_module,
+        sim::GenerationOptions {
+            override_module_name: Some("MemResetTestModule1".into()),
+            reset_mems: true,
+            ..sim::GenerationOptions::default()
+        },
+        &mut file,
+    )?;
+    sim::generate(
+        mem_comb_read_test_module(&p),
+        sim::GenerationOptions::default(),
+        &mut file,
+    )?;
+    sim::generate(
+        case_test_module(&p),
+        sim::GenerationOptions::default(),
+        &mut file,
+    )?;
+    sim::generate(
+        inout_test_module(&p),
+        sim::GenerationOptions::default(),
+        &mut file,
+    )?;
+    sim::generate(
+        poison_test_module(&p),
+        sim::GenerationOptions {
+            poison_uninitialized_regs: true,
+            ..sim::GenerationOptions::default()
+        },
+        &mut file,
+    )?;
+    let dont_care_test_module = dont_care_test_module(&p);
+    sim::generate(
+        dont_care_test_module,
+        sim::GenerationOptions::default(),
+        &mut file,
+    )?;
+    sim::generate(
+        dont_care_test_module,
+        sim::GenerationOptions {
+            override_module_name: Some("DontCareTestModuleOptimized".into()),
+            optimize: true,
+            ..sim::GenerationOptions::default()
+        },
+        &mut file,
+    )?;
+    sim::generate(
+        dont_care_test_module,
+        sim::GenerationOptions {
+            override_module_name: Some("DontCareTestModulePoisoned".into()),
+            poison_dont_care_outputs: true,
+            ..sim::GenerationOptions::default()
+        },
+        &mut file,
+    )?;
+    sim::generate(
+        lit_from_str_test_module(&p),
+        sim::GenerationOptions::default(),
+        &mut file,
+    )?;
+    sim::generate(
+        bool_network_test_module(&p),
+        sim::GenerationOptions::default(),
+        &mut file,
+    )?;
+    sim::generate(
+        register_file_test_module_0(&p),
+        sim::GenerationOptions::default(),
+        &mut file,
+    )?;
+    sim::generate(
+        register_file_test_module_1(&p),
+        sim::GenerationOptions::default(),
+        &mut file,
+    )?;
+    sim::generate(
+        debug_port_test_module(&p),
+        sim::GenerationOptions::default(),
+        &mut file,
+    )?;
+    sim::generate(
+        strobe_select_test_module(&p),
+        sim::GenerationOptions::default(),
+        &mut file,
+    )?;
+    sim::generate(
+        align_strobe_test_module_0(&p),
+        sim::GenerationOptions::default(),
+        &mut file,
+    )?;
+    sim::generate(
+        align_strobe_test_module_1(&p),
+        sim::GenerationOptions::default(),
+        &mut file,
+    )?;
+    sim::generate(
+        fifo_test_module_0(&p),
+        sim::GenerationOptions::default(),
+        &mut file,
+    )?;
+    sim::generate(
+        fifo_test_module_1(&p),
+        sim::GenerationOptions::default(),
+        &mut file,
+    )?;
+    sim::generate(
+        edge_detect_test_module(&p),
+        sim::GenerationOptions::default(),
+        &mut file,
+    )?;
+    sim::generate(
+        saturating_test_module(&p),
+        sim::GenerationOptions::default(),
+        &mut file,
+    )?;
+    sim::generate(
+        reset_domain_test_module(&p),
+        sim::GenerationOptions::default(),
+        &mut file,
+    )?;
+    sim::generate(
+        bits_dyn_test_module(&p),
+        sim::GenerationOptions::default(),
+        &mut file,
+    )?;
+    let optimize_test_module = optimize_test_module(&p);
+    sim::generate(
+        optimize_test_module,
+        sim::GenerationOptions::default(),
+        &mut file,
+    )?;
+    sim::generate(
+        optimize_test_module,
+        sim::GenerationOptions {
+            override_module_name: Some("OptimizeTestModuleOptimized".into()),
+            optimize: true,
+            ..sim::GenerationOptions::default()
+        },
+        &mut file,
+    )?;
     sim::generate(
         trace_test_module_0(&p),
         sim::GenerationOptions {
@@ -228,6 +505,16 @@ fn main() -> Result<()> {
         },
         &mut file,
     )?;
+    sim::generate(
+        trace_test_module_1(&p),
+        sim::GenerationOptions {
+            override_module_name: Some("TraceOnChangeTestModule1".into()),
+            tracing: true,
+            trace_on_change_only: true,
+            ..sim::GenerationOptions::default()
+        },
+        &mut file,
+    )?;
     sim::generate(
         trace_test_module_2(&p),
         sim::GenerationOptions {
@@ -244,87 +531,454 @@ fn main() -> Result<()> {
         },
         &mut file,
     )?;
+    sim::generate(
+        named_wire_test_module(&p),
+        sim::GenerationOptions {
+            tracing: true,
+            ..sim::GenerationOptions::default()
+        },
+        &mut file,
+    )?;
     sim::generate(
         deep_graph_test_module(&p),
+        sim::GenerationOptions {
+            max_line_width: Some(100),
+            ..sim::GenerationOptions::default()
+        },
+        &mut file,
+    )?;
+    sim::generate(
+        reorder_test_module(&p),
+        sim::GenerationOptions::default(),
+        &mut file,
+    )?;
+    sim::generate(
+        named_wire_collision_test_module(&p),
+        sim::GenerationOptions::default(),
+        &mut file,
+    )?;
+    sim::generate(
+        registered_output_test_module(&p),
+        sim::GenerationOptions::default(),
+        &mut file,
+    )?;
+    sim::generate(
+        declared_module_test_module(&p),
         sim::GenerationOptions::default(),
         &mut file,
     )?;
+    sim::generate(
+        instance_array_test_module(&p),
+        sim::GenerationOptions {
+            tracing: true,
+            ..sim::GenerationOptions::default()
+        },
+        &mut file,
+    )?;
+    sim::generate(
+        keyword_test_module(&p),
+        sim::GenerationOptions {
+            tracing: true,
+            strict_inputs: true,
+            ..sim::GenerationOptions::default()
+        },
+        &mut file,
+    )?;
+    sim::generate(
+        packed_bools_test_module(&p),
+        sim::GenerationOptions {
+            tracing: true,
+            trace_on_change_only: true,
+            ..sim::GenerationOptions::default()
+        },
+        &mut file,
+    )?;
+    sim::generate(
+        packed_bools_test_module(&p),
+        sim::GenerationOptions {
+            override_module_name: Some("PackedBoolsTestModulePacked".into()),
+            tracing: true,
+            trace_on_change_only: true,
+            pack_bools: true,
+            ..sim::GenerationOptions::default()
+        },
+        &mut file,
+    )?;
+
+    // Generated separately, so that tests can check that it compiles in a #![no_std] crate
+    let no_std_dest_path = Path::new(&out_dir).join("no_std_modules.rs");
+    let mut no_std_file = File::create(&no_std_dest_path).unwrap();
+    sim::generate(
+        no_std_test_module(&p),
+        sim::GenerationOptions {
+            reset_mems: true,
+            poison_uninitialized_regs: true,
+            no_std: true,
+            ..sim::GenerationOptions::default()
+        },
+        &mut no_std_file,
+    )?;
+    println!(
+        "cargo:rustc-env=SIM_TESTS_RUSTC={}",
+        env::var("RUSTC").unwrap()
+    );
+
+    Ok(())
+}
+
+fn input_masking<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    let m = p.module("input_masking", "InputMasking");
+
+    m.output("o", m.input("i", 27));
+
+    m
+}
+
+fn signed_lit_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    let m = p.module("signed_lit_test_module", "SignedLitTestModule");
+
+    m.output("o1", m.lit(-1i32, 8));
+    m.output("o2", m.lit(-8i8, 4));
+    m.output("o3", m.lit(-2i64, 128));
+    m.output("o4", m.lit(-1i8, 1));
+
+    let r = m.reg("r", 16);
+    r.default_value(-300i16);
+    r.drive_next(r);
+    m.output("r", r);
+
+    let addr = m.input("addr", 1);
+    let mem8 = m.mem("mem8", 1, 8);
+    mem8.initial_contents(&[-128i32, 127i32]);
+    m.output("mem8", mem8.read_port(addr, m.high()));
+    let mem1 = m.mem("mem1", 1, 1);
+    mem1.initial_contents(&[0i32, -1i32]);
+    m.output("mem1", mem1.read_port(addr, m.high()));
+
+    m
+}
+
+fn widest_input<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    let m = p.module("widest_input", "WidestInput");
+
+    m.output("o", m.input("i", 128));
+
+    m
+}
+
+fn le_bytes_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    let m = p.module("le_bytes_test_module", "LeBytesTestModule");
+
+    m.output("o65", m.input("i65", 65));
+    m.output("o100", m.input("i100", 100));
+    m.output("o128", m.input("i128", 128));
+
+    m
+}
+
+fn add_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    let m = p.module("add_test_module", "AddTestModule");
+
+    let i1 = m.input("i1", 1);
+    let i2 = m.input("i2", 1);
+    m.output("o1", i1 + i2);
+
+    let i3 = m.input("i3", 16);
+    let i4 = m.input("i4", 16);
+    m.output("o2", i3 + i4);
+
+    let i5 = m.input("i5", 32);
+    let i6 = m.input("i6", 32);
+    m.output("o3", i5 + i6);
+
+    let i7 = m.input("i7", 64);
+    let i8_ = m.input("i8", 64);
+    m.output("o4", i7 + i8_);
+
+    let i9 = m.input("i9", 128);
+    let i10 = m.input("i10", 128);
+    m.output("o5", i9 + i10);
+
+    let i11 = m.input("i11", 7);
+    let i12 = m.input("i12", 7);
+    m.output("o6", i11 + i12);
+
+    m
+}
+
+fn sub_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    let m = p.module("sub_test_module", "SubTestModule");
+
+    let i1 = m.input("i1", 1);
+    let i2 = m.input("i2", 1);
+    m.output("o1", i1 - i2);
+
+    let i3 = m.input("i3", 16);
+    let i4 = m.input("i4", 16);
+    m.output("o2", i3 - i4);
+
+    let i5 = m.input("i5", 32);
+    let i6 = m.input("i6", 32);
+    m.output("o3", i5 - i6);
+
+    let i7 = m.input("i7", 64);
+    let i8_ = m.input("i8", 64);
+    m.output("o4", i7 - i8_);
+
+    let i9 = m.input("i9", 128);
+    let i10 = m.input("i10", 128);
+    m.output("o5", i9 - i10);
+
+    let i11 = m.input("i11", 7);
+    let i12 = m.input("i12", 7);
+    m.output("o6", i11 - i12);
+
+    m
+}
+
+fn carry_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    let m = p.module("carry_test_module", "CarryTestModule");
+
+    let i1 = m.input("i1", 4);
+    let i2 = m.input("i2", 4);
+    let (sum, carry) = i1.add_with_carry(i2);
+    m.output("sum1", sum);
+    m.output("carry1", carry);
+    let (difference, borrow) = i1.sub_with_borrow(i2);
+    m.output("difference1", difference);
+    m.output("borrow1", borrow);
+
+    let i3 = m.input("i3", 128);
+    let i4 = m.input("i4", 128);
+    let (sum, carry) = i3.add_with_carry(i4);
+    m.output("sum2", sum);
+    m.output("carry2", carry);
+    let (difference, borrow) = i3.sub_with_borrow(i4);
+    m.output("difference2", difference);
+    m.output("borrow2", borrow);
+
+    m
+}
+
+fn priority_encode_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    let m = p.module("priority_encode_test_module", "PriorityEncodeTestModule");
+
+    let i1 = m.input("i1", 1);
+    let (index, valid) = i1.priority_encode_high();
+    m.output("high_index1", index);
+    m.output("high_valid1", valid);
+
+    let i2 = m.input("i2", 13);
+    let (index, valid) = i2.priority_encode_high();
+    m.output("high_index2", index);
+    m.output("high_valid2", valid);
+    let (index, valid) = i2.priority_encode_low();
+    m.output("low_index2", index);
+    m.output("low_valid2", valid);
+
+    let i3 = m.input("i3", 128);
+    let (index, valid) = i3.priority_encode_high();
+    m.output("high_index3", index);
+    m.output("high_valid3", valid);
+    let (index, valid) = i3.priority_encode_low();
+    m.output("low_index3", index);
+    m.output("low_valid3", valid);
+
+    m
+}
+
+fn onehot_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    let m = p.module("onehot_test_module", "OnehotTestModule");
+
+    let onehot = m.input("onehot", 13);
+    m.output("binary_o", onehot.onehot_to_binary());
+
+    let binary = m.input("binary", 4);
+    m.output("onehot_o", binary.binary_to_onehot(13));
+
+    m
+}
+
+fn dual_clock_mem_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    let m = p.module("dual_clock_mem_test_module", "DualClockMemTestModule");
+
+    let mem = m.mem("mem", 2, 8);
+    mem.dual_clock("read_clk", "write_clk");
+    mem.initial_contents(&[0x01u32, 0x23u32, 0x45u32, 0x67u32]);
+    mem.write_port(
+        m.input("write_addr", 2),
+        m.input("write_value", 8),
+        m.input("write_enable", 1),
+    );
+    m.output(
+        "read_value",
+        mem.read_port(m.input("read_addr", 2), m.input("read_enable", 1)),
+    );
 
-    Ok(())
+    m
 }
 
-fn input_masking<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
-    let m = p.module("input_masking", "InputMasking");
+fn reg_clock_domain_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    let m = p.module("reg_clock_domain_test_module", "RegClockDomainTestModule");
 
-    m.output("o", m.input("i", 27));
+    let fast_counter = m.reg_in_domain("fast_counter", 8, "clk_fast");
+    fast_counter.default_value(0u32);
+    fast_counter.drive_next(fast_counter + m.lit(1u32, 8));
+    m.output("fast_count", fast_counter);
+
+    let slow_counter = m.reg("slow_counter", 8);
+    slow_counter.default_value(0u32);
+    slow_counter.drive_next(slow_counter + m.lit(1u32, 8));
+    m.output("slow_count", slow_counter);
+
+    let sampled = m.reg("sampled", 8);
+    sampled.default_value(0u32);
+    sampled.drive_next(fast_counter);
+    m.output("sampled_fast_count", sampled);
 
     m
 }
 
-fn widest_input<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
-    let m = p.module("widest_input", "WidestInput");
+fn negedge_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    let m = p.module("negedge_test_module", "NegedgeTestModule");
 
-    m.output("o", m.input("i", 128));
+    let i = m.input("i", 8);
+
+    // Data is transferred from a positive edge register to a negative edge register and back within a single cycle
+    let pos = m.reg("pos", 8);
+    pos.default_value(0u32);
+    pos.drive_next(i);
+    m.output("pos", pos);
+
+    let neg = m.reg("neg", 8);
+    neg.clock_edge(Edge::Neg);
+    neg.default_value(0u32);
+    neg.drive_next(pos + m.lit(1u32, 8));
+    m.output("neg", neg);
+
+    let back = m.reg("back", 8);
+    back.default_value(0u32);
+    back.drive_next(neg);
+    m.output("back", back);
 
     m
 }
 
-fn add_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
-    let m = p.module("add_test_module", "AddTestModule");
+fn assertion_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    let m = p.module("assertion_test_module", "AssertionTestModule");
 
-    let i1 = m.input("i1", 1);
-    let i2 = m.input("i2", 1);
-    m.output("o1", i1 + i2);
+    let i = m.input("i", 8);
 
-    let i3 = m.input("i3", 16);
-    let i4 = m.input("i4", 16);
-    m.output("o2", i3 + i4);
+    // Only observable through its assertion
+    let r = m.reg("r", 8);
+    r.default_value(0u32);
+    r.drive_next(i);
+    m.assert("r_not_ff", r.ne(m.lit(0xffu32, 8)));
 
-    let i5 = m.input("i5", 32);
-    let i6 = m.input("i6", 32);
-    m.output("o3", i5 + i6);
+    let inner = m.module("inner", "AssertionTestModuleInner");
+    let inner_i = inner.input("i", 8);
+    inner.assert("i_not_odd", !inner_i.bit(0));
+    inner_i.drive(i);
 
-    let i7 = m.input("i7", 64);
-    let i8_ = m.input("i8", 64);
-    m.output("o4", i7 + i8_);
+    m.output("o", i);
 
-    let i9 = m.input("i9", 128);
-    let i10 = m.input("i10", 128);
-    m.output("o5", i9 + i10);
+    m
+}
 
-    let i11 = m.input("i11", 7);
-    let i12 = m.input("i12", 7);
-    m.output("o6", i11 + i12);
+fn assert_always_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    let m = p.module("assert_always_test_module", "AssertAlwaysTestModule");
+
+    let push = m.input("push", 1);
+
+    let count = m.reg("count", 2);
+    count.default_value(0u32);
+    count.drive_next(if_(push, count + m.lit(1u32, 2)).else_(count));
+    m.assert_always(
+        "no_overflow",
+        !(push & count.eq(m.lit(3u32, 2))),
+        "FIFO overflowed",
+    );
+
+    m.output("count", count);
 
     m
 }
 
-fn sub_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
-    let m = p.module("sub_test_module", "SubTestModule");
+fn cover_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    let m = p.module("cover_test_module", "CoverTestModule");
 
-    let i1 = m.input("i1", 1);
-    let i2 = m.input("i2", 1);
-    m.output("o1", i1 - i2);
+    let i = m.input("i", 8);
 
-    let i3 = m.input("i3", 16);
-    let i4 = m.input("i4", 16);
-    m.output("o2", i3 - i4);
+    // Only observable through its coverage point
+    let r = m.reg("r", 8);
+    r.default_value(0u32);
+    r.drive_next(i);
+    m.cover("r_ff", r.eq(m.lit(0xffu32, 8)));
 
-    let i5 = m.input("i5", 32);
-    let i6 = m.input("i6", 32);
-    m.output("o3", i5 - i6);
+    let inner = m.module("inner", "CoverTestModuleInner");
+    let inner_i = inner.input("i", 8);
+    inner.cover("i_odd", inner_i.bit(0));
+    inner_i.drive(i);
 
-    let i7 = m.input("i7", 64);
-    let i8_ = m.input("i8", 64);
-    m.output("o4", i7 - i8_);
+    m.output("o", i);
 
-    let i9 = m.input("i9", 128);
-    let i10 = m.input("i10", 128);
-    m.output("o5", i9 - i10);
+    m
+}
 
-    let i11 = m.input("i11", 7);
-    let i12 = m.input("i12", 7);
-    m.output("o6", i11 - i12);
+fn clock_divider_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    let m = p.module("clock_divider_test_module", "ClockDividerTestModule");
+
+    let div3 = m.clock_divider("div3", 3);
+    let div3_count = div3.reg("div3_count", 8);
+    div3_count.default_value(0u32);
+    div3_count.drive_next(div3_count + m.lit(1u32, 8));
+    m.output("div3_count", div3_count);
+    let sampled = div3.reg("sampled", 8);
+    sampled.drive_next(m.input("i", 8));
+    m.output("sampled", sampled);
+
+    let div2 = m.clock_divider("div2", 2);
+    let div2_count = div2.reg("div2_count", 8);
+    div2_count.default_value(0u32);
+    div2_count.drive_next(div2_count + m.lit(1u32, 8));
+    m.output("div2_count", div2_count);
+
+    m
+}
+
+fn signal_vec_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    let m = p.module("signal_vec_test_module", "SignalVecTestModule");
+
+    let v7 = m.signal_vec((0..7).map(|i| m.input(format!("a{}", i), 8)));
+    m.output("a_selected", v7.select(m.input("a_index", 4)));
+
+    let v16 = m.signal_vec((0..16).map(|i| m.input(format!("b{}", i), 3)));
+    m.output("b_selected", v16.select(m.input("b_index", 4)));
+
+    m
+}
+
+fn no_std_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    let m = p.module("no_std_test_module", "NoStdTestModule");
+
+    let i = m.input("i", 8);
+    let shift = m.input("shift", 8);
+
+    let r = m.reg("r", 8);
+    r.drive_next(i << shift);
+    m.output("shifted", r);
+
+    let mem1 = m.mem("mem1", 2, 8);
+    mem1.initial_contents(&[0x01u32, 0x23u32, 0x45u32, 0x67u32]);
+    m.output("mem1_value", mem1.read_port(i.bits(1, 0), m.high()));
+
+    let mem2 = m.mem("mem2", 2, 128);
+    mem2.write_port(
+        i.bits(1, 0),
+        m.input("wide", 128),
+        m.input("write_enable", 1),
+    );
+    m.output("mem2_value", mem2.read_port(i.bits(1, 0), m.high()));
 
     m
 }
@@ -523,6 +1177,58 @@ fn shr_arithmetic_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
     m
 }
 
+fn shift_fill_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    let m = p.module("shift_fill_test_module", "ShiftFillTestModule");
+
+    let i1 = m.input("i1", 8);
+    let i2 = m.input("i2", 4);
+    let fill = m.input("fill", 1);
+    m.output("o1", i1.shl_fill(i2, fill));
+    m.output("o2", i1.shr_fill(i2, fill));
+    m.output("o3", i1.shl_logical(i2));
+    m.output("o4", i1.shr_logical(i2));
+
+    let i3 = m.input("i3", 128);
+    let i4 = m.input("i4", 8);
+    m.output("o5", i3.shl_fill(i4, fill));
+    m.output("o6", i3.shr_fill(i4, fill));
+
+    m
+}
+
+fn rotate_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    let m = p.module("rotate_test_module", "RotateTestModule");
+
+    let i1 = m.input("i1", 7);
+    let a1 = m.input("a1", 4);
+    m.output("l1", i1.rotate_left(a1));
+    m.output("r1", i1.rotate_right(a1));
+    m.output("lc1", i1.rotl_const(3));
+    m.output("rc1", i1.rotr_const(10));
+
+    let i2 = m.input("i2", 32);
+    let a2 = m.input("a2", 6);
+    m.output("l2", i2.rotate_left(a2));
+    m.output("r2", i2.rotate_right(a2));
+
+    let i3 = m.input("i3", 128);
+    let a3 = m.input("a3", 8);
+    m.output("l3", i3.rotate_left(a3));
+    m.output("r3", i3.rotate_right(a3));
+    m.output("lc3", i3.rotl_const(4));
+    m.output("rc3", i3.rotr_const(128));
+
+    let i4 = m.input("i4", 1);
+    m.output("l4", i4.rotate_left(a1));
+
+    // Only foldable when optimized
+    let lit = m.lit(0x81u32, 8);
+    m.output("l5", lit.rotate_left(m.lit(10u32, 4)));
+    m.output("r5", lit.rotate_right(m.lit(1u32, 1)));
+
+    m
+}
+
 fn bit_and_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
     let m = p.module("bit_and_test_module", "BitAndTestModule");
 
@@ -1040,6 +1746,424 @@ fn mem_test_module_2<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
     m
 }
 
+fn mem_reset_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    let m = p.module("mem_reset_test_module", "MemResetTestModule");
+
+    // One mem with initial contents and one without, both writable
+    let write_addr = m.input("write_addr", 2);
+    let write_value = m.input("write_value", 8);
+    let write_enable = m.input("write_enable", 1);
+    let read_addr = m.input("read_addr", 2);
+    for (index, initial_contents) in [Some([0x11u32, 0x22, 0x33, 0x44]), None].iter().enumerate() {
+        let mem = m.mem(format!("mem{}", index), 2, 8);
+        if let Some(initial_contents) = initial_contents {
+            mem.initial_contents(initial_contents);
+        }
+        mem.write_port(write_addr, write_value, write_enable);
+        m.output(
+            format!("read_data{}", index),
+            mem.read_port(read_addr, m.high()),
+        );
+    }
+
+    // A register with a default value, so a reset method is generated either way
+    let r = m.reg("r", 1);
+    r.default_value(false);
+    r.drive_next(m.input("i", 1));
+    m.output("o", r);
+
+    m
+}
+
+fn mem_comb_read_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    let m = p.module("mem_comb_read_test_module", "MemCombReadTestModule");
+
+    // Initial contents, single write port, one combinational and one always-enabled synchronous read port at the same address
+    let mem = m.mem("mem", 2, 8);
+    mem.initial_contents(&[0x11u32, 0x22, 0x33, 0x44]);
+    mem.write_port(
+        m.input("write_addr", 2),
+        m.input("write_value", 8),
+        m.input("write_enable", 1),
+    );
+    let read_addr = m.input("read_addr", 2);
+    m.output("comb_read_data", mem.read_port_comb(read_addr));
+    m.output("sync_read_data", mem.read_port_always(read_addr));
+
+    m
+}
+
+fn case_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    let m = p.module("case_test_module", "CaseTestModule");
+
+    // Simple handshake state machine: idle -> busy -> finishing -> idle
+    let state = m.reg("state", 2);
+    state.default_value(0u32);
+    let start = m.input("start", 1);
+    let done = m.input("done", 1);
+    state.drive_next(m.case(
+        state,
+        &[
+            (0u32, start.mux(m.lit(1u32, 2), state)),
+            (1u32, m.lit(2u32, 2)),
+            (2u32, done.mux(m.lit(0u32, 2), state)),
+        ],
+        m.lit(0u32, 2),
+    ));
+    m.output("state", state);
+
+    // Combinational decoder with a non-constant selector and values
+    let sel = m.input("sel", 3);
+    let a = m.input("a", 8);
+    let b = m.input("b", 8);
+    m.output(
+        "decoded",
+        m.case(
+            sel ^ m.lit(1u32, 3),
+            &[(0u32, a), (1u32, b), (5u32, a + b), (7u32, !a)],
+            m.lit(0xffu32, 8),
+        ),
+    );
+
+    m
+}
+
+fn inout_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    let m = p.module("inout_test_module", "InOutTestModule");
+
+    // Bidirectional data bus that's driven with a register's value when reading, and latched into it when writing
+    let data = m.inout("data", 8);
+    let read = m.input("read", 1);
+    let r = m.reg("r", 8);
+    r.default_value(0x5au32);
+    r.drive_next(read.mux(r, data));
+    data.drive(r, read);
+
+    m
+}
+
+fn poison_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    let m = p.module("poison_test_module", "PoisonTestModule");
+
+    // A counter that's (incorrectly) never reset
+    let count = m.reg("count", 8);
+    count.drive_next(count + m.lit(1u32, 8));
+    m.output("count", count);
+    m.output("done", count.eq(m.lit(3u32, 8)));
+
+    // Unreset registers of each value type
+    let bit = m.reg("bit", 1);
+    bit.drive_next(bit);
+    m.output("bit", bit);
+    let wide = m.reg("wide", 100);
+    wide.drive_next(wide);
+    m.output("wide", wide);
+
+    // A register with a default value, which is never poisoned
+    let initialized = m.reg("initialized", 32);
+    initialized.default_value(0xfadebabeu32);
+    initialized.drive_next(initialized);
+    m.output("initialized", initialized);
+
+    m
+}
+
+fn dont_care_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    let m = p.module("dont_care_test_module", "DontCareTestModule");
+
+    let valid = m.input("valid", 1);
+    let data = m.input("data", 8);
+
+    // Data is only specified while valid is high
+    let source = m.module("source", "DontCareTestModuleSource");
+    let source_valid = source.input("valid_in", 1);
+    let source_data = source.input("data_in", 8);
+    let source_valid_out = source.output("valid", source_valid);
+    let source_data_out = source.output_dont_care_when(
+        "data",
+        source_valid.mux(source_data, source.lit(0u32, 8)),
+        !source_valid,
+    );
+    source_valid.drive(valid);
+    source_data.drive(data);
+
+    // Only accumulates specified values
+    let legal_sum = m.reg("legal_sum", 8);
+    legal_sum.default_value(0u32);
+    legal_sum.drive_next(source_valid_out.mux(legal_sum + source_data_out, legal_sum));
+    m.output("legal_sum", legal_sum);
+
+    // Illegally relies on data being 0 while valid is low
+    let illegal_sum = m.reg("illegal_sum", 8);
+    illegal_sum.default_value(0u32);
+    illegal_sum.drive_next(illegal_sum + source_data_out);
+    m.output("illegal_sum", illegal_sum);
+
+    m
+}
+
+fn lit_from_str_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    let m = p.module("lit_from_str_test_module", "LitFromStrTestModule");
+
+    m.output(
+        "hex",
+        m.lit_from_str("2b7e1516_28aed2a6_abf71588_09cf4f3c", 16, 128),
+    );
+    m.output("bin", m.lit_from_str("1010_0101", 2, 8));
+    m.output("dec", m.lit_from_str("123456789", 10, 27));
+    m.output("bytes_le", m.lit_from_bytes_le(&[0x01, 0x02, 0x03], 24));
+    m.output("bytes_be", m.lit_from_bytes_be(&[0x01, 0x02, 0x03], 24));
+
+    let r = m.reg("r", 40);
+    r.default_value(Constant::from_str_radix("ab_cdef0123", 16, 40));
+    r.drive_next(r);
+    m.output("reg", r);
+
+    let mem = m.mem("mem", 1, 72);
+    mem.initial_contents(&[
+        Constant::from_bytes_le(&[0xff; 9], 72),
+        Constant::from_str_radix("1", 10, 72),
+    ]);
+    m.output(
+        "mem_read_data",
+        mem.read_port(m.input("mem_read_addr", 1), m.high()),
+    );
+
+    m
+}
+
+fn bool_network_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    let m = p.module("bool_network_test_module", "BoolNetworkTestModule");
+
+    let a = m.input("a", 1);
+    let b = m.input("b", 1);
+    let c = m.input("c", 1);
+    let s = m.input("s", 3);
+
+    m.output("add", a + b);
+    m.output("sub", a - b);
+    m.output("shl_b", a << b);
+    m.output("shr_b", a >> b);
+    m.output("shr_arithmetic_b", a.shr_arithmetic(b));
+    m.output("shl_s", a << s);
+    m.output("shr_s", a >> s);
+    m.output("shr_arithmetic_s", a.shr_arithmetic(s));
+    m.output("logic", m.mux(c, a & !b, (a ^ b) | c));
+
+    m
+}
+
+fn register_file_test_module_0<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    // 2R1W, no write-through
+    components::register_file(
+        p,
+        "register_file_test_module_0",
+        "RegisterFileTestModule0",
+        2,
+        8,
+        2,
+        false,
+    )
+}
+
+fn register_file_test_module_1<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    // 2R1W, write-through
+    components::register_file(
+        p,
+        "register_file_test_module_1",
+        "RegisterFileTestModule1",
+        2,
+        8,
+        2,
+        true,
+    )
+}
+
+fn debug_port_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    let m = p.module("debug_port_test_module", "DebugPortTestModule");
+
+    let debug_port = components::debug_port(m, "debug_port", "DebugPort", 2, 8);
+    debug_port.shift.drive(m.input("dbg_shift", 1));
+    debug_port.update.drive(m.input("dbg_update", 1));
+    debug_port.tdi.drive(m.input("dbg_tdi", 1));
+    m.output("dbg_tdo", debug_port.tdo);
+
+    // Registers can also be poked directly, and are peeked through outputs
+    let poke_enable = m.input("poke_enable", 1);
+    let poke_addr = m.input("poke_addr", 2);
+    let poke_data: &dyn Signal = m.input("poke_data", 8);
+
+    let mut read_data = m.lit(0u32, 8);
+    for i in 0..4u32 {
+        let r = m.reg(format!("r{}", i), 8);
+        r.default_value(0u32);
+        let addr = m.lit(i, 2);
+        let is_written = debug_port.bus_write_enable & debug_port.bus_addr.eq(addr);
+        r.drive_next(
+            if_(poke_enable & poke_addr.eq(addr), poke_data)
+                .else_if(is_written, debug_port.bus_write_data)
+                .else_(r),
+        );
+        m.output(format!("r{}", i), r);
+        read_data = debug_port.bus_addr.eq(addr).mux(r, read_data);
+    }
+    debug_port.bus_read_data.drive(read_data);
+
+    m
+}
+
+fn strobe_select_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    components::strobe_select(p, "strobe_select_test_module", "StrobeSelectTestModule", 3)
+}
+
+fn align_strobe_test_module_0<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    components::align_strobe(p, "align_strobe_test_module_0", "AlignStrobeTestModule0", 3)
+}
+
+fn align_strobe_test_module_1<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    components::align_strobe(p, "align_strobe_test_module_1", "AlignStrobeTestModule1", 0)
+}
+
+fn fifo_test_module_0<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    components::fifo(p, "fifo_test_module_0", "FifoTestModule0", 8, 3).module
+}
+
+fn fifo_test_module_1<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    components::fifo(p, "fifo_test_module_1", "FifoTestModule1", 8, 4).module
+}
+
+fn edge_detect_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    let m = p.module("edge_detect_test_module", "EdgeDetectTestModule");
+
+    let i = m.input("i", 1);
+    m.output("rising", i.rising_edge("i_rising_delayed"));
+    m.output("falling", i.falling_edge("i_falling_delayed"));
+
+    m
+}
+
+fn saturating_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    let m = p.module("saturating_test_module", "SaturatingTestModule");
+
+    for &bit_width in &[8, 128] {
+        let a = m.input(format!("a{}", bit_width), bit_width);
+        let b = m.input(format!("b{}", bit_width), bit_width);
+        m.output(format!("add{}", bit_width), a.saturating_add(b));
+        m.output(format!("sub{}", bit_width), a.saturating_sub(b));
+        m.output(
+            format!("add_signed{}", bit_width),
+            a.saturating_add_signed(b),
+        );
+        m.output(
+            format!("sub_signed{}", bit_width),
+            a.saturating_sub_signed(b),
+        );
+    }
+
+    m
+}
+
+fn reset_domain_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    let m = p.module("reset_domain_test_module", "ResetDomainTestModule");
+
+    let soft = m.reset_domain("soft");
+
+    let always_on = m.reg("always_on", 32);
+    always_on.default_value(0xfadebabeu32);
+    always_on.drive_next(m.input("always_on_next", 32));
+    m.output("always_on", always_on);
+
+    let main = m.reg("main", 32);
+    main.default_value(0xdeadbeefu32);
+    main.reset_domain(soft);
+    main.drive_next(m.input("main_next", 32));
+    m.output("main", main);
+
+    // Domains with the same name in child modules share the same reset
+    let inner = m.module("inner", "ResetDomainTestModuleInner");
+    let inner_soft = inner.reset_domain("soft");
+    let inner_r = inner.reg("r", 32);
+    inner_r.default_value(0xabad1deau32);
+    inner_r.reset_domain(inner_soft);
+    let inner_i = inner.input("i", 32);
+    inner_r.drive_next(inner_i);
+    let inner_o = inner.output("o", inner_r);
+    inner_i.drive(m.input("inner_next", 32));
+    m.output("inner", inner_o);
+
+    m
+}
+
+fn bits_dyn_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    let m = p.module("bits_dyn_test_module", "BitsDynTestModule");
+
+    let i1 = m.input("i1", 32);
+    let index1 = m.input("index1", 6);
+    m.output("bit1", i1.bit_dyn(index1));
+    m.output("bits1", i1.bits_dyn(index1, 4));
+
+    let i2 = m.input("i2", 128);
+    let index2 = m.input("index2", 8);
+    m.output("bit2", i2.bit_dyn(index2));
+    m.output("bits2", i2.bits_dyn(index2, 12));
+
+    m
+}
+
+fn optimize_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    let m = p.module("optimize_test_module", "OptimizeTestModule");
+
+    let a = m.input("a", 8);
+    let b = m.input("b", 8);
+    let s = m.input("s", 1);
+
+    let zero = m.lit(0u32, 8);
+    let ones = m.lit(0xffu32, 8);
+
+    m.output("and_zero", a & zero);
+    m.output("and_ones", a & ones);
+    m.output("or_zero", zero | a);
+    m.output("or_ones", a | ones);
+    m.output("xor_zero", a ^ zero);
+    m.output("add_zero", a + zero);
+    m.output("sub_zero", a - zero);
+    m.output("shl_zero", a << m.lit(0u32, 3));
+    m.output("double_not", !!a);
+    m.output("full_bits", a.bits(7, 0));
+    m.output("nested_bits", a.bits(6, 1).bits(4, 2));
+    m.output("repeat_one", a.repeat(1));
+    m.output("mux_true", m.mux(m.high(), a, b));
+    m.output("mux_false", m.mux(m.low(), a, b));
+    m.output("mux_same", m.mux(s, a, a));
+    m.output("mux_bool", m.mux(s, m.high(), m.low()));
+    m.output("mux_not_bool", m.mux(s, m.low(), m.high()));
+    m.output(
+        "folded",
+        (m.lit(0x12u32, 8) + m.lit(0xf0u32, 8))
+            .concat(m.lit(3u32, 2).repeat(2))
+            .bits(11, 4)
+            ^ (m.lit(0x80u32, 8).shr_arithmetic(m.lit(3u32, 3))),
+    );
+    m.output(
+        "mixed",
+        (a + (m.lit(1u32, 8) - m.lit(1u32, 8))) ^ (b & ones),
+    );
+
+    m.output(
+        "staged",
+        m.lit(0x5au32, 8)
+            .reg_next_with_default("stage0", 0x5au32)
+            .reg_next_with_default("stage1", 0x5au32),
+    );
+
+    let r = m.reg("r", 8);
+    r.default_value(0u32);
+    r.drive_next(m.mux(s & m.high(), a | zero, r));
+    m.output("r", r);
+
+    m
+}
+
 fn trace_test_module_0<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
     let m = p.module("trace_test_module_0", "TraceTestModule0");
 
@@ -1123,6 +2247,23 @@ fn trace_test_module_3<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
     m
 }
 
+fn named_wire_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    let m = p.module("named_wire_test_module", "NamedWireTestModule");
+
+    let a = m.input("a", 8);
+    let b = m.input("b", 8);
+    let sum = (a + b).named("sum");
+
+    let inner = m.module("inner", "NamedWireTestModuleInner");
+    let inner_i = inner.input("i", 8);
+    let inner_o = inner.output("o", inner.named_wire("inverted", !inner_i));
+    inner_i.drive(sum);
+
+    m.output("o", inner_o);
+
+    m
+}
+
 fn deep_graph_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
     let m = p.module("deep_graph_test_module", "DeepGraphTestModule");
 
@@ -1136,3 +2277,160 @@ fn deep_graph_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
 
     m
 }
+
+fn declared_module_test_module<'a>(c: &'a Context<'a>) -> &Module<'a> {
+    let adder = c.declare_module("DeclaredAdder");
+
+    // The parent is built before the module it instantiates is defined
+    let m = c.module("declared_module_test_module", "DeclaredModuleTestModule");
+    let i1 = m.input("i1", 8);
+    let i2 = m.input("i2", 8);
+    let i3 = m.input("i3", 8);
+    let adder1 = m.instance("adder1", &adder);
+    let adder2 = m.instance("adder2", &adder);
+    adder.on_defined(move || {
+        adder1.get_input("a").drive(i1);
+        adder1.get_input("b").drive(i2);
+        adder2.get_input("a").drive(adder1.get_output("sum"));
+        adder2.get_input("b").drive(i3);
+        m.output("sum", adder2.get_output("sum"));
+    });
+
+    adder.define(|m| {
+        m.output("sum", m.input("a", 8) + m.input("b", 8));
+    });
+
+    m
+}
+
+fn instance_array_test_module<'a>(c: &'a Context<'a>) -> &Module<'a> {
+    let lane = c.declare_module("InstanceArrayLane");
+    lane.define(|m| {
+        m.output("sum", m.input("a", 8) + m.input("b", 8));
+    });
+
+    let m = c.module("instance_array_test_module", "InstanceArrayTestModule");
+    let a = m.input("a", 32);
+    let b = m.input("b", 32);
+
+    // Four independent 8-bit lanes, with lane 0 in the lower bits
+    let lanes = m.instance_array("lane", &lane, 4);
+    lanes.drive_input_each("a", |i| a.bits(i as u32 * 8 + 7, i as u32 * 8));
+    lanes.drive_input_each("b", |i| b.bits(i as u32 * 8 + 7, i as u32 * 8));
+    m.output("sum", lanes.collect_outputs_concat("sum"));
+
+    m
+}
+
+fn keyword_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    let m = p.module("keyword_test_module", "KeywordTestModule");
+
+    // None of these names can be used as Rust identifiers as-is
+    let i = m.input("match", 8);
+    let sel = m.input("type", 1);
+    let inner = m.module("inner module", "Inner<8>");
+    let inner_i = inner.input("a b", 8);
+    let state = inner.reg("state[0]", 8);
+    state.drive_next(inner.named_wire("not (a b)", !inner_i));
+    inner.output("self", state);
+    inner_i.drive(i);
+    m.output("fn", sel.mux(inner.get_output("self"), i));
+    m.output("box", m.input("yield", 100));
+
+    m
+}
+
+fn packed_bools_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    let m = p.module("packed_bools_test_module", "PackedBoolsTestModule");
+
+    let i = m.input("i", 8);
+    let en = m.input("en", 1);
+    let soft = m.reset_domain("soft");
+
+    // Enough 1-bit registers to span several packed words
+    let mut shift = i.bit(0);
+    let mut parity = m.lit(false, 1);
+    for n in 0..100 {
+        let r = m.reg(format!("shift{}", n), 1);
+        if n % 3 == 0 {
+            r.default_value(n % 2 == 0);
+        }
+        if n % 5 == 0 {
+            r.reset_domain(soft);
+        }
+        r.drive_next(en.mux(shift ^ i.bit(n % 8), r));
+        shift = r;
+        parity = parity ^ r;
+    }
+    m.output("shift", shift);
+    m.output("parity", parity);
+
+    let toggle = m.reg("toggle", 1);
+    toggle.default_value(true);
+    toggle.drive_next(!toggle);
+    m.output("toggle", toggle);
+
+    // Registers of other widths are interleaved with the 1-bit ones
+    let acc = m.reg("acc", 8);
+    acc.default_value(0x5au32);
+    acc.drive_next(toggle.mux(acc + i, acc ^ i));
+    m.output("acc", acc);
+    m.output(
+        "delayed",
+        (acc.bit(7) & en).reg_next_with_default("delayed", true),
+    );
+
+    m
+}
+
+fn reorder_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    let m = p.module("reorder_test_module", "ReorderTestModule");
+
+    for &bit_width in &[1, 8, 13, 24, 32, 64, 128] {
+        let i = m.input(format!("i{}", bit_width), bit_width);
+        m.output(format!("reversed{}", bit_width), i.reverse_bits());
+        if bit_width % 8 == 0 {
+            m.output(format!("swapped{}", bit_width), i.swap_bytes());
+        }
+    }
+
+    m
+}
+
+fn named_wire_collision_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    let m = p.module("m", "NamedWireCollisionTestModule");
+
+    let i = m.input("i", 8);
+
+    // Both named wires' locals would be called `__wire_m_a_b_c` in the generated simulator
+    let a = m.module("a", "A");
+    let a_i = a.input("i", 8);
+    a.output("o", a.named_wire("b_c", a_i));
+    a_i.drive(i);
+    let a_b = m.module("a_b", "AB");
+    let a_b_i = a_b.input("i", 8);
+    a_b.output("o", a_b.named_wire("c", !a_b_i));
+    a_b_i.drive(i);
+
+    m.output("o0", a.get_output("o"));
+    m.output("o1", a_b.get_output("o"));
+    m.output("o2", a.get_output("o") ^ a_b.get_output("o"));
+
+    m
+}
+
+fn registered_output_test_module<'a>(p: &'a impl ModuleParent<'a>) -> &Module<'a> {
+    let m = p.module(
+        "registered_output_test_module",
+        "RegisteredOutputTestModule",
+    );
+
+    let i = m.input("i", 8);
+    m.output("a", i);
+    m.output_registered("b", !i);
+    m.register_all_outputs();
+    m.output_registered("c", i);
+    m.output("d", i);
+
+    m
+}
@@ -6,12 +6,21 @@ mod tests {
         include!(concat!(env!("OUT_DIR"), "/modules.rs"));
     }
 
+    mod no_std_modules {
+        include!(concat!(env!("OUT_DIR"), "/no_std_modules.rs"));
+    }
+
     use modules::*;
 
+    use kaze::runtime::cosim::*;
+    use kaze::runtime::csv;
+    use kaze::runtime::debug::*;
+    use kaze::runtime::fuzz::*;
     use kaze::runtime::tracing::*;
 
     use std::cell::RefCell;
     use std::collections::BTreeMap;
+    use std::collections::VecDeque;
     use std::fmt;
     use std::io;
     use std::rc::Rc;
@@ -225,6 +234,54 @@ mod tests {
         assert_eq!(m.o, 0x07ffffff);
     }
 
+    #[test]
+    fn strict_input_masking() {
+        let mut m = StrictInputMasking::new();
+
+        m.i = 0x07ffffff;
+        m.prop();
+        assert_eq!(m.o, 0x07ffffff);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(
+        expected = "Input \"i\" has bits set above its declared bit width of 27 bit(s)."
+    )]
+    fn strict_input_masking_error() {
+        let mut m = StrictInputMasking::new();
+
+        m.i = 0x08000000;
+        // Panic
+        m.prop();
+    }
+
+    #[test]
+    fn signed_lit_test_module() {
+        let mut m = SignedLitTestModule::new();
+
+        m.reset();
+        m.addr = false;
+        m.prop();
+        assert_eq!(m.o1, 0xff);
+        assert_eq!(m.o2, 0x8);
+        assert_eq!(m.o3, u128::MAX - 1);
+        assert_eq!(m.o4, true);
+        assert_eq!(m.r, 0xfed4);
+
+        m.posedge_clk();
+        m.prop();
+        assert_eq!(m.mem8, 0x80);
+        assert_eq!(m.mem1, false);
+
+        m.addr = true;
+        m.prop();
+        m.posedge_clk();
+        m.prop();
+        assert_eq!(m.mem8, 0x7f);
+        assert_eq!(m.mem1, true);
+    }
+
     #[test]
     fn widest_input() {
         let mut m = WidestInput::new();
@@ -234,6 +291,43 @@ mod tests {
         assert_eq!(m.o, 0xfadebabedeadbeefabad1deabadc0de5);
     }
 
+    #[test]
+    fn le_bytes_test_module() {
+        let mut m = LeBytesTestModule::new();
+
+        // xorshift64 for reproducible pseudo-random byte patterns
+        let mut state = 0x2545f4914f6cdd1du64;
+        let mut next_byte = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as u8
+        };
+
+        for _ in 0..1000 {
+            let mut i65 = [0u8; 9];
+            let mut i100 = [0u8; 13];
+            let mut i128 = [0u8; 16];
+            for byte in i65.iter_mut().chain(i100.iter_mut()).chain(i128.iter_mut()) {
+                *byte = next_byte();
+            }
+
+            m.set_i65_from_le_bytes(&i65);
+            m.set_i100_from_le_bytes(&i100);
+            m.set_i128_from_le_bytes(&i128);
+            m.prop();
+
+            // Bits above each input's bit width are ignored
+            let mut o65 = i65;
+            o65[8] &= 0x01;
+            let mut o100 = i100;
+            o100[12] &= 0x0f;
+            assert_eq!(m.o65_to_le_bytes(), o65);
+            assert_eq!(m.o100_to_le_bytes(), o100);
+            assert_eq!(m.o128_to_le_bytes(), i128);
+        }
+    }
+
     #[test]
     fn add_test_module() {
         let mut m = AddTestModule::new();
@@ -375,279 +469,820 @@ mod tests {
     }
 
     #[test]
-    fn mul_test_module() {
-        let mut m = MulTestModule::new();
+    fn carry_test_module() {
+        let mut m = CarryTestModule::new();
+
+        for i1 in 0..16u32 {
+            for i2 in 0..16u32 {
+                m.i1 = i1;
+                m.i2 = i2;
+                m.prop();
+                assert_eq!(m.sum1, (i1 + i2) & 0xf);
+                assert_eq!(m.carry1, i1 + i2 > 0xf);
+                assert_eq!(m.difference1, i1.wrapping_sub(i2) & 0xf);
+                assert_eq!(m.borrow1, i2 > i1);
+            }
+        }
 
-        m.i1 = false;
-        m.i2 = false;
-        m.prop();
-        assert_eq!(m.o1, 0);
+        for &(i3, i4) in &[
+            (0, 0),
+            (1, 2),
+            (2, 1),
+            (u128::MAX, 1),
+            (u128::MAX, u128::MAX),
+            (1 << 127, 1 << 127),
+            ((1 << 127) - 1, 1 << 127),
+        ] {
+            m.i3 = i3;
+            m.i4 = i4;
+            m.prop();
+            assert_eq!(m.sum2, i3.wrapping_add(i4));
+            assert_eq!(m.carry2, i3.checked_add(i4).is_none());
+            assert_eq!(m.difference2, i3.wrapping_sub(i4));
+            assert_eq!(m.borrow2, i4 > i3);
+        }
+    }
 
-        m.i1 = true;
-        m.i2 = false;
-        m.prop();
-        assert_eq!(m.o1, 0);
+    #[test]
+    fn priority_encode_test_module() {
+        let mut m = PriorityEncodeTestModule::new();
 
+        // All zero
         m.i1 = false;
-        m.i2 = true;
+        m.i2 = 0;
+        m.i3 = 0;
         m.prop();
-        assert_eq!(m.o1, 0);
+        assert_eq!((m.high_index1, m.high_valid1), (false, false));
+        assert_eq!((m.high_index2, m.high_valid2), (0, false));
+        assert_eq!((m.low_index2, m.low_valid2), (0, false));
+        assert_eq!((m.high_index3, m.high_valid3), (0, false));
+        assert_eq!((m.low_index3, m.low_valid3), (0, false));
 
+        // Single bit
         m.i1 = true;
-        m.i2 = true;
         m.prop();
-        assert_eq!(m.o1, 1);
+        assert_eq!((m.high_index1, m.high_valid1), (false, true));
+        for i in 0..13 {
+            m.i2 = 1 << i;
+            m.prop();
+            assert_eq!((m.high_index2, m.high_valid2), (i, true));
+            assert_eq!((m.low_index2, m.low_valid2), (i, true));
+        }
+        for i in 0..128 {
+            m.i3 = 1 << i;
+            m.prop();
+            assert_eq!((m.high_index3, m.high_valid3), (i, true));
+            assert_eq!((m.low_index3, m.low_valid3), (i, true));
+        }
 
-        m.i3 = 7;
-        m.i4 = 15;
+        // Multiple bits
+        for i2 in 1..(1 << 13) {
+            m.i2 = i2;
+            m.prop();
+            assert_eq!(
+                (m.high_index2, m.high_valid2),
+                (31 - i2.leading_zeros(), true)
+            );
+            assert_eq!((m.low_index2, m.low_valid2), (i2.trailing_zeros(), true));
+        }
+        m.i3 = (1 << 100) | (1 << 3);
         m.prop();
-        assert_eq!(m.o2, 105);
+        assert_eq!((m.high_index3, m.high_valid3), (100, true));
+        assert_eq!((m.low_index3, m.low_valid3), (3, true));
+    }
 
-        m.i5 = 0xffffffff;
-        m.i6 = true;
-        m.prop();
-        assert_eq!(m.o3, 0xffffffff);
+    #[test]
+    fn onehot_test_module() {
+        let mut m = OnehotTestModule::new();
+
+        for i in 0..13 {
+            m.onehot = 1 << i;
+            m.binary = i;
+            m.prop();
+            assert_eq!(m.binary_o, i);
+            assert_eq!(m.onehot_o, 1 << i);
+        }
 
-        m.i7 = 0xffffffff;
-        m.i8 = 0xffffffff;
+        // Non-one-hot input
+        m.onehot = 0b1_0000_0000_0110;
         m.prop();
-        assert_eq!(m.o4, 0xfffffffe00000001);
+        assert_eq!(m.binary_o, 12 | 2 | 1);
 
-        m.i9 = 0xfadebabedeadbeef;
-        m.i10 = true;
+        // Out-of-range index
+        m.binary = 13;
         m.prop();
-        assert_eq!(m.o5, 0xfadebabedeadbeef);
+        assert_eq!(m.onehot_o, 0);
+    }
 
-        m.i11 = 0xfadebabedeadbeef;
-        m.i12 = 0xabad1deacafeb00b;
+    #[test]
+    fn dual_clock_mem_test_module() {
+        let mut m = DualClockMemTestModule::new();
+
+        // Reads only happen on read clock edges
+        m.read_addr = 1;
+        m.read_enable = true;
         m.prop();
-        assert_eq!(m.o6, 0xa83c_6c93_0366_3080_b2ff_e1cd_0bdd_8445);
+        m.posedge_write_clk();
+        m.prop();
+        assert_eq!(m.read_value, 0);
+        m.posedge_read_clk();
+        m.prop();
+        assert_eq!(m.read_value, 0x23);
 
-        m.i13 = 0x7adebabedeadbeefabad1deacafeb00b;
-        m.i14 = false;
+        // Writes only happen on write clock edges
+        m.write_addr = 1;
+        m.write_value = 0xab;
+        m.write_enable = true;
         m.prop();
-        assert_eq!(m.o7, 0);
-        m.i14 = true;
+        m.posedge_read_clk();
         m.prop();
-        assert_eq!(m.o7, 0x7adebabedeadbeefabad1deacafeb00b);
+        assert_eq!(m.read_value, 0x23);
+        m.posedge_write_clk();
+        m.write_enable = false;
+        m.prop();
+        assert_eq!(m.read_value, 0x23);
+        m.posedge_read_clk();
+        m.prop();
+        assert_eq!(m.read_value, 0xab);
+
+        // The clocks run at different rates
+        m.write_enable = true;
+        for i in 0..4 {
+            m.write_addr = i;
+            m.write_value = 0x10 + i as u32;
+            m.prop();
+            m.posedge_write_clk();
+        }
+        m.write_enable = false;
+        for i in 0..4 {
+            m.read_addr = i;
+            m.prop();
+            m.posedge_read_clk();
+            m.prop();
+            assert_eq!(m.read_value, 0x10 + i as u32);
+        }
     }
 
     #[test]
-    fn mul_signed_test_module() {
-        let mut m = MulSignedTestModule::new();
+    fn reg_clock_domain_test_module() {
+        let mut m = RegClockDomainTestModule::new();
 
-        m.i1 = false;
-        m.i2 = false;
+        m.reset();
         m.prop();
-        assert_eq!(m.o1, 0);
+        assert_eq!(m.fast_count, 0);
+        assert_eq!(m.slow_count, 0);
+        assert_eq!(m.sampled_fast_count, 0);
+
+        // Registers only advance on their own clock's edges
+        for i in 0..3 {
+            m.posedge_clk_fast();
+            m.prop();
+            assert_eq!(m.fast_count, i + 1);
+            assert_eq!(m.slow_count, 0);
+            assert_eq!(m.sampled_fast_count, 0);
+        }
 
-        m.i1 = true; // -1
-        m.i2 = false;
+        // Registers in the implicit clock domain can sample registers in other domains
+        m.posedge_clk();
         m.prop();
-        assert_eq!(m.o1, 0);
+        assert_eq!(m.fast_count, 3);
+        assert_eq!(m.slow_count, 1);
+        assert_eq!(m.sampled_fast_count, 3);
 
-        m.i1 = false;
-        m.i2 = true; // -1
-        m.prop();
-        assert_eq!(m.o1, 0);
+        // The clocks run at different rates
+        for i in 0..4 {
+            for _ in 0..4 {
+                m.posedge_clk_fast();
+                m.prop();
+            }
+            m.posedge_clk();
+            m.prop();
+            assert_eq!(m.fast_count, 3 + (i + 1) * 4);
+            assert_eq!(m.slow_count, 2 + i);
+            assert_eq!(m.sampled_fast_count, 3 + (i + 1) * 4);
+        }
 
-        m.i1 = true; // -1
-        m.i2 = true; // -1
+        // Reset affects all domains
+        m.reset();
         m.prop();
-        assert_eq!(m.o1, 1);
+        assert_eq!(m.fast_count, 0);
+        assert_eq!(m.slow_count, 0);
+        assert_eq!(m.sampled_fast_count, 0);
+    }
 
-        m.i3 = 7; // -1
-        m.i4 = 15; // -1
-        m.prop();
-        assert_eq!(m.o2, 1);
+    #[test]
+    fn negedge_test_module() {
+        let mut m = NegedgeTestModule::new();
 
-        m.i5 = 0xffffffff; // -1
-        m.i6 = true; // 1
+        m.reset();
         m.prop();
-        assert_eq!(m.o3, 1);
+        assert_eq!(m.pos, 0);
+        assert_eq!(m.neg, 0);
+        assert_eq!(m.back, 0);
+
+        for i in 0..4 {
+            // A full cycle is prop, posedge, prop, negedge, prop
+            m.i = 0x10 + i;
+            m.prop();
+            m.posedge_clk();
+            m.prop();
+            assert_eq!(m.pos, 0x10 + i);
+            // Negative edge registers don't change on positive edges
+            assert_eq!(m.neg, if i == 0 { 0 } else { 0x10 + i });
+            assert_eq!(m.back, if i == 0 { 0 } else { 0x10 + i });
+
+            m.negedge_clk();
+            m.prop();
+            assert_eq!(m.pos, 0x10 + i);
+            // Negative edge registers see the values positive edge registers took half a cycle earlier
+            assert_eq!(m.neg, 0x11 + i);
+            // Positive edge registers don't change on negative edges
+            assert_eq!(m.back, if i == 0 { 0 } else { 0x10 + i });
+        }
 
-        m.i7 = 0xffffffff; // -1
-        m.i8 = 0x7fffffff;
+        m.posedge_clk();
         m.prop();
-        assert_eq!(m.o4, 0xffffffff80000001);
+        assert_eq!(m.back, 0x14);
 
-        m.i9 = 0xfadebabedeadbeef; // negative something
-        m.i10 = true; // -1
+        m.reset();
         m.prop();
-        assert_eq!(m.o5, 0x0521454121524111);
+        assert_eq!(m.pos, 0);
+        assert_eq!(m.neg, 0);
+        assert_eq!(m.back, 0);
+    }
 
-        m.i11 = 0xfadebabedeadbeef; // negative something
-        m.i12 = 0xabad1deacafeb00b; // negative something
-        m.prop();
-        assert_eq!(m.o6, 0x1b093e959b9c186b2ffe1cd0bdd8445);
+    #[test]
+    fn negedge_test_module_io_structs() {
+        let mut m = NegedgeTestModuleIo::new();
+        m.reset();
 
-        m.i13 = 0x7adebabedeadbeefabad1deacafeb00b;
-        m.i14 = false;
-        m.prop();
-        assert_eq!(m.o7, 0);
-        m.i14 = true; // -1
-        m.prop();
-        assert_eq!(m.o7, 0x5214541215241105452e21535014ff5);
+        let vectors = [
+            (
+                NegedgeTestModuleIoInputs { i: 0x10 },
+                NegedgeTestModuleIoOutputs {
+                    pos: 0x10,
+                    neg: 0x11,
+                    back: 0x00,
+                },
+            ),
+            (
+                NegedgeTestModuleIoInputs { i: 0x20 },
+                NegedgeTestModuleIoOutputs {
+                    pos: 0x20,
+                    neg: 0x21,
+                    back: 0x11,
+                },
+            ),
+            (
+                NegedgeTestModuleIoInputs { i: 0xff },
+                NegedgeTestModuleIoOutputs {
+                    pos: 0xff,
+                    neg: 0x00,
+                    back: 0x21,
+                },
+            ),
+        ];
+        for (inputs, expected) in vectors.iter() {
+            m.set_inputs(inputs);
+            m.prop();
+            m.posedge_clk();
+            m.prop();
+            m.negedge_clk();
+            m.prop();
+            assert_eq!(m.i, inputs.i);
+            assert_eq!(m.outputs(), *expected);
+        }
+
+        assert_eq!(
+            NegedgeTestModuleIoOutputs::default(),
+            NegedgeTestModuleIoOutputs {
+                pos: 0,
+                neg: 0,
+                back: 0,
+            }
+        );
     }
 
     #[test]
-    fn shl_test_module() {
-        let mut m = ShlTestModule::new();
+    fn negedge_test_module_wrapped() {
+        let mut m = NegedgeTestModuleWrapped::new();
+        m.reset();
 
-        m.i1 = false;
-        m.i2 = false;
+        m.set_inputs(&NegedgeTestModuleWrappedInputs { i: 0x10 });
         m.prop();
-        assert_eq!(m.o1, false);
-
-        m.i1 = true;
-        m.i2 = false;
+        m.posedge_clk();
         m.prop();
-        assert_eq!(m.o1, true);
-
-        m.i1 = false;
-        m.i2 = true;
+        m.negedge_clk();
         m.prop();
-        assert_eq!(m.o1, false);
+        assert_eq!(
+            m.outputs(),
+            NegedgeTestModuleWrappedOutputs {
+                pos: 0x10,
+                neg: 0x11,
+                back: 0x00,
+            }
+        );
+    }
 
-        m.i1 = true;
-        m.i2 = true;
-        m.prop();
-        assert_eq!(m.o1, false);
+    impl CosimDut for NegedgeTestModule {
+        type Inputs = u32;
 
-        m.i3 = 0xffff;
-        m.i4 = 0;
-        m.prop();
-        assert_eq!(m.o2, 0xffff);
+        fn set_inputs(&mut self, inputs: &u32) {
+            self.i = *inputs;
+        }
 
-        m.i3 = 0xffff;
-        m.i4 = 4;
-        m.prop();
-        assert_eq!(m.o2, 0xfff0);
+        fn cycle(&mut self) {
+            self.prop();
+            self.posedge_clk();
+            self.prop();
+            self.negedge_clk();
+            self.prop();
+        }
 
-        m.i3 = 0xffff;
-        m.i4 = 8;
-        m.prop();
-        assert_eq!(m.o2, 0xff00);
+        fn outputs(&self) -> Vec<(&'static str, u128)> {
+            vec![
+                ("pos", self.pos as _),
+                ("neg", self.neg as _),
+                ("back", self.back as _),
+            ]
+        }
+    }
 
-        m.i3 = 0xffff;
-        m.i4 = 16;
-        m.prop();
-        assert_eq!(m.o2, 0);
+    /// Reference model for `NegedgeTestModule`, with the same cycle semantics.
+    #[derive(Default)]
+    struct NegedgeModel {
+        i: u8,
+        pos: u8,
+        neg: u8,
+        back: u8,
+        // Simulates a design bug that corrupts `neg` once `pos` reaches this value
+        bug_at: Option<u8>,
+    }
 
-        m.i3 = 0xffff;
-        m.i4 = 3;
-        m.prop();
-        assert_eq!(m.o2, 0xfff8);
+    impl CosimDut for NegedgeModel {
+        type Inputs = u32;
 
-        m.i3 = 0xffff;
-        m.i4 = 31;
-        m.prop();
-        assert_eq!(m.o2, 0);
+        fn set_inputs(&mut self, inputs: &u32) {
+            self.i = *inputs as u8;
+        }
 
-        m.i3 = 0xffff;
-        m.i4 = 32;
-        m.prop();
-        assert_eq!(m.o2, 0);
+        fn cycle(&mut self) {
+            // Positive edge
+            self.back = self.neg;
+            self.pos = self.i;
+            // Negative edge, half a cycle later
+            self.neg = self.pos.wrapping_add(1);
+            if self.bug_at == Some(self.pos) {
+                self.neg ^= 0x80;
+            }
+        }
 
-        m.i5 = 0xfadebabe;
-        m.i6 = 4;
-        m.prop();
-        assert_eq!(m.o3, 0xadebabe0);
+        fn outputs(&self) -> Vec<(&'static str, u128)> {
+            vec![
+                ("pos", self.pos as _),
+                ("neg", self.neg as _),
+                ("back", self.back as _),
+            ]
+        }
+    }
 
-        m.i5 = 0xdeadbeef;
-        m.i6 = 16;
-        m.prop();
-        assert_eq!(m.o3, 0xbeef0000);
+    #[test]
+    fn negedge_test_module_cosim() {
+        let stimulus = (0..256u32)
+            .map(|i| i.wrapping_mul(0x9d) & 0xff)
+            .collect::<Vec<_>>();
 
-        m.i5 = 0xdeadbeef;
-        m.i6 = std::u32::MAX;
-        m.prop();
-        assert_eq!(m.o3, 0);
+        let mut m = NegedgeTestModule::new();
+        m.reset();
+        assert_eq!(
+            cosim(&mut m, &mut NegedgeModel::default(), stimulus.clone()),
+            Ok(256)
+        );
 
-        m.i7 = 0xfadebabedeadbeef;
-        m.i8 = 4;
+        // Divergences are reported with the cycle they occurred in and the preceding cycles
+        let mut m = NegedgeTestModule::new();
+        m.reset();
+        let mut model = NegedgeModel {
+            bug_at: Some(stimulus[10] as u8),
+            ..NegedgeModel::default()
+        };
+        let divergence = Cosim::new()
+            .history(3)
+            .run(&mut m, &mut model, stimulus)
+            .unwrap_err();
+        assert_eq!(divergence.cycle, 10);
+        assert_eq!(divergence.differences.len(), 1);
+        assert_eq!(divergence.differences[0].name, "neg");
+        assert_eq!(
+            divergence.differences[0].dut ^ divergence.differences[0].model,
+            0x80
+        );
+        assert_eq!(divergence.history.len(), 3);
+        assert_eq!(divergence.history[2].cycle, 9);
+    }
+
+    #[test]
+    fn assertion_test_module() {
+        let mut m = AssertionTestModule::new();
+
+        m.reset();
+        m.i = 0x02;
         m.prop();
-        assert_eq!(m.o4, 0xadebabedeadbeef0);
+        assert_eq!(m.check_assertions(), Ok(()));
+        m.posedge_clk();
+        m.prop();
+        assert_eq!(m.check_assertions(), Ok(()));
 
-        m.i7 = 0xfadebabedeadbeef;
-        m.i8 = 16;
+        // Assertions in child instances are checked as well
+        m.i = 0xff;
         m.prop();
-        assert_eq!(m.o4, 0xbabedeadbeef0000);
+        assert_eq!(
+            m.check_assertions(),
+            Err("assertion_test_module.inner.i_not_odd")
+        );
 
-        m.i7 = 0xfadebabedeadbeef;
-        m.i8 = std::u64::MAX;
+        // Assertions are checked in hierarchy order
+        m.posedge_clk();
         m.prop();
-        assert_eq!(m.o4, 0);
+        assert_eq!(m.check_assertions(), Err("assertion_test_module.r_not_ff"));
 
-        m.i9 = 0xaaaaaaaa55555555fadebabedeadbeef;
-        m.i10 = 4;
+        m.i = 0xfe;
         m.prop();
-        assert_eq!(m.o5, 0xaaaaaaa55555555fadebabedeadbeef0);
+        assert_eq!(m.check_assertions(), Err("assertion_test_module.r_not_ff"));
+        m.posedge_clk();
+        m.prop();
+        assert_eq!(m.check_assertions(), Ok(()));
+    }
 
-        m.i9 = 0xaaaaaaaa55555555fadebabedeadbeef;
-        m.i10 = 16;
+    #[test]
+    fn assert_always_test_module() {
+        let mut m = AssertAlwaysTestModule::new();
+
+        m.reset();
+        m.push = true;
+        for _ in 0..3 {
+            m.prop();
+            m.posedge_clk();
+        }
+        m.push = false;
         m.prop();
-        assert_eq!(m.o5, 0xaaaa55555555fadebabedeadbeef0000);
+        assert_eq!(m.count, 3);
+        assert_eq!(m.check_assertions(), Ok(()));
+    }
 
-        m.i9 = 0xaaaaaaaa55555555fadebabedeadbeef;
-        m.i10 = std::u128::MAX;
+    #[test]
+    #[should_panic(
+        expected = "Assertion \"assert_always_test_module.no_overflow\" failed: FIFO overflowed"
+    )]
+    fn assert_always_test_module_error() {
+        let mut m = AssertAlwaysTestModule::new();
+
+        m.reset();
+        m.push = true;
+        for _ in 0..3 {
+            m.prop();
+            m.posedge_clk();
+        }
+        // Panic
         m.prop();
-        assert_eq!(m.o5, 0);
+    }
+
+    #[test]
+    fn assert_always_collect_test_module() {
+        let mut m = AssertAlwaysCollectTestModule::new();
+
+        m.reset();
+        m.push = true;
+        for _ in 0..3 {
+            m.prop();
+            m.posedge_clk();
+        }
+        assert!(m.assertion_failures.is_empty());
 
-        m.i11 = 0x7f;
-        m.i12 = 1;
         m.prop();
-        assert_eq!(m.o6, 0x7e);
+        assert_eq!(
+            m.assertion_failures,
+            vec!["Assertion \"assert_always_test_module.no_overflow\" failed: FIFO overflowed"]
+        );
+        assert_eq!(
+            m.check_assertions(),
+            Err("assert_always_test_module.no_overflow")
+        );
 
-        m.i11 = 0x7f;
-        m.i12 = 4;
+        // Failures are collected until the test bench clears them
+        m.posedge_clk();
+        m.push = false;
         m.prop();
-        assert_eq!(m.o6, 0x70);
+        assert_eq!(m.assertion_failures.len(), 1);
+        m.assertion_failures.clear();
+    }
 
-        m.i11 = 0x7f;
-        m.i12 = 6;
+    #[test]
+    fn cover_test_module() {
+        let mut m = CoverTestModule::new();
+
+        let coverage = |m: &CoverTestModule| {
+            let coverage = m.coverage();
+            (
+                coverage["cover_test_module.r_ff"],
+                coverage["cover_test_module.inner.i_odd"],
+            )
+        };
+
+        m.reset();
+        m.i = 0x02;
         m.prop();
-        assert_eq!(m.o6, 0x40);
+        assert_eq!(m.coverage().len(), 2);
+        assert_eq!(coverage(&m), (0, 0));
 
-        m.i11 = 0x7f;
-        m.i12 = 7;
+        // Counters are incremented by each call to prop in which their conditions are high
+        m.i = 0xff;
         m.prop();
-        assert_eq!(m.o6, 0);
+        assert_eq!(coverage(&m), (0, 1));
+        assert_eq!(
+            m.coverage_report(),
+            "cover_test_module.inner.i_odd: 1\ncover_test_module.r_ff: 0 (not hit)\n1/2 coverage point(s) hit\n"
+        );
+        m.posedge_clk();
+        m.prop();
+        assert_eq!(coverage(&m), (1, 2));
+        m.prop();
+        assert_eq!(coverage(&m), (2, 3));
+        assert_eq!(
+            m.coverage_report(),
+            "cover_test_module.inner.i_odd: 3\ncover_test_module.r_ff: 2\n2/2 coverage point(s) hit\n"
+        );
 
-        m.i13 = 0xdeadbeef;
-        m.i14 = false;
+        // Counters aren't affected by reset
+        m.reset();
+        m.i = 0x00;
         m.prop();
-        assert_eq!(m.o7, 0xdeadbeef);
+        assert_eq!(coverage(&m), (2, 3));
 
-        m.i13 = 0xdeadbeef;
+        m.reset_coverage();
+        assert_eq!(coverage(&m), (0, 0));
+        m.prop();
+        assert_eq!(coverage(&m), (0, 0));
+    }
+
+    #[test]
+    fn clock_divider_test_module() {
+        let mut m = ClockDividerTestModule::new();
+
+        m.reset();
+        m.prop();
+        assert_eq!(m.div3_count, 0);
+        assert_eq!(m.div2_count, 0);
+
+        // Registers on a divided clock are updated on the first posedge after reset, and every N posedges after that
+        for cycle in 0..12u32 {
+            m.i = cycle as _;
+            m.prop();
+            m.posedge_clk();
+            m.prop();
+            assert_eq!(m.div3_count, cycle / 3 + 1);
+            assert_eq!(m.div2_count, cycle / 2 + 1);
+            assert_eq!(m.sampled, (cycle - cycle % 3) as _);
+        }
+
+        m.reset();
+        m.prop();
+        assert_eq!(m.div3_count, 0);
+        m.posedge_clk();
+        m.prop();
+        assert_eq!(m.div3_count, 1);
+    }
+
+    #[test]
+    fn signal_vec_test_module() {
+        let mut m = SignalVecTestModule::new();
+
+        let a = [0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0xde];
+        m.a0 = a[0];
+        m.a1 = a[1];
+        m.a2 = a[2];
+        m.a3 = a[3];
+        m.a4 = a[4];
+        m.a5 = a[5];
+        m.a6 = a[6];
+        for index in 0..16 {
+            m.a_index = index;
+            m.prop();
+            // Out-of-range indices select the last element
+            assert_eq!(m.a_selected, a[(index as usize).min(a.len() - 1)]);
+        }
+
+        let b = [5, 3, 0, 7, 1, 6, 2, 4, 4, 2, 6, 1, 7, 0, 3, 5];
+        m.b0 = b[0];
+        m.b1 = b[1];
+        m.b2 = b[2];
+        m.b3 = b[3];
+        m.b4 = b[4];
+        m.b5 = b[5];
+        m.b6 = b[6];
+        m.b7 = b[7];
+        m.b8 = b[8];
+        m.b9 = b[9];
+        m.b10 = b[10];
+        m.b11 = b[11];
+        m.b12 = b[12];
+        m.b13 = b[13];
+        m.b14 = b[14];
+        m.b15 = b[15];
+        for index in 0..16 {
+            m.b_index = index;
+            m.prop();
+            assert_eq!(m.b_selected, b[index as usize]);
+        }
+    }
+
+    #[test]
+    fn no_std_test_module() {
+        let mut m = no_std_modules::NoStdTestModule::new();
+
+        m.i = 0x81;
+        m.shift = 4;
+        m.prop();
+        m.posedge_clk();
+        m.prop();
+        assert_eq!(m.shifted, 0x10);
+        assert_eq!(m.mem1_value, 0x23);
+
+        m.shift = 200;
+        m.wide = u128::MAX;
+        m.write_enable = true;
+        m.prop();
+        m.posedge_clk();
+        m.write_enable = false;
+        m.prop();
+        m.posedge_clk();
+        m.prop();
+        assert_eq!(m.shifted, 0);
+        assert_eq!(m.mem2_value, u128::MAX);
+
+        m.reset();
+        m.prop();
+        m.posedge_clk();
+        m.prop();
+        assert_eq!(m.mem2_value, 0);
+    }
+
+    #[test]
+    fn no_std_test_module_compiles_without_std() {
+        let dir = std::env::temp_dir().join(format!("kaze_no_std_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let crate_path = dir.join("lib.rs");
+        std::fs::write(
+            &crate_path,
+            format!(
+                "#![no_std]\ninclude!({:?});\n",
+                concat!(env!("OUT_DIR"), "/no_std_modules.rs")
+            ),
+        )
+        .unwrap();
+
+        let output = std::process::Command::new(env!("SIM_TESTS_RUSTC"))
+            .args(&[
+                "--crate-type",
+                "lib",
+                "--edition",
+                "2018",
+                "--emit",
+                "metadata",
+            ])
+            .arg("--out-dir")
+            .arg(&dir)
+            .arg(&crate_path)
+            .output()
+            .unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    #[test]
+    fn mul_test_module() {
+        let mut m = MulTestModule::new();
+
+        m.i1 = false;
+        m.i2 = false;
+        m.prop();
+        assert_eq!(m.o1, 0);
+
+        m.i1 = true;
+        m.i2 = false;
+        m.prop();
+        assert_eq!(m.o1, 0);
+
+        m.i1 = false;
+        m.i2 = true;
+        m.prop();
+        assert_eq!(m.o1, 0);
+
+        m.i1 = true;
+        m.i2 = true;
+        m.prop();
+        assert_eq!(m.o1, 1);
+
+        m.i3 = 7;
+        m.i4 = 15;
+        m.prop();
+        assert_eq!(m.o2, 105);
+
+        m.i5 = 0xffffffff;
+        m.i6 = true;
+        m.prop();
+        assert_eq!(m.o3, 0xffffffff);
+
+        m.i7 = 0xffffffff;
+        m.i8 = 0xffffffff;
+        m.prop();
+        assert_eq!(m.o4, 0xfffffffe00000001);
+
+        m.i9 = 0xfadebabedeadbeef;
+        m.i10 = true;
+        m.prop();
+        assert_eq!(m.o5, 0xfadebabedeadbeef);
+
+        m.i11 = 0xfadebabedeadbeef;
+        m.i12 = 0xabad1deacafeb00b;
+        m.prop();
+        assert_eq!(m.o6, 0xa83c_6c93_0366_3080_b2ff_e1cd_0bdd_8445);
+
+        m.i13 = 0x7adebabedeadbeefabad1deacafeb00b;
+        m.i14 = false;
+        m.prop();
+        assert_eq!(m.o7, 0);
         m.i14 = true;
         m.prop();
-        assert_eq!(m.o7, 0xbd5b7dde);
+        assert_eq!(m.o7, 0x7adebabedeadbeefabad1deacafeb00b);
+    }
 
-        m.i15 = 0xfadebabedeadbeef;
-        m.i16 = false;
+    #[test]
+    fn mul_signed_test_module() {
+        let mut m = MulSignedTestModule::new();
+
+        m.i1 = false;
+        m.i2 = false;
         m.prop();
-        assert_eq!(m.o8, 0xfadebabedeadbeef);
+        assert_eq!(m.o1, 0);
 
-        m.i15 = 0xfadebabedeadbeef;
-        m.i16 = true;
+        m.i1 = true; // -1
+        m.i2 = false;
         m.prop();
-        assert_eq!(m.o8, 0xf5bd757dbd5b7dde);
+        assert_eq!(m.o1, 0);
 
-        m.i17 = 0xaaaaaaaa55555555fadebabedeadbeef;
-        m.i18 = false;
+        m.i1 = false;
+        m.i2 = true; // -1
         m.prop();
-        assert_eq!(m.o9, 0xaaaaaaaa55555555fadebabedeadbeef);
+        assert_eq!(m.o1, 0);
 
-        m.i17 = 0xaaaaaaaa55555555fadebabedeadbeef;
-        m.i18 = true;
+        m.i1 = true; // -1
+        m.i2 = true; // -1
         m.prop();
-        assert_eq!(m.o9, 0x55555554aaaaaaabf5bd757dbd5b7dde);
+        assert_eq!(m.o1, 1);
+
+        m.i3 = 7; // -1
+        m.i4 = 15; // -1
+        m.prop();
+        assert_eq!(m.o2, 1);
+
+        m.i5 = 0xffffffff; // -1
+        m.i6 = true; // 1
+        m.prop();
+        assert_eq!(m.o3, 1);
+
+        m.i7 = 0xffffffff; // -1
+        m.i8 = 0x7fffffff;
+        m.prop();
+        assert_eq!(m.o4, 0xffffffff80000001);
+
+        m.i9 = 0xfadebabedeadbeef; // negative something
+        m.i10 = true; // -1
+        m.prop();
+        assert_eq!(m.o5, 0x0521454121524111);
+
+        m.i11 = 0xfadebabedeadbeef; // negative something
+        m.i12 = 0xabad1deacafeb00b; // negative something
+        m.prop();
+        assert_eq!(m.o6, 0x1b093e959b9c186b2ffe1cd0bdd8445);
+
+        m.i13 = 0x7adebabedeadbeefabad1deacafeb00b;
+        m.i14 = false;
+        m.prop();
+        assert_eq!(m.o7, 0);
+        m.i14 = true; // -1
+        m.prop();
+        assert_eq!(m.o7, 0x5214541215241105452e21535014ff5);
     }
 
     #[test]
-    fn shr_test_module() {
-        let mut m = ShrTestModule::new();
+    fn shl_test_module() {
+        let mut m = ShlTestModule::new();
 
         m.i1 = false;
         m.i2 = false;
@@ -677,12 +1312,12 @@ mod tests {
         m.i3 = 0xffff;
         m.i4 = 4;
         m.prop();
-        assert_eq!(m.o2, 0x0fff);
+        assert_eq!(m.o2, 0xfff0);
 
         m.i3 = 0xffff;
         m.i4 = 8;
         m.prop();
-        assert_eq!(m.o2, 0x00ff);
+        assert_eq!(m.o2, 0xff00);
 
         m.i3 = 0xffff;
         m.i4 = 16;
@@ -692,7 +1327,7 @@ mod tests {
         m.i3 = 0xffff;
         m.i4 = 3;
         m.prop();
-        assert_eq!(m.o2, 0x1fff);
+        assert_eq!(m.o2, 0xfff8);
 
         m.i3 = 0xffff;
         m.i4 = 31;
@@ -707,12 +1342,12 @@ mod tests {
         m.i5 = 0xfadebabe;
         m.i6 = 4;
         m.prop();
-        assert_eq!(m.o3, 0x0fadebab);
+        assert_eq!(m.o3, 0xadebabe0);
 
         m.i5 = 0xdeadbeef;
         m.i6 = 16;
         m.prop();
-        assert_eq!(m.o3, 0x0000dead);
+        assert_eq!(m.o3, 0xbeef0000);
 
         m.i5 = 0xdeadbeef;
         m.i6 = std::u32::MAX;
@@ -722,12 +1357,12 @@ mod tests {
         m.i7 = 0xfadebabedeadbeef;
         m.i8 = 4;
         m.prop();
-        assert_eq!(m.o4, 0x0fadebabedeadbee);
+        assert_eq!(m.o4, 0xadebabedeadbeef0);
 
         m.i7 = 0xfadebabedeadbeef;
         m.i8 = 16;
         m.prop();
-        assert_eq!(m.o4, 0x0000fadebabedead);
+        assert_eq!(m.o4, 0xbabedeadbeef0000);
 
         m.i7 = 0xfadebabedeadbeef;
         m.i8 = std::u64::MAX;
@@ -737,12 +1372,12 @@ mod tests {
         m.i9 = 0xaaaaaaaa55555555fadebabedeadbeef;
         m.i10 = 4;
         m.prop();
-        assert_eq!(m.o5, 0x0aaaaaaaa55555555fadebabedeadbee);
+        assert_eq!(m.o5, 0xaaaaaaa55555555fadebabedeadbeef0);
 
         m.i9 = 0xaaaaaaaa55555555fadebabedeadbeef;
         m.i10 = 16;
         m.prop();
-        assert_eq!(m.o5, 0x0000aaaaaaaa55555555fadebabedead);
+        assert_eq!(m.o5, 0xaaaa55555555fadebabedeadbeef0000);
 
         m.i9 = 0xaaaaaaaa55555555fadebabedeadbeef;
         m.i10 = std::u128::MAX;
@@ -752,17 +1387,17 @@ mod tests {
         m.i11 = 0x7f;
         m.i12 = 1;
         m.prop();
-        assert_eq!(m.o6, 0x3f);
+        assert_eq!(m.o6, 0x7e);
 
         m.i11 = 0x7f;
         m.i12 = 4;
         m.prop();
-        assert_eq!(m.o6, 0x07);
+        assert_eq!(m.o6, 0x70);
 
         m.i11 = 0x7f;
         m.i12 = 6;
         m.prop();
-        assert_eq!(m.o6, 0x01);
+        assert_eq!(m.o6, 0x40);
 
         m.i11 = 0x7f;
         m.i12 = 7;
@@ -777,7 +1412,7 @@ mod tests {
         m.i13 = 0xdeadbeef;
         m.i14 = true;
         m.prop();
-        assert_eq!(m.o7, 0x6f56df77);
+        assert_eq!(m.o7, 0xbd5b7dde);
 
         m.i15 = 0xfadebabedeadbeef;
         m.i16 = false;
@@ -787,7 +1422,162 @@ mod tests {
         m.i15 = 0xfadebabedeadbeef;
         m.i16 = true;
         m.prop();
-        assert_eq!(m.o8, 0x7d6f5d5f6f56df77);
+        assert_eq!(m.o8, 0xf5bd757dbd5b7dde);
+
+        m.i17 = 0xaaaaaaaa55555555fadebabedeadbeef;
+        m.i18 = false;
+        m.prop();
+        assert_eq!(m.o9, 0xaaaaaaaa55555555fadebabedeadbeef);
+
+        m.i17 = 0xaaaaaaaa55555555fadebabedeadbeef;
+        m.i18 = true;
+        m.prop();
+        assert_eq!(m.o9, 0x55555554aaaaaaabf5bd757dbd5b7dde);
+    }
+
+    #[test]
+    fn shr_test_module() {
+        let mut m = ShrTestModule::new();
+
+        m.i1 = false;
+        m.i2 = false;
+        m.prop();
+        assert_eq!(m.o1, false);
+
+        m.i1 = true;
+        m.i2 = false;
+        m.prop();
+        assert_eq!(m.o1, true);
+
+        m.i1 = false;
+        m.i2 = true;
+        m.prop();
+        assert_eq!(m.o1, false);
+
+        m.i1 = true;
+        m.i2 = true;
+        m.prop();
+        assert_eq!(m.o1, false);
+
+        m.i3 = 0xffff;
+        m.i4 = 0;
+        m.prop();
+        assert_eq!(m.o2, 0xffff);
+
+        m.i3 = 0xffff;
+        m.i4 = 4;
+        m.prop();
+        assert_eq!(m.o2, 0x0fff);
+
+        m.i3 = 0xffff;
+        m.i4 = 8;
+        m.prop();
+        assert_eq!(m.o2, 0x00ff);
+
+        m.i3 = 0xffff;
+        m.i4 = 16;
+        m.prop();
+        assert_eq!(m.o2, 0);
+
+        m.i3 = 0xffff;
+        m.i4 = 3;
+        m.prop();
+        assert_eq!(m.o2, 0x1fff);
+
+        m.i3 = 0xffff;
+        m.i4 = 31;
+        m.prop();
+        assert_eq!(m.o2, 0);
+
+        m.i3 = 0xffff;
+        m.i4 = 32;
+        m.prop();
+        assert_eq!(m.o2, 0);
+
+        m.i5 = 0xfadebabe;
+        m.i6 = 4;
+        m.prop();
+        assert_eq!(m.o3, 0x0fadebab);
+
+        m.i5 = 0xdeadbeef;
+        m.i6 = 16;
+        m.prop();
+        assert_eq!(m.o3, 0x0000dead);
+
+        m.i5 = 0xdeadbeef;
+        m.i6 = std::u32::MAX;
+        m.prop();
+        assert_eq!(m.o3, 0);
+
+        m.i7 = 0xfadebabedeadbeef;
+        m.i8 = 4;
+        m.prop();
+        assert_eq!(m.o4, 0x0fadebabedeadbee);
+
+        m.i7 = 0xfadebabedeadbeef;
+        m.i8 = 16;
+        m.prop();
+        assert_eq!(m.o4, 0x0000fadebabedead);
+
+        m.i7 = 0xfadebabedeadbeef;
+        m.i8 = std::u64::MAX;
+        m.prop();
+        assert_eq!(m.o4, 0);
+
+        m.i9 = 0xaaaaaaaa55555555fadebabedeadbeef;
+        m.i10 = 4;
+        m.prop();
+        assert_eq!(m.o5, 0x0aaaaaaaa55555555fadebabedeadbee);
+
+        m.i9 = 0xaaaaaaaa55555555fadebabedeadbeef;
+        m.i10 = 16;
+        m.prop();
+        assert_eq!(m.o5, 0x0000aaaaaaaa55555555fadebabedead);
+
+        m.i9 = 0xaaaaaaaa55555555fadebabedeadbeef;
+        m.i10 = std::u128::MAX;
+        m.prop();
+        assert_eq!(m.o5, 0);
+
+        m.i11 = 0x7f;
+        m.i12 = 1;
+        m.prop();
+        assert_eq!(m.o6, 0x3f);
+
+        m.i11 = 0x7f;
+        m.i12 = 4;
+        m.prop();
+        assert_eq!(m.o6, 0x07);
+
+        m.i11 = 0x7f;
+        m.i12 = 6;
+        m.prop();
+        assert_eq!(m.o6, 0x01);
+
+        m.i11 = 0x7f;
+        m.i12 = 7;
+        m.prop();
+        assert_eq!(m.o6, 0);
+
+        m.i13 = 0xdeadbeef;
+        m.i14 = false;
+        m.prop();
+        assert_eq!(m.o7, 0xdeadbeef);
+
+        m.i13 = 0xdeadbeef;
+        m.i14 = true;
+        m.prop();
+        assert_eq!(m.o7, 0x6f56df77);
+
+        m.i15 = 0xfadebabedeadbeef;
+        m.i16 = false;
+        m.prop();
+        assert_eq!(m.o8, 0xfadebabedeadbeef);
+
+        m.i15 = 0xfadebabedeadbeef;
+        m.i16 = true;
+        m.prop();
+        assert_eq!(m.o8, 0x7d6f5d5f6f56df77);
 
         m.i17 = 0xaaaaaaaa55555555fadebabedeadbeef;
         m.i18 = false;
@@ -1010,6 +1800,101 @@ mod tests {
         assert_eq!(m.o9, 0xd55555552aaaaaaafd6f5d5f6f56df77);
     }
 
+    #[test]
+    fn rotate_test_module() {
+        let mut m = RotateTestModule::new();
+        let mut m_optimized = RotateTestModuleOptimized::new();
+
+        macro_rules! check {
+            ($($input:ident = $value:expr),*; $($output:ident == $expected:expr),*) => {
+                $(m.$input = $value; m_optimized.$input = $value;)*
+                m.prop();
+                m_optimized.prop();
+                $(
+                    assert_eq!(m.$output, $expected);
+                    assert_eq!(m_optimized.$output, $expected);
+                )*
+            };
+        }
+
+        // 7 bits isn't a power of two, so amounts wrap at 7
+        check!(i1 = 0b100_0001, a1 = 1; l1 == 0b000_0011, r1 == 0b110_0000, lc1 == 0b000_1100, rc1 == 0b001_1000);
+        check!(a1 = 0; l1 == 0b100_0001, r1 == 0b100_0001);
+        check!(a1 = 7; l1 == 0b100_0001, r1 == 0b100_0001);
+        check!(a1 = 9; l1 == 0b000_0110, r1 == 0b011_0000);
+        check!(a1 = 15; l1 == 0b000_0011, r1 == 0b110_0000);
+
+        check!(i2 = 0x8000_00f1, a2 = 4; l2 == 0x0000_0f18, r2 == 0x1800_000f);
+        check!(a2 = 0; l2 == 0x8000_00f1, r2 == 0x8000_00f1);
+        check!(a2 = 32; l2 == 0x8000_00f1, r2 == 0x8000_00f1);
+        check!(a2 = 63; l2 == 0xc000_0078, r2 == 0x0000_01e3);
+
+        check!(i3 = 0x8000_0000_0000_0000_0000_0000_0000_0001, a3 = 1;
+            l3 == 0x0000_0000_0000_0000_0000_0000_0000_0003,
+            r3 == 0xc000_0000_0000_0000_0000_0000_0000_0000,
+            lc3 == 0x0000_0000_0000_0000_0000_0000_0000_0018,
+            rc3 == 0x8000_0000_0000_0000_0000_0000_0000_0001);
+        check!(a3 = 128; l3 == 0x8000_0000_0000_0000_0000_0000_0000_0001, r3 == 0x8000_0000_0000_0000_0000_0000_0000_0001);
+        check!(a3 = 0; l3 == 0x8000_0000_0000_0000_0000_0000_0000_0001, r3 == 0x8000_0000_0000_0000_0000_0000_0000_0001);
+        check!(a3 = 255;
+            l3 == 0xc000_0000_0000_0000_0000_0000_0000_0000,
+            r3 == 0x0000_0000_0000_0000_0000_0000_0000_0003);
+
+        check!(i4 = true, a1 = 5; l4 == true);
+
+        check!(; l5 == 0x06, r5 == 0xc0);
+    }
+
+    #[test]
+    fn shift_fill_test_module() {
+        let mut m = ShiftFillTestModule::new();
+
+        m.i1 = 0x81;
+        m.i2 = 2;
+        m.fill = false;
+        m.prop();
+        assert_eq!(m.o1, 0x04);
+        assert_eq!(m.o2, 0x20);
+        assert_eq!(m.o3, 0x04);
+        assert_eq!(m.o4, 0x20);
+
+        m.fill = true;
+        m.prop();
+        assert_eq!(m.o1, 0x07);
+        assert_eq!(m.o2, 0xe0);
+
+        m.i2 = 0;
+        m.prop();
+        assert_eq!(m.o1, 0x81);
+        assert_eq!(m.o2, 0x81);
+
+        // Shift amounts at or beyond the bit width leave only fill bits
+        m.i2 = 8;
+        m.prop();
+        assert_eq!(m.o1, 0xff);
+        assert_eq!(m.o2, 0xff);
+        assert_eq!(m.o3, 0x00);
+        assert_eq!(m.o4, 0x00);
+
+        m.i2 = 15;
+        m.fill = false;
+        m.prop();
+        assert_eq!(m.o1, 0x00);
+        assert_eq!(m.o2, 0x00);
+
+        m.i3 = 0x8000_0000_0000_0000_0000_0000_0000_0001;
+        m.i4 = 4;
+        m.fill = true;
+        m.prop();
+        assert_eq!(m.o5, 0x0000_0000_0000_0000_0000_0000_0000_001f);
+        assert_eq!(m.o6, 0xf800_0000_0000_0000_0000_0000_0000_0000);
+
+        m.i4 = 200;
+        m.prop();
+        assert_eq!(m.o5, u128::MAX);
+        assert_eq!(m.o6, u128::MAX);
+    }
+
     #[test]
     fn bit_and_test_module() {
         let mut m = BitAndTestModule::new();
@@ -1860,6 +2745,75 @@ mod tests {
         assert_eq!(m.read_data, 0xabad1dea);
     }
 
+    #[test]
+    fn mem_test_module_1_runtime_contents() {
+        let read = |m: &mut MemTestModule1, addr| {
+            m.read_addr = addr;
+            m.read_enable = true;
+            m.prop();
+            m.posedge_clk();
+            m.prop();
+            m.read_data
+        };
+
+        // Contents loaded at runtime replace the initial contents, and missing elements are 0
+        let mut m = MemTestModule1::new().with_mem_contents("mem_test_module_1.mem", &[0x13, 0x93]);
+        assert_eq!(read(&mut m, 0), 0x13);
+        assert_eq!(read(&mut m, 1), 0x93);
+        assert_eq!(read(&mut m, 2), 0);
+        assert_eq!(read(&mut m, 3), 0);
+
+        // The same instance can be reloaded
+        m.load_mem_contents(
+            "mem_test_module_1.mem",
+            &[0x01234567, 0x89abcdef, 0xffffffff, 0x00000001],
+        );
+        assert_eq!(read(&mut m, 0), 0x01234567);
+        assert_eq!(read(&mut m, 3), 0x00000001);
+
+        // `new()` still uses the initial contents
+        let mut m = MemTestModule1::new();
+        assert_eq!(read(&mut m, 0), 0xfadebabe);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Attempted to load 5 element(s) into memory \"mem_test_module_1.mem\", but this memory contains 4 element(s)."
+    )]
+    fn mem_test_module_1_runtime_contents_too_long_error() {
+        // Panic
+        let _ = MemTestModule1::new().with_mem_contents("mem_test_module_1.mem", &[0; 5]);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Attempted to load element 1 with value 0x100000000 into memory \"mem_test_module_1.mem\", but this memory has an element width of 32 bit(s)."
+    )]
+    fn mem_test_module_1_runtime_contents_too_wide_error() {
+        // Panic
+        let _ = MemTestModule1::new().with_mem_contents("mem_test_module_1.mem", &[0, 1 << 32]);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Attempted to load the contents of memory \"mem_test_module_1.rom\", but there's no memory with that name."
+    )]
+    fn mem_test_module_1_runtime_contents_nonexistent_error() {
+        // Panic
+        let _ = MemTestModule1::new().with_mem_contents("mem_test_module_1.rom", &[0]);
+    }
+
+    #[test]
+    fn mem_test_module_2_runtime_contents() {
+        let mut m = MemTestModule2::new().with_mem_contents("mem_test_module_2.mem", &[0, 1]);
+        m.read_addr = true;
+        m.read_enable = true;
+        m.prop();
+        m.posedge_clk();
+        m.prop();
+        assert_eq!(m.read_data, true);
+    }
+
     #[test]
     fn mem_test_module_2() {
         let mut m = MemTestModule2::new();
@@ -1970,67 +2924,1051 @@ mod tests {
         assert_eq!(m.read_data, true);
         m.posedge_clk();
         m.prop();
-        assert_eq!(m.read_data, true);
+        assert_eq!(m.read_data, true);
+
+        // Read from addr 0
+        m.write_addr = false;
+        m.write_value = false;
+        m.write_enable = false;
+        m.read_addr = false;
+        m.read_enable = true;
+        m.prop();
+        assert_eq!(m.read_data, true);
+        m.posedge_clk();
+        m.prop();
+        assert_eq!(m.read_data, false);
+
+        // Read from addr 1
+        m.write_addr = false;
+        m.write_value = false;
+        m.write_enable = false;
+        m.read_addr = true;
+        m.read_enable = true;
+        m.prop();
+        assert_eq!(m.read_data, false);
+        m.posedge_clk();
+        m.prop();
+        assert_eq!(m.read_data, true);
+
+        // Write to addr 0/read from addr 1
+        m.write_addr = false;
+        m.write_value = true;
+        m.write_enable = true;
+        m.read_addr = true;
+        m.read_enable = true;
+        m.prop();
+        assert_eq!(m.read_data, true);
+        m.posedge_clk();
+        m.prop();
+        assert_eq!(m.read_data, true);
+
+        // Write to addr 1/read from addr 0
+        m.write_addr = true;
+        m.write_value = false;
+        m.write_enable = true;
+        m.read_addr = false;
+        m.read_enable = true;
+        m.prop();
+        assert_eq!(m.read_data, true);
+        m.posedge_clk();
+        m.prop();
+        assert_eq!(m.read_data, true);
+
+        // Read from addr 1
+        m.write_addr = false;
+        m.write_value = false;
+        m.write_enable = false;
+        m.read_addr = true;
+        m.read_enable = true;
+        m.prop();
+        assert_eq!(m.read_data, true);
+        m.posedge_clk();
+        m.prop();
+        assert_eq!(m.read_data, false);
+    }
+
+    #[test]
+    fn bool_network_test_module() {
+        let mut m = BoolNetworkTestModule::new();
+
+        for a in 0..2u32 {
+            for b in 0..2u32 {
+                for c in 0..2u32 {
+                    for s in 0..8u32 {
+                        m.a = a != 0;
+                        m.b = b != 0;
+                        m.c = c != 0;
+                        m.s = s;
+                        m.prop();
+
+                        // Compare against the equivalent wider-type computations
+                        assert_eq!(m.add, (a.wrapping_add(b) & 1) != 0);
+                        assert_eq!(m.sub, (a.wrapping_sub(b) & 1) != 0);
+                        assert_eq!(m.shl_b, ((a << b) & 1) != 0);
+                        assert_eq!(m.shr_b, ((a >> b) & 1) != 0);
+                        assert_eq!(m.shr_arithmetic_b, a != 0);
+                        assert_eq!(m.shl_s, ((a << s) & 1) != 0);
+                        assert_eq!(m.shr_s, ((a >> s) & 1) != 0);
+                        assert_eq!(m.shr_arithmetic_s, a != 0);
+                        assert_eq!(
+                            m.logic,
+                            if c != 0 {
+                                (a & !b & 1) != 0
+                            } else {
+                                ((a ^ b) | c) != 0
+                            }
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn register_file_test_module_0() {
+        let mut m = RegisterFileTestModule0::new();
+
+        // Write to addr 1 while reading it on port 0; read returns the previous value
+        m.wr_addr = 1;
+        m.wr_data = 0xaa;
+        m.wr_enable = true;
+        m.rd0_addr = 1;
+        m.rd0_enable = true;
+        m.rd1_addr = 2;
+        m.rd1_enable = true;
+        m.prop();
+        m.posedge_clk();
+        m.prop();
+        assert_eq!(m.rd0_data, 0x00);
+        assert_eq!(m.rd1_data, 0x00);
+
+        // Read back addr 1 on both ports
+        m.wr_enable = false;
+        m.rd0_addr = 1;
+        m.rd1_addr = 1;
+        m.prop();
+        m.posedge_clk();
+        m.prop();
+        assert_eq!(m.rd0_data, 0xaa);
+        assert_eq!(m.rd1_data, 0xaa);
+
+        // Stall port 0 while overwriting addr 1; port 0 holds its value
+        m.wr_addr = 1;
+        m.wr_data = 0x55;
+        m.wr_enable = true;
+        m.rd0_enable = false;
+        m.rd1_addr = 0;
+        m.prop();
+        m.posedge_clk();
+        m.prop();
+        assert_eq!(m.rd0_data, 0xaa);
+        assert_eq!(m.rd1_data, 0x00);
+
+        // Unstall port 0
+        m.wr_enable = false;
+        m.rd0_enable = true;
+        m.prop();
+        m.posedge_clk();
+        m.prop();
+        assert_eq!(m.rd0_data, 0x55);
+    }
+
+    #[test]
+    fn register_file_test_module_1() {
+        let mut m = RegisterFileTestModule1::new();
+        m.reset();
+
+        // Write to addr 1 while reading it on port 0; read returns the newly-written value
+        m.wr_addr = 1;
+        m.wr_data = 0xaa;
+        m.wr_enable = true;
+        m.rd0_addr = 1;
+        m.rd0_enable = true;
+        m.rd1_addr = 2;
+        m.rd1_enable = true;
+        m.prop();
+        m.posedge_clk();
+        m.prop();
+        assert_eq!(m.rd0_data, 0xaa);
+        assert_eq!(m.rd1_data, 0x00);
+
+        // Stall port 0 while overwriting addr 1; port 0 holds its value
+        m.wr_addr = 1;
+        m.wr_data = 0x55;
+        m.wr_enable = true;
+        m.rd0_enable = false;
+        m.rd1_addr = 0;
+        m.prop();
+        m.posedge_clk();
+        m.prop();
+        assert_eq!(m.rd0_data, 0xaa);
+        assert_eq!(m.rd1_data, 0x00);
+
+        // Unstall port 0
+        m.wr_enable = false;
+        m.rd0_enable = true;
+        m.prop();
+        m.posedge_clk();
+        m.prop();
+        assert_eq!(m.rd0_data, 0x55);
+
+        // Write to addr 3 while reading it on both ports
+        m.wr_addr = 3;
+        m.wr_data = 0x12;
+        m.wr_enable = true;
+        m.rd0_addr = 3;
+        m.rd1_addr = 3;
+        m.prop();
+        m.posedge_clk();
+        m.prop();
+        assert_eq!(m.rd0_data, 0x12);
+        assert_eq!(m.rd1_data, 0x12);
+
+        // Reads without a colliding write come from the mem
+        m.wr_addr = 0;
+        m.wr_data = 0x34;
+        m.rd0_addr = 3;
+        m.rd1_addr = 1;
+        m.prop();
+        m.posedge_clk();
+        m.prop();
+        assert_eq!(m.rd0_data, 0x12);
+        assert_eq!(m.rd1_data, 0x55);
+    }
+
+    #[test]
+    fn mem_reset_test_module() {
+        macro_rules! write_and_reset {
+            ($m:expr) => {{
+                let mut m = $m;
+
+                // Overwrite every element of both mems
+                m.write_enable = true;
+                for addr in 0..4 {
+                    m.write_addr = addr;
+                    m.write_value = 0xf0 | addr;
+                    m.prop();
+                    m.posedge_clk();
+                }
+                m.write_enable = false;
+
+                m.reset();
+
+                (0..4)
+                    .map(|addr| {
+                        m.read_addr = addr;
+                        m.prop();
+                        m.posedge_clk();
+                        m.prop();
+                        (m.read_data0, m.read_data1)
+                    })
+                    .collect::<Vec<_>>()
+            }};
+        }
+
+        // Contents are preserved across reset without `reset_mems`
+        assert_eq!(
+            write_and_reset!(MemResetTestModule0::new()),
+            vec![(0xf0, 0xf0), (0xf1, 0xf1), (0xf2, 0xf2), (0xf3, 0xf3)]
+        );
+        // ..and restored to their initial contents (or zeroed) with it
+        assert_eq!(
+            write_and_reset!(MemResetTestModule1::new()),
+            vec![(0x11, 0x00), (0x22, 0x00), (0x33, 0x00), (0x44, 0x00)]
+        );
+    }
+
+    #[test]
+    fn mem_comb_read_test_module() {
+        let mut m = MemCombReadTestModule::new();
+
+        // Combinational reads of the initial contents are visible in the same cycle
+        for (addr, value) in [0x11, 0x22, 0x33, 0x44].iter().enumerate() {
+            m.read_addr = addr as _;
+            m.prop();
+            assert_eq!(m.comb_read_data, *value);
+        }
+
+        // Write to addr 2 while reading it; the write isn't visible until the next cycle
+        m.write_addr = 2;
+        m.write_value = 0xaa;
+        m.write_enable = true;
+        m.read_addr = 2;
+        m.prop();
+        assert_eq!(m.comb_read_data, 0x33);
+        m.posedge_clk();
+        m.prop();
+        assert_eq!(m.comb_read_data, 0xaa);
+        // The synchronous read happened in the same cycle as the write, so it returns the previous value
+        assert_eq!(m.sync_read_data, 0x33);
+
+        // Stop writing; the synchronous read catches up one cycle after the combinational read
+        m.write_enable = false;
+        m.prop();
+        assert_eq!(m.comb_read_data, 0xaa);
+        m.posedge_clk();
+        m.prop();
+        assert_eq!(m.comb_read_data, 0xaa);
+        assert_eq!(m.sync_read_data, 0xaa);
+
+        // Other locations still hold their initial contents
+        m.read_addr = 3;
+        m.prop();
+        assert_eq!(m.comb_read_data, 0x44);
+        assert_eq!(m.sync_read_data, 0xaa);
+        m.posedge_clk();
+        m.prop();
+        assert_eq!(m.sync_read_data, 0x44);
+    }
+
+    #[test]
+    fn inout_test_module() {
+        let mut m = InOutTestModule::new();
+
+        m.reset();
+
+        // Reading drives the bus
+        m.read = true;
+        m.prop();
+        assert_eq!(m.data_oe, true);
+        assert_eq!(m.data_out, 0x5a);
+
+        // Writing releases the bus and latches its value
+        m.read = false;
+        m.data = 0xc3;
+        m.prop();
+        assert_eq!(m.data_oe, false);
+        m.posedge_clk();
+        m.read = true;
+        m.prop();
+        assert_eq!(m.data_oe, true);
+        assert_eq!(m.data_out, 0xc3);
+
+        // The bus's value is ignored while it's driven
+        m.data = 0x00;
+        m.posedge_clk();
+        m.prop();
+        assert_eq!(m.data_out, 0xc3);
+    }
+
+    #[test]
+    fn case_test_module() {
+        let mut m = CaseTestModule::new();
+
+        m.reset();
+        m.prop();
+        assert_eq!(m.state, 0);
+
+        // Idle until started
+        m.posedge_clk();
+        m.prop();
+        assert_eq!(m.state, 0);
+        m.start = true;
+        m.prop();
+        m.posedge_clk();
+        m.prop();
+        assert_eq!(m.state, 1);
+
+        // Busy for a single cycle, then finishing until done
+        m.start = false;
+        m.posedge_clk();
+        m.prop();
+        assert_eq!(m.state, 2);
+        m.posedge_clk();
+        m.prop();
+        assert_eq!(m.state, 2);
+        m.done = true;
+        m.prop();
+        m.posedge_clk();
+        m.prop();
+        assert_eq!(m.state, 0);
+
+        m.a = 0x12;
+        m.b = 0x34;
+        for (sel, decoded) in [
+            (0, 0x34),
+            (1, 0x12),
+            (2, 0xff),
+            (3, 0xff),
+            (4, 0x46),
+            (5, 0xff),
+            (6, 0xed),
+            (7, 0xff),
+        ]
+        .iter()
+        {
+            m.sel = *sel;
+            m.prop();
+            assert_eq!(m.decoded, *decoded);
+        }
+    }
+
+    #[test]
+    fn poison_test_module() {
+        let state = |m: &mut PoisonTestModule| {
+            m.prop();
+            (m.count, m.bit, m.wide, m.initialized)
+        };
+
+        // With a fixed seed, poisoned values are deterministic, both initially and after reset
+        let mut a = PoisonTestModule::new();
+        let mut b = PoisonTestModule::new();
+        assert_eq!(state(&mut a), state(&mut b));
+        a.set_poison_seed(1234);
+        b.set_poison_seed(1234);
+        let a_state = state(&mut a);
+        assert_eq!(a_state, state(&mut b));
+        assert!(a_state.2 < 1 << 100);
+        // ..registers with default values are only affected by reset
+        assert_eq!(a_state.3, 0);
+        a.reset();
+        b.reset();
+        let a_reset_state = state(&mut a);
+        assert_eq!(a_reset_state, state(&mut b));
+        assert_ne!(a_reset_state, a_state);
+        assert_eq!(a_reset_state.3, 0xfadebabe);
+
+        // Relying on the unreset counter starting at 0 fails with at least one seed
+        let num_failing_seeds = (0..16)
+            .filter(|&seed| {
+                let mut m = PoisonTestModule::new();
+                m.set_poison_seed(seed);
+                m.reset();
+                for _ in 0..3 {
+                    m.prop();
+                    m.posedge_clk();
+                }
+                m.prop();
+                !m.done
+            })
+            .count();
+        assert!(num_failing_seeds > 0);
+    }
+
+    #[test]
+    fn dont_care_test_module() {
+        macro_rules! run {
+            ($m:expr) => {{
+                let mut m = $m;
+                m.reset();
+                for &(valid, data) in &[(true, 1), (false, 2), (true, 3), (false, 4)] {
+                    m.valid = valid;
+                    m.data = data;
+                    m.prop();
+                    m.posedge_clk();
+                }
+                m.prop();
+                (m.legal_sum, m.illegal_sum)
+            }};
+        }
+
+        // The illegal dependency goes unnoticed by default..
+        assert_eq!(run!(DontCareTestModule::new()), (4, 4));
+        // ..but optimization may change the unspecified values..
+        assert_eq!(run!(DontCareTestModuleOptimized::new()), (4, 10));
+        // ..and poisoning them (here, with 0xff, the inverse of 0) catches it
+        assert_eq!(
+            run!(DontCareTestModulePoisoned::new()),
+            (4, (1 + 0xff + 3 + 0xff) & 0xff)
+        );
+    }
+
+    #[test]
+    fn lit_from_str_test_module() {
+        let mut m = LitFromStrTestModule::new();
+
+        m.reset();
+        m.mem_read_addr = false;
+        m.prop();
+
+        assert_eq!(m.hex, 0x2b7e1516_28aed2a6_abf71588_09cf4f3c);
+        assert_eq!(m.bin, 0xa5);
+        assert_eq!(m.dec, 123456789);
+        assert_eq!(m.bytes_le, 0x030201);
+        assert_eq!(m.bytes_be, 0x010203);
+        assert_eq!(m.reg, 0xab_cdef0123);
+
+        m.posedge_clk();
+        m.mem_read_addr = true;
+        m.prop();
+
+        assert_eq!(m.mem_read_data, 0xff_ffffffff_ffffffff);
+        assert_eq!(m.reg, 0xab_cdef0123);
+
+        m.posedge_clk();
+        m.prop();
+
+        assert_eq!(m.mem_read_data, 1);
+    }
+
+    impl DebugPortPins for DebugPortTestModule {
+        fn set_shift(&mut self, value: bool) {
+            self.dbg_shift = value;
+        }
+
+        fn set_update(&mut self, value: bool) {
+            self.dbg_update = value;
+        }
+
+        fn set_tdi(&mut self, value: bool) {
+            self.dbg_tdi = value;
+        }
+
+        fn tdo(&self) -> bool {
+            self.dbg_tdo
+        }
+
+        fn cycle(&mut self) {
+            self.prop();
+            self.posedge_clk();
+            self.prop();
+        }
+    }
+
+    #[test]
+    fn debug_port_test_module() {
+        let mut m = DebugPortTestModule::new();
+        let driver = DebugPortDriver::new(2, 8);
+
+        m.reset();
+        m.prop();
+
+        // Writes through the debug port are visible through direct peeks
+        driver.write_reg(&mut m, 1, 0xab);
+        driver.write_reg(&mut m, 3, 0x5a);
+        assert_eq!((m.r0, m.r1, m.r2, m.r3), (0x00, 0xab, 0x00, 0x5a));
+
+        // Direct pokes are visible through reads from the debug port
+        m.poke_enable = true;
+        m.poke_addr = 2;
+        m.poke_data = 0xc3;
+        m.prop();
+        m.posedge_clk();
+        m.poke_enable = false;
+        m.prop();
+        assert_eq!(m.r2, 0xc3);
+
+        for addr in 0..4 {
+            let peeked = [m.r0, m.r1, m.r2, m.r3][addr as usize];
+            assert_eq!(driver.read_reg(&mut m, addr) as u32, peeked);
+        }
+
+        // Reads don't modify registers
+        assert_eq!((m.r0, m.r1, m.r2, m.r3), (0x00, 0xab, 0xc3, 0x5a));
+
+        // Overwriting a register
+        driver.write_reg(&mut m, 1, 0x01);
+        assert_eq!(m.r1, 0x01);
+        assert_eq!(driver.read_reg(&mut m, 1), 0x01);
+    }
+
+    #[test]
+    fn strobe_select_test_module() {
+        // (period, phase) of each strobe
+        const STROBES: [(u32, u32); 3] = [(8, 0), (2, 0), (12, 4)];
+        let strobe = |i: u32, t: u32| {
+            let (period, phase) = STROBES[i as usize];
+            t % period == phase
+        };
+        // First cycle at or after `t` where strobes `a` and `b` are both high
+        let boundary =
+            |a: u32, b: u32, t: u32| (t..).find(|&t| strobe(a, t) && strobe(b, t)).unwrap();
+
+        for from in 0..3 {
+            for to in 0..3 {
+                // Request the switch at every offset within the strobes' common period
+                for request_offset in 0..24 {
+                    let request_time = 48 + request_offset;
+
+                    let first_boundary = if from != 0 {
+                        Some(boundary(0, from, 0))
+                    } else {
+                        None
+                    };
+                    let second_boundary = if to != from {
+                        Some(boundary(from, to, request_time))
+                    } else {
+                        None
+                    };
+
+                    let mut m = StrobeSelectTestModule::new();
+
+                    let mut last_pulse = None;
+                    for t in 0..200 {
+                        m.strobe0 = strobe(0, t);
+                        m.strobe1 = strobe(1, t);
+                        m.strobe2 = strobe(2, t);
+                        m.select = if t < request_time { from } else { to };
+                        m.prop();
+
+                        // The old strobe is passed through up to and including the boundary, and
+                        //  the new one from the following cycle on
+                        let expected_source = match (first_boundary, second_boundary) {
+                            (_, Some(b)) if t > b => to,
+                            (Some(b), _) if t > b => from,
+                            _ if t >= request_time => from,
+                            (Some(_), _) => 0,
+                            (None, _) => from,
+                        };
+                        assert_eq!(
+                            m.strobe_out,
+                            strobe(expected_source, t),
+                            "from {} to {}, request at {}, t = {}",
+                            from,
+                            to,
+                            request_time,
+                            t
+                        );
+
+                        if m.strobe_out {
+                            if let Some(last_pulse) = last_pulse {
+                                let min_period = STROBES[from as usize]
+                                    .0
+                                    .min(STROBES[to as usize].0)
+                                    .min(STROBES[0].0);
+                                assert!(
+                                    t - last_pulse >= min_period,
+                                    "from {} to {}, request at {}, pulses at {} and {}",
+                                    from,
+                                    to,
+                                    request_time,
+                                    last_pulse,
+                                    t
+                                );
+                            }
+                            last_pulse = Some(t);
+                        }
+
+                        m.posedge_clk();
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn align_strobe_test_module_0() {
+        let mut m = AlignStrobeTestModule0::new();
+
+        // Periodic pulses are delayed by 3 cycles; the pulse at 20 replaces the one at 19
+        let pulses = [2, 9, 16, 19, 20];
+        let expected_pulses = [5, 12, 19, 23];
+        for t in 0..30 {
+            m.strobe_in = pulses.contains(&t);
+            m.prop();
+            assert_eq!(m.strobe_out, expected_pulses.contains(&t), "t = {}", t);
+            m.posedge_clk();
+        }
+    }
+
+    #[test]
+    fn align_strobe_test_module_1() {
+        let mut m = AlignStrobeTestModule1::new();
+
+        // No delay; the strobe is passed straight through
+        for &strobe_in in &[false, true, true, false] {
+            m.strobe_in = strobe_in;
+            m.prop();
+            assert_eq!(m.strobe_out, strobe_in);
+        }
+    }
+
+    #[test]
+    fn fifo_test_module_0() {
+        let mut m = FifoTestModule0::new();
+
+        m.reset();
+        m.prop();
+        assert_eq!(m.empty, true);
+        assert_eq!(m.full, false);
+
+        // Dequeueing while empty is ignored
+        m.dequeue = true;
+        m.prop();
+        m.posedge_clk();
+        m.dequeue = false;
+        m.prop();
+        assert_eq!(m.empty, true);
+        assert_eq!(m.full, false);
+
+        // Fill up to depth; entries fall through to dequeue_data
+        m.enqueue = true;
+        for i in 0..3 {
+            m.enqueue_data = 0x10 + i;
+            m.prop();
+            assert_eq!(m.full, false);
+            m.posedge_clk();
+            m.prop();
+            assert_eq!(m.empty, false);
+            assert_eq!(m.dequeue_data, 0x10);
+        }
+        assert_eq!(m.full, true);
+
+        // Enqueueing while full is ignored
+        m.enqueue_data = 0xff;
+        m.prop();
+        m.posedge_clk();
+        m.enqueue = false;
+        m.prop();
+        assert_eq!(m.full, true);
+
+        // Drain in order
+        m.dequeue = true;
+        for i in 0..3 {
+            m.prop();
+            assert_eq!(m.empty, false);
+            assert_eq!(m.dequeue_data, 0x10 + i);
+            m.posedge_clk();
+            m.prop();
+            assert_eq!(m.full, false);
+        }
+        assert_eq!(m.empty, true);
+        m.dequeue = false;
+        m.prop();
+    }
+
+    #[test]
+    fn fifo_test_module_random() {
+        macro_rules! run {
+            ($m:expr, $depth:expr) => {{
+                let mut m = $m;
+                m.reset();
+
+                // Pseudo-random enqueues and dequeues that wrap the pointers around several times, checked against a software model.
+                //  Phases that mostly enqueue alternate with phases that mostly dequeue, so the FIFO regularly fills up and drains.
+                let mut model = VecDeque::new();
+                let mut seed = 0x1234_5678u32;
+                let (mut num_full, mut num_empty) = (0, 0);
+                for t in 0..400 {
+                    seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                    let filling = (t / 20) % 2 == 0;
+                    m.enqueue = ((seed >> 16) & 3 != 0) == filling;
+                    m.enqueue_data = (seed >> 8) & 0xff;
+                    m.dequeue = ((seed >> 20) & 3 != 0) != filling;
+                    m.prop();
+
+                    assert_eq!(m.full, model.len() == $depth);
+                    assert_eq!(m.empty, model.is_empty());
+                    if let Some(&front) = model.front() {
+                        assert_eq!(m.dequeue_data, front);
+                    }
+                    num_full += m.full as u32;
+                    num_empty += m.empty as u32;
+
+                    if m.dequeue && !m.empty {
+                        model.pop_front();
+                    }
+                    if m.enqueue && !m.full {
+                        model.push_back(m.enqueue_data);
+                    }
+
+                    m.posedge_clk();
+                }
+                assert!(num_full > 0 && num_empty > 0);
+            }};
+        }
+
+        run!(FifoTestModule0::new(), 3);
+        run!(FifoTestModule1::new(), 4);
+    }
+
+    #[test]
+    fn edge_detect_test_module() {
+        let mut m = EdgeDetectTestModule::new();
+
+        m.reset();
+
+        // (i, rising, falling) for each cycle
+        for &(i, rising, falling) in &[
+            (false, false, false),
+            (true, true, false),
+            (true, false, false),
+            (true, false, false),
+            (false, false, true),
+            (false, false, false),
+            (true, true, false),
+            (false, false, true),
+            (true, true, false),
+            (false, false, true),
+        ] {
+            m.i = i;
+            m.prop();
+            assert_eq!((m.rising, m.falling), (rising, falling));
+            m.posedge_clk();
+        }
+
+        // Edges are detected relative to the registers' default values after reset
+        m.i = true;
+        m.prop();
+        m.reset();
+        m.prop();
+        assert_eq!((m.rising, m.falling), (true, false));
+    }
+
+    #[test]
+    fn saturating_test_module() {
+        let mut m = SaturatingTestModule::new();
+
+        // (a, b, add, sub, add_signed, sub_signed)
+        for &(a, b, add, sub, add_signed, sub_signed) in &[
+            (0xff, 0x01, 0xff, 0xfe, 0x00, 0xfe),
+            (0x7f, 0x01, 0x80, 0x7e, 0x7f, 0x7e),
+            (0x80, 0x01, 0x81, 0x7f, 0x81, 0x80),
+            (0x80, 0xff, 0xff, 0x00, 0x80, 0x81),
+            (0x7f, 0xff, 0xff, 0x00, 0x7e, 0x7f),
+            (0x01, 0x02, 0x03, 0x00, 0x03, 0xff),
+            (0x00, 0x00, 0x00, 0x00, 0x00, 0x00),
+        ] {
+            m.a8 = a;
+            m.b8 = b;
+            m.prop();
+            assert_eq!(
+                (m.add8, m.sub8, m.add_signed8, m.sub_signed8),
+                (add, sub, add_signed, sub_signed)
+            );
+        }
+
+        const MAX: u128 = u128::MAX;
+        const MAX_SIGNED: u128 = u128::MAX >> 1;
+        const MIN_SIGNED: u128 = 1 << 127;
+        for &(a, b, add, sub, add_signed, sub_signed) in &[
+            (MAX, 1, MAX, MAX - 1, 0, MAX - 1),
+            (
+                MAX_SIGNED,
+                1,
+                MIN_SIGNED,
+                MAX_SIGNED - 1,
+                MAX_SIGNED,
+                MAX_SIGNED - 1,
+            ),
+            (
+                MIN_SIGNED,
+                1,
+                MIN_SIGNED + 1,
+                MAX_SIGNED,
+                MIN_SIGNED + 1,
+                MIN_SIGNED,
+            ),
+            (MIN_SIGNED, MAX, MAX, 0, MIN_SIGNED, MIN_SIGNED + 1),
+            (MIN_SIGNED, MIN_SIGNED, MAX, 0, MIN_SIGNED, 0),
+            (MAX_SIGNED, MAX_SIGNED, MAX - 1, 0, MAX_SIGNED, 0),
+            (1, 2, 3, 0, 3, MAX),
+        ] {
+            m.a128 = a;
+            m.b128 = b;
+            m.prop();
+            assert_eq!(
+                (m.add128, m.sub128, m.add_signed128, m.sub_signed128),
+                (add, sub, add_signed, sub_signed)
+            );
+        }
+    }
+
+    #[test]
+    fn output_logger() {
+        let mut m = SimpleRegDelay::new();
+        let mut logger = csv::OutputLogger::new(Vec::new())
+            .column("i", 100, csv::ColumnFormat::Hex, |m: &SimpleRegDelay| m.i)
+            .column("o", 100, csv::ColumnFormat::Hex, |m: &SimpleRegDelay| m.o)
+            .every_nth_cycle(2);
+
+        m.reset();
+        for t in 0..6 {
+            m.i = t as u128 * 0x11;
+            m.prop();
+            logger.sample(&m, t).unwrap();
+            m.posedge_clk();
+        }
+
+        assert_eq!(
+            String::from_utf8(logger.into_inner()).unwrap(),
+            "cycle,i,o\n\
+             0,0000000000000000000000000,0000000000000000000000000\n\
+             2,0000000000000000000000022,0000000000000000000000000\n\
+             4,0000000000000000000000044,0000000000000000000000011\n"
+        );
+
+        let mut m = AlignStrobeTestModule0::new();
+        let mut logger = csv::OutputLogger::new(Vec::new())
+            .column(
+                "strobe_in",
+                1,
+                csv::ColumnFormat::Decimal,
+                |m: &AlignStrobeTestModule0| m.strobe_in.into(),
+            )
+            .sample_when(|m: &AlignStrobeTestModule0| m.strobe_out);
+
+        for t in 0..15 {
+            m.strobe_in = t == 2 || t == 9 || t == 12;
+            m.prop();
+            logger.sample(&m, t).unwrap();
+            m.posedge_clk();
+        }
+
+        assert_eq!(
+            String::from_utf8(logger.into_inner()).unwrap(),
+            "cycle,strobe_in\n5,0\n12,1\n"
+        );
+    }
+
+    #[test]
+    fn reset_domain_test_module() {
+        let mut m = ResetDomainTestModule::new();
+
+        m.reset();
+        m.reset_soft();
+        m.prop();
+        assert_eq!(m.always_on, 0xfadebabe);
+        assert_eq!(m.main, 0xdeadbeef);
+        assert_eq!(m.inner, 0xabad1dea);
 
-        // Read from addr 0
-        m.write_addr = false;
-        m.write_value = false;
-        m.write_enable = false;
-        m.read_addr = false;
-        m.read_enable = true;
+        m.always_on_next = 1;
+        m.main_next = 2;
+        m.inner_next = 3;
         m.prop();
-        assert_eq!(m.read_data, true);
         m.posedge_clk();
         m.prop();
-        assert_eq!(m.read_data, false);
+        assert_eq!(m.always_on, 1);
+        assert_eq!(m.main, 2);
+        assert_eq!(m.inner, 3);
 
-        // Read from addr 1
-        m.write_addr = false;
-        m.write_value = false;
-        m.write_enable = false;
-        m.read_addr = true;
-        m.read_enable = true;
+        // Soft reset only affects registers in the soft domain
+        m.reset_soft();
         m.prop();
-        assert_eq!(m.read_data, false);
-        m.posedge_clk();
-        m.prop();
-        assert_eq!(m.read_data, true);
+        assert_eq!(m.always_on, 1);
+        assert_eq!(m.main, 0xdeadbeef);
+        assert_eq!(m.inner, 0xabad1dea);
 
-        // Write to addr 0/read from addr 1
-        m.write_addr = false;
-        m.write_value = true;
-        m.write_enable = true;
-        m.read_addr = true;
-        m.read_enable = true;
         m.prop();
-        assert_eq!(m.read_data, true);
         m.posedge_clk();
         m.prop();
-        assert_eq!(m.read_data, true);
+        assert_eq!(m.always_on, 1);
+        assert_eq!(m.main, 2);
+        assert_eq!(m.inner, 3);
 
-        // Write to addr 1/read from addr 0
-        m.write_addr = true;
-        m.write_value = false;
-        m.write_enable = true;
-        m.read_addr = false;
-        m.read_enable = true;
-        m.prop();
-        assert_eq!(m.read_data, true);
-        m.posedge_clk();
+        // Implicit reset only affects registers in the implicit domain
+        m.reset();
         m.prop();
-        assert_eq!(m.read_data, true);
+        assert_eq!(m.always_on, 0xfadebabe);
+        assert_eq!(m.main, 2);
+        assert_eq!(m.inner, 3);
+    }
 
-        // Read from addr 1
-        m.write_addr = false;
-        m.write_value = false;
-        m.write_enable = false;
-        m.read_addr = true;
-        m.read_enable = true;
+    #[test]
+    fn default_and_dump_state() {
+        let mut m = ResetDomainTestModule::default();
+
+        m.reset();
+        m.reset_soft();
         m.prop();
-        assert_eq!(m.read_data, true);
-        m.posedge_clk();
+
+        assert_eq!(
+            m.dump_state(),
+            "always_on_next = 0x0\n\
+             inner_next = 0x0\n\
+             main_next = 0x0\n\
+             always_on = 0xfadebabe\n\
+             inner = 0xabad1dea\n\
+             main = 0xdeadbeef\n\
+             reset_domain_test_module.always_on = 0xfadebabe\n\
+             reset_domain_test_module.inner.r = 0xabad1dea\n\
+             reset_domain_test_module.main = 0xdeadbeef\n"
+        );
+    }
+
+    #[test]
+    fn with_trace_names_top_level_module() -> io::Result<()> {
+        let mut capture = Capture::new();
+        let trace = CaptureTrace::new(&mut capture);
+
+        let mut m = TraceTestModule0::with_trace("renamed", trace)?;
         m.prop();
-        assert_eq!(m.read_data, false);
+        m.update_trace(0)?;
+        drop(m);
+
+        assert_eq!(capture.root.as_ref().unwrap().0, "renamed");
+
+        Ok(())
+    }
+
+    #[test]
+    fn bits_dyn_test_module() {
+        let mut m = BitsDynTestModule::new();
+
+        m.i1 = 0xfadebabe;
+        m.i2 = 0xc0cac01adeadbeefabad1deabadc0de5;
+
+        // Indices beyond the source's width shift in zeros
+        for index in 0..64 {
+            m.index1 = index;
+            m.prop();
+
+            let expected = 0xfadebabeu32.checked_shr(index).unwrap_or(0);
+            assert_eq!(m.bit1, expected & 1 != 0);
+            assert_eq!(m.bits1, expected & 0xf);
+        }
+
+        for index in 0..256 {
+            m.index2 = index;
+            m.prop();
+
+            let expected = 0xc0cac01adeadbeefabad1deabadc0de5u128
+                .checked_shr(index)
+                .unwrap_or(0);
+            assert_eq!(m.bit2, expected & 1 != 0);
+            assert_eq!(m.bits2, (expected & 0xfff) as u32);
+        }
+    }
+
+    #[test]
+    fn optimize_test_module() {
+        let mut m = OptimizeTestModule::new();
+        let mut o = OptimizeTestModuleOptimized::new();
+
+        m.reset();
+        o.reset();
+
+        let space = PortSpace::for_ports(vec![("a", 8), ("b", 8), ("s", 1)], 2048);
+        for combination in space.combinations() {
+            let (a, b, s) = (
+                combination[0] as u32,
+                combination[1] as u32,
+                combination[2] != 0,
+            );
+            m.a = a;
+            m.b = b;
+            m.s = s;
+            m.prop();
+            o.a = a;
+            o.b = b;
+            o.s = s;
+            o.prop();
+
+            assert_eq!(o.and_zero, m.and_zero);
+            assert_eq!(o.and_ones, m.and_ones);
+            assert_eq!(o.or_zero, m.or_zero);
+            assert_eq!(o.or_ones, m.or_ones);
+            assert_eq!(o.xor_zero, m.xor_zero);
+            assert_eq!(o.add_zero, m.add_zero);
+            assert_eq!(o.sub_zero, m.sub_zero);
+            assert_eq!(o.shl_zero, m.shl_zero);
+            assert_eq!(o.double_not, m.double_not);
+            assert_eq!(o.full_bits, m.full_bits);
+            assert_eq!(o.nested_bits, m.nested_bits);
+            assert_eq!(o.repeat_one, m.repeat_one);
+            assert_eq!(o.mux_true, m.mux_true);
+            assert_eq!(o.mux_false, m.mux_false);
+            assert_eq!(o.mux_same, m.mux_same);
+            assert_eq!(o.mux_bool, m.mux_bool);
+            assert_eq!(o.mux_not_bool, m.mux_not_bool);
+            assert_eq!(o.folded, m.folded);
+            assert_eq!(o.mixed, m.mixed);
+            assert_eq!(o.staged, m.staged);
+            assert_eq!(o.r, m.r);
+
+            m.posedge_clk();
+            o.posedge_clk();
+        }
     }
 
     #[test]
@@ -2342,6 +4280,136 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn trace_on_change_test_module_1() -> io::Result<()> {
+        let mut capture = Capture::new();
+        let trace = CaptureTrace::new(&mut capture);
+
+        let mut m = TraceOnChangeTestModule1::new(trace)?;
+
+        // All signals are updated on the first call
+        m.reset();
+        m.prop();
+        m.update_trace(0)?;
+
+        // Only changed signals are updated on subsequent calls
+        m.i1 = 0xdeadbeef;
+        m.prop();
+        m.update_trace(0)?;
+        m.posedge_clk();
+        m.prop();
+        m.update_trace(1)?;
+        m.i1 = 0xfadebabe;
+        m.prop();
+        m.update_trace(1)?;
+        m.i2 = 0xfadebabe;
+        m.prop();
+        m.update_trace(1)?;
+        m.posedge_clk();
+        m.prop();
+        m.update_trace(2)?;
+        m.update_trace(3)?;
+
+        // All signals are updated again after a reset
+        m.reset();
+        m.prop();
+        m.update_trace(4)?;
+        m.update_trace(5)?;
+
+        assert_eq!(
+            capture,
+            Capture {
+                root: Some((
+                    "trace_test_module_1",
+                    CaptureModule {
+                        children: BTreeMap::new(),
+                        signals: vec![
+                            (
+                                "i1",
+                                Rc::new(CaptureSignal {
+                                    bit_width: 32,
+                                    type_: TraceValueType::U32,
+                                    values: RefCell::new(vec![
+                                        (0, TraceValue::U32(0)),
+                                        (0, TraceValue::U32(0xdeadbeef)),
+                                        (1, TraceValue::U32(0xfadebabe)),
+                                        (4, TraceValue::U32(0xfadebabe)),
+                                    ]),
+                                })
+                            ),
+                            (
+                                "i2",
+                                Rc::new(CaptureSignal {
+                                    bit_width: 32,
+                                    type_: TraceValueType::U32,
+                                    values: RefCell::new(vec![
+                                        (0, TraceValue::U32(0)),
+                                        (1, TraceValue::U32(0xfadebabe)),
+                                        (4, TraceValue::U32(0xfadebabe)),
+                                    ]),
+                                })
+                            ),
+                            (
+                                "o1",
+                                Rc::new(CaptureSignal {
+                                    bit_width: 32,
+                                    type_: TraceValueType::U32,
+                                    values: RefCell::new(vec![
+                                        (0, TraceValue::U32(0)),
+                                        (1, TraceValue::U32(0xdeadbeef)),
+                                        (2, TraceValue::U32(0xfadebabe)),
+                                        (4, TraceValue::U32(0)),
+                                    ]),
+                                })
+                            ),
+                            (
+                                "o2",
+                                Rc::new(CaptureSignal {
+                                    bit_width: 32,
+                                    type_: TraceValueType::U32,
+                                    values: RefCell::new(vec![
+                                        (0, TraceValue::U32(0)),
+                                        (2, TraceValue::U32(0xfadebabe)),
+                                        (4, TraceValue::U32(0xfadebabe)),
+                                    ]),
+                                })
+                            ),
+                            (
+                                "r1",
+                                Rc::new(CaptureSignal {
+                                    bit_width: 32,
+                                    type_: TraceValueType::U32,
+                                    values: RefCell::new(vec![
+                                        (0, TraceValue::U32(0)),
+                                        (1, TraceValue::U32(0xdeadbeef)),
+                                        (2, TraceValue::U32(0xfadebabe)),
+                                        (4, TraceValue::U32(0)),
+                                    ]),
+                                })
+                            ),
+                            (
+                                "r2",
+                                Rc::new(CaptureSignal {
+                                    bit_width: 32,
+                                    type_: TraceValueType::U32,
+                                    values: RefCell::new(vec![
+                                        (0, TraceValue::U32(0)),
+                                        (2, TraceValue::U32(0xfadebabe)),
+                                        (4, TraceValue::U32(0xfadebabe)),
+                                    ]),
+                                })
+                            ),
+                        ]
+                        .into_iter()
+                        .collect(),
+                    }
+                )),
+            }
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn trace_test_module_2() -> io::Result<()> {
         let mut capture = Capture::new();
@@ -2848,4 +4916,251 @@ mod tests {
         m.prop();
         assert_eq!(m.o, false);
     }
+
+    #[test]
+    fn declared_module_test_module() {
+        let mut m = DeclaredModuleTestModule::new();
+
+        m.i1 = 1;
+        m.i2 = 2;
+        m.i3 = 3;
+        m.prop();
+        assert_eq!(m.sum, 6);
+
+        m.i1 = 0xff;
+        m.i2 = 0x80;
+        m.i3 = 0x82;
+        m.prop();
+        assert_eq!(m.sum, 0x01);
+    }
+
+    #[test]
+    fn instance_array_test_module() -> io::Result<()> {
+        let recorder = recorder::Recorder::new();
+
+        let mut m = InstanceArrayTestModule::new(recorder.clone())?;
+
+        // Each lane's sum wraps independently, without carrying into the next lane
+        let lane_sums = |a: u32, b: u32| {
+            (0..4).fold(0, |acc, i| {
+                let sum = ((a >> (i * 8)) as u8).wrapping_add((b >> (i * 8)) as u8);
+                acc | (sum as u32) << (i * 8)
+            })
+        };
+
+        let stimuli = [
+            (0x00000000, 0x00000000),
+            (0x01020304, 0x10203040),
+            (0xff80ff01, 0x0180ffff),
+            (0xdeadbeef, 0x12345678),
+        ];
+        for (time_stamp, &(a, b)) in stimuli.iter().enumerate() {
+            m.a = a;
+            m.b = b;
+            m.prop();
+            m.update_trace(time_stamp as _)?;
+            assert_eq!(m.sum, lane_sums(a, b));
+        }
+
+        // Instances are traced under their indexed names
+        for i in 0..4 {
+            assert_eq!(
+                recorder.value_at(&format!("instance_array_test_module.lane_{}.sum", i), 3),
+                Some((lane_sums(0xdeadbeef, 0x12345678) >> (i * 8)) as u128 & 0xff)
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn keyword_test_module() -> io::Result<()> {
+        let recorder = recorder::Recorder::new();
+
+        let mut m = KeywordTestModule::new(recorder.clone())?;
+
+        m.r#match = 0x12;
+        m.r#type = false;
+        m.r#yield = 1 << 99;
+        m.prop();
+        m.update_trace(0)?;
+        assert_eq!(m.r#fn, 0x12);
+        assert_eq!(m.r#box, 1 << 99);
+        assert_eq!(m.box_to_le_bytes()[12], 0x08);
+        assert_eq!(
+            recorder.value_at("keyword_test_module.inner module.not (a b)", 0),
+            Some(0xed)
+        );
+
+        m.posedge_clk();
+        m.r#type = true;
+        m.prop();
+        assert_eq!(m.r#fn, 0xed);
+
+        Ok(())
+    }
+
+    #[test]
+    fn packed_bools_test_module() -> io::Result<()> {
+        let unpacked_recorder = recorder::Recorder::new();
+        let packed_recorder = recorder::Recorder::new();
+
+        let mut unpacked = PackedBoolsTestModule::new(unpacked_recorder.clone())?;
+        let mut packed = PackedBoolsTestModulePacked::new(packed_recorder.clone())?;
+        unpacked.reset();
+        packed.reset();
+
+        // xorshift64 for reproducible pseudo-random stimulus
+        let mut state = 0x9e3779b97f4a7c15u64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        for time_stamp in 0..1000 {
+            let stimulus = next();
+            if stimulus >> 56 == 0 {
+                unpacked.reset_soft();
+                packed.reset_soft();
+            }
+            unpacked.i = (stimulus & 0xff) as _;
+            packed.i = unpacked.i;
+            unpacked.en = stimulus & 0x100 != 0;
+            packed.en = unpacked.en;
+
+            unpacked.prop();
+            packed.prop();
+            unpacked.update_trace(time_stamp)?;
+            packed.update_trace(time_stamp)?;
+
+            assert_eq!(packed.shift, unpacked.shift);
+            assert_eq!(packed.parity, unpacked.parity);
+            assert_eq!(packed.toggle, unpacked.toggle);
+            assert_eq!(packed.acc, unpacked.acc);
+            assert_eq!(packed.delayed, unpacked.delayed);
+            assert_eq!(packed.dump_state(), unpacked.dump_state());
+
+            unpacked.posedge_clk();
+            packed.posedge_clk();
+        }
+
+        let paths = unpacked_recorder.signal_paths();
+        assert_eq!(packed_recorder.signal_paths(), paths);
+        for path in &paths {
+            for time_stamp in 0..1000 {
+                assert_eq!(
+                    packed_recorder.value_at(path, time_stamp),
+                    unpacked_recorder.value_at(path, time_stamp)
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn named_wire_test_module() -> io::Result<()> {
+        let recorder = recorder::Recorder::new();
+
+        let mut m = NamedWireTestModule::new(recorder.clone())?;
+
+        for (time_stamp, &(a, b)) in [(1u32, 2u32), (0xff, 0x01), (0x80, 0x0f)]
+            .iter()
+            .enumerate()
+        {
+            m.a = a;
+            m.b = b;
+            m.prop();
+            m.update_trace(time_stamp as _)?;
+
+            let sum = a.wrapping_add(b) & 0xff;
+            assert_eq!(m.o, !sum & 0xff);
+            assert_eq!(
+                recorder.value_at("named_wire_test_module.sum", time_stamp as _),
+                Some(sum as _)
+            );
+            assert_eq!(
+                recorder.value_at("named_wire_test_module.inner.inverted", time_stamp as _),
+                Some((!sum & 0xff) as _)
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn reorder_test_module() {
+        let mut m = ReorderTestModule::new();
+
+        for &value in &[
+            0u128,
+            1,
+            0x0123_4567_89ab_cdef_fedc_ba98_7654_3210,
+            0x8000_0000_0000_0000_0000_0000_0000_0001,
+            !0,
+        ] {
+            m.i1 = value & 1 != 0;
+            m.i8 = value as u32 & 0xff;
+            m.i13 = value as u32 & 0x1fff;
+            m.i24 = value as u32 & 0xff_ffff;
+            m.i32 = value as u32;
+            m.i64 = value as u64;
+            m.i128 = value;
+            m.prop();
+
+            assert_eq!(m.reversed1, m.i1);
+            assert_eq!(m.reversed8, m.i8.reverse_bits() >> 24);
+            assert_eq!(m.reversed13, m.i13.reverse_bits() >> 19);
+            assert_eq!(m.reversed24, m.i24.reverse_bits() >> 8);
+            assert_eq!(m.reversed32, m.i32.reverse_bits());
+            assert_eq!(m.reversed64, m.i64.reverse_bits());
+            assert_eq!(m.reversed128, m.i128.reverse_bits());
+            assert_eq!(m.swapped8, m.i8);
+            assert_eq!(m.swapped24, m.i24.swap_bytes() >> 8);
+            assert_eq!(m.swapped32, m.i32.swap_bytes());
+            assert_eq!(m.swapped64, m.i64.swap_bytes());
+            assert_eq!(m.swapped128, m.i128.swap_bytes());
+        }
+    }
+    #[test]
+    fn named_wire_collision_test_module() {
+        let mut m = NamedWireCollisionTestModule::new();
+
+        for &i in &[0x00, 0x5a, 0xff] {
+            m.i = i;
+            m.prop();
+
+            assert_eq!(m.o0, i);
+            assert_eq!(m.o1, !i & 0xff);
+            assert_eq!(m.o2, 0xff);
+        }
+    }
+    #[test]
+    fn registered_output_test_module() {
+        let mut m = RegisteredOutputTestModule::new();
+
+        let mut prev: Option<u32> = None;
+        for &i in &[0x12, 0x34, 0x56, 0x78] {
+            m.i = i;
+            m.prop();
+
+            // Outputs created after `register_all_outputs` with `output` aren't registered
+            assert_eq!(m.d, i);
+
+            m.posedge_clk();
+            m.prop();
+
+            assert_eq!(m.a, i);
+            // Registered twice (by `output_registered` and `register_all_outputs`), so its value is undefined until the second edge
+            if let Some(prev) = prev {
+                assert_eq!(m.b, !prev & 0xff);
+            }
+            assert_eq!(m.c, i);
+            assert_eq!(m.d, i);
+
+            prev = Some(i);
+        }
+    }
 }